@@ -0,0 +1,134 @@
+//! Plugin configuration
+//!
+//! Read from `$XDG_CONFIG_HOME/sigilforge/plugin.toml` (or
+//! `~/.config/sigilforge/plugin.toml`). Every setting is optional:
+//!
+//! ```toml
+//! # Warn about tokens expiring within this many minutes (default: 1440)
+//! expiry_warning_mins = 60
+//! ```
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Default `expiry_warning_mins`: 24 hours
+pub const DEFAULT_EXPIRY_WARNING_MINS: u64 = 24 * 60;
+
+/// Errors from loading the plugin configuration
+#[derive(Debug, Error)]
+pub enum PluginConfigError {
+    #[error("failed to read {path:?}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse {path:?}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+/// Settings for the Sigilforge plugin
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PluginConfig {
+    /// Mark tokens expiring within this many minutes as expiring soon
+    pub expiry_warning_mins: u64,
+}
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            expiry_warning_mins: DEFAULT_EXPIRY_WARNING_MINS,
+        }
+    }
+}
+
+impl PluginConfig {
+    /// Load the configuration from [`default_config_path`], or the defaults
+    /// if there is no config file
+    pub fn load() -> Result<Self, PluginConfigError> {
+        match default_config_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load the configuration from `path`, or the defaults if it does not exist
+    pub fn load_from(path: &Path) -> Result<Self, PluginConfigError> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(PluginConfigError::Read {
+                    path: path.to_path_buf(),
+                    source,
+                });
+            }
+        };
+        toml::from_str(&contents).map_err(|source| PluginConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Tokens expiring within this long are marked as expiring soon
+    pub fn expiry_warning_threshold(&self) -> chrono::Duration {
+        i64::try_from(self.expiry_warning_mins)
+            .ok()
+            .and_then(chrono::Duration::try_minutes)
+            .unwrap_or(chrono::Duration::MAX)
+    }
+}
+
+/// `$XDG_CONFIG_HOME/sigilforge/plugin.toml` (or `~/.config/...`)
+pub fn default_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("sigilforge").join("plugin.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_file_uses_defaults() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = PluginConfig::load_from(&dir.path().join("plugin.toml")).unwrap();
+        assert_eq!(config, PluginConfig::default());
+        assert_eq!(
+            config.expiry_warning_threshold(),
+            chrono::Duration::hours(24)
+        );
+    }
+
+    #[test]
+    fn test_load_expiry_warning() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("plugin.toml");
+        std::fs::write(&path, "expiry_warning_mins = 90\n").unwrap();
+
+        let config = PluginConfig::load_from(&path).unwrap();
+        assert_eq!(config.expiry_warning_mins, 90);
+        assert_eq!(
+            config.expiry_warning_threshold(),
+            chrono::Duration::minutes(90)
+        );
+    }
+
+    #[test]
+    fn test_invalid_file_is_an_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("plugin.toml");
+        std::fs::write(&path, "expiry_warning_mins = \"soon\"\n").unwrap();
+
+        let err = PluginConfig::load_from(&path).unwrap_err();
+        assert!(matches!(err, PluginConfigError::Parse { .. }));
+    }
+}
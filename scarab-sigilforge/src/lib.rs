@@ -8,21 +8,32 @@
 //!
 //! - Display account count and status in the status bar
 //! - Color-coded status indicators (green for valid, red for issues)
-//! - Warning icons for expiring tokens
+//! - Warning icons for tokens expiring within a configurable threshold
+//!   (see [`PluginConfig`])
+//! - Status refreshes when another process changes the account store
 //! - Menu integration for adding and managing accounts
+//! - Adding accounts through the browser-based OAuth flow
 //! - Support for Google, GitHub, and Spotify OAuth providers
 
+mod config;
 mod status;
 
+pub use config::{default_config_path, PluginConfig, PluginConfigError};
+
 use async_trait::async_trait;
 use scarab_plugin_api::{
     menu::{MenuAction, MenuItem},
     Plugin, PluginContext, PluginMetadata, Result as PluginResult,
 };
-use sigilforge_core::{AccountStore, AccountStoreError};
+use sigilforge_core::{
+    oauth::pkce::{open_browser, PkceFlow, RedirectConfig},
+    Account, AccountId, AccountStore, AccountStoreError, CredentialSource, DefaultTokenManager,
+    KeyringStore, ProviderRegistry, ServiceId, StoreError, TokenManager, WatchHandle,
+};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{error, info, warn};
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, error, info, warn};
 
 /// Account status information for display in the status bar
 #[derive(Debug, Clone)]
@@ -33,7 +44,7 @@ struct AccountStatus {
     account: String,
     /// Whether the token is currently valid
     token_valid: bool,
-    /// Whether the token expires soon (within 24 hours)
+    /// Whether the token expires within the configured warning threshold
     expires_soon: bool,
 }
 
@@ -45,6 +56,12 @@ pub struct SigilforgePlugin {
     account_store: Arc<RwLock<Option<AccountStore>>>,
     /// Cached account status for status bar rendering
     accounts: Arc<RwLock<Vec<AccountStatus>>>,
+    /// Settings from `plugin.toml`
+    config: PluginConfig,
+    /// Watches the account store file while the plugin is loaded
+    account_watch: Option<WatchHandle>,
+    /// Refreshes account status on changes made through the plugin's store
+    account_events: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl SigilforgePlugin {
@@ -64,46 +81,145 @@ impl SigilforgePlugin {
             metadata,
             account_store: Arc::new(RwLock::new(None)),
             accounts: Arc::new(RwLock::new(Vec::new())),
+            config: PluginConfig::default(),
+            account_watch: None,
+            account_events: None,
         }
     }
 
     /// Load account status from the account store
     async fn refresh_account_status(&self) -> Result<(), AccountStoreError> {
-        let store = self.account_store.read().await;
-
-        if let Some(store) = store.as_ref() {
-            let all_accounts = store.list_accounts(None)?;
-            let mut status_list = Vec::new();
-
-            for account in all_accounts {
-                // For now, we'll mark all accounts as valid
-                // In a full implementation, we would check token expiry
-                // by querying the keyring store
-                status_list.push(AccountStatus {
-                    service: account.service.as_str().to_string(),
-                    account: account.id.as_str().to_string(),
-                    token_valid: true,
-                    expires_soon: false,
-                });
-            }
+        let threshold = self.config.expiry_warning_threshold();
+        load_keyring_account_status(&self.account_store, &self.accounts, threshold).await
+    }
 
-            *self.accounts.write().await = status_list;
-            info!("Refreshed account status: {} accounts loaded", self.accounts.read().await.len());
-        }
+    /// Refresh account status whenever `store` changes on disk, such as when
+    /// the CLI adds an account
+    fn watch_account_store(&self, store: &AccountStore) -> Result<WatchHandle, AccountStoreError> {
+        let runtime = tokio::runtime::Handle::current();
+        let account_store = Arc::clone(&self.account_store);
+        let accounts = Arc::clone(&self.accounts);
+        let threshold = self.config.expiry_warning_threshold();
+
+        store.watch(move || {
+            let account_store = Arc::clone(&account_store);
+            let accounts = Arc::clone(&accounts);
+            runtime.spawn(async move {
+                info!("Account store changed on disk");
+                let status = load_keyring_account_status(&account_store, &accounts, threshold);
+                if let Err(e) = status.await {
+                    error!("Failed to refresh account status: {}", e);
+                }
+            });
+        })
+    }
 
-        Ok(())
+    /// Refresh account status whenever an account is changed through
+    /// `store`, such as by [`handle_add_account`](Self::handle_add_account)
+    ///
+    /// Events that arrive while a refresh runs are handled by one more
+    /// refresh. The task ends when the store is dropped.
+    fn subscribe_account_events(&self, store: &AccountStore) -> tokio::task::JoinHandle<()> {
+        let mut events = store.subscribe();
+        let account_store = Arc::clone(&self.account_store);
+        let accounts = Arc::clone(&self.accounts);
+        let threshold = self.config.expiry_warning_threshold();
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => debug!("Account store event: {:?}", event),
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        debug!("Missed {} account store events", missed)
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+                while !events.is_empty() {
+                    let _ = events.try_recv();
+                }
+
+                let status = load_keyring_account_status(&account_store, &accounts, threshold);
+                if let Err(e) = status.await {
+                    error!("Failed to refresh account status: {}", e);
+                }
+            }
+        })
     }
 
     /// Handle adding a new account for a specific service
+    ///
+    /// The account is named `default` (`default-2`, ... when taken). The PKCE
+    /// flow runs in a background task so the menu stays responsive; the
+    /// account appears in the status bar once authorization completes.
     async fn handle_add_account(&self, service: &str, _ctx: &PluginContext) -> PluginResult<()> {
         info!("Adding new {} account via Sigilforge", service);
 
-        // In a full implementation, this would:
-        // 1. Launch the OAuth flow via sigilforge-daemon
-        // 2. Wait for the flow to complete
-        // 3. Refresh the account status
+        let registry = ProviderRegistry::with_defaults();
+        let Some(provider) = registry.get(service).cloned() else {
+            warn!("Unknown provider: {}", service);
+            return Ok(());
+        };
+
+        // Client credentials and callback port are configured as for the CLI
+        let prefix = service.to_uppercase();
+        let env = |name: &str| std::env::var(name).ok();
+        let Some(client_id) =
+            env(&format!("{}_CLIENT_ID", prefix)).or_else(|| env("OAUTH_CLIENT_ID"))
+        else {
+            warn!("Cannot add {} account: set {}_CLIENT_ID or OAUTH_CLIENT_ID", service, prefix);
+            return Ok(());
+        };
+        let client_secret =
+            env(&format!("{}_CLIENT_SECRET", prefix)).or_else(|| env("OAUTH_CLIENT_SECRET"));
+        let port = env("OAUTH_CALLBACK_PORT")
+            .and_then(|port| port.parse().ok())
+            .unwrap_or(8484);
+
+        let account = match self.account_store.read().await.as_ref() {
+            Some(store) => match unused_account_id(store, service) {
+                Ok(account) => account,
+                Err(e) => {
+                    error!("Failed to read account store: {}", e);
+                    return Ok(());
+                }
+            },
+            None => {
+                warn!("Cannot add {} account: account store is not loaded", service);
+                return Ok(());
+            }
+        };
+
+        let scopes = provider.default_scopes.clone();
+        let flow = match PkceFlow::new(
+            provider,
+            client_id,
+            client_secret,
+            RedirectConfig::localhost(port),
+        ) {
+            Ok(flow) => flow,
+            Err(e) => {
+                error!("Failed to start OAuth flow for {}: {}", service, e);
+                return Ok(());
+            }
+        };
+
+        let service = ServiceId::new(service);
+        let account_store = Arc::clone(&self.account_store);
+        let accounts = Arc::clone(&self.accounts);
+        let threshold = self.config.expiry_warning_threshold();
+        tokio::spawn(async move {
+            match authorize_account(flow, &service, &account, scopes, &account_store).await {
+                Ok(()) => {
+                    info!("Added account {}/{}", service, account);
+                    let status = load_keyring_account_status(&account_store, &accounts, threshold);
+                    if let Err(e) = status.await {
+                        error!("Failed to refresh account status: {}", e);
+                    }
+                }
+                Err(e) => error!("Adding account {}/{} failed: {:#}", service, account, e),
+            }
+        });
 
-        warn!("Add account functionality requires sigilforge-daemon integration");
         Ok(())
     }
 
@@ -145,6 +261,139 @@ impl SigilforgePlugin {
     }
 }
 
+/// Token manager over the keyring the CLI and daemon store tokens in
+fn keyring_token_manager() -> Result<DefaultTokenManager<KeyringStore>, StoreError> {
+    Ok(DefaultTokenManager::new(
+        KeyringStore::try_new("sigilforge")?,
+        ProviderRegistry::with_defaults(),
+    ))
+}
+
+/// [`load_account_status`] with tokens read from the keyring
+///
+/// If the keyring is unavailable, every account is shown as invalid.
+async fn load_keyring_account_status(
+    account_store: &RwLock<Option<AccountStore>>,
+    accounts: &RwLock<Vec<AccountStatus>>,
+    expiry_warning: chrono::Duration,
+) -> Result<(), AccountStoreError> {
+    let tokens = match keyring_token_manager() {
+        Ok(tokens) => Some(tokens),
+        Err(e) => {
+            warn!("Cannot check token expiry: {}", e);
+            None
+        }
+    };
+    load_account_status(account_store, accounts, tokens.as_ref(), expiry_warning).await
+}
+
+/// Load account status from `account_store` into `accounts`
+///
+/// A token is valid if it has not expired or can be refreshed, and expires
+/// soon if it expires within `expiry_warning`.
+async fn load_account_status(
+    account_store: &RwLock<Option<AccountStore>>,
+    accounts: &RwLock<Vec<AccountStatus>>,
+    tokens: Option<&impl TokenManager>,
+    expiry_warning: chrono::Duration,
+) -> Result<(), AccountStoreError> {
+    let store = account_store.read().await;
+
+    if let Some(store) = store.as_ref() {
+        let all_accounts = store.list_accounts(None)?;
+        let mut status_list = Vec::new();
+
+        for account in all_accounts {
+            let token_set = match tokens {
+                Some(tokens) => tokens
+                    .get_token_set(&account.service, &account.id)
+                    .await
+                    .unwrap_or_else(|e| {
+                        warn!(
+                            "Failed to read token for {}/{}: {}",
+                            account.service, account.id, e
+                        );
+                        None
+                    }),
+                None => None,
+            };
+            let (token_valid, expires_soon) = match token_set {
+                Some(set) => (
+                    !set.access_token.is_expired() || set.refresh_token.is_some(),
+                    set.access_token.expires_within(expiry_warning),
+                ),
+                None => (false, false),
+            };
+
+            status_list.push(AccountStatus {
+                service: account.service.as_str().to_string(),
+                account: account.id.as_str().to_string(),
+                token_valid,
+                expires_soon,
+            });
+        }
+
+        *accounts.write().await = status_list;
+        info!("Refreshed account status: {} accounts loaded", accounts.read().await.len());
+    }
+
+    Ok(())
+}
+
+/// First of `default`, `default-2`, `default-3`, ... not used for `service`
+fn unused_account_id(store: &AccountStore, service: &str) -> Result<AccountId, AccountStoreError> {
+    let service = ServiceId::new(service);
+    let mut n = 1;
+    loop {
+        let candidate = match n {
+            1 => AccountId::new("default"),
+            n => AccountId::new(format!("default-{}", n)),
+        };
+        if store.get_account(&service, &candidate)?.is_none() {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
+/// Run `flow` in the browser, then save the account and its tokens
+async fn authorize_account(
+    flow: PkceFlow,
+    service: &ServiceId,
+    account: &AccountId,
+    scopes: Vec<String>,
+    account_store: &RwLock<Option<AccountStore>>,
+) -> anyhow::Result<()> {
+    let host = flow.redirect().host.clone();
+    let (flow, listener) = flow.prepare_flow(scopes.clone(), &host).await?;
+    let (auth_url, csrf_state) = flow.build_authorization_url(Vec::new(), HashMap::new());
+
+    info!("Authorize {}/{} in your browser: {}", service, account, auth_url);
+    if let Err(e) = open_browser(&auth_url) {
+        warn!("Could not open browser: {}", e);
+    }
+
+    let code = flow.listen_for_callback(listener, &csrf_state).await?;
+    let tokens = flow.exchange_code(code).await?;
+
+    let store = account_store.read().await;
+    let store = store
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("account store is not loaded"))?;
+    let source = CredentialSource::OAuthPkce {
+        provider_id: service.to_string(),
+    };
+    store.add_account(Account::new(service.clone(), account.clone(), scopes).with_source(source))?;
+
+    let manager = keyring_token_manager()?;
+    if let Err(e) = manager.store_token_set(service, account, tokens).await {
+        let _ = store.remove_account(service, account);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
 impl Default for SigilforgePlugin {
     fn default() -> Self {
         Self::new()
@@ -160,9 +409,19 @@ impl Plugin for SigilforgePlugin {
     async fn on_load(&mut self, _ctx: &mut PluginContext) -> PluginResult<()> {
         info!("Loading Sigilforge plugin");
 
+        match PluginConfig::load() {
+            Ok(config) => self.config = config,
+            Err(e) => error!("Failed to load plugin config, using defaults: {}", e),
+        }
+
         // Load the account store
         match AccountStore::load() {
             Ok(store) => {
+                match self.watch_account_store(&store) {
+                    Ok(watch) => self.account_watch = Some(watch),
+                    Err(e) => warn!("Account status will not follow external changes: {}", e),
+                }
+                self.account_events = Some(self.subscribe_account_events(&store));
                 *self.account_store.write().await = Some(store);
                 info!("Account store loaded successfully");
 
@@ -184,6 +443,10 @@ impl Plugin for SigilforgePlugin {
 
     async fn on_unload(&mut self) -> PluginResult<()> {
         info!("Unloading Sigilforge plugin");
+        self.account_watch = None;
+        if let Some(task) = self.account_events.take() {
+            task.abort();
+        }
         Ok(())
     }
 
@@ -241,6 +504,7 @@ impl Plugin for SigilforgePlugin {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sigilforge_core::{MemoryStore, Token, TokenSet};
 
     #[test]
     fn test_plugin_metadata() {
@@ -272,4 +536,90 @@ mod tests {
             panic!("Expected SubMenu action for Add Account");
         }
     }
+
+    /// Status of accounts holding tokens that expire `minutes` from now
+    async fn status_with_tokens_expiring_in(
+        minutes: &[i64],
+        expiry_warning: chrono::Duration,
+    ) -> Vec<AccountStatus> {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = AccountStore::load_from_path(dir.path().join("accounts.json")).unwrap();
+        let tokens = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+
+        let service = ServiceId::new("github");
+        for minutes in minutes {
+            let account = AccountId::new(format!("expires-in-{}", minutes));
+            store
+                .add_account(Account::new(service.clone(), account.clone(), vec![]))
+                .unwrap();
+            let expires_at = chrono::Utc::now() + chrono::Duration::minutes(*minutes);
+            let token = Token::new("token").with_expiry(expires_at);
+            tokens
+                .store_token_set(&service, &account, TokenSet::new(token))
+                .await
+                .unwrap();
+        }
+
+        let account_store = RwLock::new(Some(store));
+        let accounts = RwLock::new(Vec::new());
+        load_account_status(&account_store, &accounts, Some(&tokens), expiry_warning)
+            .await
+            .unwrap();
+        accounts.into_inner()
+    }
+
+    #[tokio::test]
+    async fn test_expires_soon_respects_threshold() {
+        let threshold = PluginConfig {
+            expiry_warning_mins: 60,
+        }
+        .expiry_warning_threshold();
+        let accounts = status_with_tokens_expiring_in(&[59, 61], threshold).await;
+
+        let expires_soon = |name: &str| {
+            let status = accounts.iter().find(|a| a.account == name).unwrap();
+            assert!(status.token_valid);
+            status.expires_soon
+        };
+        assert!(expires_soon("expires-in-59"));
+        assert!(!expires_soon("expires-in-61"));
+    }
+
+    #[tokio::test]
+    async fn test_default_threshold_is_a_day() {
+        let threshold = PluginConfig::default().expiry_warning_threshold();
+        let accounts = status_with_tokens_expiring_in(&[1439, 1441], threshold).await;
+
+        let soon: Vec<_> = accounts
+            .iter()
+            .filter(|a| a.expires_soon)
+            .map(|a| a.account.as_str())
+            .collect();
+        assert_eq!(soon, ["expires-in-1439"]);
+    }
+
+    #[tokio::test]
+    async fn test_account_without_token_is_invalid() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = AccountStore::load_from_path(dir.path().join("accounts.json")).unwrap();
+        store
+            .add_account(Account::new(
+                ServiceId::new("github"),
+                AccountId::new("work"),
+                vec![],
+            ))
+            .unwrap();
+        let tokens = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+
+        let account_store = RwLock::new(Some(store));
+        let accounts = RwLock::new(Vec::new());
+        let threshold = chrono::Duration::hours(24);
+        load_account_status(&account_store, &accounts, Some(&tokens), threshold)
+            .await
+            .unwrap();
+
+        let accounts = accounts.into_inner();
+        assert!(!accounts[0].token_valid);
+        assert!(!accounts[0].expires_soon);
+    }
 }
@@ -0,0 +1,325 @@
+//! Reading the daemon's request audit log for `sigilforge audit`.
+//!
+//! The daemon writes one JSON object per handled request to the file named
+//! by `audit_log_path` in `daemon.toml`. This module finds that file the
+//! same way the daemon finds its config, then parses and filters entries.
+//! The same config supplies the `store_namespace` secrets are kept under.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sigilforge_core::token_manager::is_valid_key_prefix;
+
+/// One request recorded by the daemon.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub request_id: String,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    pub success: bool,
+    pub duration_ms: u64,
+}
+
+/// Criteria an entry must match to be shown.
+#[derive(Debug, Default)]
+pub struct AuditFilter {
+    /// Only entries at or after this time
+    pub since: Option<DateTime<Utc>>,
+    pub service: Option<String>,
+    pub account: Option<String>,
+    pub method: Option<String>,
+    /// Keep only the last N matching entries
+    pub tail: Option<usize>,
+}
+
+impl AuditFilter {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.since.is_none_or(|since| entry.timestamp >= since)
+            && field_matches(self.service.as_deref(), entry.service.as_deref())
+            && field_matches(self.account.as_deref(), entry.account.as_deref())
+            && field_matches(self.method.as_deref(), Some(&entry.method))
+    }
+
+    /// Entries matching every criterion, oldest first.
+    pub fn apply(&self, entries: Vec<AuditEntry>) -> Vec<AuditEntry> {
+        let mut entries: Vec<AuditEntry> = entries
+            .into_iter()
+            .filter(|entry| self.matches(entry))
+            .collect();
+        if let Some(tail) = self.tail {
+            entries.drain(..entries.len().saturating_sub(tail));
+        }
+        entries
+    }
+}
+
+fn field_matches(wanted: Option<&str>, actual: Option<&str>) -> bool {
+    wanted.is_none_or(|wanted| actual == Some(wanted))
+}
+
+/// Subset of `daemon.toml` the CLI needs.
+#[derive(Default, Deserialize)]
+struct DaemonConfigFile {
+    #[serde(default)]
+    audit_log_path: Option<PathBuf>,
+    #[serde(default)]
+    store_namespace: Option<String>,
+}
+
+/// The daemon's config file: `daemon.toml` in `config_dir` or the default
+/// config directory, or `sigilforge-daemon.toml` in the working directory if
+/// there is none.
+pub fn daemon_config_path(config_dir: Option<&Path>) -> PathBuf {
+    config_dir
+        .map(Path::to_path_buf)
+        .or_else(sigilforge_core::account_store::config_dir)
+        .map(|dir| dir.join("daemon.toml"))
+        .unwrap_or_else(|| PathBuf::from("sigilforge-daemon.toml"))
+}
+
+/// The daemon config at `config_path`, or the defaults if it does not exist.
+fn read_daemon_config(config_path: &Path) -> Result<DaemonConfigFile> {
+    if !config_path.exists() {
+        return Ok(DaemonConfigFile::default());
+    }
+
+    let contents = std::fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read config from {:?}", config_path))?;
+    toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse config from {:?}", config_path))
+}
+
+/// Audit log path configured in the daemon config at `config_path`.
+///
+/// Returns `None` if the file does not exist or sets no `audit_log_path`.
+pub fn configured_audit_log(config_path: &Path) -> Result<Option<PathBuf>> {
+    Ok(read_daemon_config(config_path)?.audit_log_path)
+}
+
+/// Secret store namespace configured in the daemon config at `config_path`.
+///
+/// Returns `None` if the file does not exist or sets no `store_namespace`,
+/// and an error if the namespace is empty or contains `/`, as the daemon
+/// would refuse to start with it.
+pub fn configured_store_namespace(config_path: &Path) -> Result<Option<String>> {
+    let namespace = read_daemon_config(config_path)?.store_namespace;
+    let invalid = namespace
+        .as_deref()
+        .filter(|namespace| !is_valid_key_prefix(namespace));
+    if let Some(namespace) = invalid {
+        anyhow::bail!(
+            "store_namespace {:?} in {:?} must not be empty or contain '/'",
+            namespace,
+            config_path
+        );
+    }
+    Ok(namespace)
+}
+
+/// Parse every entry in the log at `path`.
+///
+/// Lines that are not valid entries are skipped with a warning on stderr,
+/// so a partially written line does not hide the rest of the log.
+pub fn read_entries(path: &Path) -> Result<Vec<AuditEntry>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read audit log {:?}", path))?;
+
+    let mut entries = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => eprintln!(
+                "Warning: skipping invalid audit entry on line {}: {}",
+                index + 1,
+                e
+            ),
+        }
+    }
+    Ok(entries)
+}
+
+/// Parse `--since`: an RFC 3339 timestamp, `YYYY-MM-DD HH:MM:SS` (UTC), or
+/// a date meaning midnight UTC.
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(timestamp) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(timestamp.and_utc());
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+    Err(format!(
+        "invalid datetime '{}' (expected e.g. 2025-01-31 or 2025-01-31T12:00:00Z)",
+        value
+    ))
+}
+
+/// Render entries as an aligned table with a header row.
+pub fn format_table(entries: &[AuditEntry]) -> String {
+    let rows: Vec<[String; 6]> = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+                entry.method.clone(),
+                entry.service.clone().unwrap_or_else(|| "-".to_string()),
+                entry.account.clone().unwrap_or_else(|| "-".to_string()),
+                if entry.success { "ok" } else { "error" }.to_string(),
+                format!("{}ms", entry.duration_ms),
+            ]
+        })
+        .collect();
+
+    let header = ["TIME", "METHOD", "SERVICE", "ACCOUNT", "STATUS", "DURATION"].map(String::from);
+    let mut widths = header.clone().map(|cell| cell.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        table.push_str(cells.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str, method: &str, service: Option<&str>) -> AuditEntry {
+        AuditEntry {
+            timestamp: parse_since(timestamp).unwrap(),
+            request_id: "id".to_string(),
+            method: method.to_string(),
+            service: service.map(|s| s.to_string()),
+            account: service.map(|_| "personal".to_string()),
+            success: true,
+            duration_ms: 5,
+        }
+    }
+
+    #[test]
+    fn test_parse_since_formats() {
+        let expected = parse_since("2025-01-31T12:30:00Z").unwrap();
+        assert_eq!(parse_since("2025-01-31 12:30:00").unwrap(), expected);
+        assert_eq!(parse_since("2025-01-31T12:30:00").unwrap(), expected);
+        assert_eq!(parse_since("2025-01-31T14:30:00+02:00").unwrap(), expected);
+        assert_eq!(
+            parse_since("2025-01-31").unwrap(),
+            parse_since("2025-01-31T00:00:00Z").unwrap()
+        );
+        assert!(parse_since("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_tail_keeps_last_matching_entries() {
+        let entries = vec![
+            entry("2025-01-01", "get_token", Some("github")),
+            entry("2025-01-02", "health_check", None),
+            entry("2025-01-03", "get_token", Some("github")),
+            entry("2025-01-04", "get_token", Some("github")),
+        ];
+        let filter = AuditFilter {
+            method: Some("get_token".to_string()),
+            tail: Some(2),
+            ..Default::default()
+        };
+
+        let days: Vec<String> = filter
+            .apply(entries)
+            .iter()
+            .map(|entry| entry.timestamp.format("%d").to_string())
+            .collect();
+        assert_eq!(days, ["03", "04"]);
+    }
+
+    #[test]
+    fn test_service_filter_excludes_untargeted_requests() {
+        let entries = vec![
+            entry("2025-01-01", "get_token", Some("github")),
+            entry("2025-01-02", "health_check", None),
+        ];
+        let filter = AuditFilter {
+            service: Some("github".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(entries).len(), 1);
+    }
+
+    #[test]
+    fn test_format_table_aligns_columns() {
+        let table = format_table(&[
+            entry("2025-01-01T08:00:00Z", "get_token", Some("github")),
+            entry("2025-01-01T09:00:00Z", "health_check", None),
+        ]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("TIME"));
+        assert_eq!(lines[0].find("METHOD"), lines[1].find("get_token"));
+        let service_column = lines[0].find("SERVICE").unwrap();
+        assert!(lines[1][service_column..].starts_with("github"));
+        assert!(lines[2][service_column..].starts_with('-'));
+        assert!(lines[2].contains("2025-01-01 09:00:00  health_check"));
+    }
+
+    #[test]
+    fn test_configured_audit_log() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config_path = dir.path().join("daemon.toml");
+        assert_eq!(configured_audit_log(&config_path).unwrap(), None);
+
+        std::fs::write(&config_path, "socket_path = \"/tmp/s.sock\"\n").unwrap();
+        assert_eq!(configured_audit_log(&config_path).unwrap(), None);
+
+        std::fs::write(
+            &config_path,
+            "audit_log_path = \"/var/log/sigilforge.log\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            configured_audit_log(&config_path).unwrap(),
+            Some(PathBuf::from("/var/log/sigilforge.log"))
+        );
+    }
+
+    #[test]
+    fn test_configured_store_namespace() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config_path = dir.path().join("daemon.toml");
+        assert_eq!(configured_store_namespace(&config_path).unwrap(), None);
+
+        std::fs::write(&config_path, "store_namespace = \"myapp\"\n").unwrap();
+        assert_eq!(
+            configured_store_namespace(&config_path).unwrap().as_deref(),
+            Some("myapp")
+        );
+
+        for invalid in ["", "my/app"] {
+            let contents = format!("store_namespace = {:?}\n", invalid);
+            std::fs::write(&config_path, contents).unwrap();
+            assert!(configured_store_namespace(&config_path).is_err(), "{:?}", invalid);
+        }
+    }
+}
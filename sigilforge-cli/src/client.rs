@@ -7,6 +7,8 @@ use anyhow::Result;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use sigilforge_core::CredentialSource;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::UnixStream;
@@ -17,6 +19,9 @@ use tracing::{debug, warn};
 pub struct GetTokenResponse {
     pub token: String,
     pub expires_at: Option<String>,
+    /// Scopes granted to the token; empty if unknown
+    #[serde(default)]
+    pub scopes: Vec<String>,
 }
 
 /// Information about a configured account.
@@ -27,6 +32,9 @@ pub struct AccountInfo {
     pub scopes: Vec<String>,
     pub created_at: String,
     pub last_used: Option<String>,
+    /// How the credentials were obtained; older daemons omit this.
+    #[serde(default)]
+    pub source: CredentialSource,
 }
 
 /// Response containing a list of accounts.
@@ -47,6 +55,68 @@ pub struct ResolveResponse {
     pub value: String,
 }
 
+/// Response from the daemon health check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthResponse {
+    pub version: String,
+    pub uptime_secs: u64,
+    pub account_count: usize,
+    pub backend: String,
+    pub ok: bool,
+}
+
+/// Token status of one account, as the daemon sees it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountStatusInfo {
+    pub service: String,
+    pub account: String,
+    pub token_valid: bool,
+    #[serde(default)]
+    pub refreshable: bool,
+    pub expires_soon: bool,
+    pub expires_at: Option<String>,
+    /// Why the account's token could not be read
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Response containing the token status of every account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountsStatusResponse {
+    pub accounts: Vec<AccountStatusInfo>,
+    pub all_valid: bool,
+    pub any_expiring_soon: bool,
+}
+
+/// Validation problems of one provider the daemon loaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderValidationInfo {
+    pub id: String,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// A provider file the daemon skipped because it failed to load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedProviderFile {
+    pub path: String,
+    pub error: String,
+}
+
+/// Response from validating the daemon's providers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateProvidersResponse {
+    pub providers: Vec<ProviderValidationInfo>,
+    pub skipped_files: Vec<SkippedProviderFile>,
+}
+
+impl ValidateProvidersResponse {
+    /// Whether every provider loaded without errors; warnings are allowed.
+    pub fn is_valid(&self) -> bool {
+        self.skipped_files.is_empty() && self.providers.iter().all(|p| p.errors.is_empty())
+    }
+}
+
 /// Client for communicating with the Sigilforge daemon.
 pub struct DaemonClient {
     stream: Option<UnixStream>,
@@ -90,9 +160,14 @@ impl DaemonClient {
         }
     }
 
+    /// Path of the socket this client was created for.
+    pub fn socket_path(&self) -> &Path {
+        &self._socket_path
+    }
+
     /// Connect to daemon using default socket path.
-    pub async fn connect_default() -> Result<Self> {
-        let socket_path = default_socket_path();
+    pub async fn connect_default(config_dir: Option<&Path>) -> Result<Self> {
+        let socket_path = default_socket_path(config_dir);
         Self::connect(&socket_path).await
     }
 
@@ -154,6 +229,17 @@ impl DaemonClient {
             .await
     }
 
+    /// Get a new access token from the provider, even if the cached one is
+    /// still valid.
+    pub async fn refresh_token(
+        &mut self,
+        service: &str,
+        account: &str,
+    ) -> Result<GetTokenResponse> {
+        self.send_request("get_token", json!([service, account, true]))
+            .await
+    }
+
     /// List all configured accounts, optionally filtered by service.
     pub async fn list_accounts(
         &mut self,
@@ -164,24 +250,65 @@ impl DaemonClient {
     }
 
     /// Add a new account with the specified scopes.
+    ///
+    /// With `revoke_existing`, an existing account of the same name is
+    /// revoked and replaced instead of being an error.
     pub async fn add_account(
         &mut self,
         service: &str,
         account: &str,
         scopes: Vec<String>,
+        revoke_existing: bool,
     ) -> Result<AddAccountResponse> {
-        self.send_request("add_account", json!([service, account, scopes]))
-            .await
+        self.send_request(
+            "add_account",
+            json!([service, account, scopes, revoke_existing]),
+        )
+        .await
     }
 
     /// Resolve a credential reference to its actual value.
     pub async fn resolve(&mut self, reference: &str) -> Result<ResolveResponse> {
         self.send_request("resolve", json!([reference])).await
     }
+
+    /// Query the daemon's health status.
+    pub async fn health_check(&mut self) -> Result<HealthResponse> {
+        self.send_request("health_check", json!([])).await
+    }
+
+    /// Query the token status of every account.
+    pub async fn accounts_status(&mut self) -> Result<AccountsStatusResponse> {
+        self.send_request("accounts_status", json!([])).await
+    }
+
+    /// Validate the providers the daemon has loaded.
+    pub async fn validate_providers(&mut self) -> Result<ValidateProvidersResponse> {
+        self.send_request("validate_providers", json!([])).await
+    }
 }
 
 /// Get the default socket path for the daemon.
-pub fn default_socket_path() -> PathBuf {
+///
+/// A `daemon.sock` in `config_dir` is used if it exists. Otherwise the
+/// `SIGILFORGE_SOCKET` environment variable overrides the platform default.
+pub fn default_socket_path(config_dir: Option<&Path>) -> PathBuf {
+    resolve_socket_path(config_dir, std::env::var_os("SIGILFORGE_SOCKET"))
+}
+
+/// Socket path for `config_dir` given the value of `SIGILFORGE_SOCKET`.
+///
+/// An empty `env_socket` is treated as unset.
+fn resolve_socket_path(config_dir: Option<&Path>, env_socket: Option<OsString>) -> PathBuf {
+    let in_config_dir = config_dir.map(|dir| dir.join("daemon.sock"));
+    if let Some(path) = in_config_dir.filter(|path| path.exists()) {
+        return path;
+    }
+
+    if let Some(path) = env_socket.filter(|path| !path.is_empty()) {
+        return PathBuf::from(path);
+    }
+
     let dirs = ProjectDirs::from("com", "raibid-labs", "sigilforge");
 
     if cfg!(unix) {
@@ -192,3 +319,39 @@ pub fn default_socket_path() -> PathBuf {
         PathBuf::from(r"\\.\pipe\sigilforge")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_socket_in_config_dir_comes_first() {
+        let dir = TempDir::new().unwrap();
+        let socket = dir.path().join("daemon.sock");
+
+        // Only a socket that is there is preferred over the usual location
+        let usual = resolve_socket_path(None, None);
+        assert_eq!(resolve_socket_path(Some(dir.path()), None), usual);
+        std::fs::write(&socket, "").unwrap();
+        assert_eq!(resolve_socket_path(Some(dir.path()), None), socket);
+
+        let env_socket = Some(OsString::from("/run/custom.sock"));
+        assert_eq!(resolve_socket_path(Some(dir.path()), env_socket), socket);
+    }
+
+    #[test]
+    fn test_socket_env_overrides_platform_default() {
+        let env_socket = Some(OsString::from("/run/custom.sock"));
+        assert_eq!(
+            resolve_socket_path(None, env_socket),
+            PathBuf::from("/run/custom.sock")
+        );
+
+        // An empty value is ignored
+        assert_eq!(
+            resolve_socket_path(None, Some(OsString::new())),
+            resolve_socket_path(None, None)
+        );
+    }
+}
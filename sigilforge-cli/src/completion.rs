@@ -0,0 +1,99 @@
+//! Shell completion generation and installation.
+//!
+//! Completion scripts are generated from the clap command definition, so
+//! every subcommand and flag is covered automatically.
+
+use anyhow::{Context, Result};
+use clap::Command;
+use clap_complete::Shell;
+use directories::BaseDirs;
+use std::path::PathBuf;
+
+/// Binary name the completion scripts are registered for.
+const BIN_NAME: &str = "sigilforge";
+
+/// Where a completion script is installed and how to activate it.
+pub struct InstallTarget {
+    /// Full path of the script file
+    pub path: PathBuf,
+    /// Instruction printed after installing
+    pub instructions: String,
+}
+
+/// Generate the completion script for `shell`.
+pub fn generate(cmd: &mut Command, shell: Shell) -> Vec<u8> {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, cmd, BIN_NAME, &mut script);
+    script
+}
+
+/// Determine the per-user completion location for `shell`.
+pub fn install_target(shell: Shell) -> Result<InstallTarget> {
+    let dirs = BaseDirs::new().context("Could not determine home directory")?;
+    let home = dirs.home_dir();
+
+    let target = match shell {
+        Shell::Bash => {
+            let path = home.join(".bash_completion.d").join(BIN_NAME);
+            InstallTarget {
+                instructions: format!("Add to ~/.bashrc:\n  source {}", path.display()),
+                path,
+            }
+        }
+        Shell::Zsh => {
+            let dir = home.join(".zfunc");
+            InstallTarget {
+                path: dir.join(format!("_{}", BIN_NAME)),
+                instructions: format!(
+                    "Add to ~/.zshrc (before compinit):\n  fpath=({} $fpath)\n  \
+                     autoload -Uz compinit && compinit",
+                    dir.display()
+                ),
+            }
+        }
+        Shell::Fish => InstallTarget {
+            path: dirs
+                .config_dir()
+                .join("fish")
+                .join("completions")
+                .join(format!("{}.fish", BIN_NAME)),
+            instructions: "Fish loads completions from this directory automatically".to_string(),
+        },
+        Shell::Elvish => InstallTarget {
+            path: dirs
+                .config_dir()
+                .join("elvish")
+                .join("lib")
+                .join(format!("{}.elv", BIN_NAME)),
+            instructions: format!("Add to ~/.config/elvish/rc.elv:\n  use {}", BIN_NAME),
+        },
+        Shell::PowerShell => {
+            let path = dirs
+                .config_dir()
+                .join("powershell")
+                .join(format!("{}.ps1", BIN_NAME));
+            InstallTarget {
+                instructions: format!("Add to your $PROFILE:\n  . {}", path.display()),
+                path,
+            }
+        }
+        other => anyhow::bail!("Installing completions for {} is not supported", other),
+    };
+
+    Ok(target)
+}
+
+/// Write the completion script for `shell` to its install location.
+pub fn install(cmd: &mut Command, shell: Shell) -> Result<InstallTarget> {
+    let target = install_target(shell)?;
+
+    if let Some(parent) = target.path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    std::fs::write(&target.path, generate(cmd, shell))
+        .with_context(|| format!("Failed to write {}", target.path.display()))?;
+
+    Ok(target)
+}
@@ -0,0 +1,337 @@
+//! Import access keys from the AWS CLI's shared credentials file.
+//!
+//! Every `[profile]` section of `~/.aws/credentials` becomes an
+//! `aws/{profile}` account: `aws_access_key_id` is stored as its
+//! [`CredentialType::ApiKey`], `aws_secret_access_key` as its
+//! [`CredentialType::ClientSecret`], and `aws_session_token`, when present,
+//! as its [`CredentialType::AccessToken`]. Other keys (e.g. `region`) are
+//! ignored.
+//!
+//! Session tokens are temporary, so their expiry is stored as the
+//! [`CredentialType::TokenExpiry`]: the RFC 3339 `aws_expiration` (or
+//! `x_security_token_expires`) some credential helpers write, otherwise
+//! [`DEFAULT_SESSION_DURATION`] after the file was last written.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use directories::BaseDirs;
+use sigilforge_core::{store::Secret, AccountId, CredentialType, ServiceId};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::{CredentialImporter, ImportReport, ImportedCredential};
+
+/// The AWS CLI's credentials file override.
+const AWS_CREDENTIALS_FILE_ENV: &str = "AWS_SHARED_CREDENTIALS_FILE";
+
+/// Service imported profiles are registered under.
+const AWS_SERVICE: &str = "aws";
+
+/// Keys credential helpers record a session token's expiry under.
+const EXPIRATION_KEYS: [&str; 2] = ["aws_expiration", "x_security_token_expires"];
+
+/// Lifetime assumed for a session token without a recorded expiry: one
+/// hour, the shortest default duration of an STS session.
+pub const DEFAULT_SESSION_DURATION: Duration = Duration::hours(1);
+
+/// Reads access keys from an AWS CLI credentials file.
+pub struct AwsCliImporter {
+    path: PathBuf,
+    profile: Option<String>,
+}
+
+impl AwsCliImporter {
+    /// Import from the credentials file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            profile: None,
+        }
+    }
+
+    /// The AWS CLI's credentials file: `$AWS_SHARED_CREDENTIALS_FILE`,
+    /// otherwise `~/.aws/credentials`.
+    pub fn default_path() -> Result<PathBuf> {
+        if let Some(path) =
+            std::env::var_os(AWS_CREDENTIALS_FILE_ENV).filter(|path| !path.is_empty())
+        {
+            return Ok(PathBuf::from(path));
+        }
+
+        let dirs = BaseDirs::new().context("Could not determine home directory")?;
+        Ok(dirs.home_dir().join(".aws").join("credentials"))
+    }
+
+    /// Only import the profile named `profile`.
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+}
+
+/// Parse the sections of an INI file into `section -> key -> value` maps.
+///
+/// Lines starting with `#` or `;` are comments. Keys before the first
+/// section header are ignored.
+pub fn parse_ini(contents: &str) -> Result<BTreeMap<String, BTreeMap<String, String>>> {
+    let mut sections: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .with_context(|| format!("line {}: unterminated section header", number + 1))?
+                .trim()
+                .to_string();
+            sections.entry(name.clone()).or_default();
+            current = Some(name);
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .with_context(|| format!("line {}: expected KEY = VALUE", number + 1))?;
+        if let Some(section) = &current {
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(key.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    Ok(sections)
+}
+
+impl CredentialImporter for AwsCliImporter {
+    fn source(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn format(&self) -> &'static str {
+        "aws-credentials"
+    }
+
+    fn import(&self) -> Result<ImportReport> {
+        let contents = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let sections = parse_ini(&contents)
+            .with_context(|| format!("Failed to parse {}", self.path.display()))?;
+
+        let written_at = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+
+        let mut report = ImportReport::default();
+        if let Some(profile) = self.profile.as_ref().filter(|p| !sections.contains_key(*p)) {
+            report
+                .warnings
+                .push(format!("{}: no such profile", profile));
+        }
+
+        for (profile, keys) in sections {
+            if self.profile.as_ref().is_some_and(|wanted| *wanted != profile) {
+                continue;
+            }
+
+            let non_empty = |key: &str| keys.get(key).filter(|value| !value.is_empty());
+            let Some(access_key_id) = non_empty("aws_access_key_id") else {
+                report
+                    .warnings
+                    .push(format!("{}: no aws_access_key_id, skipping", profile));
+                continue;
+            };
+            let Some(secret_access_key) = non_empty("aws_secret_access_key") else {
+                report
+                    .warnings
+                    .push(format!("{}: no aws_secret_access_key, skipping", profile));
+                continue;
+            };
+
+            let mut credentials = vec![
+                (CredentialType::ApiKey, access_key_id.clone()),
+                (CredentialType::ClientSecret, secret_access_key.clone()),
+            ];
+            if let Some(session_token) = non_empty("aws_session_token") {
+                credentials.push((CredentialType::AccessToken, session_token.clone()));
+
+                let expiration = EXPIRATION_KEYS.iter().find_map(|key| non_empty(key));
+                let expires_at = match expiration.map(|at| DateTime::parse_from_rfc3339(at)) {
+                    Some(Ok(at)) => at.with_timezone(&Utc),
+                    Some(Err(e)) => {
+                        report.warnings.push(format!(
+                            "{}: invalid session token expiration ({}), assuming {} minutes",
+                            profile,
+                            e,
+                            DEFAULT_SESSION_DURATION.num_minutes()
+                        ));
+                        written_at + DEFAULT_SESSION_DURATION
+                    }
+                    None => written_at + DEFAULT_SESSION_DURATION,
+                };
+                credentials.push((
+                    CredentialType::TokenExpiry,
+                    expires_at.timestamp().to_string(),
+                ));
+            }
+
+            for (credential_type, value) in credentials {
+                report.credentials.push(ImportedCredential {
+                    service: ServiceId::new(AWS_SERVICE),
+                    account: AccountId::new(profile.clone()),
+                    credential_type,
+                    value: Secret::new(value),
+                    scopes: Vec::new(),
+                });
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    fn imported(report: &ImportReport) -> Vec<(String, CredentialType, String)> {
+        report
+            .credentials
+            .iter()
+            .map(|c| {
+                (
+                    c.account.to_string(),
+                    c.credential_type.clone(),
+                    c.value.expose().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_import_all_profiles() {
+        let report = AwsCliImporter::new(fixture("aws-credentials")).import().unwrap();
+
+        assert!(report.credentials.iter().all(|c| c.service.to_string() == "aws"));
+        assert_eq!(
+            imported(&report),
+            vec![
+                ("default".to_string(), CredentialType::ApiKey, "AKIADEFAULTEXAMPLE".to_string()),
+                (
+                    "default".to_string(),
+                    CredentialType::ClientSecret,
+                    "default-secret-example".to_string()
+                ),
+                ("work".to_string(), CredentialType::ApiKey, "AKIAWORKEXAMPLE".to_string()),
+                (
+                    "work".to_string(),
+                    CredentialType::ClientSecret,
+                    "work-secret-example".to_string()
+                ),
+                (
+                    "work".to_string(),
+                    CredentialType::AccessToken,
+                    "work-session-example".to_string()
+                ),
+                (
+                    "work".to_string(),
+                    CredentialType::TokenExpiry,
+                    "2025-01-01T12:00:00Z"
+                        .parse::<DateTime<Utc>>()
+                        .unwrap()
+                        .timestamp()
+                        .to_string()
+                ),
+            ]
+        );
+
+        // The role-only profile has no keys to import
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("assumed: no aws_access_key_id"));
+    }
+
+    #[test]
+    fn test_profile_filter() {
+        let report = AwsCliImporter::new(fixture("aws-credentials"))
+            .with_profile(Some("work".to_string()))
+            .import()
+            .unwrap();
+
+        assert_eq!(report.credentials.len(), 4);
+        assert!(report.credentials.iter().all(|c| c.account.to_string() == "work"));
+        assert!(report.warnings.is_empty());
+
+        let report = AwsCliImporter::new(fixture("aws-credentials"))
+            .with_profile(Some("missing".to_string()))
+            .import()
+            .unwrap();
+        assert!(report.credentials.is_empty());
+        assert_eq!(report.warnings, vec!["missing: no such profile"]);
+    }
+
+    #[test]
+    fn test_session_token_without_expiration() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("credentials");
+        std::fs::write(
+            &path,
+            "[temp]\naws_access_key_id = AKIATEMP\naws_secret_access_key = secret\n\
+             aws_session_token = session\n\n\
+             [invalid]\naws_access_key_id = AKIAINVALID\naws_secret_access_key = secret\n\
+             aws_session_token = session\naws_expiration = soon\n",
+        )
+        .unwrap();
+        let written_at =
+            DateTime::<Utc>::from(std::fs::metadata(&path).unwrap().modified().unwrap());
+        let expected = (written_at + DEFAULT_SESSION_DURATION)
+            .timestamp()
+            .to_string();
+
+        let report = AwsCliImporter::new(&path).import().unwrap();
+
+        let expiries: Vec<_> = imported(&report)
+            .into_iter()
+            .filter(|(_, credential_type, _)| *credential_type == CredentialType::TokenExpiry)
+            .collect();
+        assert_eq!(
+            expiries,
+            vec![
+                (
+                    "invalid".to_string(),
+                    CredentialType::TokenExpiry,
+                    expected.clone()
+                ),
+                ("temp".to_string(), CredentialType::TokenExpiry, expected),
+            ]
+        );
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("invalid: invalid session token expiration"));
+    }
+
+    #[test]
+    fn test_parse_ini_errors() {
+        assert!(parse_ini("[default\nkey = value").is_err());
+        assert!(parse_ini("[default]\nnot a pair").is_err());
+        assert!(parse_ini("orphan = value\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let err = AwsCliImporter::new(fixture("missing-aws-credentials"))
+            .import()
+            .unwrap_err();
+        assert!(err.to_string().contains("missing-aws-credentials"));
+    }
+}
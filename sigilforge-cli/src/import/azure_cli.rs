@@ -0,0 +1,423 @@
+//! Import access tokens from the Azure CLI.
+//!
+//! `az account list` names the subscriptions the user is logged in to, and
+//! `az account get-access-token --subscription ID` issues a token for each.
+//! Every enabled subscription becomes an `azure/{name}` account, where the
+//! name is the subscription name in lowercase with runs of other characters
+//! replaced by `-` (`Dev Sandbox` becomes `dev-sandbox`). The subscription ID
+//! is used instead when that leaves nothing or another subscription already
+//! has the name. The token is stored as the account's
+//! [`CredentialType::AccessToken`], its expiry as its
+//! [`CredentialType::TokenExpiry`], and the tenant ID as a `tenant_id`
+//! credential.
+
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDateTime, TimeZone};
+use serde::Deserialize;
+use sigilforge_core::{store::Secret, AccountId, CredentialType, ServiceId};
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::process::Command;
+
+use super::{CredentialImporter, ImportReport, ImportedCredential};
+
+/// Service imported subscriptions are registered under.
+const AZURE_SERVICE: &str = "azure";
+
+/// Credential type the tenant ID is stored as.
+const TENANT_ID: &str = "tenant_id";
+
+/// One entry of `az account list --output json`; other keys are ignored.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// `Enabled`, `Disabled`, `Warned`, ...
+    #[serde(default)]
+    pub state: Option<String>,
+}
+
+impl Subscription {
+    /// The subscription name in lowercase, with every run of characters
+    /// other than ASCII letters and digits replaced by a single `-`.
+    pub fn slug(&self) -> String {
+        let mut slug = String::new();
+        for c in self.name.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        slug.trim_end_matches('-').to_string()
+    }
+}
+
+/// Output of `az account get-access-token --output json`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AzureAccessToken {
+    #[serde(rename = "accessToken")]
+    pub access_token: String,
+    /// Expiry in local time, as every release of `az` reports it
+    #[serde(rename = "expiresOn", default)]
+    pub expires_on: Option<String>,
+    /// Expiry as a Unix timestamp, reported by newer releases of `az`
+    #[serde(rename = "expires_on", default)]
+    pub expires_on_timestamp: Option<i64>,
+    /// Older releases of `az` call this `tenantId`
+    #[serde(rename = "tenant", alias = "tenantId", default)]
+    pub tenant_id: Option<String>,
+}
+
+impl AzureAccessToken {
+    /// When the token expires, as a Unix timestamp.
+    pub fn expiry_timestamp(&self) -> Option<i64> {
+        if let Some(timestamp) = self.expires_on_timestamp {
+            return Some(timestamp);
+        }
+
+        let expires_on = self.expires_on.as_deref()?;
+        let naive = NaiveDateTime::parse_from_str(expires_on, "%Y-%m-%d %H:%M:%S%.f").ok()?;
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|time| time.timestamp())
+    }
+}
+
+/// Parse the output of `az account list --output json`.
+pub fn parse_subscriptions(json: &str) -> Result<Vec<Subscription>> {
+    serde_json::from_str(json).context("Failed to parse az account list output")
+}
+
+/// Parse the output of `az account get-access-token --output json`.
+pub fn parse_access_token(json: &str) -> Result<AzureAccessToken> {
+    serde_json::from_str(json).context("Failed to parse az account get-access-token output")
+}
+
+/// Reads access tokens by running the Azure CLI.
+pub struct AzureCliImporter {
+    program: String,
+    subscription: Option<String>,
+}
+
+impl Default for AzureCliImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AzureCliImporter {
+    /// Import by running `az` from `$PATH`.
+    pub fn new() -> Self {
+        Self {
+            program: "az".to_string(),
+            subscription: None,
+        }
+    }
+
+    /// Only import the subscription with this name or ID.
+    pub fn with_subscription(mut self, subscription: Option<String>) -> Self {
+        self.subscription = subscription;
+        self
+    }
+
+    /// Run `az` with `args` and return its standard output.
+    fn run(&self, args: &[&str]) -> Result<String> {
+        let output = match Command::new(&self.program).args(args).output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == ErrorKind::NotFound => anyhow::bail!(
+                "{} not found in PATH; install the Azure CLI and run `az login`",
+                self.program
+            ),
+            Err(e) => return Err(e).with_context(|| format!("Failed to run {}", self.program)),
+        };
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} {} failed: {}",
+                self.program,
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        String::from_utf8(output.stdout).context("az printed invalid UTF-8")
+    }
+
+    /// Build the report from `az` output, where `run` runs `az` with the
+    /// given arguments.
+    fn import_with(&self, run: impl Fn(&[&str]) -> Result<String>) -> Result<ImportReport> {
+        let subscriptions = parse_subscriptions(&run(&["account", "list", "--output", "json"])?)?;
+
+        let mut report = ImportReport::default();
+        let mut account_names = HashSet::new();
+        if let Some(wanted) = &self.subscription {
+            if !subscriptions.iter().any(|s| s.name == *wanted || s.id == *wanted) {
+                report.warnings.push(format!("{}: no such subscription", wanted));
+            }
+        }
+
+        for subscription in subscriptions {
+            if let Some(wanted) = &self.subscription {
+                if subscription.name != *wanted && subscription.id != *wanted {
+                    continue;
+                }
+            }
+            if subscription.state.as_deref().is_some_and(|state| state != "Enabled") {
+                report.warnings.push(format!(
+                    "{}: subscription is {}, skipping",
+                    subscription.name,
+                    subscription.state.as_deref().unwrap_or_default()
+                ));
+                continue;
+            }
+
+            let args = [
+                "account",
+                "get-access-token",
+                "--subscription",
+                subscription.id.as_str(),
+                "--output",
+                "json",
+            ];
+            let token = match run(&args).and_then(|json| parse_access_token(&json)) {
+                Ok(token) => token,
+                Err(e) => {
+                    report
+                        .warnings
+                        .push(format!("{}: no access token: {:#}", subscription.name, e));
+                    continue;
+                }
+            };
+
+            let mut account = subscription.slug();
+            if account.is_empty() || !account_names.insert(account.clone()) {
+                account = subscription.id.clone();
+            }
+
+            let mut credentials = vec![(CredentialType::AccessToken, token.access_token.clone())];
+            if let Some(timestamp) = token.expiry_timestamp() {
+                credentials.push((CredentialType::TokenExpiry, timestamp.to_string()));
+            }
+            if let Some(tenant_id) = token.tenant_id.clone().or(subscription.tenant_id) {
+                credentials.push((CredentialType::Custom(TENANT_ID.to_string()), tenant_id));
+            }
+
+            for (credential_type, value) in credentials {
+                report.credentials.push(ImportedCredential {
+                    service: ServiceId::new(AZURE_SERVICE),
+                    account: AccountId::new(account.clone()),
+                    credential_type,
+                    value: Secret::new(value),
+                    scopes: Vec::new(),
+                });
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+impl CredentialImporter for AzureCliImporter {
+    fn source(&self) -> String {
+        "the Azure CLI".to_string()
+    }
+
+    fn format(&self) -> &'static str {
+        "azure-cli"
+    }
+
+    fn import(&self) -> Result<ImportReport> {
+        self.import_with(|args| self.run(args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name);
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    /// Answer `az` with the fixtures, issuing the same token to every
+    /// subscription.
+    fn fake_az(args: &[&str]) -> Result<String> {
+        match args {
+            ["account", "list", ..] => Ok(fixture("az-account-list.json")),
+            ["account", "get-access-token", "--subscription", id, ..] => {
+                Ok(fixture("az-access-token.json")
+                    .replace("0b1f6471-1bf0-4dda-aec3-cb9272f09590", id))
+            }
+            _ => anyhow::bail!("unexpected az {}", args.join(" ")),
+        }
+    }
+
+    fn imported(report: &ImportReport) -> Vec<(String, String, String)> {
+        report
+            .credentials
+            .iter()
+            .map(|c| {
+                (
+                    c.account.to_string(),
+                    c.credential_type.as_str().to_string(),
+                    c.value.expose().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_import_enabled_subscriptions() {
+        let report = AzureCliImporter::new().import_with(fake_az).unwrap();
+
+        assert!(report.credentials.iter().all(|c| c.service.to_string() == "azure"));
+        let token = "eyJ0eXAiOiJKV1QiLCJhbGciOiJSUzI1NiJ9.azure-example".to_string();
+        let tenant = "72f988bf-86f1-41af-91ab-2d7cd011db47".to_string();
+        let expiry = "1735736400".to_string();
+        assert_eq!(
+            imported(&report),
+            vec![
+                ("production".into(), "access_token".into(), token.clone()),
+                ("production".into(), "token_expiry".into(), expiry.clone()),
+                ("production".into(), "tenant_id".into(), tenant.clone()),
+                ("dev-sandbox".into(), "access_token".into(), token),
+                ("dev-sandbox".into(), "token_expiry".into(), expiry),
+                ("dev-sandbox".into(), "tenant_id".into(), tenant),
+            ]
+        );
+
+        assert_eq!(report.warnings, vec!["Legacy: subscription is Disabled, skipping"]);
+    }
+
+    #[test]
+    fn test_subscription_filter() {
+        let report = AzureCliImporter::new()
+            .with_subscription(Some("Dev Sandbox".to_string()))
+            .import_with(fake_az)
+            .unwrap();
+        assert_eq!(report.credentials.len(), 3);
+        assert!(report.credentials.iter().all(|c| c.account.as_str() == "dev-sandbox"));
+        assert!(report.warnings.is_empty());
+
+        // Subscriptions can also be picked by ID
+        let report = AzureCliImporter::new()
+            .with_subscription(Some("0b1f6471-1bf0-4dda-aec3-cb9272f09590".to_string()))
+            .import_with(fake_az)
+            .unwrap();
+        assert!(report.credentials.iter().all(|c| c.account.as_str() == "production"));
+
+        let report = AzureCliImporter::new()
+            .with_subscription(Some("Missing".to_string()))
+            .import_with(fake_az)
+            .unwrap();
+        assert!(report.credentials.is_empty());
+        assert_eq!(report.warnings, vec!["Missing: no such subscription"]);
+    }
+
+    #[test]
+    fn test_subscription_slug() {
+        let slug = |name: &str| {
+            let subscription = Subscription {
+                id: "0b1f6471-1bf0-4dda-aec3-cb9272f09590".to_string(),
+                name: name.to_string(),
+                tenant_id: None,
+                state: None,
+            };
+            subscription.slug()
+        };
+        assert_eq!(slug("Production"), "production");
+        assert_eq!(slug("Pay-As-You-Go / Team A"), "pay-as-you-go-team-a");
+        assert_eq!(slug("  (Dev) Sandbox #2 "), "dev-sandbox-2");
+        assert_eq!(slug("Über Prod"), "ber-prod");
+        assert_eq!(slug("***"), "");
+    }
+
+    #[test]
+    fn test_clashing_or_empty_names_use_subscription_id() {
+        let list = r#"[
+            {"id": "11111111-0000-0000-0000-000000000000", "name": "Dev Sandbox"},
+            {"id": "22222222-0000-0000-0000-000000000000", "name": "dev sandbox"},
+            {"id": "33333333-0000-0000-0000-000000000000", "name": "???"}
+        ]"#;
+        let report = AzureCliImporter::new()
+            .import_with(|args| match args {
+                ["account", "list", ..] => Ok(list.to_string()),
+                _ => Ok(fixture("az-access-token.json")),
+            })
+            .unwrap();
+
+        let accounts: Vec<&str> = report
+            .credentials
+            .iter()
+            .filter(|c| c.credential_type == CredentialType::AccessToken)
+            .map(|c| c.account.as_str())
+            .collect();
+        assert_eq!(
+            accounts,
+            vec![
+                "dev-sandbox",
+                "22222222-0000-0000-0000-000000000000",
+                "33333333-0000-0000-0000-000000000000",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failed_token_request_is_a_warning() {
+        let report = AzureCliImporter::new()
+            .import_with(|args| match args {
+                ["account", "list", ..] => Ok(fixture("az-account-list.json")),
+                _ => anyhow::bail!("AADSTS700082: The refresh token has expired"),
+            })
+            .unwrap();
+
+        assert!(report.credentials.is_empty());
+        assert_eq!(report.warnings.len(), 3);
+        assert!(report.warnings[0].starts_with("Production: no access token: AADSTS700082"));
+    }
+
+    #[test]
+    fn test_parse_older_access_token_output() {
+        let token = parse_access_token(
+            r#"{
+                "accessToken": "token",
+                "expiresOn": "2025-01-01 13:00:00.000000",
+                "subscription": "0b1f6471-1bf0-4dda-aec3-cb9272f09590",
+                "tenantId": "72f988bf-86f1-41af-91ab-2d7cd011db47",
+                "tokenType": "Bearer"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            token.tenant_id.as_deref(),
+            Some("72f988bf-86f1-41af-91ab-2d7cd011db47")
+        );
+        let naive = NaiveDateTime::parse_from_str("2025-01-01 13:00:00", "%Y-%m-%d %H:%M:%S");
+        let expected = Local.from_local_datetime(&naive.unwrap()).earliest().unwrap();
+        assert_eq!(token.expiry_timestamp(), Some(expected.timestamp()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_subscriptions("Please run 'az login' to setup account.").is_err());
+        assert!(parse_access_token(r#"{ "tokenType": "Bearer" }"#).is_err());
+    }
+
+    #[test]
+    fn test_missing_az() {
+        let importer = AzureCliImporter {
+            program: "sigilforge-test-missing-az".to_string(),
+            subscription: None,
+        };
+        let err = importer.import().unwrap_err();
+        assert!(err.to_string().contains("not found in PATH"), "{}", err);
+    }
+}
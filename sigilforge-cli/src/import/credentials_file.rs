@@ -0,0 +1,221 @@
+//! Load an account and its tokens from a JSON credentials file, for
+//! `sigilforge add-account --from-file`.
+//!
+//! The file describes a single account:
+//!
+//! ```json
+//! {
+//!   "service": "github",
+//!   "account": "work",
+//!   "scopes": ["repo", "read:org"],
+//!   "access_token": "gho_...",
+//!   "refresh_token": "ghr_...",
+//!   "expires_at": "2025-01-01T12:00:00Z",
+//!   "client_id": "...",
+//!   "client_secret": "..."
+//! }
+//! ```
+//!
+//! `service`, `account` and `access_token` are required; unknown keys are
+//! rejected so that a misspelt field is not silently dropped.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sigilforge_core::{store::Secret, AccountId, CredentialType, ServiceId};
+use std::path::PathBuf;
+
+use super::{CredentialImporter, ImportReport, ImportedCredential};
+
+/// Contents of a credentials file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CredentialsFile {
+    pub service: String,
+    pub account: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// RFC 3339 timestamp
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+}
+
+/// Parse and validate the contents of a credentials file.
+pub fn parse_credentials_file(json: &str) -> Result<CredentialsFile> {
+    let file: CredentialsFile = serde_json::from_str(json)?;
+    for (field, value) in [
+        ("service", &file.service),
+        ("account", &file.account),
+        ("access_token", &file.access_token),
+    ] {
+        if value.trim().is_empty() {
+            anyhow::bail!("`{}` must not be empty", field);
+        }
+    }
+    Ok(file)
+}
+
+/// Reads a single account from a JSON credentials file.
+pub struct CredentialsFileImporter {
+    path: PathBuf,
+}
+
+impl CredentialsFileImporter {
+    /// Import from the file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CredentialImporter for CredentialsFileImporter {
+    fn source(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn format(&self) -> &'static str {
+        "json"
+    }
+
+    fn import(&self) -> Result<ImportReport> {
+        let json = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let file = parse_credentials_file(&json)
+            .with_context(|| format!("Invalid credentials file {}", self.path.display()))?;
+
+        let mut credentials = vec![(CredentialType::AccessToken, file.access_token)];
+        let optional = [
+            (CredentialType::RefreshToken, file.refresh_token),
+            (
+                CredentialType::TokenExpiry,
+                file.expires_at.map(|time| time.timestamp().to_string()),
+            ),
+            (CredentialType::ClientId, file.client_id),
+            (CredentialType::ClientSecret, file.client_secret),
+        ];
+        for (credential_type, value) in optional {
+            if let Some(value) = value {
+                credentials.push((credential_type, value));
+            }
+        }
+
+        let credentials = credentials
+            .into_iter()
+            .map(|(credential_type, value)| ImportedCredential {
+                service: ServiceId::new(file.service.clone()),
+                account: AccountId::new(file.account.clone()),
+                credential_type,
+                value: Secret::new(value),
+                scopes: file.scopes.clone(),
+            })
+            .collect();
+        Ok(ImportReport {
+            credentials,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    fn imported(report: &ImportReport) -> Vec<(String, String)> {
+        report
+            .credentials
+            .iter()
+            .map(|c| {
+                let credential_type = c.credential_type.as_str().to_string();
+                (credential_type, c.value.expose().to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_import_all_fields() {
+        let report = CredentialsFileImporter::new(fixture("account-full.json"))
+            .import()
+            .unwrap();
+
+        let first = &report.credentials[0];
+        assert_eq!(first.service.as_str(), "github");
+        assert_eq!(first.account.as_str(), "work");
+        assert_eq!(first.scopes, vec!["repo", "read:org"]);
+        let expiry = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            imported(&report),
+            vec![
+                ("access_token".into(), "gho_example".into()),
+                ("refresh_token".into(), "ghr_example".into()),
+                ("token_expiry".into(), expiry.timestamp().to_string()),
+                ("client_id".into(), "client-id".into()),
+                ("client_secret".into(), "client-secret".into()),
+            ]
+        );
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_import_without_optional_fields() {
+        let report = CredentialsFileImporter::new(fixture("account-minimal.json"))
+            .import()
+            .unwrap();
+
+        assert_eq!(
+            imported(&report),
+            vec![("access_token".into(), "sk_example".into())]
+        );
+        assert!(report.credentials[0].scopes.is_empty());
+    }
+
+    #[test]
+    fn test_missing_access_token() {
+        let error = CredentialsFileImporter::new(fixture("account-missing-token.json"))
+            .import()
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("missing field `access_token`"));
+    }
+
+    #[test]
+    fn test_invalid_credentials_files() {
+        let invalid = [
+            (r#"{"service": "github""#, "EOF"),
+            (
+                r#"{"account": "work", "access_token": "t"}"#,
+                "missing field `service`",
+            ),
+            (
+                r#"{"service": "github", "access_token": "t"}"#,
+                "missing field `account`",
+            ),
+            (
+                r#"{"service": "github", "account": " ", "access_token": "t"}"#,
+                "`account` must not be empty",
+            ),
+            (
+                r#"{"service": "s", "account": "a", "access_token": "t", "expires": 1}"#,
+                "unknown field `expires`",
+            ),
+            (
+                r#"{"service": "s", "account": "a", "access_token": "t", "expires_at": "soon"}"#,
+                "input contains invalid characters",
+            ),
+        ];
+        for (json, message) in invalid {
+            let error = parse_credentials_file(json).unwrap_err();
+            assert!(error.to_string().contains(message), "{}: {}", json, error);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Import tokens from the GitHub CLI's `hosts.yml`.
+//!
+//! `gh` keeps one entry per host, each with the logged-in `user` and, unless
+//! the token lives in the system keyring, its `oauth_token`. The `github.com`
+//! entry becomes a `github/{user}` [`CredentialType::ApiKey`] credential;
+//! other hosts (GitHub Enterprise Server) need a `--service-map` entry.
+//! Scopes are read from `gh auth status` when `gh` is installed.
+
+use anyhow::{Context, Result};
+use directories::BaseDirs;
+use serde::Deserialize;
+use sigilforge_core::{store::Secret, AccountId, CredentialType, ServiceId};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::process::Command;
+
+use super::{CredentialImporter, ImportReport, ImportedCredential};
+
+/// The GitHub CLI's config directory override.
+const GH_CONFIG_DIR_ENV: &str = "GH_CONFIG_DIR";
+
+/// One host entry of `hosts.yml`; other keys (e.g. `users`) are ignored.
+#[derive(Debug, Deserialize)]
+struct HostEntry {
+    #[serde(default)]
+    user: Option<String>,
+    #[serde(default)]
+    oauth_token: Option<String>,
+}
+
+/// Reads tokens from a GitHub CLI `hosts.yml` file.
+pub struct GithubCliImporter {
+    path: PathBuf,
+    service_map: HashMap<String, String>,
+    lookup_scopes: bool,
+}
+
+impl GithubCliImporter {
+    /// Import from the `hosts.yml` file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            service_map: HashMap::from([("github.com".to_string(), "github".to_string())]),
+            lookup_scopes: true,
+        }
+    }
+
+    /// The GitHub CLI's `hosts.yml`: `$GH_CONFIG_DIR/hosts.yml`, otherwise
+    /// `gh` under `$XDG_CONFIG_HOME` or `~/.config`.
+    pub fn default_path() -> Result<PathBuf> {
+        if let Some(dir) = std::env::var_os(GH_CONFIG_DIR_ENV).filter(|dir| !dir.is_empty()) {
+            return Ok(PathBuf::from(dir).join("hosts.yml"));
+        }
+        if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            return Ok(PathBuf::from(dir).join("gh").join("hosts.yml"));
+        }
+
+        let dirs = BaseDirs::new().context("Could not determine home directory")?;
+        let config_dir = if cfg!(windows) {
+            dirs.config_dir().join("GitHub CLI")
+        } else {
+            dirs.home_dir().join(".config").join("gh")
+        };
+        Ok(config_dir.join("hosts.yml"))
+    }
+
+    /// Map hosts to services, in addition to `github.com=github`.
+    pub fn with_service_map(
+        mut self,
+        mappings: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        self.service_map.extend(
+            mappings
+                .into_iter()
+                .map(|(host, service)| (host.to_lowercase(), service)),
+        );
+        self
+    }
+
+    /// Whether to run `gh auth status` for each host to find token scopes.
+    pub fn with_scope_lookup(mut self, enabled: bool) -> Self {
+        self.lookup_scopes = enabled;
+        self
+    }
+
+    /// Scopes `gh auth status --hostname HOST` reports for the active token.
+    fn token_scopes(host: &str) -> Result<Vec<String>> {
+        let output = Command::new("gh")
+            .args(["auth", "status", "--hostname", host])
+            .output()
+            .context("Failed to run gh auth status")?;
+
+        // Older releases of gh print the status to stderr
+        let text = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        parse_token_scopes(&text).context("gh auth status did not report token scopes")
+    }
+}
+
+/// Parse the `Token scopes:` line of `gh auth status` output.
+///
+/// Newer releases quote each scope (`'repo', 'gist'`), older ones do not.
+/// A token without scopes is reported as `none`.
+pub fn parse_token_scopes(output: &str) -> Option<Vec<String>> {
+    let (_, scopes) = output
+        .lines()
+        .find_map(|line| line.split_once("Token scopes:"))?;
+    let scopes = scopes.trim();
+    if scopes.eq_ignore_ascii_case("none") {
+        return Some(Vec::new());
+    }
+
+    Some(
+        scopes
+            .split(',')
+            .map(|scope| scope.trim().trim_matches(['\'', '"']).to_string())
+            .filter(|scope| !scope.is_empty())
+            .collect(),
+    )
+}
+
+impl CredentialImporter for GithubCliImporter {
+    fn source(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn format(&self) -> &'static str {
+        "github-cli"
+    }
+
+    fn import(&self) -> Result<ImportReport> {
+        let contents = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let hosts: BTreeMap<String, HostEntry> = serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", self.path.display()))?;
+
+        let mut report = ImportReport::default();
+        for (host, entry) in hosts {
+            let Some(service) = self.service_map.get(&host.to_lowercase()) else {
+                report.warnings.push(format!(
+                    "{}: unrecognized host (map it with --service-map {}=SERVICE)",
+                    host, host
+                ));
+                continue;
+            };
+
+            let Some(user) = entry.user.filter(|user| !user.is_empty()) else {
+                report.warnings.push(format!("{}: no user, skipping", host));
+                continue;
+            };
+            let Some(token) = entry.oauth_token.filter(|token| !token.is_empty()) else {
+                report.warnings.push(format!(
+                    "{}: no oauth_token (gh may keep it in the system keyring), skipping",
+                    host
+                ));
+                continue;
+            };
+
+            let scopes = if self.lookup_scopes {
+                Self::token_scopes(&host).unwrap_or_else(|e| {
+                    report
+                        .warnings
+                        .push(format!("{}: scopes unknown: {:#}", host, e));
+                    Vec::new()
+                })
+            } else {
+                Vec::new()
+            };
+
+            report.credentials.push(ImportedCredential {
+                service: ServiceId::new(service.clone()),
+                account: AccountId::new(user),
+                credential_type: CredentialType::ApiKey,
+                value: Secret::new(token),
+                scopes,
+            });
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    fn importer() -> GithubCliImporter {
+        GithubCliImporter::new(fixture("gh-hosts.yml")).with_scope_lookup(false)
+    }
+
+    #[test]
+    fn test_import_github_com() {
+        let report = importer().import().unwrap();
+
+        assert_eq!(report.credentials.len(), 1);
+        let credential = &report.credentials[0];
+        assert_eq!(credential.service.to_string(), "github");
+        assert_eq!(credential.account.to_string(), "octocat");
+        assert_eq!(credential.credential_type, CredentialType::ApiKey);
+        assert_eq!(credential.value.expose(), "gho_example");
+
+        // Enterprise hosts are skipped unless mapped
+        assert_eq!(report.warnings.len(), 2);
+        assert!(
+            report
+                .warnings
+                .iter()
+                .all(|w| w.contains("unrecognized host"))
+        );
+    }
+
+    #[test]
+    fn test_service_map_adds_enterprise_hosts() {
+        let report = importer()
+            .with_service_map([
+                (
+                    "GitHub.example.com".to_string(),
+                    "github-enterprise".to_string(),
+                ),
+                ("ghe.example.org".to_string(), "ghe".to_string()),
+            ])
+            .import()
+            .unwrap();
+
+        let imported: Vec<(String, String, String)> = report
+            .credentials
+            .iter()
+            .map(|c| {
+                (
+                    c.service.to_string(),
+                    c.account.to_string(),
+                    c.value.expose().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            imported,
+            vec![
+                (
+                    "github".to_string(),
+                    "octocat".to_string(),
+                    "gho_example".to_string()
+                ),
+                (
+                    "github-enterprise".to_string(),
+                    "builder".to_string(),
+                    "gho_enterprise".to_string()
+                ),
+            ]
+        );
+
+        // The token of the keyring-backed login is not in the file
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("ghe.example.org: no oauth_token"));
+    }
+
+    #[test]
+    fn test_parse_token_scopes() {
+        let current = "github.com\n  \
+            ✓ Logged in to github.com account octocat (GH_CONFIG_DIR/hosts.yml)\n  \
+            - Active account: true\n  \
+            - Token: gho_************************************\n  \
+            - Token scopes: 'gist', 'read:org', 'repo', 'workflow'\n";
+        assert_eq!(
+            parse_token_scopes(current).unwrap(),
+            vec!["gist", "read:org", "repo", "workflow"]
+        );
+
+        let older = "github.com\n  ✓ Token scopes: repo, read:org\n";
+        assert_eq!(parse_token_scopes(older).unwrap(), vec!["repo", "read:org"]);
+
+        assert_eq!(
+            parse_token_scopes("  - Token scopes: none\n").unwrap(),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            parse_token_scopes("You are not logged into any GitHub hosts."),
+            None
+        );
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("hosts.yml");
+        std::fs::write(&path, "- just\n- a list\n").unwrap();
+
+        let err = GithubCliImporter::new(&path).import().unwrap_err();
+        assert!(err.to_string().contains("Failed to parse"));
+
+        let err = GithubCliImporter::new(dir.path().join("missing.yml"))
+            .import()
+            .unwrap_err();
+        assert!(err.to_string().contains("missing.yml"));
+    }
+}
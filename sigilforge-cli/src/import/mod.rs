@@ -0,0 +1,96 @@
+//! Importing credentials from other tools' configuration files.
+//!
+//! Each source implements [`CredentialImporter`], which only reads and maps
+//! credentials; [`store_credentials`] then writes them to the secret store
+//! and registers their accounts.
+
+use anyhow::Result;
+use sigilforge_core::{
+    account_store::AccountStore,
+    store::{Secret, SecretStore},
+    Account, AccountId, CredentialSource, CredentialType, ServiceId,
+};
+
+pub mod aws_cli;
+pub mod azure_cli;
+pub mod credentials_file;
+pub mod github_cli;
+pub mod netrc;
+
+/// A credential read from an import source.
+#[derive(Debug, Clone)]
+pub struct ImportedCredential {
+    /// Service the credential belongs to
+    pub service: ServiceId,
+    /// Account the credential belongs to
+    pub account: AccountId,
+    /// Which credential this is (e.g., an API key)
+    pub credential_type: CredentialType,
+    /// The credential itself
+    pub value: Secret,
+    /// Scopes the credential was granted, if the source records them
+    pub scopes: Vec<String>,
+}
+
+/// Everything an importer found.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// Credentials that mapped onto a service
+    pub credentials: Vec<ImportedCredential>,
+    /// Entries that were skipped, with the reason
+    pub warnings: Vec<String>,
+}
+
+/// A source of credentials to import.
+pub trait CredentialImporter {
+    /// Human-readable name of the source (e.g., `~/.netrc`).
+    fn source(&self) -> String;
+
+    /// Short name of the file format (e.g., `netrc`), recorded as the
+    /// [`CredentialSource::ManualImport`] format of new accounts.
+    fn format(&self) -> &'static str;
+
+    /// Read the source and map its entries onto services and accounts.
+    ///
+    /// Entries that cannot be mapped are reported in
+    /// [`ImportReport::warnings`] rather than failing the import.
+    fn import(&self) -> Result<ImportReport>;
+}
+
+/// Store imported credentials and register their accounts.
+///
+/// Accounts that already exist are kept; their credentials are overwritten.
+/// New accounts record `format` as their source. Credentials are stored under
+/// `namespace`. Returns the number of newly registered accounts.
+pub async fn store_credentials(
+    store: &dyn SecretStore,
+    accounts: &AccountStore,
+    credentials: &[ImportedCredential],
+    format: &str,
+    namespace: &str,
+) -> Result<usize> {
+    for credential in credentials {
+        let key = format!(
+            "{}/{}/{}/{}",
+            namespace,
+            credential.service,
+            credential.account,
+            credential.credential_type.as_str()
+        );
+        store.set(&key, &credential.value).await?;
+    }
+
+    let new_accounts = credentials
+        .iter()
+        .map(|c| {
+            Account::new(c.service.clone(), c.account.clone(), c.scopes.clone()).with_source(
+                CredentialSource::ManualImport {
+                    format: format.to_string(),
+                },
+            )
+        })
+        .collect();
+    let result = accounts.batch_add(new_accounts)?;
+
+    Ok(result.added)
+}
@@ -0,0 +1,233 @@
+//! Import API keys from a `~/.netrc` file.
+//!
+//! Each `machine` entry whose host belongs to a known service becomes an
+//! [`CredentialType::ApiKey`] credential: `login` is the account ID and
+//! `password` the key. Hosts can be mapped explicitly with
+//! [`NetrcImporter::with_service_map`] (`--service-map` on the CLI), which
+//! takes precedence over the built-in patterns.
+
+use anyhow::{Context, Result};
+use directories::BaseDirs;
+use sigilforge_core::{store::Secret, AccountId, CredentialType, ServiceId};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::{CredentialImporter, ImportReport, ImportedCredential};
+
+/// Host domains of known services; subdomains match too.
+const KNOWN_HOSTS: &[(&str, &str)] = &[
+    ("github.com", "github"),
+    ("spotify.com", "spotify"),
+    ("googleapis.com", "google"),
+    ("google.com", "google"),
+    ("twitter.com", "twitter"),
+    ("x.com", "twitter"),
+    ("dropboxapi.com", "dropbox"),
+    ("dropbox.com", "dropbox"),
+];
+
+/// Parse a `host=service` mapping from the command line.
+pub fn parse_service_mapping(mapping: &str) -> Result<(String, String), String> {
+    match mapping.split_once('=') {
+        Some((host, service)) if !host.trim().is_empty() && !service.trim().is_empty() => {
+            Ok((host.trim().to_lowercase(), service.trim().to_string()))
+        }
+        _ => Err(format!("expected HOST=SERVICE, got '{}'", mapping)),
+    }
+}
+
+/// Reads API keys from a netrc file.
+pub struct NetrcImporter {
+    path: PathBuf,
+    service_map: HashMap<String, String>,
+}
+
+impl NetrcImporter {
+    /// Import from the netrc file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            service_map: HashMap::new(),
+        }
+    }
+
+    /// The user's netrc file (`~/.netrc`, or `~/_netrc` on Windows).
+    pub fn default_path() -> Result<PathBuf> {
+        let dirs = BaseDirs::new().context("Could not determine home directory")?;
+        let name = if cfg!(windows) { "_netrc" } else { ".netrc" };
+        Ok(dirs.home_dir().join(name))
+    }
+
+    /// Map hosts to services explicitly (host names are matched exactly).
+    pub fn with_service_map(
+        mut self,
+        mappings: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        self.service_map.extend(
+            mappings
+                .into_iter()
+                .map(|(host, service)| (host.to_lowercase(), service)),
+        );
+        self
+    }
+
+    /// The service a machine host belongs to, if any.
+    pub fn service_for_host(&self, host: &str) -> Option<String> {
+        let host = host.to_lowercase();
+        if let Some(service) = self.service_map.get(&host) {
+            return Some(service.clone());
+        }
+
+        KNOWN_HOSTS
+            .iter()
+            .find(|(domain, _)| {
+                host == *domain
+                    || host
+                        .strip_suffix(domain)
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            })
+            .map(|(_, service)| service.to_string())
+    }
+}
+
+impl CredentialImporter for NetrcImporter {
+    fn source(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn format(&self) -> &'static str {
+        "netrc"
+    }
+
+    fn import(&self) -> Result<ImportReport> {
+        let file = std::fs::File::open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        let parsed = netrc::Netrc::parse(std::io::BufReader::new(file))
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {:?}", self.path.display(), e))?;
+
+        let mut report = ImportReport::default();
+        for (host, machine) in parsed.hosts {
+            let Some(service) = self.service_for_host(&host) else {
+                report.warnings.push(format!(
+                    "{}: unrecognized host (map it with --service-map {}=SERVICE)",
+                    host, host
+                ));
+                continue;
+            };
+
+            if machine.login.is_empty() {
+                report.warnings.push(format!("{}: no login, skipping", host));
+                continue;
+            }
+            let Some(password) = machine.password else {
+                report.warnings.push(format!("{}: no password, skipping", host));
+                continue;
+            };
+
+            report.credentials.push(ImportedCredential {
+                service: ServiceId::new(service),
+                account: AccountId::new(machine.login),
+                credential_type: CredentialType::ApiKey,
+                value: Secret::new(password),
+                scopes: Vec::new(),
+            });
+        }
+
+        if parsed.default.is_some() {
+            report
+                .warnings
+                .push("default: entries without a machine are not imported".to_string());
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn test_service_for_host() {
+        let importer = NetrcImporter::new("unused");
+
+        assert_eq!(importer.service_for_host("api.github.com").as_deref(), Some("github"));
+        assert_eq!(importer.service_for_host("github.com").as_deref(), Some("github"));
+        assert_eq!(importer.service_for_host("API.Spotify.com").as_deref(), Some("spotify"));
+        assert_eq!(importer.service_for_host("notgithub.com"), None);
+        assert_eq!(importer.service_for_host("example.com"), None);
+    }
+
+    #[test]
+    fn test_import_known_hosts() {
+        let report = NetrcImporter::new(fixture("netrc")).import().unwrap();
+
+        let imported: Vec<(String, String, String)> = report
+            .credentials
+            .iter()
+            .map(|c| {
+                (
+                    c.service.to_string(),
+                    c.account.to_string(),
+                    c.value.expose().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            imported,
+            vec![
+                ("github".to_string(), "octocat".to_string(), "ghp_example".to_string()),
+                ("spotify".to_string(), "listener".to_string(), "spotify-key".to_string()),
+            ]
+        );
+        assert!(report
+            .credentials
+            .iter()
+            .all(|c| c.credential_type == CredentialType::ApiKey));
+
+        // The unknown host and the entry without a password are warnings
+        assert_eq!(report.warnings.len(), 2);
+        assert!(report.warnings.iter().any(|w| w.starts_with("git.example.com")));
+        assert!(report.warnings.iter().any(|w| w.contains("no password")));
+    }
+
+    #[test]
+    fn test_service_map_overrides() {
+        let importer = NetrcImporter::new(fixture("netrc")).with_service_map([
+            ("git.example.com".to_string(), "gitea".to_string()),
+            ("api.github.com".to_string(), "github-enterprise".to_string()),
+        ]);
+        let report = importer.import().unwrap();
+
+        let services: Vec<String> = report
+            .credentials
+            .iter()
+            .map(|c| c.service.to_string())
+            .collect();
+        assert_eq!(services, vec!["github-enterprise", "spotify", "gitea"]);
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_service_mapping() {
+        assert_eq!(
+            parse_service_mapping("machine.example.com=myservice").unwrap(),
+            ("machine.example.com".to_string(), "myservice".to_string())
+        );
+        assert!(parse_service_mapping("no-equals").is_err());
+        assert!(parse_service_mapping("=service").is_err());
+        assert!(parse_service_mapping("host=").is_err());
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let err = NetrcImporter::new(fixture("missing-netrc")).import().unwrap_err();
+        assert!(err.to_string().contains("missing-netrc"));
+    }
+}
@@ -13,21 +13,111 @@
 //!
 //! # Get a fresh access token
 //! sigilforge get-token spotify personal
+//!
+//! # Print it as an Authorization header (bearer, curl, httpie, env)
+//! sigilforge get-token spotify personal --format=bearer
+//!
+//! # Keep printing the token as it is refreshed (for CI jobs)
+//! sigilforge get-token github ci --watch --watch-interval=60 --format=json
+//!
+//! # Show who a token was issued to, without printing it
+//! sigilforge inspect github work
+//!
+//! # Request a long list of scopes kept in a file, one or more per line
+//! sigilforge add-account google work --scopes-from-file=google-scopes.txt
+//!
+//! # Use OAuth client credentials mounted as Docker secrets
+//! sigilforge add-account google work \
+//!     --client-id-file=/run/secrets/google_client_id \
+//!     --client-secret-file=/run/secrets/google_client_secret
+//!
+//! # Connect an Okta org or any other OpenID Connect provider
+//! sigilforge add-account okta work --okta-domain=yourorg.okta.com
+//! sigilforge add-account corp-sso me --oidc-issuer=https://sso.example.com
+//!
+//! # Connect a Salesforce sandbox org
+//! sigilforge add-account salesforce staging --salesforce-sandbox
+//!
+//! # Connect a Box enterprise app account
+//! sigilforge add-account box work --box-enterprise-id=123456
+//!
+//! # Use a free callback port (for concurrent flows)
+//! sigilforge add-account github work --callback-port=0
+//!
+//! # Authorize on a machine without a browser
+//! sigilforge add-account github work --no-browser
+//!
+//! # Without a terminal, the URL is printed and the code is passed back later
+//! sigilforge add-account github work --no-browser < /dev/null
+//! sigilforge add-account github work --auth-code=CODE
+//!
+//! # Authorize with a code entered on another device
+//! sigilforge add-account github work --device-code
+//!
+//! # Register a GitHub App installation for an organization
+//! sigilforge add-account github-app my-org --app-id=123 \
+//!     --private-key-file=app.pem --installation-id=456
+//!
+//! # Provision an account from existing tokens instead of authorizing
+//! sigilforge add-account --from-file=github-work.json
+//!
+//! # Show which socket, store, and keyring are in use
+//! sigilforge whoami
+//!
+//! # Keep accounts (and look for the daemon socket) in another directory
+//! sigilforge --config-dir=/srv/sigilforge list-accounts
+//!
+//! # Review recent daemon requests for a service
+//! sigilforge audit --service=github --since=2025-01-31 --tail=20
+//!
+//! # Back up and restore account metadata
+//! sigilforge export --format=json --output accounts-backup.json
+//! sigilforge import --format=sigilforge accounts-backup.json
+//!
+//! # Import API keys from ~/.netrc
+//! sigilforge import netrc --service-map git.example.com=gitea
+//!
+//! # Import the GitHub CLI's token
+//! sigilforge import github-cli
+//!
+//! # Import one profile from ~/.aws/credentials
+//! sigilforge import aws --profile work
+//!
+//! # Install shell completions
+//! sigilforge completion zsh --install
 //! ```
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use sigilforge_core::{
-    account_store::AccountStore,
-    oauth::pkce::PkceFlow,
-    provider::ProviderRegistry,
+    account_store::{AccountStore, AccountStoreError, ImportMode, CONFIG_DIR_ENV},
+    oauth::device_code::DeviceCodeFlow,
+    oauth::github_app::{self, GitHubAppFlow},
+    oauth::pkce::{open_browser, PkceFlow, RedirectConfig},
+    provider::{okta_issuer, user_provider_dir, ProviderConfig, ProviderRegistry},
     store::{KeyringStore, MemoryStore, SecretStore},
-    AccountId, CredentialType, ServiceId,
+    token_manager::DEFAULT_KEY_PREFIX,
+    AccountId, CredentialRef, CredentialSource, CredentialType, ScopeSet, ServiceId,
 };
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tracing::{info, warn};
 use tracing_subscriber::{fmt, EnvFilter};
 
+mod audit;
 mod client;
+mod completion;
+mod import;
+mod output;
+mod pending;
+mod wait;
+mod watch;
+
+use import::CredentialImporter;
+
+/// Name of the default `--identity-file`, next to accounts.json
+const IDENTITY_FILE_NAME: &str = "identity.txt";
 
 #[derive(Parser)]
 #[command(name = "sigilforge")]
@@ -38,23 +128,253 @@ struct Cli {
     #[arg(short, long, global = true)]
     verbose: bool,
 
+    /// Directory for accounts.json, daemon.toml, providers, pending
+    /// authorizations and the daemon socket (daemon.sock), instead of the
+    /// platform default; overrides SIGILFORGE_CONFIG_DIR
+    #[arg(long, global = true, value_name = "PATH")]
+    config_dir: Option<PathBuf>,
+
+    /// Keep accounts.json encrypted with an age identity
+    #[arg(long, global = true)]
+    encrypted: bool,
+
+    /// age identity for --encrypted; defaults to identity.txt next to
+    /// accounts.json, which is generated if it doesn't exist
+    #[arg(long, global = true, value_name = "PATH", requires = "encrypted")]
+    identity_file: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+impl Cli {
+    /// `--config-dir`, or else `$SIGILFORGE_CONFIG_DIR`.
+    fn config_dir(&self) -> Option<PathBuf> {
+        self.config_dir.clone().or_else(|| {
+            std::env::var_os(CONFIG_DIR_ENV)
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+        })
+    }
+}
+
+/// The global flags that decide where the account store is and how it is
+/// opened, passed down to every command that loads it.
+#[derive(Clone, Copy)]
+struct GlobalOptions<'a> {
+    /// `--config-dir`, or else `$SIGILFORGE_CONFIG_DIR`
+    config_dir: Option<&'a Path>,
+    /// Set when `--encrypted` is, to the `--identity-file` given, if any
+    encryption: Option<Option<&'a Path>>,
+}
+
+/// Options for `add-account github-app {org}`
+#[derive(Args)]
+struct GitHubAppArgs {
+    /// GitHub App ID
+    #[arg(long, value_name = "ID", required_if_eq("service", "github-app"))]
+    app_id: Option<String>,
+
+    /// Path to the GitHub App private key (PEM)
+    #[arg(long, value_name = "FILE", required_if_eq("service", "github-app"))]
+    private_key_file: Option<std::path::PathBuf>,
+
+    /// Installation ID of the app in the organization
+    #[arg(long, value_name = "ID", required_if_eq("service", "github-app"))]
+    installation_id: Option<String>,
+
+    /// API base URL for GitHub Enterprise Server
+    #[arg(long, value_name = "URL")]
+    github_api_url: Option<String>,
+}
+
+/// OAuth client credentials for `add-account`, overriding the environment
+#[derive(Args)]
+struct ClientCredentialArgs {
+    /// OAuth client ID; overrides {SERVICE}_CLIENT_ID and OAUTH_CLIENT_ID
+    #[arg(long, value_name = "ID")]
+    client_id: Option<String>,
+
+    /// Read the OAuth client ID from a file (e.g., a Docker secret)
+    #[arg(long, value_name = "PATH", conflicts_with = "client_id")]
+    client_id_file: Option<std::path::PathBuf>,
+
+    /// OAuth client secret; overrides {SERVICE}_CLIENT_SECRET and
+    /// OAUTH_CLIENT_SECRET
+    #[arg(long, value_name = "SECRET")]
+    client_secret: Option<String>,
+
+    /// Read the OAuth client secret from a file (e.g., a Docker secret)
+    #[arg(long, value_name = "PATH", conflicts_with = "client_secret")]
+    client_secret_file: Option<std::path::PathBuf>,
+}
+
+impl ClientCredentialArgs {
+    /// Whether any client credential was given on the command line.
+    fn is_set(&self) -> bool {
+        self.client_id.is_some()
+            || self.client_id_file.is_some()
+            || self.client_secret.is_some()
+            || self.client_secret_file.is_some()
+    }
+}
+
+/// Settings shared by the `add-account` flows.
+#[derive(Clone, Copy)]
+struct AddAccountOptions<'a> {
+    scopes: Option<&'a str>,
+    callback_port: Option<u16>,
+    no_browser: bool,
+    client: &'a ClientCredentialArgs,
+    global: GlobalOptions<'a>,
+    revoke_existing: bool,
+    /// Box enterprise ID, stored once the code has been exchanged
+    box_enterprise_id: Option<&'a str>,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Add a new account for a service
     AddAccount {
-        /// Service name (e.g., spotify, gmail, github)
-        service: String,
+        /// Service name (e.g., spotify, gmail, github); omitted with --from-file
+        #[arg(required_unless_present = "from_file")]
+        service: Option<String>,
 
         /// Account identifier (e.g., personal, work)
-        account: String,
+        #[arg(required_unless_present = "from_file")]
+        account: Option<String>,
+
+        /// Store the account and tokens described by a JSON file instead of
+        /// authorizing
+        ///
+        /// The file holds "service", "account" and "access_token", and
+        /// optionally "scopes", "refresh_token", "expires_at" (RFC 3339),
+        /// "client_id" and "client_secret".
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with_all = [
+                "service",
+                "account",
+                "scopes",
+                "scopes_from_file",
+                "oidc_issuer",
+                "okta_domain",
+                "callback_port",
+                "salesforce_sandbox",
+                "box_enterprise_id",
+                "no_browser",
+                "auth_code",
+                "device_code",
+                "revoke_existing",
+            ]
+        )]
+        from_file: Option<std::path::PathBuf>,
 
         /// OAuth scopes to request (comma-separated)
         #[arg(short, long)]
         scopes: Option<String>,
+
+        /// Read scopes to request from a file, in addition to --scopes
+        ///
+        /// Scopes are separated by whitespace or newlines; lines starting
+        /// with '#' are comments.
+        #[arg(long, value_name = "PATH")]
+        scopes_from_file: Option<std::path::PathBuf>,
+
+        /// Discover the provider from an OpenID Connect issuer URL
+        ///
+        /// The discovered provider is saved to the user provider directory.
+        #[arg(long, value_name = "URL")]
+        oidc_issuer: Option<String>,
+
+        /// Discover the provider from an Okta org (e.g., yourorg.okta.com)
+        #[arg(long, value_name = "DOMAIN", conflicts_with = "oidc_issuer")]
+        okta_domain: Option<String>,
+
+        /// Local port for the OAuth callback (0 picks a free port)
+        ///
+        /// Defaults to OAUTH_CALLBACK_PORT, or 8484 if unset.
+        #[arg(long, value_name = "PORT")]
+        callback_port: Option<u16>,
+
+        /// Authorize against test.salesforce.com instead of login.salesforce.com
+        #[arg(long, conflicts_with_all = ["oidc_issuer", "okta_domain"])]
+        salesforce_sandbox: bool,
+
+        /// Enterprise ID of the Box enterprise app the account belongs to
+        #[arg(
+            long,
+            value_name = "ID",
+            conflicts_with_all = ["oidc_issuer", "okta_domain", "salesforce_sandbox"]
+        )]
+        box_enterprise_id: Option<String>,
+
+        /// Print the authorization URL instead of opening a browser
+        ///
+        /// Waits for Enter once authorization is done. Without a terminal on
+        /// stdin, prints only the URL and exits; finish with --auth-code.
+        #[arg(long)]
+        no_browser: bool,
+
+        /// Finish a --no-browser authorization with the code from the redirect
+        #[arg(
+            long,
+            value_name = "CODE",
+            conflicts_with_all = [
+                "scopes",
+                "scopes_from_file",
+                "oidc_issuer",
+                "okta_domain",
+                "callback_port",
+                "salesforce_sandbox",
+                "box_enterprise_id",
+                "no_browser",
+                "revoke_existing",
+            ]
+        )]
+        auth_code: Option<String>,
+
+        /// Use the device code flow even if the provider supports PKCE
+        ///
+        /// Prints a code to enter at the provider's verification page, from
+        /// any device, instead of listening for a browser redirect.
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "oidc_issuer",
+                "okta_domain",
+                "callback_port",
+                "salesforce_sandbox",
+                "box_enterprise_id",
+                "no_browser",
+                "auth_code",
+            ]
+        )]
+        device_code: bool,
+
+        /// Seconds between token polls, instead of the provider's interval
+        #[arg(
+            long,
+            value_name = "SECS",
+            requires = "device_code",
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        poll_interval: Option<u64>,
+
+        /// Replace the account if it already exists
+        ///
+        /// The existing refresh token is revoked at the provider, if it has
+        /// a revocation endpoint, and the account and its stored credentials
+        /// are removed before the new authorization starts.
+        #[arg(long)]
+        revoke_existing: bool,
+
+        #[command(flatten)]
+        client: ClientCredentialArgs,
+
+        #[command(flatten)]
+        github_app: GitHubAppArgs,
     },
 
     /// List all configured accounts
@@ -62,6 +382,13 @@ enum Commands {
         /// Filter by service name
         #[arg(short, long)]
         service: Option<String>,
+
+        /// Output format
+        ///
+        /// JSON is an array of accounts; with --verbose each also carries
+        /// its token_status (valid, expired, missing, unknown).
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ListFormat,
     },
 
     /// Get a fresh access token for an account
@@ -72,22 +399,86 @@ enum Commands {
         /// Account identifier
         account: String,
 
-        /// Output format (text, json)
+        /// Output format (text, json, bearer, curl, httpie, env)
+        ///
+        /// bearer, curl, httpie and env print the token as an Authorization
+        /// header for those tools, e.g.
+        /// `curl -H "Authorization: $(sigilforge get-token spotify personal
+        /// --format=bearer)" URL`. Also applies to --watch.
         #[arg(short, long, default_value = "text")]
         format: String,
+
+        /// Keep running and print the token again whenever it is refreshed
+        #[arg(long)]
+        watch: bool,
+
+        /// Seconds between token checks in watch mode
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = 300,
+            value_parser = clap::value_parser!(u64).range(1..),
+            requires = "watch"
+        )]
+        watch_interval: u64,
+
+        /// Fetch a new token from the provider even if the cached one is
+        /// still valid, e.g. after it was revoked
+        #[arg(long, conflicts_with = "watch")]
+        refresh: bool,
+
+        /// If the daemon is not running, wait for it to start instead of
+        /// reading the keyring directly; exits with code 2 on timeout
+        #[arg(long)]
+        wait_for_daemon: bool,
+
+        /// Seconds to wait for the daemon with --wait-for-daemon
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = 30,
+            requires = "wait_for_daemon"
+        )]
+        wait_timeout: u64,
     },
 
-    /// Remove an account and its credentials
-    RemoveAccount {
+    /// Show what is known about an account's token, without printing it
+    ///
+    /// Claims of JWT access tokens are shown without verifying the
+    /// signature.
+    Inspect {
         /// Service name
         service: String,
 
         /// Account identifier
         account: String,
 
-        /// Skip confirmation prompt
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Remove an account and its credentials
+    RemoveAccount {
+        /// Service name; optional with --all-services
+        #[arg(required_unless_present = "all_services")]
+        service: Option<String>,
+
+        /// Account identifier
+        #[arg(
+            required_unless_present = "all_services",
+            conflicts_with = "all_services"
+        )]
+        account: Option<String>,
+
+        /// Skip confirmation prompt; removing every account still asks
         #[arg(short, long)]
         force: bool,
+
+        /// Remove every account of SERVICE, or of every service if SERVICE
+        /// is omitted
+        #[arg(long)]
+        all_services: bool,
     },
 
     /// Resolve a credential reference
@@ -98,6 +489,265 @@ enum Commands {
 
     /// Start the daemon in foreground (for debugging)
     Daemon,
+
+    /// Check whether the daemon is running and healthy
+    ///
+    /// Exits 0 when healthy, 1 when degraded, 2 when unreachable.
+    DaemonStatus {
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Also validate the providers the daemon has loaded; invalid
+        /// providers, or provider files it skipped, exit 1
+        #[arg(long)]
+        validate_providers: bool,
+    },
+
+    /// Show the socket, daemon, account, keyring, and provider state
+    #[command(name = "whoami")]
+    WhoAmI {
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Show requests recorded in the daemon's audit log
+    ///
+    /// Reads the file set by `audit_log_path` in the daemon config; exits 1
+    /// if none is configured.
+    Audit {
+        /// Show only the last N matching entries
+        #[arg(long, value_name = "N")]
+        tail: Option<usize>,
+
+        /// Show only entries at or after this time (e.g., 2025-01-31T12:00:00Z)
+        #[arg(long, value_name = "DATETIME", value_parser = audit::parse_since)]
+        since: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// Filter by service name
+        #[arg(long)]
+        service: Option<String>,
+
+        /// Filter by account identifier
+        #[arg(long)]
+        account: Option<String>,
+
+        /// Filter by RPC method (e.g., get_token)
+        #[arg(long, value_name = "RPC_METHOD")]
+        method: Option<String>,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Upgrade the account store to the current schema version
+    Migrate {
+        /// Show pending migrations without applying them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Also encrypt accounts.json in place with the --identity-file
+        /// identity (identity.txt next to it by default, generated if it
+        /// doesn't exist); later commands then need --encrypted
+        #[arg(long, conflicts_with = "dry_run")]
+        encrypt: bool,
+    },
+
+    /// Export account metadata for backup
+    ///
+    /// Only account metadata is exported; tokens stay in the keyring.
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value = "json")]
+        format: ExportFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Import credentials from another tool, or restore an account export
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Import {
+        #[command(subcommand)]
+        source: Option<ImportSource>,
+
+        #[command(flatten)]
+        backup: RestoreArgs,
+    },
+
+    /// Save, list, and restore point-in-time copies of the account store
+    ///
+    /// Snapshots hold account metadata only; tokens stay in the keyring.
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommand,
+    },
+
+    /// Generate or install shell completions
+    Completion {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+
+        /// Install the script into the shell's per-user completion directory
+        #[arg(long)]
+        install: bool,
+
+        /// Print the script to stdout even when installing
+        #[arg(long)]
+        stdout: bool,
+    },
+}
+
+/// Subcommands of `sigilforge snapshot`
+#[derive(Subcommand)]
+enum SnapshotCommand {
+    /// Save a snapshot of the current accounts
+    Create {
+        /// Name to add to the snapshot ID (letters, digits, '-' and '_')
+        #[arg(long)]
+        label: Option<String>,
+    },
+
+    /// List saved snapshots, oldest first
+    List,
+
+    /// Replace all accounts with those in a snapshot
+    Restore {
+        /// Snapshot ID, as shown by `sigilforge snapshot list`
+        id: String,
+    },
+}
+
+/// Formats `sigilforge list-accounts` can print
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ListFormat {
+    /// Human-readable summary
+    Text,
+    /// An array of accounts
+    Json,
+}
+
+/// Formats `sigilforge export` can write
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ExportFormat {
+    /// The account store's own JSON format
+    Json,
+}
+
+/// Formats `sigilforge import --format` can restore
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum RestoreFormat {
+    /// JSON written by `sigilforge export --format=json`
+    Sigilforge,
+}
+
+/// Options for `sigilforge import --format=sigilforge FILE`
+#[derive(Args)]
+struct RestoreArgs {
+    /// Format of FILE
+    #[arg(long, value_enum, required = true)]
+    format: Option<RestoreFormat>,
+
+    /// Export file to restore
+    #[arg(value_name = "FILE", required = true)]
+    file: Option<std::path::PathBuf>,
+
+    /// Remove existing accounts instead of merging
+    #[arg(long)]
+    replace: bool,
+}
+
+/// Sources `sigilforge import` can read from
+#[derive(Subcommand)]
+enum ImportSource {
+    /// Import API keys from a netrc file
+    ///
+    /// Machines on known service hosts (e.g., api.github.com) are imported
+    /// with `login` as the account and `password` as the API key.
+    Netrc {
+        /// netrc file to read (default: ~/.netrc)
+        #[arg(long, value_name = "FILE")]
+        file: Option<std::path::PathBuf>,
+
+        /// Map a machine host to a service (repeatable)
+        #[arg(
+            long = "service-map",
+            value_name = "HOST=SERVICE",
+            value_parser = import::netrc::parse_service_mapping
+        )]
+        service_map: Vec<(String, String)>,
+
+        /// Show what would be imported without storing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Import tokens from the GitHub CLI's hosts.yml
+    ///
+    /// The github.com login becomes a github/USER account with the token as
+    /// its API key. Scopes are taken from `gh auth status` if gh is installed.
+    #[command(name = "github-cli")]
+    GithubCli {
+        /// hosts.yml to read (default: $GH_CONFIG_DIR/hosts.yml or
+        /// ~/.config/gh/hosts.yml)
+        #[arg(long, value_name = "FILE")]
+        file: Option<std::path::PathBuf>,
+
+        /// Map another host (e.g., GitHub Enterprise Server) to a service
+        /// (repeatable)
+        #[arg(
+            long = "service-map",
+            value_name = "HOST=SERVICE",
+            value_parser = import::netrc::parse_service_mapping
+        )]
+        service_map: Vec<(String, String)>,
+
+        /// Show what would be imported without storing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Import access keys from the AWS CLI's credentials file
+    ///
+    /// Each profile becomes an aws/PROFILE account with the access key ID as
+    /// its API key, the secret access key as its client secret, and any
+    /// session token as its access token.
+    Aws {
+        /// Credentials file to read (default: $AWS_SHARED_CREDENTIALS_FILE
+        /// or ~/.aws/credentials)
+        #[arg(long, value_name = "FILE")]
+        file: Option<std::path::PathBuf>,
+
+        /// Only import this profile
+        #[arg(long, value_name = "PROFILE")]
+        profile: Option<String>,
+
+        /// Show what would be imported without storing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Import access tokens from the Azure CLI
+    ///
+    /// Runs `az account list` and `az account get-access-token`; each
+    /// enabled subscription becomes an azure/NAME account, NAME being the
+    /// subscription name in lowercase with spaces and punctuation replaced
+    /// by `-` (or the subscription ID if that clashes or leaves nothing),
+    /// with the token as its access token and the tenant ID as its tenant_id.
+    #[command(name = "azure-cli")]
+    AzureCli {
+        /// Only import the subscription with this name or ID
+        #[arg(long, value_name = "NAME")]
+        subscription: Option<String>,
+
+        /// Show what would be imported without storing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[tokio::main]
@@ -106,25 +756,133 @@ async fn main() -> Result<()> {
 
     init_logging(cli.verbose);
 
+    let config_dir = cli.config_dir();
+    let global = GlobalOptions {
+        config_dir: config_dir.as_deref(),
+        encryption: cli.encrypted.then_some(cli.identity_file.as_deref()),
+    };
+    if let Commands::GetToken { wait_for_daemon: true, wait_timeout, .. } = cli.command {
+        wait_for_daemon(std::time::Duration::from_secs(wait_timeout), global.config_dir).await;
+    }
     match cli.command {
-        Commands::AddAccount { service, account, scopes } => {
-            add_account(&service, &account, scopes.as_deref()).await
+        Commands::AddAccount { from_file: Some(path), .. } => {
+            add_account_from_file(&path, global).await
+        }
+        Commands::AddAccount {
+            service: Some(service),
+            account: Some(account),
+            github_app,
+            ..
+        } if service == github_app::GITHUB_APP_SERVICE => {
+            add_github_app_account(&account, github_app, global).await
+        }
+        Commands::AddAccount {
+            service: Some(service),
+            account: Some(account),
+            scopes,
+            scopes_from_file,
+            oidc_issuer,
+            okta_domain,
+            callback_port,
+            salesforce_sandbox,
+            box_enterprise_id,
+            no_browser,
+            auth_code,
+            device_code,
+            poll_interval,
+            revoke_existing,
+            client,
+            ..
+        } => {
+            let scopes = match scopes_from_file {
+                Some(path) => merge_scopes_file(scopes.as_deref(), &path)?,
+                None => scopes,
+            };
+            let issuer = okta_domain.as_deref().map(okta_issuer).or(oidc_issuer);
+            let options = AddAccountOptions {
+                scopes: scopes.as_deref(),
+                callback_port,
+                no_browser,
+                client: &client,
+                global,
+                revoke_existing,
+                box_enterprise_id: box_enterprise_id.as_deref(),
+            };
+            if let Some(code) = auth_code {
+                complete_detached_account(&service, &account, &code, &client, global).await
+            } else if device_code {
+                add_device_code_account(&service, &account, poll_interval, options).await
+            } else if salesforce_sandbox {
+                add_salesforce_sandbox_account(&service, &account, options).await
+            } else if box_enterprise_id.is_some() {
+                add_box_enterprise_account(&service, &account, options).await
+            } else {
+                add_account(&service, &account, issuer.as_deref(), options).await
+            }
+        }
+        Commands::AddAccount { .. } => {
+            anyhow::bail!("add-account needs a service and an account, or --from-file")
+        }
+        Commands::ListAccounts { service, format } => {
+            list_accounts(service.as_deref(), format, cli.verbose, global).await
+        }
+        Commands::GetToken { service, account, format, watch: true, watch_interval, .. } => {
+            watch_token(&service, &account, &format, watch_interval, global).await
         }
-        Commands::ListAccounts { service } => {
-            list_accounts(service.as_deref()).await
+        Commands::GetToken { service, account, format, refresh: true, .. } => {
+            refresh_token(&service, &account, &format, global).await
         }
-        Commands::GetToken { service, account, format } => {
-            get_token(&service, &account, &format).await
+        Commands::GetToken { service, account, format, .. } => {
+            get_token(&service, &account, &format, global).await
         }
-        Commands::RemoveAccount { service, account, force } => {
-            remove_account(&service, &account, force).await
+        Commands::Inspect { service, account, format } => {
+            inspect_token(&service, &account, &format, global.config_dir).await
+        }
+        Commands::RemoveAccount { service, account, force, all_services } => {
+            match (service, account) {
+                (service, _) if all_services => {
+                    remove_accounts(service.as_deref(), force, global).await
+                }
+                (Some(service), Some(account)) => {
+                    remove_account(&service, &account, force, global).await
+                }
+                _ => anyhow::bail!("remove-account needs a service and an account"),
+            }
         }
         Commands::Resolve { reference } => {
-            resolve_reference(&reference).await
+            resolve_reference(&reference, global.config_dir).await
         }
         Commands::Daemon => {
             run_daemon_foreground().await
         }
+        Commands::DaemonStatus { format, validate_providers } => {
+            daemon_status(&format, validate_providers, global.config_dir).await
+        }
+        Commands::WhoAmI { format } => {
+            whoami(&format, global).await
+        }
+        Commands::Audit { tail, since, service, account, method, format } => {
+            let filter = audit::AuditFilter { since, service, account, method, tail };
+            show_audit_log(&filter, &format, global.config_dir)
+        }
+        Commands::Migrate { dry_run, encrypt } => {
+            migrate(dry_run, encrypt, global)
+        }
+        Commands::Export { format, output } => {
+            export_accounts(format, output.as_deref(), global)
+        }
+        Commands::Import { source: Some(source), .. } => {
+            import_credentials(source, global).await
+        }
+        Commands::Import { source: None, backup } => {
+            restore_accounts(backup, global)
+        }
+        Commands::Snapshot { command } => {
+            snapshot(command, global)
+        }
+        Commands::Completion { shell, install, stdout } => {
+            generate_completion(shell, install, stdout)
+        }
     }
 }
 
@@ -139,43 +897,251 @@ fn init_logging(verbose: bool) {
         .init();
 }
 
-async fn add_account(service: &str, account: &str, scopes: Option<&str>) -> Result<()> {
-    let mut client = client::DaemonClient::connect_default().await?;
+/// Load the account store in `config_dir`, or in the default location.
+///
+/// With `--encrypted`, the store is decrypted with the identity in
+/// `--identity-file`, generating one first if the file doesn't exist.
+fn load_account_store(global: GlobalOptions<'_>) -> Result<AccountStore, AccountStoreError> {
+    if let Some(identity_file) = global.encryption {
+        let path = account_store_path(global.config_dir)?;
+        return open_encrypted_store(&path, identity_file, false);
+    }
+
+    match global.config_dir {
+        Some(dir) => AccountStore::load_from_config_dir(dir),
+        None => AccountStore::load(),
+    }
+}
+
+/// accounts.json in `config_dir`, or in the default location.
+fn account_store_path(config_dir: Option<&Path>) -> Result<PathBuf, AccountStoreError> {
+    match config_dir {
+        Some(dir) => Ok(dir.join("accounts.json")),
+        None => AccountStore::default_path(),
+    }
+}
+
+/// Open the store at `path` encrypted to the identity in `identity_file`, or
+/// in identity.txt next to it, generating the identity first if the file
+/// doesn't exist. With `encrypt_plaintext`, a plaintext store is encrypted in
+/// place first.
+fn open_encrypted_store(
+    path: &Path,
+    identity_file: Option<&Path>,
+    encrypt_plaintext: bool,
+) -> Result<AccountStore, AccountStoreError> {
+    let identity_path = identity_file
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| path.with_file_name(IDENTITY_FILE_NAME));
+    let identity = if identity_path.exists() {
+        AccountStore::load_identity(&identity_path)?
+    } else {
+        info!(
+            "Generating account store identity at {}",
+            identity_path.display()
+        );
+        AccountStore::generate_identity(&identity_path)?
+    };
+
+    if encrypt_plaintext {
+        AccountStore::encrypt_in_place(path, identity)
+    } else {
+        AccountStore::open_encrypted(path, identity)
+    }
+}
+
+/// Add the scopes listed in the file at `path` to the comma-separated
+/// `scopes`, keeping the first occurrence of each.
+///
+/// Returns `None` if neither lists any scopes, so the provider's defaults
+/// are used.
+fn merge_scopes_file(scopes: Option<&str>, path: &std::path::Path) -> Result<Option<String>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read scopes from {}: {}", path.display(), e))?;
+    let from_file = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .flat_map(str::split_whitespace);
+
+    let mut merged: Vec<&str> = Vec::new();
+    for scope in scopes
+        .into_iter()
+        .flat_map(|scopes| scopes.split(','))
+        .map(str::trim)
+        .chain(from_file)
+    {
+        if !scope.is_empty() && !merged.contains(&scope) {
+            merged.push(scope);
+        }
+    }
+    Ok((!merged.is_empty()).then(|| merged.join(",")))
+}
+
+async fn add_account(
+    service: &str,
+    account: &str,
+    oidc_issuer: Option<&str>,
+    options: AddAccountOptions<'_>,
+) -> Result<()> {
+    // Discovered providers are not known to the daemon; run the flow locally
+    if let Some(issuer) = oidc_issuer {
+        let mut registry = ProviderRegistry::new();
+        registry
+            .register_oidc(service, issuer)
+            .await
+            .map_err(|e| anyhow::anyhow!("OIDC discovery for {} failed: {}", issuer, e))?;
+        let provider = registry
+            .remove(service)
+            .ok_or_else(|| anyhow::anyhow!("Provider '{}' was not registered", service))?;
+
+        let config_dir = options.global.config_dir;
+        fallback_add_account(service, account, Some(provider.clone()), options).await?;
+        return save_user_provider(&provider, config_dir);
+    }
+
+    // The daemon cannot show the URL anywhere but its own browser, and
+    // uses its own client credentials
+    if options.no_browser || options.client.is_set() {
+        return fallback_add_account(service, account, None, options).await;
+    }
+
+    let mut client = client::DaemonClient::connect_default(options.global.config_dir).await?;
 
     if client.is_connected() {
-        let scope_vec = scopes
+        let scope_vec = options
+            .scopes
             .map(|s| s.split(',').map(|s| s.trim().to_string()).collect())
             .unwrap_or_default();
 
-        match client.add_account(service, account, scope_vec).await {
+        match client
+            .add_account(service, account, scope_vec, options.revoke_existing)
+            .await
+        {
             Ok(response) => {
                 println!("{}", response.message);
                 Ok(())
             }
             Err(e) => {
                 warn!("Daemon call failed: {}", e);
-                fallback_add_account(service, account, scopes).await
+                fallback_add_account(service, account, None, options).await
             }
         }
     } else {
         warn!("Daemon not available, using fallback mode");
-        fallback_add_account(service, account, scopes).await
+        fallback_add_account(service, account, None, options).await
     }
 }
 
-async fn fallback_add_account(service: &str, account: &str, scopes: Option<&str>) -> Result<()> {
-    use sigilforge_core::{Account, AccountId, AccountStore, ServiceId};
+/// Save a discovered provider so later commands (and the daemon) know it.
+fn save_user_provider(provider: &ProviderConfig, config_dir: Option<&Path>) -> Result<()> {
+    let dir = user_provider_dir(config_dir)
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the user provider directory"))?;
+    let path = provider.save_to_dir(&dir)?;
+    println!("Saved provider '{}' to {}", provider.id, path.display());
+    Ok(())
+}
 
-    // Get provider configuration
-    let registry = ProviderRegistry::with_defaults();
-    let provider = registry.get(service).ok_or_else(|| {
+/// Add a Salesforce account against the sandbox login server.
+///
+/// The daemon only knows the production login URL, so the flow runs locally.
+async fn add_salesforce_sandbox_account(
+    service: &str,
+    account: &str,
+    options: AddAccountOptions<'_>,
+) -> Result<()> {
+    if service != "salesforce" {
+        anyhow::bail!("--salesforce-sandbox only applies to the salesforce service");
+    }
+
+    let provider = ProviderRegistry::with_defaults()
+        .get(service)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Salesforce provider is not registered"))?
+        .with_instance_url(sigilforge_core::provider::SALESFORCE_SANDBOX_URL);
+
+    fallback_add_account(service, account, Some(provider), options).await
+}
+
+/// Add a Box account belonging to an enterprise app.
+///
+/// The daemon has no way to record the enterprise ID, so the flow runs
+/// locally and the ID is stored alongside the account's tokens once they
+/// have been obtained.
+async fn add_box_enterprise_account(
+    service: &str,
+    account: &str,
+    options: AddAccountOptions<'_>,
+) -> Result<()> {
+    if service != "box" {
+        anyhow::bail!("--box-enterprise-id only applies to the box service");
+    }
+
+    // Fail before the browser flow rather than after it
+    KeyringStore::try_new("sigilforge").map_err(|e| {
         anyhow::anyhow!(
-            "Unknown provider '{}'. Available: {:?}",
-            service,
-            registry.list_ids()
+            "Keyring unavailable ({}); cannot store the enterprise ID",
+            e
         )
     })?;
 
+    fallback_add_account(service, account, None, options).await
+}
+
+/// Store the enterprise ID of a Box account whose tokens were just stored.
+async fn store_box_enterprise_id(
+    service: &str,
+    account: &str,
+    enterprise_id: &str,
+    config_dir: Option<&Path>,
+) -> Result<()> {
+    let store = KeyringStore::try_new("sigilforge")
+        .map_err(|e| anyhow::anyhow!("Keyring unavailable ({})", e))?;
+    let key = CredentialRef::new(service, account, box_enterprise_id_credential())
+        .to_key_in(&store_namespace(config_dir)?);
+    store
+        .set(&key, &sigilforge_core::store::Secret::new(enterprise_id))
+        .await?;
+    println!("  Box enterprise: {}", enterprise_id);
+    Ok(())
+}
+
+/// Credential type under which a Box account's enterprise ID is stored.
+fn box_enterprise_id_credential() -> CredentialType {
+    CredentialType::Custom("enterprise_id".to_string())
+}
+
+async fn fallback_add_account(
+    service: &str,
+    account: &str,
+    discovered: Option<ProviderConfig>,
+    options: AddAccountOptions<'_>,
+) -> Result<()> {
+    use std::io::{IsTerminal, Write};
+
+    let AddAccountOptions {
+        scopes,
+        callback_port,
+        no_browser,
+        client: client_args,
+        global,
+        revoke_existing,
+        box_enterprise_id,
+    } = options;
+
+    // Get provider configuration (discovered, or from the built-in and saved providers)
+    let registry = ProviderRegistry::with_defaults().with_user_providers(global.config_dir)?;
+    let provider = match &discovered {
+        Some(provider) => provider,
+        None => registry.get(service).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown provider '{}'. Available: {:?}",
+                service,
+                registry.list_ids()
+            )
+        })?,
+    };
+
     // Parse scopes
     let scope_list: Vec<String> = if let Some(scopes) = scopes {
         scopes.split(',').map(|s| s.trim().to_string()).collect()
@@ -183,64 +1149,432 @@ async fn fallback_add_account(service: &str, account: &str, scopes: Option<&str>
         provider.default_scopes.clone()
     };
 
-    // Get OAuth client credentials from environment or config
-    let client_id = std::env::var(format!("{}_CLIENT_ID", service.to_uppercase()))
-        .or_else(|_| std::env::var("OAUTH_CLIENT_ID"))
-        .map_err(|_| {
-            anyhow::anyhow!(
-                "Missing OAuth client ID. Set {}_CLIENT_ID or OAUTH_CLIENT_ID environment variable",
-                service.to_uppercase()
-            )
-        })?;
-
-    let client_secret = std::env::var(format!("{}_CLIENT_SECRET", service.to_uppercase()))
-        .or_else(|_| std::env::var("OAUTH_CLIENT_SECRET"))
-        .ok();
-
-    // Setup OAuth callback port
-    let callback_port: u16 = std::env::var("OAUTH_CALLBACK_PORT")
-        .unwrap_or_else(|_| "8484".to_string())
-        .parse()
-        .unwrap_or(8484);
-    let redirect_uri = format!("http://127.0.0.1:{}/callback", callback_port);
+    let (client_id, client_secret) = oauth_client_credentials(service, client_args)?;
 
-    println!("Starting OAuth flow for {}/{}...", service, account);
-    println!("  Provider: {}", provider.name);
-    println!("  Scopes: {}", scope_list.join(", "));
+    // Setup OAuth callback port (0 lets the OS pick a free one)
+    let callback_port: u16 = callback_port.unwrap_or_else(|| {
+        std::env::var("OAUTH_CALLBACK_PORT")
+            .ok()
+            .and_then(|port| port.parse().ok())
+            .unwrap_or(8484)
+    });
 
     // Create PKCE flow
     let flow = PkceFlow::new(
         provider.clone(),
-        client_id,
-        client_secret,
-        redirect_uri,
+        client_id.clone(),
+        client_secret.clone(),
+        RedirectConfig::localhost(callback_port),
     )?;
 
-    // Build authorization URL
-    let (auth_url, csrf_state) = flow.build_authorization_url(scope_list.clone());
+    if revoke_existing {
+        replace_existing_account(service, account, global).await?;
+    }
+
+    // Nobody can press Enter, so hand the URL to the caller and stop here
+    if no_browser && !std::io::stdin().is_terminal() {
+        return begin_detached_account(
+            service,
+            account,
+            &flow,
+            provider,
+            scope_list,
+            box_enterprise_id,
+            global.config_dir,
+        );
+    }
+
+    println!("Starting OAuth flow for {}/{}...", service, account);
+    println!("  Provider: {}", provider.name);
+    println!("  Scopes: {}", scope_list.join(", "));
+
+    // Bind the callback listener first so the URL carries the actual port
+    let (flow, listener) = flow.prepare_flow(scope_list.clone(), "127.0.0.1").await?;
+    let bound_port = flow.redirect().port;
+    let (auth_url, csrf_state) = flow.build_authorization_url(Vec::new(), HashMap::new());
 
     println!("\nPlease visit this URL to authorize:");
-    println!("\n  {}\n", auth_url);
 
-    // Try to open browser automatically
-    if let Err(e) = open_browser(&auth_url) {
-        info!("Could not open browser automatically: {}", e);
-        println!("(Could not open browser automatically - please copy the URL above)");
+    if no_browser {
+        // Unindented so the whole URL can be copied as is; the provider's
+        // redirect waits on the bound listener until Enter is pressed
+        println!("\n{}\n", auth_url);
+        print!("Press Enter after completing authorization in your browser");
+        std::io::stdout().flush()?;
+        std::io::stdin().read_line(&mut String::new())?;
     } else {
-        println!("(Browser should open automatically)");
+        println!("\n  {}\n", auth_url);
+
+        // Try to open browser automatically
+        if let Err(e) = open_browser(&auth_url) {
+            info!("Could not open browser automatically: {}", e);
+            println!("(Could not open browser automatically - please copy the URL above)");
+        } else {
+            println!("(Browser should open automatically)");
+        }
     }
 
-    println!("\nWaiting for authorization on port {}...", callback_port);
+    println!("\nWaiting for authorization on port {}...", bound_port);
 
     // Listen for callback
-    let auth_code = flow.listen_for_callback(callback_port, &csrf_state).await?;
+    let auth_code = flow.listen_for_callback(listener, &csrf_state).await?;
 
     println!("Authorization received! Exchanging code for tokens...");
 
     // Exchange code for tokens
     let token_set = flow.exchange_code(auth_code).await?;
 
+    // The account is only saved once its client credentials are stored
+    store_client_credentials(
+        service,
+        account,
+        &client_id,
+        client_secret.as_deref(),
+        global.config_dir,
+    )
+    .await?;
+    if let Some(enterprise_id) = box_enterprise_id {
+        store_box_enterprise_id(service, account, enterprise_id, global.config_dir).await?;
+    }
+    let source = CredentialSource::OAuthPkce {
+        provider_id: provider.id.clone(),
+    };
+    save_authorized_account(
+        service, account, source, provider, scope_list, token_set, global,
+    )
+    .await
+}
+
+/// Remove `service`/`account` and its credentials, if it exists, so
+/// `add-account --revoke-existing` can add it again.
+///
+/// The refresh token is revoked at the provider first. A failed revocation
+/// is only a warning: the local credentials are deleted either way.
+async fn replace_existing_account(
+    service: &str,
+    account: &str,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    use sigilforge_core::token_manager::DefaultTokenManager;
+
+    let store = load_account_store(global)?;
+    let (service_id, account_id) = (ServiceId::new(service), AccountId::new(account));
+    if store.get_account(&service_id, &account_id)?.is_none() {
+        return Ok(());
+    }
+
+    let secrets: Box<dyn SecretStore> = cleanup_secret_store();
+    let providers = ProviderRegistry::with_defaults().with_user_providers(global.config_dir)?;
+    let manager = DefaultTokenManager::new(secrets, providers)
+        .with_key_prefix(&store_namespace(global.config_dir)?);
+    match manager.revoke_at_provider(&service_id, &account_id).await {
+        Ok(true) => println!("Revoked the refresh token of {}/{}", service, account),
+        Ok(false) => info!("No refresh token of {}/{} to revoke", service, account),
+        Err(e) => warn!(
+            "Could not revoke the refresh token of {}/{} at the provider: {}",
+            service, account, e
+        ),
+    }
+
+    store.remove_account(&service_id, &account_id)?;
+    delete_account_secrets(service, account, global.config_dir).await?;
+    println!("Removed existing account {}/{}", service, account);
+    Ok(())
+}
+
+/// Add an account with the device authorization grant.
+///
+/// Runs locally like the other flows that need a terminal: the user code is
+/// printed here, and the token endpoint is polled until the user approves.
+async fn add_device_code_account(
+    service: &str,
+    account: &str,
+    poll_interval: Option<u64>,
+    options: AddAccountOptions<'_>,
+) -> Result<()> {
+    use std::io::Write;
+
+    let registry =
+        ProviderRegistry::with_defaults().with_user_providers(options.global.config_dir)?;
+    let provider = registry.get(service).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown provider '{}'. Available: {:?}",
+            service,
+            registry.list_ids()
+        )
+    })?;
+    if !provider.supports_device_code {
+        anyhow::bail!(
+            "Provider '{}' does not support the device code flow; run add-account without \
+             --device-code",
+            service
+        );
+    }
+
+    let scope_list: Vec<String> = match options.scopes {
+        Some(scopes) => scopes.split(',').map(|s| s.trim().to_string()).collect(),
+        None => provider.default_scopes.clone(),
+    };
+    let (client_id, client_secret) = oauth_client_credentials(service, options.client)?;
+
+    let flow = DeviceCodeFlow::new(provider.clone(), client_id.clone(), client_secret.clone())?;
+
+    if options.revoke_existing {
+        replace_existing_account(service, account, options.global).await?;
+    }
+
+    println!("Starting device code flow for {}/{}...", service, account);
+    println!("  Provider: {}", provider.name);
+    println!("  Scopes: {}", scope_list.join(", "));
+
+    let mut authorization = flow.request_device_code(scope_list.clone()).await?;
+    if let Some(interval) = poll_interval {
+        authorization.interval = interval;
+    }
+
+    println!("\nOn any device, visit:\n");
+    println!("    {}\n", authorization.verification_uri);
+    println!("and enter the code:\n");
+    println!("    {}\n", authorization.user_code);
+    if let Some(complete) = &authorization.verification_uri_complete {
+        println!("(or open {} to skip entering the code)\n", complete);
+    }
+
+    // One dot per second until the provider reports the outcome
+    print!("Waiting for authorization");
+    std::io::stdout().flush()?;
+    let poll = flow.poll_for_token(&authorization);
+    tokio::pin!(poll);
+    let mut progress = tokio::time::interval(std::time::Duration::from_secs(1));
+    progress.tick().await;
+    let token_set = loop {
+        tokio::select! {
+            result = &mut poll => break result,
+            _ = progress.tick() => {
+                print!(".");
+                std::io::stdout().flush()?;
+            }
+        }
+    };
+    println!();
+    let token_set = token_set?;
+
+    println!("Authorization received!");
+
+    store_client_credentials(
+        service,
+        account,
+        &client_id,
+        client_secret.as_deref(),
+        options.global.config_dir,
+    )
+    .await?;
+    let source = CredentialSource::OAuthDeviceCode {
+        provider_id: provider.id.clone(),
+    };
+    save_authorized_account(
+        service,
+        account,
+        source,
+        provider,
+        scope_list,
+        token_set,
+        options.global,
+    )
+    .await
+}
+
+/// OAuth client ID and optional secret for `service`.
+///
+/// Each comes from the command line if given there (as a value or a file),
+/// otherwise from the environment.
+fn oauth_client_credentials(
+    service: &str,
+    args: &ClientCredentialArgs,
+) -> Result<(String, Option<String>)> {
+    let client_id = match (&args.client_id, &args.client_id_file) {
+        (Some(client_id), _) => client_id.clone(),
+        (None, Some(path)) => read_credential_file("client ID", path)?,
+        (None, None) => std::env::var(format!("{}_CLIENT_ID", service.to_uppercase()))
+            .or_else(|_| std::env::var("OAUTH_CLIENT_ID"))
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Missing OAuth client ID. Pass --client-id, or set {}_CLIENT_ID or \
+                     OAUTH_CLIENT_ID environment variable",
+                    service.to_uppercase()
+                )
+            })?,
+    };
+
+    let client_secret = match (&args.client_secret, &args.client_secret_file) {
+        (Some(secret), _) => Some(secret.clone()),
+        (None, Some(path)) => Some(read_credential_file("client secret", path)?),
+        (None, None) => std::env::var(format!("{}_CLIENT_SECRET", service.to_uppercase()))
+            .or_else(|_| std::env::var("OAUTH_CLIENT_SECRET"))
+            .ok(),
+    };
+
+    Ok((client_id, client_secret))
+}
+
+/// Read a credential kept alone in a file, ignoring surrounding whitespace
+/// such as a trailing newline.
+fn read_credential_file(what: &str, path: &std::path::Path) -> Result<String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {} from {:?}: {}", what, path, e))?;
+    let value = contents.trim();
+    if value.is_empty() {
+        anyhow::bail!("{:?} does not contain a {}", path, what);
+    }
+    Ok(value.to_string())
+}
+
+/// Keep the OAuth client credentials in the keyring, where the token
+/// manager looks for them when refreshing the account's tokens.
+async fn store_client_credentials(
+    service: &str,
+    account: &str,
+    client_id: &str,
+    client_secret: Option<&str>,
+    config_dir: Option<&Path>,
+) -> Result<()> {
+    use sigilforge_core::token_manager::DefaultTokenManager;
+
+    let store = match KeyringStore::try_new("sigilforge") {
+        Ok(store) => store,
+        Err(e) => {
+            warn!(
+                "Keyring unavailable ({}); client credentials will not persist",
+                e
+            );
+            return Ok(());
+        }
+    };
+    let manager = DefaultTokenManager::new(store, ProviderRegistry::new())
+        .with_key_prefix(&store_namespace(config_dir)?);
+    let (service, account) = (ServiceId::new(service), AccountId::new(account));
+    manager
+        .store_credential(&service, &account, CredentialType::ClientId, client_id)
+        .await?;
+    if let Some(secret) = client_secret {
+        manager
+            .store_credential(&service, &account, CredentialType::ClientSecret, secret)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Print the authorization URL alone on stdout and save the flow's state
+/// for `add-account --auth-code`.
+fn begin_detached_account(
+    service: &str,
+    account: &str,
+    flow: &PkceFlow,
+    provider: &ProviderConfig,
+    scopes: Vec<String>,
+    box_enterprise_id: Option<&str>,
+    config_dir: Option<&Path>,
+) -> Result<()> {
+    let (auth_url, authorization) = flow.detached_authorization_url(scopes.clone())?;
+    let pending = pending::PendingAccount {
+        provider: provider.clone(),
+        scopes,
+        authorization,
+        box_enterprise_id: box_enterprise_id.map(str::to_string),
+    };
+    let path = pending::pending_path(config_dir, service, account)?;
+    pending::save(&path, &pending)?;
+
+    println!("{}", auth_url);
+    eprintln!("Visit the URL above to authorize, then finish with:");
+    eprintln!(
+        "  sigilforge add-account {} {} --auth-code=<code>",
+        service, account
+    );
+    Ok(())
+}
+
+/// Exchange the code for an authorization started by `--no-browser`
+/// without a terminal.
+async fn complete_detached_account(
+    service: &str,
+    account: &str,
+    code: &str,
+    client_args: &ClientCredentialArgs,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    let path = pending::pending_path(global.config_dir, service, account)?;
+    if !path.exists() {
+        anyhow::bail!(
+            "No pending authorization for {}/{}. Run add-account with --no-browser first",
+            service,
+            account
+        );
+    }
+    let pending = pending::load(&path)?;
+
+    let (client_id, client_secret) = oauth_client_credentials(service, client_args)?;
+    // The redirect URI comes from the pending state, not from this config
+    let flow = PkceFlow::new(
+        pending.provider.clone(),
+        client_id.clone(),
+        client_secret.clone(),
+        RedirectConfig::localhost(0),
+    )?;
+    flow.resume(&pending.authorization)?;
+
+    println!("Exchanging code for tokens...");
+    // Keep the pending state until the exchange works, so a mistyped code
+    // can be retried
+    let token_set = flow.exchange_code(code).await?;
+    std::fs::remove_file(&path).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to remove pending authorization {}: {}",
+            path.display(),
+            e
+        )
+    })?;
+
+    store_client_credentials(
+        service,
+        account,
+        &client_id,
+        client_secret.as_deref(),
+        global.config_dir,
+    )
+    .await?;
+    if let Some(enterprise_id) = &pending.box_enterprise_id {
+        store_box_enterprise_id(service, account, enterprise_id, global.config_dir).await?;
+    }
+    save_authorized_account(
+        service,
+        account,
+        CredentialSource::OAuthPkce {
+            provider_id: pending.provider.id.clone(),
+        },
+        &pending.provider,
+        pending.scopes,
+        token_set,
+        global.config_dir,
+    )
+    .await
+}
+
+/// Store a new account's tokens in the keyring and its metadata in the
+/// account store, recording how it was authorized as `source`.
+async fn save_authorized_account(
+    service: &str,
+    account: &str,
+    source: CredentialSource,
+    provider: &ProviderConfig,
+    scope_list: Vec<String>,
+    token_set: sigilforge_core::TokenSet,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    use sigilforge_core::{Account, AccountId, ServiceId};
+
     // Store tokens in keyring
+    let namespace = store_namespace(global.config_dir)?;
     let store: Box<dyn SecretStore> = match KeyringStore::try_new("sigilforge") {
         Ok(s) => {
             info!("Using keyring backend for token storage");
@@ -253,34 +1587,49 @@ async fn fallback_add_account(service: &str, account: &str, scopes: Option<&str>
     };
 
     // Store access token
-    let access_key = format!("sigilforge/{}/{}/access_token", service, account);
+    let access_key = format!("{}/{}/{}/access_token", namespace, service, account);
     let access_secret = sigilforge_core::store::Secret::new(token_set.access_token.access_token.expose());
     store.set(&access_key, &access_secret).await?;
 
     // Store refresh token if available
     if let Some(ref refresh) = token_set.refresh_token {
-        let refresh_key = format!("sigilforge/{}/{}/refresh_token", service, account);
+        let refresh_key = format!("{}/{}/{}/refresh_token", namespace, service, account);
         let refresh_secret = sigilforge_core::store::Secret::new(refresh.expose());
         store.set(&refresh_key, &refresh_secret).await?;
     }
 
     // Store expiry if available
     if let Some(expiry) = token_set.access_token.expires_at {
-        let expiry_key = format!("sigilforge/{}/{}/token_expiry", service, account);
+        let expiry_key = format!("{}/{}/{}/token_expiry", namespace, service, account);
         let expiry_secret = sigilforge_core::store::Secret::new(expiry.to_rfc3339());
         store.set(&expiry_key, &expiry_secret).await?;
     }
 
+    // Store the org instance so refreshes go to the right server (Salesforce)
+    if let Some(ref instance_url) = token_set.instance_url {
+        let instance_key = format!("{}/{}/{}/instance_url", namespace, service, account);
+        let instance_secret = sigilforge_core::store::Secret::new(instance_url.as_str());
+        store.set(&instance_key, &instance_secret).await?;
+    }
+
     // Store scopes
-    let scopes_key = format!("sigilforge/{}/{}/scopes", service, account);
+    let scopes_key = format!("{}/{}/{}/scopes", namespace, service, account);
     let scopes_secret = sigilforge_core::store::Secret::new(scope_list.join(","));
     store.set(&scopes_key, &scopes_secret).await?;
 
     // Save account to account store
-    let account_store = AccountStore::load()?;
+    let account_store = load_account_store(global)?;
     let service_id = ServiceId::new(service);
     let account_id = AccountId::new(account);
-    let new_account = Account::new(service_id, account_id, scope_list);
+    let mut new_account = Account::new(service_id, account_id, scope_list).with_source(source);
+    // Providers such as Box expire refresh tokens; remember when so it can be flagged
+    let refresh_expiry = token_set
+        .refresh_token
+        .as_ref()
+        .and_then(|_| provider.refresh_token_expiry(token_set.refreshed_at));
+    if let Some(expiry) = refresh_expiry {
+        new_account = new_account.with_token_refresh_expiry(expiry);
+    }
     account_store.add_account(new_account)?;
 
     println!("\nSuccess! Account {}/{} configured.", service, account);
@@ -291,38 +1640,148 @@ async fn fallback_add_account(service: &str, account: &str, scopes: Option<&str>
     if let Some(expiry) = token_set.access_token.expires_at {
         println!("  Token expires: {}", expiry);
     }
+    if let Some(expiry) = refresh_expiry {
+        println!(
+            "  Refresh token expires: {} (re-authorize before then)",
+            expiry
+        );
+    }
 
     Ok(())
 }
 
-/// Try to open a URL in the default browser
-fn open_browser(url: &str) -> Result<()> {
-    #[cfg(target_os = "linux")]
-    {
-        std::process::Command::new("xdg-open")
-            .arg(url)
-            .spawn()?;
+/// Store the account and tokens described by a credentials file.
+///
+/// The whole file is validated before anything is written. An existing
+/// account is kept and its credentials are overwritten, as with `import`.
+async fn add_account_from_file(path: &Path, global: GlobalOptions<'_>) -> Result<()> {
+    let importer = import::credentials_file::CredentialsFileImporter::new(path);
+    let report = importer.import()?;
+    let (service, account) = match report.credentials.first() {
+        Some(credential) => (credential.service.clone(), credential.account.clone()),
+        None => anyhow::bail!("{} holds no credentials", importer.source()),
+    };
+
+    let store = KeyringStore::try_new("sigilforge")
+        .map_err(|e| anyhow::anyhow!("Keyring unavailable ({}); cannot store credentials", e))?;
+    let accounts = load_account_store(global)?;
+    let namespace = store_namespace(global.config_dir)?;
+    let added = import::store_credentials(
+        &store,
+        &accounts,
+        &report.credentials,
+        importer.format(),
+        &namespace,
+    )
+    .await?;
+
+    let verb = if added > 0 { "Added" } else { "Updated" };
+    let source = importer.source();
+    println!("{} account {}/{} from {}", verb, service, account, source);
+    Ok(())
+}
+
+/// Register a GitHub App installation and fetch its first installation token.
+///
+/// The app credentials are stored alongside the token so the token manager
+/// can request a new installation token whenever the current one expires.
+async fn add_github_app_account(
+    org: &str,
+    args: GitHubAppArgs,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    use sigilforge_core::{token_manager::DefaultTokenManager, Account, TokenManager};
+
+    let (Some(app_id), Some(key_path), Some(installation_id)) =
+        (args.app_id, args.private_key_file, args.installation_id)
+    else {
+        anyhow::bail!(
+            "github-app accounts require --app-id, --private-key-file and --installation-id"
+        );
+    };
+
+    let private_key = std::fs::read_to_string(&key_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", key_path.display(), e))?;
+
+    let mut flow = GitHubAppFlow::new(&app_id, &installation_id, private_key.as_bytes())?;
+    if let Some(api_url) = &args.github_api_url {
+        flow = flow.with_api_url(api_url);
     }
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .arg(url)
-            .spawn()?;
+
+    println!("Requesting installation token for {} (app {})...", org, app_id);
+    let token_set = flow.request_installation_token().await?;
+
+    let store: Box<dyn SecretStore> = KeyringStore::try_new("sigilforge")
+        .map(|s| Box::new(s) as Box<dyn SecretStore>)
+        .map_err(|e| anyhow::anyhow!("Keyring unavailable ({}); cannot store app credentials", e))?;
+
+    let service_id = ServiceId::new(github_app::GITHUB_APP_SERVICE);
+    let account_id = AccountId::new(org);
+
+    let mut credentials = vec![
+        (github_app::app_id_credential(), app_id.clone()),
+        (github_app::installation_id_credential(), installation_id),
+        (github_app::private_key_credential(), private_key),
+    ];
+    if let Some(api_url) = args.github_api_url {
+        credentials.push((github_app::api_url_credential(), api_url));
     }
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("cmd")
-            .args(["/C", "start", url])
-            .spawn()?;
+    let namespace = store_namespace(global.config_dir)?;
+    for (cred_type, value) in credentials {
+        let key = format!(
+            "{}/{}/{}/{}",
+            namespace,
+            service_id,
+            account_id,
+            cred_type.as_str()
+        );
+        store
+            .set(&key, &sigilforge_core::store::Secret::new(value))
+            .await?;
+    }
+
+    let scopes = token_set.access_token.scopes.clone();
+    let expires_at = token_set.access_token.expires_at;
+
+    let manager =
+        DefaultTokenManager::new(store, ProviderRegistry::new()).with_key_prefix(&namespace);
+    manager
+        .store_token_set(&service_id, &account_id, token_set)
+        .await?;
+
+    let account = Account::new(service_id, account_id, scopes).with_source(
+        CredentialSource::ClientCredentials {
+            provider_id: github_app::GITHUB_APP_SERVICE.to_string(),
+        },
+    );
+    load_account_store(global)?.add_account(account)?;
+
+    println!("\nSuccess! GitHub App installation {} configured.", org);
+    println!("  Installation tokens are re-requested automatically on expiry");
+    if let Some(expiry) = expires_at {
+        println!("  Token expires: {}", expiry);
     }
+
     Ok(())
 }
 
-async fn list_accounts(service_filter: Option<&str>) -> Result<()> {
-    let mut client = client::DaemonClient::connect_default().await?;
+async fn list_accounts(
+    service_filter: Option<&str>,
+    format: ListFormat,
+    verbose: bool,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    let mut client = client::DaemonClient::connect_default(global.config_dir).await?;
 
     if client.is_connected() {
         match client.list_accounts(service_filter).await {
+            Ok(response) if format == ListFormat::Json => {
+                let mut accounts: Vec<_> = response.accounts.into_iter().map(Into::into).collect();
+                if verbose {
+                    daemon_token_status(&mut client, &mut accounts).await;
+                }
+                print_accounts_json(&accounts)
+            }
             Ok(response) => {
                 if response.accounts.is_empty() {
                     println!("No accounts configured");
@@ -335,86 +1794,342 @@ async fn list_accounts(service_filter: Option<&str>) -> Result<()> {
                         if let Some(last_used) = account.last_used {
                             println!("    Last used: {}", last_used);
                         }
+                        if verbose {
+                            println!("    Source: {}", account.source);
+                        }
                     }
                 }
                 Ok(())
             }
             Err(e) => {
                 warn!("Daemon call failed: {}", e);
-                fallback_list_accounts(service_filter).await
+                fallback_list_accounts(service_filter, format, verbose, global).await
             }
         }
-    } else {
-        warn!("Daemon not available, using fallback mode");
-        fallback_list_accounts(service_filter).await
+    } else {
+        warn!("Daemon not available, using fallback mode");
+        fallback_list_accounts(service_filter, format, verbose, global).await
+    }
+}
+
+async fn fallback_list_accounts(
+    service_filter: Option<&str>,
+    format: ListFormat,
+    verbose: bool,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    use sigilforge_core::ServiceId;
+
+    let store = load_account_store(global)?;
+
+    let filter = service_filter.map(ServiceId::new);
+    let accounts = store.list_accounts(filter.as_ref())?;
+
+    if format == ListFormat::Json {
+        let mut accounts: Vec<_> = accounts.iter().map(Into::into).collect();
+        if verbose {
+            local_token_status(&mut accounts, &store_namespace(global.config_dir)?).await;
+        }
+        return print_accounts_json(&accounts);
+    }
+
+    if accounts.is_empty() {
+        println!("No accounts configured");
+        if let Some(service) = service_filter {
+            println!("  (filtered by service: {})", service);
+        }
+        return Ok(());
+    }
+
+    println!("Configured accounts:");
+    for account in accounts {
+        println!("  {}/{}", account.service, account.id);
+        if !account.scopes.is_empty() {
+            println!("    Scopes: {}", account.scopes.join(", "));
+        }
+        println!("    Created: {}", account.created_at);
+        if let Some(last_used) = account.last_used {
+            println!("    Last used: {}", last_used);
+        }
+        if verbose {
+            println!("    Source: {}", account.source);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print accounts as a JSON array.
+fn print_accounts_json(accounts: &[output::AccountInfo]) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(accounts)?);
+    Ok(())
+}
+
+/// Fill in each account's token status as the daemon reports it.
+async fn daemon_token_status(
+    client: &mut client::DaemonClient,
+    accounts: &mut [output::AccountInfo],
+) {
+    let statuses = match client.accounts_status().await {
+        Ok(response) => response.accounts,
+        Err(e) => {
+            warn!("Daemon call failed ({}); token status is unknown", e);
+            Vec::new()
+        }
+    };
+    for account in accounts {
+        let status = statuses
+            .iter()
+            .find(|s| s.service == account.service && s.account == account.account)
+            .map_or(output::TokenStatus::Unknown, Into::into);
+        account.token_status = Some(status);
+    }
+}
+
+/// Fill in each account's token status from the keyring, whose keys start
+/// with `namespace`, for when the daemon is not running.
+async fn local_token_status(accounts: &mut [output::AccountInfo], namespace: &str) {
+    match KeyringStore::try_new("sigilforge") {
+        Ok(store) => {
+            let manager = sigilforge_core::token_manager::DefaultTokenManager::new(
+                store,
+                ProviderRegistry::new(),
+            )
+            .with_key_prefix(namespace);
+            for account in accounts {
+                let status =
+                    output::TokenStatus::check(&manager, &account.service, &account.account).await;
+                account.token_status = Some(status);
+            }
+        }
+        Err(e) => {
+            warn!("Keyring unavailable ({}); token status is unknown", e);
+            for account in accounts {
+                account.token_status = Some(output::TokenStatus::Unknown);
+            }
+        }
+    }
+}
+
+/// Block until the daemon answers a health check, exiting with code 2 if it
+/// does not start within `timeout`.
+async fn wait_for_daemon(timeout: std::time::Duration, config_dir: Option<&Path>) {
+    if wait::daemon_ready(config_dir).await {
+        return;
+    }
+
+    eprintln!("Waiting for sigilforge daemon...");
+    let ready = wait::wait_until(wait::POLL_INTERVAL, timeout, || {
+        wait::daemon_ready(config_dir)
+    })
+    .await;
+    if !ready {
+        eprintln!(
+            "Error: sigilforge daemon did not start within {}s",
+            timeout.as_secs()
+        );
+        std::process::exit(2);
+    }
+}
+
+async fn get_token(
+    service: &str,
+    account: &str,
+    format: &str,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    let response = fetch_token(service, account, global).await?;
+    warn_missing_scopes(service, account, &response.scopes, global);
+
+    match format {
+        "json" => {
+            let json_output = serde_json::json!({
+                "service": service,
+                "account": account,
+                "token": response.token,
+                "expires_at": response.expires_at,
+            });
+            println!("{}", serde_json::to_string_pretty(&json_output)?);
+        }
+        _ => {
+            println!("{}", output::format_token(format, &response.token));
+        }
     }
+
+    Ok(())
 }
 
-async fn fallback_list_accounts(service_filter: Option<&str>) -> Result<()> {
-    use sigilforge_core::{AccountStore, ServiceId};
+/// Force a token refresh and print the new token and its expiry.
+///
+/// Exits with code 1 if the token cannot be refreshed.
+async fn refresh_token(
+    service: &str,
+    account: &str,
+    format: &str,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    let response = match fetch_refreshed_token(service, account, global).await {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!(
+                "Error: Failed to refresh token for {}/{}: {:#}",
+                service, account, e
+            );
+            std::process::exit(1);
+        }
+    };
+    warn_missing_scopes(service, account, &response.scopes, global);
 
-    let store = AccountStore::load()?;
+    match format {
+        "json" => {
+            let json_output = serde_json::json!({
+                "service": service,
+                "account": account,
+                "token": response.token,
+                "expires_at": response.expires_at,
+            });
+            println!("{}", serde_json::to_string_pretty(&json_output)?);
+        }
+        _ => {
+            println!("{}", output::format_token(format, &response.token));
+            // On stderr, so `$(sigilforge get-token --refresh ...)` is just the token
+            match &response.expires_at {
+                Some(expires_at) => eprintln!("Expires: {}", expires_at),
+                None => eprintln!("Expires: never"),
+            }
+        }
+    }
 
-    let filter = service_filter.map(ServiceId::new);
-    let accounts = store.list_accounts(filter.as_ref())?;
+    Ok(())
+}
 
-    if accounts.is_empty() {
-        println!("No accounts configured");
-        if let Some(service) = service_filter {
-            println!("  (filtered by service: {})", service);
-        }
-        return Ok(());
+/// Warn if the token was granted only some of the scopes the account was
+/// added with, e.g. because the user unticked them on the consent screen.
+///
+/// Tokens whose provider did not report scopes are not checked.
+fn warn_missing_scopes(
+    service: &str,
+    account: &str,
+    granted: &[String],
+    global: GlobalOptions<'_>,
+) {
+    let granted: ScopeSet = granted.iter().collect();
+    if granted.is_empty() {
+        return;
+    }
+    let service_id = ServiceId::new(service);
+    let account_id = AccountId::new(account);
+    let Ok(Some(stored)) =
+        load_account_store(global).and_then(|store| store.get_account(&service_id, &account_id))
+    else {
+        return;
+    };
+
+    let requested: ScopeSet = stored.scopes.iter().collect();
+    let missing = granted.missing_from(&requested);
+    if !missing.is_empty() && granted.is_subset_of(&requested) {
+        eprintln!(
+            "Warning: {}/{} was granted only some of the requested scopes (missing: {}). \
+             Run 'sigilforge add-account {} {}' to authorize them.",
+            service, account, missing, service, account
+        );
     }
+}
 
-    println!("Configured accounts:");
-    for account in accounts {
-        println!("  {}/{}", account.service, account.id);
-        if !account.scopes.is_empty() {
-            println!("    Scopes: {}", account.scopes.join(", "));
-        }
-        println!("    Created: {}", account.created_at);
-        if let Some(last_used) = account.last_used {
-            println!("    Last used: {}", last_used);
-        }
+/// Refresh the token through the daemon, or against the provider directly
+/// if the daemon is not running.
+async fn fetch_refreshed_token(
+    service: &str,
+    account: &str,
+    global: GlobalOptions<'_>,
+) -> Result<client::GetTokenResponse> {
+    let mut client = client::DaemonClient::connect_default(global.config_dir).await?;
+    if client.is_connected() {
+        // The daemon already tried the provider, so its errors are final
+        return client.refresh_token(service, account).await;
     }
 
+    warn!("Daemon not available, refreshing directly");
+    let store: Box<dyn SecretStore> = match KeyringStore::try_new("sigilforge") {
+        Ok(s) => Box::new(s),
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Keyring unavailable: {}. Cannot refresh tokens.",
+                e
+            ));
+        }
+    };
+    let providers = ProviderRegistry::with_defaults().with_user_providers(global.config_dir)?;
+    let manager = sigilforge_core::token_manager::DefaultTokenManager::new(store, providers)
+        .with_key_prefix(&store_namespace(global.config_dir)?);
+
+    let token = manager
+        .force_refresh(&ServiceId::new(service), &AccountId::new(account))
+        .await?;
+    Ok(client::GetTokenResponse {
+        token: token.access_token.expose().to_string(),
+        expires_at: token.expires_at.map(|e| e.to_rfc3339()),
+        scopes: token.scopes,
+    })
+}
+
+/// Poll the token every `interval` seconds, printing it whenever it changes.
+async fn watch_token(
+    service: &str,
+    account: &str,
+    format: &str,
+    interval: u64,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    let format = match format {
+        "json" => watch::WatchFormat::Json,
+        format => watch::WatchFormat::Text(format),
+    };
+
+    watch::watch(
+        std::time::Duration::from_secs(interval),
+        format,
+        || fetch_token(service, account, global),
+        &mut std::io::stdout(),
+        async {
+            let _ = tokio::signal::ctrl_c().await;
+        },
+    )
+    .await?;
+
+    eprintln!("Stopped watching {}/{}", service, account);
     Ok(())
 }
 
-async fn get_token(service: &str, account: &str, format: &str) -> Result<()> {
-    let mut client = client::DaemonClient::connect_default().await?;
+/// Get a fresh access token from the daemon, or from the keyring directly.
+async fn fetch_token(
+    service: &str,
+    account: &str,
+    global: GlobalOptions<'_>,
+) -> Result<client::GetTokenResponse> {
+    let mut client = client::DaemonClient::connect_default(global.config_dir).await?;
 
     if client.is_connected() {
         match client.get_token(service, account).await {
-            Ok(response) => {
-                match format {
-                    "json" => {
-                        let json_output = serde_json::json!({
-                            "service": service,
-                            "account": account,
-                            "token": response.token,
-                            "expires_at": response.expires_at,
-                        });
-                        println!("{}", serde_json::to_string_pretty(&json_output)?);
-                    }
-                    _ => {
-                        println!("{}", response.token);
-                    }
-                }
-                Ok(())
-            }
+            Ok(response) => Ok(response),
             Err(e) => {
                 warn!("Daemon call failed: {}", e);
-                fallback_get_token(service, account, format).await
+                fallback_get_token(service, account, global.config_dir).await
             }
         }
     } else {
         warn!("Daemon not available, using fallback mode");
-        fallback_get_token(service, account, format).await
+        fallback_get_token(service, account, global.config_dir).await
     }
 }
 
-async fn fallback_get_token(service: &str, account: &str, format: &str) -> Result<()> {
+async fn fallback_get_token(
+    service: &str,
+    account: &str,
+    config_dir: Option<&Path>,
+) -> Result<client::GetTokenResponse> {
+    let namespace = store_namespace(config_dir)?;
+
     // Initialize secret store
     let store: Box<dyn SecretStore> = match KeyringStore::try_new("sigilforge") {
         Ok(s) => Box::new(s),
@@ -424,7 +2139,7 @@ async fn fallback_get_token(service: &str, account: &str, format: &str) -> Resul
     };
 
     // Try to get access token
-    let access_key = format!("sigilforge/{}/{}/access_token", service, account);
+    let access_key = format!("{}/{}/{}/access_token", namespace, service, account);
     let token = match store.get(&access_key).await? {
         Some(secret) => secret.expose().to_string(),
         None => {
@@ -436,7 +2151,7 @@ async fn fallback_get_token(service: &str, account: &str, format: &str) -> Resul
     };
 
     // Get expiry if available
-    let expiry_key = format!("sigilforge/{}/{}/token_expiry", service, account);
+    let expiry_key = format!("{}/{}/{}/token_expiry", namespace, service, account);
     let expires_at: Option<chrono::DateTime<chrono::Utc>> = match store.get(&expiry_key).await? {
         Some(secret) => chrono::DateTime::parse_from_rfc3339(secret.expose())
             .ok()
@@ -449,7 +2164,7 @@ async fn fallback_get_token(service: &str, account: &str, format: &str) -> Resul
         let now = chrono::Utc::now();
         if expiry < now {
             // Token is expired, try to refresh
-            let refresh_key = format!("sigilforge/{}/{}/refresh_token", service, account);
+            let refresh_key = format!("{}/{}/{}/refresh_token", namespace, service, account);
             if store.get(&refresh_key).await?.is_some() {
                 // TODO: Implement token refresh using refresh_token
                 // For now, warn user to re-authenticate
@@ -465,28 +2180,76 @@ async fn fallback_get_token(service: &str, account: &str, format: &str) -> Resul
         }
     }
 
-    match format {
-        "json" => {
-            let json_output = serde_json::json!({
-                "service": service,
-                "account": account,
-                "token": token,
-                "expires_at": expires_at.map(|e| e.to_rfc3339()),
-            });
-            println!("{}", serde_json::to_string_pretty(&json_output)?);
-        }
-        _ => {
-            println!("{}", token);
-        }
+    // Scopes are stored comma separated
+    let scopes_key = format!("{}/{}/{}/token_scopes", namespace, service, account);
+    let scopes = match store.get(&scopes_key).await? {
+        Some(secret) => secret.expose().parse::<ScopeSet>()?.into_iter().collect(),
+        None => Vec::new(),
+    };
+
+    Ok(client::GetTokenResponse {
+        token,
+        expires_at: expires_at.map(|e| e.to_rfc3339()),
+        scopes,
+    })
+}
+
+/// Print the stored token's introspection info from the keyring.
+async fn inspect_token(
+    service: &str,
+    account: &str,
+    format: &str,
+    config_dir: Option<&Path>,
+) -> Result<()> {
+    use sigilforge_core::{token_manager::DefaultTokenManager, TokenManager};
+
+    let store = KeyringStore::try_new("sigilforge")
+        .map_err(|e| anyhow::anyhow!("Keyring unavailable ({}); cannot read the token", e))?;
+    let manager = DefaultTokenManager::new(store, ProviderRegistry::new())
+        .with_key_prefix(&store_namespace(config_dir)?);
+    let info = manager
+        .introspect_token(&ServiceId::new(service), &AccountId::new(account))
+        .await?;
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
     }
 
+    let unknown = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+    println!("Account: {}/{}", service, account);
+    println!("  Active: {}", if info.active { "yes" } else { "no" });
+    println!(
+        "  Expires: {}",
+        info.expires_at
+            .map(|at| at.to_rfc3339())
+            .unwrap_or_else(|| "never".to_string())
+    );
+    if info.scopes.is_empty() {
+        println!("  Scopes: -");
+    } else {
+        println!("  Scopes: {}", info.scopes.join(" "));
+    }
+    println!("  Subject: {}", unknown(&info.subject));
+    println!("  Username: {}", unknown(&info.username));
+    println!("  Client ID: {}", unknown(&info.client_id));
+    println!("  Issuer: {}", unknown(&info.issuer));
+    println!("  Token ID (jti): {}", unknown(&info.jti));
+    if let Some(id) = &info.dropbox_account_id {
+        println!("  Dropbox account ID: {}", id);
+    }
     Ok(())
 }
 
-async fn remove_account(service: &str, account: &str, force: bool) -> Result<()> {
+async fn remove_account(
+    service: &str,
+    account: &str,
+    force: bool,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
     use std::io::{self, Write};
 
-    let store = AccountStore::load()?;
+    let store = load_account_store(global)?;
     let service_id = ServiceId::new(service);
     let account_id = AccountId::new(account);
 
@@ -517,7 +2280,7 @@ async fn remove_account(service: &str, account: &str, force: bool) -> Result<()>
     // Remove account from store
     store.remove_account(&service_id, &account_id)?;
 
-    delete_account_secrets(service, account).await?;
+    delete_account_secrets(service, account, global.config_dir).await?;
 
     println!("Account {}/{} removed successfully", service, account);
     println!("  Associated secrets removed from configured secret store");
@@ -525,19 +2288,139 @@ async fn remove_account(service: &str, account: &str, force: bool) -> Result<()>
     Ok(())
 }
 
-async fn delete_account_secrets(service: &str, account: &str) -> Result<()> {
-    // Choose the best available secret store
-    let store: Box<dyn SecretStore + Send + Sync> = match KeyringStore::try_new("sigilforge") {
+/// Remove every account of `service`, or of every service if `service` is
+/// `None`, and their credentials.
+///
+/// Removing every account always asks the user to type "yes", even with
+/// `--force`.
+async fn remove_accounts(
+    service: Option<&str>,
+    force: bool,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    use std::io::{self, Write};
+
+    let store = load_account_store(global)?;
+    let service_id = service.map(ServiceId::new);
+    let accounts = store.list_accounts(service_id.as_ref())?;
+
+    if accounts.is_empty() {
+        match service {
+            Some(service) => println!("No accounts configured for {}", service),
+            None => println!("No accounts configured"),
+        }
+        return Ok(());
+    }
+
+    let confirmed = match service {
+        Some(_) if force => true,
+        Some(service) => {
+            print!(
+                "Remove all {} accounts of {}? [y/N] ",
+                accounts.len(),
+                service
+            );
+            io::stdout().flush()?;
+
+            let mut response = String::new();
+            io::stdin().read_line(&mut response)?;
+            response.trim().eq_ignore_ascii_case("y") || response.trim().eq_ignore_ascii_case("yes")
+        }
+        None => {
+            print!("Remove ALL accounts? Type 'yes' to confirm: ");
+            io::stdout().flush()?;
+
+            let mut response = String::new();
+            io::stdin().read_line(&mut response)?;
+            response.trim() == "yes"
+        }
+    };
+    if !confirmed {
+        println!("Cancelled");
+        return Ok(());
+    }
+
+    for account in &accounts {
+        store.remove_account(&account.service, &account.id)?;
+    }
+
+    let namespace = store_namespace(global.config_dir)?;
+    let prefix = match service {
+        Some(service) => format!("{}/{}/", namespace, service),
+        None => format!("{}/", namespace),
+    };
+    let secrets = cleanup_secret_store();
+    match secrets.delete_prefix(&prefix).await {
+        Ok(count) => info!("Deleted {} secrets under {}", count, prefix),
+        Err(e) => {
+            // The keyring cannot list keys, so delete each account's
+            // known credential types instead
+            info!(
+                "Cannot delete secrets by prefix ({}); deleting per account",
+                e
+            );
+            for account in &accounts {
+                let (service, account) = (account.service.as_str(), account.id.as_str());
+                delete_known_secrets(&*secrets, &namespace, service, account).await;
+            }
+        }
+    }
+
+    println!("Removed {} account(s)", accounts.len());
+    for account in &accounts {
+        println!("  {}/{}", account.service, account.id);
+    }
+    println!("  Associated secrets removed from configured secret store");
+
+    Ok(())
+}
+
+/// The secret store to delete credentials from: the keyring, or a memory
+/// store (a no-op) if it is unavailable.
+fn cleanup_secret_store() -> Box<dyn SecretStore + Send + Sync> {
+    match KeyringStore::try_new("sigilforge") {
         Ok(s) => {
             info!("Using keyring backend to delete secrets");
             Box::new(s)
         }
         Err(e) => {
-            warn!("Keyring unavailable ({}); falling back to memory store (no-op)", e);
+            warn!(
+                "Keyring unavailable ({}); falling back to memory store (no-op)",
+                e
+            );
             Box::new(MemoryStore::new())
         }
-    };
+    }
+}
+
+async fn delete_account_secrets(
+    service: &str,
+    account: &str,
+    config_dir: Option<&Path>,
+) -> Result<()> {
+    let store = cleanup_secret_store();
+    delete_known_secrets(&*store, &store_namespace(config_dir)?, service, account).await;
+    Ok(())
+}
+
+/// The namespace the daemon stores secrets under: `store_namespace` in
+/// `daemon.toml`, or `sigilforge` if it is unset.
+///
+/// An unreadable config is an error rather than falling back to the
+/// default, which would put secrets where the daemon does not look.
+fn store_namespace(config_dir: Option<&Path>) -> Result<String> {
+    let namespace = audit::configured_store_namespace(&audit::daemon_config_path(config_dir))?;
+    Ok(namespace.unwrap_or_else(|| DEFAULT_KEY_PREFIX.to_string()))
+}
 
+/// Delete the common credential types of `service`/`account` from `store`,
+/// whose keys start with `namespace`.
+async fn delete_known_secrets(
+    store: &dyn SecretStore,
+    namespace: &str,
+    service: &str,
+    account: &str,
+) {
     // Common credential types to clean up
     let credential_types = [
         CredentialType::AccessToken,
@@ -550,16 +2433,14 @@ async fn delete_account_secrets(service: &str, account: &str) -> Result<()> {
     ];
 
     for cred_type in &credential_types {
-        let key = format!("sigilforge/{}/{}/{}", service, account, cred_type);
+        let key = format!("{}/{}/{}/{}", namespace, service, account, cred_type);
         // Ignore errors - the key might not exist
         let _ = store.delete(&key).await;
     }
-
-    Ok(())
 }
 
-async fn resolve_reference(reference: &str) -> Result<()> {
-    let mut client = client::DaemonClient::connect_default().await?;
+async fn resolve_reference(reference: &str, config_dir: Option<&Path>) -> Result<()> {
+    let mut client = client::DaemonClient::connect_default(config_dir).await?;
 
     if client.is_connected() {
         match client.resolve(reference).await {
@@ -569,18 +2450,16 @@ async fn resolve_reference(reference: &str) -> Result<()> {
             }
             Err(e) => {
                 warn!("Daemon call failed: {}", e);
-                fallback_resolve_reference(reference).await
+                fallback_resolve_reference(reference, config_dir).await
             }
         }
     } else {
         warn!("Daemon not available, using fallback mode");
-        fallback_resolve_reference(reference).await
+        fallback_resolve_reference(reference, config_dir).await
     }
 }
 
-async fn fallback_resolve_reference(reference: &str) -> Result<()> {
-    use sigilforge_core::CredentialRef;
-
+async fn fallback_resolve_reference(reference: &str, config_dir: Option<&Path>) -> Result<()> {
     let cred_ref = CredentialRef::from_auth_uri(reference)
         .map_err(|e| anyhow::anyhow!("Failed to parse reference '{}': {}", reference, e))?;
 
@@ -593,12 +2472,7 @@ async fn fallback_resolve_reference(reference: &str) -> Result<()> {
     };
 
     // Build the key based on credential type
-    let key = format!(
-        "sigilforge/{}/{}/{}",
-        cred_ref.service,
-        cred_ref.account,
-        cred_ref.credential_type
-    );
+    let key = cred_ref.to_key_in(&store_namespace(config_dir)?);
 
     // Retrieve the value
     let value = match store.get(&key).await? {
@@ -615,6 +2489,499 @@ async fn fallback_resolve_reference(reference: &str) -> Result<()> {
     Ok(())
 }
 
+async fn daemon_status(
+    format: &str,
+    validate_providers: bool,
+    config_dir: Option<&Path>,
+) -> Result<()> {
+    let mut client = client::DaemonClient::connect_default(config_dir).await?;
+    let socket_path = client.socket_path().display().to_string();
+
+    let health = if client.is_connected() {
+        match client.health_check().await {
+            Ok(health) => Some(health),
+            Err(e) => {
+                warn!("Health check failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Only the daemon knows which providers it loaded
+    let providers = if !validate_providers {
+        None
+    } else if health.is_some() {
+        Some(client.validate_providers().await)
+    } else {
+        Some(Err(anyhow::anyhow!(
+            "the daemon is unreachable, so its providers cannot be validated"
+        )))
+    };
+    let providers_valid = match &providers {
+        Some(Ok(report)) => report.is_valid(),
+        Some(Err(_)) => false,
+        None => true,
+    };
+
+    match format {
+        "json" => {
+            let mut json_output = match &health {
+                Some(h) => serde_json::json!({
+                    "reachable": true,
+                    "healthy": h.ok,
+                    "version": h.version,
+                    "uptime_secs": h.uptime_secs,
+                    "account_count": h.account_count,
+                    "backend": h.backend,
+                    "socket_path": socket_path,
+                }),
+                None => serde_json::json!({
+                    "reachable": false,
+                    "healthy": false,
+                    "socket_path": socket_path,
+                }),
+            };
+            match &providers {
+                Some(Ok(report)) => {
+                    let entries: Vec<_> = report
+                        .providers
+                        .iter()
+                        .map(|provider| {
+                            serde_json::json!({
+                                "id": provider.id,
+                                "valid": provider.errors.is_empty(),
+                                "errors": provider.errors,
+                                "warnings": provider.warnings,
+                            })
+                        })
+                        .collect();
+                    json_output["providers"] = serde_json::json!(entries);
+                    json_output["skipped_provider_files"] = serde_json::json!(report.skipped_files);
+                }
+                Some(Err(e)) => {
+                    json_output["providers_error"] = serde_json::json!(format!("{:#}", e));
+                }
+                None => {}
+            }
+            println!("{}", serde_json::to_string_pretty(&json_output)?);
+        }
+        _ => match &health {
+            Some(h) => {
+                println!("Daemon: {}", if h.ok { "healthy" } else { "degraded" });
+                println!("  Version: {}", h.version);
+                println!("  Uptime: {}s", h.uptime_secs);
+                println!("  Accounts: {}", h.account_count);
+                println!("  Backend: {}", h.backend);
+                println!("  Socket: {}", socket_path);
+            }
+            None => {
+                println!("Daemon: unreachable");
+                println!("  Socket: {}", socket_path);
+            }
+        },
+    }
+
+    if format != "json" {
+        match &providers {
+            Some(Ok(report)) => {
+                let status = if providers_valid { "valid" } else { "invalid" };
+                println!("Providers: {}", status);
+                for provider in &report.providers {
+                    let id = &provider.id;
+                    if provider.errors.is_empty() && provider.warnings.is_empty() {
+                        println!("  {}: ok", id);
+                    }
+                    for error in &provider.errors {
+                        println!("  {}: error: {}", id, error);
+                    }
+                    for warning in &provider.warnings {
+                        println!("  {}: warning: {}", id, warning);
+                    }
+                }
+                for skipped in &report.skipped_files {
+                    println!("  {}: skipped: {}", skipped.path, skipped.error);
+                }
+            }
+            Some(Err(e)) => {
+                println!("Providers: invalid");
+                println!("  {:#}", e);
+            }
+            None => {}
+        }
+    }
+
+    match health {
+        Some(h) if h.ok && providers_valid => Ok(()),
+        Some(_) => std::process::exit(1),
+        None => std::process::exit(2),
+    }
+}
+
+async fn whoami(format: &str, global: GlobalOptions<'_>) -> Result<()> {
+    use std::collections::BTreeSet;
+
+    // Daemon
+    let mut client = client::DaemonClient::connect_default(global.config_dir).await?;
+    let socket_path = client.socket_path().display().to_string();
+    let health = if client.is_connected() {
+        client
+            .health_check()
+            .await
+            .inspect_err(|e| warn!("Health check failed: {}", e))
+            .ok()
+    } else {
+        None
+    };
+
+    // Accounts
+    let account_store = load_account_store(global)?;
+    let accounts = account_store.list_accounts(None)?;
+    let services: BTreeSet<&str> = accounts.iter().map(|a| a.service.as_str()).collect();
+
+    // Keyring
+    let keyring = KeyringStore::try_new("sigilforge").map(|store| store.backend_name());
+
+    // Config directory
+    let config_dir_display = account_store
+        .path()
+        .parent()
+        .map(|dir| dir.display().to_string());
+
+    // Providers
+    let registry = ProviderRegistry::with_defaults().with_user_providers(global.config_dir)?;
+    let user_providers = registry.user_defined_ids();
+
+    if format == "json" {
+        let daemon = match &health {
+            Some(h) => serde_json::json!({
+                "running": true,
+                "version": h.version,
+                "uptime_secs": h.uptime_secs,
+            }),
+            None => serde_json::json!({ "running": false }),
+        };
+        let keyring = match &keyring {
+            Ok(backend) => serde_json::json!({ "available": true, "backend": backend }),
+            Err(e) => serde_json::json!({ "available": false, "error": e.to_string() }),
+        };
+        let output = serde_json::json!({
+            "socket_path": socket_path,
+            "daemon": daemon,
+            "accounts": {
+                "count": accounts.len(),
+                "services": services,
+                "store_path": account_store.path(),
+            },
+            "keyring": keyring,
+            "config_dir": config_dir_display,
+            "providers": {
+                "total": registry.len(),
+                "user_defined": user_providers,
+            },
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    match &health {
+        Some(h) => {
+            println!("Daemon: running ({})", socket_path);
+            println!("  Version: {}", h.version);
+            println!("  Uptime: {}s", h.uptime_secs);
+        }
+        None => println!("Daemon: not running ({})", socket_path),
+    }
+    println!(
+        "Accounts: {} accounts configured across {} services",
+        accounts.len(),
+        services.len()
+    );
+    match &keyring {
+        Ok(backend) => println!("Keyring: available ({})", backend),
+        Err(e) => println!("Keyring: unavailable ({})", e),
+    }
+    println!(
+        "Config directory: {}",
+        config_dir_display.as_deref().unwrap_or("unavailable")
+    );
+    if user_providers.is_empty() {
+        println!("Providers: {} built-in, no user-defined providers", registry.len());
+    } else {
+        println!(
+            "Providers: {} registered, user-defined: {}",
+            registry.len(),
+            user_providers.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+fn show_audit_log(
+    filter: &audit::AuditFilter,
+    format: &str,
+    config_dir: Option<&Path>,
+) -> Result<()> {
+    let config_path = audit::daemon_config_path(config_dir);
+    let Some(path) = audit::configured_audit_log(&config_path)? else {
+        eprintln!("No audit log configured; set audit_log_path in {}", config_path.display());
+        std::process::exit(1);
+    };
+
+    let entries = filter.apply(audit::read_entries(&path)?);
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else if entries.is_empty() {
+        println!("No matching audit entries in {}", path.display());
+    } else {
+        print!("{}", audit::format_table(&entries));
+    }
+    Ok(())
+}
+
+fn migrate(dry_run: bool, encrypt: bool, global: GlobalOptions<'_>) -> Result<()> {
+    let store = if encrypt {
+        let path = account_store_path(global.config_dir)?;
+        let store = open_encrypted_store(&path, global.encryption.flatten(), true)?;
+        println!("Encrypted {}; pass --encrypted from now on", path.display());
+        store
+    } else {
+        load_account_store(global)?
+    };
+    let pending = store.pending_migrations();
+
+    if pending.is_empty() {
+        println!(
+            "Account store is up to date (schema version {})",
+            store.schema_version()
+        );
+        return Ok(());
+    }
+
+    println!("Pending migrations for {}:", store.path().display());
+    for migration in &pending {
+        println!(
+            "  v{} -> v{}: {}",
+            migration.from_version, migration.to_version, migration.description
+        );
+    }
+
+    if dry_run {
+        println!("Dry run: no changes made");
+        return Ok(());
+    }
+
+    match store.run_migrations() {
+        Ok(version) => {
+            println!(
+                "Applied {} migration(s) to {} account(s), now at schema version {}",
+                pending.len(),
+                store.list_accounts(None)?.len(),
+                version
+            );
+            println!("Backup saved to {}", store.backup_path().display());
+            Ok(())
+        }
+        // A failing step is restored from the backup; other errors may come
+        // before the backup was taken
+        Err(e @ AccountStoreError::MigrationFailed { .. }) => {
+            anyhow::bail!(
+                "Migration failed, restored {} from backup: {}",
+                store.path().display(),
+                e
+            )
+        }
+        Err(e) => anyhow::bail!("Migration failed: {}", e),
+    }
+}
+
+fn export_accounts(
+    format: ExportFormat,
+    output: Option<&Path>,
+    global: GlobalOptions<'_>,
+) -> Result<()> {
+    let store = load_account_store(global)?;
+    let count = store.list_accounts(None)?.len();
+
+    match (format, output) {
+        (ExportFormat::Json, Some(path)) => {
+            store.create_backup(path)?;
+            eprintln!("Exported {} account(s) to {}", count, path.display());
+        }
+        (ExportFormat::Json, None) => println!("{}", store.export_json()?),
+    }
+    Ok(())
+}
+
+fn restore_accounts(args: RestoreArgs, global: GlobalOptions<'_>) -> Result<()> {
+    let (Some(RestoreFormat::Sigilforge), Some(file)) = (args.format, args.file) else {
+        anyhow::bail!("import requires --format=sigilforge and a FILE, or a source subcommand");
+    };
+
+    let json = std::fs::read_to_string(&file)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file.display(), e))?;
+    let mode = if args.replace { ImportMode::Replace } else { ImportMode::Merge };
+
+    let result = load_account_store(global)?.import_json(&json, mode)?;
+    if result.removed > 0 {
+        println!("Removed {} existing account(s)", result.removed);
+    }
+    println!(
+        "Imported {} account(s), skipped {} already present",
+        result.imported, result.skipped
+    );
+    Ok(())
+}
+
+fn snapshot(command: SnapshotCommand, global: GlobalOptions<'_>) -> Result<()> {
+    let store = load_account_store(global)?;
+    let dir = store.snapshot_dir();
+
+    match command {
+        SnapshotCommand::Create { label } => {
+            let snapshot = store.snapshot()?;
+            let saved = store.save_snapshot(&snapshot, &dir, label.as_deref().unwrap_or(""))?;
+            println!(
+                "Saved snapshot {} ({} account(s))",
+                saved.id, saved.account_count
+            );
+        }
+        SnapshotCommand::List => {
+            let snapshots = store.list_snapshots(&dir)?;
+            if snapshots.is_empty() {
+                println!("No snapshots in {}", dir.display());
+                return Ok(());
+            }
+            println!("{:<40} {:<25} ACCOUNTS", "ID", "CREATED");
+            for snapshot in snapshots {
+                println!(
+                    "{:<40} {:<25} {}",
+                    snapshot.id,
+                    snapshot.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                    snapshot.account_count
+                );
+            }
+        }
+        SnapshotCommand::Restore { id } => {
+            let snapshot = store.load_snapshot(&dir, &id)?;
+            let count = snapshot.accounts.len();
+            store.restore(snapshot)?;
+            println!("Restored {} account(s) from snapshot {}", count, id);
+        }
+    }
+    Ok(())
+}
+
+async fn import_credentials(source: ImportSource, global: GlobalOptions<'_>) -> Result<()> {
+    let (importer, dry_run): (Box<dyn CredentialImporter>, bool) = match source {
+        ImportSource::Netrc { file, service_map, dry_run } => {
+            let path = match file {
+                Some(path) => path,
+                None => import::netrc::NetrcImporter::default_path()?,
+            };
+            let importer = import::netrc::NetrcImporter::new(path).with_service_map(service_map);
+            (Box::new(importer), dry_run)
+        }
+        ImportSource::GithubCli { file, service_map, dry_run } => {
+            let path = match file {
+                Some(path) => path,
+                None => import::github_cli::GithubCliImporter::default_path()?,
+            };
+            let importer =
+                import::github_cli::GithubCliImporter::new(path).with_service_map(service_map);
+            (Box::new(importer), dry_run)
+        }
+        ImportSource::Aws { file, profile, dry_run } => {
+            let path = match file {
+                Some(path) => path,
+                None => import::aws_cli::AwsCliImporter::default_path()?,
+            };
+            let importer = import::aws_cli::AwsCliImporter::new(path).with_profile(profile);
+            (Box::new(importer), dry_run)
+        }
+        ImportSource::AzureCli { subscription, dry_run } => {
+            let importer =
+                import::azure_cli::AzureCliImporter::new().with_subscription(subscription);
+            (Box::new(importer), dry_run)
+        }
+    };
+
+    let report = importer.import()?;
+    for warning in &report.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+
+    if report.credentials.is_empty() {
+        println!("No credentials to import from {}", importer.source());
+        return Ok(());
+    }
+
+    println!("Found {} credential(s) in {}:", report.credentials.len(), importer.source());
+    for credential in &report.credentials {
+        let scopes = if credential.scopes.is_empty() {
+            String::new()
+        } else {
+            format!(", scopes: {}", credential.scopes.join(" "))
+        };
+        println!(
+            "  {}/{} ({}{})",
+            credential.service,
+            credential.account,
+            credential.credential_type.as_str(),
+            scopes
+        );
+    }
+
+    if dry_run {
+        println!("Dry run: no changes made");
+        return Ok(());
+    }
+
+    let store = KeyringStore::try_new("sigilforge")
+        .map_err(|e| anyhow::anyhow!("Keyring unavailable ({}); cannot store credentials", e))?;
+    let accounts = load_account_store(global)?;
+    let namespace = store_namespace(global.config_dir)?;
+    let added = import::store_credentials(
+        &store,
+        &accounts,
+        &report.credentials,
+        importer.format(),
+        &namespace,
+    )
+    .await?;
+
+    println!(
+        "Imported {} credential(s), {} new account(s)",
+        report.credentials.len(),
+        added
+    );
+    Ok(())
+}
+
+fn generate_completion(shell: Shell, install: bool, stdout: bool) -> Result<()> {
+    use std::io::Write;
+
+    let mut cmd = Cli::command();
+
+    if install {
+        let target = completion::install(&mut cmd, shell)?;
+        // Keep stdout clean for the script when both are requested
+        if stdout {
+            eprintln!("Installed {} completions to {}", shell, target.path.display());
+            eprintln!("{}", target.instructions);
+        } else {
+            println!("Installed {} completions to {}", shell, target.path.display());
+            println!("{}", target.instructions);
+            return Ok(());
+        }
+    }
+
+    std::io::stdout().write_all(&completion::generate(&mut cmd, shell))?;
+    Ok(())
+}
+
 async fn run_daemon_foreground() -> Result<()> {
     println!("[stub] Running daemon in foreground...");
     println!("Press Ctrl+C to stop");
@@ -0,0 +1,229 @@
+//! Machine-readable command output.
+//!
+//! JSON fields are written in struct order, so the output is stable for
+//! scripts and `jq`; add new fields at the end rather than reordering.
+//! Optional values that are absent are written as `null`, not omitted,
+//! unless a flag controls whether the field appears at all.
+
+use serde::{Deserialize, Serialize};
+use sigilforge_core::{Account, AccountId, ServiceId, TokenError, TokenManager};
+
+use crate::client;
+
+/// One account in `sigilforge list-accounts --format=json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountInfo {
+    pub service: String,
+    pub account: String,
+    pub scopes: Vec<String>,
+    /// When the account was added (ISO 8601)
+    pub created_at: String,
+    /// When a token was last handed out (ISO 8601), or `null`
+    pub last_used: Option<String>,
+    /// Status of the stored token; only included with `--verbose`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_status: Option<TokenStatus>,
+}
+
+/// Whether an account has a usable token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenStatus {
+    Valid,
+    Expired,
+    /// No token is stored for the account
+    Missing,
+    /// The token could not be read, e.g. because the keyring is locked
+    Unknown,
+}
+
+impl TokenStatus {
+    /// Introspect the account's token through `manager`.
+    pub async fn check(manager: &impl TokenManager, service: &str, account: &str) -> Self {
+        let (service, account) = (ServiceId::new(service), AccountId::new(account));
+        match manager.introspect_token(&service, &account).await {
+            Ok(info) if info.active => Self::Valid,
+            Ok(_) => Self::Expired,
+            Err(TokenError::NotFound { .. }) => Self::Missing,
+            Err(_) => Self::Unknown,
+        }
+    }
+}
+
+impl From<&client::AccountStatusInfo> for TokenStatus {
+    fn from(status: &client::AccountStatusInfo) -> Self {
+        if status.error.is_some() {
+            Self::Unknown
+        } else if status.token_valid {
+            Self::Valid
+        } else if status.expires_at.is_some() {
+            Self::Expired
+        } else {
+            Self::Missing
+        }
+    }
+}
+
+impl From<&Account> for AccountInfo {
+    fn from(account: &Account) -> Self {
+        Self {
+            service: account.service.to_string(),
+            account: account.id.to_string(),
+            scopes: account.scopes.clone(),
+            created_at: account.created_at.to_rfc3339(),
+            last_used: account.last_used.map(|dt| dt.to_rfc3339()),
+            token_status: None,
+        }
+    }
+}
+
+impl From<client::AccountInfo> for AccountInfo {
+    fn from(account: client::AccountInfo) -> Self {
+        Self {
+            service: account.service,
+            account: account.account,
+            scopes: account.scopes,
+            created_at: account.created_at,
+            last_used: account.last_used,
+            token_status: None,
+        }
+    }
+}
+
+/// `token` as printed by `get-token --format=<format>` for non-JSON formats.
+///
+/// Unknown formats print the bare token, like `text`.
+pub fn format_token(format: &str, token: &str) -> String {
+    match format {
+        "bearer" => format!("Bearer {}", token),
+        "curl" => format!("-H 'Authorization: Bearer {}'", token),
+        "httpie" => format!("Authorization:Bearer {}", token),
+        "env" => format!("export AUTHORIZATION_HEADER=\"Bearer {}\"", token),
+        _ => token.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use sigilforge_core::{DefaultTokenManager, MemoryStore, ProviderRegistry, Token, TokenSet};
+
+    fn account(service: &str, account: &str, scopes: &[&str]) -> Account {
+        let mut account = Account::new(
+            ServiceId::new(service),
+            AccountId::new(account),
+            scopes.iter().map(|s| s.to_string()).collect(),
+        );
+        account.created_at = chrono::Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap();
+        account
+    }
+
+    #[test]
+    fn test_account_json() {
+        let mut used = account("github", "work", &["repo", "read:org"]);
+        used.last_used = Some(chrono::Utc.with_ymd_and_hms(2024, 3, 2, 17, 5, 42).unwrap());
+        let accounts: Vec<AccountInfo> = [used, account("spotify", "personal", &[])]
+            .iter()
+            .map(AccountInfo::from)
+            .collect();
+
+        insta::assert_snapshot!(serde_json::to_string_pretty(&accounts).unwrap(), @r#"
+        [
+          {
+            "service": "github",
+            "account": "work",
+            "scopes": [
+              "repo",
+              "read:org"
+            ],
+            "created_at": "2024-03-01T09:30:00+00:00",
+            "last_used": "2024-03-02T17:05:42+00:00"
+          },
+          {
+            "service": "spotify",
+            "account": "personal",
+            "scopes": [],
+            "created_at": "2024-03-01T09:30:00+00:00",
+            "last_used": null
+          }
+        ]
+        "#);
+    }
+
+    #[test]
+    fn test_verbose_account_json() {
+        let mut info = AccountInfo::from(client::AccountInfo {
+            service: "google".to_string(),
+            account: "personal".to_string(),
+            scopes: vec!["openid".to_string()],
+            created_at: "2024-03-01T09:30:00+00:00".to_string(),
+            last_used: None,
+            source: Default::default(),
+        });
+        info.token_status = Some(TokenStatus::Expired);
+
+        let json = serde_json::to_string_pretty(&info).unwrap();
+        insta::assert_snapshot!(json, @r#"
+        {
+          "service": "google",
+          "account": "personal",
+          "scopes": [
+            "openid"
+          ],
+          "created_at": "2024-03-01T09:30:00+00:00",
+          "last_used": null,
+          "token_status": "expired"
+        }
+        "#);
+        assert_eq!(serde_json::from_str::<AccountInfo>(&json).unwrap(), info);
+    }
+
+    #[test]
+    fn test_token_status_from_daemon() {
+        let status = |token_valid, expires_at: Option<&str>, error: Option<&str>| {
+            TokenStatus::from(&client::AccountStatusInfo {
+                service: "github".to_string(),
+                account: "work".to_string(),
+                token_valid,
+                refreshable: false,
+                expires_soon: false,
+                expires_at: expires_at.map(String::from),
+                error: error.map(String::from),
+            })
+        };
+        let expires_at = Some("2024-03-01T09:30:00+00:00");
+
+        assert_eq!(status(true, None, None), TokenStatus::Valid);
+        assert_eq!(status(true, expires_at, None), TokenStatus::Valid);
+        assert_eq!(status(false, expires_at, None), TokenStatus::Expired);
+        assert_eq!(status(false, None, None), TokenStatus::Missing);
+        assert_eq!(status(false, None, Some("locked")), TokenStatus::Unknown);
+    }
+
+    #[tokio::test]
+    async fn test_token_status() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        for (account, hours) in [("fresh", 1), ("stale", -1)] {
+            let expires_at = chrono::Utc::now() + chrono::Duration::hours(hours);
+            let tokens = TokenSet::new(Token::new("token").with_expiry(expires_at));
+            manager
+                .store_token_set(&ServiceId::new("github"), &AccountId::new(account), tokens)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            TokenStatus::check(&manager, "github", "fresh").await,
+            TokenStatus::Valid
+        );
+        assert_eq!(
+            TokenStatus::check(&manager, "github", "stale").await,
+            TokenStatus::Expired
+        );
+        assert_eq!(
+            TokenStatus::check(&manager, "github", "none").await,
+            TokenStatus::Missing
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Authorizations started by `add-account --no-browser` without a terminal.
+//!
+//! With no one to press Enter, the first invocation prints the URL, saves
+//! the flow's state here, and exits. A later `add-account --auth-code`
+//! invocation loads that state to exchange the code. Each file holds a PKCE
+//! verifier, so it is readable only by its owner and removed once used.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sigilforge_core::{oauth::pkce::PendingAuthorization, provider::ProviderConfig};
+
+/// Everything needed to finish adding an account in a later invocation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingAccount {
+    /// Provider the authorization URL points at
+    pub provider: ProviderConfig,
+    /// Scopes requested in the URL
+    pub scopes: Vec<String>,
+    pub authorization: PendingAuthorization,
+    /// Box enterprise ID to store once the code has been exchanged
+    #[serde(default)]
+    pub box_enterprise_id: Option<String>,
+}
+
+/// Where the pending authorization for `service`/`account` is kept: under
+/// `config_dir` if given, otherwise in the platform data directory.
+pub fn pending_path(config_dir: Option<&Path>, service: &str, account: &str) -> Result<PathBuf> {
+    let dir = match config_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => directories::ProjectDirs::from("com", "raibid-labs", "sigilforge")
+            .ok_or_else(|| anyhow::anyhow!("Could not determine the data directory"))?
+            .data_dir()
+            .to_path_buf(),
+    };
+    Ok(dir
+        .join("pending")
+        .join(service)
+        .join(format!("{}.json", account)))
+}
+
+/// Write `pending` to `path` (owner read/write only), replacing any earlier
+/// authorization for the same account.
+pub fn save(path: &Path, pending: &PendingAccount) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).write(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options
+        .open(path)
+        .with_context(|| format!("Failed to open {:?}", path))?;
+    serde_json::to_writer(file, pending)
+        .with_context(|| format!("Failed to write pending authorization {:?}", path))
+}
+
+/// Read the pending authorization at `path`.
+pub fn load(path: &Path) -> Result<PendingAccount> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read pending authorization {:?}", path))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse pending authorization {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sigilforge_core::oauth::pkce::{PkceFlow, RedirectConfig};
+    use sigilforge_core::provider::ProviderRegistry;
+
+    fn pending_account() -> PendingAccount {
+        let provider = ProviderRegistry::with_defaults()
+            .get("github")
+            .unwrap()
+            .clone();
+        let flow = PkceFlow::new(
+            provider.clone(),
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap();
+        let (_url, authorization) = flow
+            .detached_authorization_url(vec!["repo".into()])
+            .unwrap();
+
+        PendingAccount {
+            provider,
+            scopes: vec!["repo".to_string()],
+            authorization,
+            box_enterprise_id: None,
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("pending/github/work.json");
+        let pending = pending_account();
+
+        save(&path, &pending).unwrap();
+        let loaded = load(&path).unwrap();
+        assert_eq!(loaded.provider.id, "github");
+        assert_eq!(loaded.scopes, ["repo"]);
+        assert_eq!(loaded.authorization, pending.authorization);
+
+        assert_eq!(loaded.box_enterprise_id, None);
+
+        // Saving again replaces the earlier authorization
+        let mut newer = pending_account();
+        newer.box_enterprise_id = Some("123456".to_string());
+        save(&path, &newer).unwrap();
+        let loaded = load(&path).unwrap();
+        assert_eq!(loaded.authorization, newer.authorization);
+        assert_eq!(loaded.box_enterprise_id.as_deref(), Some("123456"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("work.json");
+        save(&path, &pending_account()).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
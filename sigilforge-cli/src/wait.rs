@@ -0,0 +1,102 @@
+//! Waiting for the daemon in `sigilforge get-token --wait-for-daemon`.
+//!
+//! Service startup scripts may run before the daemon has opened its socket.
+//! Polling its health check lets them block until it is up instead of
+//! failing or falling back to the keyring.
+
+use std::future::Future;
+use std::path::Path;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::client::DaemonClient;
+
+/// How often the daemon's health check is polled.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Call `check` now and again every `interval` until it returns `true` or
+/// `timeout` has elapsed.
+///
+/// Returns whether `check` succeeded. The last check runs at the deadline.
+pub async fn wait_until<F, Fut>(interval: Duration, timeout: Duration, mut check: F) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        if check().await {
+            return true;
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        tokio::time::sleep(interval.min(deadline - now)).await;
+    }
+}
+
+/// Whether the daemon is listening and answers a health check.
+pub async fn daemon_ready(config_dir: Option<&Path>) -> bool {
+    match DaemonClient::connect_default(config_dir).await {
+        Ok(mut client) if client.is_connected() => client.health_check().await.is_ok(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// A check that fails `failures` times, then succeeds.
+    fn ready_after(
+        failures: usize,
+        calls: &Cell<usize>,
+    ) -> impl FnMut() -> std::future::Ready<bool> + '_ {
+        move || {
+            calls.set(calls.get() + 1);
+            std::future::ready(calls.get() > failures)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_until_ready_after_two_polls() {
+        let calls = Cell::new(0);
+        let started = Instant::now();
+
+        let ready = wait_until(POLL_INTERVAL, TIMEOUT, ready_after(2, &calls)).await;
+
+        assert!(ready);
+        assert_eq!(calls.get(), 3);
+        assert_eq!(started.elapsed(), POLL_INTERVAL * 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_until_already_ready() {
+        let calls = Cell::new(0);
+        let started = Instant::now();
+
+        assert!(wait_until(POLL_INTERVAL, TIMEOUT, ready_after(0, &calls)).await);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(started.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_until_times_out() {
+        let calls = Cell::new(0);
+        let started = Instant::now();
+        let timeout = Duration::from_millis(1200);
+
+        let ready = wait_until(POLL_INTERVAL, timeout, ready_after(usize::MAX, &calls)).await;
+
+        assert!(!ready);
+        // At 0, 500 and 1000 ms, then at the deadline
+        assert_eq!(calls.get(), 4);
+        assert_eq!(started.elapsed(), timeout);
+    }
+}
@@ -0,0 +1,198 @@
+//! Polling mode for `sigilforge get-token --watch`.
+//!
+//! CI jobs that outlive a single access token keep the command running and
+//! read a new line from stdout whenever the token is refreshed.
+
+use std::future::Future;
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::client::GetTokenResponse;
+use crate::output::format_token;
+
+/// How each token is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchFormat<'a> {
+    /// The token as `get-token --format=<format>` prints it, one per line.
+    Text(&'a str),
+    /// One JSON object per line with token hashes instead of the token.
+    Json,
+}
+
+/// Fetch a token now and again every `interval` until `shutdown` completes.
+///
+/// The first token is always written; later ones only when they differ from
+/// the previous one. A failed first fetch is returned as an error, later
+/// failures are logged and retried on the next tick.
+pub async fn watch<F, Fut, W>(
+    interval: Duration,
+    format: WatchFormat<'_>,
+    mut fetch: F,
+    out: &mut W,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<GetTokenResponse>>,
+    W: Write,
+{
+    let mut current = fetch().await?;
+    write_token(out, format, None, &current)?;
+
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => return Ok(()),
+            _ = tokio::time::sleep(interval) => {}
+        }
+
+        let next = tokio::select! {
+            _ = &mut shutdown => return Ok(()),
+            result = fetch() => result,
+        };
+
+        match next {
+            Ok(next) if next.token != current.token => {
+                write_token(out, format, Some(&current), &next)?;
+                current = next;
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Token check failed: {}", e),
+        }
+    }
+}
+
+fn write_token<W: Write>(
+    out: &mut W,
+    format: WatchFormat<'_>,
+    old: Option<&GetTokenResponse>,
+    new: &GetTokenResponse,
+) -> Result<()> {
+    match format {
+        WatchFormat::Text(format) => writeln!(out, "{}", format_token(format, &new.token))?,
+        WatchFormat::Json => {
+            let line = serde_json::json!({
+                "old_token_hash": old.map(|old| token_hash(&old.token)),
+                "new_token_hash": token_hash(&new.token),
+                "refreshed_at": chrono::Utc::now().to_rfc3339(),
+                "expires_at": new.expires_at,
+            });
+            writeln!(out, "{}", line)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Hex-encoded SHA-256 of a token, safe to put in CI logs.
+fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use tokio::sync::oneshot;
+
+    const INTERVAL: Duration = Duration::from_secs(300);
+
+    fn response(token: &str) -> GetTokenResponse {
+        GetTokenResponse {
+            token: token.to_string(),
+            expires_at: Some("2030-01-01T00:00:00+00:00".to_string()),
+            scopes: Vec::new(),
+        }
+    }
+
+    /// Run `watch` over `tokens` (one per fetch, the last repeating) for
+    /// `ticks` intervals and return its output and the number of fetches.
+    async fn run_watch(format: WatchFormat<'_>, tokens: &[&str], ticks: u32) -> (String, usize) {
+        tokio::time::pause();
+
+        let calls = Cell::new(0);
+        let fetch = || {
+            let index = calls.get();
+            calls.set(index + 1);
+            let token = tokens[index.min(tokens.len() - 1)];
+            let result = match token {
+                "error" => Err(anyhow::anyhow!("daemon unavailable")),
+                token => Ok(response(token)),
+            };
+            std::future::ready(result)
+        };
+
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let mut out = Vec::new();
+        let watcher = watch(INTERVAL, format, fetch, &mut out, async {
+            let _ = stop_rx.await;
+        });
+        let driver = async {
+            for _ in 0..ticks {
+                tokio::time::advance(INTERVAL).await;
+                tokio::task::yield_now().await;
+            }
+            stop_tx.send(()).unwrap();
+        };
+
+        let (result, ()) = tokio::join!(watcher, driver);
+        result.unwrap();
+        (String::from_utf8(out).unwrap(), calls.get())
+    }
+
+    #[tokio::test]
+    async fn test_watch_prints_only_changed_tokens() {
+        let (output, calls) =
+            run_watch(WatchFormat::Text("text"), &["a", "a", "error", "b"], 3).await;
+
+        assert_eq!(calls, 4);
+        assert_eq!(output, "a\nb\n");
+    }
+
+    #[tokio::test]
+    async fn test_watch_uses_header_format() {
+        let (output, _) = run_watch(WatchFormat::Text("bearer"), &["a", "b"], 1).await;
+
+        assert_eq!(output, "Bearer a\nBearer b\n");
+    }
+
+    #[tokio::test]
+    async fn test_watch_json_reports_hashes() {
+        let (output, _) = run_watch(WatchFormat::Json, &["a", "b"], 1).await;
+
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        assert!(lines[0]["old_token_hash"].is_null());
+        assert_eq!(lines[1]["old_token_hash"], token_hash("a"));
+        assert_eq!(lines[1]["new_token_hash"], token_hash("b"));
+        assert_eq!(lines[1]["expires_at"], "2030-01-01T00:00:00+00:00");
+        assert!(lines[1]["refreshed_at"].is_string());
+        assert!(!output.contains("\"b\""), "raw token leaked: {}", output);
+    }
+
+    #[tokio::test]
+    async fn test_watch_fails_without_initial_token() {
+        tokio::time::pause();
+        let fetch = || std::future::ready(Err(anyhow::anyhow!("no token")));
+        let shutdown = async {};
+
+        let result = watch(
+            INTERVAL,
+            WatchFormat::Text("text"),
+            fetch,
+            &mut Vec::new(),
+            shutdown,
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! Tests for `sigilforge add-account --from-file`
+//!
+//! Only invalid files are run through the binary, since a valid one would
+//! be written to the keyring; parsing valid files is covered by the unit
+//! tests of the importer.
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use tempfile::TempDir;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn add_account(config_dir: &Path, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .arg("--config-dir")
+        .arg(config_dir)
+        .arg("add-account")
+        .args(args)
+        .env("HOME", config_dir)
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+fn from_file(path: &Path) -> String {
+    format!("--from-file={}", path.display())
+}
+
+#[test]
+fn test_missing_access_token_is_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = fixture("account-missing-token.json");
+
+    let output = add_account(temp_dir.path(), &[&from_file(&path)]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid credentials file"), "{}", stderr);
+    assert!(stderr.contains("missing field"), "{}", stderr);
+    assert!(stderr.contains("`access_token`"), "{}", stderr);
+    assert!(!temp_dir.path().join("accounts.json").exists());
+}
+
+#[test]
+fn test_malformed_json_is_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("account.json");
+    std::fs::write(&path, r#"{"service": "github", "account": "work","#).unwrap();
+
+    let output = add_account(temp_dir.path(), &[&from_file(&path)]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid credentials file"), "{}", stderr);
+    assert!(!temp_dir.path().join("accounts.json").exists());
+}
+
+#[test]
+fn test_missing_file_is_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("missing.json");
+
+    let output = add_account(temp_dir.path(), &[&from_file(&path)]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to read"), "{}", stderr);
+}
+
+#[test]
+fn test_from_file_conflicts_with_service_and_flow_options() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = from_file(&fixture("account-full.json"));
+
+    for args in [
+        vec!["github", "work", path.as_str()],
+        vec![path.as_str(), "--device-code"],
+        vec![path.as_str(), "--scopes=repo"],
+    ] {
+        let output = add_account(temp_dir.path(), &args);
+        assert!(!output.status.success(), "{:?}", args);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("cannot be used with"), "{}", stderr);
+    }
+}
+
+#[test]
+fn test_service_and_account_required_without_from_file() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = add_account(temp_dir.path(), &[]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("<SERVICE>"), "{}", stderr);
+}
@@ -0,0 +1,134 @@
+//! Tests for `sigilforge audit`
+//!
+//! HOME points at a temporary directory holding a `daemon.toml` whose
+//! `audit_log_path` names the fixture log. The fixture spans several
+//! services and days and contains one truncated line.
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use tempfile::TempDir;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Where the daemon looks for its config under `home`.
+fn config_dir(home: &Path) -> PathBuf {
+    if cfg!(target_os = "macos") {
+        home.join("Library/Application Support/com.raibid-labs.sigilforge")
+    } else {
+        home.join(".config/sigilforge")
+    }
+}
+
+/// A HOME whose daemon config points at the fixture audit log.
+fn configured_home() -> TempDir {
+    let home = TempDir::new().unwrap();
+    let dir = config_dir(home.path());
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("daemon.toml"),
+        format!("audit_log_path = {:?}\n", fixture("audit.log")),
+    )
+    .unwrap();
+    home
+}
+
+fn run(home: &TempDir, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .arg("audit")
+        .args(args)
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+/// Request IDs (last digit only) of the entries printed as JSON.
+fn json_ids(home: &TempDir, args: &[&str]) -> Vec<String> {
+    let mut args = args.to_vec();
+    args.push("--format=json");
+    let output = run(home, &args);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    entries
+        .iter()
+        .map(|entry| {
+            let id = entry["request_id"].as_str().unwrap();
+            id[id.len() - 1..].to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn test_all_entries_skip_invalid_lines() {
+    let home = configured_home();
+    assert_eq!(json_ids(&home, &[]), ["1", "2", "3", "4", "5", "6", "8"]);
+}
+
+#[test]
+fn test_filter_by_service_and_account() {
+    let home = configured_home();
+    assert_eq!(json_ids(&home, &["--service=github"]), ["2", "4", "5", "8"]);
+    assert_eq!(json_ids(&home, &["--service=github", "--account=work"]), ["2", "8"]);
+    assert_eq!(json_ids(&home, &["--account=work"]), ["2", "6", "8"]);
+    assert!(json_ids(&home, &["--service=dropbox"]).is_empty());
+}
+
+#[test]
+fn test_filter_by_method_since_and_tail() {
+    let home = configured_home();
+    assert_eq!(json_ids(&home, &["--method=get_token"]), ["2", "3", "5", "8"]);
+    assert_eq!(json_ids(&home, &["--since=2025-01-31"]), ["4", "5", "6", "8"]);
+    assert_eq!(
+        json_ids(&home, &["--since=2025-01-31T10:30:00Z", "--service=github"]),
+        ["5", "8"]
+    );
+    assert_eq!(json_ids(&home, &["--tail=2"]), ["6", "8"]);
+    assert_eq!(json_ids(&home, &["--method=get_token", "--tail=3"]), ["3", "5", "8"]);
+}
+
+#[test]
+fn test_text_output_is_a_table() {
+    let home = configured_home();
+    let output = run(&home, &["--service=spotify"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("TIME"));
+    assert!(lines[1].starts_with("2025-01-30 09:00:00"));
+    assert!(lines[1].contains("get_token"));
+    assert!(lines[1].contains("personal"));
+    assert!(lines[1].contains("error"));
+    assert!(lines[1].ends_with("230ms"));
+}
+
+#[test]
+fn test_invalid_since_is_rejected() {
+    let home = configured_home();
+    let output = run(&home, &["--since=yesterday"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid datetime"));
+}
+
+#[test]
+fn test_exits_1_without_audit_log() {
+    let home = TempDir::new().unwrap();
+    let output = run(&home, &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No audit log configured"));
+
+    // A daemon config without audit_log_path is treated the same
+    let dir = config_dir(home.path());
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("daemon.toml"), "log_level = \"debug\"\n").unwrap();
+    let output = run(&home, &[]);
+    assert_eq!(output.status.code(), Some(1));
+}
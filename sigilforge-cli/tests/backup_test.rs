@@ -0,0 +1,110 @@
+//! Tests for `sigilforge export` and `sigilforge import --format=sigilforge`
+//!
+//! HOME points at a temporary directory so the real account store is never
+//! touched.
+
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn run(home: &TempDir, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(args)
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+fn accounts_json(accounts: &[(&str, &str)]) -> String {
+    let accounts: Vec<serde_json::Value> = accounts
+        .iter()
+        .map(|(service, account)| {
+            serde_json::json!({
+                "service": service,
+                "id": account,
+                "scopes": ["read"],
+                "created_at": "2025-01-01T00:00:00Z",
+                "last_used": null,
+            })
+        })
+        .collect();
+    serde_json::json!({ "version": 1, "accounts": accounts }).to_string()
+}
+
+fn write(path: &Path, contents: &str) {
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn test_export_then_restore() {
+    let source_home = TempDir::new().unwrap();
+    let backup = source_home.path().join("backup.json");
+    write(&backup, &accounts_json(&[("spotify", "personal"), ("github", "work")]));
+
+    let output = run(&source_home, &["import", "--format=sigilforge", backup.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("Imported 2 account(s)"));
+
+    // Export to stdout and to a file
+    let output = run(&source_home, &["export", "--format=json"]);
+    assert!(output.status.success());
+    let exported: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(exported["accounts"].as_array().unwrap().len(), 2);
+
+    let export_file = source_home.path().join("export.json");
+    let output = run(&source_home, &["export", "--output", export_file.to_str().unwrap()]);
+    assert!(output.status.success());
+
+    // Restoring the export elsewhere reproduces the accounts
+    let target_home = TempDir::new().unwrap();
+    let output = run(
+        &target_home,
+        &["import", "--format=sigilforge", export_file.to_str().unwrap()],
+    );
+    assert!(output.status.success());
+
+    let output = run(&target_home, &["export"]);
+    let restored: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(restored, exported);
+}
+
+#[test]
+fn test_restore_merge_and_replace() {
+    let home = TempDir::new().unwrap();
+    let first = home.path().join("first.json");
+    let second = home.path().join("second.json");
+    write(&first, &accounts_json(&[("spotify", "personal")]));
+    write(&second, &accounts_json(&[("spotify", "personal"), ("github", "work")]));
+
+    run(&home, &["import", "--format=sigilforge", first.to_str().unwrap()]);
+
+    let output = run(&home, &["import", "--format=sigilforge", second.to_str().unwrap()]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Imported 1 account(s), skipped 1"), "{}", stdout);
+
+    let output = run(
+        &home,
+        &["import", "--format=sigilforge", "--replace", first.to_str().unwrap()],
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Removed 2 existing account(s)"), "{}", stdout);
+}
+
+#[test]
+fn test_restore_refuses_newer_schema() {
+    let home = TempDir::new().unwrap();
+    let backup = home.path().join("future.json");
+    write(&backup, r#"{ "version": 999, "accounts": [] }"#);
+
+    let output = run(&home, &["import", "--format=sigilforge", backup.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("newer than supported"));
+}
+
+#[test]
+fn test_import_requires_format_or_source() {
+    let home = TempDir::new().unwrap();
+    let output = run(&home, &["import"]);
+    assert!(!output.status.success());
+}
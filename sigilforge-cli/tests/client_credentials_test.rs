@@ -0,0 +1,101 @@
+//! Tests for `sigilforge add-account --client-id` and related flags
+//!
+//! Runs with `--no-browser` and no terminal, so the client ID in use can be
+//! read from the printed authorization URL.
+
+#![cfg(unix)]
+
+use std::path::PathBuf;
+use std::process::{Output, Stdio};
+use tempfile::TempDir;
+
+fn add_account(home: &TempDir, env_client_id: Option<&str>, args: &[&str]) -> Output {
+    let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"));
+    command
+        .args(["add-account", "google", "work", "--no-browser"])
+        .args(args)
+        .env("HOME", home.path())
+        .env_remove("GOOGLE_CLIENT_ID")
+        .env_remove("OAUTH_CLIENT_ID")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("OAUTH_CALLBACK_PORT")
+        .stdin(Stdio::piped());
+    if let Some(client_id) = env_client_id {
+        command.env("GOOGLE_CLIENT_ID", client_id);
+    }
+    command.output().expect("failed to run sigilforge binary")
+}
+
+/// The `client_id` parameter of the authorization URL on stdout.
+fn requested_client_id(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (_, query) = stdout.trim().split_once('?').expect("no authorization URL");
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("client_id="))
+        .expect("authorization URL has no client_id")
+        .to_string()
+}
+
+fn write_file(home: &TempDir, name: &str, contents: &str) -> PathBuf {
+    let path = home.path().join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_flag_overrides_environment() {
+    let home = TempDir::new().unwrap();
+    let output = add_account(&home, Some("env-client"), &["--client-id=flag-client"]);
+    assert_eq!(requested_client_id(&output), "flag-client");
+
+    let home = TempDir::new().unwrap();
+    let output = add_account(&home, Some("env-client"), &[]);
+    assert_eq!(requested_client_id(&output), "env-client");
+}
+
+#[test]
+fn test_reads_client_id_from_file() {
+    let home = TempDir::new().unwrap();
+    // Docker secrets usually end with a newline
+    let path = write_file(&home, "client_id", "file-client\n");
+
+    let output = add_account(
+        &home,
+        Some("env-client"),
+        &[&format!("--client-id-file={}", path.display())],
+    );
+    assert_eq!(requested_client_id(&output), "file-client");
+}
+
+#[test]
+fn test_missing_client_id_is_an_error() {
+    let home = TempDir::new().unwrap();
+    let output = add_account(&home, None, &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Missing OAuth client ID"), "{}", stderr);
+
+    let path = write_file(&home, "empty", "\n");
+    let output = add_account(
+        &home,
+        Some("env-client"),
+        &[&format!("--client-id-file={}", path.display())],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("does not contain a client ID"),
+        "{}",
+        stderr
+    );
+
+    let output = add_account(&home, None, &["--client-id=a", "--client-id-file=b"]);
+    assert!(!output.status.success());
+}
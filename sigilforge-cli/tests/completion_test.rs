@@ -0,0 +1,84 @@
+//! Snapshot-style tests for the completion command
+//!
+//! These tests run the `sigilforge` binary and check that the generated
+//! scripts define the expected completion functions and cover the CLI's
+//! subcommands and flags.
+
+use std::process::Output;
+use tempfile::TempDir;
+
+/// Run `sigilforge completion ...` with HOME pointed at `home`.
+fn run_completion(home: &TempDir, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .arg("completion")
+        .args(args)
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+fn script_for(shell: &str) -> String {
+    let home = TempDir::new().unwrap();
+    let output = run_completion(&home, &[shell]);
+    assert!(output.status.success(), "completion {} failed", shell);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_bash_completion_script() {
+    let script = script_for("bash");
+
+    assert!(script.contains("_sigilforge()"));
+    assert!(script.contains("complete -F _sigilforge"));
+    for word in ["add-account", "list-accounts", "get-token", "--service", "--format"] {
+        assert!(script.contains(word), "bash script missing {}", word);
+    }
+}
+
+#[test]
+fn test_zsh_completion_script() {
+    let script = script_for("zsh");
+
+    assert!(script.starts_with("#compdef sigilforge"));
+    assert!(script.contains("_sigilforge()"));
+    assert!(script.contains("_sigilforge_commands"));
+}
+
+#[test]
+fn test_fish_completion_script() {
+    let script = script_for("fish");
+
+    assert!(script.contains("complete -c sigilforge"));
+    assert!(script.contains("__fish_sigilforge_using_subcommand"));
+    assert!(script.contains("-l format"));
+}
+
+#[test]
+fn test_install_writes_script() {
+    let home = TempDir::new().unwrap();
+    let output = run_completion(&home, &["bash", "--install"]);
+    assert!(output.status.success());
+
+    let path = home.path().join(".bash_completion.d").join("sigilforge");
+    let installed = std::fs::read_to_string(&path).unwrap();
+    assert!(installed.contains("_sigilforge()"));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&path.display().to_string()));
+    assert!(stdout.contains("source"));
+}
+
+#[test]
+fn test_install_with_stdout_prints_script() {
+    let home = TempDir::new().unwrap();
+    let output = run_completion(&home, &["fish", "--install", "--stdout"]);
+    assert!(output.status.success());
+
+    let path = home.path().join(".config/fish/completions/sigilforge.fish");
+    assert!(path.exists());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("complete -c sigilforge"));
+    assert!(!stdout.contains("Installed"));
+}
@@ -0,0 +1,126 @@
+//! Tests for the global `--config-dir` flag and `SIGILFORGE_CONFIG_DIR`
+//!
+//! HOME points at a temporary directory so the real account store is never
+//! touched.
+
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn run(home: &TempDir, env_config_dir: Option<&Path>, args: &[&str]) -> Output {
+    let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"));
+    command
+        .args(args)
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("SIGILFORGE_CONFIG_DIR")
+        .env("SIGILFORGE_SOCKET", home.path().join("missing.sock"));
+    if let Some(dir) = env_config_dir {
+        command.env("SIGILFORGE_CONFIG_DIR", dir);
+    }
+    command.output().expect("failed to run sigilforge binary")
+}
+
+/// Restore a backup with one `github/{account}` account.
+fn import_account(
+    home: &TempDir,
+    env_config_dir: Option<&Path>,
+    flags: &[&str],
+    account: &str,
+) -> Output {
+    let backup = home.path().join(format!("{}.json", account));
+    let json = serde_json::json!({
+        "version": 1,
+        "accounts": [{
+            "service": "github",
+            "id": account,
+            "scopes": [],
+            "created_at": "2025-01-01T00:00:00Z",
+            "last_used": null,
+        }],
+    });
+    std::fs::write(&backup, json.to_string()).unwrap();
+
+    let mut args = flags.to_vec();
+    args.extend(["import", "--format=sigilforge", backup.to_str().unwrap()]);
+    run(home, env_config_dir, &args)
+}
+
+/// IDs of the accounts in the store file at `path`.
+fn stored_accounts(path: &Path) -> Vec<String> {
+    let contents = std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
+    let store: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    store["accounts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|account| account["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_config_dir_flag_moves_account_store() {
+    let home = TempDir::new().unwrap();
+    let dir = home.path().join("custom");
+
+    let flag = format!("--config-dir={}", dir.display());
+    let output = import_account(&home, None, &[&flag], "work");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(stored_accounts(&dir.join("accounts.json")), ["work"]);
+
+    // Other commands read the same store
+    let output = run(&home, None, &[&flag, "export", "--format=json"]);
+    let exported: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(exported["accounts"][0]["id"], "work");
+
+    let output = run(&home, None, &["export", "--format=json"]);
+    let exported: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(exported["accounts"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_config_dir_environment_variable() {
+    let home = TempDir::new().unwrap();
+    let env_dir = home.path().join("from-env");
+    let flag_dir = home.path().join("from-flag");
+
+    let output = import_account(&home, Some(&env_dir), &[], "env");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(stored_accounts(&env_dir.join("accounts.json")), ["env"]);
+
+    // The flag takes precedence over the environment
+    let flag = format!("--config-dir={}", flag_dir.display());
+    let output = import_account(&home, Some(&env_dir), &[&flag], "flag");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(stored_accounts(&flag_dir.join("accounts.json")), ["flag"]);
+    assert_eq!(stored_accounts(&env_dir.join("accounts.json")), ["env"]);
+}
+
+#[test]
+fn test_whoami_reports_config_dir() {
+    let home = TempDir::new().unwrap();
+    let dir = home.path().join("custom");
+
+    let flag = format!("--config-dir={}", dir.display());
+    let output = run(&home, None, &[&flag, "whoami", "--format=json"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let whoami: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(whoami["config_dir"], dir.display().to_string());
+}
@@ -0,0 +1,200 @@
+//! Integration tests for the daemon-status command
+//!
+//! These tests run the `sigilforge` binary against an in-process daemon and
+//! verify the documented exit codes: 0 healthy, 1 degraded (or invalid
+//! providers with `--validate-providers`), 2 unreachable.
+
+use sigilforge_core::provider::ProviderRegistry;
+use sigilforge_core::AccountStore;
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle};
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration};
+
+/// Detect whether the sandbox allows binding Unix sockets. Skip tests if not.
+fn can_bind_unix_socket() -> bool {
+    let path = std::env::temp_dir().join("sigilforge-cli-socket-permission-check.sock");
+    let _ = std::fs::remove_file(&path);
+    let ok = std::os::unix::net::UnixListener::bind(&path).is_ok();
+    let _ = std::fs::remove_file(&path);
+    ok
+}
+
+/// Run `sigilforge daemon-status` against the given socket path.
+async fn run_daemon_status(socket_path: &Path, format: &str) -> Output {
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["daemon-status", "--format", format])
+        .env("SIGILFORGE_SOCKET", socket_path)
+        .output()
+        .await
+        .expect("failed to run sigilforge binary")
+}
+
+/// Start a daemon backed by a memory store in the given temp directory.
+async fn start_test_daemon(temp_dir: &TempDir, prefer_keyring: bool) -> ServerHandle {
+    let socket_path = temp_dir.path().join("test.sock");
+    let accounts = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+
+    let mut state = ApiState::with_store(accounts);
+    state.prefer_keyring = prefer_keyring;
+
+    let handle = start_server(&socket_path, state).await.unwrap();
+    sleep(Duration::from_millis(100)).await;
+    handle
+}
+
+#[tokio::test]
+async fn test_daemon_status_unreachable_exits_2() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("missing.sock");
+
+    let output = run_daemon_status(&socket_path, "text").await;
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("unreachable"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_daemon_status_healthy_exits_0() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let handle = start_test_daemon(&temp_dir, false).await;
+
+    let output = run_daemon_status(&temp_dir.path().join("test.sock"), "json").await;
+
+    assert_eq!(output.status.code(), Some(0));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["healthy"], true);
+    assert_eq!(json["backend"], "memory");
+    assert_eq!(json["account_count"], 0);
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_daemon_status_degraded_exits_1() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    // Keyring requested but the state holds a memory store
+    let temp_dir = TempDir::new().unwrap();
+    let handle = start_test_daemon(&temp_dir, true).await;
+
+    let output = run_daemon_status(&temp_dir.path().join("test.sock"), "text").await;
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("degraded"));
+
+    handle.stop().await.unwrap();
+}
+
+/// Run `sigilforge daemon-status --validate-providers`.
+async fn run_validate_providers(socket_path: &Path, format: &str) -> Output {
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["daemon-status", "--validate-providers", "--format", format])
+        .env("SIGILFORGE_SOCKET", socket_path)
+        .output()
+        .await
+        .expect("failed to run sigilforge binary")
+}
+
+/// Start a daemon that refreshes tokens with `providers`.
+async fn start_daemon_with_providers(
+    temp_dir: &TempDir,
+    providers: ProviderRegistry,
+) -> ServerHandle {
+    let socket_path = temp_dir.path().join("test.sock");
+    let accounts = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store_and_providers(accounts, providers);
+
+    let handle = start_server(&socket_path, state).await.unwrap();
+    sleep(Duration::from_millis(100)).await;
+    handle
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_validate_providers_reports_warnings() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let handle = start_daemon_with_providers(&temp_dir, ProviderRegistry::with_defaults()).await;
+
+    let socket_path = temp_dir.path().join("test.sock");
+    let output = run_validate_providers(&socket_path, "json").await;
+
+    // Spotify has no revocation endpoint, which is only a warning
+    assert_eq!(output.status.code(), Some(0));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let providers = json["providers"].as_array().unwrap();
+    let spotify = providers.iter().find(|p| p["id"] == "spotify").unwrap();
+    assert_eq!(spotify["valid"], true);
+    assert_eq!(spotify["errors"].as_array().unwrap().len(), 0);
+    assert_eq!(spotify["warnings"].as_array().unwrap().len(), 1);
+    assert_eq!(json["skipped_provider_files"].as_array().unwrap().len(), 0);
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_skipped_provider_file_exits_1() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let provider_dir = temp_dir.path().join("providers");
+    std::fs::create_dir_all(&provider_dir).unwrap();
+    std::fs::write(
+        provider_dir.join("acme.toml"),
+        "id = \"acme\"\nname = \"Acme\"\nauth_url = \"https://acme.example.com/auth\"\n\
+         token_url = \"not a url\"\ndefault_scopes = []\nsupports_pkce = true\n\
+         supports_device_code = false\n",
+    )
+    .unwrap();
+    // The daemon skips the invalid file, as it does for user providers
+    let providers = ProviderRegistry::with_defaults()
+        .merge_valid_from_dir(&provider_dir)
+        .unwrap();
+    assert!(!providers.contains("acme"));
+    let handle = start_daemon_with_providers(&temp_dir, providers).await;
+
+    let socket_path = temp_dir.path().join("test.sock");
+    let output = run_validate_providers(&socket_path, "text").await;
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Daemon: healthy"), "{}", stdout);
+    assert!(stdout.contains("Providers: invalid"), "{}", stdout);
+    assert!(stdout.contains("acme.toml: skipped"), "{}", stdout);
+    assert!(
+        stdout.contains("token_url 'not a url' is not a valid URL"),
+        "{}",
+        stdout
+    );
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_validate_providers_needs_the_daemon() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("missing.sock");
+
+    let output = run_validate_providers(&socket_path, "json").await;
+
+    assert_eq!(output.status.code(), Some(2));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let error = json["providers_error"].as_str().unwrap();
+    assert!(error.contains("unreachable"), "{}", error);
+}
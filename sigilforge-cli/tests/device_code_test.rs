@@ -0,0 +1,150 @@
+//! Tests for `sigilforge add-account --device-code`
+//!
+//! A wiremock server plays the provider's device authorization and token
+//! endpoints. The provider is defined in a provider file in `--config-dir`
+//! (a temporary directory), next to the account store.
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use tempfile::TempDir;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// The `--config-dir` each run uses under `home`.
+fn config_dir(home: &Path) -> PathBuf {
+    home.join("config")
+}
+
+/// Save a `mock` provider whose endpoints live on `server`.
+fn write_provider(home: &Path, server: &MockServer, supports_device_code: bool) {
+    let dir = config_dir(home).join("providers");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("mock.toml"),
+        format!(
+            "id = \"mock\"\nname = \"Mock\"\nauth_url = \"{uri}/oauth/authorize\"\n\
+             token_url = \"{uri}/oauth/token\"\ndefault_scopes = [\"read\"]\n\
+             supports_pkce = true\nsupports_device_code = {}\n",
+            supports_device_code,
+            uri = server.uri()
+        ),
+    )
+    .unwrap();
+}
+
+async fn add_account(home: &TempDir, args: &[&str]) -> Output {
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["add-account", "mock", "work", "--client-id=test-client"])
+        .args(args)
+        .arg("--config-dir")
+        .arg(config_dir(home.path()))
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("SIGILFORGE_CONFIG_DIR")
+        .output()
+        .await
+        .expect("failed to run sigilforge binary")
+}
+
+async fn mount_device_authorization(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/oauth/device/code"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "device_code": "device-code-123",
+            "user_code": "WDJB-MJHT",
+            "verification_uri": "https://example.com/device",
+            "expires_in": 900,
+            "interval": 5
+        })))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_pending_then_success() {
+    let server = MockServer::start().await;
+    mount_device_authorization(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .and(body_string_contains("device_code=device-code-123"))
+        .respond_with(
+            ResponseTemplate::new(400)
+                .set_body_json(serde_json::json!({ "error": "authorization_pending" })),
+        )
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "access_token": "device-access-token",
+            "token_type": "bearer",
+            "expires_in": 3600,
+            "refresh_token": "device-refresh-token",
+            "scope": "read"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    write_provider(home.path(), &server, true);
+
+    let output = add_account(&home, &["--device-code", "--poll-interval=1"]).await;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert!(stdout.contains("https://example.com/device"));
+    assert!(stdout.contains("    WDJB-MJHT"));
+    assert!(stdout.contains("Waiting for authorization"));
+    assert!(stdout.contains("Success! Account mock/work configured."));
+    assert!(!stdout.contains("device-access-token"), "tokens must not be printed");
+
+    let accounts = std::fs::read_to_string(config_dir(home.path()).join("accounts.json")).unwrap();
+    assert!(accounts.contains("\"oauth_device_code\""), "{}", accounts);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_provider_without_device_code_support() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    write_provider(home.path(), &server, false);
+
+    let output = add_account(&home, &["--device-code"]).await;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Provider 'mock' does not support the device code flow"),
+        "{}",
+        stderr
+    );
+}
+
+#[tokio::test]
+async fn test_poll_interval_requires_device_code() {
+    let home = TempDir::new().unwrap();
+
+    let output = add_account(&home, &["--poll-interval=1"]).await;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--device-code"));
+
+    let output = add_account(&home, &["--device-code", "--poll-interval=0"]).await;
+    assert!(!output.status.success());
+}
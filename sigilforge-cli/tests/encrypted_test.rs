@@ -0,0 +1,104 @@
+//! Tests for the global `--encrypted` and `--identity-file` flags
+//!
+//! Accounts live in a temporary `--config-dir`, so the real account store is
+//! never touched.
+
+use std::process::Output;
+use tempfile::TempDir;
+
+fn run(config_dir: &TempDir, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .arg("--config-dir")
+        .arg(config_dir.path())
+        .args(args)
+        .env("HOME", config_dir.path())
+        .env_remove("SIGILFORGE_CONFIG_DIR")
+        .env("SIGILFORGE_SOCKET", config_dir.path().join("missing.sock"))
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+/// Restore a backup with one `github/work` account, passing `flags`.
+fn import_account(config_dir: &TempDir, flags: &[&str]) -> Output {
+    let backup = config_dir.path().join("backup.json");
+    let json = serde_json::json!({
+        "version": 1,
+        "accounts": [{
+            "service": "github",
+            "id": "work",
+            "scopes": [],
+            "created_at": "2025-01-01T00:00:00Z",
+            "last_used": null,
+        }],
+    });
+    std::fs::write(&backup, json.to_string()).unwrap();
+
+    let mut args = flags.to_vec();
+    args.extend(["import", "--format=sigilforge", backup.to_str().unwrap()]);
+    run(config_dir, &args)
+}
+
+#[test]
+fn test_encrypted_generates_identity_and_round_trips() {
+    let config_dir = TempDir::new().unwrap();
+
+    let output = import_account(&config_dir, &["--encrypted"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(config_dir.path().join("identity.txt").exists());
+
+    let contents = std::fs::read(config_dir.path().join("accounts.json")).unwrap();
+    assert!(serde_json::from_slice::<serde_json::Value>(&contents).is_err());
+
+    let output = run(&config_dir, &["--encrypted", "export", "--format=json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let exported: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(exported["accounts"][0]["id"], "work");
+
+    // Without the identity, the file cannot be read
+    let output = run(&config_dir, &["export", "--format=json"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_identity_file_flag() {
+    let config_dir = TempDir::new().unwrap();
+    let identity = config_dir.path().join("keys").join("sigilforge.key");
+    let identity = identity.to_str().unwrap();
+
+    let output = import_account(&config_dir, &["--encrypted", "--identity-file", identity]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(std::path::Path::new(identity).exists());
+    assert!(!config_dir.path().join("identity.txt").exists());
+
+    let flags = ["--encrypted", "--identity-file", identity, "export"];
+    let output = run(&config_dir, &flags);
+    assert!(output.status.success(), "{:?}", output);
+}
+
+#[test]
+fn test_identity_file_requires_encrypted() {
+    let config_dir = TempDir::new().unwrap();
+
+    let output = run(&config_dir, &["--identity-file", "identity.txt", "export"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_migrate_encrypt_encrypts_in_place() {
+    let config_dir = TempDir::new().unwrap();
+
+    let output = import_account(&config_dir, &[]);
+    assert!(output.status.success(), "{:?}", output);
+
+    let output = run(&config_dir, &["migrate", "--encrypt"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(config_dir.path().join("identity.txt").exists());
+
+    let contents = std::fs::read(config_dir.path().join("accounts.json")).unwrap();
+    assert!(serde_json::from_slice::<serde_json::Value>(&contents).is_err());
+
+    let output = run(&config_dir, &["--encrypted", "export", "--format=json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let exported: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(exported["accounts"][0]["id"], "work");
+}
@@ -0,0 +1,78 @@
+//! Tests for the Authorization header formats of `sigilforge get-token`
+//!
+//! The token is served by an in-process daemon backed by a memory store.
+
+use sigilforge_core::{Account, AccountId, AccountStore, ServiceId, Token, TokenManager, TokenSet};
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle};
+use std::process::Output;
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration};
+
+/// Detect whether the sandbox allows binding Unix sockets. Skip tests if not.
+fn can_bind_unix_socket() -> bool {
+    let path = std::env::temp_dir().join("sigilforge-cli-format-permission-check.sock");
+    let _ = std::fs::remove_file(&path);
+    let ok = std::os::unix::net::UnixListener::bind(&path).is_ok();
+    let _ = std::fs::remove_file(&path);
+    ok
+}
+
+/// Start a daemon where spotify/personal has the token `abc123`.
+async fn start_test_daemon(temp_dir: &TempDir) -> ServerHandle {
+    let socket_path = temp_dir.path().join("test.sock");
+    let accounts = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(accounts);
+
+    let (service, account) = (ServiceId::new("spotify"), AccountId::new("personal"));
+    state
+        .accounts
+        .add_account(Account::new(service.clone(), account.clone(), vec![]))
+        .unwrap();
+    state
+        .token_manager
+        .store_token_set(&service, &account, TokenSet::new(Token::new("abc123")))
+        .await
+        .unwrap();
+
+    let handle = start_server(&socket_path, state).await.unwrap();
+    sleep(Duration::from_millis(100)).await;
+    handle
+}
+
+async fn get_token(temp_dir: &TempDir, format: &str) -> Output {
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["get-token", "spotify", "personal", "--format", format])
+        .env("HOME", temp_dir.path())
+        .env("SIGILFORGE_SOCKET", temp_dir.path().join("test.sock"))
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .output()
+        .await
+        .expect("failed to run sigilforge binary")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_header_formats() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let handle = start_test_daemon(&temp_dir).await;
+
+    let cases = [
+        ("text", "abc123\n"),
+        ("bearer", "Bearer abc123\n"),
+        ("curl", "-H 'Authorization: Bearer abc123'\n"),
+        ("httpie", "Authorization:Bearer abc123\n"),
+        ("env", "export AUTHORIZATION_HEADER=\"Bearer abc123\"\n"),
+    ];
+    for (format, expected) in cases {
+        let output = get_token(&temp_dir, format).await;
+        assert!(output.status.success(), "--format={} failed", format);
+        assert_eq!(String::from_utf8_lossy(&output.stdout), expected, "--format={}", format);
+    }
+
+    handle.stop().await.unwrap();
+}
@@ -0,0 +1,36 @@
+//! Tests for `sigilforge get-token --refresh`
+//!
+//! SIGILFORGE_SOCKET points at a socket nobody listens on, so the command
+//! refreshes against the keyring, where there is no token to refresh.
+//! Refreshing against a provider is covered by the token manager's tests.
+
+use std::process::Output;
+use tempfile::TempDir;
+
+fn run(home: &TempDir, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .arg("get-token")
+        .args(args)
+        .env("HOME", home.path())
+        .env("SIGILFORGE_SOCKET", home.path().join("missing.sock"))
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+#[test]
+fn test_refresh_failure_exits_with_code_1() {
+    let home = TempDir::new().unwrap();
+    let output = run(&home, &["github", "work", "--refresh"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to refresh token"));
+}
+
+#[test]
+fn test_refresh_conflicts_with_watch() {
+    let home = TempDir::new().unwrap();
+    let output = run(&home, &["github", "work", "--refresh", "--watch"]);
+    assert_eq!(output.status.code(), Some(2));
+}
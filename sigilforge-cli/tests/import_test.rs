@@ -0,0 +1,251 @@
+//! Tests for the `import` command
+//!
+//! These run the `sigilforge` binary in dry-run mode so nothing is written
+//! to the keyring or the user's account store.
+
+use std::path::PathBuf;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Run `sigilforge import netrc ...` with HOME pointed at `home`.
+fn run_import(home: &TempDir, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["import", "netrc"])
+        .args(args)
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+#[test]
+fn test_netrc_dry_run() {
+    let home = TempDir::new().unwrap();
+    let netrc = fixture("netrc");
+    let output = run_import(&home, &["--file", netrc.to_str().unwrap(), "--dry-run"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Found 2 credential(s)"));
+    assert!(stdout.contains("github/octocat (api_key)"));
+    assert!(stdout.contains("spotify/listener (api_key)"));
+    assert!(stdout.contains("Dry run"));
+    assert!(!stdout.contains("ghp_example"), "secrets must not be printed");
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Warning: git.example.com: unrecognized host"));
+}
+
+#[test]
+fn test_netrc_service_map() {
+    let home = TempDir::new().unwrap();
+    let netrc = fixture("netrc");
+    let output = run_import(
+        &home,
+        &[
+            "--file",
+            netrc.to_str().unwrap(),
+            "--service-map",
+            "git.example.com=gitea",
+            "--dry-run",
+        ],
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Found 3 credential(s)"));
+    assert!(stdout.contains("gitea/builder (api_key)"));
+}
+
+#[test]
+fn test_invalid_service_map() {
+    let home = TempDir::new().unwrap();
+    let output = run_import(&home, &["--service-map", "no-equals-sign"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("expected HOST=SERVICE"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_default_netrc_location() {
+    let home = TempDir::new().unwrap();
+    std::fs::copy(fixture("netrc"), home.path().join(".netrc")).unwrap();
+
+    let output = run_import(&home, &["--dry-run"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains("Found 2 credential(s)"));
+}
+
+/// Run `sigilforge import github-cli ...` with HOME pointed at `home`.
+///
+/// PATH is cleared so a real `gh` is never asked for scopes.
+fn run_github_cli_import(home: &TempDir, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["import", "github-cli"])
+        .args(args)
+        .env("HOME", home.path())
+        .env("PATH", "")
+        .env_remove("GH_CONFIG_DIR")
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+#[test]
+fn test_github_cli_dry_run() {
+    let home = TempDir::new().unwrap();
+    let hosts = fixture("gh-hosts.yml");
+    let output = run_github_cli_import(
+        &home,
+        &[
+            "--file",
+            hosts.to_str().unwrap(),
+            "--service-map",
+            "github.example.com=github-enterprise",
+            "--dry-run",
+        ],
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Found 2 credential(s)"));
+    assert!(stdout.contains("github/octocat (api_key)"));
+    assert!(stdout.contains("github-enterprise/builder (api_key)"));
+    assert!(!stdout.contains("gho_example"), "secrets must not be printed");
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Warning: ghe.example.org: unrecognized host"));
+    assert!(stderr.contains("Warning: github.com: scopes unknown"));
+}
+
+#[test]
+fn test_github_cli_config_dir() {
+    let home = TempDir::new().unwrap();
+    let config_dir = home.path().join("gh-config");
+    std::fs::create_dir(&config_dir).unwrap();
+    std::fs::copy(fixture("gh-hosts.yml"), config_dir.join("hosts.yml")).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["import", "github-cli", "--dry-run"])
+        .env("HOME", home.path())
+        .env("PATH", "")
+        .env("GH_CONFIG_DIR", &config_dir)
+        .output()
+        .expect("failed to run sigilforge binary");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Found 1 credential(s)"));
+    assert!(stdout.contains("github/octocat (api_key)"));
+}
+
+#[test]
+fn test_github_cli_missing_hosts_file() {
+    let home = TempDir::new().unwrap();
+    let output = run_github_cli_import(&home, &["--dry-run"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(".config/gh/hosts.yml"), "{}", stderr);
+}
+
+/// Run `sigilforge import aws ...` with HOME pointed at `home`.
+fn run_aws_import(home: &TempDir, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["import", "aws"])
+        .args(args)
+        .env("HOME", home.path())
+        .env_remove("AWS_SHARED_CREDENTIALS_FILE")
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+#[test]
+fn test_aws_dry_run() {
+    let home = TempDir::new().unwrap();
+    let credentials = fixture("aws-credentials");
+    let output = run_aws_import(&home, &["--file", credentials.to_str().unwrap(), "--dry-run"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Found 6 credential(s)"));
+    assert!(stdout.contains("aws/default (api_key)"));
+    assert!(stdout.contains("aws/default (client_secret)"));
+    assert!(stdout.contains("aws/work (access_token)"));
+    assert!(stdout.contains("aws/work (token_expiry)"));
+    assert!(!stdout.contains("work-secret-example"), "secrets must not be printed");
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Warning: assumed: no aws_access_key_id"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_aws_default_location_with_profile() {
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir(home.path().join(".aws")).unwrap();
+    std::fs::copy(fixture("aws-credentials"), home.path().join(".aws/credentials")).unwrap();
+
+    let output = run_aws_import(&home, &["--profile", "work", "--dry-run"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Found 4 credential(s)"));
+    assert!(!stdout.contains("aws/default"));
+}
+
+/// Run `sigilforge import azure-cli ...` with only `path` on `$PATH`.
+fn run_azure_import(home: &TempDir, path: &std::path::Path, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["import", "azure-cli"])
+        .args(args)
+        .env("HOME", home.path())
+        .env("PATH", path)
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+#[cfg(unix)]
+#[test]
+fn test_azure_cli_dry_run() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // A stand-in `az` answering from the fixtures
+    let home = TempDir::new().unwrap();
+    let script = format!(
+        "#!/bin/sh\ncase \"$2\" in\n  list) cat '{}' ;;\n  get-access-token) cat '{}' ;;\nesac\n",
+        fixture("az-account-list.json").display(),
+        fixture("az-access-token.json").display()
+    );
+    let az = home.path().join("az");
+    std::fs::write(&az, script).unwrap();
+    std::fs::set_permissions(&az, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let args = ["--subscription", "Production", "--dry-run"];
+    let output = run_azure_import(&home, home.path(), &args);
+    assert!(output.status.success(), "{:?}", output);
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Found 3 credential(s)"));
+    assert!(stdout.contains("azure/production (access_token)"));
+    assert!(stdout.contains("azure/production (tenant_id)"));
+    assert!(!stdout.contains("azure-example"), "secrets must not be printed");
+}
+
+#[test]
+fn test_azure_cli_not_installed() {
+    let home = TempDir::new().unwrap();
+    let output = run_azure_import(&home, home.path(), &["--dry-run"]);
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("az not found in PATH"), "{}", stderr);
+}
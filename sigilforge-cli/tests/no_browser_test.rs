@@ -0,0 +1,102 @@
+//! Tests for `sigilforge add-account --no-browser` without a terminal
+//!
+//! stdin is a pipe, so the command prints the authorization URL, saves the
+//! pending authorization under HOME (a temporary directory), and exits.
+//! Finishing with `--auth-code` needs a real provider and is not covered.
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+use tempfile::TempDir;
+
+/// Where pending authorizations are kept under `home`.
+fn pending_dir(home: &Path) -> PathBuf {
+    if cfg!(target_os = "macos") {
+        home.join("Library/Application Support/com.raibid-labs.sigilforge/pending")
+    } else {
+        home.join(".local/share/sigilforge/pending")
+    }
+}
+
+fn run(home: &TempDir, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .arg("add-account")
+        .args(args)
+        .env("HOME", home.path())
+        .env("GITHUB_CLIENT_ID", "test-client")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("OAUTH_CALLBACK_PORT")
+        .stdin(Stdio::piped())
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+#[test]
+fn test_prints_url_and_exits_without_tty() {
+    let home = TempDir::new().unwrap();
+    let output = run(&home, &["github", "work", "--no-browser", "--scopes=repo"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The URL is the last line of stdout, on its own
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let url = stdout.lines().last().unwrap();
+    assert!(
+        url.starts_with("https://github.com/login/oauth/authorize?"),
+        "{}",
+        stdout
+    );
+    assert!(url.contains("client_id=test-client"));
+    assert!(url.contains("code_challenge="));
+    assert!(url.contains("redirect_uri=http%3A%2F%2F127.0.0.1%3A8484%2Fcallback"));
+    assert!(!stdout.contains("Press Enter"));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("sigilforge add-account github work --auth-code=<code>"));
+
+    let pending = pending_dir(home.path()).join("github/work.json");
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&pending).unwrap()).unwrap();
+    assert_eq!(saved["provider"]["id"], "github");
+    assert_eq!(saved["scopes"], serde_json::json!(["repo"]));
+    assert!(saved["authorization"]["code_verifier"].is_string());
+
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(&pending).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn test_ephemeral_callback_port_is_refused_without_tty() {
+    let home = TempDir::new().unwrap();
+    let output = run(
+        &home,
+        &["github", "work", "--no-browser", "--callback-port=0"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("fixed redirect port"));
+    assert!(!pending_dir(home.path()).exists());
+}
+
+#[test]
+fn test_auth_code_requires_pending_authorization() {
+    let home = TempDir::new().unwrap();
+    let output = run(&home, &["github", "work", "--auth-code=abc123"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No pending authorization"));
+}
+
+#[test]
+fn test_auth_code_conflicts_with_no_browser() {
+    let home = TempDir::new().unwrap();
+    let output = run(
+        &home,
+        &["github", "work", "--no-browser", "--auth-code=abc123"],
+    );
+    assert_eq!(output.status.code(), Some(2));
+}
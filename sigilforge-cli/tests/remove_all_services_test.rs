@@ -0,0 +1,114 @@
+//! Tests for `sigilforge remove-account --all-services`
+//!
+//! Accounts live in a temporary `--config-dir`, so the real account store is
+//! never touched.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use sigilforge_core::{Account, AccountId, AccountStore, ServiceId};
+use tempfile::TempDir;
+
+/// A config dir holding github/work, github/personal and gitlab/work.
+fn config_dir_with_accounts() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_config_dir(temp_dir.path()).unwrap();
+    for (service, account) in [
+        ("github", "work"),
+        ("github", "personal"),
+        ("gitlab", "work"),
+    ] {
+        let account = Account::new(ServiceId::new(service), AccountId::new(account), vec![]);
+        store.add_account(account).unwrap();
+    }
+    temp_dir
+}
+
+/// Run `remove-account` with `args`, typing `input` at the prompt.
+fn remove_account(config_dir: &TempDir, args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .arg("--config-dir")
+        .arg(config_dir.path())
+        .arg("remove-account")
+        .args(args)
+        .env("HOME", config_dir.path())
+        .env("SIGILFORGE_SOCKET", config_dir.path().join("missing.sock"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run sigilforge binary");
+    // The command may exit before reading, e.g. on a usage error
+    let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+/// `service/account` of every account left in the store.
+fn remaining_accounts(config_dir: &TempDir) -> Vec<String> {
+    let store = AccountStore::load_from_config_dir(config_dir.path()).unwrap();
+    let mut accounts: Vec<String> = store
+        .list_accounts(None)
+        .unwrap()
+        .iter()
+        .map(|account| format!("{}/{}", account.service, account.id))
+        .collect();
+    accounts.sort();
+    accounts
+}
+
+#[test]
+fn test_remove_all_accounts_of_service() {
+    let config_dir = config_dir_with_accounts();
+
+    let output = remove_account(&config_dir, &["github", "--all-services"], "y\n");
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(remaining_accounts(&config_dir), vec!["gitlab/work"]);
+}
+
+#[test]
+fn test_remove_all_accounts() {
+    let config_dir = config_dir_with_accounts();
+
+    let output = remove_account(&config_dir, &["--all-services"], "y\n");
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Cancelled"));
+    assert_eq!(remaining_accounts(&config_dir).len(), 3);
+
+    let output = remove_account(&config_dir, &["--all-services"], "yes\n");
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Remove ALL accounts? Type 'yes' to confirm"));
+    assert!(remaining_accounts(&config_dir).is_empty());
+}
+
+#[test]
+fn test_force_still_confirms_removing_all_accounts() {
+    let config_dir = config_dir_with_accounts();
+
+    let output = remove_account(&config_dir, &["--all-services", "--force"], "");
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(remaining_accounts(&config_dir).len(), 3);
+
+    let output = remove_account(&config_dir, &["--all-services", "--force"], "yes\n");
+    assert!(output.status.success(), "{:?}", output);
+    assert!(remaining_accounts(&config_dir).is_empty());
+}
+
+#[test]
+fn test_force_skips_confirming_one_service() {
+    let config_dir = config_dir_with_accounts();
+
+    let output = remove_account(&config_dir, &["gitlab", "--all-services", "--force"], "");
+    assert!(output.status.success(), "{:?}", output);
+    let remaining = remaining_accounts(&config_dir);
+    assert_eq!(remaining, vec!["github/personal", "github/work"]);
+}
+
+#[test]
+fn test_account_conflicts_with_all_services() {
+    let config_dir = config_dir_with_accounts();
+
+    let output = remove_account(&config_dir, &["github", "work", "--all-services"], "yes\n");
+    assert!(!output.status.success());
+    assert_eq!(remaining_accounts(&config_dir).len(), 3);
+}
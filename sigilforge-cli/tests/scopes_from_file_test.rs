@@ -0,0 +1,113 @@
+//! Tests for `sigilforge add-account --scopes-from-file`
+//!
+//! Runs with `--no-browser` and no terminal, so the requested scopes can be
+//! read back from the saved pending authorization.
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+use tempfile::TempDir;
+
+fn pending_file(home: &Path) -> PathBuf {
+    let data_dir = if cfg!(target_os = "macos") {
+        home.join("Library/Application Support/com.raibid-labs.sigilforge")
+    } else {
+        home.join(".local/share/sigilforge")
+    };
+    data_dir.join("pending/google/work.json")
+}
+
+fn add_account(home: &TempDir, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["add-account", "google", "work", "--no-browser"])
+        .args(args)
+        .env("HOME", home.path())
+        .env("GOOGLE_CLIENT_ID", "test-client")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("SIGILFORGE_CONFIG_DIR")
+        .env_remove("OAUTH_CALLBACK_PORT")
+        .stdin(Stdio::piped())
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+fn requested_scopes(home: &TempDir) -> serde_json::Value {
+    let pending = std::fs::read_to_string(pending_file(home.path())).unwrap();
+    serde_json::from_str::<serde_json::Value>(&pending).unwrap()["scopes"].clone()
+}
+
+fn write_scopes(home: &TempDir, contents: &str) -> PathBuf {
+    let path = home.path().join("scopes.txt");
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_reads_scopes_skipping_comments_and_blank_lines() {
+    let home = TempDir::new().unwrap();
+    let path = write_scopes(
+        &home,
+        "# Gmail\n\
+         https://www.googleapis.com/auth/gmail.readonly\n\
+         \n\
+         \t# Calendar, indented\n\
+         https://www.googleapis.com/auth/calendar  \t openid\n\
+         \r\n\
+         email   profile\n",
+    );
+
+    let output = add_account(&home, &[&format!("--scopes-from-file={}", path.display())]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        requested_scopes(&home),
+        serde_json::json!([
+            "https://www.googleapis.com/auth/gmail.readonly",
+            "https://www.googleapis.com/auth/calendar",
+            "openid",
+            "email",
+            "profile",
+        ])
+    );
+}
+
+#[test]
+fn test_merges_with_scopes_flag() {
+    let home = TempDir::new().unwrap();
+    let path = write_scopes(&home, "email\nopenid profile\n");
+
+    let output = add_account(
+        &home,
+        &[
+            "--scopes=openid,email",
+            &format!("--scopes-from-file={}", path.display()),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        requested_scopes(&home),
+        serde_json::json!(["openid", "email", "profile"])
+    );
+}
+
+#[test]
+fn test_missing_file_is_an_error() {
+    let home = TempDir::new().unwrap();
+    let path = home.path().join("missing.txt");
+
+    let output = add_account(&home, &[&format!("--scopes-from-file={}", path.display())]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to read scopes from"), "{}", stderr);
+    assert!(stderr.contains("missing.txt"), "{}", stderr);
+    assert!(!pending_file(home.path()).exists());
+}
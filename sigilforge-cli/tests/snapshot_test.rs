@@ -0,0 +1,82 @@
+//! Tests for `sigilforge snapshot`
+//!
+//! Accounts live in a temporary `--config-dir`, so the real account store is
+//! never touched.
+
+use std::process::{Command, Output};
+
+use sigilforge_core::{Account, AccountId, AccountStore, ServiceId};
+use tempfile::TempDir;
+
+fn run(config_dir: &TempDir, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .arg("--config-dir")
+        .arg(config_dir.path())
+        .arg("snapshot")
+        .args(args)
+        .env("HOME", config_dir.path())
+        .env("SIGILFORGE_SOCKET", config_dir.path().join("missing.sock"))
+        .output()
+        .expect("failed to run sigilforge binary")
+}
+
+/// `service/account` of every account in the store.
+fn accounts(config_dir: &TempDir) -> Vec<String> {
+    let store = AccountStore::load_from_config_dir(config_dir.path()).unwrap();
+    let mut accounts: Vec<String> = store
+        .list_accounts(None)
+        .unwrap()
+        .iter()
+        .map(|account| format!("{}/{}", account.service, account.id))
+        .collect();
+    accounts.sort();
+    accounts
+}
+
+#[test]
+fn test_snapshot_create_list_restore() {
+    let config_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_config_dir(config_dir.path()).unwrap();
+    for (service, account) in [("github", "work"), ("gitlab", "work")] {
+        let account = Account::new(ServiceId::new(service), AccountId::new(account), vec![]);
+        store.add_account(account).unwrap();
+    }
+
+    let output = run(&config_dir, &["create", "--label", "before-cleanup"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("(2 account(s))"), "{}", stdout);
+    let id = stdout.split_whitespace().nth(2).unwrap().to_string();
+    assert!(id.ends_with("-before-cleanup"), "{}", id);
+
+    // Changes after the snapshot are undone by restoring it
+    store
+        .remove_account(&ServiceId::new("github"), &AccountId::new("work"))
+        .unwrap();
+    let spotify = Account::new(ServiceId::new("spotify"), AccountId::new("new"), vec![]);
+    store.add_account(spotify).unwrap();
+    assert_eq!(accounts(&config_dir), vec!["gitlab/work", "spotify/new"]);
+
+    let output = run(&config_dir, &["list"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains(&id));
+
+    let output = run(&config_dir, &["restore", &id]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(accounts(&config_dir), vec!["github/work", "gitlab/work"]);
+}
+
+#[test]
+fn test_snapshot_errors() {
+    let config_dir = TempDir::new().unwrap();
+
+    let output = run(&config_dir, &["list"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No snapshots"));
+
+    let output = run(&config_dir, &["restore", "20250101T000000.000Z"]);
+    assert!(!output.status.success());
+
+    let output = run(&config_dir, &["create", "--label", "not/valid"]);
+    assert!(!output.status.success());
+}
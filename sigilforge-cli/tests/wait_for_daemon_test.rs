@@ -0,0 +1,92 @@
+//! Tests for `sigilforge get-token --wait-for-daemon`
+
+use sigilforge_core::{Account, AccountId, AccountStore, ServiceId, Token, TokenManager, TokenSet};
+use sigilforge_daemon::api::{start_server, ApiState};
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration};
+
+/// Detect whether the sandbox allows binding Unix sockets. Skip tests if not.
+fn can_bind_unix_socket() -> bool {
+    let path = std::env::temp_dir().join("sigilforge-cli-socket-permission-check.sock");
+    let _ = std::fs::remove_file(&path);
+    let ok = std::os::unix::net::UnixListener::bind(&path).is_ok();
+    let _ = std::fs::remove_file(&path);
+    ok
+}
+
+fn get_token_command(home: &Path, socket_path: &Path, args: &[&str]) -> tokio::process::Command {
+    let mut command = tokio::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"));
+    command
+        .args(["get-token", "github", "work", "--wait-for-daemon"])
+        .args(args)
+        .env("HOME", home)
+        .env("SIGILFORGE_SOCKET", socket_path);
+    command
+}
+
+#[tokio::test]
+async fn test_wait_for_daemon_times_out_with_exit_code_2() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("missing.sock");
+
+    let output = get_token_command(temp_dir.path(), &socket_path, &["--wait-timeout=1"])
+        .output()
+        .await
+        .expect("failed to run sigilforge binary");
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Waiting for"), "{}", stderr);
+    assert!(stderr.contains("did not start within 1s"), "{}", stderr);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_wait_for_daemon_gets_token_once_daemon_starts() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("test.sock");
+    let child = get_token_command(temp_dir.path(), &socket_path, &[])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("failed to run sigilforge binary");
+
+    // Start the daemon while the command is polling
+    sleep(Duration::from_millis(1200)).await;
+    let accounts = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(accounts);
+    let (service, account) = (ServiceId::new("github"), AccountId::new("work"));
+    state
+        .accounts
+        .add_account(Account::new(service.clone(), account.clone(), vec![]))
+        .unwrap();
+    state
+        .token_manager
+        .store_token_set(&service, &account, TokenSet::new(Token::new("gh-token")))
+        .await
+        .unwrap();
+    let handle = start_server(&socket_path, state).await.unwrap();
+
+    let output = child.wait_with_output().await.unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "gh-token");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Waiting for sigilforge daemon..."));
+
+    handle.stop().await.unwrap();
+}
+
+#[test]
+fn test_wait_timeout_requires_wait_for_daemon() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["get-token", "github", "work", "--wait-timeout=5"])
+        .output()
+        .expect("failed to run sigilforge binary");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--wait-for-daemon"));
+}
@@ -0,0 +1,118 @@
+//! Integration tests for the whoami command
+//!
+//! These tests run the `sigilforge` binary with an isolated config directory
+//! and check the reported state with and without a running daemon.
+
+use sigilforge_core::{Account, AccountId, AccountStore, ServiceId};
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle};
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration};
+
+/// Detect whether the sandbox allows binding Unix sockets. Skip tests if not.
+fn can_bind_unix_socket() -> bool {
+    let path = std::env::temp_dir().join("sigilforge-cli-whoami-permission-check.sock");
+    let _ = std::fs::remove_file(&path);
+    let ok = std::os::unix::net::UnixListener::bind(&path).is_ok();
+    let _ = std::fs::remove_file(&path);
+    ok
+}
+
+/// Run `sigilforge whoami` with its config directory inside `home`.
+async fn run_whoami(home: &Path, socket_path: &Path, format: &str) -> Output {
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_sigilforge"))
+        .args(["whoami", "--format", format])
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("SIGILFORGE_SOCKET", socket_path)
+        .output()
+        .await
+        .expect("failed to run sigilforge binary")
+}
+
+/// Write accounts to the store the binary will load from `home`.
+fn populate_store(home: &Path) {
+    let store =
+        AccountStore::load_from_path(home.join(".config/sigilforge/accounts.json")).unwrap();
+    for (service, account) in [("github", "work"), ("github", "oss"), ("spotify", "personal")] {
+        let account = Account::new(ServiceId::new(service), AccountId::new(account), vec![]);
+        store.add_account(account).unwrap();
+    }
+}
+
+async fn start_test_daemon(temp_dir: &TempDir) -> ServerHandle {
+    let socket_path = temp_dir.path().join("test.sock");
+    let accounts = AccountStore::load_from_path(temp_dir.path().join("daemon.json")).unwrap();
+    let handle = start_server(&socket_path, ApiState::with_store(accounts))
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(100)).await;
+    handle
+}
+
+#[tokio::test]
+async fn test_whoami_without_daemon() {
+    let home = TempDir::new().unwrap();
+    let socket_path = home.path().join("missing.sock");
+
+    let output = run_whoami(home.path(), &socket_path, "text").await;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("Daemon: not running ({})", socket_path.display())));
+    assert!(stdout.contains("0 accounts configured across 0 services"));
+    assert!(stdout.contains("Keyring: "));
+    assert!(stdout.contains(&format!(
+        "Config directory: {}",
+        home.path().join(".config/sigilforge").display()
+    )));
+    assert!(stdout.contains("no user-defined providers"));
+}
+
+#[tokio::test]
+async fn test_whoami_with_populated_store() {
+    let home = TempDir::new().unwrap();
+    populate_store(home.path());
+
+    let output = run_whoami(home.path(), &home.path().join("missing.sock"), "text").await;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .contains("3 accounts configured across 2 services"));
+
+    let output = run_whoami(home.path(), &home.path().join("missing.sock"), "json").await;
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["daemon"]["running"], false);
+    assert_eq!(json["accounts"]["count"], 3);
+    assert_eq!(json["accounts"]["services"], serde_json::json!(["github", "spotify"]));
+    assert_eq!(json["providers"]["user_defined"], serde_json::json!([]));
+    assert!(json["keyring"]["available"].is_boolean());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_whoami_with_running_daemon() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let handle = start_test_daemon(&temp_dir).await;
+    let socket_path = temp_dir.path().join("test.sock");
+
+    let output = run_whoami(temp_dir.path(), &socket_path, "json").await;
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["socket_path"], socket_path.display().to_string());
+    assert_eq!(json["daemon"]["running"], true);
+    assert_eq!(json["daemon"]["version"], env!("CARGO_PKG_VERSION"));
+    assert!(json["daemon"]["uptime_secs"].is_u64());
+
+    let output = run_whoami(temp_dir.path(), &socket_path, "text").await;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Daemon: running"));
+    assert!(stdout.contains("Uptime: "));
+
+    handle.stop().await.unwrap();
+}
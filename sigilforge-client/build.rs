@@ -0,0 +1,11 @@
+//! Generates the gRPC client from `proto/sigilforge.proto`, a copy of the
+//! daemon's, when the `grpc` feature is enabled. Requires `protoc` on the
+//! `PATH`.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    tonic_build::configure()
+        .build_server(false)
+        .compile_protos(&["proto/sigilforge.proto"], &["proto"])
+        .expect("failed to compile sigilforge.proto");
+}
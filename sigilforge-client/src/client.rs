@@ -1,9 +1,20 @@
 use crate::fallback::{FallbackConfig, FallbackResolver};
-use crate::socket::{default_socket_path, DaemonConnection};
+use crate::socket::{default_socket_path, default_timeout, DaemonConnection};
 use crate::types::{AccessToken, DaemonHealth, Result, SecretValue, SigilforgeError};
 use async_trait::async_trait;
+use futures::stream::{self, Stream, StreamExt};
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::PathBuf;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::time::MissedTickBehavior;
+use tokio_stream::wrappers::IntervalStream;
+
+#[cfg(feature = "tls")]
+use std::path::Path;
 use tracing::{debug, info, warn};
 
 /// Trait for obtaining tokens and credentials.
@@ -37,6 +48,11 @@ pub trait TokenProvider: Send + Sync {
 /// it falls back to configured fallback strategies (environment variables,
 /// config files, etc.).
 ///
+/// Unless a socket path is given explicitly, the client connects to the
+/// socket named by the `SIGILFORGE_SOCKET` environment variable, or the
+/// platform default if it is unset. `SIGILFORGE_SOCKET_TIMEOUT_SECS` sets the
+/// default request timeout (5 seconds).
+///
 /// # Example
 ///
 /// ```no_run
@@ -62,6 +78,9 @@ pub struct SigilforgeClient {
     daemon: Option<DaemonConnection>,
     fallback: FallbackResolver,
     prefer_daemon: bool,
+    token_cache: TokenCache,
+    /// Set to `true` once pre-warming is done; `None` if nothing is pre-warmed.
+    prewarmed: Option<watch::Receiver<bool>>,
 }
 
 impl SigilforgeClient {
@@ -74,6 +93,8 @@ impl SigilforgeClient {
             daemon,
             fallback,
             prefer_daemon: true,
+            token_cache: TokenCache::default(),
+            prewarmed: None,
         }
     }
 
@@ -86,6 +107,8 @@ impl SigilforgeClient {
             daemon,
             fallback,
             prefer_daemon: true,
+            token_cache: TokenCache::default(),
+            prewarmed: None,
         }
     }
 
@@ -95,6 +118,8 @@ impl SigilforgeClient {
             daemon: None,
             fallback: FallbackResolver::new(config),
             prefer_daemon: false,
+            token_cache: TokenCache::default(),
+            prewarmed: None,
         }
     }
 
@@ -104,6 +129,15 @@ impl SigilforgeClient {
         self
     }
 
+    /// Drop the fallback's cached value for `reference`, so the next lookup
+    /// resolves it again.
+    ///
+    /// Returns whether a value was cached; see
+    /// [`SigilforgeClientBuilder::with_fallback_cache`].
+    pub fn invalidate_fallback(&self, reference: &str) -> Result<bool> {
+        self.fallback.invalidate(reference)
+    }
+
     /// Set the daemon connection timeout.
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         if let Some(daemon) = self.daemon.take() {
@@ -136,6 +170,144 @@ impl SigilforgeClient {
         }
     }
 
+    /// List the `(service, account)` pairs configured in the daemon.
+    ///
+    /// Fallback strategies cannot enumerate accounts, so this needs the
+    /// daemon.
+    pub async fn list_accounts(&self) -> Result<Vec<(String, String)>> {
+        match &self.daemon {
+            Some(daemon) if self.prefer_daemon => daemon.list_accounts().await,
+            _ => Err(SigilforgeError::DaemonUnavailable(
+                "no daemon configured".to_string(),
+            )),
+        }
+    }
+
+    /// Watch the token of `service`/`account`, calling
+    /// [`ensure_token`](TokenProvider::ensure_token) every `interval`.
+    ///
+    /// The first result is yielded right away; after that a result is only
+    /// yielded when it differs from the previous one, such as when the
+    /// token is refreshed. A failure is yielded once until it changes or
+    /// the token is available again.
+    pub fn watch_token(
+        &self,
+        service: &str,
+        account: &str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<AccessToken>> + use<'_> {
+        let (service, account) = (service.to_string(), account.to_string());
+        let mut last = None;
+        ticks(interval)
+            .then(move |_| {
+                let (service, account) = (service.clone(), account.clone());
+                async move { self.ensure_token(&service, &account).await }
+            })
+            .filter_map(move |result| {
+                let hash = result_hash(&result);
+                let changed = last.replace(hash) != Some(hash);
+                std::future::ready(changed.then_some(result))
+            })
+    }
+
+    /// Watch the tokens of every account in the daemon, like
+    /// [`watch_token`](Self::watch_token), yielding `(service, account,
+    /// result)` for each account whose result changed.
+    ///
+    /// Accounts are listed again on every tick, so added accounts are
+    /// picked up. A tick on which the accounts cannot be listed is skipped.
+    pub fn watch_any_token(
+        &self,
+        interval: Duration,
+    ) -> impl Stream<Item = (String, String, Result<AccessToken>)> + '_ {
+        let mut last: HashMap<(String, String), u64> = HashMap::new();
+        ticks(interval)
+            .then(move |_| self.ensure_all_tokens())
+            .flat_map(move |results| {
+                let Some(results) = results else {
+                    return stream::iter(Vec::new());
+                };
+
+                // Rebuilt every tick, so a removed account starts over
+                let previous = std::mem::take(&mut last);
+                let changed: Vec<_> = results
+                    .into_iter()
+                    .filter(|(service, account, result)| {
+                        let key = (service.clone(), account.clone());
+                        let hash = result_hash(result);
+                        last.insert(key.clone(), hash);
+                        previous.get(&key) != Some(&hash)
+                    })
+                    .collect();
+                stream::iter(changed)
+            })
+    }
+
+    /// Wait until the tokens requested with
+    /// [`with_prewarm`](SigilforgeClientBuilder::with_prewarm) or
+    /// [`with_prewarm_all`](SigilforgeClientBuilder::with_prewarm_all) have
+    /// been fetched.
+    ///
+    /// Resolves right away if nothing is pre-warmed. Tokens that failed to
+    /// pre-warm are fetched as usual on first use.
+    pub fn wait_for_prewarm(&self) -> impl Future<Output = ()> + 'static {
+        let prewarmed = self.prewarmed.clone();
+        async move {
+            if let Some(mut prewarmed) = prewarmed {
+                // An error means the pre-warm task is gone, so it is done too
+                let _ = prewarmed.wait_for(|done| *done).await;
+            }
+        }
+    }
+
+    /// Ensure the tokens of `accounts`, or of every account in the daemon
+    /// if `None`, and cache them.
+    async fn prewarm(&self, accounts: Option<Vec<(String, String)>>) {
+        let accounts = match accounts {
+            Some(accounts) => accounts,
+            None => match self.list_accounts().await {
+                Ok(accounts) => accounts,
+                Err(e) => {
+                    warn!("failed to list accounts to pre-warm: {}", e);
+                    return;
+                }
+            },
+        };
+
+        let prewarm = accounts.into_iter().map(|(service, account)| async move {
+            match self.ensure_token(&service, &account).await {
+                Ok(token) => {
+                    debug!("pre-warmed token for {}/{}", service, account);
+                    self.token_cache.insert(service, account, token);
+                }
+                Err(e) => warn!(
+                    "failed to pre-warm token for {}/{}: {}",
+                    service, account, e
+                ),
+            }
+        });
+        futures::future::join_all(prewarm).await;
+    }
+
+    /// Ensure the token of every account in the daemon, or `None` if the
+    /// accounts cannot be listed.
+    async fn ensure_all_tokens(&self) -> Option<Vec<(String, String, Result<AccessToken>)>> {
+        let accounts = match self.list_accounts().await {
+            Ok(accounts) => accounts,
+            Err(e) => {
+                warn!("failed to list accounts to watch: {}", e);
+                return None;
+            }
+        };
+
+        let mut results = Vec::with_capacity(accounts.len());
+        for (service, account) in accounts {
+            let result = self.ensure_token(&service, &account).await;
+            results.push((service, account, result));
+        }
+        Some(results)
+    }
+
     /// Try to get a token from the daemon.
     async fn try_daemon_token(&self, service: &str, account: &str) -> Option<Result<AccessToken>> {
         if !self.prefer_daemon {
@@ -228,6 +400,10 @@ impl Default for SigilforgeClient {
 #[async_trait]
 impl TokenProvider for SigilforgeClient {
     async fn get_token(&self, service: &str, account: &str) -> Result<AccessToken> {
+        if let Some(token) = self.token_cache.get(service, account) {
+            return Ok(token);
+        }
+
         // Try daemon first
         if let Some(result) = self.try_daemon_token(service, account).await {
             return result;
@@ -242,6 +418,10 @@ impl TokenProvider for SigilforgeClient {
     }
 
     async fn ensure_token(&self, service: &str, account: &str) -> Result<AccessToken> {
+        if let Some(token) = self.token_cache.get(service, account) {
+            return Ok(token);
+        }
+
         // Try daemon first (with refresh)
         if let Some(result) = self.try_daemon_ensure_token(service, account).await {
             return result;
@@ -267,12 +447,87 @@ impl TokenProvider for SigilforgeClient {
     }
 }
 
+/// Cached tokens are no longer served once they expire within this many
+/// seconds.
+const TOKEN_CACHE_EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// Cached tokens without an expiry are served for at most this long, since
+/// they can still be revoked or rotated.
+const TOKEN_CACHE_MAX_AGE: Duration = Duration::from_secs(300);
+
+/// Tokens fetched by pre-warming, keyed by `(service, account)`, with when
+/// they were cached.
+///
+/// A token is served until it is about to expire, or for
+/// [`TOKEN_CACHE_MAX_AGE`] if it has no expiry; after that it is dropped
+/// and fetched as usual.
+#[derive(Debug, Clone, Default)]
+struct TokenCache {
+    tokens: Arc<Mutex<HashMap<(String, String), (AccessToken, Instant)>>>,
+}
+
+impl TokenCache {
+    /// The cached token for `service`/`account`, unless it is about to expire
+    /// or too old.
+    fn get(&self, service: &str, account: &str) -> Option<AccessToken> {
+        let mut tokens = self.tokens.lock().expect("token cache lock poisoned");
+        let key = (service.to_string(), account.to_string());
+        let margin = chrono::Duration::seconds(TOKEN_CACHE_EXPIRY_MARGIN_SECS);
+        let is_fresh = |token: &AccessToken, cached_at: &Instant| match token.expires_at {
+            Some(_) => !token.expires_within(margin),
+            None => cached_at.elapsed() < TOKEN_CACHE_MAX_AGE,
+        };
+
+        match tokens.get(&key) {
+            Some((token, cached_at)) if is_fresh(token, cached_at) => Some(token.clone()),
+            Some(_) => {
+                tokens.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, service: String, account: String, token: AccessToken) {
+        self.tokens
+            .lock()
+            .expect("token cache lock poisoned")
+            .insert((service, account), (token, Instant::now()));
+    }
+}
+
+/// A tick every `interval`, starting now; slow ticks delay the next.
+fn ticks(interval: Duration) -> IntervalStream {
+    let mut interval = tokio::time::interval(interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    IntervalStream::new(interval)
+}
+
+/// Hash of a token value, or of the error that took its place.
+fn result_hash(result: &Result<AccessToken>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    result
+        .as_ref()
+        .map(|token| &token.token)
+        .map_err(|e| e.to_string())
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
 /// Builder for creating a `SigilforgeClient` with custom configuration.
 pub struct SigilforgeClientBuilder {
     socket_path: Option<PathBuf>,
+    tcp_addr: Option<String>,
+    #[cfg(feature = "tls")]
+    tls: Option<Arc<tokio_rustls::rustls::ClientConfig>>,
+    #[cfg(feature = "grpc")]
+    grpc_socket: Option<PathBuf>,
     fallback: FallbackConfig,
+    fallback_cache: Option<(usize, Duration)>,
     timeout: Duration,
     use_daemon: bool,
+    prewarm: Vec<(String, String)>,
+    prewarm_all: bool,
 }
 
 impl SigilforgeClientBuilder {
@@ -280,9 +535,17 @@ impl SigilforgeClientBuilder {
     pub fn new() -> Self {
         Self {
             socket_path: default_socket_path(),
+            tcp_addr: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+            #[cfg(feature = "grpc")]
+            grpc_socket: None,
             fallback: FallbackConfig::default(),
-            timeout: Duration::from_secs(5),
+            fallback_cache: None,
+            timeout: default_timeout(),
             use_daemon: true,
+            prewarm: Vec::new(),
+            prewarm_all: false,
         }
     }
 
@@ -292,6 +555,34 @@ impl SigilforgeClientBuilder {
         self
     }
 
+    /// Connect to a daemon listening on TCP at `host:port` instead of the
+    /// Unix socket.
+    pub fn tcp_address(mut self, addr: impl Into<String>) -> Self {
+        self.tcp_addr = Some(addr.into());
+        self
+    }
+
+    /// Use TLS for the TCP connection.
+    ///
+    /// `cert` is a PEM file holding the client certificate and private key
+    /// (for daemons that require mutual TLS); `ca` holds the CAs trusted to
+    /// sign the daemon's certificate. Requires the `tls` feature.
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, cert: &Path, ca: &Path) -> Result<Self> {
+        self.tls = Some(Arc::new(crate::tls::client_config(cert, ca)?));
+        Ok(self)
+    }
+
+    /// Connect to a daemon serving gRPC on the Unix socket at `path`
+    /// instead of JSON-RPC.
+    ///
+    /// Requires the `grpc` feature.
+    #[cfg(feature = "grpc")]
+    pub fn grpc_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.grpc_socket = Some(path.into());
+        self
+    }
+
     /// Disable daemon connection.
     pub fn no_daemon(mut self) -> Self {
         self.use_daemon = false;
@@ -304,25 +595,112 @@ impl SigilforgeClientBuilder {
         self
     }
 
+    /// Cache up to `capacity` credentials resolved by the fallback for `ttl`.
+    ///
+    /// See [`FallbackResolver::with_cache`].
+    pub fn with_fallback_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.fallback_cache = Some((capacity, ttl));
+        self
+    }
+
     /// Set the request timeout.
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         self
     }
 
+    /// Fetch the token of `service`/`account` in the background as soon as
+    /// the client is built, so the first request for it is served from
+    /// cache.
+    ///
+    /// See [`SigilforgeClient::wait_for_prewarm`]. Pre-warming requires
+    /// `build()` to be called within a Tokio runtime; otherwise it is
+    /// skipped with a warning.
+    pub fn with_prewarm(mut self, service: &str, account: &str) -> Self {
+        self.prewarm
+            .push((service.to_string(), account.to_string()));
+        self
+    }
+
+    /// Pre-warm the token of every account in the daemon, like
+    /// [`with_prewarm`](Self::with_prewarm).
+    pub fn with_prewarm_all(mut self) -> Self {
+        self.prewarm_all = true;
+        self
+    }
+
     /// Build the client.
+    ///
+    /// Returns right away; tokens to pre-warm are fetched by a task spawned
+    /// on the current Tokio runtime. Outside a runtime nothing is pre-warmed.
     pub fn build(self) -> SigilforgeClient {
-        let daemon = if self.use_daemon {
+        let client = self.client();
+        if self.prewarm.is_empty() && !self.prewarm_all {
+            return client;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("build() was called outside a Tokio runtime; not pre-warming tokens");
+            return client;
+        };
+
+        // A second client shares the cache, so the task does not borrow the
+        // one handed out
+        let warmer = SigilforgeClient {
+            token_cache: client.token_cache.clone(),
+            ..self.client()
+        };
+        let accounts = (!self.prewarm_all).then_some(self.prewarm);
+        let (done, prewarmed) = watch::channel(false);
+        runtime.spawn(async move {
+            warmer.prewarm(accounts).await;
+            let _ = done.send(true);
+        });
+
+        SigilforgeClient {
+            prewarmed: Some(prewarmed),
+            ..client
+        }
+    }
+
+    /// A client for the configured daemon and fallback.
+    fn client(&self) -> SigilforgeClient {
+        #[cfg(feature = "grpc")]
+        let grpc = self
+            .grpc_socket
+            .clone()
+            .map(|path| DaemonConnection::grpc(path).with_timeout(self.timeout));
+        #[cfg(not(feature = "grpc"))]
+        let grpc = None;
+
+        let daemon = if !self.use_daemon {
+            None
+        } else if grpc.is_some() {
+            grpc
+        } else if let Some(addr) = self.tcp_addr.clone() {
+            let connection = DaemonConnection::tcp(addr).with_timeout(self.timeout);
+            #[cfg(feature = "tls")]
+            let connection = match self.tls.clone() {
+                Some(config) => connection.with_tls(config),
+                None => connection,
+            };
+            Some(connection)
+        } else {
             self.socket_path
+                .clone()
                 .map(|p| DaemonConnection::new(p).with_timeout(self.timeout))
-        } else {
-            None
         };
 
+        let mut fallback = FallbackResolver::new(self.fallback.clone());
+        if let Some((capacity, ttl)) = self.fallback_cache {
+            fallback = fallback.with_cache(capacity, ttl);
+        }
+
         SigilforgeClient {
             daemon,
-            fallback: FallbackResolver::new(self.fallback),
+            fallback,
             prefer_daemon: self.use_daemon,
+            token_cache: TokenCache::default(),
+            prewarmed: None,
         }
     }
 }
@@ -366,6 +744,31 @@ mod tests {
         unsafe { std::env::remove_var("SIGILFORGE_CLIENTTEST_RESOLVE_API_KEY") };
     }
 
+    #[tokio::test]
+    async fn test_invalidate_fallback() {
+        // SAFETY: Test-only env var manipulation, no concurrent access
+        unsafe { std::env::set_var("CLIENTINV_GITHUB_OSS_API_KEY", "first-key") };
+
+        let client = SigilforgeClientBuilder::new()
+            .no_daemon()
+            .fallback(FallbackConfig::env_vars_with_prefix("CLIENTINV"))
+            .with_fallback_cache(8, Duration::from_secs(60))
+            .build();
+        client.resolve("auth://github/oss/api_key").await.unwrap();
+
+        // SAFETY: Test-only env var manipulation
+        unsafe { std::env::set_var("CLIENTINV_GITHUB_OSS_API_KEY", "second-key") };
+        let result = client.resolve("auth://github/oss/api_key").await.unwrap();
+        assert_eq!(result.value, "first-key");
+
+        assert!(client.invalidate_fallback("auth://github/oss/api_key").unwrap());
+        let result = client.resolve("auth://github/oss/api_key").await.unwrap();
+        assert_eq!(result.value, "second-key");
+
+        // SAFETY: Test-only env var manipulation
+        unsafe { std::env::remove_var("CLIENTINV_GITHUB_OSS_API_KEY") };
+    }
+
     #[tokio::test]
     async fn test_builder() {
         let client = SigilforgeClientBuilder::new()
@@ -384,4 +787,269 @@ mod tests {
         // Daemon likely won't be running in tests
         let _ = client.is_daemon_available().await;
     }
+
+    /// Detect whether the sandbox allows binding Unix sockets. Skip tests if not.
+    #[cfg(unix)]
+    fn can_bind_unix_socket() -> bool {
+        let path = std::env::temp_dir().join("sigilforge-client-watch-permission-check.sock");
+        let _ = std::fs::remove_file(&path);
+        let ok = std::os::unix::net::UnixListener::bind(&path).is_ok();
+        let _ = std::fs::remove_file(&path);
+        ok
+    }
+
+    /// Answer `list_accounts` and `get_token` like a daemon. Each account
+    /// hands out its tokens in turn, then repeats the last one.
+    #[cfg(unix)]
+    fn mock_daemon(socket_path: &std::path::Path, accounts: Vec<(&str, &str, Vec<&str>)>) {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let mut accounts: Vec<(String, String, Vec<String>)> = accounts
+            .into_iter()
+            .map(|(service, account, tokens)| {
+                let tokens = tokens.into_iter().rev().map(str::to_string).collect();
+                (service.to_string(), account.to_string(), tokens)
+            })
+            .collect();
+        let listener = tokio::net::UnixListener::bind(socket_path).unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (reader, mut writer) = stream.into_split();
+                let mut line = String::new();
+                BufReader::new(reader).read_line(&mut line).await.unwrap();
+                let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+
+                let result = match request["method"].as_str() {
+                    Some("list_accounts") => {
+                        let listed: Vec<_> = accounts
+                            .iter()
+                            .map(|(service, account, _)| {
+                                serde_json::json!({ "service": service, "account": account })
+                            })
+                            .collect();
+                        serde_json::json!({ "accounts": listed })
+                    }
+                    _ => {
+                        let params = &request["params"];
+                        let (_, _, tokens) = accounts
+                            .iter_mut()
+                            .find(|(service, account, _)| {
+                                params["service"] == *service && params["account"] == *account
+                            })
+                            .unwrap();
+                        let token = match tokens.len() {
+                            1 => tokens[0].clone(),
+                            _ => tokens.pop().unwrap(),
+                        };
+                        serde_json::json!({ "access_token": token, "token_type": "Bearer" })
+                    }
+                };
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": result,
+                });
+                let _ = writer.write_all(format!("{}\n", response).as_bytes()).await;
+            }
+        });
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_watch_token_yields_changed_tokens() {
+        if !can_bind_unix_socket() {
+            eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+            return;
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("watch.sock");
+        let tokens = vec!["a", "a", "b", "b", "c"];
+        mock_daemon(&socket_path, vec![("github", "work", tokens)]);
+
+        let client = SigilforgeClient::with_socket(&socket_path);
+        let tokens: Vec<String> = client
+            .watch_token("github", "work", Duration::from_millis(10))
+            .take(3)
+            .map(|result| result.unwrap().token)
+            .collect()
+            .await;
+        assert_eq!(tokens, vec!["a", "b", "c"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_watch_any_token_yields_each_account() {
+        if !can_bind_unix_socket() {
+            eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+            return;
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("watch.sock");
+        mock_daemon(
+            &socket_path,
+            vec![
+                ("github", "work", vec!["gh-1", "gh-1", "gh-2"]),
+                ("spotify", "personal", vec!["sp-1"]),
+            ],
+        );
+
+        let client = SigilforgeClient::with_socket(&socket_path);
+        let updates: Vec<(String, String, String)> = client
+            .watch_any_token(Duration::from_millis(10))
+            .take(3)
+            .map(|(service, account, result)| (service, account, result.unwrap().token))
+            .collect()
+            .await;
+        let update = |service: &str, account: &str, token: &str| {
+            (service.to_string(), account.to_string(), token.to_string())
+        };
+        assert_eq!(
+            updates,
+            vec![
+                update("github", "work", "gh-1"),
+                update("spotify", "personal", "sp-1"),
+                update("github", "work", "gh-2"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watch_token_yields_failure_once() {
+        let client = SigilforgeClient::fallback_only(FallbackConfig::None);
+        let results: Vec<_> = client
+            .watch_token("missing", "account", Duration::from_millis(5))
+            .take_until(tokio::time::sleep(Duration::from_millis(50)))
+            .collect()
+            .await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+
+    /// Get a token, asserting that it comes back within a millisecond.
+    async fn get_cached_token(client: &SigilforgeClient, service: &str, account: &str) -> String {
+        let started = std::time::Instant::now();
+        let token = client.get_token(service, account).await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(1));
+        token.token
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_prewarm_serves_token_from_cache() {
+        if !can_bind_unix_socket() {
+            eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+            return;
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("prewarm.sock");
+        mock_daemon(&socket_path, vec![("github", "work", vec!["gh-1", "gh-2"])]);
+
+        let client = SigilforgeClientBuilder::new()
+            .socket_path(&socket_path)
+            .with_prewarm("github", "work")
+            .build();
+        client.wait_for_prewarm().await;
+
+        // The daemon would hand out "gh-2" next
+        assert_eq!(get_cached_token(&client, "github", "work").await, "gh-1");
+        assert_eq!(get_cached_token(&client, "github", "work").await, "gh-1");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_prewarm_all_serves_every_account_from_cache() {
+        if !can_bind_unix_socket() {
+            eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+            return;
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("prewarm.sock");
+        mock_daemon(
+            &socket_path,
+            vec![
+                ("github", "work", vec!["gh-1", "gh-2"]),
+                ("spotify", "personal", vec!["sp-1", "sp-2"]),
+            ],
+        );
+
+        let client = SigilforgeClientBuilder::new()
+            .socket_path(&socket_path)
+            .with_prewarm_all()
+            .build();
+        client.wait_for_prewarm().await;
+
+        assert_eq!(get_cached_token(&client, "github", "work").await, "gh-1");
+        let token = get_cached_token(&client, "spotify", "personal").await;
+        assert_eq!(token, "sp-1");
+    }
+
+    #[tokio::test]
+    async fn test_prewarm_from_fallback() {
+        // SAFETY: Test-only env var manipulation, no concurrent access
+        unsafe { std::env::set_var("SIGILFORGE_PREWARM_FALLBACK_TOKEN", "fallback-token") };
+
+        let client = SigilforgeClientBuilder::new()
+            .no_daemon()
+            .fallback(FallbackConfig::env_vars())
+            .with_prewarm("prewarm", "fallback")
+            .build();
+        client.wait_for_prewarm().await;
+
+        // SAFETY: Test-only env var manipulation
+        unsafe { std::env::remove_var("SIGILFORGE_PREWARM_FALLBACK_TOKEN") };
+
+        let token = get_cached_token(&client, "prewarm", "fallback").await;
+        assert_eq!(token, "fallback-token");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_prewarm_without_prewarm() {
+        let client = SigilforgeClient::fallback_only(FallbackConfig::None);
+        tokio::time::timeout(Duration::from_secs(1), client.wait_for_prewarm())
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_token_cache_drops_expiring_tokens() {
+        let cache = TokenCache::default();
+        let soon = chrono::Utc::now() + chrono::Duration::seconds(10);
+        let later = chrono::Utc::now() + chrono::Duration::hours(1);
+        let expiring = AccessToken::bearer("expiring").with_expiry(soon);
+        let fresh = AccessToken::bearer("fresh").with_expiry(later);
+        cache.insert("github".to_string(), "work".to_string(), expiring);
+        cache.insert("spotify".to_string(), "personal".to_string(), fresh);
+
+        assert!(cache.get("github", "work").is_none());
+        assert_eq!(cache.get("spotify", "personal").unwrap().token, "fresh");
+        assert!(cache.get("gitlab", "work").is_none());
+    }
+
+    #[test]
+    fn test_token_cache_drops_old_tokens_without_expiry() {
+        let cache = TokenCache::default();
+        let new = AccessToken::bearer("new");
+        cache.insert("github".to_string(), "work".to_string(), new);
+        let cached_at = Instant::now() - TOKEN_CACHE_MAX_AGE - Duration::from_secs(1);
+        let key = ("spotify".to_string(), "personal".to_string());
+        let old = (AccessToken::bearer("old"), cached_at);
+        cache.tokens.lock().unwrap().insert(key, old);
+
+        assert_eq!(cache.get("github", "work").unwrap().token, "new");
+        assert!(cache.get("spotify", "personal").is_none());
+    }
+
+    #[test]
+    fn test_build_outside_runtime_skips_prewarm() {
+        let client = SigilforgeClientBuilder::new()
+            .no_daemon()
+            .with_prewarm("github", "work")
+            .build();
+        assert!(client.prewarmed.is_none());
+    }
 }
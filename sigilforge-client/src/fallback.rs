@@ -1,9 +1,17 @@
 use crate::resolve::AuthRef;
 use crate::types::{AccessToken, CredentialType, Result, SecretValue, SigilforgeError};
+use lru::LruCache;
 use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::{debug, trace};
 
+/// How long values read from Vault are cached before being fetched again.
+#[cfg(feature = "fallback-vault")]
+pub const VAULT_FALLBACK_CACHE_TTL_SECS: u64 = 300;
+
 /// Configuration for fallback behavior when daemon is unavailable.
 #[derive(Debug, Clone)]
 pub enum FallbackConfig {
@@ -26,6 +34,32 @@ pub enum FallbackConfig {
         path: PathBuf,
     },
 
+    /// Read from a `.env` file, without changing the process environment.
+    ///
+    /// Variables are named as for [`EnvVars`](Self::EnvVars) with the
+    /// default prefix, e.g. `SIGILFORGE_SPOTIFY_PERSONAL_TOKEN`.
+    #[cfg(feature = "fallback-dotenv")]
+    Dotenv {
+        /// Path to the `.env` file.
+        path: PathBuf,
+    },
+
+    /// Read from a HashiCorp Vault KV v2 secrets engine.
+    ///
+    /// Secrets are read from `{address}/v1/{mount}/data/{path_prefix}/{service}/{account}`,
+    /// with one field per credential type (e.g., `token`, `api_key`).
+    #[cfg(feature = "fallback-vault")]
+    Vault {
+        /// Vault server address (e.g., `https://vault.example.com:8200`).
+        address: String,
+        /// Environment variable holding the Vault token (default: "VAULT_TOKEN").
+        token_env_var: String,
+        /// Mount path of the KV v2 engine (e.g., "secret").
+        mount: String,
+        /// Path under the mount where Sigilforge secrets live (default: "sigilforge").
+        path_prefix: String,
+    },
+
     /// Chain multiple fallback strategies.
     ///
     /// Tries each in order until one succeeds.
@@ -73,6 +107,29 @@ impl FallbackConfig {
         Self::ConfigFile { path: path.into() }
     }
 
+    /// Create a `.env` file fallback.
+    #[cfg(feature = "fallback-dotenv")]
+    pub fn dotenv(path: impl Into<PathBuf>) -> Self {
+        Self::Dotenv { path: path.into() }
+    }
+
+    /// Create a fallback reading `.env` in the current directory.
+    #[cfg(feature = "fallback-dotenv")]
+    pub fn default_dotenv() -> Self {
+        Self::dotenv(".env")
+    }
+
+    /// Create a Vault fallback using `VAULT_TOKEN` and the `sigilforge` path prefix.
+    #[cfg(feature = "fallback-vault")]
+    pub fn vault(address: impl Into<String>, mount: impl Into<String>) -> Self {
+        Self::Vault {
+            address: address.into(),
+            token_env_var: "VAULT_TOKEN".to_string(),
+            mount: mount.into(),
+            path_prefix: "sigilforge".to_string(),
+        }
+    }
+
     /// Chain multiple fallback strategies.
     pub fn chain(strategies: Vec<FallbackConfig>) -> Self {
         Self::Chain(strategies)
@@ -86,15 +143,100 @@ fn default_config_path() -> Option<PathBuf> {
         .map(|dirs| dirs.config_dir().join("credentials.toml"))
 }
 
+/// Cache of resolved fallback credentials, keyed by `auth://` URI.
+///
+/// Entries older than `ttl` are resolved again; the least recently used
+/// entry is evicted once the cache is full.
+#[derive(Debug, Clone)]
+pub struct FallbackResolverCache {
+    cache: Arc<Mutex<LruCache<String, (SecretValue, Instant)>>>,
+    ttl: Duration,
+}
+
+impl FallbackResolverCache {
+    /// Create a cache holding up to `capacity` credentials for `ttl` each.
+    pub fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(LruCache::new(capacity))),
+            ttl,
+        }
+    }
+
+    /// The cached value for `key`, unless it has expired.
+    fn get(&self, key: &str) -> Option<SecretValue> {
+        let mut cache = self.cache.lock().expect("fallback cache lock poisoned");
+
+        match cache.get(key) {
+            Some((value, resolved_at)) if resolved_at.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                cache.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: String, value: SecretValue) {
+        self.cache
+            .lock()
+            .expect("fallback cache lock poisoned")
+            .put(key, (value, Instant::now()));
+    }
+
+    fn remove(&self, key: &str) -> bool {
+        self.cache
+            .lock()
+            .expect("fallback cache lock poisoned")
+            .pop(key)
+            .is_some()
+    }
+}
+
 /// Fallback resolver for when daemon is unavailable.
 pub struct FallbackResolver {
     config: FallbackConfig,
+    cache: Option<FallbackResolverCache>,
+    #[cfg(feature = "fallback-vault")]
+    http_client: reqwest::Client,
+    /// Values read from Vault, keyed by secret URL and field.
+    #[cfg(feature = "fallback-vault")]
+    vault_cache: Mutex<HashMap<String, (Instant, SecretValue)>>,
 }
 
 impl FallbackResolver {
     /// Create a new fallback resolver.
     pub fn new(config: FallbackConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            cache: None,
+            #[cfg(feature = "fallback-vault")]
+            http_client: reqwest::Client::new(),
+            #[cfg(feature = "fallback-vault")]
+            vault_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cache up to `capacity` resolved credentials for `ttl` each.
+    ///
+    /// A `capacity` of zero disables caching. Credentials changed at their
+    /// source are only seen once their entry expires or is dropped with
+    /// [`invalidate`](Self::invalidate).
+    pub fn with_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.cache =
+            NonZeroUsize::new(capacity).map(|capacity| FallbackResolverCache::new(capacity, ttl));
+        self
+    }
+
+    /// Drop the cached value for `reference`, so the next lookup resolves it
+    /// again.
+    ///
+    /// Returns whether a value was cached.
+    pub fn invalidate(&self, reference: &str) -> Result<bool> {
+        let auth_ref = AuthRef::parse(reference)?;
+        Ok(self
+            .cache
+            .as_ref()
+            .is_some_and(|cache| cache.remove(&auth_ref.to_uri())))
     }
 
     /// Try to resolve a token using fallback strategies.
@@ -116,7 +258,19 @@ impl FallbackResolver {
 
     /// Resolve an AuthRef using configured fallback strategies.
     pub async fn resolve_ref(&self, auth_ref: &AuthRef) -> Result<SecretValue> {
-        self.resolve_with_config(&self.config, auth_ref).await
+        let Some(cache) = &self.cache else {
+            return self.resolve_with_config(&self.config, auth_ref).await;
+        };
+
+        let key = auth_ref.to_uri();
+        if let Some(value) = cache.get(&key) {
+            debug!("using cached fallback credential for {}", key);
+            return Ok(value);
+        }
+
+        let value = self.resolve_with_config(&self.config, auth_ref).await?;
+        cache.insert(key, value.clone());
+        Ok(value)
     }
 
     fn resolve_with_config<'a>(
@@ -142,6 +296,20 @@ impl FallbackResolver {
                     self.resolve_from_config_file(path, auth_ref).await
                 }
 
+                #[cfg(feature = "fallback-dotenv")]
+                FallbackConfig::Dotenv { path } => self.resolve_from_dotenv(path, auth_ref),
+
+                #[cfg(feature = "fallback-vault")]
+                FallbackConfig::Vault {
+                    address,
+                    token_env_var,
+                    mount,
+                    path_prefix,
+                } => {
+                    self.resolve_from_vault(address, token_env_var, mount, path_prefix, auth_ref)
+                        .await
+                }
+
                 FallbackConfig::Chain(strategies) => {
                     for strategy in strategies {
                         match self.resolve_with_config(strategy, auth_ref).await {
@@ -162,13 +330,7 @@ impl FallbackResolver {
     }
 
     fn resolve_from_env(&self, prefix: &str, auth_ref: &AuthRef) -> Result<SecretValue> {
-        let env_var = format!(
-            "{}_{}_{}_{}",
-            prefix,
-            auth_ref.service.to_uppercase(),
-            auth_ref.account.to_uppercase(),
-            auth_ref.credential_type.env_suffix()
-        );
+        let env_var = env_var_name(prefix, auth_ref);
 
         debug!("looking for env var: {}", env_var);
 
@@ -186,6 +348,41 @@ impl FallbackResolver {
         }
     }
 
+    /// Look up the `SIGILFORGE_{SERVICE}_{ACCOUNT}_{TYPE}` variable in the
+    /// `.env` file at `path`.
+    ///
+    /// The file is parsed on every call and never loaded into the process
+    /// environment, so its values only apply to this strategy.
+    #[cfg(feature = "fallback-dotenv")]
+    fn resolve_from_dotenv(
+        &self,
+        path: &std::path::Path,
+        auth_ref: &AuthRef,
+    ) -> Result<SecretValue> {
+        let env_var = env_var_name("SIGILFORGE", auth_ref);
+
+        debug!("looking for {} in .env file: {:?}", env_var, path);
+
+        let entries = dotenvy::from_path_iter(path).map_err(|e| {
+            SigilforgeError::ConfigError(format!("failed to read .env file: {}", e))
+        })?;
+
+        for entry in entries {
+            let (key, value) = entry.map_err(|e| {
+                SigilforgeError::ConfigError(format!("failed to parse .env file: {}", e))
+            })?;
+            if key == env_var {
+                debug!("found credential in .env file for {}", env_var);
+                return Ok(SecretValue::new(value));
+            }
+        }
+
+        Err(SigilforgeError::NoFallback {
+            service: auth_ref.service.clone(),
+            account: auth_ref.account.clone(),
+        })
+    }
+
     #[cfg(feature = "fallback-config")]
     async fn resolve_from_config_file(
         &self,
@@ -221,6 +418,144 @@ impl FallbackResolver {
             account: auth_ref.account.clone(),
         })
     }
+
+    #[cfg(feature = "fallback-vault")]
+    async fn resolve_from_vault(
+        &self,
+        address: &str,
+        token_env_var: &str,
+        mount: &str,
+        path_prefix: &str,
+        auth_ref: &AuthRef,
+    ) -> Result<SecretValue> {
+        let secret_path = [
+            path_prefix.trim_matches('/'),
+            auth_ref.service.as_str(),
+            auth_ref.account.as_str(),
+        ]
+        .iter()
+        .filter(|segment| !segment.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("/");
+
+        let url = format!(
+            "{}/v1/{}/data/{}",
+            address.trim_end_matches('/'),
+            mount.trim_matches('/'),
+            secret_path
+        );
+        let field = auth_ref.credential_type.to_string();
+        let cache_key = format!("{}#{}", url, field);
+
+        if let Some(value) = self.cached_vault_value(&cache_key) {
+            debug!("using cached Vault credential for {}", cache_key);
+            return Ok(value);
+        }
+
+        let token = std::env::var(token_env_var).map_err(|_| {
+            SigilforgeError::ConfigError(format!(
+                "Vault token env var {} is not set",
+                token_env_var
+            ))
+        })?;
+
+        debug!("looking for credential in Vault: {}", url);
+
+        let response = self
+            .http_client
+            .get(&url)
+            .header("X-Vault-Token", token)
+            .send()
+            .await
+            .map_err(|e| SigilforgeError::NetworkError(format!("Vault request failed: {}", e)))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(SigilforgeError::NoFallback {
+                service: auth_ref.service.clone(),
+                account: auth_ref.account.clone(),
+            });
+        }
+        if !status.is_success() {
+            return Err(SigilforgeError::NetworkError(format!(
+                "Vault returned {} for {}",
+                status, url
+            )));
+        }
+
+        let body: VaultKvResponse = response.json().await.map_err(|e| {
+            SigilforgeError::NetworkError(format!("invalid Vault KV v2 response: {}", e))
+        })?;
+
+        let value = match body.data.data.get(&field) {
+            Some(serde_json::Value::String(value)) => value.clone(),
+            _ => {
+                return Err(SigilforgeError::NoFallback {
+                    service: auth_ref.service.clone(),
+                    account: auth_ref.account.clone(),
+                })
+            }
+        };
+
+        debug!("found credential in Vault for {}", cache_key);
+
+        let mut secret = SecretValue::new(value);
+        if let Some(metadata) = body.data.metadata {
+            secret = secret.with_metadata(metadata);
+        }
+
+        self.vault_cache
+            .lock()
+            .expect("vault cache lock poisoned")
+            .insert(cache_key, (Instant::now(), secret.clone()));
+
+        Ok(secret)
+    }
+
+    #[cfg(feature = "fallback-vault")]
+    fn cached_vault_value(&self, key: &str) -> Option<SecretValue> {
+        let ttl = Duration::from_secs(VAULT_FALLBACK_CACHE_TTL_SECS);
+        let mut cache = self.vault_cache.lock().expect("vault cache lock poisoned");
+
+        match cache.get(key) {
+            Some((fetched_at, value)) if fetched_at.elapsed() < ttl => Some(value.clone()),
+            Some(_) => {
+                cache.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+/// Name of the variable holding `auth_ref`: `{prefix}_{SERVICE}_{ACCOUNT}_{TYPE}`.
+fn env_var_name(prefix: &str, auth_ref: &AuthRef) -> String {
+    format!(
+        "{}_{}_{}_{}",
+        prefix,
+        auth_ref.service.to_uppercase(),
+        auth_ref.account.to_uppercase(),
+        auth_ref.credential_type.env_suffix()
+    )
+}
+
+/// Vault KV v2 read response (`GET /v1/{mount}/data/{path}`).
+#[cfg(feature = "fallback-vault")]
+#[derive(Debug, serde::Deserialize)]
+struct VaultKvResponse {
+    data: VaultKvData,
+}
+
+#[cfg(feature = "fallback-vault")]
+#[derive(Debug, serde::Deserialize)]
+struct VaultKvData {
+    /// The secret's key/value pairs.
+    #[serde(default)]
+    data: HashMap<String, serde_json::Value>,
+    /// Version metadata (created_time, version, ...).
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
 }
 
 /// TOML config file structure for credentials.
@@ -303,4 +638,344 @@ mod tests {
         // SAFETY: Test-only env var manipulation
         unsafe { std::env::remove_var("BACKUP_GITHUB_OSS_API_KEY") };
     }
+
+    #[tokio::test]
+    async fn test_cache_hit() {
+        // SAFETY: Test-only env var manipulation, no concurrent access
+        unsafe { std::env::set_var("CACHEHIT_GITHUB_OSS_API_KEY", "first-key") };
+
+        let resolver = FallbackResolver::new(FallbackConfig::env_vars_with_prefix("CACHEHIT"))
+            .with_cache(8, Duration::from_secs(60));
+        let result = resolver.resolve("auth://github/oss/api_key").await.unwrap();
+        assert_eq!(result.value, "first-key");
+
+        // SAFETY: Test-only env var manipulation
+        unsafe { std::env::set_var("CACHEHIT_GITHUB_OSS_API_KEY", "second-key") };
+
+        let result = resolver.resolve("auth://github/oss/api_key").await.unwrap();
+        assert_eq!(result.value, "first-key");
+
+        // SAFETY: Test-only env var manipulation
+        unsafe { std::env::remove_var("CACHEHIT_GITHUB_OSS_API_KEY") };
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl_expiry() {
+        // SAFETY: Test-only env var manipulation, no concurrent access
+        unsafe { std::env::set_var("CACHETTL_GITHUB_OSS_API_KEY", "first-key") };
+
+        let resolver = FallbackResolver::new(FallbackConfig::env_vars_with_prefix("CACHETTL"))
+            .with_cache(8, Duration::from_millis(50));
+        let result = resolver.resolve("auth://github/oss/api_key").await.unwrap();
+        assert_eq!(result.value, "first-key");
+
+        // SAFETY: Test-only env var manipulation
+        unsafe { std::env::set_var("CACHETTL_GITHUB_OSS_API_KEY", "second-key") };
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let result = resolver.resolve("auth://github/oss/api_key").await.unwrap();
+        assert_eq!(result.value, "second-key");
+
+        // SAFETY: Test-only env var manipulation
+        unsafe { std::env::remove_var("CACHETTL_GITHUB_OSS_API_KEY") };
+    }
+
+    #[tokio::test]
+    async fn test_cache_invalidate() {
+        // SAFETY: Test-only env var manipulation, no concurrent access
+        unsafe { std::env::set_var("CACHEINV_GITHUB_OSS_API_KEY", "first-key") };
+
+        let resolver = FallbackResolver::new(FallbackConfig::env_vars_with_prefix("CACHEINV"))
+            .with_cache(8, Duration::from_secs(60));
+        resolver.resolve("auth://github/oss/api_key").await.unwrap();
+
+        // SAFETY: Test-only env var manipulation
+        unsafe { std::env::set_var("CACHEINV_GITHUB_OSS_API_KEY", "second-key") };
+
+        assert!(resolver.invalidate("auth://github/oss/api_key").unwrap());
+        assert!(!resolver.invalidate("auth://github/oss/api_key").unwrap());
+        assert!(resolver.invalidate("not-a-uri").is_err());
+
+        let result = resolver.resolve("auth://github/oss/api_key").await.unwrap();
+        assert_eq!(result.value, "second-key");
+
+        // SAFETY: Test-only env var manipulation
+        unsafe { std::env::remove_var("CACHEINV_GITHUB_OSS_API_KEY") };
+    }
+
+    #[tokio::test]
+    async fn test_cache_does_not_store_failures() {
+        let resolver = FallbackResolver::new(FallbackConfig::env_vars_with_prefix("CACHEMISS"))
+            .with_cache(8, Duration::from_secs(60));
+        assert!(resolver.resolve("auth://github/oss/api_key").await.is_err());
+
+        // SAFETY: Test-only env var manipulation, no concurrent access
+        unsafe { std::env::set_var("CACHEMISS_GITHUB_OSS_API_KEY", "late-key") };
+
+        let result = resolver.resolve("auth://github/oss/api_key").await.unwrap();
+        assert_eq!(result.value, "late-key");
+
+        // SAFETY: Test-only env var manipulation
+        unsafe { std::env::remove_var("CACHEMISS_GITHUB_OSS_API_KEY") };
+    }
+
+    #[cfg(feature = "fallback-dotenv")]
+    mod dotenv {
+        use super::*;
+        use std::io::Write;
+        use tempfile::NamedTempFile;
+
+        fn dotenv_file(contents: &str) -> NamedTempFile {
+            let mut file = NamedTempFile::new().unwrap();
+            file.write_all(contents.as_bytes()).unwrap();
+            file
+        }
+
+        #[tokio::test]
+        async fn test_dotenv_fallback() {
+            let file = dotenv_file(
+                "# Local credentials\n\
+                 SIGILFORGE_SPOTIFY_PERSONAL_TOKEN=dotenv-token\n\
+                 SIGILFORGE_OPENAI_DEFAULT_API_KEY=\"sk-dotenv\"\n",
+            );
+            let resolver = FallbackResolver::new(FallbackConfig::dotenv(file.path()));
+
+            let token = resolver.get_token("spotify", "personal").await.unwrap();
+            assert_eq!(token.token, "dotenv-token");
+            let api_key = resolver
+                .resolve("auth://openai/default/api_key")
+                .await
+                .unwrap();
+            assert_eq!(api_key.value, "sk-dotenv");
+
+            let result = resolver.get_token("github", "work").await;
+            assert!(matches!(result, Err(SigilforgeError::NoFallback { .. })));
+        }
+
+        #[tokio::test]
+        async fn test_dotenv_is_isolated_from_process_env() {
+            // SAFETY: Test-only env var manipulation, no concurrent access
+            unsafe { std::env::set_var("SIGILFORGE_DOTENVISO_WORK_TOKEN", "process-token") };
+
+            let file = dotenv_file("SIGILFORGE_DOTENVISO_OTHER_TOKEN=file-token\n");
+            let resolver = FallbackResolver::new(FallbackConfig::dotenv(file.path()));
+
+            // The process environment is not consulted...
+            let result = resolver.get_token("dotenviso", "work").await;
+            assert!(matches!(result, Err(SigilforgeError::NoFallback { .. })));
+
+            // ...and the file does not leak into it
+            let token = resolver.get_token("dotenviso", "other").await.unwrap();
+            assert_eq!(token.token, "file-token");
+            assert!(std::env::var("SIGILFORGE_DOTENVISO_OTHER_TOKEN").is_err());
+
+            // SAFETY: Test-only env var manipulation
+            unsafe { std::env::remove_var("SIGILFORGE_DOTENVISO_WORK_TOKEN") };
+        }
+
+        #[tokio::test]
+        async fn test_dotenv_missing_file_falls_through_chain() {
+            let file = dotenv_file("SIGILFORGE_GITHUB_OSS_API_KEY=second-key\n");
+            let resolver = FallbackResolver::new(FallbackConfig::chain(vec![
+                FallbackConfig::dotenv("/nonexistent/sigilforge/.env"),
+                FallbackConfig::dotenv(file.path()),
+            ]));
+
+            let result = resolver.resolve("auth://github/oss/api_key").await.unwrap();
+            assert_eq!(result.value, "second-key");
+
+            let missing = FallbackResolver::new(FallbackConfig::dotenv("/nonexistent/.env"));
+            let result = missing.resolve("auth://github/oss/api_key").await;
+            assert!(matches!(result, Err(SigilforgeError::ConfigError(_))));
+        }
+
+        #[test]
+        fn test_default_dotenv_reads_current_directory() {
+            match FallbackConfig::default_dotenv() {
+                FallbackConfig::Dotenv { path } => assert_eq!(path, PathBuf::from(".env")),
+                other => panic!("expected Dotenv config, got {:?}", other),
+            }
+        }
+    }
+
+    #[cfg(feature = "fallback-vault")]
+    mod vault {
+        use super::*;
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        fn kv_response() -> serde_json::Value {
+            serde_json::json!({
+                "request_id": "8f0c1e9a-2b34-4c7d-9e1f-0a1b2c3d4e5f",
+                "lease_id": "",
+                "renewable": false,
+                "lease_duration": 0,
+                "data": {
+                    "data": {
+                        "token": "vault-access-token",
+                        "api_key": "vault-api-key"
+                    },
+                    "metadata": {
+                        "created_time": "2025-01-15T10:00:00.000000Z",
+                        "deletion_time": "",
+                        "destroyed": false,
+                        "version": 3
+                    }
+                }
+            })
+        }
+
+        fn vault_config(server: &MockServer, token_env_var: &str) -> FallbackConfig {
+            FallbackConfig::Vault {
+                address: server.uri(),
+                token_env_var: token_env_var.to_string(),
+                mount: "secret".to_string(),
+                path_prefix: "sigilforge".to_string(),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_vault_kv2_parsing() {
+            // SAFETY: Test-only env var manipulation, no concurrent access
+            unsafe { std::env::set_var("VAULT_TEST_TOKEN_PARSE", "s.test-token") };
+
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/v1/secret/data/sigilforge/spotify/personal"))
+                .and(header("X-Vault-Token", "s.test-token"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(kv_response()))
+                .mount(&server)
+                .await;
+
+            let resolver =
+                FallbackResolver::new(vault_config(&server, "VAULT_TEST_TOKEN_PARSE"));
+
+            let token = resolver.get_token("spotify", "personal").await.unwrap();
+            assert_eq!(token.token, "vault-access-token");
+
+            let api_key = resolver
+                .resolve("auth://spotify/personal/api_key")
+                .await
+                .unwrap();
+            assert_eq!(api_key.value, "vault-api-key");
+            assert_eq!(api_key.metadata.unwrap()["version"], 3);
+
+            // SAFETY: Test-only env var manipulation
+            unsafe { std::env::remove_var("VAULT_TEST_TOKEN_PARSE") };
+        }
+
+        #[tokio::test]
+        async fn test_vault_missing_field_and_secret() {
+            // SAFETY: Test-only env var manipulation, no concurrent access
+            unsafe { std::env::set_var("VAULT_TEST_TOKEN_MISSING", "s.test-token") };
+
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/v1/secret/data/sigilforge/spotify/personal"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(kv_response()))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/v1/secret/data/sigilforge/github/work"))
+                .respond_with(
+                    ResponseTemplate::new(404).set_body_json(serde_json::json!({ "errors": [] })),
+                )
+                .mount(&server)
+                .await;
+
+            let resolver =
+                FallbackResolver::new(vault_config(&server, "VAULT_TEST_TOKEN_MISSING"));
+
+            let result = resolver.resolve("auth://spotify/personal/client_secret").await;
+            assert!(matches!(result, Err(SigilforgeError::NoFallback { .. })));
+
+            let result = resolver.get_token("github", "work").await;
+            assert!(matches!(result, Err(SigilforgeError::NoFallback { .. })));
+
+            // SAFETY: Test-only env var manipulation
+            unsafe { std::env::remove_var("VAULT_TEST_TOKEN_MISSING") };
+        }
+
+        #[tokio::test]
+        async fn test_vault_results_are_cached() {
+            // SAFETY: Test-only env var manipulation, no concurrent access
+            unsafe { std::env::set_var("VAULT_TEST_TOKEN_CACHE", "s.test-token") };
+
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/v1/secret/data/sigilforge/spotify/personal"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(kv_response()))
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let resolver =
+                FallbackResolver::new(vault_config(&server, "VAULT_TEST_TOKEN_CACHE"));
+
+            for _ in 0..3 {
+                let token = resolver.get_token("spotify", "personal").await.unwrap();
+                assert_eq!(token.token, "vault-access-token");
+            }
+
+            // SAFETY: Test-only env var manipulation
+            unsafe { std::env::remove_var("VAULT_TEST_TOKEN_CACHE") };
+        }
+
+        #[tokio::test]
+        async fn test_vault_missing_token_env_var() {
+            let server = MockServer::start().await;
+            let resolver =
+                FallbackResolver::new(vault_config(&server, "VAULT_TEST_TOKEN_UNSET"));
+
+            let result = resolver.get_token("spotify", "personal").await;
+            assert!(matches!(result, Err(SigilforgeError::ConfigError(_))));
+        }
+
+        #[tokio::test]
+        async fn test_vault_in_chain_falls_through() {
+            // SAFETY: Test-only env var manipulation, no concurrent access
+            unsafe {
+                std::env::set_var("VAULT_TEST_TOKEN_CHAIN", "s.test-token");
+                std::env::set_var("VAULTCHAIN_GITHUB_OSS_API_KEY", "env-key");
+            }
+
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(403))
+                .mount(&server)
+                .await;
+
+            let resolver = FallbackResolver::new(FallbackConfig::chain(vec![
+                vault_config(&server, "VAULT_TEST_TOKEN_CHAIN"),
+                FallbackConfig::env_vars_with_prefix("VAULTCHAIN"),
+            ]));
+
+            let result = resolver.resolve("auth://github/oss/api_key").await.unwrap();
+            assert_eq!(result.value, "env-key");
+
+            // SAFETY: Test-only env var manipulation
+            unsafe {
+                std::env::remove_var("VAULT_TEST_TOKEN_CHAIN");
+                std::env::remove_var("VAULTCHAIN_GITHUB_OSS_API_KEY");
+            }
+        }
+
+        #[test]
+        fn test_vault_constructor_defaults() {
+            match FallbackConfig::vault("https://vault.example.com:8200", "kv") {
+                FallbackConfig::Vault {
+                    address,
+                    token_env_var,
+                    mount,
+                    path_prefix,
+                } => {
+                    assert_eq!(address, "https://vault.example.com:8200");
+                    assert_eq!(token_env_var, "VAULT_TOKEN");
+                    assert_eq!(mount, "kv");
+                    assert_eq!(path_prefix, "sigilforge");
+                }
+                other => panic!("expected Vault config, got {:?}", other),
+            }
+        }
+    }
 }
@@ -0,0 +1,182 @@
+//! gRPC transport for connecting to a daemon started with
+//! `grpc_socket_path`.
+//!
+//! Requires the `grpc` feature. Each request opens a new HTTP/2 connection
+//! over the Unix socket, like the JSON-RPC transports; use [`proto`]
+//! directly to keep a channel open across requests.
+
+use crate::types::{AccessToken, DaemonHealth, Result, SecretValue, SigilforgeError};
+use chrono::{DateTime, Utc};
+use hyper_util::rt::TokioIo;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::net::UnixStream;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tonic::{Code, Status};
+
+/// Messages and client stub generated from `proto/sigilforge.proto`, a copy
+/// of the daemon's.
+pub mod proto {
+    tonic::include_proto!("sigilforge.v1");
+}
+
+use proto::sigilforge_client::SigilforgeClient as GrpcClient;
+
+/// gRPC socket of a daemon.
+#[derive(Clone)]
+pub(crate) struct GrpcEndpoint {
+    socket_path: PathBuf,
+}
+
+impl GrpcEndpoint {
+    /// Connect to the daemon's gRPC socket at `socket_path`.
+    pub(crate) fn new(socket_path: impl Into<PathBuf>) -> Self {
+        Self {
+            socket_path: socket_path.into(),
+        }
+    }
+
+    /// Get an access token; the daemon refreshes it if needed.
+    pub(crate) async fn get_token(
+        &self,
+        service: &str,
+        account: &str,
+        timeout: Duration,
+    ) -> Result<AccessToken> {
+        let request = proto::GetTokenRequest {
+            service: service.to_string(),
+            account: account.to_string(),
+            force_refresh: false,
+        };
+        let response = self
+            .connect(timeout)
+            .await?
+            .get_token(request)
+            .await
+            .map_err(from_status)?
+            .into_inner();
+
+        Ok(AccessToken {
+            token: response.token,
+            token_type: "Bearer".to_string(),
+            expires_at: response
+                .expires_at
+                .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+                .map(|at| at.with_timezone(&Utc)),
+        })
+    }
+
+    /// List the `(service, account)` pairs the daemon has configured.
+    pub(crate) async fn list_accounts(&self, timeout: Duration) -> Result<Vec<(String, String)>> {
+        let response = self
+            .connect(timeout)
+            .await?
+            .list_accounts(proto::ListAccountsRequest { service: None })
+            .await
+            .map_err(from_status)?
+            .into_inner();
+
+        Ok(response
+            .accounts
+            .into_iter()
+            .map(|info| (info.service, info.account))
+            .collect())
+    }
+
+    /// Resolve an auth:// reference.
+    pub(crate) async fn resolve(&self, reference: &str, timeout: Duration) -> Result<SecretValue> {
+        let request = proto::ResolveRequest {
+            reference: reference.to_string(),
+        };
+        let response = self
+            .connect(timeout)
+            .await?
+            .resolve(request)
+            .await
+            .map_err(from_status)?
+            .into_inner();
+
+        Ok(SecretValue {
+            value: response.value,
+            metadata: None,
+        })
+    }
+
+    /// Check that the daemon answers, using `AccountsStatus`.
+    ///
+    /// The gRPC interface has no version call, so `version` is always `None`.
+    pub(crate) async fn health_check(&self, timeout: Duration) -> Result<DaemonHealth> {
+        let response = self
+            .connect(timeout)
+            .await?
+            .accounts_status(proto::AccountsStatusRequest {})
+            .await
+            .map_err(from_status)?
+            .into_inner();
+
+        Ok(DaemonHealth {
+            running: true,
+            version: None,
+            account_count: Some(response.accounts.len() as u32),
+        })
+    }
+
+    async fn connect(&self, timeout: Duration) -> Result<GrpcClient<Channel>> {
+        // The URI is required but unused; every connection goes to the socket
+        let endpoint = Endpoint::from_static("http://[::]:50051")
+            .connect_timeout(timeout)
+            .timeout(timeout);
+
+        let socket_path = self.socket_path.clone();
+        let connector = tower::service_fn(move |_: Uri| {
+            let socket_path = socket_path.clone();
+            async move {
+                let stream = UnixStream::connect(socket_path).await?;
+                Ok::<_, std::io::Error>(TokioIo::new(stream))
+            }
+        });
+        let channel = endpoint
+            .connect_with_connector(connector)
+            .await
+            .map_err(|e| {
+                SigilforgeError::DaemonUnavailable(format!(
+                    "failed to connect to {:?}: {}",
+                    self.socket_path, e
+                ))
+            })?;
+        Ok(GrpcClient::new(channel))
+    }
+}
+
+/// Map a gRPC status from the daemon to a client error.
+fn from_status(status: Status) -> SigilforgeError {
+    match status.code() {
+        Code::Unavailable => SigilforgeError::DaemonUnavailable(status.message().to_string()),
+        Code::DeadlineExceeded | Code::Cancelled => SigilforgeError::Timeout,
+        code => SigilforgeError::DaemonError {
+            code: code as i32,
+            message: status.message().to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_mapping() {
+        assert!(matches!(
+            from_status(Status::unavailable("down")),
+            SigilforgeError::DaemonUnavailable(message) if message == "down"
+        ));
+        assert!(matches!(
+            from_status(Status::deadline_exceeded("slow")),
+            SigilforgeError::Timeout
+        ));
+        assert!(matches!(
+            from_status(Status::invalid_argument("Account github/work not found")),
+            SigilforgeError::DaemonError { code: 3, .. }
+        ));
+    }
+}
@@ -90,21 +90,29 @@
 //!
 //! - `fallback-env` (default): Enable environment variable fallback
 //! - `fallback-config` (default): Enable TOML config file fallback
+//! - `tls`: Enable TLS for daemon connections over TCP
+//! - `grpc`: Enable the gRPC daemon transport over a Unix socket (needs
+//!   `protoc` to build)
+//! - `fallback-vault`: Enable HashiCorp Vault (KV v2) fallback
 //! - `fusabi-host-functions`: Enable Fusabi host function integration
 
 mod client;
 pub mod fallback;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod resolve;
 pub mod socket;
+#[cfg(feature = "tls")]
+pub mod tls;
 pub mod types;
 
 // Re-export main types from client module
 pub use client::{SigilforgeClient, SigilforgeClientBuilder, TokenProvider};
 
 // Re-export from other modules
-pub use fallback::{FallbackConfig, FallbackResolver};
+pub use fallback::{FallbackConfig, FallbackResolver, FallbackResolverCache};
 pub use resolve::{is_auth_uri, AuthRef};
-pub use socket::{default_socket_path, DaemonConnection};
+pub use socket::{default_socket_path, default_timeout, DaemonConnection};
 pub use types::{AccessToken, CredentialType, DaemonHealth, Result, SecretValue, SigilforgeError};
 
 // Note: Fusabi host function integration is provided through fusabi-stdlib-ext.
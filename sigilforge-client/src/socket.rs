@@ -1,14 +1,18 @@
 use crate::types::{AccessToken, DaemonHealth, Result, SecretValue, SigilforgeError};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tracing::{debug, trace};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tracing::{debug, trace, warn};
 
-#[cfg(windows)]
-use tracing::warn;
+#[cfg(feature = "tls")]
+use std::sync::Arc;
+#[cfg(feature = "tls")]
+use tokio_rustls::rustls::ClientConfig;
 
 #[cfg(unix)]
 use tokio::net::UnixStream;
@@ -56,9 +60,22 @@ struct ResolveResponse {
     metadata: Option<serde_json::Value>,
 }
 
-/// Response for status method.
+/// Response for list_accounts method.
 #[derive(Debug, Deserialize)]
-struct StatusResponse {
+struct ListAccountsResponse {
+    accounts: Vec<AccountEntry>,
+}
+
+/// One account in a list_accounts response.
+#[derive(Debug, Deserialize)]
+struct AccountEntry {
+    service: String,
+    account: String,
+}
+
+/// Response for the health_check method.
+#[derive(Debug, Deserialize)]
+struct HealthResponse {
     version: Option<String>,
     account_count: Option<u32>,
 }
@@ -66,8 +83,29 @@ struct StatusResponse {
 /// Counter for generating unique request IDs.
 static REQUEST_ID: AtomicU64 = AtomicU64::new(1);
 
+/// Environment variable overriding the default socket path.
+pub const SOCKET_ENV_VAR: &str = "SIGILFORGE_SOCKET";
+
+/// Environment variable overriding the default request timeout, in seconds.
+pub const SOCKET_TIMEOUT_ENV_VAR: &str = "SIGILFORGE_SOCKET_TIMEOUT_SECS";
+
+/// Request timeout used when `SIGILFORGE_SOCKET_TIMEOUT_SECS` is not set.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Get the default socket path for the current platform.
+///
+/// The `SIGILFORGE_SOCKET` environment variable overrides the platform default.
 pub fn default_socket_path() -> Option<PathBuf> {
+    socket_path_from_env(std::env::var_os(SOCKET_ENV_VAR))
+}
+
+/// Socket path given the value of `SIGILFORGE_SOCKET`; an empty value is
+/// treated as unset.
+fn socket_path_from_env(env_socket: Option<OsString>) -> Option<PathBuf> {
+    if let Some(path) = env_socket.filter(|path| !path.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
     #[cfg(target_os = "linux")]
     {
         // Try XDG_RUNTIME_DIR first
@@ -97,9 +135,51 @@ pub fn default_socket_path() -> Option<PathBuf> {
     }
 }
 
-/// Client for communicating with the Sigilforge daemon over Unix socket.
+/// Get the default request timeout.
+///
+/// The `SIGILFORGE_SOCKET_TIMEOUT_SECS` environment variable overrides
+/// [`DEFAULT_TIMEOUT`]; values that are not a positive whole number of seconds
+/// are ignored.
+pub fn default_timeout() -> Duration {
+    timeout_from_env(std::env::var(SOCKET_TIMEOUT_ENV_VAR).ok())
+}
+
+/// Request timeout given the value of `SIGILFORGE_SOCKET_TIMEOUT_SECS`.
+fn timeout_from_env(env_timeout: Option<String>) -> Duration {
+    let Some(value) = env_timeout else {
+        return DEFAULT_TIMEOUT;
+    };
+
+    match value.trim().parse::<u64>() {
+        Ok(secs) if secs > 0 => Duration::from_secs(secs),
+        _ => {
+            warn!("ignoring invalid {}={:?}", SOCKET_TIMEOUT_ENV_VAR, value);
+            DEFAULT_TIMEOUT
+        }
+    }
+}
+
+/// TCP endpoint for a daemon started with `listen_tcp`.
+#[derive(Clone)]
+struct TcpEndpoint {
+    /// `host:port` to connect to; the host is also the TLS server name.
+    addr: String,
+    #[cfg(feature = "tls")]
+    tls: Option<Arc<ClientConfig>>,
+}
+
+/// Client for communicating with the Sigilforge daemon over a Unix socket
+/// or TCP.
+///
+/// [`default_socket_path`] honours the `SIGILFORGE_SOCKET` environment
+/// variable, and connections start with the timeout from
+/// `SIGILFORGE_SOCKET_TIMEOUT_SECS` (5 seconds if unset) until
+/// [`with_timeout`](Self::with_timeout) is called.
 pub struct DaemonConnection {
     socket_path: PathBuf,
+    tcp: Option<TcpEndpoint>,
+    #[cfg(feature = "grpc")]
+    grpc: Option<crate::grpc::GrpcEndpoint>,
     timeout: Duration,
 }
 
@@ -108,8 +188,51 @@ impl DaemonConnection {
     pub fn new(socket_path: PathBuf) -> Self {
         Self {
             socket_path,
-            timeout: Duration::from_secs(5),
+            tcp: None,
+            #[cfg(feature = "grpc")]
+            grpc: None,
+            timeout: default_timeout(),
+        }
+    }
+
+    /// Create a connection to a daemon listening on TCP at `host:port`.
+    pub fn tcp(addr: impl Into<String>) -> Self {
+        Self {
+            socket_path: PathBuf::new(),
+            tcp: Some(TcpEndpoint {
+                addr: addr.into(),
+                #[cfg(feature = "tls")]
+                tls: None,
+            }),
+            #[cfg(feature = "grpc")]
+            grpc: None,
+            timeout: default_timeout(),
+        }
+    }
+
+    /// Create a connection to a daemon serving gRPC on the Unix socket at
+    /// `socket_path`.
+    ///
+    /// Requires the `grpc` feature.
+    #[cfg(feature = "grpc")]
+    pub fn grpc(socket_path: impl Into<PathBuf>) -> Self {
+        Self {
+            socket_path: PathBuf::new(),
+            tcp: None,
+            grpc: Some(crate::grpc::GrpcEndpoint::new(socket_path)),
+            timeout: default_timeout(),
+        }
+    }
+
+    /// Wrap the TCP connection in TLS using the given client configuration.
+    ///
+    /// Has no effect on Unix socket connections.
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, config: Arc<ClientConfig>) -> Self {
+        if let Some(tcp) = self.tcp.as_mut() {
+            tcp.tls = Some(config);
         }
+        self
     }
 
     /// Set the request timeout.
@@ -125,19 +248,29 @@ impl DaemonConnection {
 
     /// Health check - verify daemon is running.
     pub async fn health_check(&self) -> Result<DaemonHealth> {
-        let response = self.send_request("status", None).await?;
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.health_check(self.timeout).await;
+        }
+
+        let response = self.send_request("health_check", None).await?;
 
-        let status: StatusResponse = serde_json::from_value(response)?;
+        let health: HealthResponse = serde_json::from_value(response)?;
 
         Ok(DaemonHealth {
             running: true,
-            version: status.version,
-            account_count: status.account_count,
+            version: health.version,
+            account_count: health.account_count,
         })
     }
 
     /// Get an access token from the daemon.
     pub async fn get_token(&self, service: &str, account: &str) -> Result<AccessToken> {
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.get_token(service, account, self.timeout).await;
+        }
+
         let params = serde_json::json!({
             "service": service,
             "account": account
@@ -155,6 +288,12 @@ impl DaemonConnection {
 
     /// Ensure a valid token (refresh if needed).
     pub async fn ensure_token(&self, service: &str, account: &str) -> Result<AccessToken> {
+        // The daemon's GetToken RPC always refreshes expired tokens
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.get_token(service, account, self.timeout).await;
+        }
+
         let params = serde_json::json!({
             "service": service,
             "account": account,
@@ -171,8 +310,30 @@ impl DaemonConnection {
         })
     }
 
+    /// List the `(service, account)` pairs the daemon has configured.
+    pub async fn list_accounts(&self) -> Result<Vec<(String, String)>> {
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.list_accounts(self.timeout).await;
+        }
+
+        let response = self.send_request("list_accounts", None).await?;
+        let list_resp: ListAccountsResponse = serde_json::from_value(response)?;
+
+        Ok(list_resp
+            .accounts
+            .into_iter()
+            .map(|entry| (entry.service, entry.account))
+            .collect())
+    }
+
     /// Resolve an auth:// reference.
     pub async fn resolve(&self, reference: &str) -> Result<SecretValue> {
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.resolve(reference, self.timeout).await;
+        }
+
         let params = serde_json::json!({
             "reference": reference
         });
@@ -187,11 +348,64 @@ impl DaemonConnection {
     }
 
     /// Send a JSON-RPC request to the daemon.
-    #[cfg(unix)]
     async fn send_request(
         &self,
         method: &str,
         params: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        match &self.tcp {
+            Some(tcp) => self.send_tcp_request(tcp, method, params).await,
+            None => self.send_socket_request(method, params).await,
+        }
+    }
+
+    /// Send a JSON-RPC request over TCP, with TLS if configured.
+    async fn send_tcp_request(
+        &self,
+        tcp: &TcpEndpoint,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        debug!("connecting to daemon at tcp://{}", tcp.addr);
+
+        let stream = tokio::time::timeout(self.timeout, TcpStream::connect(&tcp.addr))
+            .await
+            .map_err(|_| SigilforgeError::Timeout)?
+            .map_err(|e| {
+                SigilforgeError::DaemonUnavailable(format!(
+                    "failed to connect to {}: {}",
+                    tcp.addr, e
+                ))
+            })?;
+
+        #[cfg(feature = "tls")]
+        if let Some(config) = &tcp.tls {
+            let host = tcp.addr.rsplit_once(':').map_or(tcp.addr.as_str(), |(h, _)| h);
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            let server_name = tokio_rustls::rustls::pki_types::ServerName::try_from(host)
+                .map_err(|e| SigilforgeError::ConfigError(format!("invalid TLS host: {}", e)))?
+                .to_owned();
+
+            let connector = tokio_rustls::TlsConnector::from(config.clone());
+            let stream = tokio::time::timeout(self.timeout, connector.connect(server_name, stream))
+                .await
+                .map_err(|_| SigilforgeError::Timeout)?
+                .map_err(|e| {
+                    SigilforgeError::DaemonUnavailable(format!("TLS handshake failed: {}", e))
+                })?;
+
+            return self.exchange(stream, method, params).await;
+        }
+
+        self.exchange(stream, method, params).await
+    }
+
+    /// Send a JSON-RPC request over the Unix socket.
+    #[cfg(unix)]
+    async fn send_socket_request(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
     ) -> Result<serde_json::Value> {
         debug!("connecting to daemon at {:?}", self.socket_path);
 
@@ -210,7 +424,20 @@ impl DaemonConnection {
             ))
         })?;
 
-        let (reader, mut writer) = stream.into_split();
+        self.exchange(stream, method, params).await
+    }
+
+    /// Write one request line to `stream` and read one response line back.
+    async fn exchange<S>(
+        &self,
+        stream: S,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let (reader, mut writer) = tokio::io::split(stream);
         let mut reader = BufReader::new(reader);
 
         // Build request
@@ -260,7 +487,7 @@ impl DaemonConnection {
 
     /// Send a JSON-RPC request (Windows named pipe).
     #[cfg(windows)]
-    async fn send_request(
+    async fn send_socket_request(
         &self,
         method: &str,
         params: Option<serde_json::Value>,
@@ -275,7 +502,7 @@ impl DaemonConnection {
 
     /// Stub for non-Unix/Windows platforms.
     #[cfg(not(any(unix, windows)))]
-    async fn send_request(
+    async fn send_socket_request(
         &self,
         _method: &str,
         _params: Option<serde_json::Value>,
@@ -298,6 +525,39 @@ mod tests {
         assert!(path.is_some());
     }
 
+    #[test]
+    fn test_socket_env_overrides() {
+        let env_socket = Some(OsString::from("/run/custom/sigilforge.sock"));
+        assert_eq!(
+            socket_path_from_env(env_socket),
+            Some(PathBuf::from("/run/custom/sigilforge.sock"))
+        );
+        assert_ne!(
+            socket_path_from_env(Some(OsString::new())),
+            Some(PathBuf::new())
+        );
+        assert_eq!(
+            socket_path_from_env(Some(OsString::new())),
+            socket_path_from_env(None)
+        );
+    }
+
+    #[test]
+    fn test_timeout_env_overrides() {
+        assert_eq!(timeout_from_env(Some("42".into())), Duration::from_secs(42));
+        assert_eq!(
+            timeout_from_env(Some(" 42 ".into())),
+            Duration::from_secs(42)
+        );
+        assert_eq!(timeout_from_env(Some("soon".into())), DEFAULT_TIMEOUT);
+        assert_eq!(timeout_from_env(Some("0".into())), DEFAULT_TIMEOUT);
+        assert_eq!(timeout_from_env(None), DEFAULT_TIMEOUT);
+        assert_eq!(
+            DaemonConnection::new(PathBuf::from("/tmp/test.sock")).timeout,
+            default_timeout()
+        );
+    }
+
     #[test]
     fn test_json_rpc_request_serialization() {
         let request = JsonRpcRequest {
@@ -0,0 +1,64 @@
+//! TLS configuration for connecting to a daemon over TCP.
+//!
+//! Requires the `tls` feature.
+
+use crate::types::{Result, SigilforgeError};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use tokio_rustls::rustls::pki_types::CertificateDer;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+
+/// Build a rustls client configuration from PEM files.
+///
+/// - `cert`: PEM file with the client certificate chain followed by its
+///   private key, presented to daemons that require mutual TLS
+/// - `ca`: PEM file of CAs trusted to sign the daemon's certificate
+pub fn client_config(cert: &Path, ca: &Path) -> Result<ClientConfig> {
+    let mut roots = RootCertStore::empty();
+    for ca_cert in load_certs(ca)? {
+        roots.add(ca_cert).map_err(|e| {
+            SigilforgeError::ConfigError(format!(
+                "invalid CA certificate in {}: {}",
+                ca.display(),
+                e
+            ))
+        })?;
+    }
+
+    let certs = load_certs(cert)?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(open(cert)?))
+        .map_err(|e| pem_error(cert, e))?
+        .ok_or_else(|| {
+            SigilforgeError::ConfigError(format!("no private key found in {}", cert.display()))
+        })?;
+
+    ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_client_auth_cert(certs, key)
+        .map_err(|e| SigilforgeError::ConfigError(format!("invalid client certificate: {}", e)))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(open(path)?))
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(|e| pem_error(path, e))?;
+
+    if certs.is_empty() {
+        return Err(SigilforgeError::ConfigError(format!(
+            "no certificates found in {}",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+fn open(path: &Path) -> Result<File> {
+    File::open(path).map_err(|e| {
+        SigilforgeError::ConfigError(format!("failed to open {}: {}", path.display(), e))
+    })
+}
+
+fn pem_error(path: &Path, e: std::io::Error) -> SigilforgeError {
+    SigilforgeError::ConfigError(format!("failed to parse PEM in {}: {}", path.display(), e))
+}
@@ -8,6 +8,52 @@
 //! Accounts are stored at `~/.config/sigilforge/accounts.json` on Linux/macOS
 //! and `%APPDATA%\sigilforge\accounts.json` on Windows.
 //!
+//! # Read-Only Mode
+//!
+//! [`AccountStore::open_read_only`] loads the file without creating it or its
+//! directory, and every method that would write fails with
+//! [`AccountStoreError::ReadOnly`]. Setting `SIGILFORGE_READ_ONLY=1` makes
+//! [`AccountStore::load`] open the store this way, for read-only config
+//! mounts and other restricted environments.
+//!
+//! # Multi-Process Access
+//!
+//! The daemon, the CLI, and the TUI may all open the same file. Writes take
+//! an exclusive lock on `{path}.lock` and reads a shared one, so no process
+//! reads a half-written file or interleaves its write with another's. The
+//! lock does not merge changes: each write replaces the file with the
+//! writer's view of the accounts. Read-only stores do not lock.
+//!
+//! # Watching for External Changes
+//!
+//! With the `watch` feature, [`AccountStore::watch`] and
+//! [`AccountStore::on_change`] reload the store when another process writes
+//! the file, such as `sigilforge add-account` while the daemon is running.
+//!
+//! # Encryption at Rest
+//!
+//! With the `encrypted-account-store` feature, [`AccountStore::open_encrypted`]
+//! keeps the file encrypted with [age](https://age-encryption.org) to an
+//! X25519 identity, which [`AccountStore::generate_identity`] creates. Such a
+//! file cannot be opened without the identity. An existing plaintext store is
+//! converted with [`AccountStore::encrypt_in_place`].
+//!
+//! # Change Events
+//!
+//! [`AccountStore::subscribe`] reports changes made through the store itself
+//! as [`AccountStoreEvent`]s. Changes picked up from disk by a watch, imports,
+//! restores, and migrations are not reported.
+//!
+//! # Snapshots
+//!
+//! [`AccountStore::snapshot`] copies the accounts as they are now, and
+//! [`AccountStore::restore`] puts such a copy back in place of the file.
+//! [`AccountStore::save_snapshot`] keeps snapshots as JSON files in a
+//! directory, by default `snapshots/` next to the store, where
+//! [`AccountStore::list_snapshots`] and [`AccountStore::load_snapshot`] find
+//! them. Snapshot files are kept in the store's format, so those of an
+//! encrypted store are encrypted too.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -23,13 +69,36 @@
 //! store.add_account(account)?;
 //! ```
 
+use crate::migrations::{self, Migration, MigrationDescription, CURRENT_VERSION, MIGRATIONS};
 use crate::model::{Account, AccountId, ServiceId};
+use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use thiserror::Error;
+use tokio::sync::broadcast;
+
+/// Environment variable that makes [`AccountStore::load`] open the store
+/// read-only when set to `1`.
+pub const READ_ONLY_ENV: &str = "SIGILFORGE_READ_ONLY";
+
+/// Environment variable naming a directory to use in place of the platform
+/// configuration directory.
+pub const CONFIG_DIR_ENV: &str = "SIGILFORGE_CONFIG_DIR";
+
+/// How long to wait for another process to release the store's lock file.
+pub const LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Events buffered for each [`AccountStore::subscribe`] receiver.
+///
+/// A receiver that falls further behind loses the oldest events and gets
+/// [`broadcast::error::RecvError::Lagged`].
+pub const EVENT_CAPACITY: usize = 32;
+
+/// How often a contended lock is retried until [`LOCK_TIMEOUT`].
+const LOCK_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(10);
 
 /// Error type for account store operations.
 #[derive(Debug, Error)]
@@ -53,12 +122,169 @@ pub enum AccountStoreError {
     /// Configuration directory not available.
     #[error("configuration directory not available")]
     ConfigDirUnavailable,
+
+    /// The store was written by a newer version of sigilforge.
+    #[error("account store version {found} is newer than supported version {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    /// Account has an empty service or account ID.
+    #[error("invalid account {service}/{account}: {reason}")]
+    InvalidAccount {
+        service: String,
+        account: String,
+        reason: String,
+    },
+
+    /// A schema migration failed.
+    #[error("migration from v{from_version} to v{to_version} failed: {message}")]
+    MigrationFailed {
+        from_version: u32,
+        to_version: u32,
+        message: String,
+    },
+
+    /// The store was opened read-only.
+    #[error("account store {path:?} is read-only")]
+    ReadOnly { path: PathBuf },
+
+    /// Another process held the store's lock file for too long.
+    #[error("timed out after {timeout:?} waiting for lock {path:?}")]
+    LockTimeout {
+        path: PathBuf,
+        timeout: std::time::Duration,
+    },
+
+    /// The store file could not be watched for changes.
+    #[cfg(feature = "watch")]
+    #[error("failed to watch account store: {0}")]
+    Watch(#[from] notify::Error),
+
+    /// The store file or identity could not be encrypted or decrypted.
+    #[cfg(feature = "encrypted-account-store")]
+    #[error("account store encryption error: {0}")]
+    Encryption(String),
+
+    /// No snapshot with this ID is saved in the snapshot directory.
+    #[error("snapshot {id} not found")]
+    SnapshotNotFound { id: String },
+
+    /// A snapshot label contains characters not allowed in its ID.
+    #[error("invalid snapshot label '{label}': use only letters, digits, '-' and '_'")]
+    InvalidSnapshotLabel { label: String },
+}
+
+/// Outcome of [`AccountStore::batch_add`].
+#[derive(Debug, Default)]
+pub struct BatchAddResult {
+    /// Number of accounts added.
+    pub added: usize,
+
+    /// Number of accounts skipped because they already exist.
+    pub skipped: usize,
+
+    /// Accounts that were rejected without aborting the batch.
+    pub errors: Vec<AccountStoreError>,
+}
+
+/// How [`AccountStore::import_json`] treats accounts already in the store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Keep existing accounts and skip imported duplicates.
+    Merge,
+
+    /// Remove all existing accounts before importing.
+    Replace,
+}
+
+/// Outcome of [`AccountStore::import_json`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportResult {
+    /// Number of accounts imported.
+    pub imported: usize,
+
+    /// Number of accounts skipped because they already exist.
+    pub skipped: usize,
+
+    /// Number of existing accounts removed by [`ImportMode::Replace`].
+    pub removed: usize,
+}
+
+/// A change made through an [`AccountStore`].
+///
+/// Received from [`AccountStore::subscribe`].
+#[derive(Debug, Clone)]
+pub enum AccountStoreEvent {
+    /// An account was added.
+    AccountAdded(Account),
+
+    /// An account was removed.
+    AccountRemoved {
+        service: ServiceId,
+        account: AccountId,
+    },
+
+    /// An account's metadata changed, such as its `last_used` time.
+    AccountUpdated(Account),
+
+    /// An account's scopes were replaced.
+    ScopesUpdated {
+        service: ServiceId,
+        account: AccountId,
+        scopes: Vec<String>,
+    },
+}
+
+/// A point-in-time copy of an [`AccountStore`]'s accounts.
+///
+/// Created by [`AccountStore::snapshot`] and put back by
+/// [`AccountStore::restore`]. Serializes to the store file format plus
+/// `created_at`, so older snapshots can be migrated like store files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountStoreSnapshot {
+    /// Schema version of the accounts (see [`crate::migrations`]).
+    pub version: u32,
+
+    /// When the snapshot was taken.
+    pub created_at: DateTime<Utc>,
+
+    /// The accounts at that time.
+    pub accounts: Vec<Account>,
+}
+
+/// A snapshot saved by [`AccountStore::save_snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotMetadata {
+    /// ID to pass to [`AccountStore::load_snapshot`]; also the file name
+    /// without `.json`.
+    pub id: String,
+
+    /// Label given when the snapshot was saved, if any.
+    pub label: Option<String>,
+
+    /// When the snapshot was taken.
+    pub created_at: DateTime<Utc>,
+
+    /// Number of accounts in the snapshot.
+    pub account_count: usize,
+
+    /// The snapshot file.
+    pub path: PathBuf,
+}
+
+/// Contents of a snapshot file.
+#[derive(Serialize, Deserialize)]
+struct SnapshotFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+
+    #[serde(flatten)]
+    snapshot: AccountStoreSnapshot,
 }
 
 /// Internal storage format for accounts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct AccountStoreData {
-    /// Version of the store format (for future migrations).
+    /// Version of the store format (see [`crate::migrations`]).
     version: u32,
 
     /// All stored accounts.
@@ -68,7 +294,7 @@ struct AccountStoreData {
 impl Default for AccountStoreData {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: CURRENT_VERSION,
             accounts: Vec::new(),
         }
     }
@@ -82,89 +308,587 @@ impl Default for AccountStoreData {
 /// # Thread Safety
 ///
 /// This implementation uses interior mutability via `RwLock` and is safe to
-/// share across threads via `Arc`.
+/// share across threads via `Arc`. Other processes are kept out by a file
+/// lock; see [`with_file_locking`](Self::with_file_locking).
 pub struct AccountStore {
     /// Path to the accounts JSON file.
     path: PathBuf,
 
     /// In-memory cache of account data.
     data: Arc<RwLock<AccountStoreData>>,
+
+    /// Schema version of the file on disk.
+    disk_version: Arc<RwLock<u32>>,
+
+    /// Whether mutations are refused.
+    is_read_only: bool,
+
+    /// Whether reads and writes take the `{path}.lock` file lock.
+    file_locking: bool,
+
+    /// How the file is kept on disk.
+    format: FileFormat,
+
+    /// Sends change events to [`subscribe`](Self::subscribe) receivers.
+    events: broadcast::Sender<AccountStoreEvent>,
+
+    /// Watchers registered with [`on_change`](Self::on_change).
+    #[cfg(feature = "watch")]
+    watchers: parking_lot::Mutex<Vec<WatchHandle>>,
+
+    /// Number of times the file has been written (for tests).
+    #[cfg(test)]
+    saves: std::sync::atomic::AtomicUsize,
 }
 
 impl AccountStore {
     /// Get the default storage path for accounts.
     ///
     /// Returns the platform-specific configuration directory path for the
-    /// accounts.json file.
+    /// accounts.json file, or `accounts.json` in `$SIGILFORGE_CONFIG_DIR`
+    /// if that is set.
     pub fn default_path() -> Result<PathBuf, AccountStoreError> {
-        let dirs = directories::ProjectDirs::from("com", "raibid-labs", "sigilforge")
-            .ok_or(AccountStoreError::ConfigDirUnavailable)?;
-
-        let config_dir = dirs.config_dir();
-        Ok(config_dir.join("accounts.json"))
+        config_dir()
+            .map(|dir| dir.join("accounts.json"))
+            .ok_or(AccountStoreError::ConfigDirUnavailable)
     }
 
     /// Load the account store from the default location.
     ///
-    /// Creates the file and parent directories if they don't exist.
+    /// Creates the file and parent directories if they don't exist, unless
+    /// `SIGILFORGE_READ_ONLY=1` is set, in which case the store is opened
+    /// with [`open_read_only`](Self::open_read_only).
     pub fn load() -> Result<Self, AccountStoreError> {
-        let path = Self::default_path()?;
-        Self::load_from_path(path)
+        Self::load_default(Self::default_path()?)
+    }
+
+    /// Like [`load`](Self::load), but with `accounts.json` in `config_dir`
+    /// rather than the default configuration directory.
+    pub fn load_from_config_dir(config_dir: &Path) -> Result<Self, AccountStoreError> {
+        Self::load_default(config_dir.join("accounts.json"))
+    }
+
+    fn load_default(path: PathBuf) -> Result<Self, AccountStoreError> {
+        if read_only_requested() {
+            Self::open_read_only(path)
+        } else {
+            Self::load_from_path(path)
+        }
     }
 
     /// Load the account store from a specific path.
     ///
     /// Creates the file and parent directories if they don't exist.
+    ///
+    /// Files with an older schema are upgraded in memory only; use
+    /// [`run_migrations`](Self::run_migrations) to upgrade the file on disk.
     pub fn load_from_path(path: PathBuf) -> Result<Self, AccountStoreError> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        // Load or create the data file
-        let data = if path.exists() {
-            let contents = fs::read_to_string(&path)?;
-            serde_json::from_str(&contents)?
-        } else {
-            AccountStoreData::default()
-        };
+        Self::open(path, false)
+    }
+
+    /// Load the account store at `path` without ever writing to disk.
+    ///
+    /// Neither the file nor its directory is created; a missing file is an
+    /// empty store. Mutations fail with [`AccountStoreError::ReadOnly`].
+    pub fn open_read_only(path: PathBuf) -> Result<Self, AccountStoreError> {
+        Self::open(path, true)
+    }
+
+    fn open(path: PathBuf, is_read_only: bool) -> Result<Self, AccountStoreError> {
+        Self::open_with_format(path, is_read_only, FileFormat::Plain)
+    }
+
+    fn open_with_format(
+        path: PathBuf,
+        is_read_only: bool,
+        format: FileFormat,
+    ) -> Result<Self, AccountStoreError> {
+        // Creating the lock file would write to a read-only location
+        let file_locking = !is_read_only;
+        let (data, disk_version) = read_data_locked(&path, file_locking, &format)?;
 
         Ok(Self {
             path,
             data: Arc::new(RwLock::new(data)),
+            disk_version: Arc::new(RwLock::new(disk_version)),
+            is_read_only,
+            file_locking,
+            format,
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            #[cfg(feature = "watch")]
+            watchers: parking_lot::Mutex::new(Vec::new()),
+            #[cfg(test)]
+            saves: std::sync::atomic::AtomicUsize::new(0),
+        })
+    }
+
+    /// Receive an event for every later change made through this store.
+    ///
+    /// Events are sent once the change has been written to disk.
+    pub fn subscribe(&self) -> broadcast::Receiver<AccountStoreEvent> {
+        self.events.subscribe()
+    }
+
+    /// Send `event` to subscribers, if there are any.
+    fn notify(&self, event: AccountStoreEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Whether the store was opened read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.is_read_only
+    }
+
+    /// Set whether later reads and writes lock the `{path}.lock` file
+    /// (default: `true`, except for read-only stores).
+    ///
+    /// Only disable locking when no other process uses the file. Loading
+    /// the store has already taken the lock by the time this is called.
+    pub fn with_file_locking(mut self, enabled: bool) -> Self {
+        self.file_locking = enabled;
+        self
+    }
+
+    /// Lock the store file for this process, unless locking is disabled.
+    fn lock(&self, kind: LockKind) -> Result<Option<FileLock>, AccountStoreError> {
+        if !self.file_locking {
+            return Ok(None);
+        }
+        FileLock::acquire(&self.path, kind).map(Some)
+    }
+
+    /// Fail with [`AccountStoreError::ReadOnly`] if the store is read-only.
+    fn ensure_writable(&self) -> Result<(), AccountStoreError> {
+        if self.is_read_only {
+            return Err(AccountStoreError::ReadOnly {
+                path: self.path.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Schema version of the store file on disk.
+    pub fn schema_version(&self) -> u32 {
+        *self.disk_version.read()
+    }
+
+    /// Path the store file is copied to before migrating.
+    pub fn backup_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".bak");
+        PathBuf::from(path)
+    }
+
+    /// Describe the migrations needed to bring the file on disk up to date.
+    pub fn pending_migrations(&self) -> Vec<MigrationDescription> {
+        migrations::pending(MIGRATIONS, self.schema_version())
+            .into_iter()
+            .map(Migration::describe)
+            .collect()
+    }
+
+    /// Upgrade the store file on disk to the current schema version.
+    ///
+    /// The file is copied to [`backup_path`](Self::backup_path) first and
+    /// restored from that backup if any migration fails. Returns the final
+    /// schema version.
+    pub fn run_migrations(&self) -> Result<u32, AccountStoreError> {
+        self.run_migrations_with(MIGRATIONS)
+    }
+
+    fn run_migrations_with(&self, registry: &[Migration]) -> Result<u32, AccountStoreError> {
+        let version = self.schema_version();
+        let pending = migrations::pending(registry, version);
+
+        let Some(target) = pending.last().map(|m| m.to_version()) else {
+            return Ok(version);
+        };
+        self.ensure_writable()?;
+
+        let _lock = self.lock(LockKind::Exclusive)?;
+        let backup = self.backup_path();
+        fs::copy(&self.path, &backup)?;
+
+        match self.migrate_file(&pending) {
+            Ok(data) => {
+                *self.data.write() = data;
+                *self.disk_version.write() = target;
+                Ok(target)
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "account store migration failed, restoring backup");
+                fs::copy(&backup, &self.path)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Apply migrations to the file on disk, writing after each step.
+    fn migrate_file(
+        &self,
+        pending: &[&Migration],
+    ) -> Result<AccountStoreData, AccountStoreError> {
+        let contents = self.format.read(&self.path)?;
+        let mut document: serde_json::Value = serde_json::from_str(&contents)?;
+
+        for migration in pending {
+            apply_migration(migration, &mut document)?;
+            let contents = serde_json::to_string_pretty(&document)?;
+            self.format.write(&self.path, &contents)?;
+        }
+
+        Ok(serde_json::from_value(document)?)
+    }
+
+    /// Apply `change` to the accounts on disk and save the result.
+    ///
+    /// The file is read again under the exclusive file lock, so accounts
+    /// another process saved since this store last read it are kept. The file
+    /// lock is taken before the write guard on `self.data`, so no thread
+    /// waits for the file while holding the accounts. If `change` or the
+    /// write fails, the store keeps the accounts as they were read. Nothing
+    /// is written if `change` leaves them unchanged.
+    fn mutate<T>(
+        &self,
+        change: impl FnOnce(&mut AccountStoreData) -> Result<T, AccountStoreError>,
+    ) -> Result<T, AccountStoreError> {
+        self.ensure_writable()?;
+        let _lock = self.lock(LockKind::Exclusive)?;
+        let mut data = self.data.write();
+        let (current, version) = read_data(&self.path, &self.format)?;
+        *data = current;
+        *self.disk_version.write() = version;
+
+        let mut updated = data.clone();
+        let value = change(&mut updated)?;
+        if serde_json::to_value(&updated)? != serde_json::to_value(&*data)? {
+            self.write_data(&updated)?;
+            *data = updated;
+        }
+        Ok(value)
+    }
+
+    /// Write `data` to disk. Callers hold the exclusive file lock.
+    fn write_data(&self, data: &AccountStoreData) -> Result<(), AccountStoreError> {
+        let contents = serde_json::to_string_pretty(data)?;
+        self.format.write(&self.path, &contents)?;
+        *self.disk_version.write() = data.version;
+
+        #[cfg(test)]
+        self.saves
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Serialize the store as pretty-printed JSON, in the on-disk format.
+    pub fn export_json(&self) -> Result<String, AccountStoreError> {
+        Ok(serde_json::to_string_pretty(&*self.data.read())?)
+    }
+
+    /// Import accounts from JSON produced by [`export_json`](Self::export_json).
+    ///
+    /// Exports from older schema versions are migrated first; exports from a
+    /// newer version are refused with [`AccountStoreError::UnsupportedVersion`].
+    /// Fails without modifying the store if any account is invalid.
+    pub fn import_json(
+        &self,
+        json: &str,
+        mode: ImportMode,
+    ) -> Result<ImportResult, AccountStoreError> {
+        self.ensure_writable()?;
+        let mut document: serde_json::Value = serde_json::from_str(json)?;
+        let version = migrations::detect_version(&document);
+        if version > CURRENT_VERSION {
+            return Err(AccountStoreError::UnsupportedVersion {
+                found: version,
+                supported: CURRENT_VERSION,
+            });
+        }
+        for migration in migrations::pending(MIGRATIONS, version) {
+            apply_migration(migration, &mut document)?;
+        }
+
+        let imported: AccountStoreData = serde_json::from_value(document)?;
+        for account in &imported.accounts {
+            validate_account(account)?;
+        }
+
+        self.mutate(|data| {
+            let mut result = ImportResult::default();
+            if mode == ImportMode::Replace {
+                result.removed = data.accounts.len();
+                data.accounts.clear();
+            }
+
+            for account in imported.accounts {
+                if contains_account(&data.accounts, &account) {
+                    result.skipped += 1;
+                } else {
+                    data.accounts.push(account);
+                    result.imported += 1;
+                }
+            }
+            Ok(result)
         })
     }
 
-    /// Save the current state to disk.
-    fn save(&self) -> Result<(), AccountStoreError> {
+    /// Copy the store file to `dest`.
+    ///
+    /// The copy is written next to `dest` and renamed into place, so `dest`
+    /// is never left half-written. If the store has not been saved yet, the
+    /// in-memory accounts are written instead, in the store's format.
+    pub fn create_backup(&self, dest: &Path) -> Result<(), AccountStoreError> {
+        if self.path.exists() {
+            Ok(replace_file(dest, |temp| {
+                fs::copy(&self.path, temp).map(drop)
+            })?)
+        } else {
+            self.format.write_atomic(dest, &self.export_json()?)
+        }
+    }
+
+    /// Copy the accounts as they are now.
+    pub fn snapshot(&self) -> Result<AccountStoreSnapshot, AccountStoreError> {
         let data = self.data.read();
-        let contents = serde_json::to_string_pretty(&*data)?;
-        fs::write(&self.path, contents)?;
+        Ok(AccountStoreSnapshot {
+            version: data.version,
+            created_at: Utc::now(),
+            accounts: data.accounts.clone(),
+        })
+    }
+
+    /// Replace every account with those in `snapshot`.
+    ///
+    /// Snapshots from older schema versions are migrated first; snapshots
+    /// from a newer version are refused with
+    /// [`AccountStoreError::UnsupportedVersion`]. The file is written next to
+    /// the store and renamed over it, so a failed restore leaves the store as
+    /// it was.
+    pub fn restore(&self, snapshot: AccountStoreSnapshot) -> Result<(), AccountStoreError> {
+        self.ensure_writable()?;
+        if snapshot.version > CURRENT_VERSION {
+            return Err(AccountStoreError::UnsupportedVersion {
+                found: snapshot.version,
+                supported: CURRENT_VERSION,
+            });
+        }
+
+        let mut document = serde_json::to_value(&snapshot)?;
+        for migration in migrations::pending(MIGRATIONS, snapshot.version) {
+            apply_migration(migration, &mut document)?;
+        }
+        let restored: AccountStoreData = serde_json::from_value(document)?;
+        for account in &restored.accounts {
+            validate_account(account)?;
+        }
+
+        let contents = serde_json::to_string_pretty(&restored)?;
+        let lock = self.lock(LockKind::Exclusive)?;
+        self.format.write_atomic(&self.path, &contents)?;
+        drop(lock);
+
+        *self.disk_version.write() = restored.version;
+        *self.data.write() = restored;
         Ok(())
     }
 
+    /// Directory snapshots are saved in by default: `snapshots` next to the
+    /// store file.
+    pub fn snapshot_dir(&self) -> PathBuf {
+        self.path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("snapshots")
+    }
+
+    /// Save `snapshot` in `dir` under an ID made of its creation time and
+    /// `label`, creating `dir` if needed.
+    ///
+    /// An empty `label` saves the snapshot without one.
+    pub fn save_snapshot(
+        &self,
+        snapshot: &AccountStoreSnapshot,
+        dir: &Path,
+        label: &str,
+    ) -> Result<SnapshotMetadata, AccountStoreError> {
+        let valid = label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(AccountStoreError::InvalidSnapshotLabel {
+                label: label.to_string(),
+            });
+        }
+
+        let timestamp = snapshot.created_at.format("%Y%m%dT%H%M%S%.3fZ");
+        let (id, label) = if label.is_empty() {
+            (timestamp.to_string(), None)
+        } else {
+            (format!("{}-{}", timestamp, label), Some(label.to_string()))
+        };
+
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.json", id));
+        if path.exists() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("snapshot {} already exists", id),
+            )
+            .into());
+        }
+
+        let file = SnapshotFile {
+            label: label.clone(),
+            snapshot: snapshot.clone(),
+        };
+        self.format
+            .write_atomic(&path, &serde_json::to_string_pretty(&file)?)?;
+
+        Ok(SnapshotMetadata {
+            id,
+            label,
+            created_at: snapshot.created_at,
+            account_count: snapshot.accounts.len(),
+            path,
+        })
+    }
+
+    /// List the snapshots saved in `dir`, oldest first.
+    ///
+    /// A missing `dir` has no snapshots. Files that are not snapshots are
+    /// skipped with a warning.
+    pub fn list_snapshots(&self, dir: &Path) -> Result<Vec<SnapshotMetadata>, AccountStoreError> {
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            match read_snapshot_file(&path, &self.format) {
+                Ok(file) => snapshots.push(SnapshotMetadata {
+                    id: id.to_string(),
+                    label: file.label,
+                    created_at: file.snapshot.created_at,
+                    account_count: file.snapshot.accounts.len(),
+                    path: path.clone(),
+                }),
+                Err(e) => tracing::warn!("Skipping {}: {}", path.display(), e),
+            }
+        }
+
+        snapshots.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(snapshots)
+    }
+
+    /// Read the snapshot with `id` from `dir`.
+    pub fn load_snapshot(
+        &self,
+        dir: &Path,
+        id: &str,
+    ) -> Result<AccountStoreSnapshot, AccountStoreError> {
+        let path = dir.join(format!("{}.json", id));
+        // An ID with a path separator could name a file outside `dir`
+        if id.contains(['/', '\\']) || !path.exists() {
+            return Err(AccountStoreError::SnapshotNotFound { id: id.to_string() });
+        }
+        Ok(read_snapshot_file(&path, &self.format)?.snapshot)
+    }
+
     /// Add a new account to the store.
     ///
     /// Returns an error if an account with the same service/id already exists.
     pub fn add_account(&self, account: Account) -> Result<(), AccountStoreError> {
-        let mut data = self.data.write();
+        self.mutate(|data| {
+            // Check for duplicates
+            if data
+                .accounts
+                .iter()
+                .any(|a| a.service == account.service && a.id == account.id)
+            {
+                return Err(AccountStoreError::AlreadyExists {
+                    service: account.service.to_string(),
+                    account: account.id.to_string(),
+                });
+            }
+
+            data.accounts.push(account.clone());
+            Ok(())
+        })?;
+        self.notify(AccountStoreEvent::AccountAdded(account));
+        Ok(())
+    }
 
-        // Check for duplicates
-        if data
-            .accounts
-            .iter()
-            .any(|a| a.service == account.service && a.id == account.id)
-        {
-            return Err(AccountStoreError::AlreadyExists {
-                service: account.service.to_string(),
-                account: account.id.to_string(),
-            });
+    /// Add many accounts with a single write to disk.
+    ///
+    /// Accounts that already exist (in the store or earlier in the batch) are
+    /// skipped; accounts with an empty service or account ID are reported in
+    /// [`BatchAddResult::errors`]. Neither aborts the batch.
+    pub fn batch_add(&self, accounts: Vec<Account>) -> Result<BatchAddResult, AccountStoreError> {
+        let (result, added) = self.mutate(|data| {
+            let mut result = BatchAddResult::default();
+            let mut added = Vec::new();
+            for account in accounts {
+                if let Err(e) = validate_account(&account) {
+                    result.errors.push(e);
+                } else if contains_account(&data.accounts, &account) {
+                    result.skipped += 1;
+                } else {
+                    data.accounts.push(account.clone());
+                    added.push(account);
+                    result.added += 1;
+                }
+            }
+            Ok((result, added))
+        })?;
+
+        for account in added {
+            self.notify(AccountStoreEvent::AccountAdded(account));
         }
+        Ok(result)
+    }
 
-        data.accounts.push(account);
-        drop(data);
-
-        self.save()
+    /// Add many accounts as a single transaction.
+    ///
+    /// Fails without modifying the store if any account is invalid or already
+    /// exists, including duplicates within the batch itself.
+    pub fn batch_add_strict(&self, accounts: Vec<Account>) -> Result<usize, AccountStoreError> {
+        self.mutate(|data| {
+            for (index, account) in accounts.iter().enumerate() {
+                validate_account(account)?;
+
+                if contains_account(&data.accounts, account)
+                    || contains_account(&accounts[..index], account)
+                {
+                    return Err(AccountStoreError::AlreadyExists {
+                        service: account.service.to_string(),
+                        account: account.id.to_string(),
+                    });
+                }
+            }
+
+            data.accounts.extend(accounts.iter().cloned());
+            Ok(())
+        })?;
+
+        let added = accounts.len();
+        for account in accounts {
+            self.notify(AccountStoreEvent::AccountAdded(account));
+        }
+        Ok(added)
     }
 
     /// Get an account by service and account ID.
@@ -214,22 +938,24 @@ impl AccountStore {
         service: &ServiceId,
         account: &AccountId,
     ) -> Result<(), AccountStoreError> {
-        let mut data = self.data.write();
-
-        let initial_len = data.accounts.len();
-        data.accounts
-            .retain(|a| &a.service != service || &a.id != account);
-
-        if data.accounts.len() == initial_len {
-            return Err(AccountStoreError::NotFound {
-                service: service.to_string(),
-                account: account.to_string(),
-            });
-        }
-
-        drop(data);
-
-        self.save()
+        self.mutate(|data| {
+            let initial_len = data.accounts.len();
+            data.accounts
+                .retain(|a| &a.service != service || &a.id != account);
+
+            if data.accounts.len() == initial_len {
+                return Err(AccountStoreError::NotFound {
+                    service: service.to_string(),
+                    account: account.to_string(),
+                });
+            }
+            Ok(())
+        })?;
+        self.notify(AccountStoreEvent::AccountRemoved {
+            service: service.clone(),
+            account: account.clone(),
+        });
+        Ok(())
     }
 
     /// Update the last_used timestamp for an account.
@@ -240,21 +966,78 @@ impl AccountStore {
         service: &ServiceId,
         account: &AccountId,
     ) -> Result<(), AccountStoreError> {
-        let mut data = self.data.write();
-
-        let account_entry = data
-            .accounts
-            .iter_mut()
-            .find(|a| &a.service == service && &a.id == account)
-            .ok_or_else(|| AccountStoreError::NotFound {
-                service: service.to_string(),
-                account: account.to_string(),
-            })?;
+        let updated = self.mutate(|data| {
+            let account_entry = data
+                .accounts
+                .iter_mut()
+                .find(|a| &a.service == service && &a.id == account)
+                .ok_or_else(|| AccountStoreError::NotFound {
+                    service: service.to_string(),
+                    account: account.to_string(),
+                })?;
+
+            account_entry.last_used = Some(chrono::Utc::now());
+            Ok(account_entry.clone())
+        })?;
+        self.notify(AccountStoreEvent::AccountUpdated(updated));
+        Ok(())
+    }
 
-        account_entry.last_used = Some(chrono::Utc::now());
-        drop(data);
+    /// Replace the scopes recorded for an account.
+    ///
+    /// Returns an error if the account doesn't exist.
+    pub fn update_scopes(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+        scopes: Vec<String>,
+    ) -> Result<(), AccountStoreError> {
+        self.mutate(|data| {
+            let account_entry = data
+                .accounts
+                .iter_mut()
+                .find(|a| &a.service == service && &a.id == account)
+                .ok_or_else(|| AccountStoreError::NotFound {
+                    service: service.to_string(),
+                    account: account.to_string(),
+                })?;
+
+            account_entry.scopes = scopes.clone();
+            Ok(())
+        })?;
+        self.notify(AccountStoreEvent::ScopesUpdated {
+            service: service.clone(),
+            account: account.clone(),
+            scopes,
+        });
+        Ok(())
+    }
 
-        self.save()
+    /// Record when an account's refresh token expires, after a new refresh
+    /// token was stored for it.
+    ///
+    /// Returns an error if the account doesn't exist.
+    pub fn update_token_refresh_expiry(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(), AccountStoreError> {
+        let updated = self.mutate(|data| {
+            let account_entry = data
+                .accounts
+                .iter_mut()
+                .find(|a| &a.service == service && &a.id == account)
+                .ok_or_else(|| AccountStoreError::NotFound {
+                    service: service.to_string(),
+                    account: account.to_string(),
+                })?;
+
+            account_entry.token_refresh_expiry = expires_at;
+            Ok(account_entry.clone())
+        })?;
+        self.notify(AccountStoreEvent::AccountUpdated(updated));
+        Ok(())
     }
 
     /// Get the storage path for this store.
@@ -263,34 +1046,529 @@ impl AccountStore {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
-
-    fn test_account() -> Account {
-        Account::new(
-            ServiceId::new("spotify"),
-            AccountId::new("personal"),
-            vec!["user-read-email".to_string()],
-        )
+#[cfg(feature = "encrypted-account-store")]
+impl AccountStore {
+    /// Load the store at `path`, kept encrypted to `identity`.
+    ///
+    /// Creates the parent directories if they don't exist; a missing file
+    /// is an empty store. The file is decrypted with `identity` on every
+    /// read, and each write encrypts to its recipient in a temporary file
+    /// that is then renamed over `path`.
+    ///
+    /// With `SIGILFORGE_READ_ONLY=1`, the store is opened read-only like
+    /// [`open_read_only`](Self::open_read_only) and nothing is created.
+    pub fn open_encrypted(
+        path: &Path,
+        identity: age::x25519::Identity,
+    ) -> Result<Self, AccountStoreError> {
+        Self::open_encrypted_with(path, identity, read_only_requested())
     }
 
-    fn test_store() -> (AccountStore, TempDir) {
-        let temp_dir = TempDir::new().unwrap();
-        let path = temp_dir.path().join("accounts.json");
-        let store = AccountStore::load_from_path(path).unwrap();
-        (store, temp_dir)
+    fn open_encrypted_with(
+        path: &Path,
+        identity: age::x25519::Identity,
+        is_read_only: bool,
+    ) -> Result<Self, AccountStoreError> {
+        // Creating directories would write to a read-only location
+        if let Some(parent) = path.parent().filter(|_| !is_read_only) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let format = FileFormat::Encrypted(Arc::new(identity));
+        Self::open_with_format(path.to_path_buf(), is_read_only, format)
     }
 
-    #[test]
-    fn test_add_and_get_account() {
-        let (store, _temp) = test_store();
-        let account = test_account();
+    /// Encrypt the plaintext store at `path` to `identity` in place, then
+    /// open it as [`open_encrypted`](Self::open_encrypted) does.
+    ///
+    /// The file is rewritten under the store's exclusive lock and renamed
+    /// into place, so other processes see either the plaintext or the
+    /// encrypted file. A store that is already encrypted to `identity`, or
+    /// a missing file, is opened unchanged. Backups and snapshots taken
+    /// before are left in plaintext.
+    pub fn encrypt_in_place(
+        path: &Path,
+        identity: age::x25519::Identity,
+    ) -> Result<Self, AccountStoreError> {
+        if read_only_requested() {
+            return Err(AccountStoreError::ReadOnly {
+                path: path.to_path_buf(),
+            });
+        }
 
-        store.add_account(account.clone()).unwrap();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
 
-        let retrieved = store
+        let format = FileFormat::Encrypted(Arc::new(identity));
+        {
+            let _lock = FileLock::acquire(path, LockKind::Exclusive)?;
+            // Anything but JSON is left for decryption to accept or reject
+            let plaintext = fs::read_to_string(path)
+                .ok()
+                .filter(|contents| serde_json::from_str::<serde_json::Value>(contents).is_ok());
+            if let Some(contents) = plaintext {
+                format.write(path, &contents)?;
+            }
+        }
+
+        Self::open_with_format(path.to_path_buf(), false, format)
+    }
+
+    /// Generate a new age identity and save it to a new file at `path`.
+    ///
+    /// The file is in the format `age-keygen` writes and is readable only
+    /// by its owner on Unix. An existing file is never overwritten, since
+    /// stores encrypted to its identity could not be read again.
+    pub fn generate_identity(path: &Path) -> Result<age::x25519::Identity, AccountStoreError> {
+        use age::secrecy::ExposeSecret;
+        use std::io::Write;
+
+        let identity = age::x25519::Identity::generate();
+        let contents = format!(
+            "# created: {}\n# public key: {}\n{}\n",
+            chrono::Utc::now().to_rfc3339(),
+            identity.to_public(),
+            identity.to_string().expose_secret()
+        );
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options.open(path)?.write_all(contents.as_bytes())?;
+
+        Ok(identity)
+    }
+
+    /// Read the age identity in the file at `path`.
+    ///
+    /// Lines starting with `#` are comments, as in files written by
+    /// [`generate_identity`](Self::generate_identity) and `age-keygen`.
+    pub fn load_identity(path: &Path) -> Result<age::x25519::Identity, AccountStoreError> {
+        let contents = fs::read_to_string(path)?;
+        let key = contents
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .ok_or_else(|| {
+                AccountStoreError::Encryption(format!("no identity in {}", path.display()))
+            })?;
+        key.parse().map_err(|e| {
+            AccountStoreError::Encryption(format!("invalid identity in {}: {}", path.display(), e))
+        })
+    }
+}
+
+#[cfg(feature = "watch")]
+impl AccountStore {
+    /// Reload the store whenever its file changes on disk, then call `callback`.
+    ///
+    /// Changes within [`WATCH_DEBOUNCE`] of each other cause a single reload.
+    /// The callback is skipped when the file matches what is already in
+    /// memory, as after this store's own writes. A file that fails to parse,
+    /// such as one caught mid-write, is logged and otherwise ignored.
+    ///
+    /// Watching stops when the returned handle is dropped.
+    pub fn watch(
+        &self,
+        callback: impl Fn() + Send + 'static,
+    ) -> Result<WatchHandle, AccountStoreError> {
+        use notify::Watcher;
+        use std::sync::mpsc::RecvTimeoutError;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<_>| {
+            let _ = tx.send(event);
+        })?;
+
+        // Watch the directory, so a file replaced by a rename is still seen
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        watcher.watch(dir, notify::RecursiveMode::NonRecursive)?;
+
+        let path = self.path.clone();
+        let data = Arc::clone(&self.data);
+        let disk_version = Arc::clone(&self.disk_version);
+        let file_locking = self.file_locking;
+        let format = self.format.clone();
+        std::thread::Builder::new()
+            .name("account-store-watch".to_string())
+            .spawn(move || {
+                while let Ok(event) = rx.recv() {
+                    if !event_touches(&event, &path) {
+                        continue;
+                    }
+
+                    // Wait for the writes to settle; the channel closes when
+                    // the watcher is dropped
+                    loop {
+                        match rx.recv_timeout(WATCH_DEBOUNCE) {
+                            Ok(_) => continue,
+                            Err(RecvTimeoutError::Timeout) => break,
+                            Err(RecvTimeoutError::Disconnected) => return,
+                        }
+                    }
+
+                    match reload(&path, &data, &disk_version, file_locking, &format) {
+                        Ok(true) => callback(),
+                        Ok(false) => {}
+                        Err(e) => tracing::warn!(error = %e, "failed to reload account store"),
+                    }
+                }
+            })?;
+
+        Ok(WatchHandle { _watcher: watcher })
+    }
+
+    /// Like [`watch`](Self::watch), but keeps watching for as long as the
+    /// store lives.
+    pub fn on_change(&self, callback: impl Fn() + Send + 'static) -> Result<(), AccountStoreError> {
+        let handle = self.watch(callback)?;
+        self.watchers.lock().push(handle);
+        Ok(())
+    }
+}
+
+/// How long [`AccountStore::watch`] waits for changes to stop before reloading.
+#[cfg(feature = "watch")]
+pub const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Watches an account store file until dropped.
+///
+/// Returned by [`AccountStore::watch`].
+#[cfg(feature = "watch")]
+#[derive(Debug)]
+pub struct WatchHandle {
+    _watcher: notify::RecommendedWatcher,
+}
+
+/// Whether a watcher event may have changed the file at `path`.
+#[cfg(feature = "watch")]
+fn event_touches(event: &notify::Result<notify::Event>, path: &Path) -> bool {
+    use notify::event::{AccessKind, AccessMode, EventKind};
+
+    let Ok(event) = event else {
+        // Events may have been dropped; check the file to be safe
+        return true;
+    };
+    if let EventKind::Access(kind) = event.kind {
+        if kind != AccessKind::Close(AccessMode::Write) {
+            return false;
+        }
+    }
+    event
+        .paths
+        .iter()
+        .any(|changed| changed.file_name() == path.file_name())
+}
+
+/// Replace `data` with the contents of `path`, returning whether it changed.
+#[cfg(feature = "watch")]
+fn reload(
+    path: &Path,
+    data: &RwLock<AccountStoreData>,
+    disk_version: &RwLock<u32>,
+    file_locking: bool,
+    format: &FileFormat,
+) -> Result<bool, AccountStoreError> {
+    let _lock = if file_locking {
+        Some(FileLock::acquire(path, LockKind::Shared)?)
+    } else {
+        None
+    };
+    // Read under the data lock, so a write this store makes in the meantime
+    // is not replaced with the older file
+    let mut current = data.write();
+    let (new_data, version) = read_data(path, format)?;
+    *disk_version.write() = version;
+
+    if serde_json::to_value(&*current)? == serde_json::to_value(&new_data)? {
+        return Ok(false);
+    }
+    *current = new_data;
+    Ok(true)
+}
+
+/// [`read_data`] under a shared lock, if `file_locking` is set.
+fn read_data_locked(
+    path: &Path,
+    file_locking: bool,
+    format: &FileFormat,
+) -> Result<(AccountStoreData, u32), AccountStoreError> {
+    let _lock = if file_locking {
+        Some(FileLock::acquire(path, LockKind::Shared)?)
+    } else {
+        None
+    };
+    read_data(path, format)
+}
+
+/// Whether a [`FileLock`] excludes other readers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LockKind {
+    Shared,
+    Exclusive,
+}
+
+/// An advisory lock on the `{path}.lock` file next to a store, held until
+/// dropped.
+///
+/// A separate file is locked because the store file itself is replaced by
+/// each write.
+struct FileLock {
+    file: fs::File,
+}
+
+impl FileLock {
+    /// Lock the lock file of the store at `store_path`, retrying until
+    /// [`LOCK_TIMEOUT`] while another process holds a conflicting lock.
+    fn acquire(store_path: &Path, kind: LockKind) -> Result<Self, AccountStoreError> {
+        let mut path = store_path.as_os_str().to_owned();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+        let deadline = std::time::Instant::now() + LOCK_TIMEOUT;
+        loop {
+            // Called through the trait: `std::fs::File` has inherent
+            // methods of the same names in newer toolchains
+            let result = match kind {
+                LockKind::Shared => fs2::FileExt::try_lock_shared(&file),
+                LockKind::Exclusive => fs2::FileExt::try_lock_exclusive(&file),
+            };
+            match result {
+                Ok(()) => return Ok(Self { file }),
+                Err(e) if e.raw_os_error() != fs2::lock_contended_error().raw_os_error() => {
+                    return Err(e.into());
+                }
+                Err(_) if std::time::Instant::now() >= deadline => {
+                    return Err(AccountStoreError::LockTimeout {
+                        path,
+                        timeout: LOCK_TIMEOUT,
+                    });
+                }
+                Err(_) => std::thread::sleep(LOCK_RETRY_INTERVAL),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs2::FileExt::unlock(&self.file);
+    }
+}
+
+/// Read and migrate (in memory) the store file at `path`, or an empty store
+/// if there is none yet. Also returns the schema version of the file.
+fn read_data(
+    path: &Path,
+    format: &FileFormat,
+) -> Result<(AccountStoreData, u32), AccountStoreError> {
+    if !path.exists() {
+        return Ok((AccountStoreData::default(), CURRENT_VERSION));
+    }
+
+    let contents = format.read(path)?;
+    let mut document: serde_json::Value = serde_json::from_str(&contents)?;
+    let version = migrations::detect_version(&document);
+
+    if version > CURRENT_VERSION {
+        return Err(AccountStoreError::UnsupportedVersion {
+            found: version,
+            supported: CURRENT_VERSION,
+        });
+    }
+
+    for migration in migrations::pending(MIGRATIONS, version) {
+        apply_migration(migration, &mut document)?;
+    }
+
+    Ok((serde_json::from_value(document)?, version))
+}
+
+/// How a store file is kept on disk.
+#[derive(Clone)]
+enum FileFormat {
+    /// Pretty-printed JSON.
+    Plain,
+
+    /// JSON encrypted with age to the identity's recipient.
+    #[cfg(feature = "encrypted-account-store")]
+    Encrypted(Arc<age::x25519::Identity>),
+}
+
+impl FileFormat {
+    /// Read the JSON document in the file at `path`.
+    fn read(&self, path: &Path) -> Result<String, AccountStoreError> {
+        match self {
+            FileFormat::Plain => Ok(fs::read_to_string(path)?),
+            #[cfg(feature = "encrypted-account-store")]
+            FileFormat::Encrypted(identity) => {
+                let ciphertext = fs::read(path)?;
+                if serde_json::from_slice::<serde_json::Value>(&ciphertext).is_ok() {
+                    return Err(AccountStoreError::Encryption(format!(
+                        "{} is not encrypted yet; encrypt it in place first",
+                        path.display()
+                    )));
+                }
+                let plaintext = age::decrypt(identity.as_ref(), &ciphertext).map_err(|e| {
+                    AccountStoreError::Encryption(format!(
+                        "failed to decrypt {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                String::from_utf8(plaintext).map_err(|e| {
+                    AccountStoreError::Encryption(format!("{} is not UTF-8: {}", path.display(), e))
+                })
+            }
+        }
+    }
+
+    /// Like [`write`](Self::write), but `contents` is written next to `path`
+    /// and renamed into place, so `path` is never left half-written.
+    fn write_atomic(&self, path: &Path, contents: &str) -> Result<(), AccountStoreError> {
+        match self {
+            FileFormat::Plain => Ok(replace_file(path, |temp| fs::write(temp, contents))?),
+            // Encrypted files are always renamed into place
+            #[cfg(feature = "encrypted-account-store")]
+            FileFormat::Encrypted(_) => self.write(path, contents),
+        }
+    }
+
+    /// Write the JSON document `contents` to the file at `path`.
+    fn write(&self, path: &Path, contents: &str) -> Result<(), AccountStoreError> {
+        match self {
+            FileFormat::Plain => Ok(fs::write(path, contents)?),
+            #[cfg(feature = "encrypted-account-store")]
+            FileFormat::Encrypted(identity) => {
+                let ciphertext = age::encrypt(&identity.to_public(), contents.as_bytes())
+                    .map_err(|e| AccountStoreError::Encryption(e.to_string()))?;
+
+                // Renamed into place, so readers never see a partial ciphertext
+                Ok(replace_file(path, |temp| fs::write(temp, ciphertext))?)
+            }
+        }
+    }
+}
+
+/// Write `path` by calling `write` with a temporary file next to it, then
+/// renaming that into place, so `path` is never left half-written.
+fn replace_file(
+    path: &Path,
+    write: impl FnOnce(&Path) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    if let Err(e) = write(&temp).and_then(|()| fs::rename(&temp, path)) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Read the snapshot file at `path`, kept in `format`.
+fn read_snapshot_file(path: &Path, format: &FileFormat) -> Result<SnapshotFile, AccountStoreError> {
+    Ok(serde_json::from_str(&format.read(path)?)?)
+}
+
+/// The configuration directory: `$SIGILFORGE_CONFIG_DIR` if that is set,
+/// otherwise the platform-specific one.
+pub fn config_dir() -> Option<PathBuf> {
+    config_dir_from(std::env::var_os(CONFIG_DIR_ENV))
+}
+
+/// [`config_dir`], with `env` as the value of `$SIGILFORGE_CONFIG_DIR`.
+fn config_dir_from(env: Option<std::ffi::OsString>) -> Option<PathBuf> {
+    env.filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            directories::ProjectDirs::from("com", "raibid-labs", "sigilforge")
+                .map(|dirs| dirs.config_dir().to_path_buf())
+        })
+}
+
+/// Whether `SIGILFORGE_READ_ONLY=1` is set.
+fn read_only_requested() -> bool {
+    std::env::var(READ_ONLY_ENV).is_ok_and(|value| value == "1")
+}
+
+fn contains_account(accounts: &[Account], account: &Account) -> bool {
+    accounts
+        .iter()
+        .any(|a| a.service == account.service && a.id == account.id)
+}
+
+fn validate_account(account: &Account) -> Result<(), AccountStoreError> {
+    let reason = if account.service.as_str().is_empty() {
+        "service ID is empty"
+    } else if account.id.as_str().is_empty() {
+        "account ID is empty"
+    } else {
+        return Ok(());
+    };
+
+    Err(AccountStoreError::InvalidAccount {
+        service: account.service.to_string(),
+        account: account.id.to_string(),
+        reason: reason.to_string(),
+    })
+}
+
+fn apply_migration(
+    migration: &Migration,
+    document: &mut serde_json::Value,
+) -> Result<(), AccountStoreError> {
+    migrations::apply_one(migration, document).map_err(|message| {
+        AccountStoreError::MigrationFailed {
+            from_version: migration.from_version,
+            to_version: migration.to_version(),
+            message,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::CredentialSource;
+    use tempfile::TempDir;
+
+    fn test_account() -> Account {
+        Account::new(
+            ServiceId::new("spotify"),
+            AccountId::new("personal"),
+            vec!["user-read-email".to_string()],
+        )
+    }
+
+    fn test_store() -> (AccountStore, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts.json");
+        let store = AccountStore::load_from_path(path).unwrap();
+        (store, temp_dir)
+    }
+
+    #[test]
+    fn test_add_and_get_account() {
+        let (store, _temp) = test_store();
+        let account = test_account();
+
+        store.add_account(account.clone()).unwrap();
+
+        let retrieved = store
             .get_account(&account.service, &account.id)
             .unwrap()
             .unwrap();
@@ -451,4 +1729,835 @@ mod tests {
             .unwrap();
         assert!(retrieved.last_used.is_some());
     }
+
+    #[test]
+    fn test_read_only_rejects_mutations() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts.json");
+        AccountStore::load_from_path(path.clone())
+            .unwrap()
+            .add_account(test_account())
+            .unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+
+        let store = AccountStore::open_read_only(path.clone()).unwrap();
+        assert!(store.is_read_only());
+        let account = test_account();
+        let stored = store.get_account(&account.service, &account.id).unwrap();
+        assert!(stored.is_some());
+
+        let other = Account::new(ServiceId::new("github"), AccountId::new("work"), vec![]);
+        let results = [
+            store.add_account(other.clone()),
+            store.remove_account(&account.service, &account.id),
+            store.update_last_used(&account.service, &account.id),
+            store.batch_add(vec![other.clone()]).map(drop),
+            store.batch_add_strict(vec![other]).map(drop),
+            store
+                .import_json(&store.export_json().unwrap(), ImportMode::Replace)
+                .map(drop),
+        ];
+        for result in results {
+            assert!(matches!(result, Err(AccountStoreError::ReadOnly { .. })));
+        }
+
+        assert_eq!(store.list_accounts(None).unwrap().len(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    }
+
+    #[test]
+    fn test_config_dir_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("custom");
+
+        assert_eq!(config_dir_from(Some(dir.clone().into())), Some(dir.clone()));
+        assert_eq!(config_dir_from(Some("".into())), config_dir_from(None));
+
+        let store = AccountStore::load_from_config_dir(&dir).unwrap();
+        store.add_account(test_account()).unwrap();
+        assert_eq!(store.path(), &dir.join("accounts.json"));
+        assert!(dir.join("accounts.json").exists());
+    }
+
+    #[test]
+    fn test_read_only_missing_file_creates_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("config");
+
+        let store = AccountStore::open_read_only(dir.join("accounts.json")).unwrap();
+        assert!(store.list_accounts(None).unwrap().is_empty());
+        assert!(store.add_account(test_account()).is_err());
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_read_only_refuses_migrations() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts.json");
+        let original = write_legacy_store(&path);
+
+        let store = AccountStore::open_read_only(path.clone()).unwrap();
+        assert_eq!(store.list_accounts(None).unwrap().len(), 1);
+        assert!(matches!(
+            store.run_migrations(),
+            Err(AccountStoreError::ReadOnly { .. })
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    fn write_legacy_store(path: &PathBuf) -> String {
+        let legacy = serde_json::to_string_pretty(&vec![test_account()]).unwrap();
+        fs::write(path, &legacy).unwrap();
+        legacy
+    }
+
+    #[test]
+    fn test_migrations_noop_when_current() {
+        let (store, _temp) = test_store();
+        store.add_account(test_account()).unwrap();
+
+        assert_eq!(store.schema_version(), CURRENT_VERSION);
+        assert!(store.pending_migrations().is_empty());
+        assert_eq!(store.run_migrations().unwrap(), CURRENT_VERSION);
+        assert!(!store.backup_path().exists());
+    }
+
+    #[test]
+    fn test_run_migrations_upgrades_legacy_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts.json");
+        let legacy = write_legacy_store(&path);
+
+        let store = AccountStore::load_from_path(path.clone()).unwrap();
+        assert_eq!(store.schema_version(), 0);
+        assert_eq!(store.pending_migrations().len(), MIGRATIONS.len());
+
+        // Legacy accounts are readable before migrating
+        assert_eq!(store.list_accounts(None).unwrap().len(), 1);
+
+        assert_eq!(store.run_migrations().unwrap(), CURRENT_VERSION);
+        assert!(store.pending_migrations().is_empty());
+        assert_eq!(fs::read_to_string(store.backup_path()).unwrap(), legacy);
+
+        let reloaded = AccountStore::load_from_path(path).unwrap();
+        assert_eq!(reloaded.schema_version(), CURRENT_VERSION);
+        let accounts = reloaded.list_accounts(None).unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].source, CredentialSource::Unknown);
+    }
+
+    #[test]
+    fn test_run_migrations_rolls_back_on_failure() {
+        fn fail(_: &mut serde_json::Value) -> Result<(), String> {
+            Err("injected failure".to_string())
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts.json");
+        let legacy = write_legacy_store(&path);
+
+        let registry = [
+            MIGRATIONS[0],
+            Migration {
+                from_version: 1,
+                description: "always fails",
+                apply: fail,
+            },
+        ];
+
+        let store = AccountStore::load_from_path(path.clone()).unwrap();
+        let result = store.run_migrations_with(&registry);
+
+        assert!(matches!(
+            result,
+            Err(AccountStoreError::MigrationFailed {
+                from_version: 1,
+                to_version: 2,
+                ..
+            })
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), legacy);
+        assert_eq!(store.schema_version(), 0);
+    }
+
+    #[test]
+    fn test_load_rejects_newer_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts.json");
+        fs::write(&path, r#"{ "version": 999, "accounts": [] }"#).unwrap();
+
+        let result = AccountStore::load_from_path(path);
+        assert!(matches!(
+            result,
+            Err(AccountStoreError::UnsupportedVersion { found: 999, .. })
+        ));
+    }
+
+    fn saves(store: &AccountStore) -> usize {
+        store.saves.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    fn batch() -> Vec<Account> {
+        vec![
+            Account::new(ServiceId::new("spotify"), AccountId::new("personal"), vec![]),
+            Account::new(ServiceId::new("spotify"), AccountId::new("work"), vec![]),
+            Account::new(ServiceId::new("github"), AccountId::new("main"), vec![]),
+        ]
+    }
+
+    #[test]
+    fn test_batch_add_writes_once() {
+        let (store, _temp) = test_store();
+
+        let result = store.batch_add(batch()).unwrap();
+
+        assert_eq!(result.added, 3);
+        assert_eq!(result.skipped, 0);
+        assert!(result.errors.is_empty());
+        assert_eq!(saves(&store), 1);
+
+        let reloaded = AccountStore::load_from_path(store.path().clone()).unwrap();
+        assert_eq!(reloaded.list_accounts(None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_batch_add_skips_duplicates() {
+        let (store, _temp) = test_store();
+        store.add_account(test_account()).unwrap();
+
+        let mut accounts = batch();
+        accounts.push(Account::new(ServiceId::new("github"), AccountId::new("main"), vec![]));
+        accounts.push(Account::new(ServiceId::new(""), AccountId::new("orphan"), vec![]));
+
+        let result = store.batch_add(accounts).unwrap();
+
+        // spotify/personal exists already; github/main appears twice
+        assert_eq!(result.added, 2);
+        assert_eq!(result.skipped, 2);
+        assert_eq!(result.errors.len(), 1);
+        assert!(matches!(
+            result.errors[0],
+            AccountStoreError::InvalidAccount { .. }
+        ));
+        assert_eq!(store.list_accounts(None).unwrap().len(), 3);
+        assert_eq!(saves(&store), 2);
+    }
+
+    #[test]
+    fn test_batch_add_nothing_new_skips_write() {
+        let (store, _temp) = test_store();
+        store.batch_add(batch()).unwrap();
+
+        let result = store.batch_add(batch()).unwrap();
+
+        assert_eq!(result.added, 0);
+        assert_eq!(result.skipped, 3);
+        assert_eq!(saves(&store), 1);
+    }
+
+    #[test]
+    fn test_batch_add_strict_rolls_back_on_duplicate() {
+        let (store, _temp) = test_store();
+        store.add_account(test_account()).unwrap();
+
+        let result = store.batch_add_strict(batch());
+
+        assert!(matches!(
+            result,
+            Err(AccountStoreError::AlreadyExists { .. })
+        ));
+        assert_eq!(store.list_accounts(None).unwrap().len(), 1);
+        assert_eq!(saves(&store), 1);
+    }
+
+    #[test]
+    fn test_batch_add_strict_rejects_duplicates_within_batch() {
+        let (store, _temp) = test_store();
+
+        let mut accounts = batch();
+        accounts.push(Account::new(ServiceId::new("github"), AccountId::new("main"), vec![]));
+
+        assert!(store.batch_add_strict(accounts).is_err());
+        assert!(store.list_accounts(None).unwrap().is_empty());
+
+        assert_eq!(store.batch_add_strict(batch()).unwrap(), 3);
+        assert_eq!(saves(&store), 1);
+    }
+
+    #[test]
+    fn test_import_rejects_newer_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+
+        let err = store
+            .import_json(r#"{ "version": 999, "accounts": [] }"#, ImportMode::Merge)
+            .unwrap_err();
+        assert!(matches!(err, AccountStoreError::UnsupportedVersion { found: 999, .. }));
+        assert_eq!(saves(&store), 0);
+    }
+
+    #[test]
+    fn test_import_migrates_legacy_export() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+
+        let legacy = serde_json::json!({ "accounts": [test_account()] }).to_string();
+        let result = store.import_json(&legacy, ImportMode::Merge).unwrap();
+
+        assert_eq!(result.imported, 1);
+        assert_eq!(store.schema_version(), CURRENT_VERSION);
+    }
+
+    #[test]
+    fn test_import_invalid_account_changes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+        store.add_account(test_account()).unwrap();
+
+        let invalid = Account::new(ServiceId::new(""), AccountId::new("x"), vec![]);
+        let json = serde_json::json!({ "version": CURRENT_VERSION, "accounts": [invalid] });
+        let err = store
+            .import_json(&json.to_string(), ImportMode::Replace)
+            .unwrap_err();
+
+        assert!(matches!(err, AccountStoreError::InvalidAccount { .. }));
+        assert_eq!(store.list_accounts(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_create_backup_before_first_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+        let dest = temp_dir.path().join("backup.json");
+
+        store.create_backup(&dest).unwrap();
+
+        let backup: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&dest).unwrap()).unwrap();
+        assert_eq!(backup["version"], CURRENT_VERSION);
+        assert!(!temp_dir.path().join("backup.json.tmp").exists());
+    }
+
+    fn account_names(store: &AccountStore) -> Vec<String> {
+        let mut names: Vec<String> = store
+            .list_accounts(None)
+            .unwrap()
+            .iter()
+            .map(|a| format!("{}/{}", a.service, a.id))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_snapshot_modify_restore() {
+        let (store, temp_dir) = test_store();
+        store.add_account(test_account()).unwrap();
+        let snapshot = store.snapshot().unwrap();
+        assert_eq!(snapshot.version, CURRENT_VERSION);
+
+        store
+            .remove_account(&ServiceId::new("spotify"), &AccountId::new("personal"))
+            .unwrap();
+        let github = Account::new(ServiceId::new("github"), AccountId::new("work"), vec![]);
+        store.add_account(github).unwrap();
+
+        store.restore(snapshot).unwrap();
+        assert_eq!(account_names(&store), vec!["spotify/personal"]);
+        assert!(!temp_dir.path().join("accounts.json.tmp").exists());
+
+        // The restored accounts are on disk, not only in memory
+        let reloaded = AccountStore::load_from_path(store.path().clone()).unwrap();
+        assert_eq!(account_names(&reloaded), vec!["spotify/personal"]);
+        let account = reloaded
+            .get_account(&ServiceId::new("spotify"), &AccountId::new("personal"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(account.scopes, vec!["user-read-email"]);
+    }
+
+    #[test]
+    fn test_restore_rejects_newer_version() {
+        let (store, _temp) = test_store();
+        store.add_account(test_account()).unwrap();
+        let mut snapshot = store.snapshot().unwrap();
+        snapshot.version = CURRENT_VERSION + 1;
+        snapshot.accounts.clear();
+
+        let err = store.restore(snapshot).unwrap_err();
+        assert!(matches!(err, AccountStoreError::UnsupportedVersion { .. }));
+        assert_eq!(store.list_accounts(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_restore_read_only() {
+        let (store, temp_dir) = test_store();
+        let snapshot = store.snapshot().unwrap();
+        let read_only =
+            AccountStore::open_read_only(temp_dir.path().join("accounts.json")).unwrap();
+
+        let err = read_only.restore(snapshot).unwrap_err();
+        assert!(matches!(err, AccountStoreError::ReadOnly { .. }));
+    }
+
+    #[test]
+    fn test_save_list_and_load_snapshots() {
+        let (store, temp_dir) = test_store();
+        let dir = store.snapshot_dir();
+        assert_eq!(dir, temp_dir.path().join("snapshots"));
+        assert!(store.list_snapshots(&dir).unwrap().is_empty());
+
+        let first = store.snapshot().unwrap();
+        let saved_first = store.save_snapshot(&first, &dir, "").unwrap();
+        assert_eq!(saved_first.label, None);
+
+        store.add_account(test_account()).unwrap();
+        let mut second = store.snapshot().unwrap();
+        second.created_at = first.created_at + chrono::Duration::seconds(1);
+        let saved_second = store
+            .save_snapshot(&second, &dir, "before-upgrade")
+            .unwrap();
+        assert!(saved_second.id.ends_with("-before-upgrade"));
+
+        // Not a snapshot, so skipped
+        fs::write(dir.join("notes.txt"), "hello").unwrap();
+
+        let listed = store.list_snapshots(&dir).unwrap();
+        assert_eq!(listed, vec![saved_first.clone(), saved_second.clone()]);
+        assert_eq!(listed[1].label.as_deref(), Some("before-upgrade"));
+        assert_eq!(listed[1].account_count, 1);
+
+        let loaded = store.load_snapshot(&dir, &saved_first.id).unwrap();
+        assert!(loaded.accounts.is_empty());
+        store.restore(loaded).unwrap();
+        assert!(store.list_accounts(None).unwrap().is_empty());
+
+        // Saving the same snapshot twice does not overwrite the first file
+        assert!(store.save_snapshot(&first, &dir, "").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_errors() {
+        let (store, _temp) = test_store();
+        let dir = store.snapshot_dir();
+        let snapshot = store.snapshot().unwrap();
+
+        let err = store
+            .save_snapshot(&snapshot, &dir, "../escape")
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AccountStoreError::InvalidSnapshotLabel { .. }
+        ));
+
+        let err = store.load_snapshot(&dir, "missing").unwrap_err();
+        assert!(matches!(err, AccountStoreError::SnapshotNotFound { .. }));
+        let err = store.load_snapshot(&dir, "../accounts").unwrap_err();
+        assert!(matches!(err, AccountStoreError::SnapshotNotFound { .. }));
+    }
+
+    #[test]
+    fn test_save_waits_for_file_lock() {
+        let (store, temp_dir) = test_store();
+        let path = temp_dir.path().join("accounts.json");
+        assert!(temp_dir.path().join("accounts.json.lock").exists());
+
+        // Another process would hold the lock through its own file handle
+        let lock = FileLock::acquire(&path, LockKind::Shared).unwrap();
+        std::thread::scope(|scope| {
+            let writer = scope.spawn(|| store.add_account(test_account()));
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            assert!(!path.exists());
+
+            drop(lock);
+            writer.join().unwrap().unwrap();
+        });
+        let reloaded = AccountStore::load_from_path(path).unwrap();
+        assert_eq!(reloaded.list_accounts(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_writes_keep_accounts_saved_by_other_stores() {
+        let (first, temp_dir) = test_store();
+        let second = AccountStore::load_from_path(first.path().clone()).unwrap();
+
+        first.add_account(test_account()).unwrap();
+        let work = Account::new(ServiceId::new("spotify"), AccountId::new("work"), vec![]);
+        second.add_account(work).unwrap();
+        assert!(matches!(
+            second.add_account(test_account()),
+            Err(AccountStoreError::AlreadyExists { .. })
+        ));
+
+        let reloaded = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+        assert_eq!(reloaded.list_accounts(None).unwrap().len(), 2);
+        assert_eq!(second.list_accounts(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_disabled_file_locking_ignores_lock() {
+        let (store, temp_dir) = test_store();
+        let store = store.with_file_locking(false);
+        let path = temp_dir.path().join("accounts.json");
+
+        let _lock = FileLock::acquire(&path, LockKind::Exclusive).unwrap();
+        store.add_account(test_account()).unwrap();
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_read_only_does_not_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts.json");
+        AccountStore::load_from_path(path.clone())
+            .unwrap()
+            .add_account(test_account())
+            .unwrap();
+        fs::remove_file(temp_dir.path().join("accounts.json.lock")).unwrap();
+
+        let store = AccountStore::open_read_only(path).unwrap();
+        assert_eq!(store.list_accounts(None).unwrap().len(), 1);
+        assert!(!temp_dir.path().join("accounts.json.lock").exists());
+    }
+
+    #[test]
+    fn test_subscribe_receives_every_event_type() {
+        let (store, _temp) = test_store();
+        let mut events = store.subscribe();
+        let account = test_account();
+        let (service, id) = (account.service.clone(), account.id.clone());
+
+        store.add_account(account).unwrap();
+        match events.try_recv().unwrap() {
+            AccountStoreEvent::AccountAdded(added) => assert_eq!(added.id, id),
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        store.update_last_used(&service, &id).unwrap();
+        match events.try_recv().unwrap() {
+            AccountStoreEvent::AccountUpdated(updated) => assert!(updated.last_used.is_some()),
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let scopes = vec!["user-library-read".to_string()];
+        store.update_scopes(&service, &id, scopes.clone()).unwrap();
+        match events.try_recv().unwrap() {
+            AccountStoreEvent::ScopesUpdated {
+                account,
+                scopes: updated,
+                ..
+            } => {
+                assert_eq!(account, id);
+                assert_eq!(updated, scopes);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert_eq!(store.get_account(&service, &id).unwrap().unwrap().scopes, scopes);
+
+        store.remove_account(&service, &id).unwrap();
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            AccountStoreEvent::AccountRemoved { service: s, account: a } if s == service && a == id
+        ));
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_failed_changes_send_no_event() {
+        let (store, _temp) = test_store();
+        store.add_account(test_account()).unwrap();
+        let mut events = store.subscribe();
+
+        assert!(store.add_account(test_account()).is_err());
+        let missing = AccountId::new("missing");
+        assert!(store.remove_account(&ServiceId::new("spotify"), &missing).is_err());
+        assert!(store.update_scopes(&ServiceId::new("spotify"), &missing, vec![]).is_err());
+
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_batch_add_sends_event_per_added_account() {
+        let (store, _temp) = test_store();
+        store.add_account(test_account()).unwrap();
+        let mut events = store.subscribe();
+
+        let work = Account::new(ServiceId::new("spotify"), AccountId::new("work"), vec![]);
+        store.batch_add(vec![test_account(), work]).unwrap();
+
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            AccountStoreEvent::AccountAdded(added) if added.id.as_str() == "work"
+        ));
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_slow_subscriber_lags_past_capacity() {
+        let (store, _temp) = test_store();
+        store.add_account(test_account()).unwrap();
+        let mut events = store.subscribe();
+        let (service, id) = (ServiceId::new("spotify"), AccountId::new("personal"));
+
+        for _ in 0..EVENT_CAPACITY + 1 {
+            store.update_last_used(&service, &id).unwrap();
+        }
+
+        assert!(matches!(
+            events.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(1))
+        ));
+    }
+
+    #[cfg(feature = "encrypted-account-store")]
+    mod encrypted {
+        use super::*;
+
+        /// An encrypted store in a new directory, with its identity file.
+        fn encrypted_store() -> (AccountStore, PathBuf, TempDir) {
+            let temp_dir = TempDir::new().unwrap();
+            let identity_path = temp_dir.path().join("identity.txt");
+            let identity = AccountStore::generate_identity(&identity_path).unwrap();
+            let path = temp_dir.path().join("accounts.json");
+            let store = AccountStore::open_encrypted(&path, identity).unwrap();
+            (store, identity_path, temp_dir)
+        }
+
+        #[test]
+        fn test_encrypted_round_trip() {
+            let (store, identity_path, _temp_dir) = encrypted_store();
+            store.add_account(test_account()).unwrap();
+
+            let identity = AccountStore::load_identity(&identity_path).unwrap();
+            let reopened = AccountStore::open_encrypted(store.path(), identity).unwrap();
+            let accounts = reopened.list_accounts(None).unwrap();
+            assert_eq!(accounts.len(), 1);
+            assert_eq!(accounts[0].id, test_account().id);
+            assert!(!store.path().with_extension("json.tmp").exists());
+        }
+
+        #[test]
+        fn test_encrypted_file_is_not_plaintext() {
+            let (store, _identity_path, _temp_dir) = encrypted_store();
+            store.add_account(test_account()).unwrap();
+
+            assert!(AccountStore::load_from_path(store.path().to_path_buf()).is_err());
+            let contents = fs::read(store.path()).unwrap();
+            assert!(!String::from_utf8_lossy(&contents).contains("spotify"));
+        }
+
+        #[test]
+        fn test_wrong_identity_cannot_decrypt() {
+            let (store, _identity_path, temp_dir) = encrypted_store();
+            store.add_account(test_account()).unwrap();
+
+            let other = AccountStore::generate_identity(&temp_dir.path().join("other.txt"));
+            let result = AccountStore::open_encrypted(store.path(), other.unwrap());
+            assert!(matches!(result, Err(AccountStoreError::Encryption(_))));
+        }
+
+        #[test]
+        fn test_encrypted_snapshots_and_backups() {
+            let (store, _identity_path, temp_dir) = encrypted_store();
+            let dir = store.snapshot_dir();
+            let backup = temp_dir.path().join("accounts.json.bak");
+
+            // Before the first save, the backup comes from memory
+            assert!(!store.path().exists());
+            store.create_backup(&backup).unwrap();
+            let contents = fs::read(&backup).unwrap();
+            assert!(serde_json::from_slice::<serde_json::Value>(&contents).is_err());
+
+            store.add_account(test_account()).unwrap();
+            let saved = store
+                .save_snapshot(&store.snapshot().unwrap(), &dir, "")
+                .unwrap();
+            let contents = fs::read(&saved.path).unwrap();
+            assert!(!String::from_utf8_lossy(&contents).contains("spotify"));
+
+            assert_eq!(store.list_snapshots(&dir).unwrap(), vec![saved.clone()]);
+            let loaded = store.load_snapshot(&dir, &saved.id).unwrap();
+            assert_eq!(loaded.accounts.len(), 1);
+        }
+
+        #[test]
+        fn test_encrypt_in_place() {
+            let (store, temp_dir) = test_store();
+            store.add_account(test_account()).unwrap();
+            let identity_path = temp_dir.path().join("identity.txt");
+            let identity = AccountStore::generate_identity(&identity_path).unwrap();
+
+            let reloaded = AccountStore::load_identity(&identity_path).unwrap();
+            let result = AccountStore::open_encrypted(store.path(), reloaded);
+            assert!(matches!(result, Err(AccountStoreError::Encryption(_))));
+
+            let encrypted = AccountStore::encrypt_in_place(store.path(), identity).unwrap();
+            assert_eq!(encrypted.list_accounts(None).unwrap().len(), 1);
+            assert!(AccountStore::load_from_path(store.path().to_path_buf()).is_err());
+
+            // Encrypting again leaves the file as it is
+            let contents = fs::read(store.path()).unwrap();
+            let identity = AccountStore::load_identity(&identity_path).unwrap();
+            let reopened = AccountStore::encrypt_in_place(store.path(), identity).unwrap();
+            assert_eq!(reopened.list_accounts(None).unwrap().len(), 1);
+            assert_eq!(fs::read(store.path()).unwrap(), contents);
+        }
+
+        #[test]
+        fn test_read_only_encrypted_store_creates_nothing() {
+            let temp_dir = TempDir::new().unwrap();
+            let identity = AccountStore::generate_identity(&temp_dir.path().join("identity.txt"));
+            let path = temp_dir.path().join("missing").join("accounts.json");
+
+            let store = AccountStore::open_encrypted_with(&path, identity.unwrap(), true).unwrap();
+            assert!(store.is_read_only());
+            assert!(matches!(
+                store.add_account(test_account()),
+                Err(AccountStoreError::ReadOnly { .. })
+            ));
+            assert!(!temp_dir.path().join("missing").exists());
+        }
+
+        #[test]
+        fn test_generate_identity_never_overwrites() {
+            let (_store, identity_path, _temp_dir) = encrypted_store();
+            let original = fs::read_to_string(&identity_path).unwrap();
+
+            assert!(AccountStore::generate_identity(&identity_path).is_err());
+            assert_eq!(fs::read_to_string(&identity_path).unwrap(), original);
+        }
+    }
+
+    #[cfg(feature = "watch")]
+    mod watch {
+        use super::*;
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        /// How long to wait for a callback that should come
+        const TIMEOUT: Duration = Duration::from_secs(5);
+
+        /// How long to wait before concluding a callback will not come
+        const QUIET: Duration = Duration::from_millis(500);
+
+        /// A store watching its file, with a channel that receives a message
+        /// for each callback.
+        fn watched_store() -> (AccountStore, WatchHandle, mpsc::Receiver<()>, TempDir) {
+            let (store, temp_dir) = test_store();
+            let (tx, rx) = mpsc::channel();
+            let handle = store
+                .watch(move || {
+                    let _ = tx.send(());
+                })
+                .unwrap();
+            (store, handle, rx, temp_dir)
+        }
+
+        /// Add accounts `names` from another store in a separate thread, as
+        /// another process would.
+        fn add_externally(path: &Path, names: &[&str]) {
+            let path = path.to_path_buf();
+            let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+            std::thread::spawn(move || {
+                let other = AccountStore::load_from_path(path).unwrap();
+                for name in names {
+                    let account =
+                        Account::new(ServiceId::new("github"), AccountId::new(name), vec![]);
+                    other.add_account(account).unwrap();
+                }
+            })
+            .join()
+            .unwrap();
+        }
+
+        #[test]
+        fn test_external_change_reloads_and_notifies() {
+            let (store, _handle, rx, _temp_dir) = watched_store();
+
+            add_externally(store.path(), &["work"]);
+
+            rx.recv_timeout(TIMEOUT).expect("callback did not fire");
+            let account = store
+                .get_account(&ServiceId::new("github"), &AccountId::new("work"))
+                .unwrap();
+            assert!(account.is_some());
+        }
+
+        #[test]
+        fn test_events_of_one_write_reload_once() {
+            let (store, _handle, rx, _temp_dir) = watched_store();
+
+            // One write creates, fills and renames a temporary file
+            let other = AccountStore::load_from_path(store.path().clone()).unwrap();
+            let accounts = ["one", "two", "three", "four", "five"]
+                .into_iter()
+                .map(|name| Account::new(ServiceId::new("github"), AccountId::new(name), vec![]))
+                .collect();
+            other.batch_add_strict(accounts).unwrap();
+
+            rx.recv_timeout(TIMEOUT).expect("callback did not fire");
+            assert!(rx.recv_timeout(QUIET).is_err(), "callback fired twice");
+            assert_eq!(store.list_accounts(None).unwrap().len(), 5);
+        }
+
+        #[test]
+        fn test_reload_reports_whether_data_changed() {
+            let (store, _temp_dir) = test_store();
+            let reload_store = || {
+                reload(
+                    store.path(),
+                    &store.data,
+                    &store.disk_version,
+                    true,
+                    &store.format,
+                )
+            };
+
+            // The file already matches what is in memory
+            store.add_account(test_account()).unwrap();
+            assert!(!reload_store().unwrap());
+
+            add_externally(store.path(), &["work"]);
+            assert!(reload_store().unwrap());
+            assert_eq!(store.list_accounts(None).unwrap().len(), 2);
+            assert!(!reload_store().unwrap());
+        }
+
+        #[test]
+        fn test_own_writes_do_not_notify() {
+            let (store, _handle, rx, _temp_dir) = watched_store();
+
+            store.add_account(test_account()).unwrap();
+
+            assert!(rx.recv_timeout(QUIET).is_err());
+            assert_eq!(store.list_accounts(None).unwrap().len(), 1);
+        }
+
+        #[test]
+        fn test_dropped_handle_stops_watching() {
+            let (store, handle, rx, _temp_dir) = watched_store();
+            drop(handle);
+
+            add_externally(store.path(), &["work"]);
+
+            assert!(rx.recv_timeout(QUIET).is_err());
+            assert!(store.list_accounts(None).unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_on_change_watches_for_store_lifetime() {
+            let (store, _temp_dir) = test_store();
+            let (tx, rx) = mpsc::channel();
+            store
+                .on_change(move || {
+                    let _ = tx.send(());
+                })
+                .unwrap();
+
+            add_externally(store.path(), &["work"]);
+
+            rx.recv_timeout(TIMEOUT).expect("callback did not fire");
+            assert_eq!(store.list_accounts(None).unwrap().len(), 1);
+        }
+    }
 }
@@ -26,6 +26,7 @@ pub mod token;
 pub mod resolve;
 pub mod error;
 pub mod account_store;
+pub mod migrations;
 
 #[cfg(feature = "oauth")]
 pub mod provider;
@@ -42,11 +43,14 @@ pub use model::{
     AccountId,
     Account,
     CredentialRef,
+    CredentialSource,
     CredentialType,
 };
 
 pub use store::{
+    ConflictPolicy,
     Secret,
+    SecretParseError,
     SecretStore,
     StoreError,
     MemoryStore,
@@ -62,6 +66,9 @@ pub use token::{
     TokenInfo,
     TokenManager,
     TokenError,
+    TokenEvent,
+    ScopeSet,
+    JwtClaims,
 };
 
 pub use resolve::{
@@ -69,6 +76,7 @@ pub use resolve::{
     ReferenceResolver,
     ResolveError,
     ResolverConfig,
+    TemplateExpander,
 };
 
 #[cfg(feature = "oauth")]
@@ -79,12 +87,23 @@ pub use error::SigilforgeError;
 pub use account_store::{
     AccountStore,
     AccountStoreError,
+    AccountStoreEvent,
+    AccountStoreSnapshot,
+    BatchAddResult,
+    ImportMode,
+    ImportResult,
+    SnapshotMetadata,
 };
 
+#[cfg(feature = "watch")]
+pub use account_store::WatchHandle;
+
 #[cfg(feature = "oauth")]
 pub use provider::{
     ProviderConfig,
+    ProviderError,
     ProviderRegistry,
+    ProviderValidationError,
 };
 
 #[cfg(feature = "oauth")]
@@ -0,0 +1,202 @@
+//! Schema migrations for the account store file.
+//!
+//! Each [`Migration`] upgrades the raw JSON document by exactly one version.
+//! Migrations operate on [`serde_json::Value`] rather than typed structs so
+//! that older layouts can be read even after the typed format has changed.
+//!
+//! To add a migration, bump [`CURRENT_VERSION`] and append an entry to
+//! [`MIGRATIONS`] whose `from_version` is the previous current version.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Schema version written by this build.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// A single schema upgrade step.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Version this migration upgrades from.
+    pub from_version: u32,
+
+    /// Short human-readable summary.
+    pub description: &'static str,
+
+    /// Transform the document in place.
+    pub apply: fn(&mut Value) -> Result<(), String>,
+}
+
+impl Migration {
+    /// Version this migration upgrades to.
+    pub fn to_version(&self) -> u32 {
+        self.from_version + 1
+    }
+
+    /// Describe this migration without its transform.
+    pub fn describe(&self) -> MigrationDescription {
+        MigrationDescription {
+            from_version: self.from_version,
+            to_version: self.to_version(),
+            description: self.description,
+        }
+    }
+}
+
+/// Description of a pending migration (for dry runs and reporting).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationDescription {
+    /// Version this migration upgrades from.
+    pub from_version: u32,
+
+    /// Version this migration upgrades to.
+    pub to_version: u32,
+
+    /// Short human-readable summary.
+    pub description: &'static str,
+}
+
+/// All registered migrations, ordered by `from_version`.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        from_version: 0,
+        description: "wrap legacy account list in a versioned document",
+        apply: migrate_v0_to_v1,
+    },
+    Migration {
+        from_version: 1,
+        description: "record an unknown credential source for existing accounts",
+        apply: migrate_v1_to_v2,
+    },
+];
+
+/// Detect the schema version of a raw account store document.
+///
+/// Documents without a `version` field (including bare account arrays)
+/// predate versioning and are treated as version 0.
+pub fn detect_version(document: &Value) -> u32 {
+    document
+        .get("version")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(0)
+}
+
+/// Migrations from `migrations` that apply to a document at `version`.
+pub fn pending<'a>(migrations: &'a [Migration], version: u32) -> Vec<&'a Migration> {
+    migrations
+        .iter()
+        .filter(|m| m.from_version >= version)
+        .collect()
+}
+
+/// Apply a single migration and stamp the resulting version.
+pub fn apply_one(migration: &Migration, document: &mut Value) -> Result<(), String> {
+    (migration.apply)(document)?;
+    match document.as_object_mut() {
+        Some(object) => {
+            object.insert("version".to_string(), Value::from(migration.to_version()));
+            Ok(())
+        }
+        None => Err(format!(
+            "migration to v{} did not produce a JSON object",
+            migration.to_version()
+        )),
+    }
+}
+
+/// v0 -> v1: legacy files were either a bare array of accounts or an object
+/// without a `version` field.
+fn migrate_v0_to_v1(document: &mut Value) -> Result<(), String> {
+    match document {
+        Value::Array(accounts) => {
+            let accounts = std::mem::take(accounts);
+            *document = serde_json::json!({ "accounts": accounts });
+            Ok(())
+        }
+        Value::Object(object) => {
+            object
+                .entry("accounts")
+                .or_insert_with(|| Value::Array(Vec::new()));
+            Ok(())
+        }
+        _ => Err("expected a JSON array or object".to_string()),
+    }
+}
+
+/// v1 -> v2: accounts gained a `source`; existing ones get `unknown`.
+fn migrate_v1_to_v2(document: &mut Value) -> Result<(), String> {
+    let accounts = document
+        .get_mut("accounts")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| "expected an `accounts` array".to_string())?;
+
+    for account in accounts {
+        let account = account
+            .as_object_mut()
+            .ok_or_else(|| "expected each account to be a JSON object".to_string())?;
+        account
+            .entry("source")
+            .or_insert_with(|| serde_json::json!({ "type": "unknown" }));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_version() {
+        assert_eq!(detect_version(&serde_json::json!([])), 0);
+        assert_eq!(detect_version(&serde_json::json!({ "accounts": [] })), 0);
+        assert_eq!(
+            detect_version(&serde_json::json!({ "version": 1, "accounts": [] })),
+            1
+        );
+    }
+
+    #[test]
+    fn test_registry_is_contiguous() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.from_version, index as u32);
+        }
+        assert_eq!(MIGRATIONS.len() as u32, CURRENT_VERSION);
+    }
+
+    #[test]
+    fn test_v0_array_to_v1() {
+        let mut document = serde_json::json!([{ "service": "github" }]);
+        apply_one(&MIGRATIONS[0], &mut document).unwrap();
+
+        assert_eq!(detect_version(&document), 1);
+        assert_eq!(document["accounts"][0]["service"], "github");
+    }
+
+    #[test]
+    fn test_v1_to_v2_defaults_source() {
+        let mut document = serde_json::json!({
+            "version": 1,
+            "accounts": [
+                { "service": "github", "id": "work" },
+                { "service": "gitea", "id": "ci", "source": { "type": "daemon" } },
+            ],
+        });
+        apply_one(&MIGRATIONS[1], &mut document).unwrap();
+
+        assert_eq!(detect_version(&document), 2);
+        assert_eq!(document["accounts"][0]["source"], serde_json::json!({ "type": "unknown" }));
+        assert_eq!(document["accounts"][1]["source"], serde_json::json!({ "type": "daemon" }));
+    }
+
+    #[test]
+    fn test_v1_to_v2_rejects_malformed_accounts() {
+        let mut document = serde_json::json!({ "version": 1, "accounts": ["github/work"] });
+        assert!(apply_one(&MIGRATIONS[1], &mut document).is_err());
+    }
+
+    #[test]
+    fn test_pending_when_current() {
+        assert!(pending(MIGRATIONS, CURRENT_VERSION).is_empty());
+        assert_eq!(pending(MIGRATIONS, 0).len(), MIGRATIONS.len());
+    }
+}
@@ -9,7 +9,11 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt;
+use std::str::FromStr;
+
+use crate::token::{REFRESH_TOKEN_WARNING_DAYS, TokenEvent};
 
 /// Identifier for a service (e.g., "spotify", "gmail", "github").
 ///
@@ -23,7 +27,7 @@ use std::fmt;
 /// let spotify = ServiceId::new("spotify");
 /// let ms_graph = ServiceId::new("msgraph");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ServiceId(String);
 
 impl ServiceId {
@@ -70,7 +74,7 @@ impl From<String> for ServiceId {
 /// let personal = AccountId::new("personal");
 /// let work = AccountId::new("work");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct AccountId(String);
 
 impl AccountId {
@@ -123,10 +127,19 @@ pub struct Account {
 
     /// When the account was last used to fetch a token.
     pub last_used: Option<DateTime<Utc>>,
+
+    /// How the account's credentials were obtained.
+    #[serde(default)]
+    pub source: CredentialSource,
+
+    /// When the account's refresh token expires, for providers whose
+    /// refresh tokens have a fixed lifetime (e.g. Box's 60 days).
+    #[serde(default)]
+    pub token_refresh_expiry: Option<DateTime<Utc>>,
 }
 
 impl Account {
-    /// Create a new account with the current timestamp.
+    /// Create a new account with the current timestamp and an unknown source.
     pub fn new(service: ServiceId, id: AccountId, scopes: Vec<String>) -> Self {
         Self {
             service,
@@ -134,15 +147,91 @@ impl Account {
             scopes,
             created_at: Utc::now(),
             last_used: None,
+            source: CredentialSource::Unknown,
+            token_refresh_expiry: None,
         }
     }
 
+    /// Record how the account's credentials were obtained.
+    pub fn with_source(mut self, source: CredentialSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Record when the account's refresh token expires.
+    pub fn with_token_refresh_expiry(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.token_refresh_expiry = Some(expires_at);
+        self
+    }
+
+    /// The [`TokenEvent::RefreshTokenExpiringSoon`] event for this account, if
+    /// its refresh token expires within [`REFRESH_TOKEN_WARNING_DAYS`] of
+    /// `now`.
+    pub fn refresh_token_event(&self, now: DateTime<Utc>) -> Option<TokenEvent> {
+        let expires_at = self.token_refresh_expiry?;
+        if expires_at - now > chrono::Duration::days(REFRESH_TOKEN_WARNING_DAYS) {
+            return None;
+        }
+
+        Some(TokenEvent::RefreshTokenExpiringSoon {
+            service: self.service.clone(),
+            account: self.id.clone(),
+            expires_at,
+        })
+    }
+
     /// Create a unique key for this account.
     pub fn key(&self) -> String {
         format!("{}/{}", self.service, self.id)
     }
 }
 
+/// How an account's credentials were obtained.
+///
+/// Serialized with a `type` tag, e.g.
+/// `{"type": "oauth_pkce", "provider_id": "github"}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CredentialSource {
+    /// OAuth authorization code flow with PKCE.
+    #[serde(rename = "oauth_pkce")]
+    OAuthPkce { provider_id: String },
+
+    /// OAuth device authorization grant.
+    #[serde(rename = "oauth_device_code")]
+    OAuthDeviceCode { provider_id: String },
+
+    /// OAuth client credentials grant.
+    ClientCredentials { provider_id: String },
+
+    /// Imported from another tool's credential file (e.g. `netrc`).
+    ManualImport { format: String },
+
+    /// Added through the daemon's `add_account` RPC.
+    Daemon,
+
+    /// Not recorded (accounts created before sources were tracked).
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OAuthPkce { provider_id } => write!(f, "OAuth PKCE ({})", provider_id),
+            Self::OAuthDeviceCode { provider_id } => {
+                write!(f, "OAuth device code ({})", provider_id)
+            }
+            Self::ClientCredentials { provider_id } => {
+                write!(f, "client credentials ({})", provider_id)
+            }
+            Self::ManualImport { format } => write!(f, "imported ({})", format),
+            Self::Daemon => write!(f, "daemon"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
 /// Type of credential stored for an account.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -194,10 +283,29 @@ impl fmt::Display for CredentialType {
     }
 }
 
+/// Credential types sort by [`CredentialType::as_str`]. A custom type named
+/// like a built-in one sorts after it.
+impl Ord for CredentialType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let is_custom = |t: &Self| matches!(t, Self::Custom(_));
+        self.as_str()
+            .cmp(other.as_str())
+            .then_with(|| is_custom(self).cmp(&is_custom(other)))
+    }
+}
+
+impl PartialOrd for CredentialType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// Reference to a stored credential.
 ///
-/// Used to construct storage keys and parse `auth://` URIs.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Used to construct storage keys and parse `auth://` URIs. References are
+/// displayed as, and parse from, their `auth://` URI, and sort by service,
+/// account, then credential type.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct CredentialRef {
     /// The service the credential belongs to.
     pub service: ServiceId,
@@ -227,9 +335,16 @@ impl CredentialRef {
     ///
     /// Keys follow the pattern: `sigilforge/{service}/{account}/{type}`
     pub fn to_key(&self) -> String {
+        self.to_key_in("sigilforge")
+    }
+
+    /// Convert to a storage key in another namespace.
+    ///
+    /// Keys follow the pattern: `{namespace}/{service}/{account}/{type}`
+    pub fn to_key_in(&self, namespace: &str) -> String {
         format!(
-            "sigilforge/{}/{}/{}",
-            self.service, self.account, self.credential_type
+            "{}/{}/{}/{}",
+            namespace, self.service, self.account, self.credential_type
         )
     }
 
@@ -274,9 +389,11 @@ impl CredentialRef {
         let credential_type = match parts[2] {
             "token" | "access_token" => CredentialType::AccessToken,
             "refresh_token" => CredentialType::RefreshToken,
+            "token_expiry" => CredentialType::TokenExpiry,
             "api_key" => CredentialType::ApiKey,
             "client_id" => CredentialType::ClientId,
             "client_secret" => CredentialType::ClientSecret,
+            "token_scopes" => CredentialType::TokenScopes,
             other => CredentialType::Custom(other.to_string()),
         };
 
@@ -289,13 +406,36 @@ impl CredentialRef {
 
     /// Convert to an `auth://` URI.
     pub fn to_auth_uri(&self) -> String {
-        format!(
+        self.to_string()
+    }
+}
+
+impl fmt::Display for CredentialRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
             "auth://{}/{}/{}",
             self.service, self.account, self.credential_type
         )
     }
 }
 
+impl FromStr for CredentialRef {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_auth_uri(s)
+    }
+}
+
+impl TryFrom<&str> for CredentialRef {
+    type Error = ParseError;
+
+    fn try_from(uri: &str) -> Result<Self, Self::Error> {
+        Self::from_auth_uri(uri)
+    }
+}
+
 /// Error parsing a credential reference.
 #[derive(Debug, thiserror::Error)]
 pub enum ParseError {
@@ -320,6 +460,7 @@ mod tests {
     fn test_credential_ref_to_key() {
         let cred = CredentialRef::new("spotify", "personal", CredentialType::AccessToken);
         assert_eq!(cred.to_key(), "sigilforge/spotify/personal/access_token");
+        assert_eq!(cred.to_key_in("myapp"), "myapp/spotify/personal/access_token");
     }
 
     #[test]
@@ -338,6 +479,107 @@ mod tests {
         assert_eq!(original, parsed);
     }
 
+    #[test]
+    fn test_credential_ref_hash_matches_eq() {
+        use std::hash::{BuildHasher, RandomState};
+
+        // `token` and `access_token` parse to the same reference
+        let a = CredentialRef::from_auth_uri("auth://spotify/personal/token").unwrap();
+        let b = CredentialRef::from_auth_uri("auth://SPOTIFY/personal/access_token").unwrap();
+        assert_eq!(a, b);
+        let state = RandomState::new();
+        assert_eq!(state.hash_one(&a), state.hash_one(&b));
+
+        let set: std::collections::HashSet<_> = [a.clone(), b, a.clone()].into_iter().collect();
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(&CredentialRef::new(
+            "spotify",
+            "personal",
+            CredentialType::AccessToken
+        )));
+
+        let other = CredentialRef::new("spotify", "personal", CredentialType::RefreshToken);
+        assert_ne!(a, other);
+        assert!(!set.contains(&other));
+    }
+
+    #[test]
+    fn test_credential_ref_ordering() {
+        let refs = [
+            CredentialRef::new("github", "work", CredentialType::AccessToken),
+            CredentialRef::new("github", "work", CredentialType::ApiKey),
+            CredentialRef::new("github", "work", CredentialType::Custom("subject".into())),
+            CredentialRef::new("github", "zeta", CredentialType::AccessToken),
+            CredentialRef::new("spotify", "alpha", CredentialType::AccessToken),
+        ];
+
+        // Sorting any permutation gives service, account, type order
+        let mut shuffled = refs.to_vec();
+        shuffled.reverse();
+        shuffled.swap(1, 3);
+        shuffled.sort();
+        assert_eq!(shuffled, refs);
+
+        // Transitive and consistent with Eq
+        for a in &refs {
+            assert_eq!(a.cmp(a), Ordering::Equal);
+            for b in &refs {
+                assert_eq!(a.cmp(b), b.cmp(a).reverse());
+                assert_eq!(a.cmp(b) == Ordering::Equal, a == b);
+                for c in &refs {
+                    if a < b && b < c {
+                        assert!(a < c);
+                    }
+                }
+            }
+        }
+
+        // A custom type named like a built-in one is distinct and sorts after it
+        let builtin = CredentialType::AccessToken;
+        let custom = CredentialType::Custom("access_token".into());
+        assert_ne!(builtin, custom);
+        assert!(builtin < custom);
+
+        let map: std::collections::BTreeMap<_, _> =
+            refs.iter().cloned().rev().map(|r| (r, ())).collect();
+        assert_eq!(map.keys().next(), Some(&refs[0]));
+    }
+
+    #[test]
+    fn test_credential_ref_display_roundtrip() {
+        let types = [
+            CredentialType::AccessToken,
+            CredentialType::RefreshToken,
+            CredentialType::TokenExpiry,
+            CredentialType::ApiKey,
+            CredentialType::ClientId,
+            CredentialType::ClientSecret,
+            CredentialType::TokenScopes,
+            CredentialType::Custom("subject".into()),
+        ];
+        for credential_type in types {
+            let original = CredentialRef::new("gmail", "work", credential_type);
+            let displayed = original.to_string();
+            assert_eq!(displayed, original.to_auth_uri());
+            assert_eq!(displayed.parse::<CredentialRef>().unwrap(), original);
+            assert_eq!(
+                CredentialRef::try_from(displayed.as_str()).unwrap(),
+                original
+            );
+        }
+
+        assert_eq!(
+            CredentialRef::new("github", "ci", CredentialType::ApiKey).to_string(),
+            "auth://github/ci/api_key"
+        );
+        assert!(
+            "https://github/ci/api_key"
+                .parse::<CredentialRef>()
+                .is_err()
+        );
+        assert!(CredentialRef::try_from("auth://github/ci").is_err());
+    }
+
     #[test]
     fn test_invalid_auth_uri_scheme() {
         let result = CredentialRef::from_auth_uri("https://spotify/personal/token");
@@ -349,4 +591,70 @@ mod tests {
         let result = CredentialRef::from_auth_uri("auth://spotify/personal");
         assert!(matches!(result, Err(ParseError::InvalidPath { .. })));
     }
+
+    #[test]
+    fn test_credential_source_serialization() {
+        let source = CredentialSource::OAuthPkce {
+            provider_id: "github".to_string(),
+        };
+        let json = serde_json::to_value(&source).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "oauth_pkce", "provider_id": "github" }));
+        assert_eq!(serde_json::from_value::<CredentialSource>(json).unwrap(), source);
+
+        let json = serde_json::to_value(CredentialSource::Daemon).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "daemon" }));
+    }
+
+    #[test]
+    fn test_account_source_defaults_to_unknown() {
+        let account: Account = serde_json::from_value(serde_json::json!({
+            "service": "github",
+            "id": "work",
+            "scopes": [],
+            "created_at": "2025-01-01T00:00:00Z",
+            "last_used": null,
+        }))
+        .unwrap();
+
+        assert_eq!(account.source, CredentialSource::Unknown);
+        assert_eq!(account.token_refresh_expiry, None);
+    }
+
+    #[test]
+    fn test_refresh_token_event() {
+        let now = "2025-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let account = Account::new(ServiceId::new("box"), AccountId::new("work"), vec![]);
+        assert_eq!(account.refresh_token_event(now), None);
+
+        let far = account
+            .clone()
+            .with_token_refresh_expiry(now + chrono::Duration::days(30));
+        assert_eq!(far.refresh_token_event(now), None);
+
+        let expires_at = now + chrono::Duration::days(REFRESH_TOKEN_WARNING_DAYS);
+        let soon = account.clone().with_token_refresh_expiry(expires_at);
+        assert_eq!(
+            soon.refresh_token_event(now),
+            Some(TokenEvent::RefreshTokenExpiringSoon {
+                service: ServiceId::new("box"),
+                account: AccountId::new("work"),
+                expires_at,
+            })
+        );
+
+        let expired = account.with_token_refresh_expiry(now - chrono::Duration::days(1));
+        assert!(expired.refresh_token_event(now).is_some());
+    }
+
+    #[test]
+    fn test_token_refresh_expiry_roundtrip() {
+        let expires_at = "2025-03-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let account = Account::new(ServiceId::new("box"), AccountId::new("work"), vec![])
+            .with_token_refresh_expiry(expires_at);
+
+        let json = serde_json::to_value(&account).unwrap();
+        assert_eq!(json["token_refresh_expiry"], "2025-03-01T12:00:00Z");
+        let parsed: Account = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.token_refresh_expiry, Some(expires_at));
+    }
 }
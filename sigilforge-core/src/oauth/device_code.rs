@@ -38,19 +38,25 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Progress
+//!
+//! [`DeviceCodeFlow::with_progress_callback`] registers a callback that is
+//! told the [`DeviceCodeStatus`] of each polling iteration, e.g. to show a
+//! countdown while the user authorizes.
 
 use oauth2::{
-    basic::BasicClient,
     DeviceAuthorizationUrl, Scope, StandardDeviceAuthorizationResponse,
     reqwest::async_http_client,
 };
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
 use std::time::Duration;
 use tokio::time::sleep;
 
 use crate::provider::ProviderConfig;
 use crate::token::{Token, TokenSet, TokenError};
-use super::create_oauth_client;
+use super::{create_oauth_client, OAuthClient};
 
 /// Device authorization response.
 ///
@@ -76,6 +82,39 @@ pub struct DeviceAuthorization {
     pub expires_in: u64,
 }
 
+/// Status of a device code polling iteration.
+///
+/// Each iteration reports [`Pending`](Self::Pending) before it polls, then
+/// the outcome of the poll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceCodeStatus {
+    /// About to poll the token endpoint.
+    Pending {
+        /// Seconds since polling started.
+        elapsed_secs: u64,
+        /// Seconds until the device code expires.
+        expires_in_secs: u64,
+    },
+
+    /// The user has not authorized the device yet.
+    AuthorizationPending,
+
+    /// The server asked for slower polling.
+    SlowDown,
+
+    /// The user authorized the device and tokens were issued.
+    Complete,
+
+    /// The user denied authorization.
+    AccessDenied,
+
+    /// The device code expired before the user authorized.
+    Expired,
+}
+
+/// Callback told the status of each polling iteration.
+type ProgressCallback = Box<dyn Fn(DeviceCodeStatus) + Send>;
+
 /// Device code flow implementation for OAuth 2.0 device authorization grant.
 ///
 /// This flow is designed for devices with limited input capabilities or
@@ -84,6 +123,8 @@ pub struct DeviceCodeFlow {
     config: ProviderConfig,
     client_id: String,
     client_secret: Option<String>,
+    // Behind a mutex so the flow stays `Sync` for a callback that is only `Send`
+    progress_callback: Option<Mutex<ProgressCallback>>,
 }
 
 impl DeviceCodeFlow {
@@ -109,9 +150,28 @@ impl DeviceCodeFlow {
             config,
             client_id,
             client_secret,
+            progress_callback: None,
         })
     }
 
+    /// Call `callback` with the status of each polling iteration of
+    /// [`poll_for_token`](Self::poll_for_token).
+    pub fn with_progress_callback(
+        mut self,
+        callback: impl Fn(DeviceCodeStatus) + Send + 'static,
+    ) -> Self {
+        self.progress_callback = Some(Mutex::new(Box::new(callback)));
+        self
+    }
+
+    /// Tell the progress callback, if any, about `status`.
+    fn report(&self, status: DeviceCodeStatus) {
+        if let Some(callback) = &self.progress_callback {
+            let callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+            callback(status);
+        }
+    }
+
     /// Request device and user codes from the authorization server.
     ///
     /// # Arguments
@@ -171,7 +231,8 @@ impl DeviceCodeFlow {
     ///
     /// # Returns
     ///
-    /// Token set once the user has authorized.
+    /// Token set once the user has authorized. The progress callback, if
+    /// any, is told the status of each polling iteration.
     ///
     /// # Errors
     ///
@@ -194,12 +255,19 @@ impl DeviceCodeFlow {
 
         loop {
             // Check for timeout
-            if start_time.elapsed() > timeout {
+            let elapsed = start_time.elapsed();
+            if elapsed > timeout {
+                self.report(DeviceCodeStatus::Expired);
                 return Err(TokenError::OAuthError {
                     message: "device code expired".to_string(),
                 });
             }
 
+            self.report(DeviceCodeStatus::Pending {
+                elapsed_secs: elapsed.as_secs(),
+                expires_in_secs: (timeout - elapsed).as_secs(),
+            });
+
             // Wait before polling
             sleep(poll_interval).await;
 
@@ -253,6 +321,7 @@ impl DeviceCodeFlow {
                             token_set = token_set.with_refresh_token(refresh_token);
                         }
 
+                        self.report(DeviceCodeStatus::Complete);
                         return Ok(token_set);
                     } else {
                         // Parse error response
@@ -262,19 +331,23 @@ impl DeviceCodeFlow {
                             match error_code {
                                 "authorization_pending" => {
                                     tracing::debug!("Authorization pending, continuing to poll...");
+                                    self.report(DeviceCodeStatus::AuthorizationPending);
                                     continue;
                                 }
                                 "slow_down" => {
                                     tracing::warn!("Polling too fast, slowing down...");
+                                    self.report(DeviceCodeStatus::SlowDown);
                                     sleep(Duration::from_secs(5)).await;
                                     continue;
                                 }
                                 "access_denied" => {
+                                    self.report(DeviceCodeStatus::AccessDenied);
                                     return Err(TokenError::OAuthError {
                                         message: "user denied authorization".to_string(),
                                     });
                                 }
                                 "expired_token" => {
+                                    self.report(DeviceCodeStatus::Expired);
                                     return Err(TokenError::OAuthError {
                                         message: "device code expired".to_string(),
                                     });
@@ -329,7 +402,7 @@ impl DeviceCodeFlow {
     fn create_client_with_device_url(
         &self,
         device_auth_url: &str,
-    ) -> Result<BasicClient, TokenError> {
+    ) -> Result<OAuthClient, TokenError> {
         let mut client = create_oauth_client(
             &self.config,
             &self.client_id,
@@ -363,6 +436,12 @@ mod tests {
             default_scopes: vec![],
             supports_pkce: true,
             supports_device_code: true,
+            default_auth_params: Default::default(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         };
 
         let flow = DeviceCodeFlow::new(config, "client-id".to_string(), None);
@@ -380,6 +459,12 @@ mod tests {
             default_scopes: vec![],
             supports_pkce: true,
             supports_device_code: false,
+            default_auth_params: Default::default(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         };
 
         let flow = DeviceCodeFlow::new(config, "client-id".to_string(), None);
@@ -397,6 +482,12 @@ mod tests {
             default_scopes: vec![],
             supports_pkce: true,
             supports_device_code: true,
+            default_auth_params: Default::default(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         };
 
         let flow = DeviceCodeFlow::new(config, "client-id".to_string(), None).unwrap();
@@ -416,6 +507,12 @@ mod tests {
             default_scopes: vec![],
             supports_pkce: true,
             supports_device_code: true,
+            default_auth_params: Default::default(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         };
 
         let flow = DeviceCodeFlow::new(config, "client-id".to_string(), None).unwrap();
@@ -423,4 +520,79 @@ mod tests {
 
         assert_eq!(url, "https://oauth2.googleapis.com/device/code");
     }
+
+    #[tokio::test]
+    async fn test_progress_callback_pending_then_success() {
+        use std::sync::Arc;
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("device_code=device-123"))
+            .respond_with(
+                ResponseTemplate::new(400)
+                    .set_body_json(serde_json::json!({ "error": "authorization_pending" })),
+            )
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "gho_device",
+                "token_type": "bearer",
+                "scope": "repo",
+            })))
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            auth_url: format!("{}/authorize", server.uri()),
+            token_url: format!("{}/token", server.uri()),
+            revoke_url: None,
+            default_scopes: vec![],
+            supports_pkce: true,
+            supports_device_code: true,
+            default_auth_params: Default::default(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
+        };
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&statuses);
+        let flow = DeviceCodeFlow::new(config, "client-id".to_string(), None)
+            .unwrap()
+            .with_progress_callback(move |status| recorded.lock().unwrap().push(status));
+
+        let device_auth = DeviceAuthorization {
+            device_code: "device-123".to_string(),
+            user_code: "ABCD-1234".to_string(),
+            verification_uri: format!("{}/device", server.uri()),
+            verification_uri_complete: None,
+            interval: 0,
+            expires_in: 900,
+        };
+        let token_set = flow.poll_for_token(&device_auth).await.unwrap();
+        assert_eq!(token_set.access_token.access_token.expose(), "gho_device");
+
+        let statuses = statuses.lock().unwrap();
+        assert_eq!(statuses.len(), 4, "{:?}", statuses);
+        assert!(matches!(
+            statuses[0],
+            DeviceCodeStatus::Pending {
+                elapsed_secs: 0,
+                expires_in_secs: 899..=900
+            }
+        ));
+        assert_eq!(statuses[1], DeviceCodeStatus::AuthorizationPending);
+        assert!(matches!(statuses[2], DeviceCodeStatus::Pending { .. }));
+        assert_eq!(statuses[3], DeviceCodeStatus::Complete);
+    }
 }
@@ -0,0 +1,295 @@
+//! GitHub App installation access tokens.
+//!
+//! GitHub Apps act on behalf of an installation rather than a user. The app
+//! signs a short-lived JWT with its private key and exchanges it for an
+//! installation access token that is valid for one hour.
+//!
+//! There is no refresh token: a new installation token is requested with a
+//! fresh JWT whenever the previous one expires. [`DefaultTokenManager`] does
+//! this automatically for accounts that have GitHub App credentials stored.
+//!
+//! [`DefaultTokenManager`]: crate::token_manager::DefaultTokenManager
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use sigilforge_core::oauth::github_app::GitHubAppFlow;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let private_key = std::fs::read("my-app.private-key.pem")?;
+//! let flow = GitHubAppFlow::new("123456", "7890123", &private_key)?;
+//!
+//! let token_set = flow.request_installation_token().await?;
+//! println!("Installation token expires at {:?}", token_set.access_token.expires_at);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+
+use crate::model::CredentialType;
+use crate::token::{Token, TokenError, TokenSet};
+
+/// Public GitHub REST API base URL.
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Service ID used for GitHub App installation accounts.
+pub const GITHUB_APP_SERVICE: &str = "github-app";
+
+/// Lifetime of the app JWT (GitHub allows at most 10 minutes).
+const JWT_LIFETIME_SECS: i64 = 600;
+
+/// Backdate `iat` to tolerate clock drift between us and GitHub.
+const JWT_CLOCK_DRIFT_SECS: i64 = 60;
+
+/// Credential type holding the GitHub App ID.
+pub fn app_id_credential() -> CredentialType {
+    CredentialType::Custom("app_id".to_string())
+}
+
+/// Credential type holding the installation ID.
+pub fn installation_id_credential() -> CredentialType {
+    CredentialType::Custom("installation_id".to_string())
+}
+
+/// Credential type holding the app's PEM-encoded private key.
+pub fn private_key_credential() -> CredentialType {
+    CredentialType::Custom("private_key".to_string())
+}
+
+/// Credential type holding a non-default API base URL (GitHub Enterprise Server).
+pub fn api_url_credential() -> CredentialType {
+    CredentialType::Custom("api_url".to_string())
+}
+
+/// Claims of the JWT used to authenticate as the app.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppClaims {
+    /// Issued-at time (seconds since the epoch).
+    pub iat: i64,
+    /// Expiration time (seconds since the epoch).
+    pub exp: i64,
+    /// The GitHub App ID.
+    pub iss: String,
+}
+
+/// Response from `POST /app/installations/{id}/access_tokens`.
+#[derive(Debug, Deserialize)]
+struct InstallationTokenResponse {
+    token: String,
+    expires_at: DateTime<Utc>,
+    #[serde(default)]
+    permissions: BTreeMap<String, String>,
+}
+
+/// Requests installation access tokens for a GitHub App.
+pub struct GitHubAppFlow {
+    app_id: String,
+    installation_id: String,
+    encoding_key: EncodingKey,
+    api_url: String,
+    http_client: reqwest::Client,
+}
+
+impl GitHubAppFlow {
+    /// Create a flow for the given app and installation.
+    ///
+    /// `private_key_pem` is the private key downloaded from the app's
+    /// settings page (PKCS#1 or PKCS#8 PEM).
+    pub fn new(
+        app_id: impl Into<String>,
+        installation_id: impl Into<String>,
+        private_key_pem: &[u8],
+    ) -> Result<Self, TokenError> {
+        let encoding_key =
+            EncodingKey::from_rsa_pem(private_key_pem).map_err(|e| TokenError::OAuthError {
+                message: format!("invalid GitHub App private key: {}", e),
+            })?;
+
+        Ok(Self {
+            app_id: app_id.into(),
+            installation_id: installation_id.into(),
+            encoding_key,
+            api_url: GITHUB_API_URL.to_string(),
+            http_client: reqwest::Client::new(),
+        })
+    }
+
+    /// Use a different API base URL (e.g., `https://github.example.com/api/v3`).
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Use a custom HTTP client.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Sign a JWT asserting the app's identity (RS256, valid for 10 minutes).
+    pub fn app_jwt(&self) -> Result<String, TokenError> {
+        let now = Utc::now().timestamp();
+        let claims = AppClaims {
+            iat: now - JWT_CLOCK_DRIFT_SECS,
+            exp: now + JWT_LIFETIME_SECS,
+            iss: self.app_id.clone(),
+        };
+
+        let header = Header::new(Algorithm::RS256);
+        jsonwebtoken::encode(&header, &claims, &self.encoding_key).map_err(|e| {
+            TokenError::OAuthError {
+                message: format!("failed to sign GitHub App JWT: {}", e),
+            }
+        })
+    }
+
+    /// Exchange an app JWT for an installation access token.
+    ///
+    /// The returned token set has no refresh token. Granted permissions are
+    /// recorded as `name:level` scopes (e.g., `contents:read`).
+    pub async fn request_installation_token(&self) -> Result<TokenSet, TokenError> {
+        let url = format!(
+            "{}/app/installations/{}/access_tokens",
+            self.api_url, self.installation_id
+        );
+
+        let response = self
+            .http_client
+            .post(&url)
+            .bearer_auth(self.app_jwt()?)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .header(reqwest::header::USER_AGENT, "sigilforge")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+            .send()
+            .await
+            .map_err(|e| TokenError::NetworkError {
+                message: format!("installation token request failed: {}", e),
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(TokenError::OAuthError {
+                message: format!("GitHub returned {} for installation token: {}", status, body),
+            });
+        }
+
+        let body: InstallationTokenResponse =
+            response.json().await.map_err(|e| TokenError::OAuthError {
+                message: format!("invalid installation token response: {}", e),
+            })?;
+
+        let scopes = body
+            .permissions
+            .iter()
+            .map(|(name, level)| format!("{}:{}", name, level))
+            .collect();
+
+        let token = Token::new(body.token)
+            .with_expiry(body.expires_at)
+            .with_scopes(scopes);
+
+        Ok(TokenSet::new(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{DecodingKey, Validation};
+    use wiremock::matchers::{header, header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const TEST_PRIVATE_KEY: &str = include_str!("../../tests/fixtures/github_app_test_key.pem");
+    const TEST_PUBLIC_KEY: &str = include_str!("../../tests/fixtures/github_app_test_key.pub.pem");
+
+    fn test_flow(server: &MockServer) -> GitHubAppFlow {
+        GitHubAppFlow::new("12345", "67890", TEST_PRIVATE_KEY.as_bytes())
+            .unwrap()
+            .with_api_url(server.uri())
+    }
+
+    #[test]
+    fn test_app_jwt_claims() {
+        let flow = GitHubAppFlow::new("12345", "67890", TEST_PRIVATE_KEY.as_bytes()).unwrap();
+        let jwt = flow.app_jwt().unwrap();
+
+        let key = DecodingKey::from_rsa_pem(TEST_PUBLIC_KEY.as_bytes()).unwrap();
+        let validation = Validation::new(Algorithm::RS256);
+        let claims = jsonwebtoken::decode::<AppClaims>(&jwt, &key, &validation)
+            .unwrap()
+            .claims;
+
+        let now = Utc::now().timestamp();
+        assert_eq!(claims.iss, "12345");
+        assert!(claims.exp <= now + JWT_LIFETIME_SECS);
+        assert!(claims.exp > now + JWT_LIFETIME_SECS - 10);
+        assert!(claims.iat < now);
+    }
+
+    #[test]
+    fn test_invalid_private_key() {
+        let result = GitHubAppFlow::new("12345", "67890", b"not a key");
+        assert!(matches!(result, Err(TokenError::OAuthError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_request_installation_token() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/app/installations/67890/access_tokens"))
+            .and(header("accept", "application/vnd.github+json"))
+            .and(header_exists("authorization"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "token": "ghs_installation_token",
+                "expires_at": "2030-01-01T00:00:00Z",
+                "permissions": { "contents": "read", "issues": "write" },
+                "repository_selection": "all"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let token_set = test_flow(&server).request_installation_token().await.unwrap();
+
+        assert_eq!(
+            token_set.access_token.access_token.expose(),
+            "ghs_installation_token"
+        );
+        assert_eq!(
+            token_set.access_token.expires_at.unwrap().to_rfc3339(),
+            "2030-01-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            token_set.access_token.scopes,
+            vec!["contents:read", "issues:write"]
+        );
+        assert!(token_set.refresh_token.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_request_installation_token_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/app/installations/67890/access_tokens"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "message": "A JSON web token could not be decoded"
+            })))
+            .mount(&server)
+            .await;
+
+        let result = test_flow(&server).request_installation_token().await;
+
+        match result {
+            Err(TokenError::OAuthError { message }) => assert!(message.contains("401")),
+            other => panic!("expected OAuthError, got {:?}", other),
+        }
+    }
+}
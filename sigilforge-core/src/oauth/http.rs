@@ -0,0 +1,138 @@
+//! Adapter for sending `oauth2` requests through a caller-supplied
+//! [`reqwest::Client`].
+//!
+//! `oauth2::reqwest::async_http_client` builds its own client, so proxy,
+//! TLS, and timeout settings cannot be changed. [`send_request`] performs the
+//! same request with any client:
+//!
+//! ```rust,no_run
+//! # async fn example(client: sigilforge_core::oauth::OAuthClient) {
+//! use oauth2::RefreshToken;
+//! use sigilforge_core::oauth::http::send_request;
+//!
+//! let http_client = reqwest::Client::builder()
+//!     .proxy(reqwest::Proxy::all("http://proxy.internal:3128").unwrap())
+//!     .redirect(reqwest::redirect::Policy::none())
+//!     .build()
+//!     .unwrap();
+//!
+//! let response = client
+//!     .exchange_refresh_token(&RefreshToken::new("refresh".to_string()))
+//!     .request_async(|request| send_request(&http_client, request))
+//!     .await;
+//! # }
+//! ```
+//!
+//! Token endpoints should not be followed across redirects; build the client
+//! with `redirect::Policy::none()` as above.
+
+use oauth2::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use oauth2::{HttpRequest, HttpResponse};
+
+/// Errors from [`send_request`].
+#[derive(Debug, thiserror::Error)]
+pub enum HttpClientError {
+    /// The request could not be sent or the response body not read.
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// The `oauth2` request used a method reqwest does not accept.
+    #[error("invalid HTTP method: {0}")]
+    InvalidMethod(String),
+
+    /// The server answered with a status code outside 100-999.
+    #[error("invalid HTTP status code: {0}")]
+    InvalidStatus(u16),
+
+    /// The server answered with a 5xx status code.
+    ///
+    /// [`send_request`] returns such responses as they are; callers that
+    /// retry transient failures use this to report them as failed requests.
+    #[error("server error: HTTP {0}")]
+    ServerError(u16),
+}
+
+/// Send an `oauth2` HTTP request with `client` and convert the response.
+///
+/// Response headers that cannot be represented are dropped.
+pub async fn send_request(
+    client: &reqwest::Client,
+    request: HttpRequest,
+) -> Result<HttpResponse, HttpClientError> {
+    let method = reqwest::Method::from_bytes(request.method.as_str().as_bytes())
+        .map_err(|_| HttpClientError::InvalidMethod(request.method.to_string()))?;
+
+    let mut builder = client.request(method, request.url.as_str()).body(request.body);
+    for (name, value) in &request.headers {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+
+    let response = builder.send().await?;
+
+    let status = response.status().as_u16();
+    let status_code =
+        StatusCode::from_u16(status).map_err(|_| HttpClientError::InvalidStatus(status))?;
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in response.headers() {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_str().as_bytes()),
+            HeaderValue::from_bytes(value.as_bytes()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+
+    let body = response.bytes().await?.to_vec();
+
+    Ok(HttpResponse {
+        status_code,
+        headers,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oauth2::http::{header, Method};
+    use wiremock::{
+        matchers::{body_string, header as header_matcher, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_send_request_round_trip() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(header_matcher("content-type", "application/x-www-form-urlencoded"))
+            .and(body_string("grant_type=refresh_token"))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .insert_header("x-test", "yes")
+                    .set_body_string("{}"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        let request = HttpRequest {
+            url: format!("{}/token", server.uri()).parse().unwrap(),
+            method: Method::POST,
+            headers,
+            body: b"grant_type=refresh_token".to_vec(),
+        };
+
+        let response = send_request(&reqwest::Client::new(), request).await.unwrap();
+
+        assert_eq!(response.status_code, StatusCode::CREATED);
+        assert_eq!(response.headers.get("x-test").unwrap(), "yes");
+        assert_eq!(response.body, b"{}");
+    }
+}
@@ -3,6 +3,9 @@
 //! This module provides OAuth 2.0 flow implementations:
 //! - [`pkce`] - Authorization Code flow with PKCE
 //! - [`device_code`] - Device Authorization Grant flow
+//! - [`github_app`] - GitHub App installation access tokens
+//! - [`oidc`] - OpenID Connect ID token validation
+//! - [`http`] - Sending `oauth2` requests through a custom HTTP client
 //!
 //! # Features
 //!
@@ -14,15 +17,71 @@ pub mod pkce;
 #[cfg(feature = "oauth")]
 pub mod device_code;
 
+#[cfg(feature = "oauth")]
+pub mod github_app;
+
+#[cfg(feature = "oauth")]
+pub mod oidc;
+
+#[cfg(feature = "oauth")]
+pub mod http;
+
 #[cfg(feature = "oauth")]
 use oauth2::{
-    basic::BasicClient, AuthUrl, ClientId, ClientSecret, RedirectUrl, TokenUrl,
+    basic::{
+        BasicErrorResponse, BasicRevocationErrorResponse, BasicTokenIntrospectionResponse,
+        BasicTokenType,
+    },
+    AuthUrl, ClientId, ClientSecret, ExtraTokenFields, RedirectUrl, StandardRevocableToken,
+    StandardTokenResponse, TokenUrl,
 };
 #[cfg(feature = "oauth")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "oauth")]
 use crate::provider::ProviderConfig;
 #[cfg(feature = "oauth")]
 use crate::token::TokenError;
 
+/// Non-standard fields some providers include in token responses.
+///
+/// Unknown fields are ignored; these are the ones Sigilforge persists.
+#[cfg(feature = "oauth")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProviderTokenFields {
+    /// Dropbox account identifier (Dropbox returns this instead of `sub`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+
+    /// OpenID Connect ID token, validated by flows that receive one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+
+    /// Salesforce org instance (e.g. `https://example.my.salesforce.com`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_url: Option<String>,
+}
+
+#[cfg(feature = "oauth")]
+impl ExtraTokenFields for ProviderTokenFields {}
+
+/// Token response type that keeps [`ProviderTokenFields`].
+#[cfg(feature = "oauth")]
+pub type ProviderTokenResponse = StandardTokenResponse<ProviderTokenFields, BasicTokenType>;
+
+/// OAuth2 client used by all Sigilforge flows.
+///
+/// Equivalent to `oauth2::basic::BasicClient` except that token responses
+/// retain [`ProviderTokenFields`].
+#[cfg(feature = "oauth")]
+pub type OAuthClient = oauth2::Client<
+    BasicErrorResponse,
+    ProviderTokenResponse,
+    BasicTokenType,
+    BasicTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+>;
+
 /// Create an OAuth2 client from a provider configuration.
 ///
 /// # Arguments
@@ -41,7 +100,7 @@ pub fn create_oauth_client(
     client_id: impl Into<String>,
     client_secret: Option<impl Into<String>>,
     redirect_uri: Option<impl Into<String>>,
-) -> Result<BasicClient, TokenError> {
+) -> Result<OAuthClient, TokenError> {
     let auth_url = AuthUrl::new(config.auth_url.clone())
         .map_err(|e| TokenError::OAuthError {
             message: format!("invalid auth URL: {}", e),
@@ -52,7 +111,7 @@ pub fn create_oauth_client(
             message: format!("invalid token URL: {}", e),
         })?;
 
-    let mut client = BasicClient::new(
+    let mut client = OAuthClient::new(
         ClientId::new(client_id.into()),
         client_secret.map(|s| ClientSecret::new(s.into())),
         auth_url,
@@ -102,6 +161,12 @@ mod tests {
             default_scopes: vec![],
             supports_pkce: true,
             supports_device_code: false,
+            default_auth_params: Default::default(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         };
 
         let client = create_oauth_client(
@@ -125,6 +190,12 @@ mod tests {
             default_scopes: vec![],
             supports_pkce: true,
             supports_device_code: false,
+            default_auth_params: Default::default(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         };
 
         let client = create_oauth_client(
@@ -137,6 +208,30 @@ mod tests {
         assert!(client.is_err());
     }
 
+    #[test]
+    fn test_provider_token_fields_parse_account_id() {
+        let response: ProviderTokenResponse = serde_json::from_value(serde_json::json!({
+            "access_token": "sl.token",
+            "token_type": "bearer",
+            "expires_in": 14400,
+            "account_id": "dbid:AAH4f99T0taONIb-OurWxbNQ6ywGRopQngc",
+            "uid": "12345"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            response.extra_fields().account_id.as_deref(),
+            Some("dbid:AAH4f99T0taONIb-OurWxbNQ6ywGRopQngc")
+        );
+
+        let response: ProviderTokenResponse = serde_json::from_value(serde_json::json!({
+            "access_token": "token",
+            "token_type": "bearer"
+        }))
+        .unwrap();
+        assert!(response.extra_fields().account_id.is_none());
+    }
+
     #[test]
     fn test_generate_random_string() {
         let s1 = generate_random_string(32);
@@ -0,0 +1,491 @@
+//! OpenID Connect ID token validation.
+//!
+//! Providers that speak OpenID Connect return a signed `id_token` alongside
+//! the access token. [`OidcTokenValidator`] verifies its signature against the
+//! provider's JSON Web Key Set and then checks that the token was issued for
+//! this client:
+//!
+//! - `aud` must contain the expected audience (RFC 7519 §4.1.3), which is
+//!   [`ProviderConfig::expected_audience`] or the client ID.
+//! - When `aud` lists several audiences, `azp` must be present and name the
+//!   client (OpenID Connect Core §3.1.3.7).
+//! - `iss` must equal [`ProviderConfig::issuer`], when the provider has one.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use sigilforge_core::oauth::oidc::OidcTokenValidator;
+//!
+//! # async fn example(id_token: &str) -> Result<(), Box<dyn std::error::Error>> {
+//! let validator = OidcTokenValidator::fetch(
+//!     "https://www.googleapis.com/oauth2/v3/certs",
+//!     "my-client-id",
+//!     &reqwest::Client::new(),
+//! )
+//! .await?;
+//!
+//! let claims = validator.validate(id_token)?;
+//! println!("Signed in as {}", claims.sub);
+//! # Ok(())
+//! # }
+//! ```
+
+use jsonwebtoken::jwk::{JwkSet, PublicKeyUse};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::provider::ProviderConfig;
+use crate::token::TokenError;
+
+/// The `aud` claim: a single audience or a list of them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Audience {
+    /// A single audience string.
+    Single(String),
+    /// Several audiences.
+    Multiple(Vec<String>),
+}
+
+impl Audience {
+    /// Whether `audience` is one of the token's audiences.
+    pub fn contains(&self, audience: &str) -> bool {
+        match self {
+            Self::Single(aud) => aud == audience,
+            Self::Multiple(auds) => auds.iter().any(|aud| aud == audience),
+        }
+    }
+
+    /// Number of audiences.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Single(_) => 1,
+            Self::Multiple(auds) => auds.len(),
+        }
+    }
+
+    /// Whether the claim lists no audiences.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Claims of a validated ID token.
+///
+/// Only the standard claims Sigilforge uses are modeled; others are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdTokenClaims {
+    /// Issuer identifier.
+    pub iss: String,
+    /// Subject (the user's stable identifier at the provider).
+    pub sub: String,
+    /// Intended audience(s).
+    #[serde(default)]
+    pub aud: Option<Audience>,
+    /// Authorized party the token was issued to.
+    #[serde(default)]
+    pub azp: Option<String>,
+    /// Expiration time (seconds since the epoch).
+    pub exp: i64,
+    /// Issued-at time (seconds since the epoch).
+    #[serde(default)]
+    pub iat: Option<i64>,
+    /// Email address, if the `email` scope was granted.
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+/// A key an ID token may be signed with.
+#[derive(Clone)]
+struct VerificationKey {
+    kid: Option<String>,
+    key: DecodingKey,
+}
+
+/// Validates ID tokens issued for one OAuth client.
+#[derive(Clone)]
+pub struct OidcTokenValidator {
+    audience: String,
+    client_id: String,
+    issuer: Option<String>,
+    keys: Vec<VerificationKey>,
+}
+
+impl std::fmt::Debug for OidcTokenValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OidcTokenValidator")
+            .field("audience", &self.audience)
+            .field("client_id", &self.client_id)
+            .field("issuer", &self.issuer)
+            .field("keys", &self.keys.len())
+            .finish()
+    }
+}
+
+impl OidcTokenValidator {
+    /// Create a validator for `client_id` with no keys.
+    ///
+    /// The expected audience is the client ID; add keys with
+    /// [`with_jwks`](Self::with_jwks) or [`with_key`](Self::with_key).
+    pub fn new(client_id: impl Into<String>) -> Self {
+        let client_id = client_id.into();
+        Self {
+            audience: client_id.clone(),
+            client_id,
+            issuer: None,
+            keys: Vec::new(),
+        }
+    }
+
+    /// Create a validator using the provider's expected audience and issuer,
+    /// if set.
+    pub fn for_provider(config: &ProviderConfig, client_id: impl Into<String>) -> Self {
+        let mut validator = Self::new(client_id);
+        if let Some(audience) = &config.expected_audience {
+            validator = validator.with_audience(audience.clone());
+        }
+        if let Some(issuer) = &config.issuer {
+            validator = validator.with_issuer(issuer.clone());
+        }
+        validator
+    }
+
+    /// Fetch the key set at `jwks_uri` and build a validator for `client_id`.
+    pub async fn fetch(
+        jwks_uri: &str,
+        client_id: impl Into<String>,
+        http_client: &reqwest::Client,
+    ) -> Result<Self, TokenError> {
+        let jwks = fetch_jwks(jwks_uri, http_client).await?;
+        Self::new(client_id).with_jwks(&jwks)
+    }
+
+    /// Expect `audience` in the `aud` claim instead of the client ID.
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience = audience.into();
+        self
+    }
+
+    /// Require the `iss` claim to equal `issuer`.
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Add the signing keys from a JSON Web Key Set.
+    ///
+    /// Keys marked for encryption (`"use": "enc"`) are skipped.
+    pub fn with_jwks(mut self, jwks: &JwkSet) -> Result<Self, TokenError> {
+        for jwk in &jwks.keys {
+            if jwk.common.public_key_use == Some(PublicKeyUse::Encryption) {
+                continue;
+            }
+            let key = DecodingKey::from_jwk(jwk).map_err(|e| TokenError::OAuthError {
+                message: format!("invalid JWKS key: {}", e),
+            })?;
+            self.keys.push(VerificationKey {
+                kid: jwk.common.key_id.clone(),
+                key,
+            });
+        }
+        Ok(self)
+    }
+
+    /// Add a single signing key, optionally identified by `kid`.
+    pub fn with_key(mut self, kid: Option<String>, key: DecodingKey) -> Self {
+        self.keys.push(VerificationKey { kid, key });
+        self
+    }
+
+    /// The audience the `aud` claim must contain.
+    pub fn audience(&self) -> &str {
+        &self.audience
+    }
+
+    /// Verify an ID token's signature and claims.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TokenError::OAuthError`] if the signature, expiry, or issuer
+    /// is invalid, and `OAuthError { message: "invalid audience" }` if the
+    /// token was not issued for this client.
+    pub fn validate(&self, id_token: &str) -> Result<IdTokenClaims, TokenError> {
+        let header = jsonwebtoken::decode_header(id_token).map_err(invalid_token)?;
+
+        // Only asymmetric algorithms can be checked against a published key set
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(invalid_token(format!(
+                "unsupported algorithm {:?}",
+                header.alg
+            )));
+        }
+
+        let key = self.select_key(header.kid.as_deref())?;
+
+        let mut validation = Validation::new(header.alg);
+        // `aud` is checked below so a mismatch gets a precise error
+        validation.validate_aud = false;
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+
+        let claims = jsonwebtoken::decode::<IdTokenClaims>(id_token, key, &validation)
+            .map_err(invalid_token)?
+            .claims;
+
+        self.check_audience(&claims)?;
+        Ok(claims)
+    }
+
+    /// Pick the key named by `kid`, or the only key if the token names none.
+    fn select_key(&self, kid: Option<&str>) -> Result<&DecodingKey, TokenError> {
+        let key = match kid {
+            Some(kid) => self.keys.iter().find(|k| k.kid.as_deref() == Some(kid)),
+            None if self.keys.len() == 1 => self.keys.first(),
+            None => None,
+        };
+
+        key.map(|k| &k.key).ok_or_else(|| {
+            invalid_token(format!(
+                "no matching signing key for kid {}",
+                kid.unwrap_or("(none)")
+            ))
+        })
+    }
+
+    /// Apply the RFC 7519 `aud` and OpenID Connect `azp` rules.
+    fn check_audience(&self, claims: &IdTokenClaims) -> Result<(), TokenError> {
+        let audience_ok = match &claims.aud {
+            Some(aud) if aud.contains(&self.audience) => {
+                aud.len() == 1 || claims.azp.as_deref() == Some(self.client_id.as_str())
+            }
+            _ => false,
+        };
+        let azp_ok = claims
+            .azp
+            .as_deref()
+            .is_none_or(|azp| azp == self.client_id);
+
+        if audience_ok && azp_ok {
+            Ok(())
+        } else {
+            Err(TokenError::OAuthError {
+                message: "invalid audience".to_string(),
+            })
+        }
+    }
+}
+
+/// Fetch a JSON Web Key Set.
+pub async fn fetch_jwks(
+    jwks_uri: &str,
+    http_client: &reqwest::Client,
+) -> Result<JwkSet, TokenError> {
+    let response = http_client
+        .get(jwks_uri)
+        .send()
+        .await
+        .map_err(|e| TokenError::NetworkError {
+            message: format!("JWKS request failed: {}", e),
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(TokenError::NetworkError {
+            message: format!("JWKS endpoint returned {}", status),
+        });
+    }
+
+    response.json().await.map_err(|e| TokenError::OAuthError {
+        message: format!("invalid JWKS document: {}", e),
+    })
+}
+
+fn invalid_token(reason: impl std::fmt::Display) -> TokenError {
+    TokenError::OAuthError {
+        message: format!("invalid ID token: {}", reason),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    const TEST_PRIVATE_KEY: &str = include_str!("../../tests/fixtures/github_app_test_key.pem");
+    const TEST_JWKS: &str = include_str!("../../tests/fixtures/oidc_test_jwks.json");
+    const CLIENT_ID: &str = "client-id";
+
+    fn sign(claims: serde_json::Value) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some("test-key".to_string());
+        let key = EncodingKey::from_rsa_pem(TEST_PRIVATE_KEY.as_bytes()).unwrap();
+        jsonwebtoken::encode(&header, &claims, &key).unwrap()
+    }
+
+    fn claims_with(extra: serde_json::Value) -> serde_json::Value {
+        let mut claims = json!({
+            "iss": "https://issuer.example.com",
+            "sub": "user-123",
+            "exp": Utc::now().timestamp() + 3600,
+            "iat": Utc::now().timestamp(),
+        });
+        for (key, value) in extra.as_object().unwrap() {
+            claims[key] = value.clone();
+        }
+        claims
+    }
+
+    fn validator() -> OidcTokenValidator {
+        let jwks: JwkSet = serde_json::from_str(TEST_JWKS).unwrap();
+        OidcTokenValidator::new(CLIENT_ID).with_jwks(&jwks).unwrap()
+    }
+
+    fn assert_invalid_audience(result: Result<IdTokenClaims, TokenError>) {
+        match result {
+            Err(TokenError::OAuthError { message }) => assert_eq!(message, "invalid audience"),
+            other => panic!("expected invalid audience, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_valid_audience() {
+        let token = sign(claims_with(json!({ "aud": CLIENT_ID })));
+        let claims = validator().validate(&token).unwrap();
+
+        assert_eq!(claims.sub, "user-123");
+        assert_eq!(claims.aud, Some(Audience::Single(CLIENT_ID.to_string())));
+    }
+
+    #[test]
+    fn test_missing_audience() {
+        let token = sign(claims_with(json!({})));
+        assert_invalid_audience(validator().validate(&token));
+    }
+
+    #[test]
+    fn test_mismatched_audience() {
+        let token = sign(claims_with(json!({ "aud": "someone-else" })));
+        assert_invalid_audience(validator().validate(&token));
+
+        let token = sign(claims_with(json!({ "aud": ["someone-else", "another"] })));
+        assert_invalid_audience(validator().validate(&token));
+    }
+
+    #[test]
+    fn test_multiple_audiences_require_azp() {
+        let token = sign(claims_with(json!({ "aud": [CLIENT_ID, "api"] })));
+        assert_invalid_audience(validator().validate(&token));
+
+        let token = sign(claims_with(
+            json!({ "aud": [CLIENT_ID, "api"], "azp": "api" }),
+        ));
+        assert_invalid_audience(validator().validate(&token));
+
+        let token = sign(claims_with(
+            json!({ "aud": [CLIENT_ID, "api"], "azp": CLIENT_ID }),
+        ));
+        assert!(validator().validate(&token).is_ok());
+    }
+
+    #[test]
+    fn test_expected_audience_from_provider() {
+        let jwks: JwkSet = serde_json::from_str(TEST_JWKS).unwrap();
+        let config = ProviderConfig::new("test", "Test").with_expected_audience("api://sigil");
+        let validator = OidcTokenValidator::for_provider(&config, CLIENT_ID)
+            .with_jwks(&jwks)
+            .unwrap();
+        assert_eq!(validator.audience(), "api://sigil");
+
+        let token = sign(claims_with(json!({ "aud": "api://sigil" })));
+        assert!(validator.validate(&token).is_ok());
+
+        let token = sign(claims_with(json!({ "aud": CLIENT_ID })));
+        assert_invalid_audience(validator.validate(&token));
+    }
+
+    #[test]
+    fn test_issuer_from_provider() {
+        let jwks: JwkSet = serde_json::from_str(TEST_JWKS).unwrap();
+        let config = ProviderConfig::new("test", "Test").with_issuer("https://issuer.example.com");
+        let validator = OidcTokenValidator::for_provider(&config, CLIENT_ID)
+            .with_jwks(&jwks)
+            .unwrap();
+
+        let token = sign(claims_with(json!({ "aud": CLIENT_ID })));
+        assert!(validator.validate(&token).is_ok());
+
+        let token = sign(claims_with(
+            json!({ "aud": CLIENT_ID, "iss": "https://evil.example.com" }),
+        ));
+        assert!(matches!(
+            validator.validate(&token),
+            Err(TokenError::OAuthError { message }) if message.starts_with("invalid ID token")
+        ));
+
+        // Issuers are compared exactly
+        let token = sign(claims_with(
+            json!({ "aud": CLIENT_ID, "iss": "https://issuer.example.com/" }),
+        ));
+        assert!(validator.validate(&token).is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_signature_and_issuer() {
+        let token = sign(claims_with(json!({ "aud": CLIENT_ID })));
+        let (body, _signature) = token.rsplit_once('.').unwrap();
+        let tampered = format!("{}.{}", body, "AAAA");
+        assert!(matches!(
+            validator().validate(&tampered),
+            Err(TokenError::OAuthError { message }) if message.starts_with("invalid ID token")
+        ));
+
+        let result = validator()
+            .with_issuer("https://other.example.com")
+            .validate(&token);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_unknown_kid() {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some("rotated-away".to_string());
+        let key = EncodingKey::from_rsa_pem(TEST_PRIVATE_KEY.as_bytes()).unwrap();
+        let token =
+            jsonwebtoken::encode(&header, &claims_with(json!({ "aud": CLIENT_ID })), &key).unwrap();
+
+        let err = validator().validate(&token).unwrap_err();
+        assert!(err.to_string().contains("no matching signing key"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_jwks() {
+        use wiremock::{
+            Mock, MockServer, ResponseTemplate,
+            matchers::{method, path},
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/jwks"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(TEST_JWKS))
+            .mount(&server)
+            .await;
+
+        let validator = OidcTokenValidator::fetch(
+            &format!("{}/jwks", server.uri()),
+            CLIENT_ID,
+            &reqwest::Client::new(),
+        )
+        .await
+        .unwrap();
+
+        let token = sign(claims_with(json!({ "aud": CLIENT_ID })));
+        assert!(validator.validate(&token).is_ok());
+    }
+}
@@ -16,8 +16,9 @@
 //! ```rust,no_run
 //! # #[cfg(feature = "oauth")]
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-//! use sigilforge_core::oauth::pkce::PkceFlow;
+//! use sigilforge_core::oauth::pkce::{PkceFlow, RedirectConfig};
 //! use sigilforge_core::provider::ProviderRegistry;
+//! use std::collections::HashMap;
 //!
 //! let registry = ProviderRegistry::with_defaults();
 //! let github = registry.get("github").unwrap();
@@ -26,10 +27,11 @@
 //!     github.clone(),
 //!     "my-client-id".to_string(),
 //!     Some("my-client-secret".to_string()),
-//!     "http://localhost:8080/callback".to_string(),
+//!     RedirectConfig::localhost(8080),
 //! )?;
 //!
-//! let (auth_url, _csrf_state) = flow.build_authorization_url(vec!["repo".to_string()]);
+//! let (auth_url, _csrf_state) =
+//!     flow.build_authorization_url(vec!["repo".to_string()], HashMap::new());
 //! println!("Visit: {}", auth_url);
 //!
 //! // After user authorizes and you receive the code...
@@ -37,16 +39,195 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Ephemeral Callback Ports
+//!
+//! With a redirect port of `0`, the callback listener is bound first and the
+//! authorization URL is built afterwards with the port the OS assigned. This
+//! lets several flows run at once without competing for a fixed port:
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "oauth")]
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # use sigilforge_core::oauth::pkce::{PkceFlow, RedirectConfig};
+//! # use sigilforge_core::provider::ProviderRegistry;
+//! # use std::collections::HashMap;
+//! # let registry = ProviderRegistry::with_defaults();
+//! # let github = registry.get("github").unwrap();
+//! let redirect = RedirectConfig::localhost(0);
+//! let flow = PkceFlow::new(github.clone(), "client-id".to_string(), None, redirect)?;
+//!
+//! let (flow, listener) = flow.prepare_flow(vec!["repo".to_string()], "127.0.0.1").await?;
+//! let (auth_url, csrf_state) = flow.build_authorization_url(vec![], HashMap::new());
+//! println!("Visit: {}", auth_url);
+//!
+//! let code = flow.listen_for_callback(listener, &csrf_state).await?;
+//! let token_set = flow.exchange_code(code).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Verifier Settings
+//!
+//! Flows use a 128-character verifier and the `S256` challenge method by
+//! default. [`PkceFlow::with_pkce_config`] changes either, for providers that
+//! require a shorter verifier or only understand `plain`.
+//!
+//! # Detached Flows
+//!
+//! When nothing can listen for the redirect (e.g., on a headless machine),
+//! [`PkceFlow::detached_authorization_url`] builds the URL without a listener
+//! and returns a [`PendingAuthorization`]. The user copies the `code` from the
+//! redirect, and a later flow, possibly in another process, calls
+//! [`PkceFlow::resume`] with that state before [`PkceFlow::exchange_code`].
+//!
+//! # Retries
+//!
+//! A code exchange that fails to reach the token endpoint, or gets a 5xx
+//! response, is retried with the same code and verifier, backing off
+//! between attempts as set by [`PkceFlowConfig`]. Errors reported by the
+//! provider, such as an invalid code, are returned at once.
 
 use oauth2::{
-    AuthorizationCode, CsrfToken, PkceCodeChallenge, PkceCodeVerifier, Scope,
-    TokenResponse, reqwest::async_http_client,
+    AuthorizationCode, CsrfToken, HttpRequest, HttpResponse, PkceCodeChallenge, PkceCodeVerifier,
+    RequestTokenError, Scope, TokenResponse,
 };
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
 
 use crate::provider::ProviderConfig;
+use crate::store::Secret;
 use crate::token::{Token, TokenSet, TokenError};
-use super::create_oauth_client;
+use super::http::{send_request, HttpClientError};
+use super::oidc::{fetch_jwks, OidcTokenValidator};
+use super::{create_oauth_client, generate_random_string};
+
+/// Shortest `code_verifier` allowed by RFC 7636 §4.1.
+pub const MIN_VERIFIER_LENGTH: usize = 43;
+
+/// Longest `code_verifier` allowed by RFC 7636 §4.1.
+pub const MAX_VERIFIER_LENGTH: usize = 128;
+
+/// How the `code_challenge` is derived from the verifier (RFC 7636 §4.2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PkceMethod {
+    /// Base64url-encoded SHA-256 hash of the verifier
+    #[default]
+    S256,
+    /// The verifier itself; only for providers that cannot do `S256`
+    Plain,
+}
+
+/// Code verifier settings for a [`PkceFlow`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PkceConfig {
+    /// Number of characters in each generated verifier (43 to 128)
+    pub verifier_length: usize,
+    /// Method used to derive the challenge sent in the authorization URL
+    pub challenge_method: PkceMethod,
+}
+
+impl Default for PkceConfig {
+    fn default() -> Self {
+        Self {
+            verifier_length: MAX_VERIFIER_LENGTH,
+            challenge_method: PkceMethod::S256,
+        }
+    }
+}
+
+impl PkceConfig {
+    /// Check that `verifier_length` is within the range RFC 7636 allows.
+    pub fn validate(&self) -> Result<(), TokenError> {
+        if !(MIN_VERIFIER_LENGTH..=MAX_VERIFIER_LENGTH).contains(&self.verifier_length) {
+            return Err(TokenError::OAuthError {
+                message: format!(
+                    "PKCE verifier length {} is outside {}..={}",
+                    self.verifier_length, MIN_VERIFIER_LENGTH, MAX_VERIFIER_LENGTH
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Retry settings for a [`PkceFlow`]'s code exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PkceFlowConfig {
+    /// Retries after a network failure or 5xx response (default: 3)
+    pub exchange_max_retries: u32,
+    /// Wait before the first retry, doubled for each one after (default: 500 ms)
+    pub exchange_retry_delay: Duration,
+}
+
+impl Default for PkceFlowConfig {
+    fn default() -> Self {
+        Self {
+            exchange_max_retries: 3,
+            exchange_retry_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Where the provider redirects the browser after authorization.
+///
+/// The callback listener binds to `host:port`. A `port` of `0` asks the OS for
+/// an ephemeral port; [`PkceFlow::prepare_flow`] binds it before any
+/// authorization URL is built, so the URL carries the port actually bound.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedirectConfig {
+    /// Host to bind and to use in the redirect URI (e.g., `127.0.0.1`)
+    pub host: String,
+    /// Port to bind, or `0` for an ephemeral port
+    pub port: u16,
+    /// Path of the redirect URI (e.g., `/callback`)
+    pub path: String,
+}
+
+impl RedirectConfig {
+    /// Create a redirect configuration.
+    pub fn new(host: impl Into<String>, port: u16, path: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            path: path.into(),
+        }
+    }
+
+    /// Redirect to `http://127.0.0.1:{port}/callback`.
+    pub fn localhost(port: u16) -> Self {
+        Self::new("127.0.0.1", port, "/callback")
+    }
+
+    /// The redirect URI for this configuration.
+    pub fn uri(&self) -> String {
+        let path = if self.path.starts_with('/') {
+            self.path.clone()
+        } else {
+            format!("/{}", self.path)
+        };
+        format!("http://{}:{}{}", self.host, self.port, path)
+    }
+}
+
+/// State needed to finish an authorization started by
+/// [`PkceFlow::detached_authorization_url`].
+///
+/// Holds the PKCE verifier, so store it where only the user can read it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingAuthorization {
+    /// Redirect URI the authorization URL was built with
+    pub redirect_uri: String,
+    /// Verifier matching the URL's `code_challenge`
+    pub code_verifier: Secret,
+    /// Set once the code has been exchanged; a used authorization cannot be
+    /// resumed
+    #[serde(default)]
+    pub pkce_verifier_used: bool,
+}
 
 /// PKCE flow implementation for OAuth 2.0 authorization code flow.
 ///
@@ -56,8 +237,19 @@ pub struct PkceFlow {
     config: ProviderConfig,
     client_id: String,
     client_secret: Option<String>,
-    redirect_uri: String,
+    redirect: RedirectConfig,
+    /// Redirect URI sent in the last authorization URL; the token exchange
+    /// must repeat it exactly
+    redirect_uri: Arc<Mutex<String>>,
     verifier: Arc<Mutex<Option<PkceCodeVerifier>>>,
+    /// Verifier length and challenge method
+    pkce: PkceConfig,
+    /// Code exchange retries
+    flow_config: PkceFlowConfig,
+    /// Validator for ID tokens; fetched from `jwks_uri` when not set
+    id_token_validator: Option<OidcTokenValidator>,
+    /// Scopes requested by every authorization URL, set by `prepare_flow`
+    scopes: Vec<String>,
 }
 
 impl PkceFlow {
@@ -68,12 +260,12 @@ impl PkceFlow {
     /// * `config` - OAuth provider configuration
     /// * `client_id` - OAuth client ID
     /// * `client_secret` - Optional client secret (for confidential clients)
-    /// * `redirect_uri` - Redirect URI registered with the provider
+    /// * `redirect` - Redirect location registered with the provider
     pub fn new(
         config: ProviderConfig,
         client_id: String,
         client_secret: Option<String>,
-        redirect_uri: String,
+        redirect: RedirectConfig,
     ) -> Result<Self, TokenError> {
         if !config.supports_pkce {
             tracing::warn!(
@@ -86,11 +278,88 @@ impl PkceFlow {
             config,
             client_id,
             client_secret,
-            redirect_uri,
+            redirect_uri: Arc::new(Mutex::new(redirect.uri())),
+            redirect,
             verifier: Arc::new(Mutex::new(None)),
+            pkce: PkceConfig::default(),
+            flow_config: PkceFlowConfig::default(),
+            id_token_validator: None,
+            scopes: Vec::new(),
         })
     }
 
+    /// Generate verifiers and challenges according to `pkce`.
+    ///
+    /// The `plain` method sends the verifier itself, so it is refused with a
+    /// warning (keeping `S256`) for providers that declare PKCE support.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pkce.verifier_length` is outside 43 to 128.
+    pub fn with_pkce_config(mut self, mut pkce: PkceConfig) -> Result<Self, TokenError> {
+        pkce.validate()?;
+
+        if pkce.challenge_method == PkceMethod::Plain && self.config.supports_pkce {
+            tracing::warn!(
+                "Provider {} supports PKCE; refusing the insecure plain method and using S256",
+                self.config.id
+            );
+            pkce.challenge_method = PkceMethod::S256;
+        }
+
+        self.pkce = pkce;
+        Ok(self)
+    }
+
+    /// Retry code exchanges according to `flow_config`.
+    pub fn with_flow_config(mut self, flow_config: PkceFlowConfig) -> Self {
+        self.flow_config = flow_config;
+        self
+    }
+
+    /// Validate ID tokens with `validator` instead of the provider's `jwks_uri`.
+    pub fn with_id_token_validator(mut self, validator: OidcTokenValidator) -> Self {
+        self.id_token_validator = Some(validator);
+        self
+    }
+
+    /// The redirect configuration this flow was created with.
+    pub fn redirect(&self) -> &RedirectConfig {
+        &self.redirect
+    }
+
+    /// Bind the callback listener on `callback_host` and return the flow that
+    /// redirects to it, along with the listener.
+    ///
+    /// Binding comes before any authorization URL is built, so a redirect
+    /// port of `0` works: the OS picks a free port and the returned flow's
+    /// redirect URI carries it. Build the URL from the returned flow, then
+    /// pass the listener to [`listen_for_callback`](Self::listen_for_callback).
+    /// `scopes` are requested by every URL the returned flow builds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listener cannot be bound.
+    pub async fn prepare_flow(
+        mut self,
+        scopes: Vec<String>,
+        callback_host: &str,
+    ) -> Result<(PkceFlow, TcpListener), TokenError> {
+        let addr = format!("{}:{}", callback_host, self.redirect.port);
+        let bind_error = |e: std::io::Error| TokenError::OAuthError {
+            message: format!("failed to bind to {}: {}", addr, e),
+        };
+        let listener = TcpListener::bind(&addr).await.map_err(bind_error)?;
+        let local_addr = listener.local_addr().map_err(bind_error)?;
+        tracing::info!("Listening for OAuth callback on {}", local_addr);
+
+        self.redirect.host = callback_host.to_string();
+        self.redirect.port = local_addr.port();
+        *self.redirect_uri.lock().unwrap() = self.redirect.uri();
+        self.scopes = scopes;
+        Ok((self, listener))
+    }
+
     /// Build an authorization URL for the user to visit.
     ///
     /// This generates a new PKCE code verifier and challenge, and constructs
@@ -99,22 +368,105 @@ impl PkceFlow {
     /// # Arguments
     ///
     /// * `scopes` - OAuth scopes to request
+    /// * `extra_params` - Query parameters for this authorization only (e.g.,
+    ///   Google's `prompt=consent`), merged over the provider's
+    ///   [`default_auth_params`](ProviderConfig::default_auth_params); they
+    ///   play no part in the PKCE challenge
     ///
     /// # Returns
     ///
     /// A tuple of (authorization URL, CSRF state token). The state token should
     /// be verified when receiving the redirect to prevent CSRF attacks.
-    pub fn build_authorization_url(&self, scopes: Vec<String>) -> (String, String) {
+    ///
+    /// The redirect URI uses the configured port as is; with port `0`, build
+    /// the URL from the flow returned by [`prepare_flow`](Self::prepare_flow).
+    pub fn build_authorization_url(
+        &self,
+        scopes: Vec<String>,
+        extra_params: HashMap<String, String>,
+    ) -> (String, String) {
+        self.authorization_url(scopes, extra_params, self.redirect.uri())
+    }
+
+    /// Build an authorization URL whose code is exchanged later.
+    ///
+    /// No callback listener is started; the user copies the `code` from the
+    /// redirect instead. Pass the returned state to [`resume`](Self::resume)
+    /// before calling [`exchange_code`](Self::exchange_code).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the redirect port is `0`, since the exchange must
+    /// repeat the exact redirect URI.
+    pub fn detached_authorization_url(
+        &self,
+        scopes: Vec<String>,
+    ) -> Result<(String, PendingAuthorization), TokenError> {
+        if self.redirect.port == 0 {
+            return Err(TokenError::OAuthError {
+                message: "a detached authorization needs a fixed redirect port".to_string(),
+            });
+        }
+
+        let redirect_uri = self.redirect.uri();
+        let (url, _csrf_state) =
+            self.authorization_url(scopes, HashMap::new(), redirect_uri.clone());
+        let code_verifier = self
+            .verifier
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|verifier| Secret::new(verifier.secret().as_str()))
+            .expect("authorization_url stores a verifier");
+
+        let pending = PendingAuthorization {
+            redirect_uri,
+            code_verifier,
+            pkce_verifier_used: false,
+        };
+        Ok((url, pending))
+    }
+
+    /// Restore the state of a detached authorization so that
+    /// [`exchange_code`](Self::exchange_code) can finish it.
+    ///
+    /// Fails if `pending.pkce_verifier_used` is set.
+    pub fn resume(&self, pending: &PendingAuthorization) -> Result<(), TokenError> {
+        if pending.pkce_verifier_used {
+            return Err(TokenError::OAuthError {
+                message: "the PKCE verifier of this authorization was already used".to_string(),
+            });
+        }
+
+        *self.redirect_uri.lock().unwrap() = pending.redirect_uri.clone();
+        let verifier = PkceCodeVerifier::new(pending.code_verifier.expose().to_string());
+        *self.verifier.lock().unwrap() = Some(verifier);
+        Ok(())
+    }
+
+    fn authorization_url(
+        &self,
+        scopes: Vec<String>,
+        extra_params: HashMap<String, String>,
+        redirect_uri: String,
+    ) -> (String, String) {
         let client = create_oauth_client(
             &self.config,
             &self.client_id,
             self.client_secret.as_ref(),
-            Some(&self.redirect_uri),
+            Some(&redirect_uri),
         )
         .expect("OAuth client configuration should be valid");
 
+        *self.redirect_uri.lock().unwrap() = redirect_uri;
+
         // Generate PKCE challenge
-        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let pkce_verifier =
+            PkceCodeVerifier::new(generate_random_string(self.pkce.verifier_length));
+        let pkce_challenge = match self.pkce.challenge_method {
+            PkceMethod::S256 => PkceCodeChallenge::from_code_verifier_sha256(&pkce_verifier),
+            PkceMethod::Plain => PkceCodeChallenge::from_code_verifier_plain(&pkce_verifier),
+        };
 
         // Store verifier for later use
         *self.verifier.lock().unwrap() = Some(pkce_verifier);
@@ -124,11 +476,26 @@ impl PkceFlow {
             .authorize_url(CsrfToken::new_random)
             .set_pkce_challenge(pkce_challenge);
 
-        // Add scopes
+        // Add scopes, starting with those given to prepare_flow
+        let mut requested = self.scopes.clone();
         for scope in scopes {
+            if !requested.contains(&scope) {
+                requested.push(scope);
+            }
+        }
+        for scope in requested {
             auth_request = auth_request.add_scope(Scope::new(scope));
         }
 
+        // Add provider defaults (e.g., token_access_type=offline), letting the
+        // caller's parameters override them; sorted so URLs are deterministic
+        let mut params = BTreeMap::new();
+        params.extend(self.config.default_auth_params.clone());
+        params.extend(extra_params);
+        for (key, value) in params {
+            auth_request = auth_request.add_extra_param(key, value);
+        }
+
         let (url, csrf_state) = auth_request.url();
 
         (url.to_string(), csrf_state.secret().to_string())
@@ -138,6 +505,8 @@ impl PkceFlow {
     ///
     /// This exchanges the authorization code received from the redirect for
     /// an access token (and optionally a refresh token) using the PKCE verifier.
+    /// Network failures and 5xx responses are retried as configured with
+    /// [`with_flow_config`](Self::with_flow_config).
     ///
     /// # Arguments
     ///
@@ -148,29 +517,80 @@ impl PkceFlow {
     /// Returns an error if:
     /// - The PKCE verifier is not available (authorization URL not generated)
     /// - The token exchange fails
-    /// - Network errors occur
+    /// - Network errors persist after the last retry
+    /// - The response carries an `id_token` that fails validation (bad
+    ///   signature, or an audience other than this client)
     pub async fn exchange_code(&self, code: impl Into<String>) -> Result<TokenSet, TokenError> {
         let verifier = self.verifier.lock().unwrap().take()
             .ok_or_else(|| TokenError::OAuthError {
                 message: "PKCE verifier not found. Call build_authorization_url first.".to_string(),
             })?;
 
+        // The first attempt consumes the verifier; retries rebuild it
+        let verifier_secret = verifier.secret().to_string();
+        let mut verifier = Some(verifier);
+
+        let redirect_uri = self.redirect_uri.lock().unwrap().clone();
         let client = create_oauth_client(
             &self.config,
             &self.client_id,
             self.client_secret.as_ref(),
-            Some(&self.redirect_uri),
+            Some(&redirect_uri),
         )?;
-
-        let token_result = client
-            .exchange_code(AuthorizationCode::new(code.into()))
-            .set_pkce_verifier(verifier)
-            .request_async(async_http_client)
-            .await
-            .map_err(|e| TokenError::OAuthError {
-                message: format!("token exchange failed: {}", e),
+        // Token endpoints must not be followed across redirects
+        let http_client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| TokenError::NetworkError {
+                message: format!("failed to build HTTP client: {}", e),
             })?;
 
+        let code = code.into();
+        let mut delay = self.flow_config.exchange_retry_delay;
+        let mut retries = 0;
+        let token_result = loop {
+            let verifier = verifier
+                .take()
+                .unwrap_or_else(|| PkceCodeVerifier::new(verifier_secret.clone()));
+            let result = client
+                .exchange_code(AuthorizationCode::new(code.clone()))
+                .set_pkce_verifier(verifier)
+                .request_async(|request| send_token_request(&http_client, request))
+                .await;
+
+            match result {
+                Ok(response) => break response,
+                Err(RequestTokenError::Request(e))
+                    if retries < self.flow_config.exchange_max_retries =>
+                {
+                    retries += 1;
+                    tracing::warn!(
+                        "Token exchange failed ({}); retry {} of {} in {:?}",
+                        e,
+                        retries,
+                        self.flow_config.exchange_max_retries,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+                Err(RequestTokenError::Request(e)) => {
+                    return Err(TokenError::NetworkError {
+                        message: format!("token exchange failed: {}", e),
+                    });
+                }
+                Err(e) => {
+                    return Err(TokenError::OAuthError {
+                        message: format!("token exchange failed: {}", e),
+                    });
+                }
+            }
+        };
+
+        if let Some(id_token) = &token_result.extra_fields().id_token {
+            self.validate_id_token(id_token).await?;
+        }
+
         // Extract token information
         let access_token = token_result.access_token().secret().to_string();
         let expires_in = token_result.expires_in();
@@ -198,19 +618,53 @@ impl PkceFlow {
             token_set = token_set.with_refresh_token(refresh_token.secret());
         }
 
+        // Keep provider-specific subject identifiers (e.g., Dropbox account_id)
+        if let Some(account_id) = &token_result.extra_fields().account_id {
+            token_set = token_set.with_subject(account_id);
+        }
+        if let Some(instance_url) = &token_result.extra_fields().instance_url {
+            token_set = token_set.with_instance_url(instance_url);
+        }
+
         Ok(token_set)
     }
 
-    /// Start a local HTTP server to listen for the OAuth callback.
+    /// Verify an ID token returned by the token endpoint.
     ///
-    /// This is a convenience method that starts a simple HTTP server on the
-    /// specified port to receive the authorization code. The server will
+    /// Without a configured validator the provider's key set is fetched from
+    /// its `jwks_uri`; providers with neither cannot be verified and the
+    /// token is accepted with a warning.
+    async fn validate_id_token(&self, id_token: &str) -> Result<(), TokenError> {
+        if let Some(validator) = &self.id_token_validator {
+            validator.validate(id_token)?;
+            return Ok(());
+        }
+
+        let Some(jwks_uri) = &self.config.jwks_uri else {
+            tracing::warn!(
+                "Provider {} returned an ID token but has no jwks_uri; skipping validation",
+                self.config.id
+            );
+            return Ok(());
+        };
+
+        let jwks = fetch_jwks(jwks_uri, &reqwest::Client::new()).await?;
+        let validator = OidcTokenValidator::for_provider(&self.config, self.client_id.clone())
+            .with_jwks(&jwks)?;
+        validator.validate(id_token)?;
+        Ok(())
+    }
+
+    /// Serve the OAuth callback on a listener bound by [`prepare_flow`](Self::prepare_flow).
+    ///
+    /// This is a convenience method that runs a simple HTTP server on
+    /// `listener` to receive the authorization code. The server will
     /// automatically shut down after receiving the callback.
     ///
     /// # Arguments
     ///
-    /// * `port` - Port to listen on (must match the redirect URI)
-    /// * `csrf_state` - Expected CSRF state token for validation
+    /// * `listener` - Callback listener returned by `prepare_flow`
+    /// * `expected_state` - Expected CSRF state token for validation
     ///
     /// # Returns
     ///
@@ -221,125 +675,160 @@ impl PkceFlow {
     /// ```rust,no_run
     /// # #[cfg(feature = "oauth")]
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-    /// # use sigilforge_core::oauth::pkce::PkceFlow;
+    /// # use sigilforge_core::oauth::pkce::{PkceFlow, RedirectConfig};
     /// # use sigilforge_core::provider::ProviderRegistry;
+    /// # use std::collections::HashMap;
     /// # let registry = ProviderRegistry::with_defaults();
     /// # let github = registry.get("github").unwrap();
     /// # let flow = PkceFlow::new(
     /// #     github.clone(),
     /// #     "client-id".to_string(),
     /// #     None,
-    /// #     "http://localhost:8080/callback".to_string(),
+    /// #     RedirectConfig::localhost(0),
     /// # )?;
-    /// let (auth_url, csrf_state) = flow.build_authorization_url(vec![]);
+    /// let (flow, listener) = flow.prepare_flow(vec![], "127.0.0.1").await?;
+    /// let (auth_url, csrf_state) = flow.build_authorization_url(vec![], HashMap::new());
     ///
     /// println!("Visit: {}", auth_url);
-    /// let code = flow.listen_for_callback(8080, &csrf_state).await?;
+    /// let code = flow.listen_for_callback(listener, &csrf_state).await?;
     /// let token_set = flow.exchange_code(code).await?;
     /// # Ok(())
     /// # }
     /// ```
     pub async fn listen_for_callback(
         &self,
-        port: u16,
+        listener: TcpListener,
         expected_state: &str,
     ) -> Result<String, TokenError> {
-        use tokio::net::TcpListener;
-        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        accept_callback(listener, expected_state).await
+    }
+}
+
+/// Send a token request, reporting a 5xx response as a failed request so
+/// that the exchange is retried.
+async fn send_token_request(
+    client: &reqwest::Client,
+    request: HttpRequest,
+) -> Result<HttpResponse, HttpClientError> {
+    let response = send_request(client, request).await?;
+    if response.status_code.is_server_error() {
+        return Err(HttpClientError::ServerError(response.status_code.as_u16()));
+    }
+    Ok(response)
+}
 
-        let addr = format!("127.0.0.1:{}", port);
-        let listener = TcpListener::bind(&addr)
+/// Serve the callback endpoint on `listener` until a valid redirect arrives.
+async fn accept_callback(
+    listener: TcpListener,
+    expected_state: &str,
+) -> Result<String, TokenError> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    loop {
+        let (mut socket, _) = listener.accept()
             .await
             .map_err(|e| TokenError::OAuthError {
-                message: format!("failed to bind to {}: {}", addr, e),
+                message: format!("failed to accept connection: {}", e),
             })?;
 
-        tracing::info!("Listening for OAuth callback on {}", addr);
+        let mut buffer = [0; 4096];
+        let n = socket.read(&mut buffer)
+            .await
+            .map_err(|e| TokenError::OAuthError {
+                message: format!("failed to read request: {}", e),
+            })?;
 
-        loop {
-            let (mut socket, _) = listener.accept()
-                .await
-                .map_err(|e| TokenError::OAuthError {
-                    message: format!("failed to accept connection: {}", e),
-                })?;
+        let request = String::from_utf8_lossy(&buffer[..n]);
 
-            let mut buffer = [0; 4096];
-            let n = socket.read(&mut buffer)
-                .await
-                .map_err(|e| TokenError::OAuthError {
-                    message: format!("failed to read request: {}", e),
-                })?;
+        // Parse the request line
+        if let Some(first_line) = request.lines().next() {
+            if let Some(path) = first_line.split_whitespace().nth(1) {
+                // Parse query parameters
+                if let Some(query) = path.split('?').nth(1) {
+                    let mut code = None;
+                    let mut state = None;
+                    let mut error = None;
 
-            let request = String::from_utf8_lossy(&buffer[..n]);
-
-            // Parse the request line
-            if let Some(first_line) = request.lines().next() {
-                if let Some(path) = first_line.split_whitespace().nth(1) {
-                    // Parse query parameters
-                    if let Some(query) = path.split('?').nth(1) {
-                        let mut code = None;
-                        let mut state = None;
-                        let mut error = None;
-
-                        for param in query.split('&') {
-                            let parts: Vec<&str> = param.splitn(2, '=').collect();
-                            if parts.len() == 2 {
-                                match parts[0] {
-                                    "code" => code = Some(parts[1].to_string()),
-                                    "state" => state = Some(parts[1].to_string()),
-                                    "error" => error = Some(parts[1].to_string()),
-                                    _ => {}
-                                }
+                    for param in query.split('&') {
+                        let parts: Vec<&str> = param.splitn(2, '=').collect();
+                        if parts.len() == 2 {
+                            match parts[0] {
+                                "code" => code = Some(parts[1].to_string()),
+                                "state" => state = Some(parts[1].to_string()),
+                                "error" => error = Some(parts[1].to_string()),
+                                _ => {}
                             }
                         }
+                    }
+
+                    // Check for OAuth error
+                    if let Some(err) = error {
+                        let response = b"HTTP/1.1 200 OK\r\n\r\n\
+                            <html><body><h1>Authentication Failed</h1>\
+                            <p>The OAuth provider returned an error.</p></body></html>";
+                        let _ = socket.write_all(response).await;
+
+                        return Err(TokenError::OAuthError {
+                            message: format!("OAuth provider returned error: {}", err),
+                        });
+                    }
 
-                        // Check for OAuth error
-                        if let Some(err) = error {
+                    // Verify state
+                    if let Some(received_state) = &state {
+                        if received_state != expected_state {
                             let response = b"HTTP/1.1 200 OK\r\n\r\n\
                                 <html><body><h1>Authentication Failed</h1>\
-                                <p>The OAuth provider returned an error.</p></body></html>";
+                                <p>Invalid state parameter (CSRF protection).</p></body></html>";
                             let _ = socket.write_all(response).await;
 
                             return Err(TokenError::OAuthError {
-                                message: format!("OAuth provider returned error: {}", err),
+                                message: "state parameter mismatch".to_string(),
                             });
                         }
+                    }
 
-                        // Verify state
-                        if let Some(received_state) = &state {
-                            if received_state != expected_state {
-                                let response = b"HTTP/1.1 200 OK\r\n\r\n\
-                                    <html><body><h1>Authentication Failed</h1>\
-                                    <p>Invalid state parameter (CSRF protection).</p></body></html>";
-                                let _ = socket.write_all(response).await;
-
-                                return Err(TokenError::OAuthError {
-                                    message: "state parameter mismatch".to_string(),
-                                });
-                            }
-                        }
+                    // Return the code
+                    if let Some(auth_code) = code {
+                        let response = b"HTTP/1.1 200 OK\r\n\r\n\
+                            <html><body><h1>Authentication Successful!</h1>\
+                            <p>You can close this window and return to your application.</p></body></html>";
+                        let _ = socket.write_all(response).await;
 
-                        // Return the code
-                        if let Some(auth_code) = code {
-                            let response = b"HTTP/1.1 200 OK\r\n\r\n\
-                                <html><body><h1>Authentication Successful!</h1>\
-                                <p>You can close this window and return to your application.</p></body></html>";
-                            let _ = socket.write_all(response).await;
-
-                            return Ok(auth_code);
-                        }
+                        return Ok(auth_code);
                     }
                 }
             }
-
-            // If we got here, something was wrong with the request
-            let response = b"HTTP/1.1 400 Bad Request\r\n\r\n\
-                <html><body><h1>Bad Request</h1></body></html>";
-            let _ = socket.write_all(response).await;
         }
+
+        // If we got here, something was wrong with the request
+        let response = b"HTTP/1.1 400 Bad Request\r\n\r\n\
+            <html><body><h1>Bad Request</h1></body></html>";
+        let _ = socket.write_all(response).await;
     }
 }
 
+/// Open `url` in the user's default browser.
+///
+/// The launcher's output is discarded so it cannot draw over a TUI.
+pub fn open_browser(url: &str) -> std::io::Result<()> {
+    use std::process::{Command, Stdio};
+
+    #[cfg(target_os = "macos")]
+    let (program, args) = ("open", vec![url]);
+    #[cfg(target_os = "windows")]
+    let (program, args) = ("cmd", vec!["/C", "start", url]);
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let (program, args) = ("xdg-open", vec![url]);
+
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
 #[cfg(all(test, feature = "oauth"))]
 mod tests {
     use super::*;
@@ -355,13 +844,19 @@ mod tests {
             default_scopes: vec![],
             supports_pkce: true,
             supports_device_code: false,
+            default_auth_params: Default::default(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         };
 
         let flow = PkceFlow::new(
             config,
             "client-id".to_string(),
             None,
-            "http://localhost:8080/callback".to_string(),
+            RedirectConfig::localhost(8080),
         );
 
         assert!(flow.is_ok());
@@ -378,17 +873,23 @@ mod tests {
             default_scopes: vec![],
             supports_pkce: true,
             supports_device_code: false,
+            default_auth_params: Default::default(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         };
 
         let flow = PkceFlow::new(
             config,
             "client-id".to_string(),
             None,
-            "http://localhost:8080/callback".to_string(),
+            RedirectConfig::localhost(8080),
         )
         .unwrap();
 
-        let (url, state) = flow.build_authorization_url(vec!["read".to_string()]);
+        let (url, state) = flow.build_authorization_url(vec!["read".to_string()], HashMap::new());
 
         assert!(url.contains("https://example.com/auth"));
         assert!(url.contains("client_id=client-id"));
@@ -396,4 +897,719 @@ mod tests {
         assert!(url.contains("code_challenge_method=S256"));
         assert!(!state.is_empty());
     }
+
+    #[test]
+    fn test_build_authorization_url_twitter() {
+        let registry = crate::provider::ProviderRegistry::with_defaults();
+        let twitter = registry.get("twitter").unwrap().clone();
+        let scopes = twitter.default_scopes.clone();
+
+        let flow = PkceFlow::new(
+            twitter,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap();
+
+        let (url, state) = flow.build_authorization_url(scopes, HashMap::new());
+
+        assert!(url.starts_with("https://twitter.com/i/oauth2/authorize?"));
+        assert!(url.contains("response_type=code"));
+        assert!(url.contains("code_challenge="));
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains("offline.access"));
+        assert!(url.contains(&format!("state={}", state)));
+    }
+
+    #[test]
+    fn test_build_authorization_url_dropbox_extra_params() {
+        let registry = crate::provider::ProviderRegistry::with_defaults();
+        let dropbox = registry.get("dropbox").unwrap().clone();
+        let scopes = dropbox.default_scopes.clone();
+
+        let flow = PkceFlow::new(
+            dropbox,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap();
+
+        let (url, _state) = flow.build_authorization_url(scopes, HashMap::new());
+
+        assert!(url.starts_with("https://www.dropbox.com/oauth2/authorize?"));
+        assert!(url.contains("token_access_type=offline"));
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains("files.content.read"));
+    }
+
+    #[test]
+    fn test_build_authorization_url_google_extra_params() {
+        let registry = crate::provider::ProviderRegistry::with_defaults();
+        let google = registry.get("google").unwrap().clone();
+        let scopes = google.default_scopes.clone();
+
+        let flow = PkceFlow::new(
+            google,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap();
+
+        let extra_params = HashMap::from([("prompt".to_string(), "consent".to_string())]);
+        let (url, _state) = flow.build_authorization_url(scopes, extra_params);
+
+        assert!(url.starts_with("https://accounts.google.com/"));
+        // From the provider's defaults
+        assert!(url.contains("access_type=offline"));
+        assert!(url.contains("prompt=consent"));
+        assert!(url.contains("code_challenge_method=S256"));
+    }
+
+    #[test]
+    fn test_extra_params_are_not_kept_between_urls() {
+        let registry = crate::provider::ProviderRegistry::with_defaults();
+        let google = registry.get("google").unwrap().clone();
+
+        let flow = PkceFlow::new(
+            google,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap();
+
+        let extra_params = HashMap::from([("prompt".to_string(), "consent".to_string())]);
+        flow.build_authorization_url(vec![], extra_params);
+        let (url, _state) = flow.build_authorization_url(vec![], HashMap::new());
+
+        assert!(url.contains("access_type=offline"));
+        assert!(!url.contains("prompt="));
+    }
+
+    #[test]
+    fn test_extra_params_override_provider_defaults() {
+        let registry = crate::provider::ProviderRegistry::with_defaults();
+        let dropbox = registry.get("dropbox").unwrap().clone();
+
+        let flow = PkceFlow::new(
+            dropbox,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap();
+
+        let extra_params = HashMap::from([
+            ("token_access_type".to_string(), "online".to_string()),
+            ("force_reapprove".to_string(), "true".to_string()),
+        ]);
+        let (url, _state) = flow.build_authorization_url(vec![], extra_params);
+
+        assert!(url.contains("token_access_type=online"));
+        assert!(!url.contains("token_access_type=offline"));
+        assert_eq!(url.matches("token_access_type=").count(), 1);
+        assert!(url.contains("force_reapprove=true"));
+    }
+
+    #[test]
+    fn test_build_authorization_url_box() {
+        let registry = crate::provider::ProviderRegistry::with_defaults();
+        let box_provider = registry.get("box").unwrap().clone();
+        let scopes = box_provider.default_scopes.clone();
+
+        let flow = PkceFlow::new(
+            box_provider,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap();
+
+        let (url, _state) = flow.build_authorization_url(scopes, HashMap::new());
+
+        assert!(url.starts_with("https://account.box.com/api/oauth2/authorize?"));
+        assert!(url.contains("response_type=code"));
+        assert!(url.contains("client_id=client-id"));
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains("scope=root_readonly+root_readwrite"));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code_parses_dropbox_account_id() {
+        use wiremock::{
+            matchers::{body_string_contains, method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/oauth2/token"))
+            .and(body_string_contains("grant_type=authorization_code"))
+            .and(body_string_contains("code_verifier="))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "sl.dropbox-access",
+                "token_type": "bearer",
+                "expires_in": 14400,
+                "refresh_token": "dropbox-refresh",
+                "scope": "account_info.read files.content.read",
+                "uid": "12345",
+                "account_id": "dbid:AAH4f99T0taONIb-OurWxbNQ6ywGRopQngc"
+            })))
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig::new("dropbox", "Dropbox")
+            .with_auth_url(format!("{}/oauth2/authorize", server.uri()))
+            .with_token_url(format!("{}/oauth2/token", server.uri()))
+            .with_pkce(true)
+            .with_default_auth_param("token_access_type", "offline");
+
+        let flow = PkceFlow::new(
+            config,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap();
+
+        let (url, _state) = flow.build_authorization_url(vec![], HashMap::new());
+        assert!(url.contains("token_access_type=offline"));
+
+        let token_set = flow.exchange_code("auth-code").await.unwrap();
+        assert_eq!(token_set.access_token.access_token.expose(), "sl.dropbox-access");
+        assert_eq!(token_set.refresh_token.unwrap().expose(), "dropbox-refresh");
+        assert_eq!(
+            token_set.subject.as_deref(),
+            Some("dbid:AAH4f99T0taONIb-OurWxbNQ6ywGRopQngc")
+        );
+    }
+
+    /// Sign an ID token with the test key for the given audience.
+    fn test_id_token(aud: serde_json::Value) -> String {
+        use jsonwebtoken::{Algorithm, EncodingKey, Header};
+
+        let key = EncodingKey::from_rsa_pem(include_bytes!(
+            "../../tests/fixtures/github_app_test_key.pem"
+        ))
+        .unwrap();
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some("test-key".to_string());
+
+        let claims = serde_json::json!({
+            "iss": "https://issuer.example.com",
+            "sub": "user-123",
+            "aud": aud,
+            "exp": chrono::Utc::now().timestamp() + 3600,
+        });
+        jsonwebtoken::encode(&header, &claims, &key).unwrap()
+    }
+
+    /// Mount a token endpoint returning `id_token` and a JWKS endpoint.
+    async fn mount_oidc_endpoints(server: &wiremock::MockServer, id_token: String) {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, ResponseTemplate,
+        };
+
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "oidc-access",
+                "token_type": "bearer",
+                "expires_in": 3600,
+                "id_token": id_token,
+            })))
+            .mount(server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/jwks"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(include_str!(
+                "../../tests/fixtures/oidc_test_jwks.json"
+            )))
+            .mount(server)
+            .await;
+    }
+
+    fn oidc_flow(server: &wiremock::MockServer) -> PkceFlow {
+        let config = ProviderConfig::new("oidc", "OIDC")
+            .with_auth_url(format!("{}/authorize", server.uri()))
+            .with_token_url(format!("{}/token", server.uri()))
+            .with_jwks_uri(format!("{}/jwks", server.uri()))
+            .with_pkce(true);
+
+        PkceFlow::new(config, "client-id".to_string(), None, RedirectConfig::localhost(8484))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code_validates_id_token_audience() {
+        let server = wiremock::MockServer::start().await;
+        mount_oidc_endpoints(&server, test_id_token(serde_json::json!("client-id"))).await;
+
+        let flow = oidc_flow(&server);
+        flow.build_authorization_url(vec![], HashMap::new());
+        let token_set = flow.exchange_code("auth-code").await.unwrap();
+        assert_eq!(token_set.access_token.access_token.expose(), "oidc-access");
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code_rejects_mismatched_audience() {
+        let server = wiremock::MockServer::start().await;
+        mount_oidc_endpoints(&server, test_id_token(serde_json::json!("other-client"))).await;
+
+        let flow = oidc_flow(&server);
+        flow.build_authorization_url(vec![], HashMap::new());
+        match flow.exchange_code("auth-code").await {
+            Err(TokenError::OAuthError { message }) => assert_eq!(message, "invalid audience"),
+            other => panic!("expected invalid audience, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code_uses_configured_validator() {
+        let server = wiremock::MockServer::start().await;
+        mount_oidc_endpoints(&server, test_id_token(serde_json::json!("api://sigil"))).await;
+
+        let jwks = serde_json::from_str(include_str!("../../tests/fixtures/oidc_test_jwks.json"))
+            .unwrap();
+        let validator = OidcTokenValidator::new("client-id")
+            .with_audience("api://sigil")
+            .with_jwks(&jwks)
+            .unwrap();
+
+        let flow = oidc_flow(&server).with_id_token_validator(validator);
+        flow.build_authorization_url(vec![], HashMap::new());
+        assert!(flow.exchange_code("auth-code").await.is_ok());
+    }
+
+    fn test_provider() -> ProviderConfig {
+        ProviderConfig::new("test", "Test")
+            .with_auth_url("https://example.com/auth")
+            .with_token_url("https://example.com/token")
+            .with_pkce(true)
+    }
+
+    fn flow_with_pkce(supports_pkce: bool, pkce: PkceConfig) -> Result<PkceFlow, TokenError> {
+        let config = test_provider().with_pkce(supports_pkce);
+        PkceFlow::new(
+            config,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap()
+        .with_pkce_config(pkce)
+    }
+
+    fn stored_verifier(flow: &PkceFlow) -> String {
+        flow.verifier
+            .lock()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .secret()
+            .clone()
+    }
+
+    #[test]
+    fn test_pkce_config_rejects_out_of_range_verifier_length() {
+        for verifier_length in [0, 42, 129] {
+            let pkce = PkceConfig {
+                verifier_length,
+                ..Default::default()
+            };
+            assert!(pkce.validate().is_err());
+            assert!(matches!(
+                flow_with_pkce(true, pkce),
+                Err(TokenError::OAuthError { .. })
+            ));
+        }
+
+        for verifier_length in [MIN_VERIFIER_LENGTH, MAX_VERIFIER_LENGTH] {
+            let pkce = PkceConfig {
+                verifier_length,
+                ..Default::default()
+            };
+            let flow = flow_with_pkce(true, pkce).unwrap();
+            flow.build_authorization_url(vec![], HashMap::new());
+            assert_eq!(stored_verifier(&flow).len(), verifier_length);
+        }
+    }
+
+    #[test]
+    fn test_s256_challenge_method() {
+        let flow = flow_with_pkce(true, PkceConfig::default()).unwrap();
+        let (url, _state) = flow.build_authorization_url(vec![], HashMap::new());
+
+        assert!(url.contains("code_challenge_method=S256"));
+        let verifier = stored_verifier(&flow);
+        assert_eq!(verifier.len(), 128);
+        assert!(!url.contains(&format!("code_challenge={}", verifier)));
+    }
+
+    #[test]
+    fn test_plain_challenge_method() {
+        let pkce = PkceConfig {
+            verifier_length: 64,
+            challenge_method: PkceMethod::Plain,
+        };
+        let flow = flow_with_pkce(false, pkce).unwrap();
+        let (url, _state) = flow.build_authorization_url(vec![], HashMap::new());
+
+        assert!(url.contains("code_challenge_method=plain"));
+        // The plain challenge is the verifier itself
+        assert!(url.contains(&format!("code_challenge={}", stored_verifier(&flow))));
+    }
+
+    #[test]
+    fn test_plain_refused_for_pkce_provider() {
+        let pkce = PkceConfig {
+            verifier_length: 64,
+            challenge_method: PkceMethod::Plain,
+        };
+        let flow = flow_with_pkce(true, pkce).unwrap();
+        let (url, _state) = flow.build_authorization_url(vec![], HashMap::new());
+
+        assert!(url.contains("code_challenge_method=S256"));
+        assert_eq!(stored_verifier(&flow).len(), 64);
+    }
+
+    fn ephemeral_flow() -> PkceFlow {
+        PkceFlow::new(
+            test_provider(),
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_redirect_config_uri() {
+        assert_eq!(
+            RedirectConfig::localhost(8484).uri(),
+            "http://127.0.0.1:8484/callback"
+        );
+        assert_eq!(
+            RedirectConfig::new("localhost", 9000, "oauth/done").uri(),
+            "http://localhost:9000/oauth/done"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_flows_get_distinct_ports() {
+        let (first, first_listener) = ephemeral_flow()
+            .prepare_flow(vec![], "127.0.0.1")
+            .await
+            .unwrap();
+        let (second, second_listener) = ephemeral_flow()
+            .prepare_flow(vec![], "127.0.0.1")
+            .await
+            .unwrap();
+
+        let first_port = first_listener.local_addr().unwrap().port();
+        let second_port = second_listener.local_addr().unwrap().port();
+        assert_ne!(first_port, 0);
+        assert_ne!(second_port, 0);
+        assert_ne!(first_port, second_port);
+        assert_eq!(first.redirect().port, first_port);
+        assert_eq!(second.redirect().port, second_port);
+
+        let (first_url, _) = first.build_authorization_url(vec![], HashMap::new());
+        let (second_url, _) = second.build_authorization_url(vec![], HashMap::new());
+        assert!(first_url.contains(&format!("127.0.0.1%3A{}%2Fcallback", first_port)));
+        assert!(second_url.contains(&format!("127.0.0.1%3A{}%2Fcallback", second_port)));
+    }
+
+    #[tokio::test]
+    async fn test_zero_port_always_binds_a_real_port() {
+        for host in ["127.0.0.1", "localhost"] {
+            for _ in 0..5 {
+                let (flow, listener) = ephemeral_flow().prepare_flow(vec![], host).await.unwrap();
+                let port = listener.local_addr().unwrap().port();
+                assert_ne!(port, 0);
+                assert_eq!(flow.redirect().host, host);
+                assert_eq!(flow.redirect().port, port);
+
+                let (url, _state) = flow.build_authorization_url(vec![], HashMap::new());
+                assert!(url.contains(&format!("{}%3A{}%2Fcallback", host, port)));
+                assert!(!url.contains(&format!("{}%3A0%2F", host)));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prepare_flow_requests_its_scopes() {
+        let (flow, _listener) = ephemeral_flow()
+            .prepare_flow(vec!["read".to_string()], "127.0.0.1")
+            .await
+            .unwrap();
+
+        let scopes = vec!["read".to_string(), "write".to_string()];
+        let (url, _state) = flow.build_authorization_url(scopes, HashMap::new());
+        assert!(url.contains("scope=read+write"));
+    }
+
+    #[tokio::test]
+    async fn test_listen_for_callback_on_ephemeral_port() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (flow, listener) = ephemeral_flow()
+            .prepare_flow(vec!["read".to_string()], "127.0.0.1")
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (_url, state) = flow.build_authorization_url(vec![], HashMap::new());
+        let sent_state = state.clone();
+
+        let browser = tokio::spawn(async move {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            let request = format!(
+                "GET /callback?code=the-code&state={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+                sent_state
+            );
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        });
+
+        let code = flow.listen_for_callback(listener, &state).await.unwrap();
+        assert_eq!(code, "the-code");
+        assert!(browser.await.unwrap().contains("Authentication Successful"));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_uses_bound_redirect_uri() {
+        use wiremock::{
+            matchers::{body_string_contains, method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        let config = ProviderConfig::new("test", "Test")
+            .with_auth_url(format!("{}/auth", server.uri()))
+            .with_token_url(format!("{}/token", server.uri()))
+            .with_pkce(true);
+        let flow = PkceFlow::new(
+            config,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(0),
+        )
+        .unwrap();
+
+        let (flow, listener) = flow.prepare_flow(vec![], "127.0.0.1").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        flow.build_authorization_url(vec![], HashMap::new());
+
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains(format!(
+                "redirect_uri=http%3A%2F%2F127.0.0.1%3A{}%2Fcallback",
+                port
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "access",
+                "token_type": "bearer"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let token_set = flow.exchange_code("auth-code").await.unwrap();
+        assert_eq!(token_set.access_token.access_token.expose(), "access");
+    }
+
+    /// A flow against `server` with a verifier ready for an exchange, that
+    /// retries up to `max_retries` times without waiting long.
+    fn retrying_flow(server: &wiremock::MockServer, max_retries: u32) -> PkceFlow {
+        let config = ProviderConfig::new("test", "Test")
+            .with_auth_url(format!("{}/auth", server.uri()))
+            .with_token_url(format!("{}/token", server.uri()))
+            .with_pkce(true);
+        let flow = PkceFlow::new(
+            config,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap()
+        .with_flow_config(PkceFlowConfig {
+            exchange_max_retries: max_retries,
+            exchange_retry_delay: Duration::from_millis(1),
+        });
+        flow.build_authorization_url(vec![], HashMap::new());
+        flow
+    }
+
+    #[tokio::test]
+    async fn test_exchange_retries_server_errors() {
+        use wiremock::{
+            matchers::{body_string_contains, method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        let flow = retrying_flow(&server, 3);
+        let verifier = stored_verifier(&flow);
+
+        // Fail twice, then succeed; every attempt sends the same verifier
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains(format!("code_verifier={}", verifier)))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains(format!("code_verifier={}", verifier)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "access",
+                "token_type": "bearer"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let token_set = flow.exchange_code("auth-code").await.unwrap();
+        assert_eq!(token_set.access_token.access_token.expose(), "access");
+    }
+
+    #[tokio::test]
+    async fn test_exchange_gives_up_after_max_retries() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let flow = retrying_flow(&server, 1);
+        let err = flow.exchange_code("auth-code").await.unwrap_err();
+        assert!(matches!(err, TokenError::NetworkError { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_exchange_does_not_retry_provider_errors() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": "invalid_grant"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let flow = retrying_flow(&server, 3);
+        let err = flow.exchange_code("auth-code").await.unwrap_err();
+        assert!(matches!(err, TokenError::OAuthError { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_detached_authorization_rejects_ephemeral_port() {
+        let flow = ephemeral_flow();
+        let result = flow.detached_authorization_url(vec![]);
+        assert!(matches!(result, Err(TokenError::OAuthError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_resumed_flow_exchanges_detached_code() {
+        use wiremock::{
+            matchers::{body_string_contains, method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        let config = ProviderConfig::new("test", "Test")
+            .with_auth_url(format!("{}/auth", server.uri()))
+            .with_token_url(format!("{}/token", server.uri()))
+            .with_pkce(true);
+        let new_flow = || {
+            PkceFlow::new(
+                config.clone(),
+                "client-id".to_string(),
+                None,
+                RedirectConfig::localhost(8484),
+            )
+            .unwrap()
+        };
+
+        let (url, pending) = new_flow().detached_authorization_url(vec![]).unwrap();
+        assert!(url.contains("code_challenge="));
+        assert_eq!(pending.redirect_uri, "http://127.0.0.1:8484/callback");
+
+        // The pending state survives a round trip through storage
+        let saved = serde_json::to_string(&pending).unwrap();
+        let restored: PendingAuthorization = serde_json::from_str(&saved).unwrap();
+        assert_eq!(restored, pending);
+
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains(format!(
+                "code_verifier={}",
+                pending.code_verifier.expose()
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "access",
+                "token_type": "bearer"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // A fresh flow has no verifier until it is resumed
+        let flow = new_flow();
+        assert!(flow.exchange_code("auth-code").await.is_err());
+        flow.resume(&restored).unwrap();
+        let token_set = flow.exchange_code("auth-code").await.unwrap();
+        assert_eq!(token_set.access_token.access_token.expose(), "access");
+
+        // The verifier was taken by the exchange
+        assert!(flow.exchange_code("auth-code").await.is_err());
+    }
+
+    #[test]
+    fn test_resume_refuses_used_authorization() {
+        let flow = PkceFlow::new(
+            ProviderConfig::new("test", "Test")
+                .with_auth_url("http://127.0.0.1:1/auth")
+                .with_token_url("http://127.0.0.1:1/token")
+                .with_pkce(true),
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap();
+        let (_url, mut pending) = flow.detached_authorization_url(vec![]).unwrap();
+        pending.pkce_verifier_used = true;
+
+        let result = flow.resume(&pending);
+        assert!(matches!(result, Err(TokenError::OAuthError { .. })));
+
+        // Authorizations saved before the flag existed are unused
+        let json = r#"{"redirect_uri":"http://127.0.0.1:8484/callback","code_verifier":"v"}"#;
+        let older: PendingAuthorization = serde_json::from_str(json).unwrap();
+        assert!(!older.pkce_verifier_used);
+        flow.resume(&older).unwrap();
+    }
 }
+
@@ -3,12 +3,238 @@
 //! This module provides:
 //! - [`ProviderConfig`] - Configuration for an OAuth provider
 //! - [`ProviderRegistry`] - Registry of configured OAuth providers
+//! - [`OidcDiscoveryDocument`] - OpenID Connect discovery metadata
+//! - `ProviderDiscoveryCache` (with `discovery-cache` feature) - Cache of discovered providers
 //!
-//! The registry comes pre-configured with common providers (GitHub, Spotify, Google)
-//! and can be extended with custom providers.
+//! The registry comes pre-configured with common providers (GitHub, Spotify, Google,
+//! Twitter/X, Dropbox, Salesforce, Box) and can be extended with custom providers.
 
+use crate::account_store;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Error type for provider configuration and discovery.
+#[derive(Debug, Error)]
+pub enum ProviderError {
+    /// The issuer URL is malformed.
+    #[error("invalid issuer URL '{issuer}': {message}")]
+    InvalidIssuer { issuer: String, message: String },
+
+    /// The discovery document could not be fetched.
+    #[error("failed to fetch discovery document: {message}")]
+    NetworkError { message: String },
+
+    /// The discovery document is malformed or inconsistent.
+    #[error("invalid discovery document: {message}")]
+    InvalidDocument { message: String },
+
+    /// A provider file or directory could not be read or parsed.
+    #[error("failed to load providers from {}: {message}", path.display())]
+    LoadError { path: PathBuf, message: String },
+
+    /// A provider file could not be written.
+    #[error("failed to save provider to {}: {message}", path.display())]
+    SaveError { path: PathBuf, message: String },
+
+    /// The provider configuration has hard validation errors.
+    #[error("invalid provider '{id}': {}", join_problems(errors))]
+    ValidationFailed {
+        id: String,
+        errors: Vec<ProviderValidationError>,
+    },
+
+    /// A provider with this ID is already registered.
+    #[error("provider '{id}' is already registered")]
+    AlreadyRegistered { id: String },
+}
+
+/// A problem found by [`ProviderConfig::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ProviderValidationError {
+    /// An endpoint URL does not parse or is not HTTP(S).
+    #[error("{field} '{url}' is not a valid URL: {reason}")]
+    InvalidUrl {
+        field: &'static str,
+        url: String,
+        reason: String,
+    },
+
+    /// A required field is empty.
+    #[error("{field} must not be empty")]
+    EmptyRequiredField { field: &'static str },
+
+    /// A default scope is empty or contains whitespace.
+    #[error("scope '{scope}' is empty or contains whitespace")]
+    InvalidScope { scope: String },
+
+    /// No revocation endpoint is configured. This is only a warning.
+    #[error("no revoke_url is set, so tokens cannot be revoked at the provider")]
+    MissingRevocationUrl,
+}
+
+impl ProviderValidationError {
+    /// Check if this is a warning rather than an error.
+    pub fn is_warning(&self) -> bool {
+        matches!(self, Self::MissingRevocationUrl)
+    }
+}
+
+fn join_problems(problems: &[ProviderValidationError]) -> String {
+    problems
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Check that `url` is an absolute HTTP(S) URL.
+fn check_url(field: &'static str, url: &str) -> Option<ProviderValidationError> {
+    let reason = match url::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => return None,
+        Ok(parsed) => format!("unsupported scheme '{}'", parsed.scheme()),
+        Err(e) => e.to_string(),
+    };
+    Some(ProviderValidationError::InvalidUrl {
+        field,
+        url: url.to_string(),
+        reason,
+    })
+}
+
+/// A field that differs between a registered provider and a replacement,
+/// as reported by [`ProviderRegistry::diff_provider`].
+///
+/// Values are rendered with their `Debug` representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderFieldDiff {
+    /// Name of the [`ProviderConfig`] field.
+    pub field: &'static str,
+    /// Value in the registered provider.
+    pub old: String,
+    /// Value in the replacement.
+    pub new: String,
+}
+
+/// `claims` request parameter asking for the email claims in the ID token.
+const ID_TOKEN_EMAIL_CLAIMS: &str = r#"{"id_token":{"email":null,"email_verified":null}}"#;
+
+/// OpenID Connect discovery document (`/.well-known/openid-configuration`).
+///
+/// Only the standard fields relevant to Sigilforge are modeled; unknown
+/// fields are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OidcDiscoveryDocument {
+    /// Issuer identifier; must match the URL discovery was performed against.
+    pub issuer: String,
+
+    /// Authorization endpoint URL.
+    pub authorization_endpoint: String,
+
+    /// Token endpoint URL.
+    pub token_endpoint: String,
+
+    /// UserInfo endpoint URL.
+    #[serde(default)]
+    pub userinfo_endpoint: Option<String>,
+
+    /// JSON Web Key Set URL.
+    #[serde(default)]
+    pub jwks_uri: Option<String>,
+
+    /// Token revocation endpoint URL (RFC 7009).
+    #[serde(default)]
+    pub revocation_endpoint: Option<String>,
+
+    /// Token introspection endpoint URL (RFC 7662).
+    #[serde(default)]
+    pub introspection_endpoint: Option<String>,
+
+    /// Device authorization endpoint URL (RFC 8628).
+    #[serde(default)]
+    pub device_authorization_endpoint: Option<String>,
+
+    /// Scopes supported by the provider.
+    #[serde(default)]
+    pub scopes_supported: Vec<String>,
+
+    /// Response types supported by the provider.
+    #[serde(default)]
+    pub response_types_supported: Vec<String>,
+
+    /// Grant types supported by the provider.
+    #[serde(default)]
+    pub grant_types_supported: Vec<String>,
+
+    /// PKCE code challenge methods supported by the provider.
+    #[serde(default)]
+    pub code_challenge_methods_supported: Vec<String>,
+
+    /// Whether the provider accepts the `claims` request parameter.
+    #[serde(default)]
+    pub claims_parameter_supported: bool,
+}
+
+impl OidcDiscoveryDocument {
+    /// Convert the discovery document into a provider configuration.
+    ///
+    /// The provider ID and name are derived from the issuer host. When the
+    /// provider supports the `claims` parameter, its default authorization
+    /// parameters ask for the email claims in the ID token itself, so the
+    /// account can be identified without a UserInfo request.
+    pub fn into_provider_config(self) -> Result<ProviderConfig, ProviderError> {
+        let host = url::Url::parse(&self.issuer)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .ok_or_else(|| ProviderError::InvalidDocument {
+                message: format!("issuer '{}' is not a valid URL", self.issuer),
+            })?;
+
+        let default_scopes: Vec<String> = ["openid", "email", "profile"]
+            .iter()
+            .filter(|scope| {
+                self.scopes_supported.is_empty()
+                    || self.scopes_supported.iter().any(|s| s == *scope)
+            })
+            .map(|s| s.to_string())
+            .collect();
+
+        let supports_pkce = self
+            .code_challenge_methods_supported
+            .iter()
+            .any(|m| m.eq_ignore_ascii_case("S256"));
+
+        let supports_device_code = self.device_authorization_endpoint.is_some()
+            || self
+                .grant_types_supported
+                .iter()
+                .any(|g| g == "urn:ietf:params:oauth:grant-type:device_code");
+
+        let mut default_auth_params = HashMap::new();
+        if self.claims_parameter_supported {
+            default_auth_params.insert("claims".to_string(), ID_TOKEN_EMAIL_CLAIMS.to_string());
+        }
+
+        Ok(ProviderConfig {
+            id: host.clone(),
+            name: host,
+            auth_url: self.authorization_endpoint,
+            token_url: self.token_endpoint,
+            revoke_url: self.revocation_endpoint,
+            default_scopes,
+            supports_pkce,
+            supports_device_code,
+            default_auth_params,
+            jwks_uri: self.jwks_uri,
+            expected_audience: None,
+            issuer: Some(self.issuer),
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
+        })
+    }
+}
 
 /// Configuration for an OAuth provider.
 ///
@@ -28,6 +254,12 @@ use std::collections::HashMap;
 ///     default_scopes: vec!["repo".to_string(), "user".to_string()],
 ///     supports_pkce: true,
 ///     supports_device_code: true,
+///     default_auth_params: Default::default(),
+///     jwks_uri: None,
+///     expected_audience: None,
+///     issuer: None,
+///     instance_url: None,
+///     refresh_token_lifetime_secs: None,
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -39,6 +271,9 @@ pub struct ProviderConfig {
     pub name: String,
 
     /// OAuth authorization endpoint URL.
+    ///
+    /// May be empty for providers used only with the client credentials
+    /// grant, which set `supports_pkce = false`.
     pub auth_url: String,
 
     /// OAuth token endpoint URL.
@@ -55,6 +290,37 @@ pub struct ProviderConfig {
 
     /// Whether this provider supports the device code flow.
     pub supports_device_code: bool,
+
+    /// Query parameters added to every authorization URL (e.g., Dropbox's
+    /// `token_access_type=offline`); per-call parameters override them.
+    #[serde(default, alias = "extra_auth_params")]
+    pub default_auth_params: HashMap<String, String>,
+
+    /// JSON Web Key Set URL used to verify OpenID Connect ID tokens.
+    #[serde(default)]
+    pub jwks_uri: Option<String>,
+
+    /// Audience expected in ID tokens; `None` means the OAuth client ID.
+    #[serde(default)]
+    pub expected_audience: Option<String>,
+
+    /// OpenID Connect issuer identifier. When set, ID tokens must carry it
+    /// in their `iss` claim.
+    #[serde(default)]
+    pub issuer: Option<String>,
+
+    /// Base URL of the login instance the endpoints live under, for providers
+    /// with several (e.g. Salesforce production vs. sandbox).
+    ///
+    /// When set, a per-account `instance_url` from the token response is used
+    /// for token refresh.
+    #[serde(default)]
+    pub instance_url: Option<String>,
+
+    /// How long refresh tokens stay valid after they are issued, in seconds,
+    /// for providers that expire them (Box: 60 days).
+    #[serde(default)]
+    pub refresh_token_lifetime_secs: Option<u64>,
 }
 
 impl ProviderConfig {
@@ -69,6 +335,12 @@ impl ProviderConfig {
             default_scopes: Vec::new(),
             supports_pkce: false,
             supports_device_code: false,
+            default_auth_params: HashMap::new(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         }
     }
 
@@ -107,6 +379,376 @@ impl ProviderConfig {
         self.supports_device_code = enabled;
         self
     }
+
+    /// Add a default authorization URL parameter.
+    pub fn with_default_auth_param(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.default_auth_params.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the JSON Web Key Set URL used to verify ID tokens.
+    pub fn with_jwks_uri(mut self, url: impl Into<String>) -> Self {
+        self.jwks_uri = Some(url.into());
+        self
+    }
+
+    /// Expect `audience` in ID tokens instead of the client ID.
+    pub fn with_expected_audience(mut self, audience: impl Into<String>) -> Self {
+        self.expected_audience = Some(audience.into());
+        self
+    }
+
+    /// Require ID tokens to be issued by `issuer`.
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Set how long refresh tokens stay valid after they are issued.
+    pub fn with_refresh_token_lifetime(mut self, secs: u64) -> Self {
+        self.refresh_token_lifetime_secs = Some(secs);
+        self
+    }
+
+    /// When a refresh token issued at `issued_at` expires, if the provider
+    /// expires them at all.
+    pub fn refresh_token_expiry(&self, issued_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let secs = i64::try_from(self.refresh_token_lifetime_secs?).ok()?;
+        Some(issued_at + chrono::Duration::seconds(secs))
+    }
+
+    /// Check the configuration for problems that would break an OAuth flow.
+    ///
+    /// All problems are returned, including warnings such as
+    /// [`ProviderValidationError::MissingRevocationUrl`]; use
+    /// [`ProviderValidationError::is_warning`] to tell them apart.
+    pub fn validate(&self) -> Result<(), Vec<ProviderValidationError>> {
+        let mut problems = Vec::new();
+
+        for (field, value) in [("id", &self.id), ("name", &self.name)] {
+            if value.trim().is_empty() {
+                problems.push(ProviderValidationError::EmptyRequiredField { field });
+            }
+        }
+
+        // Only the authorization code flow visits auth_url
+        let urls = [
+            ("auth_url", &self.auth_url, self.supports_pkce),
+            ("token_url", &self.token_url, true),
+        ];
+        for (field, url, required) in urls {
+            if !url.trim().is_empty() {
+                problems.extend(check_url(field, url));
+            } else if required {
+                problems.push(ProviderValidationError::EmptyRequiredField { field });
+            }
+        }
+
+        let optional_urls = [
+            ("revoke_url", &self.revoke_url),
+            ("jwks_uri", &self.jwks_uri),
+            ("instance_url", &self.instance_url),
+        ];
+        for (field, url) in optional_urls {
+            if let Some(url) = url {
+                problems.extend(check_url(field, url));
+            }
+        }
+
+        for scope in &self.default_scopes {
+            if scope.is_empty() || scope.contains(char::is_whitespace) {
+                problems.push(ProviderValidationError::InvalidScope {
+                    scope: scope.clone(),
+                });
+            }
+        }
+
+        if self.revoke_url.is_none() {
+            problems.push(ProviderValidationError::MissingRevocationUrl);
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Fail with [`ProviderError::ValidationFailed`] if [`validate`](Self::validate)
+    /// finds anything other than warnings.
+    fn ensure_valid(&self) -> Result<(), ProviderError> {
+        let errors: Vec<_> = self
+            .validate()
+            .err()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|problem| !problem.is_warning())
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ProviderError::ValidationFailed {
+                id: self.id.clone(),
+                errors,
+            })
+        }
+    }
+
+    /// Load a provider configuration from a TOML file and validate it.
+    ///
+    /// Read, parse, and validation errors are all reported as
+    /// [`ProviderError::LoadError`] for `path`.
+    pub fn from_toml_file(path: &Path) -> Result<ProviderConfig, ProviderError> {
+        let load_error = |message: String| ProviderError::LoadError {
+            path: path.to_path_buf(),
+            message,
+        };
+
+        let contents = std::fs::read_to_string(path).map_err(|e| load_error(e.to_string()))?;
+        let config: ProviderConfig =
+            toml::from_str(&contents).map_err(|e| load_error(e.to_string()))?;
+        config.ensure_valid().map_err(|e| load_error(e.to_string()))?;
+        Ok(config)
+    }
+
+    /// Point the provider at another login instance.
+    ///
+    /// Endpoints under the previous [`instance_url`](Self::instance_url) are
+    /// moved to `url`, so `with_instance_url(SALESFORCE_SANDBOX_URL)` turns the
+    /// built-in Salesforce provider into one for sandbox orgs.
+    pub fn with_instance_url(mut self, url: impl Into<String>) -> Self {
+        let url = url.into().trim_end_matches('/').to_string();
+        if let Some(old) = self.instance_url.take() {
+            let rebase = |endpoint: &mut String| {
+                if let Some(path) = endpoint.strip_prefix(old.as_str()) {
+                    *endpoint = format!("{}{}", url, path);
+                }
+            };
+            rebase(&mut self.auth_url);
+            rebase(&mut self.token_url);
+            if let Some(revoke_url) = self.revoke_url.as_mut() {
+                rebase(revoke_url);
+            }
+        }
+        self.instance_url = Some(url);
+        self
+    }
+
+    /// Build a provider configuration from an OpenID Connect discovery document.
+    ///
+    /// Fetches `{issuer}/.well-known/openid-configuration` and maps the standard
+    /// fields onto a [`ProviderConfig`], which must pass
+    /// [`validate`](Self::validate). Pass a `client` to reuse custom TLS
+    /// roots or proxy settings; otherwise a default client is used.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), sigilforge_core::provider::ProviderError> {
+    /// use sigilforge_core::provider::ProviderConfig;
+    ///
+    /// let config = ProviderConfig::from_oidc_discovery("https://accounts.google.com", None).await?;
+    /// assert!(config.supports_pkce);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_oidc_discovery(
+        issuer: &str,
+        client: Option<reqwest::Client>,
+    ) -> Result<ProviderConfig, ProviderError> {
+        let issuer = issuer.trim_end_matches('/');
+        let discovery_url = url::Url::parse(&format!("{}/.well-known/openid-configuration", issuer))
+            .map_err(|e| ProviderError::InvalidIssuer {
+                issuer: issuer.to_string(),
+                message: e.to_string(),
+            })?;
+
+        let client = client.unwrap_or_default();
+        let response = client
+            .get(discovery_url)
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError {
+                message: e.to_string(),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(ProviderError::NetworkError {
+                message: format!("discovery endpoint returned HTTP {}", response.status()),
+            });
+        }
+
+        let document: OidcDiscoveryDocument =
+            response.json().await.map_err(|e| ProviderError::InvalidDocument {
+                message: e.to_string(),
+            })?;
+
+        if document.issuer.trim_end_matches('/') != issuer {
+            return Err(ProviderError::InvalidDocument {
+                message: format!(
+                    "issuer mismatch: expected '{}', document declares '{}'",
+                    issuer, document.issuer
+                ),
+            });
+        }
+
+        let config = document.into_provider_config()?;
+        config.ensure_valid()?;
+        Ok(config)
+    }
+
+    /// Write this provider to `{dir}/{id}.toml`, creating `dir` if needed.
+    ///
+    /// The file uses the format read by [`ProviderRegistry::merge_from_dir`]
+    /// and replaces any existing file for the same ID. Returns the path written.
+    pub fn save_to_dir(&self, dir: &Path) -> Result<PathBuf, ProviderError> {
+        let path = dir.join(format!("{}.toml", self.id));
+        let save_error = |message: String| ProviderError::SaveError {
+            path: path.clone(),
+            message,
+        };
+
+        if self.id.is_empty() || self.id.contains(['/', '\\']) || self.id.starts_with('.') {
+            return Err(save_error(format!(
+                "'{}' is not a valid provider ID",
+                self.id
+            )));
+        }
+
+        let contents = toml::to_string(self).map_err(|e| save_error(e.to_string()))?;
+        std::fs::create_dir_all(dir).map_err(|e| save_error(e.to_string()))?;
+        std::fs::write(&path, contents).map_err(|e| save_error(e.to_string()))?;
+        Ok(path)
+    }
+}
+
+/// Cache of provider configurations discovered via OIDC.
+///
+/// Entries are keyed by issuer URL and expire after 24 hours by default.
+#[cfg(feature = "discovery-cache")]
+#[derive(Debug)]
+pub struct ProviderDiscoveryCache {
+    entries: parking_lot::RwLock<HashMap<String, (std::time::Instant, ProviderConfig)>>,
+    ttl: std::time::Duration,
+}
+
+#[cfg(feature = "discovery-cache")]
+impl ProviderDiscoveryCache {
+    /// Default time-to-live for cached discovery results (24 hours).
+    pub const DEFAULT_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+    /// Create a cache with the default 24 hour TTL.
+    pub fn new() -> Self {
+        Self::with_ttl(Self::DEFAULT_TTL)
+    }
+
+    /// Create a cache with a custom TTL.
+    pub fn with_ttl(ttl: std::time::Duration) -> Self {
+        Self {
+            entries: parking_lot::RwLock::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// Get a cached configuration if present and not expired.
+    pub fn get(&self, issuer: &str) -> Option<ProviderConfig> {
+        let entries = self.entries.read();
+        entries
+            .get(issuer.trim_end_matches('/'))
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < self.ttl)
+            .map(|(_, config)| config.clone())
+    }
+
+    /// Return the cached configuration or perform discovery and cache the result.
+    pub async fn get_or_discover(
+        &self,
+        issuer: &str,
+        client: Option<reqwest::Client>,
+    ) -> Result<ProviderConfig, ProviderError> {
+        if let Some(config) = self.get(issuer) {
+            return Ok(config);
+        }
+
+        let config = ProviderConfig::from_oidc_discovery(issuer, client).await?;
+        self.entries.write().insert(
+            issuer.trim_end_matches('/').to_string(),
+            (std::time::Instant::now(), config.clone()),
+        );
+        Ok(config)
+    }
+
+    /// Remove all cached entries.
+    pub fn clear(&self) {
+        self.entries.write().clear();
+    }
+}
+
+#[cfg(feature = "discovery-cache")]
+impl Default for ProviderDiscoveryCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// IDs of the providers registered by [`ProviderRegistry::with_defaults`].
+pub const BUILTIN_PROVIDER_IDS: &[&str] =
+    &["github", "spotify", "google", "twitter", "dropbox", "salesforce", "box"];
+
+/// Salesforce production login instance.
+pub const SALESFORCE_LOGIN_URL: &str = "https://login.salesforce.com";
+
+/// Salesforce sandbox login instance.
+pub const SALESFORCE_SANDBOX_URL: &str = "https://test.salesforce.com";
+
+/// Lifetime of a Box refresh token (60 days).
+pub const BOX_REFRESH_TOKEN_LIFETIME_SECS: u64 = 60 * 24 * 60 * 60;
+
+/// Directory of user-defined provider files (`<config dir>/providers`).
+///
+/// `config_dir` overrides the configuration directory, as the CLI's
+/// `--config-dir` does; otherwise [`account_store::config_dir`] is used.
+/// Providers discovered by `sigilforge add-account --oidc-issuer` are saved
+/// here, and [`ProviderRegistry::with_user_providers`] loads them back.
+pub fn user_provider_dir(config_dir: Option<&Path>) -> Option<PathBuf> {
+    config_dir
+        .map(Path::to_path_buf)
+        .or_else(account_store::config_dir)
+        .map(|dir| dir.join("providers"))
+}
+
+/// The `*.toml` files in `dir`, sorted by name.
+fn provider_files(dir: &Path) -> Result<Vec<PathBuf>, ProviderError> {
+    let load_error = |message: String| ProviderError::LoadError {
+        path: dir.to_path_buf(),
+        message,
+    };
+
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(|e| load_error(e.to_string()))? {
+        let path = entry.map_err(|e| load_error(e.to_string()))?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "toml") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Issuer URL of an Okta org's authorization server.
+///
+/// Accepts a bare domain (`yourorg.okta.com`) or a full URL, which is used
+/// as given apart from any trailing slash.
+pub fn okta_issuer(domain: &str) -> String {
+    let domain = domain.trim().trim_end_matches('/');
+    if domain.contains("://") {
+        domain.to_string()
+    } else {
+        format!("https://{}", domain)
+    }
 }
 
 /// Registry of OAuth provider configurations.
@@ -129,6 +771,7 @@ impl ProviderConfig {
 #[derive(Debug, Clone)]
 pub struct ProviderRegistry {
     providers: HashMap<String, ProviderConfig>,
+    skipped_files: Vec<(PathBuf, String)>,
 }
 
 impl ProviderRegistry {
@@ -136,6 +779,7 @@ impl ProviderRegistry {
     pub fn new() -> Self {
         Self {
             providers: HashMap::new(),
+            skipped_files: Vec::new(),
         }
     }
 
@@ -145,11 +789,17 @@ impl ProviderRegistry {
     /// - GitHub
     /// - Spotify
     /// - Google
+    /// - Twitter/X
+    /// - Dropbox
+    ///
+    /// Note that Twitter only issues OAuth 2.0 tokens for apps that have enabled
+    /// OAuth 2.0 in the developer portal; apps limited to OAuth 1.0a will be
+    /// rejected at the authorization endpoint.
     pub fn with_defaults() -> Self {
         let mut registry = Self::new();
 
         // GitHub configuration
-        registry.register(ProviderConfig {
+        registry.insert(ProviderConfig {
             id: "github".to_string(),
             name: "GitHub".to_string(),
             auth_url: "https://github.com/login/oauth/authorize".to_string(),
@@ -158,10 +808,16 @@ impl ProviderRegistry {
             default_scopes: vec!["repo".to_string(), "user".to_string()],
             supports_pkce: true,
             supports_device_code: true,
+            default_auth_params: HashMap::new(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         });
 
         // Spotify configuration
-        registry.register(ProviderConfig {
+        registry.insert(ProviderConfig {
             id: "spotify".to_string(),
             name: "Spotify".to_string(),
             auth_url: "https://accounts.spotify.com/authorize".to_string(),
@@ -173,10 +829,16 @@ impl ProviderRegistry {
             ],
             supports_pkce: true,
             supports_device_code: false,
+            default_auth_params: HashMap::new(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
         });
 
-        // Google configuration
-        registry.register(ProviderConfig {
+        // Google configuration (refresh tokens require access_type=offline)
+        registry.insert(ProviderConfig {
             id: "google".to_string(),
             name: "Google".to_string(),
             auth_url: "https://accounts.google.com/o/oauth2/v2/auth".to_string(),
@@ -189,6 +851,104 @@ impl ProviderRegistry {
             ],
             supports_pkce: true,
             supports_device_code: true,
+            default_auth_params: HashMap::from([(
+                "access_type".to_string(),
+                "offline".to_string(),
+            )]),
+            jwks_uri: Some("https://www.googleapis.com/oauth2/v3/certs".to_string()),
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
+        });
+
+        // Twitter/X configuration (OAuth 2.0, PKCE is mandatory).
+        // A refresh token is only returned when `offline.access` is requested.
+        registry.insert(ProviderConfig {
+            id: "twitter".to_string(),
+            name: "Twitter".to_string(),
+            auth_url: "https://twitter.com/i/oauth2/authorize".to_string(),
+            token_url: "https://api.twitter.com/2/oauth2/token".to_string(),
+            revoke_url: Some("https://api.twitter.com/2/oauth2/revoke".to_string()),
+            default_scopes: vec![
+                "tweet.read".to_string(),
+                "users.read".to_string(),
+                "offline.access".to_string(),
+            ],
+            supports_pkce: true,
+            supports_device_code: false,
+            default_auth_params: HashMap::new(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
+        });
+
+        // Dropbox configuration (refresh tokens require token_access_type=offline)
+        registry.insert(ProviderConfig {
+            id: "dropbox".to_string(),
+            name: "Dropbox".to_string(),
+            auth_url: "https://www.dropbox.com/oauth2/authorize".to_string(),
+            token_url: "https://api.dropboxapi.com/oauth2/token".to_string(),
+            revoke_url: Some("https://api.dropboxapi.com/2/auth/token/revoke".to_string()),
+            default_scopes: vec![
+                "files.content.read".to_string(),
+                "account_info.read".to_string(),
+            ],
+            supports_pkce: true,
+            supports_device_code: false,
+            default_auth_params: HashMap::from([(
+                "token_access_type".to_string(),
+                "offline".to_string(),
+            )]),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: None,
+        });
+
+        // Salesforce configuration. The client ID is a connected app's consumer
+        // key; access tokens carry no expiry unless the org sets a session timeout.
+        registry.insert(ProviderConfig {
+            id: "salesforce".to_string(),
+            name: "Salesforce".to_string(),
+            auth_url: format!("{}/services/oauth2/authorize", SALESFORCE_LOGIN_URL),
+            token_url: format!("{}/services/oauth2/token", SALESFORCE_LOGIN_URL),
+            revoke_url: Some(format!("{}/services/oauth2/revoke", SALESFORCE_LOGIN_URL)),
+            default_scopes: vec![
+                "api".to_string(),
+                "refresh_token".to_string(),
+                "offline_access".to_string(),
+            ],
+            supports_pkce: true,
+            supports_device_code: false,
+            default_auth_params: HashMap::new(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: Some(SALESFORCE_LOGIN_URL.to_string()),
+            refresh_token_lifetime_secs: None,
+        });
+
+        // Box configuration. Each refresh issues a new refresh token, and an
+        // unused one expires after 60 days.
+        registry.insert(ProviderConfig {
+            id: "box".to_string(),
+            name: "Box".to_string(),
+            auth_url: "https://account.box.com/api/oauth2/authorize".to_string(),
+            token_url: "https://api.box.com/oauth2/token".to_string(),
+            revoke_url: Some("https://api.box.com/oauth2/revoke".to_string()),
+            default_scopes: vec!["root_readonly".to_string(), "root_readwrite".to_string()],
+            supports_pkce: true,
+            supports_device_code: false,
+            default_auth_params: HashMap::new(),
+            jwks_uri: None,
+            expected_audience: None,
+            issuer: None,
+            instance_url: None,
+            refresh_token_lifetime_secs: Some(BOX_REFRESH_TOKEN_LIFETIME_SECS),
         });
 
         registry
@@ -196,11 +956,109 @@ impl ProviderRegistry {
 
     /// Register a new provider configuration.
     ///
-    /// If a provider with the same ID already exists, it will be replaced.
-    pub fn register(&mut self, config: ProviderConfig) {
+    /// If a provider with the same ID already exists, it will be replaced,
+    /// and a warning listing the changed fields is logged when the two differ.
+    /// Fails with [`ProviderError::ValidationFailed`] if
+    /// [`ProviderConfig::validate`] finds errors; warnings are ignored.
+    pub fn register(&mut self, config: ProviderConfig) -> Result<(), ProviderError> {
+        config.ensure_valid()?;
+        let diffs = self.diff_provider(&config.id, &config);
+        if !diffs.is_empty() {
+            let fields: Vec<&str> = diffs.iter().map(|diff| diff.field).collect();
+            tracing::warn!(
+                provider = %config.id,
+                changed = %fields.join(","),
+                "replacing registered provider with a different configuration"
+            );
+        }
+        self.insert(config);
+        Ok(())
+    }
+
+    /// Register a new provider configuration, refusing to replace one.
+    ///
+    /// Like [`register`](Self::register), but fails with
+    /// [`ProviderError::AlreadyRegistered`] if the ID is already taken.
+    pub fn register_checked(&mut self, config: ProviderConfig) -> Result<(), ProviderError> {
+        if self.contains(&config.id) {
+            return Err(ProviderError::AlreadyRegistered { id: config.id });
+        }
+        config.ensure_valid()?;
+        self.insert(config);
+        Ok(())
+    }
+
+    /// List the fields of the provider registered as `id` that differ in `new`.
+    ///
+    /// Returns an empty list if no provider is registered as `id`.
+    pub fn diff_provider(&self, id: &str, new: &ProviderConfig) -> Vec<ProviderFieldDiff> {
+        let Some(old) = self.get(id) else {
+            return Vec::new();
+        };
+
+        let mut diffs = Vec::new();
+        let mut check = |field: &'static str, old: String, new: String| {
+            if old != new {
+                diffs.push(ProviderFieldDiff { field, old, new });
+            }
+        };
+        macro_rules! check_fields {
+            ($($field:ident),* $(,)?) => {
+                $(check(
+                    stringify!($field),
+                    format!("{:?}", old.$field),
+                    format!("{:?}", new.$field),
+                );)*
+            };
+        }
+        check_fields!(
+            id,
+            name,
+            auth_url,
+            token_url,
+            revoke_url,
+            default_scopes,
+            supports_pkce,
+            supports_device_code,
+            jwks_uri,
+            expected_audience,
+            issuer,
+            instance_url,
+            refresh_token_lifetime_secs,
+        );
+        // Sorted, since a map's debug output follows its iteration order
+        let sorted = |params: &HashMap<String, String>| {
+            format!("{:?}", params.iter().collect::<BTreeMap<_, _>>())
+        };
+        check(
+            "default_auth_params",
+            sorted(&old.default_auth_params),
+            sorted(&new.default_auth_params),
+        );
+        diffs
+    }
+
+    /// Register `config` without validating it.
+    fn insert(&mut self, config: ProviderConfig) {
         self.providers.insert(config.id.clone(), config);
     }
 
+    /// Discover an OpenID Connect provider from `issuer` and register it as `id`.
+    ///
+    /// See [`ProviderConfig::from_oidc_discovery`]; the discovered name is kept.
+    pub async fn register_oidc(&mut self, id: &str, issuer: &str) -> Result<(), ProviderError> {
+        let config = ProviderConfig::from_oidc_discovery(issuer, None).await?;
+        self.register(ProviderConfig {
+            id: id.to_string(),
+            ..config
+        })
+    }
+
+    /// Register an Okta org as `id`, discovered from [`okta_issuer`]`(domain)`.
+    pub async fn register_okta(&mut self, id: &str, domain: &str) -> Result<(), ProviderError> {
+        self.register_oidc(id, &okta_issuer(domain)).await
+    }
+
     /// Get a provider configuration by ID.
     ///
     /// Returns `None` if the provider is not registered.
@@ -230,6 +1088,86 @@ impl ProviderRegistry {
         self.providers.remove(id)
     }
 
+    /// IDs of registered providers that are not built in, sorted.
+    pub fn user_defined_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .list_ids()
+            .into_iter()
+            .filter(|id| !BUILTIN_PROVIDER_IDS.contains(id))
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Add all providers from `other`, replacing any with the same ID.
+    pub fn merge(mut self, other: ProviderRegistry) -> Self {
+        self.providers.extend(other.providers);
+        self.skipped_files.extend(other.skipped_files);
+        self
+    }
+
+    /// Merge in every `*.toml` provider file in `dir`.
+    ///
+    /// Each file holds one [`ProviderConfig`], loaded with
+    /// [`ProviderConfig::from_toml_file`]. Each file may
+    /// override a provider already registered, but two files defining the
+    /// same `id` fail with [`ProviderError::AlreadyRegistered`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sigilforge_core::provider::ProviderRegistry;
+    /// use std::path::Path;
+    ///
+    /// # fn example() -> Result<(), sigilforge_core::provider::ProviderError> {
+    /// let registry = ProviderRegistry::with_defaults()
+    ///     .merge_from_dir(Path::new("/etc/sigilforge/providers.d"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge_from_dir(self, dir: &Path) -> Result<Self, ProviderError> {
+        let mut loaded = ProviderRegistry::new();
+        for path in provider_files(dir)? {
+            loaded.register_checked(ProviderConfig::from_toml_file(&path)?)?;
+        }
+
+        Ok(self.merge(loaded))
+    }
+
+    /// Merge in the providers saved under [`user_provider_dir`], if it exists.
+    ///
+    /// Unlike [`merge_from_dir`](Self::merge_from_dir), a malformed or
+    /// invalid provider file is logged and skipped, so one bad file does not
+    /// keep the others (or the daemon) from loading.
+    pub fn with_user_providers(self, config_dir: Option<&Path>) -> Result<Self, ProviderError> {
+        match user_provider_dir(config_dir) {
+            Some(dir) if dir.is_dir() => self.merge_valid_from_dir(&dir),
+            _ => Ok(self),
+        }
+    }
+
+    /// Like [`merge_from_dir`](Self::merge_from_dir), but logs and skips
+    /// files that fail to load; see [`skipped_files`](Self::skipped_files).
+    pub fn merge_valid_from_dir(self, dir: &Path) -> Result<Self, ProviderError> {
+        let mut loaded = ProviderRegistry::new();
+        for path in provider_files(dir)? {
+            let result = ProviderConfig::from_toml_file(&path)
+                .and_then(|config| loaded.register_checked(config));
+            if let Err(e) = result {
+                tracing::warn!("Skipping provider file {}: {}", path.display(), e);
+                loaded.skipped_files.push((path, e.to_string()));
+            }
+        }
+
+        Ok(self.merge(loaded))
+    }
+
+    /// Provider files that [`with_user_providers`](Self::with_user_providers)
+    /// skipped, with the reason each failed to load.
+    pub fn skipped_files(&self) -> &[(PathBuf, String)] {
+        &self.skipped_files
+    }
+
     /// Get the number of registered providers.
     pub fn len(&self) -> usize {
         self.providers.len()
@@ -251,6 +1189,270 @@ impl Default for ProviderRegistry {
 mod tests {
     use super::*;
 
+    /// A provider with valid endpoints under `https://{id}.example.com`.
+    fn valid_provider(id: &str, name: &str) -> ProviderConfig {
+        ProviderConfig::new(id, name)
+            .with_auth_url(format!("https://{}.example.com/authorize", id))
+            .with_token_url(format!("https://{}.example.com/token", id))
+    }
+
+    #[test]
+    fn test_user_defined_ids() {
+        let mut registry = ProviderRegistry::with_defaults();
+        assert_eq!(registry.len(), BUILTIN_PROVIDER_IDS.len());
+        assert!(registry.user_defined_ids().is_empty());
+
+        registry.register(valid_provider("gitea", "Gitea")).unwrap();
+        registry.register(valid_provider("acme", "Acme")).unwrap();
+        assert_eq!(registry.user_defined_ids(), vec!["acme", "gitea"]);
+    }
+
+    fn fixture_provider_dir() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/providers")
+    }
+
+    #[test]
+    fn test_merge_prefers_other() {
+        let mut other = ProviderRegistry::new();
+        other.register(valid_provider("github", "GitHub Enterprise")).unwrap();
+        other.register(valid_provider("gitea", "Gitea")).unwrap();
+
+        let merged = ProviderRegistry::with_defaults().merge(other);
+
+        assert_eq!(merged.len(), BUILTIN_PROVIDER_IDS.len() + 1);
+        assert_eq!(merged.get("github").unwrap().name, "GitHub Enterprise");
+        assert_eq!(merged.get("gitea").unwrap().name, "Gitea");
+        assert_eq!(merged.get("spotify").unwrap().name, "Spotify");
+    }
+
+    #[test]
+    fn test_merge_from_dir() {
+        let registry = ProviderRegistry::with_defaults()
+            .merge_from_dir(&fixture_provider_dir())
+            .unwrap();
+
+        // The GitHub override replaces the built-in provider
+        let github = registry.get("github").unwrap();
+        assert_eq!(github.name, "GitHub Enterprise");
+        assert_eq!(github.auth_url, "https://github.example.com/login/oauth/authorize");
+        assert!(!github.supports_device_code);
+        assert_eq!(github.revoke_url, None);
+
+        // The custom provider is added alongside the remaining defaults
+        let gitea = registry.get("gitea").unwrap();
+        assert_eq!(gitea.default_scopes, vec!["read:user", "read:repository"]);
+        assert_eq!(gitea.default_auth_params["prompt"], "consent");
+        assert!(registry.contains("google"));
+        assert_eq!(registry.len(), BUILTIN_PROVIDER_IDS.len() + 1);
+    }
+
+    #[test]
+    fn test_merge_from_dir_rejects_duplicate_ids() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let provider = |name: &str| {
+            format!(
+                "id = \"acme\"\nname = \"{}\"\nauth_url = \"https://acme.example.com/auth\"\n\
+                 token_url = \"https://acme.example.com/token\"\n\
+                 default_scopes = []\nsupports_pkce = false\nsupports_device_code = false\n",
+                name
+            )
+        };
+        std::fs::write(dir.path().join("b.toml"), provider("Second")).unwrap();
+        std::fs::write(dir.path().join("a.toml"), provider("First")).unwrap();
+
+        match ProviderRegistry::new().merge_from_dir(dir.path()) {
+            Err(ProviderError::AlreadyRegistered { id }) => assert_eq!(id, "acme"),
+            other => panic!("expected AlreadyRegistered, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_merge_from_dir_errors() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let bad = dir.path().join("bad.toml");
+        std::fs::write(&bad, "id = \"broken\"").unwrap();
+
+        match ProviderRegistry::new().merge_from_dir(dir.path()) {
+            Err(ProviderError::LoadError { path, .. }) => assert_eq!(path, bad),
+            other => panic!("expected LoadError, got {:?}", other),
+        }
+
+        let missing = dir.path().join("missing");
+        assert!(matches!(
+            ProviderRegistry::new().merge_from_dir(&missing),
+            Err(ProviderError::LoadError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_user_providers_skip_invalid_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("bad.toml"), "id = \"broken\"").unwrap();
+        let acme = valid_provider("acme", "Acme");
+        acme.save_to_dir(dir.path()).unwrap();
+
+        let registry = ProviderRegistry::new()
+            .merge_valid_from_dir(dir.path())
+            .unwrap();
+        assert!(registry.contains("acme"));
+        assert!(!registry.contains("broken"));
+        assert_eq!(registry.len(), 1);
+
+        let skipped = registry.skipped_files();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].0, dir.path().join("bad.toml"));
+    }
+
+    #[test]
+    fn test_builtin_providers_are_valid() {
+        let registry = ProviderRegistry::with_defaults();
+        for id in BUILTIN_PROVIDER_IDS {
+            let config = registry.get(id).unwrap();
+            let problems = config.validate().err().unwrap_or_default();
+            assert!(
+                problems.iter().all(|p| p.is_warning()),
+                "{}: {:?}",
+                id,
+                problems
+            );
+        }
+        assert_eq!(registry.get("google").unwrap().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_invalid_url() {
+        let config = valid_provider("acme", "Acme")
+            .with_auth_url("not a url")
+            .with_token_url("ftp://acme.example.com/token")
+            .with_revoke_url("https://acme.example.com/revoke")
+            .with_jwks_uri("/keys");
+
+        let problems = config.validate().unwrap_err();
+        let fields: Vec<_> = problems
+            .iter()
+            .map(|problem| match problem {
+                ProviderValidationError::InvalidUrl { field, .. } => *field,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(fields, vec!["auth_url", "token_url", "jwks_uri"]);
+        assert_eq!(
+            problems[1].to_string(),
+            "token_url 'ftp://acme.example.com/token' is not a valid URL: unsupported scheme 'ftp'"
+        );
+    }
+
+    #[test]
+    fn test_validate_empty_required_fields() {
+        let problems = ProviderConfig::new("", " ")
+            .with_pkce(true)
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            problems,
+            vec![
+                ProviderValidationError::EmptyRequiredField { field: "id" },
+                ProviderValidationError::EmptyRequiredField { field: "name" },
+                ProviderValidationError::EmptyRequiredField { field: "auth_url" },
+                ProviderValidationError::EmptyRequiredField { field: "token_url" },
+                ProviderValidationError::MissingRevocationUrl,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_client_credentials_provider_without_auth_url() {
+        let config = ProviderConfig::new("acme", "Acme")
+            .with_token_url("https://acme.example.com/token")
+            .with_revoke_url("https://acme.example.com/revoke");
+        assert!(!config.supports_pkce);
+        assert_eq!(config.validate(), Ok(()));
+
+        let problems = config.with_pkce(true).validate().unwrap_err();
+        assert_eq!(
+            problems,
+            vec![ProviderValidationError::EmptyRequiredField { field: "auth_url" }]
+        );
+    }
+
+    #[test]
+    fn test_validate_invalid_scope() {
+        let config = valid_provider("acme", "Acme")
+            .with_revoke_url("https://acme.example.com/revoke")
+            .with_scopes(vec!["read".into(), "read write".into(), "".into()]);
+
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                ProviderValidationError::InvalidScope {
+                    scope: "read write".into()
+                },
+                ProviderValidationError::InvalidScope { scope: "".into() },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_missing_revocation_url_is_only_a_warning() {
+        let config = valid_provider("acme", "Acme");
+        let problems = config.validate().unwrap_err();
+        assert_eq!(
+            problems,
+            vec![ProviderValidationError::MissingRevocationUrl]
+        );
+        assert!(problems[0].is_warning());
+
+        let mut registry = ProviderRegistry::new();
+        registry.register(config).unwrap();
+        assert!(registry.contains("acme"));
+    }
+
+    #[test]
+    fn test_register_rejects_invalid_provider() {
+        let mut registry = ProviderRegistry::new();
+        let result = registry.register(ProviderConfig::new("acme", "Acme"));
+
+        match result {
+            Err(ProviderError::ValidationFailed { id, errors }) => {
+                assert_eq!(id, "acme");
+                // Warnings are left out
+                assert_eq!(errors.len(), 2);
+                assert!(errors.iter().all(|e| !e.is_warning()));
+            }
+            other => panic!("expected ValidationFailed, got {:?}", other),
+        }
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_from_toml_file_validates() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("acme.toml");
+        std::fs::write(
+            &path,
+            "id = \"acme\"\nname = \"Acme\"\nauth_url = \"https://acme.example.com/auth\"\n\
+             token_url = \"\"\ndefault_scopes = []\nsupports_pkce = true\n\
+             supports_device_code = false\n",
+        )
+        .unwrap();
+
+        match ProviderConfig::from_toml_file(&path) {
+            Err(ProviderError::LoadError {
+                path: err_path,
+                message,
+            }) => {
+                assert_eq!(err_path, path);
+                assert!(
+                    message.contains("token_url must not be empty"),
+                    "{}",
+                    message
+                );
+            }
+            other => panic!("expected LoadError, got {:?}", other),
+        }
+        assert!(ProviderRegistry::new().merge_from_dir(dir.path()).is_err());
+    }
+
     #[test]
     fn test_provider_config_builder() {
         let config = ProviderConfig::new("test", "Test Provider")
@@ -289,12 +1491,27 @@ mod tests {
         assert!(github.supports_device_code);
     }
 
+    #[test]
+    fn test_provider_registry_twitter_defaults() {
+        let registry = ProviderRegistry::with_defaults();
+        let twitter = registry.get("twitter").unwrap();
+
+        assert_eq!(twitter.auth_url, "https://twitter.com/i/oauth2/authorize");
+        assert_eq!(twitter.token_url, "https://api.twitter.com/2/oauth2/token");
+        assert!(twitter.supports_pkce);
+        assert!(!twitter.supports_device_code);
+        assert_eq!(
+            twitter.default_scopes,
+            vec!["tweet.read", "users.read", "offline.access"]
+        );
+    }
+
     #[test]
     fn test_provider_registry_register_and_get() {
         let mut registry = ProviderRegistry::new();
 
-        let config = ProviderConfig::new("test", "Test");
-        registry.register(config.clone());
+        let config = valid_provider("test", "Test");
+        registry.register(config.clone()).unwrap();
 
         let retrieved = registry.get("test").unwrap();
         assert_eq!(retrieved.id, "test");
@@ -305,14 +1522,103 @@ mod tests {
     fn test_provider_registry_replace() {
         let mut registry = ProviderRegistry::new();
 
-        registry.register(ProviderConfig::new("test", "Test 1"));
-        registry.register(ProviderConfig::new("test", "Test 2"));
+        registry.register(valid_provider("test", "Test 1")).unwrap();
+        registry.register(valid_provider("test", "Test 2")).unwrap();
 
         let config = registry.get("test").unwrap();
         assert_eq!(config.name, "Test 2");
         assert_eq!(registry.len(), 1);
     }
 
+    #[test]
+    fn test_provider_registry_register_checked() {
+        let mut registry = ProviderRegistry::new();
+        registry.register_checked(valid_provider("test", "Test 1")).unwrap();
+
+        match registry.register_checked(valid_provider("test", "Test 2")) {
+            Err(ProviderError::AlreadyRegistered { id }) => assert_eq!(id, "test"),
+            other => panic!("expected AlreadyRegistered, got {:?}", other),
+        }
+        assert_eq!(registry.get("test").unwrap().name, "Test 1");
+    }
+
+    #[test]
+    fn test_diff_provider() {
+        let mut registry = ProviderRegistry::new();
+        registry.register(valid_provider("test", "Test")).unwrap();
+
+        let same = valid_provider("test", "Test");
+        assert!(registry.diff_provider("test", &same).is_empty());
+        assert!(registry.diff_provider("missing", &same).is_empty());
+
+        let changed = valid_provider("test", "Renamed").with_pkce(true);
+        assert_eq!(
+            registry.diff_provider("test", &changed),
+            vec![
+                ProviderFieldDiff {
+                    field: "name",
+                    old: "\"Test\"".to_string(),
+                    new: "\"Renamed\"".to_string(),
+                },
+                ProviderFieldDiff {
+                    field: "supports_pkce",
+                    old: "false".to_string(),
+                    new: "true".to_string(),
+                },
+            ]
+        );
+    }
+
+    /// Run `f` and return everything it logged at `WARN` or above.
+    fn capture_warnings(f: impl FnOnce()) -> String {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::WARN)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, f);
+
+        let bytes = buffer.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_register_warns_on_changed_replacement() {
+        let mut registry = ProviderRegistry::new();
+        registry.register(valid_provider("test", "Test")).unwrap();
+
+        let logs = capture_warnings(|| {
+            registry.register(valid_provider("test", "Test")).unwrap();
+        });
+        assert!(logs.is_empty(), "unexpected warning: {}", logs);
+
+        let logs = capture_warnings(|| {
+            registry.register(valid_provider("test", "Renamed")).unwrap();
+        });
+        assert!(logs.contains("WARN"));
+        assert!(logs.contains("provider=test"));
+        assert!(logs.contains("changed=name"));
+        assert_eq!(registry.get("test").unwrap().name, "Renamed");
+    }
+
     #[test]
     fn test_provider_registry_remove() {
         let mut registry = ProviderRegistry::with_defaults();
@@ -333,5 +1639,445 @@ mod tests {
         assert!(ids.contains(&"github"));
         assert!(ids.contains(&"spotify"));
         assert!(ids.contains(&"google"));
+        assert!(ids.contains(&"twitter"));
+        assert!(ids.contains(&"dropbox"));
+    }
+
+    #[test]
+    fn test_provider_registry_dropbox_defaults() {
+        let registry = ProviderRegistry::with_defaults();
+        let dropbox = registry.get("dropbox").unwrap();
+
+        assert_eq!(dropbox.auth_url, "https://www.dropbox.com/oauth2/authorize");
+        assert_eq!(dropbox.token_url, "https://api.dropboxapi.com/oauth2/token");
+        assert!(dropbox.supports_pkce);
+        assert_eq!(dropbox.default_auth_params["token_access_type"], "offline");
+    }
+
+    #[test]
+    fn test_provider_registry_box_defaults() {
+        let registry = ProviderRegistry::with_defaults();
+        let box_provider = registry.get("box").unwrap();
+
+        assert_eq!(
+            box_provider.auth_url,
+            "https://account.box.com/api/oauth2/authorize"
+        );
+        assert_eq!(box_provider.token_url, "https://api.box.com/oauth2/token");
+        assert!(box_provider.supports_pkce);
+        assert_eq!(
+            box_provider.default_scopes,
+            vec!["root_readonly", "root_readwrite"]
+        );
+        assert_eq!(
+            box_provider.refresh_token_lifetime_secs,
+            Some(BOX_REFRESH_TOKEN_LIFETIME_SECS)
+        );
+    }
+
+    #[test]
+    fn test_refresh_token_expiry() {
+        let issued_at = "2025-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let registry = ProviderRegistry::with_defaults();
+
+        assert_eq!(
+            registry.get("box").unwrap().refresh_token_expiry(issued_at),
+            Some("2025-03-02T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(registry.get("github").unwrap().refresh_token_expiry(issued_at), None);
+
+        let custom = ProviderConfig::new("custom", "Custom").with_refresh_token_lifetime(3600);
+        assert_eq!(
+            custom.refresh_token_expiry(issued_at),
+            Some("2025-01-01T01:00:00Z".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_provider_registry_salesforce_defaults() {
+        let registry = ProviderRegistry::with_defaults();
+        let salesforce = registry.get("salesforce").unwrap();
+
+        assert_eq!(
+            salesforce.auth_url,
+            "https://login.salesforce.com/services/oauth2/authorize"
+        );
+        assert_eq!(
+            salesforce.token_url,
+            "https://login.salesforce.com/services/oauth2/token"
+        );
+        assert_eq!(salesforce.instance_url.as_deref(), Some(SALESFORCE_LOGIN_URL));
+        assert_eq!(
+            salesforce.default_scopes,
+            vec!["api", "refresh_token", "offline_access"]
+        );
+    }
+
+    #[test]
+    fn test_with_instance_url_rebases_endpoints() {
+        let registry = ProviderRegistry::with_defaults();
+        let sandbox = registry
+            .get("salesforce")
+            .unwrap()
+            .clone()
+            .with_instance_url(SALESFORCE_SANDBOX_URL);
+
+        assert_eq!(sandbox.auth_url, "https://test.salesforce.com/services/oauth2/authorize");
+        assert_eq!(sandbox.token_url, "https://test.salesforce.com/services/oauth2/token");
+        assert_eq!(
+            sandbox.revoke_url.as_deref(),
+            Some("https://test.salesforce.com/services/oauth2/revoke")
+        );
+
+        // Trailing slashes are ignored and foreign endpoints are left alone
+        let org = sandbox
+            .with_token_url("https://elsewhere.example.com/token")
+            .with_instance_url("https://acme.my.salesforce.com/");
+        assert_eq!(org.instance_url.as_deref(), Some("https://acme.my.salesforce.com"));
+        assert_eq!(org.auth_url, "https://acme.my.salesforce.com/services/oauth2/authorize");
+        assert_eq!(org.token_url, "https://elsewhere.example.com/token");
+    }
+
+    #[test]
+    fn test_with_instance_url_without_previous_instance() {
+        let config = ProviderConfig::new("acme", "Acme")
+            .with_token_url("https://acme.example.com/token")
+            .with_instance_url("https://other.example.com");
+
+        assert_eq!(config.token_url, "https://acme.example.com/token");
+        assert_eq!(config.instance_url.as_deref(), Some("https://other.example.com"));
+    }
+
+    #[test]
+    fn test_provider_config_default_auth_params_default() {
+        let config: ProviderConfig = serde_json::from_value(serde_json::json!({
+            "id": "test",
+            "name": "Test",
+            "auth_url": "https://example.com/auth",
+            "token_url": "https://example.com/token",
+            "revoke_url": null,
+            "default_scopes": [],
+            "supports_pkce": true,
+            "supports_device_code": false
+        }))
+        .unwrap();
+        assert!(config.default_auth_params.is_empty());
+
+        let config = config.with_default_auth_param("prompt", "consent");
+        assert_eq!(config.default_auth_params["prompt"], "consent");
+    }
+
+    #[test]
+    fn test_provider_config_reads_legacy_extra_auth_params() {
+        let config: ProviderConfig = toml::from_str(
+            r#"
+            id = "test"
+            name = "Test"
+            auth_url = "https://example.com/auth"
+            token_url = "https://example.com/token"
+            default_scopes = []
+            supports_pkce = true
+            supports_device_code = false
+
+            [extra_auth_params]
+            token_access_type = "offline"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.default_auth_params["token_access_type"], "offline");
+    }
+
+    fn discovery_document(issuer: &str) -> serde_json::Value {
+        serde_json::json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{}/authorize", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "userinfo_endpoint": format!("{}/userinfo", issuer),
+            "jwks_uri": format!("{}/jwks", issuer),
+            "revocation_endpoint": format!("{}/revoke", issuer),
+            "device_authorization_endpoint": format!("{}/device", issuer),
+            "scopes_supported": ["openid", "email", "offline_access"],
+            "response_types_supported": ["code"],
+            "code_challenge_methods_supported": ["plain", "S256"],
+            "claims_supported": ["sub", "email"]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_from_oidc_discovery() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(discovery_document(&server.uri())))
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig::from_oidc_discovery(&server.uri(), Some(reqwest::Client::new()))
+            .await
+            .unwrap();
+
+        assert_eq!(config.id, "127.0.0.1");
+        assert_eq!(config.auth_url, format!("{}/authorize", server.uri()));
+        assert_eq!(config.token_url, format!("{}/token", server.uri()));
+        assert_eq!(config.revoke_url, Some(format!("{}/revoke", server.uri())));
+        assert_eq!(config.jwks_uri, Some(format!("{}/jwks", server.uri())));
+        assert_eq!(config.issuer, Some(server.uri()));
+        assert_eq!(config.default_scopes, vec!["openid", "email"]);
+        assert!(config.supports_pkce);
+        assert!(config.supports_device_code);
+        assert!(config.default_auth_params.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_from_oidc_discovery_claims_parameter() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        let mut document = discovery_document(&server.uri());
+        document["claims_parameter_supported"] = serde_json::json!(true);
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(document))
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig::from_oidc_discovery(&server.uri(), None)
+            .await
+            .unwrap();
+
+        let claims: serde_json::Value =
+            serde_json::from_str(&config.default_auth_params["claims"]).unwrap();
+        assert!(claims["id_token"].get("email").is_some());
+        assert!(claims["id_token"].get("email_verified").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_from_oidc_discovery_issuer_mismatch() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(discovery_document("https://evil.example.com")),
+            )
+            .mount(&server)
+            .await;
+
+        let result = ProviderConfig::from_oidc_discovery(&server.uri(), None).await;
+        assert!(matches!(result, Err(ProviderError::InvalidDocument { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_from_oidc_discovery_http_error() {
+        use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let result = ProviderConfig::from_oidc_discovery(&server.uri(), None).await;
+        assert!(matches!(result, Err(ProviderError::NetworkError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_from_oidc_discovery_validates() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        let mut document = discovery_document(&server.uri());
+        document["token_endpoint"] = serde_json::json!("token");
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(document))
+            .mount(&server)
+            .await;
+
+        let result = ProviderConfig::from_oidc_discovery(&server.uri(), None).await;
+        match result {
+            Err(ProviderError::ValidationFailed { errors, .. }) => {
+                assert!(matches!(
+                    errors.as_slice(),
+                    [ProviderValidationError::InvalidUrl {
+                        field: "token_url",
+                        ..
+                    }]
+                ));
+            }
+            other => panic!("expected ValidationFailed, got {:?}", other),
+        }
+    }
+
+    fn okta_discovery_document(issuer: &str) -> serde_json::Value {
+        serde_json::json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{}/oauth2/v1/authorize", issuer),
+            "token_endpoint": format!("{}/oauth2/v1/token", issuer),
+            "revocation_endpoint": format!("{}/oauth2/v1/revoke", issuer),
+            "jwks_uri": format!("{}/oauth2/v1/keys", issuer),
+            "scopes_supported": ["openid", "email", "profile", "offline_access", "groups"],
+            "response_types_supported": ["code", "id_token", "code id_token"],
+            "grant_types_supported": [
+                "authorization_code",
+                "refresh_token",
+                "urn:ietf:params:oauth:grant-type:device_code"
+            ],
+            "code_challenge_methods_supported": ["S256"]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_register_okta() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(okta_discovery_document(&server.uri())),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut registry = ProviderRegistry::with_defaults();
+        registry
+            .register_okta("okta", &format!("{}/", server.uri()))
+            .await
+            .unwrap();
+
+        let okta = registry.get("okta").unwrap();
+        assert_eq!(okta.id, "okta");
+        assert_eq!(
+            okta.auth_url,
+            format!("{}/oauth2/v1/authorize", server.uri())
+        );
+        assert_eq!(okta.token_url, format!("{}/oauth2/v1/token", server.uri()));
+        assert_eq!(okta.default_scopes, vec!["openid", "email", "profile"]);
+        assert!(okta.supports_pkce);
+        assert!(okta.supports_device_code);
+        assert_eq!(registry.user_defined_ids(), vec!["okta"]);
+    }
+
+    #[tokio::test]
+    async fn test_register_oidc_failure_leaves_registry_unchanged() {
+        use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let mut registry = ProviderRegistry::new();
+        let result = registry.register_oidc("corp", &server.uri()).await;
+
+        assert!(matches!(result, Err(ProviderError::NetworkError { .. })));
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_okta_issuer() {
+        assert_eq!(okta_issuer("yourorg.okta.com"), "https://yourorg.okta.com");
+        assert_eq!(
+            okta_issuer(" yourorg.okta.com/ "),
+            "https://yourorg.okta.com"
+        );
+        assert_eq!(
+            okta_issuer("https://yourorg.okta.com/"),
+            "https://yourorg.okta.com"
+        );
+        assert_eq!(
+            okta_issuer("http://127.0.0.1:8080"),
+            "http://127.0.0.1:8080"
+        );
+    }
+
+    #[test]
+    fn test_save_to_dir_round_trips() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().join("providers");
+        let config = ProviderConfig::new("okta", "yourorg.okta.com")
+            .with_auth_url("https://yourorg.okta.com/oauth2/v1/authorize")
+            .with_token_url("https://yourorg.okta.com/oauth2/v1/token")
+            .with_default_auth_param("prompt", "consent");
+
+        let path = config.save_to_dir(&dir).unwrap();
+        assert_eq!(path, dir.join("okta.toml"));
+
+        let registry = ProviderRegistry::new().merge_from_dir(&dir).unwrap();
+        assert_eq!(registry.get("okta"), Some(&config));
+    }
+
+    #[test]
+    fn test_save_to_dir_rejects_path_like_ids() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        for id in ["", "../escape", "a/b", ".hidden"] {
+            let result = ProviderConfig::new(id, "Bad").save_to_dir(temp_dir.path());
+            assert!(
+                matches!(result, Err(ProviderError::SaveError { .. })),
+                "{:?}",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn test_discovery_document_without_pkce() {
+        let mut doc: OidcDiscoveryDocument =
+            serde_json::from_value(discovery_document("https://id.example.com")).unwrap();
+        doc.code_challenge_methods_supported.clear();
+        doc.device_authorization_endpoint = None;
+
+        let config = doc.into_provider_config().unwrap();
+        assert_eq!(config.id, "id.example.com");
+        assert!(!config.supports_pkce);
+        assert!(!config.supports_device_code);
+    }
+
+    #[cfg(feature = "discovery-cache")]
+    #[tokio::test]
+    async fn test_discovery_cache_reuses_result() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(discovery_document(&server.uri())))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache = ProviderDiscoveryCache::new();
+        let first = cache.get_or_discover(&server.uri(), None).await.unwrap();
+        let second = cache.get_or_discover(&server.uri(), None).await.unwrap();
+        assert_eq!(first, second);
+
+        let expired = ProviderDiscoveryCache::with_ttl(std::time::Duration::ZERO);
+        assert!(expired.get(&server.uri()).is_none());
     }
 }
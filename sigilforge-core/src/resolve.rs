@@ -5,6 +5,7 @@
 //! - [`ReferenceResolver`] - Trait for resolving credential references
 //! - Support for `auth://service/account/credential` URIs
 //! - Optional support for `vals:ref+...` external references
+//! - [`TemplateExpander`] - `${ENV_VAR}` substitution in resolved values
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -39,6 +40,10 @@ pub enum ResolveError {
     /// Error calling external resolver (e.g., vals).
     #[error("external resolver error: {message}")]
     ExternalError { message: String },
+
+    /// A `${VAR}` placeholder referenced an undefined variable.
+    #[error("template expansion failed: variable '{var_name}' is not set")]
+    TemplateExpansionFailed { var_name: String },
 }
 
 /// The result of resolving a reference.
@@ -138,6 +143,10 @@ pub struct ResolverConfig {
 
     /// Cache resolved values for this duration (seconds).
     pub cache_ttl_secs: Option<u64>,
+
+    /// Expand `${ENV_VAR}` placeholders in resolved values (default: false).
+    #[serde(default)]
+    pub enable_template_expansion: bool,
 }
 
 impl Default for ResolverConfig {
@@ -147,10 +156,85 @@ impl Default for ResolverConfig {
             enable_vals: false,
             vals_path: None,
             cache_ttl_secs: None,
+            enable_template_expansion: false,
         }
     }
 }
 
+/// Expands `${VAR}` placeholders in credential values.
+///
+/// Supports values such as `Bearer ${TOKEN}` or `${API_BASE}/v1/endpoint`.
+/// Expansion is a single pass: substituted values are never re-scanned, so a
+/// variable whose value itself contains `${...}` cannot trigger further
+/// expansion or loops.
+///
+/// # Example
+///
+/// ```
+/// use sigilforge_core::TemplateExpander;
+///
+/// let expanded = TemplateExpander::expand_with("${BASE}/v1", |name| {
+///     (name == "BASE").then(|| "https://api.example.com".to_string())
+/// })
+/// .unwrap();
+/// assert_eq!(expanded, "https://api.example.com/v1");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TemplateExpander;
+
+impl TemplateExpander {
+    /// Check whether a value contains any `${...}` placeholder.
+    pub fn has_placeholders(value: &str) -> bool {
+        value.contains("${")
+    }
+
+    /// Expand placeholders using the process environment.
+    pub fn expand(value: &str) -> Result<String, ResolveError> {
+        Self::expand_with(value, |name| std::env::var(name).ok())
+    }
+
+    /// Expand placeholders using a custom variable lookup.
+    ///
+    /// Returns [`ResolveError::TemplateExpansionFailed`] if `lookup` returns
+    /// `None` for any referenced variable, and [`ResolveError::InvalidFormat`]
+    /// for an unterminated placeholder.
+    pub fn expand_with<F>(value: &str, lookup: F) -> Result<String, ResolveError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut output = String::with_capacity(value.len());
+        let mut rest = value;
+
+        while let Some(start) = rest.find("${") {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+
+            let end = after.find('}').ok_or_else(|| ResolveError::InvalidFormat {
+                message: "unterminated '${' placeholder in template".to_string(),
+            })?;
+
+            let var_name = &after[..end];
+            if var_name.is_empty() {
+                return Err(ResolveError::InvalidFormat {
+                    message: "empty '${}' placeholder in template".to_string(),
+                });
+            }
+
+            let replacement = lookup(var_name).ok_or_else(|| {
+                ResolveError::TemplateExpansionFailed {
+                    var_name: var_name.to_string(),
+                }
+            })?;
+            output.push_str(&replacement);
+
+            rest = &after[end + 1..];
+        }
+
+        output.push_str(rest);
+        Ok(output)
+    }
+}
+
 /// Default implementation of ReferenceResolver.
 ///
 /// This resolver handles:
@@ -183,6 +267,7 @@ where
     store: S,
     token_manager: T,
     config: ResolverConfig,
+    key_prefix: String,
 }
 
 #[cfg(feature = "oauth")]
@@ -197,6 +282,7 @@ where
             store,
             token_manager,
             config: ResolverConfig::default(),
+            key_prefix: crate::token_manager::DEFAULT_KEY_PREFIX.to_string(),
         }
     }
 
@@ -206,8 +292,19 @@ where
             store,
             token_manager,
             config,
+            key_prefix: crate::token_manager::DEFAULT_KEY_PREFIX.to_string(),
         }
     }
+
+    /// Read credentials from `{prefix}/{service}/{account}/{type}` instead
+    /// of `sigilforge/...`.
+    ///
+    /// Use the same prefix as the token manager's
+    /// [`with_key_prefix`](crate::token_manager::DefaultTokenManager::with_key_prefix).
+    pub fn with_key_prefix(mut self, prefix: &str) -> Self {
+        self.key_prefix = prefix.to_string();
+        self
+    }
 }
 
 #[cfg(feature = "oauth")]
@@ -257,7 +354,7 @@ where
     async fn resolve_ref(&self, cred_ref: &CredentialRef) -> Result<ResolvedValue, ResolveError> {
         use crate::model::CredentialType;
 
-        match &cred_ref.credential_type {
+        let resolved = match &cred_ref.credential_type {
             // For access tokens, use the token manager (handles refresh)
             CredentialType::AccessToken => {
                 let token = self
@@ -271,7 +368,7 @@ where
 
             // For other credential types, fetch directly from store
             _ => {
-                let key = cred_ref.to_key();
+                let key = cred_ref.to_key_in(&self.key_prefix);
                 match self.store.get(&key).await? {
                     Some(secret) => Ok(ResolvedValue::Secret(secret)),
                     None => Err(ResolveError::NotFound {
@@ -279,6 +376,18 @@ where
                     }),
                 }
             }
+        }?;
+
+        if !self.config.enable_template_expansion {
+            return Ok(resolved);
+        }
+
+        match resolved {
+            ResolvedValue::Secret(secret) if TemplateExpander::has_placeholders(secret.expose()) => {
+                let expanded = TemplateExpander::expand(secret.expose())?;
+                Ok(ResolvedValue::Secret(Secret::new(expanded)))
+            }
+            other => Ok(other),
         }
     }
 
@@ -337,6 +446,68 @@ mod tests {
         };
         assert!(err.to_string().contains("bad format"));
     }
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "TOKEN" => Some("abc123".to_string()),
+            "API_BASE" => Some("https://api.example.com".to_string()),
+            "NESTED" => Some("${TOKEN}".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_template_expansion() {
+        assert_eq!(
+            TemplateExpander::expand_with("Bearer ${TOKEN}", lookup).unwrap(),
+            "Bearer abc123"
+        );
+        assert_eq!(
+            TemplateExpander::expand_with("${API_BASE}/v1/${TOKEN}", lookup).unwrap(),
+            "https://api.example.com/v1/abc123"
+        );
+    }
+
+    #[test]
+    fn test_template_expansion_is_single_level() {
+        // The substituted value is not expanded again
+        assert_eq!(
+            TemplateExpander::expand_with("x-${NESTED}", lookup).unwrap(),
+            "x-${TOKEN}"
+        );
+    }
+
+    #[test]
+    fn test_template_expansion_missing_variable() {
+        let err = TemplateExpander::expand_with("Bearer ${MISSING}", lookup).unwrap_err();
+        assert!(matches!(
+            err,
+            ResolveError::TemplateExpansionFailed { ref var_name } if var_name == "MISSING"
+        ));
+    }
+
+    #[test]
+    fn test_template_expansion_unterminated() {
+        let err = TemplateExpander::expand_with("Bearer ${TOKEN", lookup).unwrap_err();
+        assert!(matches!(err, ResolveError::InvalidFormat { .. }));
+    }
+
+    #[test]
+    fn test_template_expansion_passthrough() {
+        let value = "plain-secret-$value-without-braces";
+        assert!(!TemplateExpander::has_placeholders(value));
+        assert_eq!(TemplateExpander::expand_with(value, lookup).unwrap(), value);
+    }
+
+    #[test]
+    fn test_template_expansion_from_env() {
+        // SAFETY: the variable name is unique to this test
+        unsafe { std::env::set_var("SIGILFORGE_TEST_TEMPLATE_VAR", "from-env") };
+        assert_eq!(
+            TemplateExpander::expand("v=${SIGILFORGE_TEST_TEMPLATE_VAR}").unwrap(),
+            "v=from-env"
+        );
+    }
 }
 
 #[cfg(all(test, feature = "oauth"))]
@@ -403,4 +574,62 @@ mod oauth_tests {
         let result: Result<ResolvedValue, ResolveError> = resolver.resolve("auth://test/account/api_key").await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_default_resolver_template_expansion() {
+        use crate::store::SecretStore;
+
+        let store: Box<dyn crate::store::SecretStore> = Box::new(MemoryStore::new());
+        let token_manager = DefaultTokenManager::new(store, ProviderRegistry::new());
+
+        let resolver_store: Box<dyn crate::store::SecretStore> = Box::new(MemoryStore::new());
+        resolver_store
+            .set(
+                "sigilforge/test/account/api_key",
+                &Secret::new("${SIGILFORGE_TEST_RESOLVER_BASE}/v1"),
+            )
+            .await
+            .unwrap();
+        resolver_store
+            .set("sigilforge/test/account/client_id", &Secret::new("no-placeholders"))
+            .await
+            .unwrap();
+
+        let config = ResolverConfig {
+            enable_template_expansion: true,
+            ..ResolverConfig::default()
+        };
+        let resolver = DefaultReferenceResolver::with_config(resolver_store, token_manager, config);
+
+        // Undefined variables surface as an error
+        let result = resolver.resolve("auth://test/account/api_key").await;
+        assert!(matches!(result, Err(ResolveError::TemplateExpansionFailed { .. })));
+
+        // SAFETY: the variable name is unique to this test
+        unsafe { std::env::set_var("SIGILFORGE_TEST_RESOLVER_BASE", "https://api.example.com") };
+        let value = resolver.resolve("auth://test/account/api_key").await.unwrap();
+        assert_eq!(value.expose(), "https://api.example.com/v1");
+
+        let value = resolver.resolve("auth://test/account/client_id").await.unwrap();
+        assert_eq!(value.expose(), "no-placeholders");
+    }
+
+    #[tokio::test]
+    async fn test_default_resolver_key_prefix() {
+        use crate::store::SecretStore;
+
+        let store: Box<dyn crate::store::SecretStore> = Box::new(MemoryStore::new());
+        let token_manager = DefaultTokenManager::new(store, ProviderRegistry::new());
+
+        let resolver_store: Box<dyn crate::store::SecretStore> = Box::new(MemoryStore::new());
+        resolver_store
+            .set("myapp/test/account/api_key", &Secret::new("myapp-key"))
+            .await
+            .unwrap();
+        let resolver =
+            DefaultReferenceResolver::new(resolver_store, token_manager).with_key_prefix("myapp");
+
+        let value = resolver.resolve("auth://test/account/api_key").await.unwrap();
+        assert_eq!(value.expose(), "myapp-key");
+    }
 }
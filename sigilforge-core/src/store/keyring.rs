@@ -3,7 +3,7 @@
 use async_trait::async_trait;
 use keyring::Entry;
 
-use super::{Secret, SecretStore, StoreError};
+use super::{has_expired, is_ttl_key, ttl_key, Secret, SecretStore, StoreError};
 
 /// OS keyring-backed secret store.
 ///
@@ -17,6 +17,20 @@ use super::{Secret, SecretStore, StoreError};
 /// Keys are stored using the format: `{service_name}/{key}`
 /// where the service_name is set during construction.
 ///
+/// # Transactions
+///
+/// Platform keyrings have no multi-entry transactions, so
+/// [`SecretStore::transaction`] uses compare-and-swap: every entry the
+/// transaction touches is read when first used and read again before the
+/// writes, and the commit fails with [`StoreError::Conflict`] if any changed.
+///
+/// # Expiry
+///
+/// The expiry of a secret stored with
+/// [`set_with_ttl`](SecretStore::set_with_ttl) is an entry of its own, so
+/// every read looks up two entries. Writing or deleting a key removes its
+/// expiry entry.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -64,19 +78,9 @@ impl KeyringStore {
             message: format!("failed to create keyring entry: {}", e),
         })
     }
-}
-
-impl std::fmt::Debug for KeyringStore {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("KeyringStore")
-            .field("service_name", &self.service_name)
-            .finish()
-    }
-}
 
-#[async_trait]
-impl SecretStore for KeyringStore {
-    async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+    /// Read the entry for `key`, ignoring any expiry.
+    fn read_entry(&self, key: &str) -> Result<Option<Secret>, StoreError> {
         let entry = self.create_entry(key)?;
 
         match entry.get_password() {
@@ -97,7 +101,48 @@ impl SecretStore for KeyringStore {
         }
     }
 
+    /// Delete the entry for `key`, succeeding if there is none.
+    fn delete_entry(&self, key: &str) -> Result<(), StoreError> {
+        let entry = self.create_entry(key)?;
+
+        match entry.delete_credential() {
+            Ok(()) => Ok(()),
+            Err(keyring::Error::NoEntry) => Ok(()), // Idempotent delete
+            Err(e) => Err(StoreError::BackendError {
+                message: format!("failed to delete keyring entry: {}", e),
+            }),
+        }
+    }
+}
+
+impl std::fmt::Debug for KeyringStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyringStore")
+            .field("service_name", &self.service_name)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl SecretStore for KeyringStore {
+    async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+        if !is_ttl_key(key) {
+            let expiry_key = ttl_key(key);
+            let expiry = self.read_entry(&expiry_key)?;
+            if expiry.is_some_and(|expiry| has_expired(&expiry)) {
+                self.delete_entry(key)?;
+                self.delete_entry(&expiry_key)?;
+                return Ok(None);
+            }
+        }
+        self.read_entry(key)
+    }
+
     async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError> {
+        // Drop the old expiry first, so it cannot expire the new secret
+        if !is_ttl_key(key) {
+            self.delete_entry(&ttl_key(key))?;
+        }
         let entry = self.create_entry(key)?;
 
         entry
@@ -108,15 +153,11 @@ impl SecretStore for KeyringStore {
     }
 
     async fn delete(&self, key: &str) -> Result<(), StoreError> {
-        let entry = self.create_entry(key)?;
-
-        match entry.delete_credential() {
-            Ok(()) => Ok(()),
-            Err(keyring::Error::NoEntry) => Ok(()), // Idempotent delete
-            Err(e) => Err(StoreError::BackendError {
-                message: format!("failed to delete keyring entry: {}", e),
-            }),
+        self.delete_entry(key)?;
+        if !is_ttl_key(key) {
+            self.delete_entry(&ttl_key(key))?;
         }
+        Ok(())
     }
 
     async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
@@ -133,6 +174,10 @@ impl SecretStore for KeyringStore {
             ),
         })
     }
+
+    fn backend_name(&self) -> &'static str {
+        "keyring"
+    }
 }
 
 #[cfg(test)]
@@ -3,8 +3,28 @@
 use async_trait::async_trait;
 use parking_lot::RwLock;
 use std::collections::HashMap;
+use std::sync::Arc;
+#[cfg(feature = "versioned-store")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
-use super::{Secret, SecretStore, StoreError};
+use super::{
+    expiry_after, has_expired, is_ttl_key, plan_rename, renamed_away, ttl_key, ConflictPolicy,
+    Secret, SecretStore, StoreError, TransactionLog, TTL_SUFFIX,
+};
+
+/// Most recent versions of each key kept for [`MemoryStore::get_at_version`].
+#[cfg(feature = "versioned-store")]
+const MAX_VERSIONS_PER_KEY: usize = 16;
+
+/// What is kept per key.
+#[cfg(not(feature = "versioned-store"))]
+type Entry = Secret;
+
+/// What is kept per key: recent `(value, version)` pairs, newest last.
+#[cfg(feature = "versioned-store")]
+type Entry = Vec<(Secret, u64)>;
 
 /// In-memory secret store for testing and development.
 ///
@@ -13,24 +33,175 @@ use super::{Secret, SecretStore, StoreError};
 /// # Thread Safety
 ///
 /// This implementation uses interior mutability via `RwLock` and is
-/// safe to share across threads.
+/// safe to share across threads. Transactions hold a store-wide lock from
+/// start to commit, so concurrent transactions run one after another.
+/// [`copy_key`](SecretStore::copy_key),
+/// [`move_key`](SecretStore::move_key) and
+/// [`rename_prefix`](SecretStore::rename_prefix) run under one write lock,
+/// so readers never see a moved secret at neither key or at both.
+///
+/// # Expiry
+///
+/// [`set_with_ttl`](SecretStore::set_with_ttl) writes a secret and its
+/// expiry under one lock. Reading an expired secret deletes it, and any
+/// other write or delete of a key drops its expiry.
+///
+/// # Versioning
+///
+/// With the `versioned-store` feature, every write is given a new version,
+/// enabling [`SecretStore::set_if_version`] for optimistic concurrency and
+/// reads of earlier values with [`get_at_version`](Self::get_at_version).
+/// Versions are unique across the store, so a deleted and recreated key
+/// never repeats an old version.
 pub struct MemoryStore {
-    data: RwLock<HashMap<String, Secret>>,
+    data: RwLock<HashMap<String, Entry>>,
+    transactions: Arc<Mutex<()>>,
+    #[cfg(feature = "versioned-store")]
+    last_version: AtomicU64,
 }
 
 impl MemoryStore {
     /// Create a new empty memory store.
     pub fn new() -> Self {
-        Self {
-            data: RwLock::new(HashMap::new()),
-        }
+        Self::with_data(HashMap::new())
     }
 
     /// Create a memory store with initial data.
     pub fn with_data(data: HashMap<String, Secret>) -> Self {
-        Self {
-            data: RwLock::new(data),
+        let store = Self {
+            data: RwLock::new(HashMap::new()),
+            transactions: Arc::new(Mutex::new(())),
+            #[cfg(feature = "versioned-store")]
+            last_version: AtomicU64::new(0),
+        };
+        {
+            let mut entries = store.data.write();
+            for (key, secret) in data {
+                store.write_entry(&mut entries, key, secret);
+            }
+        }
+        store
+    }
+
+    /// The current value of `entry`.
+    #[cfg(not(feature = "versioned-store"))]
+    fn current(entry: &Entry) -> Option<&Secret> {
+        Some(entry)
+    }
+
+    /// The current value of `entry`.
+    #[cfg(feature = "versioned-store")]
+    fn current(entry: &Entry) -> Option<&Secret> {
+        entry.last().map(|(secret, _)| secret)
+    }
+
+    /// Write `secret` to `key` in `data`, which is locked by the caller.
+    #[cfg(not(feature = "versioned-store"))]
+    fn write_entry(&self, data: &mut HashMap<String, Entry>, key: String, secret: Secret) {
+        data.insert(key, secret);
+    }
+
+    /// Write `secret` to `key` in `data`, which is locked by the caller.
+    #[cfg(feature = "versioned-store")]
+    fn write_entry(&self, data: &mut HashMap<String, Entry>, key: String, secret: Secret) {
+        let version = self.last_version.fetch_add(1, Ordering::SeqCst) + 1;
+        let versions = data.entry(key).or_default();
+        versions.push((secret, version));
+        if versions.len() > MAX_VERSIONS_PER_KEY {
+            versions.remove(0);
+        }
+    }
+
+    /// Copy the current value of `from` to `to` in `data`, which is locked
+    /// by the caller.
+    fn copy_entry(
+        &self,
+        data: &mut HashMap<String, Entry>,
+        from: &str,
+        to: &str,
+        overwrite: bool,
+    ) -> Result<(), StoreError> {
+        if !overwrite && data.get(to).and_then(Self::current).is_some() {
+            return Err(StoreError::AlreadyExists {
+                key: to.to_string(),
+            });
+        }
+        let secret = data
+            .get(from)
+            .and_then(Self::current)
+            .cloned()
+            .ok_or_else(|| StoreError::NotFound {
+                key: from.to_string(),
+            })?;
+        self.write_entry(data, to.to_string(), secret);
+        Ok(())
+    }
+
+    /// Drop the expiry of `key` in `data`, which is locked by the caller.
+    fn clear_ttl(data: &mut HashMap<String, Entry>, key: &str) {
+        if !is_ttl_key(key) {
+            data.remove(&ttl_key(key));
+        }
+    }
+
+    /// Whether `key` has an expiry in `data` that has passed.
+    fn is_expired(data: &HashMap<String, Entry>, key: &str) -> bool {
+        !is_ttl_key(key)
+            && data
+                .get(&ttl_key(key))
+                .and_then(Self::current)
+                .is_some_and(has_expired)
+    }
+
+    /// Delete `key` and its expiry if the expiry has passed, returning
+    /// whether it had.
+    fn expire(&self, key: &str) -> bool {
+        if !Self::is_expired(&self.data.read(), key) {
+            return false;
         }
+        // Check again, as the key may have been rewritten in between
+        let mut data = self.data.write();
+        if !Self::is_expired(&data, key) {
+            return false;
+        }
+        data.remove(key);
+        Self::clear_ttl(&mut data, key);
+        true
+    }
+}
+
+#[cfg(feature = "versioned-store")]
+impl MemoryStore {
+    /// The value `key` had as of `version`: its newest write at or before it.
+    ///
+    /// Returns `None` if the key did not exist then, has since been deleted,
+    /// or `version` is older than the versions still kept for it.
+    pub fn get_at_version(&self, key: &str, version: u64) -> Option<Secret> {
+        let data = self.data.read();
+        let versions = data.get(key)?;
+        if versions
+            .first()
+            .is_some_and(|(_, oldest)| *oldest > version)
+        {
+            return None;
+        }
+        versions
+            .iter()
+            .rev()
+            .find(|(_, written)| *written <= version)
+            .map(|(secret, _)| secret.clone())
+    }
+
+    /// Keys matching `prefix` with their current versions, sorted by key.
+    pub fn list_keys_with_versions(&self, prefix: &str) -> Vec<(String, u64)> {
+        let data = self.data.read();
+        let mut keys: Vec<(String, u64)> = data
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .filter_map(|(key, versions)| Some((key.clone(), versions.last()?.1)))
+            .collect();
+        keys.sort();
+        keys
     }
 }
 
@@ -52,19 +223,52 @@ impl std::fmt::Debug for MemoryStore {
 #[async_trait]
 impl SecretStore for MemoryStore {
     async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+        if self.expire(key) {
+            return Ok(None);
+        }
         let data = self.data.read();
-        Ok(data.get(key).cloned())
+        Ok(data.get(key).and_then(Self::current).cloned())
     }
 
     async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError> {
         let mut data = self.data.write();
-        data.insert(key.to_string(), secret.clone());
+        self.write_entry(&mut data, key.to_string(), secret.clone());
+        Self::clear_ttl(&mut data, key);
         Ok(())
     }
 
+    async fn set_with_ttl(
+        &self,
+        key: &str,
+        secret: &Secret,
+        ttl: Duration,
+    ) -> Result<(), StoreError> {
+        let mut data = self.data.write();
+        self.write_entry(&mut data, key.to_string(), secret.clone());
+        self.write_entry(&mut data, ttl_key(key), expiry_after(ttl));
+        Ok(())
+    }
+
+    async fn cleanup_expired(&self, prefix: &str) -> Result<usize, StoreError> {
+        let mut data = self.data.write();
+        let expired: Vec<String> = data
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .filter_map(|key| key.strip_suffix(TTL_SUFFIX))
+            .filter(|key| Self::is_expired(&data, key))
+            .map(str::to_string)
+            .collect();
+        for key in &expired {
+            data.remove(key);
+            Self::clear_ttl(&mut data, key);
+        }
+        Ok(expired.len())
+    }
+
     async fn delete(&self, key: &str) -> Result<(), StoreError> {
         let mut data = self.data.write();
         data.remove(key);
+        Self::clear_ttl(&mut data, key);
         Ok(())
     }
 
@@ -77,6 +281,111 @@ impl SecretStore for MemoryStore {
             .collect();
         Ok(keys)
     }
+
+    async fn copy_key(&self, from: &str, to: &str, overwrite: bool) -> Result<(), StoreError> {
+        let mut data = self.data.write();
+        self.copy_entry(&mut data, from, to, overwrite)
+    }
+
+    async fn move_key(&self, from: &str, to: &str, overwrite: bool) -> Result<(), StoreError> {
+        let mut data = self.data.write();
+        self.copy_entry(&mut data, from, to, overwrite)?;
+        if from != to {
+            data.remove(from);
+        }
+        Ok(())
+    }
+
+    async fn rename_prefix(
+        &self,
+        old_prefix: &str,
+        new_prefix: &str,
+        conflict: ConflictPolicy,
+    ) -> Result<usize, StoreError> {
+        let mut data = self.data.write();
+        let keys: Vec<String> = data
+            .keys()
+            .filter(|k| k.starts_with(old_prefix))
+            .cloned()
+            .collect();
+        let renames = plan_rename(keys, old_prefix, new_prefix, conflict, |key| {
+            data.get(key).and_then(Self::current).is_some()
+        })?;
+
+        for (from, to) in &renames {
+            self.copy_entry(&mut data, from, to, true)?;
+        }
+        for key in renamed_away(&renames) {
+            data.remove(key);
+        }
+        Ok(renames.len())
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<usize, StoreError> {
+        let mut data = self.data.write();
+        let before = data.len();
+        data.retain(|key, _| !key.starts_with(prefix));
+        Ok(before - data.len())
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "memory"
+    }
+
+    #[cfg(feature = "versioned-store")]
+    async fn get_version(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        let data = self.data.read();
+        Ok(data
+            .get(key)
+            .and_then(|versions| versions.last())
+            .map(|(_, version)| *version))
+    }
+
+    #[cfg(feature = "versioned-store")]
+    async fn set_if_version(
+        &self,
+        key: &str,
+        secret: &Secret,
+        expected_version: u64,
+    ) -> Result<bool, StoreError> {
+        let mut data = self.data.write();
+        let current = data
+            .get(key)
+            .and_then(|versions| versions.last())
+            .map_or(0, |(_, version)| *version);
+        if current != expected_version {
+            return Ok(false);
+        }
+        self.write_entry(&mut data, key.to_string(), secret.clone());
+        Self::clear_ttl(&mut data, key);
+        Ok(true)
+    }
+
+    async fn lock_transactions(&self) -> Option<OwnedMutexGuard<()>> {
+        Some(self.transactions.clone().lock_owned().await)
+    }
+
+    async fn commit_transaction(&self, log: TransactionLog) -> Result<(), StoreError> {
+        // Writes outside a transaction do not take the transaction lock, so
+        // check for them under the data lock before applying anything
+        let mut data = self.data.write();
+        for (key, seen) in &log.reads {
+            if data.get(key).and_then(Self::current) != seen.as_ref() {
+                return Err(StoreError::Conflict { key: key.clone() });
+            }
+        }
+
+        for (key, value) in log.writes {
+            Self::clear_ttl(&mut data, &key);
+            match value {
+                Some(secret) => self.write_entry(&mut data, key, secret),
+                None => {
+                    data.remove(&key);
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -138,6 +447,95 @@ mod tests {
         assert_eq!(all_keys.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_memory_store_transaction_commits_together() {
+        let store = MemoryStore::new();
+        store.set("token", &Secret::new("old")).await.unwrap();
+        store
+            .set("expiry", &Secret::new("1700000000"))
+            .await
+            .unwrap();
+
+        let previous = store
+            .transaction(|tx| async move {
+                let previous = tx.get("token").await?;
+                tx.set("token", &Secret::new("new")).await?;
+                tx.delete("expiry").await?;
+                Ok(previous)
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(previous, Some(Secret::new("old")));
+        assert_eq!(store.get("token").await.unwrap(), Some(Secret::new("new")));
+        assert_eq!(store.get("expiry").await.unwrap(), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_memory_store_concurrent_transactions_are_isolated() {
+        let store = Arc::new(MemoryStore::new());
+        store.set("counter", &Secret::new("0")).await.unwrap();
+
+        // Each transaction reads, yields, then writes; without isolation most
+        // increments would be lost
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..20 {
+            let store = store.clone();
+            tasks.spawn(async move {
+                store
+                    .transaction(|tx| async move {
+                        let current = tx.get("counter").await?.unwrap();
+                        let next: u32 = current.expose().parse::<u32>().unwrap() + 1;
+                        tokio::task::yield_now().await;
+                        tx.set("counter", &Secret::new(next.to_string())).await
+                    })
+                    .await
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            result.unwrap().unwrap();
+        }
+
+        let counter = store.get("counter").await.unwrap().unwrap();
+        assert_eq!(counter.expose(), "20");
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_transaction_writes_invisible_until_commit() {
+        let store = MemoryStore::new();
+        let outside = &store;
+
+        store
+            .transaction(|tx| async move {
+                tx.set("key", &Secret::new("pending")).await?;
+                assert_eq!(outside.get("key").await?, None);
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get("key").await.unwrap(),
+            Some(Secret::new("pending"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_transaction_conflicts_with_direct_write() {
+        let store = MemoryStore::new();
+        let outside = &store;
+
+        let result = store
+            .transaction(|tx| async move {
+                tx.set("key", &Secret::new("from-transaction")).await?;
+                outside.set("key", &Secret::new("direct")).await
+            })
+            .await;
+
+        assert!(matches!(result, Err(StoreError::Conflict { .. })));
+        assert_eq!(store.get("key").await.unwrap(), Some(Secret::new("direct")));
+    }
+
     #[tokio::test]
     async fn test_memory_store_exists() {
         let store = MemoryStore::new();
@@ -149,4 +547,425 @@ mod tests {
 
         assert!(store.exists("test-key").await.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_memory_store_copy_key() {
+        let store = MemoryStore::new();
+        store.set("from", &Secret::new("value")).await.unwrap();
+        store.set("taken", &Secret::new("kept")).await.unwrap();
+
+        store.copy_key("from", "to", false).await.unwrap();
+        assert_eq!(store.get("to").await.unwrap(), Some(Secret::new("value")));
+        assert_eq!(store.get("from").await.unwrap(), Some(Secret::new("value")));
+
+        let result = store.copy_key("from", "taken", false).await;
+        assert!(matches!(result, Err(StoreError::AlreadyExists { key }) if key == "taken"));
+        assert_eq!(store.get("taken").await.unwrap(), Some(Secret::new("kept")));
+        store.copy_key("from", "taken", true).await.unwrap();
+        let copied = store.get("taken").await.unwrap();
+        assert_eq!(copied, Some(Secret::new("value")));
+
+        let result = store.copy_key("missing", "other", true).await;
+        assert!(matches!(result, Err(StoreError::NotFound { key }) if key == "missing"));
+        assert!(!store.exists("other").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_move_key() {
+        let store = MemoryStore::new();
+        store.set("from", &Secret::new("value")).await.unwrap();
+        store.set("taken", &Secret::new("kept")).await.unwrap();
+
+        let result = store.move_key("from", "taken", false).await;
+        assert!(matches!(result, Err(StoreError::AlreadyExists { .. })));
+        assert!(store.exists("from").await.unwrap());
+
+        store.move_key("from", "to", false).await.unwrap();
+        assert_eq!(store.get("from").await.unwrap(), None);
+        assert_eq!(store.get("to").await.unwrap(), Some(Secret::new("value")));
+
+        // Moving a key onto itself keeps it
+        store.move_key("to", "to", true).await.unwrap();
+        assert_eq!(store.get("to").await.unwrap(), Some(Secret::new("value")));
+    }
+
+    /// A store with two credentials under `old/` and one under `new/`.
+    fn rename_fixture() -> MemoryStore {
+        MemoryStore::with_data(HashMap::from([
+            ("old/access_token".to_string(), Secret::new("old-access")),
+            ("old/refresh_token".to_string(), Secret::new("old-refresh")),
+            ("new/access_token".to_string(), Secret::new("new-access")),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_rename_prefix() {
+        let store = MemoryStore::with_data(HashMap::from([
+            ("old/access_token".to_string(), Secret::new("access")),
+            ("old/refresh_token".to_string(), Secret::new("refresh")),
+            ("other/access_token".to_string(), Secret::new("other")),
+        ]));
+
+        let renamed = store
+            .rename_prefix("old/", "new/", ConflictPolicy::Error)
+            .await
+            .unwrap();
+        assert_eq!(renamed, 2);
+        assert_eq!(store.list_keys("").await.unwrap().len(), 3);
+        assert!(store.list_keys("old/").await.unwrap().is_empty());
+        let access = store.get("new/access_token").await.unwrap();
+        assert_eq!(access, Some(Secret::new("access")));
+        let other = store.get("other/access_token").await.unwrap();
+        assert_eq!(other, Some(Secret::new("other")));
+
+        let renamed = store
+            .rename_prefix("missing/", "new/", ConflictPolicy::Error)
+            .await
+            .unwrap();
+        assert_eq!(renamed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_rename_prefix_conflict_skip() {
+        let store = rename_fixture();
+
+        let renamed = store
+            .rename_prefix("old/", "new/", ConflictPolicy::Skip)
+            .await
+            .unwrap();
+        assert_eq!(renamed, 1);
+        assert_eq!(store.list_keys("").await.unwrap().len(), 3);
+        let kept = store.get("new/access_token").await.unwrap();
+        assert_eq!(kept, Some(Secret::new("new-access")));
+        let skipped = store.get("old/access_token").await.unwrap();
+        assert_eq!(skipped, Some(Secret::new("old-access")));
+        let moved = store.get("new/refresh_token").await.unwrap();
+        assert_eq!(moved, Some(Secret::new("old-refresh")));
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_rename_prefix_conflict_overwrite() {
+        let store = rename_fixture();
+
+        let renamed = store
+            .rename_prefix("old/", "new/", ConflictPolicy::Overwrite)
+            .await
+            .unwrap();
+        assert_eq!(renamed, 2);
+        assert!(store.list_keys("old/").await.unwrap().is_empty());
+        let replaced = store.get("new/access_token").await.unwrap();
+        assert_eq!(replaced, Some(Secret::new("old-access")));
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_rename_prefix_conflict_error() {
+        let store = rename_fixture();
+
+        let result = store
+            .rename_prefix("old/", "new/", ConflictPolicy::Error)
+            .await;
+        assert!(
+            matches!(result, Err(StoreError::AlreadyExists { key }) if key == "new/access_token")
+        );
+        // Nothing was renamed, not even the key without a conflict
+        assert_eq!(store.list_keys("old/").await.unwrap().len(), 2);
+        assert!(!store.exists("new/refresh_token").await.unwrap());
+        let kept = store.get("new/access_token").await.unwrap();
+        assert_eq!(kept, Some(Secret::new("new-access")));
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_rename_prefix_nested() {
+        let store = MemoryStore::with_data(HashMap::from([
+            ("a/x".to_string(), Secret::new("outer")),
+            ("a/a/x".to_string(), Secret::new("inner")),
+        ]));
+
+        // a/x is renamed onto a/a/x, which moves on to a/a/a/x
+        let renamed = store
+            .rename_prefix("a/", "a/a/", ConflictPolicy::Error)
+            .await
+            .unwrap();
+        assert_eq!(renamed, 2);
+        assert_eq!(store.list_keys("").await.unwrap().len(), 2);
+        assert_eq!(store.get("a/x").await.unwrap(), None);
+        let outer = store.get("a/a/x").await.unwrap();
+        assert_eq!(outer, Some(Secret::new("outer")));
+        let inner = store.get("a/a/a/x").await.unwrap();
+        assert_eq!(inner, Some(Secret::new("inner")));
+
+        let renamed = store
+            .rename_prefix("a/a/", "a/", ConflictPolicy::Error)
+            .await
+            .unwrap();
+        assert_eq!(renamed, 2);
+        assert_eq!(store.get("a/x").await.unwrap(), Some(Secret::new("outer")));
+        let inner = store.get("a/a/x").await.unwrap();
+        assert_eq!(inner, Some(Secret::new("inner")));
+        assert_eq!(store.get("a/a/a/x").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_delete_prefix() {
+        let store = MemoryStore::with_data(HashMap::from([
+            ("github/work/access_token".to_string(), Secret::new("a")),
+            ("github/personal/api_key".to_string(), Secret::new("k")),
+            ("gitlab/work/access_token".to_string(), Secret::new("g")),
+            ("other/access_token".to_string(), Secret::new("o")),
+        ]));
+
+        assert_eq!(store.delete_prefix("github/").await.unwrap(), 2);
+        assert!(store.list_keys("github/").await.unwrap().is_empty());
+        assert!(store.exists("gitlab/work/access_token").await.unwrap());
+
+        assert_eq!(store.delete_prefix("git").await.unwrap(), 1);
+        let keys = store.list_keys("").await.unwrap();
+        assert_eq!(keys, vec!["other/access_token"]);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_ttl_expires_immediately() {
+        let store = MemoryStore::new();
+        store
+            .set_with_ttl("token", &Secret::new("t"), Duration::ZERO)
+            .await
+            .unwrap();
+
+        assert_eq!(store.get("token").await.unwrap(), None);
+        assert!(!store.exists("token").await.unwrap());
+        // The expired secret and its expiry were deleted by the read
+        assert!(store.list_keys("").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_ttl_in_future() {
+        let store = MemoryStore::new();
+        let hour = Duration::from_secs(3600);
+        store
+            .set_with_ttl("token", &Secret::new("t"), hour)
+            .await
+            .unwrap();
+        assert_eq!(store.get("token").await.unwrap(), Some(Secret::new("t")));
+        assert!(store.exists("token/__ttl").await.unwrap());
+
+        // A plain write drops the expiry, as does a delete
+        store
+            .set_with_ttl("token", &Secret::new("t"), Duration::ZERO)
+            .await
+            .unwrap();
+        store.set("token", &Secret::new("kept")).await.unwrap();
+        assert_eq!(store.get("token").await.unwrap(), Some(Secret::new("kept")));
+        store
+            .set_with_ttl("token", &Secret::new("t"), hour)
+            .await
+            .unwrap();
+        store.delete("token").await.unwrap();
+        assert!(store.list_keys("").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_cleanup_expired() {
+        let store = MemoryStore::new();
+        let hour = Duration::from_secs(3600);
+        for key in ["github/work/access_token", "gitlab/work/access_token"] {
+            store
+                .set_with_ttl(key, &Secret::new("t"), Duration::ZERO)
+                .await
+                .unwrap();
+        }
+        store
+            .set_with_ttl("github/oss/access_token", &Secret::new("t"), hour)
+            .await
+            .unwrap();
+        store
+            .set("github/oss/refresh_token", &Secret::new("r"))
+            .await
+            .unwrap();
+
+        assert_eq!(store.cleanup_expired("github/").await.unwrap(), 1);
+        let mut keys = store.list_keys("github/").await.unwrap();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "github/oss/access_token",
+                "github/oss/access_token/__ttl",
+                "github/oss/refresh_token",
+            ]
+        );
+        assert_eq!(store.list_keys("gitlab/").await.unwrap().len(), 2);
+
+        assert_eq!(store.cleanup_expired("").await.unwrap(), 1);
+        assert!(store.list_keys("gitlab/").await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_memory_store_move_key_atomic_under_concurrent_reads() {
+        let store = Arc::new(MemoryStore::new());
+        store.set("a", &Secret::new("token")).await.unwrap();
+        let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        // Readers check both keys while the secret moves back and forth;
+        // it must be at exactly one of them every time
+        let mut readers = tokio::task::JoinSet::new();
+        for _ in 0..4 {
+            let (store, done) = (store.clone(), done.clone());
+            readers.spawn(async move {
+                while !done.load(std::sync::atomic::Ordering::SeqCst) {
+                    let (a, b) = {
+                        let data = store.data.read();
+                        (data.contains_key("a"), data.contains_key("b"))
+                    };
+                    assert!(a != b, "secret at a: {}, at b: {}", a, b);
+                    tokio::task::yield_now().await;
+                }
+            });
+        }
+
+        for i in 0..500 {
+            let (from, to) = if i % 2 == 0 { ("a", "b") } else { ("b", "a") };
+            store.move_key(from, to, false).await.unwrap();
+            tokio::task::yield_now().await;
+        }
+        done.store(true, std::sync::atomic::Ordering::SeqCst);
+        while let Some(result) = readers.join_next().await {
+            result.unwrap();
+        }
+        assert_eq!(store.get("a").await.unwrap(), Some(Secret::new("token")));
+    }
+
+    #[cfg(feature = "versioned-store")]
+    #[tokio::test]
+    async fn test_memory_store_versions_increase() {
+        let store = MemoryStore::new();
+        assert_eq!(store.get_version("key").await.unwrap(), None);
+
+        store.set("key", &Secret::new("v1")).await.unwrap();
+        let first = store.get_version("key").await.unwrap().unwrap();
+        store.set("key", &Secret::new("v2")).await.unwrap();
+        let second = store.get_version("key").await.unwrap().unwrap();
+        assert!(second > first);
+
+        // MVCC-style reads see the value as of each version
+        assert_eq!(store.get_at_version("key", first), Some(Secret::new("v1")));
+        assert_eq!(store.get_at_version("key", second), Some(Secret::new("v2")));
+        assert_eq!(store.get_at_version("key", first - 1), None);
+
+        // Recreating a deleted key does not reuse its old version
+        store.delete("key").await.unwrap();
+        assert_eq!(store.get_version("key").await.unwrap(), None);
+        store.set("key", &Secret::new("v3")).await.unwrap();
+        assert!(store.get_version("key").await.unwrap().unwrap() > second);
+    }
+
+    #[cfg(feature = "versioned-store")]
+    #[tokio::test]
+    async fn test_memory_store_set_if_version() {
+        let store = MemoryStore::new();
+
+        // Version 0 only matches a missing key
+        assert!(
+            store
+                .set_if_version("key", &Secret::new("v1"), 0)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !store
+                .set_if_version("key", &Secret::new("v1b"), 0)
+                .await
+                .unwrap()
+        );
+
+        let version = store.get_version("key").await.unwrap().unwrap();
+        store.set("key", &Secret::new("v2")).await.unwrap();
+
+        // A writer that read v1 must not replace the newer v2
+        assert!(
+            !store
+                .set_if_version("key", &Secret::new("stale"), version)
+                .await
+                .unwrap()
+        );
+        assert_eq!(store.get("key").await.unwrap(), Some(Secret::new("v2")));
+
+        let version = store.get_version("key").await.unwrap().unwrap();
+        assert!(
+            store
+                .set_if_version("key", &Secret::new("v3"), version)
+                .await
+                .unwrap()
+        );
+        assert_eq!(store.get("key").await.unwrap(), Some(Secret::new("v3")));
+    }
+
+    #[cfg(feature = "versioned-store")]
+    #[tokio::test]
+    async fn test_memory_store_keeps_recent_versions() {
+        let store = MemoryStore::new();
+        store.set("key", &Secret::new("0")).await.unwrap();
+        let oldest = store.get_version("key").await.unwrap().unwrap();
+        for i in 1..=MAX_VERSIONS_PER_KEY {
+            store.set("key", &Secret::new(i.to_string())).await.unwrap();
+        }
+
+        assert_eq!(store.get_at_version("key", oldest), None);
+        assert_eq!(
+            store.get_at_version("key", oldest + 1),
+            Some(Secret::new("1"))
+        );
+    }
+
+    #[cfg(feature = "versioned-store")]
+    #[tokio::test]
+    async fn test_memory_store_list_keys_with_versions() {
+        let store = MemoryStore::new();
+        store.set("sigilforge/b", &Secret::new("b")).await.unwrap();
+        store.set("sigilforge/a", &Secret::new("a")).await.unwrap();
+        store.set("other/c", &Secret::new("c")).await.unwrap();
+
+        store
+            .transaction(|tx| async move { tx.set("sigilforge/b", &Secret::new("b2")).await })
+            .await
+            .unwrap();
+
+        let keys = store.list_keys_with_versions("sigilforge/");
+        let names: Vec<&str> = keys.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(names, ["sigilforge/a", "sigilforge/b"]);
+        for (key, version) in &keys {
+            assert_eq!(store.get_version(key).await.unwrap(), Some(*version));
+        }
+        // The transaction's write is the newest in the store
+        assert!(keys[1].1 > keys[0].1);
+    }
+
+    #[cfg(feature = "versioned-store")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_memory_store_concurrent_set_if_version() {
+        let store = Arc::new(MemoryStore::new());
+        store.set("token", &Secret::new("original")).await.unwrap();
+        let version = store.get_version("token").await.unwrap().unwrap();
+
+        // Every writer read the same version, so exactly one may win
+        let mut tasks = tokio::task::JoinSet::new();
+        for i in 0..20 {
+            let store = store.clone();
+            tasks.spawn(async move {
+                let secret = Secret::new(format!("writer-{}", i));
+                tokio::task::yield_now().await;
+                let won = store
+                    .set_if_version("token", &secret, version)
+                    .await
+                    .unwrap();
+                won.then_some(secret)
+            });
+        }
+
+        let mut winners = Vec::new();
+        while let Some(result) = tasks.join_next().await {
+            winners.extend(result.unwrap());
+        }
+        assert_eq!(winners.len(), 1);
+        assert_eq!(store.get("token").await.unwrap(), winners.pop());
+    }
 }
@@ -5,12 +5,20 @@
 //! - [`SecretStore`] - Trait for secret storage backends
 //! - [`MemoryStore`] - In-memory implementation for testing
 //! - [`KeyringStore`] - OS keyring implementation (with `keyring-store` feature)
+//! - [`SecretStoreTransaction`] - Buffered multi-key updates via [`SecretStore::transaction`]
 //! - [`create_store`] - Helper to select backend based on availability
 //!
 //! # Storage Key Convention
 //!
 //! Keys follow the pattern: `sigilforge/{service}/{account}/{credential_type}`
 //!
+//! # Expiry
+//!
+//! A secret stored with [`SecretStore::set_with_ttl`] has its expiry kept
+//! beside it, at [`ttl_key`] of its key, as a Unix timestamp. Once that
+//! passes the secret reads as missing and is deleted on the next read, or
+//! by [`SecretStore::cleanup_expired`].
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -26,23 +34,33 @@
 //! ```
 
 use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
 use thiserror::Error;
+use tokio::sync::OwnedMutexGuard;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 mod memory;
 #[cfg(feature = "keyring-store")]
 mod keyring;
+mod transaction;
 
 pub use memory::MemoryStore;
 #[cfg(feature = "keyring-store")]
 pub use keyring::KeyringStore;
+pub use transaction::{SecretStoreTransaction, TransactionLog};
 
 /// A secret value that prevents accidental exposure in logs.
 ///
 /// The inner value is only accessible via [`expose()`](Secret::expose).
 /// Debug and Display implementations show `[REDACTED]` instead of the value.
 /// Memory is automatically zeroed when the secret is dropped.
+///
+/// Binary secrets, such as HMAC or signing keys, are held as unpadded
+/// base64url; see [`from_bytes()`](Secret::from_bytes).
 #[derive(Clone, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 pub struct Secret(String);
 
@@ -64,6 +82,63 @@ impl Secret {
     pub fn into_inner(self) -> String {
         self.0.clone()
     }
+
+    /// Create a secret from binary data, stored as unpadded base64url.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    /// The binary data of a secret created with
+    /// [`from_bytes()`](Secret::from_bytes), or `None` if the value is not
+    /// unpadded base64url.
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        URL_SAFE_NO_PAD.decode(&self.0).ok()
+    }
+
+    /// Create a binary secret from hex digits (either case).
+    pub fn from_hex(hex: &str) -> Result<Self, SecretParseError> {
+        let mut bytes = hex::decode(hex).map_err(SecretParseError::from)?;
+        let secret = Self::from_bytes(&bytes);
+        bytes.zeroize();
+        Ok(secret)
+    }
+
+    /// The binary data of the secret as lowercase hex, or `None` if it is
+    /// not a binary secret.
+    pub fn to_hex(&self) -> Option<String> {
+        let mut bytes = self.to_bytes()?;
+        let hex = hex::encode(&bytes);
+        bytes.zeroize();
+        Some(hex)
+    }
+}
+
+/// Error parsing an encoded [`Secret`].
+///
+/// Messages never include the secret's contents.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SecretParseError {
+    /// The value has an odd number of hex digits.
+    #[error("invalid hex secret: odd number of digits")]
+    OddLength,
+
+    /// The value has a character that is not a hex digit.
+    #[error("invalid hex secret: non-hex character at position {index}")]
+    InvalidCharacter { index: usize },
+}
+
+impl From<hex::FromHexError> for SecretParseError {
+    fn from(error: hex::FromHexError) -> Self {
+        match error {
+            hex::FromHexError::InvalidHexCharacter { index, .. } => {
+                Self::InvalidCharacter { index }
+            }
+            // Only returned by decoding into a fixed-size buffer
+            hex::FromHexError::OddLength | hex::FromHexError::InvalidStringLength => {
+                Self::OddLength
+            }
+        }
+    }
 }
 
 impl std::fmt::Debug for Secret {
@@ -108,6 +183,110 @@ pub enum StoreError {
     /// The keyring backend is not available.
     #[error("keyring not available: {message}")]
     KeyringUnavailable { message: String },
+
+    /// A key touched by a transaction was changed by another writer.
+    #[error("transaction conflict: {key} was modified concurrently")]
+    Conflict { key: String },
+
+    /// A copy or move would have replaced an existing secret.
+    #[error("secret already exists: {key}")]
+    AlreadyExists { key: String },
+}
+
+/// Suffix of the key holding the expiry of a secret stored with
+/// [`SecretStore::set_with_ttl`].
+pub const TTL_SUFFIX: &str = "/__ttl";
+
+/// Key holding the expiry of the secret at `key`.
+pub fn ttl_key(key: &str) -> String {
+    format!("{}{}", key, TTL_SUFFIX)
+}
+
+/// Whether `key` holds the expiry of another secret rather than a secret.
+pub fn is_ttl_key(key: &str) -> bool {
+    key.ends_with(TTL_SUFFIX)
+}
+
+/// Expiry stored at [`ttl_key`] for a secret that lives for `ttl`, as a
+/// Unix timestamp rounded down to the second.
+pub(crate) fn expiry_after(ttl: Duration) -> Secret {
+    let ttl = i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX);
+    let expires_at = chrono::Utc::now().timestamp().saturating_add(ttl);
+    Secret::new(expires_at.to_string())
+}
+
+/// Whether the expiry stored at a [`ttl_key`] has passed.
+///
+/// An expiry that isn't a timestamp never passes.
+pub(crate) fn has_expired(expiry: &Secret) -> bool {
+    expiry
+        .expose()
+        .parse::<i64>()
+        .is_ok_and(|expires_at| chrono::Utc::now().timestamp() >= expires_at)
+}
+
+/// What [`SecretStore::rename_prefix`] does with a key whose new name is
+/// already taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Leave the key under its old name and keep the existing secret.
+    Skip,
+    /// Replace the existing secret.
+    Overwrite,
+    /// Fail with [`StoreError::AlreadyExists`] before renaming anything.
+    Error,
+}
+
+/// Plan a [`SecretStore::rename_prefix`] of `keys`, all of which start
+/// with `old_prefix`, as `(from, to)` pairs.
+///
+/// `exists` tells whether a key currently holds a secret. When one prefix
+/// extends the other, a key can be renamed onto another key that is itself
+/// being renamed; the pairs are ordered so that such a key is copied away
+/// before it is overwritten, and it only counts as taken if it stays.
+pub(crate) fn plan_rename(
+    mut keys: Vec<String>,
+    old_prefix: &str,
+    new_prefix: &str,
+    conflict: ConflictPolicy,
+    exists: impl Fn(&str) -> bool,
+) -> Result<Vec<(String, String)>, StoreError> {
+    if old_prefix == new_prefix {
+        return Ok(Vec::new());
+    }
+    // Renaming lengthens or shortens every key alike, so sorting by length
+    // puts each key's new name ahead of the key
+    if new_prefix.len() > old_prefix.len() {
+        keys.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    } else {
+        keys.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    }
+
+    let mut renames: Vec<(String, String)> = Vec::with_capacity(keys.len());
+    for key in &keys {
+        let new_key = format!("{}{}", new_prefix, &key[old_prefix.len()..]);
+        let moving_away = renames.iter().any(|(from, _)| *from == new_key);
+        if !moving_away && exists(&new_key) {
+            match conflict {
+                ConflictPolicy::Skip => continue,
+                ConflictPolicy::Overwrite => {}
+                ConflictPolicy::Error => {
+                    return Err(StoreError::AlreadyExists { key: new_key });
+                }
+            }
+        }
+        renames.push((key.clone(), new_key));
+    }
+    Ok(renames)
+}
+
+/// Keys of a planned rename to delete once all are copied: every source
+/// that is not also the new name of another key.
+pub(crate) fn renamed_away(renames: &[(String, String)]) -> impl Iterator<Item = &str> {
+    renames
+        .iter()
+        .map(|(from, _)| from.as_str())
+        .filter(|from| !renames.iter().any(|(_, to)| to == from))
 }
 
 /// Abstraction over secret storage backends.
@@ -125,9 +304,46 @@ pub trait SecretStore: Send + Sync {
 
     /// Store a secret at the given key.
     ///
-    /// Overwrites any existing value.
+    /// Overwrites any existing value. Backends that check expiry in
+    /// [`get`](Self::get) also drop any TTL the key had.
     async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError>;
 
+    /// Store a secret that reads as missing once `ttl` has passed.
+    ///
+    /// The default calls [`set`](Self::set) and then stores the expiry at
+    /// [`ttl_key`] of `key`, so the secret may briefly be readable without
+    /// it. Expiry is only enforced by backends whose [`get`](Self::get)
+    /// checks it, as [`MemoryStore`] and [`KeyringStore`] do.
+    async fn set_with_ttl(
+        &self,
+        key: &str,
+        secret: &Secret,
+        ttl: Duration,
+    ) -> Result<(), StoreError> {
+        self.set(key, secret).await?;
+        self.set(&ttl_key(key), &expiry_after(ttl)).await
+    }
+
+    /// Delete every expired secret whose key starts with `prefix`, with its
+    /// expiry, returning how many secrets were deleted.
+    ///
+    /// Expired secrets are otherwise only deleted when next read. Fails if
+    /// the backend cannot list its keys.
+    async fn cleanup_expired(&self, prefix: &str) -> Result<usize, StoreError> {
+        let mut deleted = 0;
+        for expiry_key in self.list_keys(prefix).await? {
+            let Some(key) = expiry_key.strip_suffix(TTL_SUFFIX) else {
+                continue;
+            };
+            if self.get(&expiry_key).await?.is_some_and(|expiry| has_expired(&expiry)) {
+                self.delete(key).await?;
+                self.delete(&expiry_key).await?;
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
     /// Delete a secret by key.
     ///
     /// Returns `Ok(())` even if the key didn't exist.
@@ -142,6 +358,187 @@ pub trait SecretStore: Send + Sync {
     async fn exists(&self, key: &str) -> Result<bool, StoreError> {
         Ok(self.get(key).await?.is_some())
     }
+
+    /// Copy the secret at `from` to `to`.
+    ///
+    /// Fails with [`StoreError::NotFound`] if `from` doesn't exist, and with
+    /// [`StoreError::AlreadyExists`] if `to` does and `overwrite` is false.
+    /// The default reads and writes separately, so a concurrent write to
+    /// either key may land in between; backends that can should override it
+    /// to copy under one lock.
+    async fn copy_key(&self, from: &str, to: &str, overwrite: bool) -> Result<(), StoreError> {
+        if !overwrite && self.exists(to).await? {
+            return Err(StoreError::AlreadyExists {
+                key: to.to_string(),
+            });
+        }
+        let secret = self.get(from).await?.ok_or_else(|| StoreError::NotFound {
+            key: from.to_string(),
+        })?;
+        self.set(to, &secret).await
+    }
+
+    /// Move the secret at `from` to `to`: [`copy_key`](Self::copy_key),
+    /// then delete `from`.
+    ///
+    /// The secret is always readable at one of the keys; if the delete fails
+    /// it is at both.
+    async fn move_key(&self, from: &str, to: &str, overwrite: bool) -> Result<(), StoreError> {
+        self.copy_key(from, to, overwrite).await?;
+        self.delete(from).await
+    }
+
+    /// Rename every key starting with `old_prefix` to start with
+    /// `new_prefix` instead, returning how many keys were renamed.
+    ///
+    /// `conflict` decides what happens to keys whose new name already
+    /// exists. All keys are copied before any is deleted, so every secret
+    /// stays readable under one of its names; if a copy fails, the new
+    /// names written so far are restored to what they held before, and if a
+    /// delete fails the secret is under both. The default is not atomic;
+    /// backends that can should override it to rename under one lock.
+    async fn rename_prefix(
+        &self,
+        old_prefix: &str,
+        new_prefix: &str,
+        conflict: ConflictPolicy,
+    ) -> Result<usize, StoreError> {
+        let keys = self.list_keys(old_prefix).await?;
+        let mut taken = std::collections::HashSet::new();
+        for key in &keys {
+            let new_key = format!("{}{}", new_prefix, &key[old_prefix.len()..]);
+            if self.exists(&new_key).await? {
+                taken.insert(new_key);
+            }
+        }
+        let renames = plan_rename(keys, old_prefix, new_prefix, conflict, |key| {
+            taken.contains(key)
+        })?;
+
+        let mut written: Vec<(&str, Option<Secret>)> = Vec::with_capacity(renames.len());
+        for (from, to) in &renames {
+            let copied = match self.get(to).await {
+                Ok(previous) => self.copy_key(from, to, true).await.map(|()| previous),
+                Err(e) => Err(e),
+            };
+            match copied {
+                Ok(previous) => written.push((to, previous)),
+                Err(e) => {
+                    // Undo in reverse, since a new name may be an old name
+                    // copied away earlier
+                    for (key, previous) in written.into_iter().rev() {
+                        let _ = match previous {
+                            Some(secret) => self.set(key, &secret).await,
+                            None => self.delete(key).await,
+                        };
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        for key in renamed_away(&renames) {
+            self.delete(key).await?;
+        }
+        Ok(renames.len())
+    }
+
+    /// Delete every key starting with `prefix`, returning how many keys
+    /// were deleted.
+    ///
+    /// Fails if the backend cannot list its keys. The default is not
+    /// atomic; backends that can should override it to delete under one
+    /// lock.
+    async fn delete_prefix(&self, prefix: &str) -> Result<usize, StoreError> {
+        let keys = self.list_keys(prefix).await?;
+        for key in &keys {
+            self.delete(key).await?;
+        }
+        Ok(keys.len())
+    }
+
+    /// Short name of the storage backend (e.g., "keyring", "memory").
+    ///
+    /// Used for diagnostics such as the daemon health check.
+    fn backend_name(&self) -> &'static str {
+        "unknown"
+    }
+
+    /// Current version of the secret at `key`.
+    ///
+    /// Versions increase with every write, so a changed version means the
+    /// secret was rewritten since it was last read. Returns `Ok(None)` if the
+    /// key doesn't exist or the backend does not track versions (the default).
+    async fn get_version(&self, _key: &str) -> Result<Option<u64>, StoreError> {
+        Ok(None)
+    }
+
+    /// Store a secret only if `key` is still at `expected_version`.
+    ///
+    /// An `expected_version` of `0` matches a key that doesn't exist. Returns
+    /// `Ok(false)`, without writing, if the version has moved on. Backends
+    /// that do not track versions write unconditionally and return `Ok(true)`.
+    async fn set_if_version(
+        &self,
+        key: &str,
+        secret: &Secret,
+        _expected_version: u64,
+    ) -> Result<bool, StoreError> {
+        self.set(key, secret).await?;
+        Ok(true)
+    }
+
+    /// Run `f` as a transaction and apply its writes together.
+    ///
+    /// Reads through the [`SecretStoreTransaction`] see its own pending
+    /// writes; nothing is written unless `f` returns `Ok`. Fails with
+    /// [`StoreError::Conflict`] if a key the transaction touched was changed
+    /// by another writer before commit.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # async fn example() -> Result<(), sigilforge_core::store::StoreError> {
+    /// use sigilforge_core::store::{MemoryStore, Secret, SecretStore};
+    ///
+    /// let store = MemoryStore::new();
+    /// store
+    ///     .transaction(|tx| async move {
+    ///         tx.set("sigilforge/github/work/access_token", &Secret::new("new")).await?;
+    ///         tx.delete("sigilforge/github/work/token_expiry").await
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn transaction<'a, F, Fut, R>(
+        &'a self,
+        f: F,
+    ) -> impl Future<Output = Result<R, StoreError>> + Send + 'a
+    where
+        Self: Sized,
+        F: FnOnce(SecretStoreTransaction<'a>) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<R, StoreError>> + Send + 'a,
+        R: Send + 'a,
+    {
+        transaction::run(self, f)
+    }
+
+    /// Serialize transactions on this store for as long as the guard is held.
+    ///
+    /// Backends without a lock return `None` and rely on
+    /// [`commit_transaction`](Self::commit_transaction) to detect conflicts.
+    async fn lock_transactions(&self) -> Option<OwnedMutexGuard<()>> {
+        None
+    }
+
+    /// Apply a finished transaction.
+    ///
+    /// The default re-reads every key in `log.reads`, fails with
+    /// [`StoreError::Conflict`] if any changed, and then applies the writes,
+    /// restoring earlier ones if a later write fails.
+    async fn commit_transaction(&self, log: TransactionLog) -> Result<(), StoreError> {
+        transaction::compare_and_swap(self, log).await
+    }
 }
 
 /// Blanket implementation of SecretStore for Box<dyn SecretStore>.
@@ -162,6 +559,19 @@ impl SecretStore for Box<dyn SecretStore> {
         (**self).delete(key).await
     }
 
+    async fn set_with_ttl(
+        &self,
+        key: &str,
+        secret: &Secret,
+        ttl: Duration,
+    ) -> Result<(), StoreError> {
+        (**self).set_with_ttl(key, secret, ttl).await
+    }
+
+    async fn cleanup_expired(&self, prefix: &str) -> Result<usize, StoreError> {
+        (**self).cleanup_expired(prefix).await
+    }
+
     async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
         (**self).list_keys(prefix).await
     }
@@ -169,6 +579,54 @@ impl SecretStore for Box<dyn SecretStore> {
     async fn exists(&self, key: &str) -> Result<bool, StoreError> {
         (**self).exists(key).await
     }
+
+    async fn copy_key(&self, from: &str, to: &str, overwrite: bool) -> Result<(), StoreError> {
+        (**self).copy_key(from, to, overwrite).await
+    }
+
+    async fn move_key(&self, from: &str, to: &str, overwrite: bool) -> Result<(), StoreError> {
+        (**self).move_key(from, to, overwrite).await
+    }
+
+    async fn rename_prefix(
+        &self,
+        old_prefix: &str,
+        new_prefix: &str,
+        conflict: ConflictPolicy,
+    ) -> Result<usize, StoreError> {
+        (**self)
+            .rename_prefix(old_prefix, new_prefix, conflict)
+            .await
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<usize, StoreError> {
+        (**self).delete_prefix(prefix).await
+    }
+
+    fn backend_name(&self) -> &'static str {
+        (**self).backend_name()
+    }
+
+    async fn get_version(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        (**self).get_version(key).await
+    }
+
+    async fn set_if_version(
+        &self,
+        key: &str,
+        secret: &Secret,
+        expected_version: u64,
+    ) -> Result<bool, StoreError> {
+        (**self).set_if_version(key, secret, expected_version).await
+    }
+
+    async fn lock_transactions(&self) -> Option<OwnedMutexGuard<()>> {
+        (**self).lock_transactions().await
+    }
+
+    async fn commit_transaction(&self, log: TransactionLog) -> Result<(), StoreError> {
+        (**self).commit_transaction(log).await
+    }
 }
 
 /// Create a secret store with automatic backend selection.
@@ -263,6 +721,84 @@ mod tests {
         assert_eq!(inner, "my-value");
     }
 
+    #[test]
+    fn test_secret_bytes_round_trip() {
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        for bytes in [&[][..], &[0], &[0xff, 0xfe, 0xfd], &all_bytes] {
+            let secret = Secret::from_bytes(bytes);
+            assert_eq!(secret.to_bytes().unwrap(), bytes);
+        }
+
+        // Unpadded base64url, so no '+', '/' or '='
+        let secret = Secret::from_bytes(&all_bytes);
+        assert_eq!(secret.expose().len(), 342);
+        let base64url = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+        assert!(secret.expose().chars().all(base64url));
+        assert_eq!(Secret::from_bytes(&[]).expose(), "");
+    }
+
+    #[test]
+    fn test_secret_to_bytes_not_base64url() {
+        assert!(Secret::new("not base64!").to_bytes().is_none());
+        assert!(Secret::new("YQ==").to_bytes().is_none());
+        assert!(Secret::new("a+b/").to_bytes().is_none());
+    }
+
+    #[test]
+    fn test_secret_hex_round_trip() {
+        let secret = Secret::from_hex("00FFa1").unwrap();
+        assert_eq!(secret.to_bytes().unwrap(), vec![0x00, 0xff, 0xa1]);
+        assert_eq!(secret.to_hex().unwrap(), "00ffa1");
+        assert_eq!(secret, Secret::from_bytes(&[0x00, 0xff, 0xa1]));
+
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        let hex = Secret::from_bytes(&all_bytes).to_hex().unwrap();
+        assert_eq!(hex.len(), 512);
+        let secret = Secret::from_hex(&hex).unwrap();
+        assert_eq!(secret.to_bytes().unwrap(), all_bytes);
+
+        assert_eq!(Secret::from_hex("").unwrap().to_hex().unwrap(), "");
+        assert!(Secret::new("not base64!").to_hex().is_none());
+    }
+
+    #[test]
+    fn test_secret_from_hex_invalid() {
+        let error = Secret::from_hex("abc").unwrap_err();
+        assert_eq!(error, SecretParseError::OddLength);
+        let error = Secret::from_hex("00zz").unwrap_err();
+        assert_eq!(error, SecretParseError::InvalidCharacter { index: 2 });
+        assert!(!error.to_string().contains('z'));
+    }
+
+    #[test]
+    fn test_binary_secret_redacted() {
+        let secret = Secret::from_hex("deadbeef").unwrap();
+        for shown in [format!("{:?}", secret), format!("{}", secret)] {
+            assert!(shown.contains("[REDACTED]"));
+            assert!(!shown.contains("deadbeef"));
+            assert!(!shown.contains(secret.expose()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_binary_key_credential_round_trip() {
+        use crate::model::{CredentialRef, CredentialType};
+
+        let store = MemoryStore::new();
+        let key = CredentialRef::new(
+            "webhooks",
+            "default",
+            CredentialType::Custom("binary_key".to_string()),
+        )
+        .to_key();
+        let hmac_key: Vec<u8> = (0..64).map(|i| (i * 7) as u8).collect();
+
+        let secret = Secret::from_bytes(&hmac_key);
+        store.set(&key, &secret).await.unwrap();
+        let stored = store.get(&key).await.unwrap().unwrap();
+        assert_eq!(stored.to_bytes().unwrap(), hmac_key);
+    }
+
     #[test]
     fn test_secret_equality() {
         let s1 = Secret::new("same");
@@ -299,6 +835,29 @@ mod tests {
         assert!(!store.exists("boxed-key").await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_box_dyn_secret_store_transaction() {
+        let store: Box<dyn SecretStore> = Box::new(MemoryStore::new());
+        store.set("a", &Secret::new("1")).await.unwrap();
+        let outside = &store;
+
+        // The boxed store forwards to MemoryStore's commit, which sees the
+        // direct write below
+        let result = store
+            .transaction(|tx| async move {
+                tx.set("a", &Secret::new("2")).await?;
+                outside.set("a", &Secret::new("3")).await
+            })
+            .await;
+        assert!(matches!(result, Err(StoreError::Conflict { .. })));
+
+        store
+            .transaction(|tx| async move { tx.set("b", &Secret::new("4")).await })
+            .await
+            .unwrap();
+        assert_eq!(store.get("b").await.unwrap(), Some(Secret::new("4")));
+    }
+
     #[tokio::test]
     async fn test_create_store_memory_fallback() {
         // This should always return a store, even if keyring is unavailable
@@ -363,5 +922,211 @@ mod tests {
 
         let err = StoreError::KeyringUnavailable { message: "no keyring".to_string() };
         assert!(err.to_string().contains("no keyring"));
+
+        let err = StoreError::AlreadyExists { key: "test-key".to_string() };
+        assert!(err.to_string().contains("already exists"));
+    }
+
+    /// A memory store seen only through the required trait methods, so the
+    /// default implementations run.
+    struct PlainStore(MemoryStore);
+
+    #[async_trait]
+    impl SecretStore for PlainStore {
+        async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+            self.0.get(key).await
+        }
+
+        async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError> {
+            self.0.set(key, secret).await
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), StoreError> {
+            self.0.delete(key).await
+        }
+
+        async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+            self.0.list_keys(prefix).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_rename_prefix() {
+        let store = PlainStore(MemoryStore::with_data(std::collections::HashMap::from([
+            ("old/access_token".to_string(), Secret::new("access")),
+            ("old/refresh_token".to_string(), Secret::new("refresh")),
+            ("new/access_token".to_string(), Secret::new("taken")),
+        ])));
+
+        let result = store
+            .rename_prefix("old/", "new/", ConflictPolicy::Error)
+            .await;
+        assert!(matches!(result, Err(StoreError::AlreadyExists { .. })));
+        assert_eq!(store.list_keys("old/").await.unwrap().len(), 2);
+
+        let renamed = store
+            .rename_prefix("old/", "new/", ConflictPolicy::Skip)
+            .await
+            .unwrap();
+        assert_eq!(renamed, 1);
+        assert_eq!(store.list_keys("").await.unwrap().len(), 3);
+        assert!(store.exists("old/access_token").await.unwrap());
+
+        let renamed = store
+            .rename_prefix("old/", "new/", ConflictPolicy::Overwrite)
+            .await
+            .unwrap();
+        assert_eq!(renamed, 1);
+        assert!(store.list_keys("old/").await.unwrap().is_empty());
+        let access = store.get("new/access_token").await.unwrap();
+        assert_eq!(access, Some(Secret::new("access")));
+    }
+
+    /// A [`PlainStore`] whose writes to one key fail.
+    struct FailingSetStore {
+        inner: PlainStore,
+        fail_key: &'static str,
+    }
+
+    #[async_trait]
+    impl SecretStore for FailingSetStore {
+        async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+            self.inner.get(key).await
+        }
+
+        async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError> {
+            if key == self.fail_key {
+                return Err(StoreError::BackendError {
+                    message: "write failed".to_string(),
+                });
+            }
+            self.inner.set(key, secret).await
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), StoreError> {
+            self.inner.delete(key).await
+        }
+
+        async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+            self.inner.list_keys(prefix).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_rename_prefix_rolls_back_failed_copy() {
+        let data = std::collections::HashMap::from([
+            ("old/access_token".to_string(), Secret::new("access")),
+            ("old/refresh_token".to_string(), Secret::new("refresh")),
+            ("new/access_token".to_string(), Secret::new("taken")),
+        ]);
+        let store = FailingSetStore {
+            inner: PlainStore(MemoryStore::with_data(data.clone())),
+            fail_key: "new/refresh_token",
+        };
+
+        let result = store
+            .rename_prefix("old/", "new/", ConflictPolicy::Overwrite)
+            .await;
+        assert!(matches!(result, Err(StoreError::BackendError { .. })));
+        assert_eq!(store.list_keys("old/").await.unwrap().len(), 2);
+        assert!(!store.exists("new/refresh_token").await.unwrap());
+        let taken = store.get("new/access_token").await.unwrap();
+        assert_eq!(taken, Some(Secret::new("taken")));
+
+        // A nested rename overwrites old names it has copied away
+        let store = FailingSetStore {
+            inner: PlainStore(MemoryStore::with_data(std::collections::HashMap::from([
+                ("a/x".to_string(), Secret::new("outer")),
+                ("a/a/x".to_string(), Secret::new("inner")),
+                ("a/y".to_string(), Secret::new("y")),
+            ]))),
+            fail_key: "a/a/y",
+        };
+        let result = store
+            .rename_prefix("a/", "a/a/", ConflictPolicy::Error)
+            .await;
+        assert!(result.is_err());
+        let outer = store.get("a/x").await.unwrap();
+        assert_eq!(outer, Some(Secret::new("outer")));
+        let inner = store.get("a/a/x").await.unwrap();
+        assert_eq!(inner, Some(Secret::new("inner")));
+        assert_eq!(store.list_keys("").await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_default_delete_prefix() {
+        let store = PlainStore(MemoryStore::with_data(std::collections::HashMap::from([
+            ("github/work/access_token".to_string(), Secret::new("a")),
+            ("github/work/refresh_token".to_string(), Secret::new("r")),
+            ("gitlab/work/access_token".to_string(), Secret::new("g")),
+        ])));
+
+        let deleted = store.delete_prefix("github/").await.unwrap();
+        assert_eq!(deleted, 2);
+        assert_eq!(store.list_keys("").await.unwrap().len(), 1);
+        assert_eq!(store.delete_prefix("github/").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_default_set_with_ttl() {
+        let store = PlainStore(MemoryStore::new());
+        let hour = Duration::from_secs(3600);
+
+        store
+            .set_with_ttl("github/work/access_token", &Secret::new("a"), Duration::ZERO)
+            .await
+            .unwrap();
+        store
+            .set_with_ttl("gitlab/work/access_token", &Secret::new("g"), hour)
+            .await
+            .unwrap();
+
+        assert_eq!(store.get("github/work/access_token").await.unwrap(), None);
+        assert!(store.list_keys("github/").await.unwrap().is_empty());
+        let access = store.get("gitlab/work/access_token").await.unwrap();
+        assert_eq!(access, Some(Secret::new("g")));
+        assert!(store.exists("gitlab/work/access_token/__ttl").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_default_cleanup_expired() {
+        let store = PlainStore(MemoryStore::new());
+        let hour = Duration::from_secs(3600);
+        for (key, ttl) in [
+            ("github/work/access_token", Some(Duration::ZERO)),
+            ("github/oss/access_token", Some(hour)),
+            ("github/oss/refresh_token", None),
+            ("gitlab/work/access_token", Some(Duration::ZERO)),
+        ] {
+            match ttl {
+                Some(ttl) => store.set_with_ttl(key, &Secret::new("t"), ttl).await.unwrap(),
+                None => store.set(key, &Secret::new("t")).await.unwrap(),
+            }
+        }
+
+        assert_eq!(store.cleanup_expired("github/").await.unwrap(), 1);
+        let mut keys = store.list_keys("").await.unwrap();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "github/oss/access_token",
+                "github/oss/access_token/__ttl",
+                "github/oss/refresh_token",
+                "gitlab/work/access_token",
+                "gitlab/work/access_token/__ttl",
+            ]
+        );
+        assert_eq!(store.cleanup_expired("").await.unwrap(), 1);
+        assert_eq!(store.cleanup_expired("").await.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_expiry_after() {
+        assert!(has_expired(&expiry_after(Duration::ZERO)));
+        assert!(!has_expired(&expiry_after(Duration::from_secs(60))));
+        assert!(!has_expired(&expiry_after(Duration::MAX)));
+        assert!(!has_expired(&Secret::new("never")));
+        assert!(is_ttl_key(&ttl_key("github/work/access_token")));
     }
 }
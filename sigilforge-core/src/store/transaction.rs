@@ -0,0 +1,289 @@
+//! Buffered multi-key transactions over a [`SecretStore`].
+//!
+//! A transaction records the value each key had when it was first touched
+//! and buffers every write. On commit the backend checks that none of those
+//! keys changed in the meantime and applies the writes together; see
+//! [`SecretStore::transaction`].
+
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use super::{Secret, SecretStore, StoreError};
+
+/// Keys touched by a transaction, handed to the backend on commit.
+#[derive(Debug, Default)]
+pub struct TransactionLog {
+    /// Value each touched key had when the transaction first saw it.
+    pub reads: BTreeMap<String, Option<Secret>>,
+
+    /// Pending writes; `None` deletes the key.
+    pub writes: BTreeMap<String, Option<Secret>>,
+}
+
+/// Handle passed to a [`SecretStore::transaction`] closure.
+///
+/// Writes are buffered until the closure returns; reads see the
+/// transaction's own pending writes.
+pub struct SecretStoreTransaction<'a> {
+    store: &'a dyn SecretStore,
+    log: Arc<Mutex<TransactionLog>>,
+}
+
+impl SecretStoreTransaction<'_> {
+    /// Retrieve a secret, including writes made earlier in this transaction.
+    ///
+    /// Repeated reads of a key return the value seen the first time.
+    pub async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+        let pending = self.log.lock().writes.get(key).cloned();
+        if let Some(pending) = pending {
+            return Ok(pending);
+        }
+        self.observe(key).await?;
+        Ok(self.log.lock().reads.get(key).cloned().flatten())
+    }
+
+    /// Current version of `key` in the store; see [`SecretStore::get_version`].
+    ///
+    /// The key counts as read, so the commit fails if it is rewritten after
+    /// its version was taken.
+    pub async fn get_version(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.observe(key).await?;
+        self.store.get_version(key).await
+    }
+
+    /// Store a secret when the transaction commits.
+    pub async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError> {
+        self.observe(key).await?;
+        self.log
+            .lock()
+            .writes
+            .insert(key.to_string(), Some(secret.clone()));
+        Ok(())
+    }
+
+    /// Delete a secret when the transaction commits.
+    pub async fn delete(&self, key: &str) -> Result<(), StoreError> {
+        self.observe(key).await?;
+        self.log.lock().writes.insert(key.to_string(), None);
+        Ok(())
+    }
+
+    /// Record the current value of `key` the first time it is touched.
+    async fn observe(&self, key: &str) -> Result<(), StoreError> {
+        let seen = self.log.lock().reads.contains_key(key);
+        if !seen {
+            let value = self.store.get(key).await?;
+            self.log
+                .lock()
+                .reads
+                .entry(key.to_string())
+                .or_insert(value);
+        }
+        Ok(())
+    }
+}
+
+/// Run `f` as a transaction over `store` and commit its writes.
+pub(crate) async fn run<'a, S, F, Fut, R>(store: &'a S, f: F) -> Result<R, StoreError>
+where
+    S: SecretStore,
+    F: FnOnce(SecretStoreTransaction<'a>) -> Fut,
+    Fut: Future<Output = Result<R, StoreError>>,
+{
+    let _lock = store.lock_transactions().await;
+    let log = Arc::new(Mutex::new(TransactionLog::default()));
+
+    let result = f(SecretStoreTransaction {
+        store,
+        log: log.clone(),
+    })
+    .await?;
+
+    let log = std::mem::take(&mut *log.lock());
+    store.commit_transaction(log).await?;
+    Ok(result)
+}
+
+/// Commit a transaction by re-reading every touched key and writing only if
+/// none of them changed.
+///
+/// The check and the writes are separate backend calls, so this narrows
+/// rather than closes the window for a concurrent writer. If a write fails,
+/// keys already written are put back to their original values.
+pub(crate) async fn compare_and_swap<S>(store: &S, log: TransactionLog) -> Result<(), StoreError>
+where
+    S: SecretStore + ?Sized,
+{
+    for (key, seen) in &log.reads {
+        if store.get(key).await? != *seen {
+            return Err(StoreError::Conflict { key: key.clone() });
+        }
+    }
+
+    let mut applied = Vec::new();
+    for (key, value) in &log.writes {
+        if let Err(e) = write(store, key, value.as_ref()).await {
+            for key in applied {
+                if let Some(original) = log.reads.get(key) {
+                    let _ = write(store, key, original.as_ref()).await;
+                }
+            }
+            return Err(e);
+        }
+        applied.push(key);
+    }
+
+    Ok(())
+}
+
+async fn write<S>(store: &S, key: &str, value: Option<&Secret>) -> Result<(), StoreError>
+where
+    S: SecretStore + ?Sized,
+{
+    match value {
+        Some(secret) => store.set(key, secret).await,
+        None => store.delete(key).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use async_trait::async_trait;
+
+    /// Memory-backed store that refuses to write one key and commits through
+    /// the default compare-and-swap path.
+    struct FailingStore {
+        inner: MemoryStore,
+        fail_key: &'static str,
+    }
+
+    #[async_trait]
+    impl SecretStore for FailingStore {
+        async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+            self.inner.get(key).await
+        }
+
+        async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError> {
+            if key == self.fail_key {
+                return Err(StoreError::BackendError {
+                    message: "write refused".to_string(),
+                });
+            }
+            self.inner.set(key, secret).await
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), StoreError> {
+            self.inner.delete(key).await
+        }
+
+        async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+            self.inner.list_keys(prefix).await
+        }
+    }
+
+    fn failing_store(fail_key: &'static str) -> FailingStore {
+        FailingStore {
+            inner: MemoryStore::new(),
+            fail_key,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compare_and_swap_applies_writes() {
+        let store = failing_store("none");
+        store.set("a", &Secret::new("old")).await.unwrap();
+        store.set("b", &Secret::new("gone")).await.unwrap();
+
+        store
+            .transaction(|tx| async move {
+                tx.set("a", &Secret::new("new")).await?;
+                tx.delete("b").await?;
+                assert_eq!(tx.get("a").await?, Some(Secret::new("new")));
+                assert_eq!(tx.get("b").await?, None);
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(store.get("a").await.unwrap(), Some(Secret::new("new")));
+        assert_eq!(store.get("b").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_compare_and_swap_detects_concurrent_write() {
+        let store = failing_store("none");
+        store.set("a", &Secret::new("v1")).await.unwrap();
+        let other_writer = &store;
+
+        let result = store
+            .transaction(|tx| async move {
+                let current = tx.get("a").await?;
+                assert_eq!(current, Some(Secret::new("v1")));
+                // Another writer changes the key before this one commits
+                other_writer.set("a", &Secret::new("v2")).await?;
+                tx.set("a", &Secret::new("v1+1")).await
+            })
+            .await;
+
+        assert!(matches!(result, Err(StoreError::Conflict { ref key }) if key == "a"));
+        assert_eq!(store.get("a").await.unwrap(), Some(Secret::new("v2")));
+    }
+
+    #[tokio::test]
+    async fn test_compare_and_swap_restores_keys_on_failed_write() {
+        let store = failing_store("b");
+        store.set("a", &Secret::new("original")).await.unwrap();
+
+        let result = store
+            .transaction(|tx| async move {
+                tx.set("a", &Secret::new("changed")).await?;
+                tx.set("b", &Secret::new("refused")).await
+            })
+            .await;
+
+        assert!(matches!(result, Err(StoreError::BackendError { .. })));
+        assert_eq!(store.get("a").await.unwrap(), Some(Secret::new("original")));
+        assert_eq!(store.get("b").await.unwrap(), None);
+    }
+
+    #[cfg(feature = "versioned-store")]
+    #[tokio::test]
+    async fn test_get_version_detects_later_write() {
+        let store = MemoryStore::new();
+        store.set("a", &Secret::new("v1")).await.unwrap();
+        let version = store.get_version("a").await.unwrap();
+        let other_writer = &store;
+
+        let result = store
+            .transaction(|tx| async move {
+                assert_eq!(tx.get_version("a").await?, version);
+                other_writer.set("a", &Secret::new("v2")).await?;
+                tx.set("b", &Secret::new("dependent")).await
+            })
+            .await;
+
+        assert!(matches!(result, Err(StoreError::Conflict { ref key }) if key == "a"));
+        assert_eq!(store.get("b").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_failed_closure_writes_nothing() {
+        let store = failing_store("none");
+
+        let result: Result<(), _> = store
+            .transaction(|tx| async move {
+                tx.set("a", &Secret::new("never")).await?;
+                Err(StoreError::BackendError {
+                    message: "aborted".to_string(),
+                })
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(store.get("a").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,239 @@
+//! Sets of OAuth 2.0 scopes.
+//!
+//! Providers disagree on how to write a list of scopes: RFC 6749 separates
+//! them with spaces, while some APIs (and Sigilforge's own keyring entries)
+//! use commas. [`ScopeSet`] accepts either and compares scopes as sets, so
+//! order and duplicates do not matter.
+
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// An ordered set of OAuth scopes.
+///
+/// # Example
+///
+/// ```rust
+/// use sigilforge_core::token::ScopeSet;
+///
+/// let requested: ScopeSet = "repo read:org workflow".parse().unwrap();
+/// let granted: ScopeSet = "repo,read:org".parse().unwrap();
+///
+/// assert!(granted.is_subset_of(&requested));
+/// assert_eq!(granted.missing_from(&requested).to_space_delimited(), "workflow");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ScopeSet(BTreeSet<String>);
+
+impl ScopeSet {
+    /// Create an empty scope set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of scopes in the set.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check if the set has no scopes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Check if `scope` is in the set.
+    pub fn contains(&self, scope: &str) -> bool {
+        self.0.contains(scope)
+    }
+
+    /// Add a scope, returning whether it was new.
+    pub fn insert(&mut self, scope: impl Into<String>) -> bool {
+        self.0.insert(scope.into())
+    }
+
+    /// Iterate over the scopes in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Scopes in both `self` and `other`.
+    pub fn intersection(&self, other: &ScopeSet) -> ScopeSet {
+        Self(self.0.intersection(&other.0).cloned().collect())
+    }
+
+    /// Scopes in either `self` or `other`.
+    pub fn union(&self, other: &ScopeSet) -> ScopeSet {
+        Self(self.0.union(&other.0).cloned().collect())
+    }
+
+    /// Scopes in `self` but not in `other`.
+    pub fn difference(&self, other: &ScopeSet) -> ScopeSet {
+        Self(self.0.difference(&other.0).cloned().collect())
+    }
+
+    /// Check if every scope in `self` is also in `other`.
+    pub fn is_subset_of(&self, other: &ScopeSet) -> bool {
+        self.0.is_subset(&other.0)
+    }
+
+    /// Check if every scope in `other` is also in `self`.
+    pub fn is_superset_of(&self, other: &ScopeSet) -> bool {
+        self.0.is_superset(&other.0)
+    }
+
+    /// Scopes in `required` that `self` lacks.
+    ///
+    /// With `self` as a token's granted scopes, an empty result means the
+    /// token has everything that was asked for.
+    pub fn missing_from(&self, required: &ScopeSet) -> ScopeSet {
+        required.difference(self)
+    }
+
+    /// Join the scopes with spaces, as in an OAuth `scope` parameter.
+    pub fn to_space_delimited(&self) -> String {
+        self.join(" ")
+    }
+
+    /// Join the scopes with commas.
+    pub fn to_comma_delimited(&self) -> String {
+        self.join(",")
+    }
+
+    fn join(&self, separator: &str) -> String {
+        self.iter().collect::<Vec<_>>().join(separator)
+    }
+}
+
+impl FromStr for ScopeSet {
+    type Err = Infallible;
+
+    /// Parse scopes separated by spaces, commas, or both.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|scope| !scope.is_empty())
+            .collect())
+    }
+}
+
+impl fmt::Display for ScopeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_space_delimited())
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for ScopeSet {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl IntoIterator for ScopeSet {
+    type Item = String;
+    type IntoIter = std::collections::btree_set::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(s: &str) -> ScopeSet {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_space_and_comma_delimited() {
+        let google = scopes(
+            "openid https://www.googleapis.com/auth/gmail.readonly \
+             https://www.googleapis.com/auth/calendar",
+        );
+        assert_eq!(google.len(), 3);
+        assert!(google.contains("https://www.googleapis.com/auth/calendar"));
+
+        // GitHub reports granted scopes comma separated, with spaces
+        let github = scopes("repo, read:org,workflow");
+        assert_eq!(github, scopes("workflow repo read:org"));
+
+        assert!(scopes("").is_empty());
+        assert!(scopes(" , ").is_empty());
+        assert_eq!(scopes("repo repo,repo").len(), 1);
+    }
+
+    #[test]
+    fn test_delimited_output_is_sorted() {
+        let spotify = scopes("user-read-private playlist-read-private user-read-email");
+        assert_eq!(
+            spotify.to_space_delimited(),
+            "playlist-read-private user-read-email user-read-private"
+        );
+        assert_eq!(
+            spotify.to_comma_delimited(),
+            "playlist-read-private,user-read-email,user-read-private"
+        );
+        assert_eq!(spotify.to_string(), spotify.to_space_delimited());
+        assert_eq!(scopes(&spotify.to_comma_delimited()), spotify);
+        assert_eq!(ScopeSet::new().to_space_delimited(), "");
+    }
+
+    #[test]
+    fn test_intersection_union_difference() {
+        let requested = scopes("repo read:org workflow");
+        let granted = scopes("repo read:org gist");
+
+        assert_eq!(requested.intersection(&granted), scopes("repo read:org"));
+        assert_eq!(
+            requested.union(&granted),
+            scopes("repo read:org workflow gist")
+        );
+        assert_eq!(requested.difference(&granted), scopes("workflow"));
+        assert_eq!(granted.difference(&requested), scopes("gist"));
+        assert!(requested.difference(&requested).is_empty());
+    }
+
+    #[test]
+    fn test_subset_and_superset() {
+        let full = scopes("openid email profile offline_access");
+        let partial = scopes("openid email");
+
+        assert!(partial.is_subset_of(&full));
+        assert!(!full.is_subset_of(&partial));
+        assert!(full.is_superset_of(&partial));
+        assert!(!partial.is_superset_of(&full));
+
+        // A set is a subset and superset of itself, and of the empty set
+        assert!(full.is_subset_of(&full) && full.is_superset_of(&full));
+        assert!(ScopeSet::new().is_subset_of(&partial));
+        assert!(partial.is_superset_of(&ScopeSet::new()));
+    }
+
+    #[test]
+    fn test_missing_from() {
+        let required = scopes("Mail.Read Calendars.Read offline_access");
+
+        let granted = scopes("Mail.Read offline_access");
+        assert_eq!(granted.missing_from(&required), scopes("Calendars.Read"));
+
+        // Extra granted scopes do not count, and scopes are case sensitive
+        let granted = scopes("Mail.Read Calendars.Read offline_access User.Read");
+        assert!(granted.missing_from(&required).is_empty());
+        assert_eq!(
+            scopes("mail.read").missing_from(&scopes("Mail.Read")),
+            scopes("Mail.Read")
+        );
+    }
+
+    #[test]
+    fn test_serde_as_list() {
+        let set = scopes("repo gist");
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, r#"["gist","repo"]"#);
+        assert_eq!(serde_json::from_str::<ScopeSet>(&json).unwrap(), set);
+    }
+}
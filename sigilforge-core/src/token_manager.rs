@@ -9,6 +9,10 @@
 //! - Persistent storage via [`SecretStore`]
 //! - Integration with OAuth provider configurations
 //! - Configurable expiry buffer to refresh tokens before they expire
+//! - Listing every stored token when given an [`AccountStore`]
+//! - Optional caching of [`TokenManager::introspect_token`] results
+//! - A configurable storage key namespace, so several applications can
+//!   share one keyring
 //!
 //! # Example
 //!
@@ -35,19 +39,32 @@
 //! # }
 //! ```
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use chrono::{Duration, Utc};
-use oauth2::{RefreshToken, TokenResponse, reqwest::async_http_client};
+use chrono::{DateTime, Duration, Utc};
+use futures::stream::{FuturesUnordered, StreamExt};
+use oauth2::{RefreshToken, TokenResponse};
+use tokio::sync::RwLock;
+use tokio::time::Instant;
 
 use crate::{
+    account_store::{AccountStore, AccountStoreError},
     model::{AccountId, CredentialType, ServiceId},
     provider::ProviderRegistry,
-    store::{Secret, SecretStore},
-    token::{Token, TokenError, TokenInfo, TokenManager, TokenSet},
+    store::{expiry_after, ttl_key, ConflictPolicy, Secret, SecretStore, StoreError},
+    token::{pre_warm_each, Token, TokenError, TokenInfo, TokenManager, TokenSet},
 };
 
 #[cfg(feature = "oauth")]
-use crate::oauth::create_oauth_client;
+use crate::oauth::{create_oauth_client, http::send_request};
+
+#[cfg(feature = "oauth")]
+use crate::oauth::github_app::{self, GitHubAppFlow};
+
+#[cfg(feature = "oauth")]
+use crate::oauth::pkce::{PendingAuthorization, PkceFlow};
 
 /// Default expiry buffer in minutes.
 ///
@@ -55,6 +72,77 @@ use crate::oauth::create_oauth_client;
 /// This prevents race conditions where a token expires between fetching and using it.
 const DEFAULT_EXPIRY_BUFFER_MINUTES: i64 = 5;
 
+/// Namespace storage keys start with unless [`DefaultTokenManager::with_key_prefix`]
+/// sets another.
+pub const DEFAULT_KEY_PREFIX: &str = "sigilforge";
+
+/// Whether `prefix` can namespace storage keys: it must be non-empty and
+/// must not contain `/`, which separates the parts of a key.
+pub fn is_valid_key_prefix(prefix: &str) -> bool {
+    !prefix.is_empty() && !prefix.contains('/')
+}
+
+/// Histogram of refresh-token exchange durations in milliseconds, labelled
+/// by `service`.
+///
+/// Only recorded when the `metrics` feature is enabled.
+pub const TOKEN_REFRESH_DURATION_METRIC: &str = "sigilforge.token.refresh.duration_ms";
+
+/// Credential type under which the provider-reported subject is stored.
+fn subject_credential_type() -> CredentialType {
+    CredentialType::Custom("subject".to_string())
+}
+
+/// Credential type under which the provider instance URL is stored.
+fn instance_url_credential_type() -> CredentialType {
+    CredentialType::Custom("instance_url".to_string())
+}
+
+/// Credential type under which a pending PKCE authorization is stored.
+#[cfg(feature = "oauth")]
+fn pkce_verifier_credential_type() -> CredentialType {
+    CredentialType::Custom("pkce_verifier".to_string())
+}
+
+/// Introspection results, each kept for a fixed time after it was computed.
+///
+/// `active` is computed when the result is cached, so a token that expires
+/// during the TTL is still reported as active until the entry expires.
+struct IntrospectionCache {
+    ttl: std::time::Duration,
+    entries: RwLock<HashMap<(ServiceId, AccountId), (TokenInfo, Instant)>>,
+}
+
+impl IntrospectionCache {
+    fn new(ttl: std::time::Duration) -> Self {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// The cached result for an account, unless it is older than the TTL.
+    async fn get(&self, service: &ServiceId, account: &AccountId) -> Option<TokenInfo> {
+        let entries = self.entries.read().await;
+        let (info, cached_at) = entries.get(&(service.clone(), account.clone()))?;
+        (cached_at.elapsed() < self.ttl).then(|| info.clone())
+    }
+
+    async fn insert(&self, service: &ServiceId, account: &AccountId, info: TokenInfo) {
+        self.entries
+            .write()
+            .await
+            .insert((service.clone(), account.clone()), (info, Instant::now()));
+    }
+
+    async fn invalidate(&self, service: &ServiceId, account: &AccountId) {
+        self.entries
+            .write()
+            .await
+            .remove(&(service.clone(), account.clone()));
+    }
+}
+
 /// Default implementation of TokenManager.
 ///
 /// This implementation:
@@ -72,6 +160,9 @@ pub struct DefaultTokenManager<S: SecretStore> {
     providers: ProviderRegistry,
     http_client: reqwest::Client,
     expiry_buffer: Duration,
+    account_store: Option<Arc<AccountStore>>,
+    introspection_cache: Option<IntrospectionCache>,
+    key_prefix: String,
 }
 
 impl<S: SecretStore> DefaultTokenManager<S> {
@@ -84,6 +175,9 @@ impl<S: SecretStore> DefaultTokenManager<S> {
             providers,
             http_client: reqwest::Client::new(),
             expiry_buffer: Duration::minutes(DEFAULT_EXPIRY_BUFFER_MINUTES),
+            account_store: None,
+            introspection_cache: None,
+            key_prefix: DEFAULT_KEY_PREFIX.to_string(),
         }
     }
 
@@ -104,6 +198,77 @@ impl<S: SecretStore> DefaultTokenManager<S> {
             providers,
             http_client: reqwest::Client::new(),
             expiry_buffer: Duration::minutes(expiry_buffer_minutes),
+            account_store: None,
+            introspection_cache: None,
+            key_prefix: DEFAULT_KEY_PREFIX.to_string(),
+        }
+    }
+
+    /// Use a custom HTTP client for token refresh and GitHub App requests.
+    ///
+    /// Configure proxies, extra root certificates, or timeouts on the client
+    /// before passing it in. The default is `reqwest::Client::new()`.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Enumerate accounts from `store` in [`TokenManager::list_tokens`], and
+    /// keep their refresh token expiry up to date as tokens are stored.
+    ///
+    /// Without an account store, `list_tokens` returns an empty list.
+    pub fn with_account_store(mut self, store: Arc<AccountStore>) -> Self {
+        self.account_store = Some(store);
+        self
+    }
+
+    /// Store credentials under `{prefix}/{service}/{account}/{type}` instead
+    /// of `sigilforge/...`.
+    ///
+    /// Applications sharing one keyring use different prefixes to keep
+    /// their credentials apart. Credentials already stored under another
+    /// prefix are not moved.
+    pub fn with_key_prefix(mut self, prefix: &str) -> Self {
+        self.key_prefix = prefix.to_string();
+        self
+    }
+
+    /// The namespace storage keys start with.
+    pub fn key_prefix(&self) -> &str {
+        &self.key_prefix
+    }
+
+    /// The providers tokens are refreshed with.
+    pub fn providers(&self) -> &ProviderRegistry {
+        &self.providers
+    }
+
+    /// Cache [`TokenManager::introspect_token`] results for `ttl`.
+    ///
+    /// Storing or revoking an account's tokens, including by a refresh,
+    /// drops its cached result. Changes made to the store by other processes
+    /// are not seen until the entry expires.
+    pub fn with_introspection_cache(mut self, ttl: std::time::Duration) -> Self {
+        self.introspection_cache = Some(IntrospectionCache::new(ttl));
+        self
+    }
+
+    /// The cached introspection result for an account, if caching is on.
+    async fn cached_introspection(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+    ) -> Option<TokenInfo> {
+        self.introspection_cache
+            .as_ref()?
+            .get(service, account)
+            .await
+    }
+
+    /// Drop the cached introspection result for an account.
+    async fn invalidate_introspection(&self, service: &ServiceId, account: &AccountId) {
+        if let Some(cache) = &self.introspection_cache {
+            cache.invalidate(service, account).await;
         }
     }
 
@@ -120,15 +285,31 @@ impl<S: SecretStore> DefaultTokenManager<S> {
         cred_type: CredentialType,
     ) -> String {
         format!(
-            "sigilforge/{}/{}/{}",
+            "{}/{}/{}/{}",
+            self.key_prefix,
             service.as_str(),
             account.as_str(),
             cred_type.as_str()
         )
     }
 
+    /// The prefix shared by the storage keys of every credential of an
+    /// account, including the trailing `/`.
+    pub fn account_key_prefix(&self, service: &ServiceId, account: &AccountId) -> String {
+        format!(
+            "{}/{}/{}/",
+            self.key_prefix,
+            service.as_str(),
+            account.as_str()
+        )
+    }
+
     /// Store a secret value for a service/account/credential type.
-    async fn store_credential(
+    ///
+    /// Use this for values the manager reads back later, such as the
+    /// [`CredentialType::ClientId`] and [`CredentialType::ClientSecret`]
+    /// that refreshing a token requires.
+    pub async fn store_credential(
         &self,
         service: &ServiceId,
         account: &AccountId,
@@ -151,6 +332,431 @@ impl<S: SecretStore> DefaultTokenManager<S> {
         Ok(self.store.get(&key).await?)
     }
 
+    /// Write every field of `token_set` in one transaction.
+    ///
+    /// With `expected_version`, the transaction first checks that the access
+    /// token still has that version, or is missing because the store dropped
+    /// it at its TTL. If not, another writer stored a newer token since it
+    /// was read, and nothing is written (returning `Ok(false)`). An access
+    /// token with an expiry is written along with its TTL, so the store
+    /// drops it once it expires.
+    async fn write_token_set(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+        token_set: TokenSet,
+        expected_version: Option<u64>,
+    ) -> Result<bool, TokenError> {
+        let key = |cred_type| self.credential_key(service, account, cred_type);
+        // A new refresh token restarts its lifetime
+        let refresh_issued_at = token_set
+            .refresh_token
+            .as_ref()
+            .map(|_| token_set.refreshed_at);
+        let access_token = token_set.access_token;
+        let access_key = key(CredentialType::AccessToken);
+        let ttl = access_token
+            .expires_at
+            .map(|expires_at| (expires_at - Utc::now()).to_std().unwrap_or_default());
+
+        // Collect every field first so they are written in one transaction;
+        // `None` removes a stale value
+        let mut fields = vec![(access_key.clone(), Some(access_token.access_token))];
+        // Writing the token drops its old TTL
+        if let Some(ttl) = ttl {
+            fields.push((ttl_key(&access_key), Some(expiry_after(ttl))));
+        }
+
+        // Salesforce access tokens carry no expires_in, so drop any old expiry
+        fields.push((
+            key(CredentialType::TokenExpiry),
+            access_token
+                .expires_at
+                .map(|expires_at| Secret::new(expires_at.timestamp().to_string())),
+        ));
+
+        if !access_token.scopes.is_empty() {
+            let scopes = Secret::new(access_token.scopes.join(","));
+            fields.push((key(CredentialType::TokenScopes), Some(scopes)));
+        }
+
+        if let Some(refresh_token) = token_set.refresh_token {
+            fields.push((key(CredentialType::RefreshToken), Some(refresh_token)));
+        }
+
+        // Subject and provider instance (Salesforce), if reported
+        if let Some(subject) = token_set.subject {
+            fields.push((key(subject_credential_type()), Some(Secret::new(subject))));
+        }
+        if let Some(instance_url) = token_set.instance_url {
+            fields.push((
+                key(instance_url_credential_type()),
+                Some(Secret::new(instance_url)),
+            ));
+        }
+
+        let access_key = &access_key;
+        let written = self
+            .store
+            .transaction(|tx| async move {
+                if let Some(version) = expected_version {
+                    // An expired token is deleted when read rather than
+                    // rewritten, so only a different version is a newer token
+                    let current = tx.get_version(access_key).await?;
+                    if current.is_some_and(|current| current != version) {
+                        return Ok(false);
+                    }
+                }
+                for (key, value) in fields {
+                    match value {
+                        Some(secret) => tx.set(&key, &secret).await?,
+                        None => tx.delete(&key).await?,
+                    }
+                }
+                Ok(true)
+            })
+            .await;
+        // Invalidate even on failure, as part of the token may be written
+        self.invalidate_introspection(service, account).await;
+        let written = match written {
+            // The token was rewritten while the transaction ran
+            Err(StoreError::Conflict { .. }) if expected_version.is_some() => false,
+            written => written?,
+        };
+        if !written {
+            return Ok(false);
+        }
+
+        tracing::debug!("Stored token set for {}/{}", service, account);
+
+        if let Some(issued_at) = refresh_issued_at {
+            self.record_refresh_token_expiry(service, account, issued_at);
+        }
+
+        Ok(true)
+    }
+
+    /// Update the account's [`Account::token_refresh_expiry`](crate::model::Account::token_refresh_expiry) for a refresh
+    /// token issued at `issued_at`.
+    ///
+    /// The token is already stored, so a failure is only logged.
+    fn record_refresh_token_expiry(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+        issued_at: DateTime<Utc>,
+    ) {
+        let Some(account_store) = &self.account_store else {
+            return;
+        };
+        let Some(provider) = self.providers.get(service.as_str()) else {
+            return;
+        };
+        let expires_at = provider.refresh_token_expiry(issued_at);
+        match account_store.update_token_refresh_expiry(service, account, expires_at) {
+            // Accounts are added to the store after their first token is stored
+            Ok(()) | Err(AccountStoreError::NotFound { .. }) => {}
+            Err(e) => tracing::warn!(
+                "Failed to record refresh token expiry for {}/{}: {}",
+                service,
+                account,
+                e
+            ),
+        }
+    }
+
+    /// Store a token set produced by a refresh, unless the access token has
+    /// changed from `expected_version` since it was read.
+    ///
+    /// Returns the access token to use: the refreshed one, or the newer one
+    /// that another refresh stored first.
+    async fn store_refreshed_token_set(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+        token_set: TokenSet,
+        expected_version: Option<u64>,
+    ) -> Result<Token, TokenError> {
+        let token = token_set.access_token.clone();
+        if self
+            .write_token_set(service, account, token_set, expected_version)
+            .await?
+        {
+            return Ok(token);
+        }
+
+        tracing::debug!(
+            "Token for {}/{} changed during refresh; keeping the newer token",
+            service,
+            account
+        );
+        self.get_token_set(service, account)
+            .await?
+            .map(|current| current.access_token)
+            .ok_or_else(|| TokenError::NotFound {
+                service: service.to_string(),
+                account: account.to_string(),
+            })
+    }
+
+    /// Replace `token_set` with a new one from the provider: a refresh
+    /// token grant, or a new GitHub App installation token.
+    ///
+    /// `version` is the access token's version when `token_set` was read
+    /// (see [`Self::store_refreshed_token_set`]).
+    async fn renew_token_set(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+        token_set: &TokenSet,
+        version: Option<u64>,
+    ) -> Result<Token, TokenError> {
+        if let Some(refresh_token) = &token_set.refresh_token {
+            tracing::info!("Refreshing access token for {}/{}", service, account);
+
+            let started = std::time::Instant::now();
+            let refreshed = self
+                .refresh_access_token(service, account, refresh_token.expose())
+                .await;
+            record_refresh_duration(service, started.elapsed());
+
+            match refreshed {
+                Ok(new_token_set) => {
+                    // Store the new token set
+                    let token = self
+                        .store_refreshed_token_set(service, account, new_token_set, version)
+                        .await?;
+
+                    tracing::info!(
+                        "Successfully refreshed access token for {}/{}",
+                        service,
+                        account
+                    );
+
+                    return Ok(token);
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to refresh token for {}/{}: {}",
+                        service,
+                        account,
+                        e
+                    );
+                    return Err(TokenError::Expired {
+                        message: format!("token refresh failed: {}", e),
+                    });
+                }
+            }
+        }
+
+        // GitHub App installations have no refresh token; request a new one
+        if let Some(new_token_set) = self.reissue_installation_token(service, account).await? {
+            let token = self
+                .store_refreshed_token_set(service, account, new_token_set, version)
+                .await?;
+
+            tracing::info!(
+                "Requested new installation token for {}/{}",
+                service,
+                account
+            );
+            return Ok(token);
+        }
+
+        Err(TokenError::Expired {
+            message: "no refresh token available".to_string(),
+        })
+    }
+
+    /// Fetch a new access token from the provider even if the cached one is
+    /// still valid, e.g. after it was revoked upstream.
+    ///
+    /// The refresh token is kept (or replaced, if the provider rotates it).
+    /// Fails with [`TokenError::NotFound`] if no token is stored, and with
+    /// [`TokenError::Expired`] if the token cannot be refreshed.
+    pub async fn force_refresh(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+    ) -> Result<Token, TokenError> {
+        let access_key = self.credential_key(service, account, CredentialType::AccessToken);
+        let version = self.store.get_version(&access_key).await?;
+
+        let Some(token_set) = self.get_token_set(service, account).await? else {
+            return Err(TokenError::NotFound {
+                service: service.to_string(),
+                account: account.to_string(),
+            });
+        };
+        self.renew_token_set(service, account, &token_set, version).await
+    }
+
+    /// Revoke the account's refresh token at the provider's `revoke_url`,
+    /// as an RFC 7009 revocation request.
+    ///
+    /// Returns `Ok(false)` without a request if no refresh token is stored
+    /// or the provider has no `revoke_url`. Stored credentials are kept;
+    /// delete them with [`revoke_tokens`](TokenManager::revoke_tokens).
+    #[cfg(feature = "oauth")]
+    pub async fn revoke_at_provider(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+    ) -> Result<bool, TokenError> {
+        let Some(refresh_token) = self
+            .get_credential(service, account, CredentialType::RefreshToken)
+            .await?
+        else {
+            return Ok(false);
+        };
+        let Some(revoke_url) = self
+            .providers
+            .get(service.as_str())
+            .and_then(|provider| provider.revoke_url.clone())
+        else {
+            return Ok(false);
+        };
+
+        let client_id = self
+            .get_credential(service, account, CredentialType::ClientId)
+            .await?
+            .ok_or_else(|| TokenError::OAuthError {
+                message: format!("client ID not found for {}/{}", service, account),
+            })?;
+        let client_secret = self
+            .get_credential(service, account, CredentialType::ClientSecret)
+            .await?;
+
+        let revoke_url = revoke_url.replace("{client_id}", client_id.expose());
+        let mut request = self.http_client.post(&revoke_url).form(&[
+            ("token", refresh_token.expose()),
+            ("token_type_hint", "refresh_token"),
+            ("client_id", client_id.expose()),
+        ]);
+        if let Some(secret) = &client_secret {
+            request = request.basic_auth(client_id.expose(), Some(secret.expose()));
+        }
+
+        let response = request.send().await.map_err(|e| TokenError::NetworkError {
+            message: format!("revocation request failed: {}", e),
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(TokenError::OAuthError {
+                message: format!("revocation failed with {}: {}", status, body.trim()),
+            });
+        }
+
+        tracing::info!(
+            "Revoked refresh token for {}/{} at the provider",
+            service,
+            account
+        );
+        Ok(true)
+    }
+
+    /// Keep `pending` in the store until the authorization code arrives,
+    /// e.g. across a daemon restart.
+    ///
+    /// Replaces any earlier pending authorization for the account.
+    #[cfg(feature = "oauth")]
+    pub async fn save_pending_authorization(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+        pending: &PendingAuthorization,
+    ) -> Result<(), TokenError> {
+        let json = serde_json::to_string(pending).map_err(|e| TokenError::OAuthError {
+            message: format!("failed to serialize pending authorization: {}", e),
+        })?;
+        self.store_credential(service, account, pkce_verifier_credential_type(), &json)
+            .await
+    }
+
+    /// Exchange `code` for tokens with the verifier saved by
+    /// [`save_pending_authorization`](Self::save_pending_authorization).
+    ///
+    /// The verifier is removed once the exchange succeeds, so a second call
+    /// fails. A failed exchange leaves it in place for a retry. The returned
+    /// tokens are not stored.
+    #[cfg(feature = "oauth")]
+    pub async fn exchange_pending_code(
+        &self,
+        flow: &PkceFlow,
+        service: &ServiceId,
+        account: &AccountId,
+        code: impl Into<String>,
+    ) -> Result<TokenSet, TokenError> {
+        let Some(saved) = self
+            .get_credential(service, account, pkce_verifier_credential_type())
+            .await?
+        else {
+            return Err(TokenError::OAuthError {
+                message: format!(
+                    "no unused PKCE verifier for {}/{}; start a new authorization",
+                    service, account
+                ),
+            });
+        };
+        let pending: PendingAuthorization =
+            serde_json::from_str(saved.expose()).map_err(|e| TokenError::OAuthError {
+                message: format!("invalid pending authorization: {}", e),
+            })?;
+
+        flow.resume(&pending)?;
+        let token_set = flow.exchange_code(code).await?;
+        self.mark_verifier_used(service, account).await?;
+        Ok(token_set)
+    }
+
+    /// Delete the account's saved PKCE verifier.
+    ///
+    /// Authorization codes are single use, and so is the verifier that goes
+    /// with one; call this once the code has been exchanged.
+    #[cfg(feature = "oauth")]
+    pub async fn mark_verifier_used(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+    ) -> Result<(), TokenError> {
+        let key = self.credential_key(service, account, pkce_verifier_credential_type());
+        self.store.delete(&key).await?;
+        Ok(())
+    }
+
+    /// Move every stored credential of `service/from` to `service/to`.
+    ///
+    /// All credentials are copied before any is deleted, so the tokens stay
+    /// readable under one of the names throughout; if one cannot be copied,
+    /// the copies made so far are removed again. Fails, without changing
+    /// anything, if `to` already has a credential of the same type. Account
+    /// metadata in the [`AccountStore`] is not renamed.
+    pub async fn rename_account(
+        &self,
+        service: &ServiceId,
+        from: &AccountId,
+        to: &AccountId,
+    ) -> Result<(), TokenError> {
+        let prefix = self.account_key_prefix(service, from);
+        let new_prefix = self.account_key_prefix(service, to);
+        let renamed = self
+            .store
+            .rename_prefix(&prefix, &new_prefix, ConflictPolicy::Error)
+            .await?;
+        if renamed == 0 {
+            return Err(TokenError::NotFound {
+                service: service.to_string(),
+                account: from.to_string(),
+            });
+        }
+
+        self.invalidate_introspection(service, from).await;
+        self.invalidate_introspection(service, to).await;
+        tracing::info!("Renamed {}/{} to {}/{}", service, from, service, to);
+        Ok(())
+    }
+
     /// Refresh an access token using a refresh token.
     #[cfg(feature = "oauth")]
     async fn refresh_access_token(
@@ -166,6 +772,22 @@ impl<S: SecretStore> DefaultTokenManager<S> {
             }
         })?;
 
+        // Refresh against the account's own instance when the provider has several
+        let instance_url = if provider.instance_url.is_some() {
+            self.get_credential(service, account, instance_url_credential_type())
+                .await?
+        } else {
+            None
+        };
+        let rebased;
+        let provider = match &instance_url {
+            Some(url) => {
+                rebased = provider.clone().with_instance_url(url.expose());
+                &rebased
+            }
+            None => provider,
+        };
+
         // Get client credentials
         let client_id = self
             .get_credential(service, account, CredentialType::ClientId)
@@ -189,7 +811,7 @@ impl<S: SecretStore> DefaultTokenManager<S> {
         // Execute refresh request
         let token_response = client
             .exchange_refresh_token(&RefreshToken::new(refresh_token.to_string()))
-            .request_async(async_http_client)
+            .request_async(|request| send_request(&self.http_client, request))
             .await
             .map_err(|e| TokenError::RefreshFailed {
                 message: format!("token refresh failed: {}", e),
@@ -216,6 +838,13 @@ impl<S: SecretStore> DefaultTokenManager<S> {
 
         let mut token_set = TokenSet::new(token);
 
+        if let Some(account_id) = &token_response.extra_fields().account_id {
+            token_set = token_set.with_subject(account_id);
+        }
+        if let Some(instance_url) = &token_response.extra_fields().instance_url {
+            token_set = token_set.with_instance_url(instance_url);
+        }
+
         // Add refresh token (use new one if provided, otherwise keep existing)
         if let Some(new_refresh_token) = token_response.refresh_token() {
             token_set = token_set.with_refresh_token(new_refresh_token.secret());
@@ -226,6 +855,60 @@ impl<S: SecretStore> DefaultTokenManager<S> {
         Ok(token_set)
     }
 
+    /// Request a new GitHub App installation token, if this account has
+    /// app credentials stored.
+    ///
+    /// Returns `Ok(None)` for accounts that are not GitHub App installations.
+    /// The caller is responsible for storing the new token set.
+    #[cfg(feature = "oauth")]
+    async fn reissue_installation_token(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+    ) -> Result<Option<TokenSet>, TokenError> {
+        let app_id = self
+            .get_credential(service, account, github_app::app_id_credential())
+            .await?;
+        let installation_id = self
+            .get_credential(service, account, github_app::installation_id_credential())
+            .await?;
+        let private_key = self
+            .get_credential(service, account, github_app::private_key_credential())
+            .await?;
+
+        let (Some(app_id), Some(installation_id), Some(private_key)) =
+            (app_id, installation_id, private_key)
+        else {
+            return Ok(None);
+        };
+
+        let mut flow = GitHubAppFlow::new(
+            app_id.expose(),
+            installation_id.expose(),
+            private_key.expose().as_bytes(),
+        )?
+        .with_http_client(self.http_client.clone());
+
+        if let Some(api_url) = self
+            .get_credential(service, account, github_app::api_url_credential())
+            .await?
+        {
+            flow = flow.with_api_url(api_url.expose());
+        }
+
+        Ok(Some(flow.request_installation_token().await?))
+    }
+
+    /// Reissue stub for non-oauth builds.
+    #[cfg(not(feature = "oauth"))]
+    async fn reissue_installation_token(
+        &self,
+        _service: &ServiceId,
+        _account: &AccountId,
+    ) -> Result<Option<TokenSet>, TokenError> {
+        Ok(None)
+    }
+
     /// Refresh an access token (stub for non-oauth builds).
     #[cfg(not(feature = "oauth"))]
     async fn refresh_access_token(
@@ -243,13 +926,28 @@ impl<S: SecretStore> DefaultTokenManager<S> {
     }
 }
 
-#[async_trait]
-impl<S: SecretStore + Send + Sync + 'static> TokenManager for DefaultTokenManager<S> {
+/// Record how long a refresh attempt took, successful or not.
+#[cfg(feature = "metrics")]
+fn record_refresh_duration(service: &ServiceId, elapsed: std::time::Duration) {
+    ::metrics::histogram!(TOKEN_REFRESH_DURATION_METRIC, "service" => service.to_string())
+        .record(elapsed.as_secs_f64() * 1000.0);
+}
+
+#[cfg(not(feature = "metrics"))]
+fn record_refresh_duration(_service: &ServiceId, _elapsed: std::time::Duration) {}
+
+#[async_trait]
+impl<S: SecretStore + Send + Sync + 'static> TokenManager for DefaultTokenManager<S> {
     async fn ensure_access_token(
         &self,
         service: &ServiceId,
         account: &AccountId,
     ) -> Result<Token, TokenError> {
+        // Version of the access token before reading it, so a refresh cannot
+        // replace a newer token stored meanwhile
+        let access_key = self.credential_key(service, account, CredentialType::AccessToken);
+        let version = self.store.get_version(&access_key).await?;
+
         // Try to get existing token set
         if let Some(token_set) = self.get_token_set(service, account).await? {
             // Check if the access token is still valid
@@ -262,48 +960,9 @@ impl<S: SecretStore + Send + Sync + 'static> TokenManager for DefaultTokenManage
                 return Ok(token_set.access_token);
             }
 
-            // Token is expired, try to refresh
-            if let Some(refresh_token) = &token_set.refresh_token {
-                tracing::info!(
-                    "Access token expired for {}/{}, attempting refresh",
-                    service,
-                    account
-                );
-
-                match self
-                    .refresh_access_token(service, account, refresh_token.expose())
-                    .await
-                {
-                    Ok(new_token_set) => {
-                        // Store the new token set
-                        self.store_token_set(service, account, new_token_set.clone())
-                            .await?;
-
-                        tracing::info!(
-                            "Successfully refreshed access token for {}/{}",
-                            service,
-                            account
-                        );
-
-                        return Ok(new_token_set.access_token);
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to refresh token for {}/{}: {}",
-                            service,
-                            account,
-                            e
-                        );
-                        return Err(TokenError::Expired {
-                            message: format!("token refresh failed: {}", e),
-                        });
-                    }
-                }
-            } else {
-                return Err(TokenError::Expired {
-                    message: "token expired and no refresh token available".to_string(),
-                });
-            }
+            return self
+                .renew_token_set(service, account, &token_set, version)
+                .await;
         }
 
         // No token found
@@ -325,6 +984,15 @@ impl<S: SecretStore + Send + Sync + 'static> TokenManager for DefaultTokenManage
 
         let access_token_str = match access_token_secret {
             Some(secret) => secret,
+            // The store drops an access token once it expires; the rest of
+            // the set can still renew it, so it reads as an empty token
+            None if self
+                .get_credential(service, account, CredentialType::TokenExpiry)
+                .await?
+                .is_some() =>
+            {
+                Secret::new("")
+            }
             None => return Ok(None),
         };
 
@@ -367,6 +1035,21 @@ impl<S: SecretStore + Send + Sync + 'static> TokenManager for DefaultTokenManage
             token_set = token_set.with_refresh_token(refresh.expose());
         }
 
+        // Try to get the provider-reported subject
+        if let Some(subject) = self
+            .get_credential(service, account, subject_credential_type())
+            .await?
+        {
+            token_set = token_set.with_subject(subject.expose());
+        }
+
+        if let Some(instance_url) = self
+            .get_credential(service, account, instance_url_credential_type())
+            .await?
+        {
+            token_set = token_set.with_instance_url(instance_url.expose());
+        }
+
         Ok(Some(token_set))
     }
 
@@ -376,53 +1059,9 @@ impl<S: SecretStore + Send + Sync + 'static> TokenManager for DefaultTokenManage
         account: &AccountId,
         token_set: TokenSet,
     ) -> Result<(), TokenError> {
-        // Store access token
-        self.store_credential(
-            service,
-            account,
-            CredentialType::AccessToken,
-            token_set.access_token.access_token.expose(),
-        )
-        .await?;
-
-        // Store expiry if available
-        if let Some(expires_at) = token_set.access_token.expires_at {
-            let timestamp = expires_at.timestamp().to_string();
-            self.store_credential(
-                service,
-                account,
-                CredentialType::TokenExpiry,
-                &timestamp,
-            )
-            .await?;
-        }
-
-        // Store scopes if available
-        if !token_set.access_token.scopes.is_empty() {
-            let scopes_str = token_set.access_token.scopes.join(",");
-            self.store_credential(
-                service,
-                account,
-                CredentialType::TokenScopes,
-                &scopes_str,
-            )
-            .await?;
-        }
-
-        // Store refresh token if available
-        if let Some(refresh_token) = &token_set.refresh_token {
-            self.store_credential(
-                service,
-                account,
-                CredentialType::RefreshToken,
-                refresh_token.expose(),
-            )
-            .await?;
-        }
-
-        tracing::debug!("Stored token set for {}/{}", service, account);
-
-        Ok(())
+        self.write_token_set(service, account, token_set, None)
+            .await
+            .map(|_| ())
     }
 
     async fn revoke_tokens(
@@ -434,11 +1073,17 @@ impl<S: SecretStore + Send + Sync + 'static> TokenManager for DefaultTokenManage
         let access_key = self.credential_key(service, account, CredentialType::AccessToken);
         let refresh_key = self.credential_key(service, account, CredentialType::RefreshToken);
         let expiry_key = self.credential_key(service, account, CredentialType::TokenExpiry);
+        let subject_key = self.credential_key(service, account, subject_credential_type());
+        let instance_url_key =
+            self.credential_key(service, account, instance_url_credential_type());
 
         // Delete all (ignore errors for missing keys)
         let _ = self.store.delete(&access_key).await;
         let _ = self.store.delete(&refresh_key).await;
         let _ = self.store.delete(&expiry_key).await;
+        let _ = self.store.delete(&subject_key).await;
+        let _ = self.store.delete(&instance_url_key).await;
+        self.invalidate_introspection(service, account).await;
 
         tracing::info!("Revoked tokens for {}/{}", service, account);
 
@@ -450,6 +1095,10 @@ impl<S: SecretStore + Send + Sync + 'static> TokenManager for DefaultTokenManage
         service: &ServiceId,
         account: &AccountId,
     ) -> Result<TokenInfo, TokenError> {
+        if let Some(info) = self.cached_introspection(service, account).await {
+            return Ok(info);
+        }
+
         // Get the current token set
         let token_set = self
             .get_token_set(service, account)
@@ -462,19 +1111,112 @@ impl<S: SecretStore + Send + Sync + 'static> TokenManager for DefaultTokenManage
         // Build token info from what we know
         let active = !self.is_token_expired(&token_set.access_token);
 
-        Ok(TokenInfo {
+        // Dropbox reports `account_id` in place of an OIDC `sub`
+        let dropbox_account_id = if service.as_str() == "dropbox" {
+            token_set.subject.clone()
+        } else {
+            None
+        };
+
+        // Stored credentials take precedence over the claims of a JWT
+        // access token, which are read without verifying the signature
+        #[cfg(feature = "oauth")]
+        let claims = token_set.access_token.try_decode_jwt().unwrap_or_default();
+        #[cfg(not(feature = "oauth"))]
+        let claims = crate::token::JwtClaims::default();
+        let client_id = self
+            .get_credential(service, account, CredentialType::ClientId)
+            .await?
+            .map(|client_id| client_id.expose().to_string())
+            .or_else(|| claims.client().map(str::to_string));
+
+        let info = TokenInfo {
             active,
-            subject: None,
-            client_id: None,
+            refreshable: token_set.refresh_token.is_some(),
+            subject: token_set.subject.clone().or_else(|| claims.sub.clone()),
+            client_id,
             scopes: token_set.access_token.scopes.clone(),
             expires_at: token_set.access_token.expires_at,
+            dropbox_account_id,
+            jti: claims.jti.clone(),
+            issuer: claims.iss.clone(),
+            username: claims.user_name().map(str::to_string),
+        };
+        if let Some(cache) = &self.introspection_cache {
+            cache.insert(service, account, info.clone()).await;
+        }
+        Ok(info)
+    }
+
+    async fn list_tokens(
+        &self,
+    ) -> Result<Vec<(ServiceId, AccountId, Result<TokenInfo, TokenError>)>, TokenError> {
+        let Some(account_store) = &self.account_store else {
+            return Ok(Vec::new());
+        };
+        let accounts = account_store.list_accounts(None).map_err(|e| {
+            TokenError::StorageError(StoreError::BackendError {
+                message: format!("failed to list accounts: {}", e),
+            })
+        })?;
+
+        let mut pending: FuturesUnordered<_> = accounts
+            .into_iter()
+            .enumerate()
+            .map(|(index, account)| async move {
+                let info = self.introspect_token(&account.service, &account.id).await;
+                (index, account, info)
+            })
+            .collect();
+
+        // Accounts without tokens are left out; other failures are listed
+        let mut tokens = Vec::new();
+        while let Some((index, account, info)) = pending.next().await {
+            if !matches!(info, Err(TokenError::NotFound { .. })) {
+                tokens.push((index, account.service, account.id, info));
+            }
+        }
+
+        // Introspections finish in any order; report accounts in store order
+        tokens.sort_by_key(|(index, ..)| *index);
+        let tokens = tokens
+            .into_iter()
+            .map(|(_, service, account, info)| (service, account, info))
+            .collect();
+        Ok(tokens)
+    }
+
+    /// Tokens that are still valid are returned as stored; only expired
+    /// ones go through [`ensure_access_token`](TokenManager::ensure_access_token)
+    /// and a refresh request.
+    async fn pre_warm_tokens(
+        &self,
+        accounts: &[(ServiceId, AccountId)],
+    ) -> Vec<((ServiceId, AccountId), Result<Token, TokenError>)> {
+        let warmed = pre_warm_each(accounts, |service, account| async move {
+            match self.get_token_set(service, account).await? {
+                Some(token_set) if !self.is_token_expired(&token_set.access_token) => {
+                    Ok(token_set.access_token)
+                }
+                _ => self.ensure_access_token(service, account).await,
+            }
         })
+        .await;
+
+        let failed = warmed.iter().filter(|(_, result)| result.is_err()).count();
+        tracing::debug!(
+            "Pre-warmed {} of {} tokens",
+            warmed.len() - failed,
+            warmed.len()
+        );
+        warmed
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::provider::ProviderConfig;
     use crate::store::MemoryStore;
 
     #[tokio::test]
@@ -523,6 +1265,48 @@ mod tests {
         assert!(retrieved.refresh_token.is_some());
     }
 
+    #[tokio::test]
+    async fn test_token_manager_access_token_ttl() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+        let access_key = manager.credential_key(&service, &account, CredentialType::AccessToken);
+
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        let token = Token::new("live").with_expiry(expires_at);
+        manager
+            .store_token_set(&service, &account, TokenSet::new(token))
+            .await
+            .unwrap();
+        let expiry = manager.store.get(&ttl_key(&access_key)).await.unwrap();
+        let expiry: i64 = expiry.unwrap().expose().parse().unwrap();
+        assert!((expires_at.timestamp() - expiry).abs() <= 1);
+
+        // An expired token is dropped, but the set still reads with its expiry
+        let expired_at = Utc::now() - chrono::Duration::hours(1);
+        let token = Token::new("expired").with_expiry(expired_at);
+        let token_set = TokenSet::new(token).with_refresh_token("refresh");
+        manager
+            .store_token_set(&service, &account, token_set)
+            .await
+            .unwrap();
+        let stored = manager.get_token_set(&service, &account).await.unwrap().unwrap();
+        assert_eq!(stored.access_token.access_token.expose(), "");
+        let stored_expiry = stored.access_token.expires_at.unwrap();
+        assert_eq!(stored_expiry.timestamp(), expired_at.timestamp());
+        assert_eq!(stored.refresh_token.unwrap().expose(), "refresh");
+        assert_eq!(manager.store.get(&access_key).await.unwrap(), None);
+
+        // A token without expiry drops the old TTL
+        manager
+            .store_token_set(&service, &account, TokenSet::new(Token::new("forever")))
+            .await
+            .unwrap();
+        assert!(!manager.store.exists(&ttl_key(&access_key)).await.unwrap());
+        let stored = manager.get_token_set(&service, &account).await.unwrap().unwrap();
+        assert_eq!(stored.access_token.access_token.expose(), "forever");
+    }
+
     #[tokio::test]
     async fn test_token_manager_ensure_valid_token() {
         let store = MemoryStore::new();
@@ -574,6 +1358,162 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_token_manager_rename_account() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("github");
+        let (old, new) = (AccountId::new("work"), AccountId::new("employer"));
+        let token_set = TokenSet::new(Token::new("token")).with_refresh_token("refresh");
+        manager
+            .store_token_set(&service, &old, token_set)
+            .await
+            .unwrap();
+        manager
+            .store_credential(&service, &old, CredentialType::ClientId, "client")
+            .await
+            .unwrap();
+
+        manager.rename_account(&service, &old, &new).await.unwrap();
+
+        let old_tokens = manager.get_token_set(&service, &old).await.unwrap();
+        assert!(old_tokens.is_none());
+        let renamed = manager
+            .get_token_set(&service, &new)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(renamed.access_token.access_token.expose(), "token");
+        assert!(renamed.refresh_token.is_some());
+        let client_id = manager
+            .get_credential(&service, &new, CredentialType::ClientId)
+            .await
+            .unwrap();
+        assert_eq!(client_id, Some(Secret::new("client")));
+
+        let result = manager.rename_account(&service, &old, &new).await;
+        assert!(matches!(result, Err(TokenError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_rename_account_keeps_existing_target() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("github");
+        let (old, new) = (AccountId::new("work"), AccountId::new("personal"));
+        let old_tokens = TokenSet::new(Token::new("old")).with_refresh_token("refresh");
+        manager
+            .store_token_set(&service, &old, old_tokens)
+            .await
+            .unwrap();
+        manager
+            .store_token_set(&service, &new, TokenSet::new(Token::new("new")))
+            .await
+            .unwrap();
+
+        let result = manager.rename_account(&service, &old, &new).await;
+        assert!(matches!(
+            result,
+            Err(TokenError::StorageError(StoreError::AlreadyExists { .. }))
+        ));
+
+        // Keys copied before the conflict are removed again
+        let kept = manager
+            .get_token_set(&service, &new)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(kept.access_token.access_token.expose(), "new");
+        assert!(kept.refresh_token.is_none());
+        let old_tokens = manager
+            .get_token_set(&service, &old)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(old_tokens.access_token.access_token.expose(), "old");
+    }
+
+    /// Memory store shared by several managers.
+    struct SharedStore(Arc<MemoryStore>);
+
+    #[async_trait]
+    impl SecretStore for SharedStore {
+        async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+            self.0.get(key).await
+        }
+
+        async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError> {
+            self.0.set(key, secret).await
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), StoreError> {
+            self.0.delete(key).await
+        }
+
+        async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+            self.0.list_keys(prefix).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_key_prefixes_are_isolated() {
+        let store = Arc::new(MemoryStore::new());
+        let default = DefaultTokenManager::new(SharedStore(store.clone()), ProviderRegistry::new());
+        let myapp = DefaultTokenManager::new(SharedStore(store.clone()), ProviderRegistry::new())
+            .with_key_prefix("myapp");
+        assert_eq!(default.key_prefix(), DEFAULT_KEY_PREFIX);
+        assert_eq!(myapp.key_prefix(), "myapp");
+
+        let service = ServiceId::new("github");
+        let account = AccountId::new("work");
+        for (manager, token) in [(&default, "sigilforge-token"), (&myapp, "myapp-token")] {
+            manager
+                .store_token_set(&service, &account, TokenSet::new(Token::new(token)))
+                .await
+                .unwrap();
+        }
+        myapp
+            .store_credential(&service, &account, CredentialType::ClientId, "client")
+            .await
+            .unwrap();
+
+        let myapp_keys = store.list_keys("myapp/").await.unwrap();
+        assert!(myapp_keys.contains(&"myapp/github/work/access_token".to_string()));
+        assert!(myapp_keys.contains(&"myapp/github/work/client_id".to_string()));
+        let default_client_id = default
+            .get_credential(&service, &account, CredentialType::ClientId)
+            .await
+            .unwrap();
+        assert!(default_client_id.is_none());
+
+        // Each manager only sees and changes its own tokens
+        let token = myapp.ensure_access_token(&service, &account).await.unwrap();
+        assert_eq!(token.access_token.expose(), "myapp-token");
+        myapp.revoke_tokens(&service, &account).await.unwrap();
+        let token = default
+            .ensure_access_token(&service, &account)
+            .await
+            .unwrap();
+        assert_eq!(token.access_token.expose(), "sigilforge-token");
+
+        let renamed = AccountId::new("employer");
+        default
+            .rename_account(&service, &account, &renamed)
+            .await
+            .unwrap();
+        let default_keys = store.list_keys("sigilforge/github/work/").await.unwrap();
+        assert!(default_keys.is_empty());
+        let myapp_keys = store.list_keys("myapp/github/work/").await.unwrap();
+        assert!(myapp_keys.contains(&"myapp/github/work/client_id".to_string()));
+        assert!(!myapp_keys.contains(&"myapp/github/work/access_token".to_string()));
+    }
+
+    #[test]
+    fn test_is_valid_key_prefix() {
+        assert!(is_valid_key_prefix(DEFAULT_KEY_PREFIX));
+        assert!(is_valid_key_prefix("my-app"));
+        assert!(!is_valid_key_prefix(""));
+        assert!(!is_valid_key_prefix("my/app"));
+    }
+
     #[tokio::test]
     async fn test_token_manager_introspect() {
         let store = MemoryStore::new();
@@ -599,4 +1539,1004 @@ mod tests {
         assert!(info.active);
         assert_eq!(info.scopes, vec!["read", "write"]);
     }
+
+    /// A JWT signed with a key the manager does not know.
+    #[cfg(feature = "oauth")]
+    fn jwt_access_token(claims: serde_json::Value) -> Token {
+        let key = jsonwebtoken::EncodingKey::from_secret(b"issuer-secret");
+        let jwt = jsonwebtoken::encode(&jsonwebtoken::Header::default(), &claims, &key).unwrap();
+        Token::new(jwt).with_expiry(Utc::now() + Duration::hours(1))
+    }
+
+    #[cfg(feature = "oauth")]
+    #[tokio::test]
+    async fn test_token_manager_introspect_jwt_claims() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("keycloak");
+        let account = AccountId::new("work");
+        let token = jwt_access_token(serde_json::json!({
+            "iss": "https://sso.example.com/realms/main",
+            "sub": "f1b2c3d4",
+            "azp": "sigilforge",
+            "jti": "8d1e5a70",
+            "preferred_username": "ada",
+        }));
+        manager
+            .store_token_set(&service, &account, TokenSet::new(token))
+            .await
+            .unwrap();
+
+        let info = manager.introspect_token(&service, &account).await.unwrap();
+        assert!(info.active);
+        assert_eq!(info.subject.as_deref(), Some("f1b2c3d4"));
+        assert_eq!(info.client_id.as_deref(), Some("sigilforge"));
+        assert_eq!(info.jti.as_deref(), Some("8d1e5a70"));
+        assert_eq!(
+            info.issuer.as_deref(),
+            Some("https://sso.example.com/realms/main")
+        );
+        assert_eq!(info.username.as_deref(), Some("ada"));
+    }
+
+    #[cfg(feature = "oauth")]
+    #[tokio::test]
+    async fn test_token_manager_introspect_prefers_stored_credentials() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("keycloak");
+        let account = AccountId::new("work");
+        manager
+            .store_credential(&service, &account, CredentialType::ClientId, "stored-client")
+            .await
+            .unwrap();
+        let token = jwt_access_token(serde_json::json!({
+            "sub": "jwt-subject",
+            "client_id": "jwt-client",
+        }));
+        manager
+            .store_token_set(
+                &service,
+                &account,
+                TokenSet::new(token).with_subject("stored-subject"),
+            )
+            .await
+            .unwrap();
+
+        let info = manager.introspect_token(&service, &account).await.unwrap();
+        assert_eq!(info.subject.as_deref(), Some("stored-subject"));
+        assert_eq!(info.client_id.as_deref(), Some("stored-client"));
+        assert!(info.jti.is_none() && info.issuer.is_none() && info.username.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_introspect_opaque_token() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("github");
+        let account = AccountId::new("work");
+        manager
+            .store_token_set(&service, &account, TokenSet::new(Token::new("gho_opaque")))
+            .await
+            .unwrap();
+
+        let info = manager.introspect_token(&service, &account).await.unwrap();
+        assert!(info.subject.is_none() && info.client_id.is_none());
+        assert!(info.jti.is_none() && info.issuer.is_none() && info.username.is_none());
+    }
+
+    /// A manager caching introspection for a minute, holding a token for
+    /// `test/test` with the given scopes.
+    async fn manager_with_introspection_cache(
+        scopes: &[&str],
+    ) -> (DefaultTokenManager<MemoryStore>, ServiceId, AccountId) {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new())
+            .with_introspection_cache(std::time::Duration::from_secs(60));
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+        store_scoped_token(&manager, &service, &account, scopes).await;
+        (manager, service, account)
+    }
+
+    async fn store_scoped_token(
+        manager: &DefaultTokenManager<MemoryStore>,
+        service: &ServiceId,
+        account: &AccountId,
+        scopes: &[&str],
+    ) {
+        let token = Token::new("test-token")
+            .with_expiry(Utc::now() + chrono::Duration::hours(1))
+            .with_scopes(scopes.iter().map(|s| s.to_string()).collect());
+        manager
+            .store_token_set(service, account, TokenSet::new(token))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_token_manager_introspection_cache_hit() {
+        let (manager, service, account) = manager_with_introspection_cache(&["read"]).await;
+        let info = manager.introspect_token(&service, &account).await.unwrap();
+        assert_eq!(info.scopes, vec!["read"]);
+
+        // Removing the token behind the manager's back goes unnoticed
+        let access_key = manager.credential_key(&service, &account, CredentialType::AccessToken);
+        manager.store.delete(&access_key).await.unwrap();
+        let cached = manager.introspect_token(&service, &account).await.unwrap();
+        assert_eq!(cached.scopes, vec!["read"]);
+        assert_eq!(cached.expires_at, info.expires_at);
+
+        // Other accounts are looked up as usual
+        let other = AccountId::new("other");
+        let err = manager
+            .introspect_token(&service, &other)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TokenError::NotFound { .. }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_token_manager_introspection_cache_invalidated_on_store() {
+        let (manager, service, account) = manager_with_introspection_cache(&["read"]).await;
+        manager.introspect_token(&service, &account).await.unwrap();
+
+        store_scoped_token(&manager, &service, &account, &["read", "write"]).await;
+        let info = manager.introspect_token(&service, &account).await.unwrap();
+        assert_eq!(info.scopes, vec!["read", "write"]);
+
+        manager.revoke_tokens(&service, &account).await.unwrap();
+        let err = manager
+            .introspect_token(&service, &account)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TokenError::NotFound { .. }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_token_manager_introspection_cache_expires() {
+        let (manager, service, account) = manager_with_introspection_cache(&["read"]).await;
+        manager.introspect_token(&service, &account).await.unwrap();
+
+        let access_key = manager.credential_key(&service, &account, CredentialType::AccessToken);
+        manager.store.delete(&access_key).await.unwrap();
+
+        tokio::time::advance(std::time::Duration::from_secs(59)).await;
+        assert!(manager.introspect_token(&service, &account).await.is_ok());
+
+        tokio::time::advance(std::time::Duration::from_secs(1)).await;
+        let err = manager
+            .introspect_token(&service, &account)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TokenError::NotFound { .. }));
+    }
+
+    #[cfg(feature = "versioned-store")]
+    #[tokio::test]
+    async fn test_token_manager_stale_refresh_keeps_newer_token() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+        let access_key = manager.credential_key(&service, &account, CredentialType::AccessToken);
+
+        let expired = Token::new("expired").with_expiry(Utc::now() - chrono::Duration::hours(1));
+        manager
+            .store_token_set(&service, &account, TokenSet::new(expired))
+            .await
+            .unwrap();
+        let read_version = manager.store.get_version(&access_key).await.unwrap();
+
+        // Another refresh stores its result first
+        let newer = TokenSet::new(Token::new("newer")).with_refresh_token("refresh-2");
+        manager
+            .store_token_set(&service, &account, newer)
+            .await
+            .unwrap();
+
+        let stale = TokenSet::new(Token::new("stale")).with_refresh_token("refresh-1");
+        let token = manager
+            .store_refreshed_token_set(&service, &account, stale, read_version)
+            .await
+            .unwrap();
+        assert_eq!(token.access_token.expose(), "newer");
+
+        let stored = manager.get_token_set(&service, &account).await.unwrap().unwrap();
+        assert_eq!(stored.access_token.access_token.expose(), "newer");
+        assert_eq!(stored.refresh_token.unwrap().expose(), "refresh-2");
+    }
+
+    #[cfg(feature = "versioned-store")]
+    #[tokio::test]
+    async fn test_token_manager_refresh_of_evicted_token_checks_version() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+        let access_key = manager.credential_key(&service, &account, CredentialType::AccessToken);
+
+        let expired = Token::new("expired").with_expiry(Utc::now() - chrono::Duration::hours(1));
+        let token_set = TokenSet::new(expired).with_refresh_token("refresh");
+        manager
+            .store_token_set(&service, &account, token_set)
+            .await
+            .unwrap();
+        let read_version = manager.store.get_version(&access_key).await.unwrap();
+        assert!(read_version.is_some());
+
+        // Reading drops the expired token, which still matches the version
+        let stored = manager.get_token_set(&service, &account).await.unwrap().unwrap();
+        assert_eq!(stored.access_token.access_token.expose(), "");
+        let refreshed = TokenSet::new(Token::new("refreshed"));
+        let token = manager
+            .store_refreshed_token_set(&service, &account, refreshed, read_version)
+            .await
+            .unwrap();
+        assert_eq!(token.access_token.expose(), "refreshed");
+
+        // A token stored by another writer since the read is kept
+        let stale = TokenSet::new(Token::new("stale"));
+        let token = manager
+            .store_refreshed_token_set(&service, &account, stale, read_version)
+            .await
+            .unwrap();
+        assert_eq!(token.access_token.expose(), "refreshed");
+    }
+
+    #[cfg(feature = "versioned-store")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_token_manager_concurrent_refreshes_store_one_result() {
+        let manager = Arc::new(DefaultTokenManager::new(
+            MemoryStore::new(),
+            ProviderRegistry::new(),
+        ));
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+        manager
+            .store_token_set(&service, &account, TokenSet::new(Token::new("expired")))
+            .await
+            .unwrap();
+        let access_key = manager.credential_key(&service, &account, CredentialType::AccessToken);
+        let read_version = manager.store.get_version(&access_key).await.unwrap();
+
+        // Every refresh started from the same token, so one result is kept
+        // and the rest all see it
+        let mut tasks = tokio::task::JoinSet::new();
+        for i in 0..10 {
+            let manager = manager.clone();
+            let (service, account) = (service.clone(), account.clone());
+            tasks.spawn(async move {
+                let refreshed = TokenSet::new(Token::new(format!("refreshed-{}", i)))
+                    .with_refresh_token(format!("refresh-{}", i));
+                tokio::task::yield_now().await;
+                manager
+                    .store_refreshed_token_set(&service, &account, refreshed, read_version)
+                    .await
+                    .unwrap()
+            });
+        }
+
+        let mut returned = Vec::new();
+        while let Some(token) = tasks.join_next().await {
+            returned.push(token.unwrap().access_token.expose().to_string());
+        }
+
+        let stored = manager.get_token_set(&service, &account).await.unwrap().unwrap();
+        let winner = stored.access_token.access_token.expose();
+        assert!(returned.iter().all(|token| token == winner), "{:?}", returned);
+        let suffix = winner.trim_start_matches("refreshed-");
+        assert_eq!(stored.refresh_token.unwrap().expose(), format!("refresh-{}", suffix));
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_list_tokens_requires_account_store() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+        manager
+            .store_token_set(&service, &account, TokenSet::new(Token::new("test-token")))
+            .await
+            .unwrap();
+
+        assert!(manager.list_tokens().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_list_tokens() {
+        use crate::model::Account;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let accounts = AccountStore::load_from_path(dir.path().join("accounts.json")).unwrap();
+        let accounts = Arc::new(accounts);
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new())
+            .with_account_store(Arc::clone(&accounts));
+
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        let expired_at = Utc::now() - chrono::Duration::hours(1);
+        let known = [
+            ("spotify", "personal", Some(expires_at), vec!["streaming"]),
+            ("github", "work", None, vec!["repo", "gist"]),
+            ("github", "oss", Some(expired_at), vec![]),
+        ];
+        for (service, account, expiry, scopes) in &known {
+            let (service, account) = (ServiceId::new(*service), AccountId::new(*account));
+            let scopes: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+            let configured = Account::new(service.clone(), account.clone(), scopes.clone());
+            accounts.add_account(configured).unwrap();
+
+            let mut token = Token::new(format!("{}-token", account)).with_scopes(scopes);
+            token.expires_at = *expiry;
+            let mut token_set = TokenSet::new(token);
+            if account.as_str() == "oss" {
+                token_set = token_set.with_refresh_token("oss-refresh");
+            }
+            manager
+                .store_token_set(&service, &account, token_set)
+                .await
+                .unwrap();
+        }
+
+        // An account that was never authorized has no token to list
+        accounts
+            .add_account(Account::new(
+                ServiceId::new("google"),
+                AccountId::new("pending"),
+                vec![],
+            ))
+            .unwrap();
+
+        let tokens = manager.list_tokens().await.unwrap();
+        assert_eq!(tokens.len(), known.len());
+        let mut infos = Vec::new();
+        for ((service, account, info), (want_service, want_account, expiry, scopes)) in
+            tokens.iter().zip(&known)
+        {
+            let info = info.as_ref().unwrap();
+            assert_eq!(service.as_str(), *want_service);
+            assert_eq!(account.as_str(), *want_account);
+            assert_eq!(info.expires_at, *expiry);
+            assert_eq!(info.scopes, *scopes);
+            infos.push(info);
+        }
+
+        let active: Vec<bool> = infos.iter().map(|info| info.active).collect();
+        assert_eq!(active, [true, true, false]);
+        let refreshable: Vec<bool> = infos.iter().map(|info| info.refreshable).collect();
+        assert_eq!(refreshable, [false, false, true]);
+    }
+
+    /// Memory store whose reads fail for the `broken` account once `broken`
+    /// is set.
+    struct BrokenAccountStore {
+        inner: MemoryStore,
+        broken: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait]
+    impl SecretStore for BrokenAccountStore {
+        async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+            let broken = self.broken.load(std::sync::atomic::Ordering::SeqCst);
+            if broken && key.contains("/broken/") {
+                return Err(StoreError::BackendError {
+                    message: "keyring locked".to_string(),
+                });
+            }
+            self.inner.get(key).await
+        }
+
+        async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError> {
+            self.inner.set(key, secret).await
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), StoreError> {
+            self.inner.delete(key).await
+        }
+
+        async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+            self.inner.list_keys(prefix).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_list_tokens_reports_errors_per_account() {
+        use crate::model::Account;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let accounts = AccountStore::load_from_path(dir.path().join("accounts.json")).unwrap();
+        let accounts = Arc::new(accounts);
+        let store = BrokenAccountStore {
+            inner: MemoryStore::new(),
+            broken: std::sync::atomic::AtomicBool::new(false),
+        };
+        let manager = DefaultTokenManager::new(store, ProviderRegistry::new())
+            .with_account_store(Arc::clone(&accounts));
+
+        let service = ServiceId::new("github");
+        for name in ["broken", "work"] {
+            let account = AccountId::new(name);
+            accounts
+                .add_account(Account::new(service.clone(), account.clone(), vec![]))
+                .unwrap();
+            manager
+                .store_token_set(&service, &account, TokenSet::new(Token::new("token")))
+                .await
+                .unwrap();
+        }
+        manager
+            .store
+            .broken
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+
+        let tokens = manager.list_tokens().await.unwrap();
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].1.as_str(), "broken");
+        assert!(matches!(tokens[0].2, Err(TokenError::StorageError(_))));
+        assert_eq!(tokens[1].1.as_str(), "work");
+        assert!(tokens[1].2.as_ref().unwrap().active);
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_records_refresh_token_expiry() {
+        use crate::model::Account;
+        use crate::provider::BOX_REFRESH_TOKEN_LIFETIME_SECS;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let accounts = AccountStore::load_from_path(dir.path().join("accounts.json")).unwrap();
+        let accounts = Arc::new(accounts);
+        let manager =
+            DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::with_defaults())
+                .with_account_store(Arc::clone(&accounts));
+
+        let service = ServiceId::new("box");
+        let account = AccountId::new("work");
+        accounts
+            .add_account(Account::new(service.clone(), account.clone(), vec![]))
+            .unwrap();
+
+        // Without a new refresh token the recorded expiry is left alone
+        let token_set = TokenSet::new(Token::new("access-only"));
+        manager
+            .store_token_set(&service, &account, token_set)
+            .await
+            .unwrap();
+        let stored = accounts.get_account(&service, &account).unwrap().unwrap();
+        assert_eq!(stored.token_refresh_expiry, None);
+
+        let token_set = TokenSet::new(Token::new("access")).with_refresh_token("refresh");
+        let lifetime = chrono::Duration::seconds(BOX_REFRESH_TOKEN_LIFETIME_SECS as i64);
+        let expected = token_set.refreshed_at + lifetime;
+        manager
+            .store_token_set(&service, &account, token_set)
+            .await
+            .unwrap();
+        let stored = accounts.get_account(&service, &account).unwrap().unwrap();
+        assert_eq!(stored.token_refresh_expiry, Some(expected));
+
+        // Accounts not in the store yet are skipped
+        let token_set = TokenSet::new(Token::new("access")).with_refresh_token("refresh");
+        manager
+            .store_token_set(&service, &AccountId::new("new"), token_set)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_pre_warm_tokens() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let github = (ServiceId::new("github"), AccountId::new("work"));
+        let spotify = (ServiceId::new("spotify"), AccountId::new("personal"));
+        let valid = Token::new("valid").with_expiry(Utc::now() + chrono::Duration::hours(1));
+        manager
+            .store_token_set(&github.0, &github.1, TokenSet::new(valid))
+            .await
+            .unwrap();
+
+        let warmed = manager
+            .pre_warm_tokens(&[spotify.clone(), github.clone()])
+            .await;
+        assert_eq!(warmed.len(), 2);
+        assert_eq!(warmed[0].0, spotify);
+        assert!(matches!(warmed[0].1, Err(TokenError::NotFound { .. })));
+        assert_eq!(warmed[1].0, github);
+        let token = warmed[1].1.as_ref().unwrap();
+        assert_eq!(token.access_token.expose(), "valid");
+    }
+
+    /// Memory store whose access token reads only return once `barrier` is
+    /// full, which needs that many reads in flight at once.
+    struct BarrierStore {
+        inner: MemoryStore,
+        barrier: tokio::sync::Barrier,
+    }
+
+    #[async_trait]
+    impl SecretStore for BarrierStore {
+        async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+            if key.ends_with("/access_token") {
+                self.barrier.wait().await;
+            }
+            self.inner.get(key).await
+        }
+
+        async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError> {
+            self.inner.set(key, secret).await
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), StoreError> {
+            self.inner.delete(key).await
+        }
+
+        async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+            self.inner.list_keys(prefix).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_pre_warm_tokens_concurrently() {
+        let accounts: Vec<(ServiceId, AccountId)> = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| (ServiceId::new("github"), AccountId::new(name)))
+            .collect();
+        let data = accounts
+            .iter()
+            .map(|(service, account)| {
+                let key = format!("sigilforge/{}/{}/access_token", service, account);
+                (key, Secret::new(format!("token-{}", account)))
+            })
+            .collect();
+        let store = BarrierStore {
+            inner: MemoryStore::with_data(data),
+            barrier: tokio::sync::Barrier::new(accounts.len()),
+        };
+        let manager = DefaultTokenManager::new(store, ProviderRegistry::new());
+
+        // Reading the tokens one after another would wait on the barrier forever
+        let warmed = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            manager.pre_warm_tokens(&accounts),
+        )
+        .await
+        .expect("tokens were not pre-warmed concurrently");
+        for ((_, account), result) in &warmed {
+            let token = result.as_ref().unwrap();
+            assert_eq!(token.access_token.expose(), format!("token-{}", account));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_refresh_token_stored_only_when_present() {
+        let store = MemoryStore::new();
+        let registry = ProviderRegistry::with_defaults();
+        let manager = DefaultTokenManager::new(store, registry);
+
+        let service = ServiceId::new("twitter");
+        let without = AccountId::new("no-offline");
+        let with = AccountId::new("offline");
+
+        // Twitter omits the refresh token unless `offline.access` was granted
+        let token = Token::new("access-only").with_scopes(vec!["tweet.read".to_string()]);
+        manager
+            .store_token_set(&service, &without, TokenSet::new(token))
+            .await
+            .unwrap();
+
+        let token = Token::new("access-with-refresh")
+            .with_scopes(vec!["tweet.read".to_string(), "offline.access".to_string()]);
+        manager
+            .store_token_set(
+                &service,
+                &with,
+                TokenSet::new(token).with_refresh_token("refresh"),
+            )
+            .await
+            .unwrap();
+
+        let refresh_key = manager.credential_key(&service, &without, CredentialType::RefreshToken);
+        assert!(!manager.store.exists(&refresh_key).await.unwrap());
+        let retrieved = manager.get_token_set(&service, &without).await.unwrap().unwrap();
+        assert!(retrieved.refresh_token.is_none());
+
+        let refresh_key = manager.credential_key(&service, &with, CredentialType::RefreshToken);
+        assert!(manager.store.exists(&refresh_key).await.unwrap());
+        let retrieved = manager.get_token_set(&service, &with).await.unwrap().unwrap();
+        assert_eq!(retrieved.refresh_token.unwrap().expose(), "refresh");
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_dropbox_subject_roundtrip() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::with_defaults());
+
+        let service = ServiceId::new("dropbox");
+        let account = AccountId::new("personal");
+
+        let token = Token::new("sl.access").with_expiry(Utc::now() + chrono::Duration::hours(4));
+        let token_set = TokenSet::new(token)
+            .with_refresh_token("refresh")
+            .with_subject("dbid:abc123");
+
+        manager
+            .store_token_set(&service, &account, token_set)
+            .await
+            .unwrap();
+
+        let info = manager.introspect_token(&service, &account).await.unwrap();
+        assert_eq!(info.subject.as_deref(), Some("dbid:abc123"));
+        assert_eq!(info.dropbox_account_id.as_deref(), Some("dbid:abc123"));
+
+        // Non-Dropbox services never populate the Dropbox-specific field
+        let other = ServiceId::new("github");
+        let token_set = TokenSet::new(Token::new("gh")).with_subject("octocat");
+        manager.store_token_set(&other, &account, token_set).await.unwrap();
+        let info = manager.introspect_token(&other, &account).await.unwrap();
+        assert_eq!(info.subject.as_deref(), Some("octocat"));
+        assert!(info.dropbox_account_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_reissues_github_app_token_on_expiry() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/app/installations/67890/access_tokens"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "token": "ghs_fresh",
+                "expires_at": (Utc::now() + Duration::hours(1)).to_rfc3339(),
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new(github_app::GITHUB_APP_SERVICE);
+        let account = AccountId::new("raibid-labs");
+
+        let credentials = [
+            (github_app::app_id_credential(), "12345".to_string()),
+            (github_app::installation_id_credential(), "67890".to_string()),
+            (
+                github_app::private_key_credential(),
+                include_str!("../tests/fixtures/github_app_test_key.pem").to_string(),
+            ),
+            (github_app::api_url_credential(), server.uri()),
+        ];
+        for (cred_type, value) in credentials {
+            manager
+                .store_credential(&service, &account, cred_type, &value)
+                .await
+                .unwrap();
+        }
+
+        // An expired installation token with no refresh token
+        let expired = Token::new("ghs_stale").with_expiry(Utc::now() - Duration::minutes(1));
+        manager
+            .store_token_set(&service, &account, TokenSet::new(expired))
+            .await
+            .unwrap();
+
+        let token = manager.ensure_access_token(&service, &account).await.unwrap();
+        assert_eq!(token.access_token.expose(), "ghs_fresh");
+
+        // The new token is persisted, so a second call needs no request
+        let token = manager.ensure_access_token(&service, &account).await.unwrap();
+        assert_eq!(token.access_token.expose(), "ghs_fresh");
+    }
+
+    /// A manager whose "test" provider refreshes tokens against `server`,
+    /// with a valid access token and a refresh token stored for test/test.
+    async fn manager_with_refreshable_token(
+        server: &wiremock::MockServer,
+    ) -> DefaultTokenManager<MemoryStore> {
+        let mut registry = ProviderRegistry::new();
+        registry
+            .register(
+                ProviderConfig::new("test", "Test")
+                    .with_auth_url(format!("{}/authorize", server.uri()))
+                    .with_token_url(format!("{}/token", server.uri())),
+            )
+            .unwrap();
+        let manager = DefaultTokenManager::new(MemoryStore::new(), registry);
+
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+        manager
+            .store_credential(&service, &account, CredentialType::ClientId, "client-id")
+            .await
+            .unwrap();
+        let tokens =
+            TokenSet::new(Token::new("cached").with_expiry(Utc::now() + Duration::hours(1)))
+                .with_refresh_token("refresh-me");
+        manager
+            .store_token_set(&service, &account, tokens)
+            .await
+            .unwrap();
+        manager
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_force_refresh_replaces_valid_token() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("refresh_token=refresh-me"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "fresh",
+                "token_type": "bearer",
+                "expires_in": 7200,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let manager = manager_with_refreshable_token(&server).await;
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+
+        // The cached token is still valid, so ensure does not refresh
+        let token = manager
+            .ensure_access_token(&service, &account)
+            .await
+            .unwrap();
+        assert_eq!(token.access_token.expose(), "cached");
+
+        let token = manager.force_refresh(&service, &account).await.unwrap();
+        assert_eq!(token.access_token.expose(), "fresh");
+        assert!(token.expires_at.unwrap() > Utc::now() + Duration::hours(1));
+
+        // The new token is stored and the refresh token kept
+        let stored = manager
+            .get_token_set(&service, &account)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.access_token.access_token.expose(), "fresh");
+        assert_eq!(stored.refresh_token.unwrap().expose(), "refresh-me");
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_force_refresh_failure_keeps_token() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": "invalid_grant",
+            })))
+            .mount(&server)
+            .await;
+
+        let manager = manager_with_refreshable_token(&server).await;
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+
+        let err = manager.force_refresh(&service, &account).await.unwrap_err();
+        assert!(matches!(err, TokenError::Expired { .. }), "{err}");
+
+        let token = manager
+            .ensure_access_token(&service, &account)
+            .await
+            .unwrap();
+        assert_eq!(token.access_token.expose(), "cached");
+    }
+
+    #[tokio::test]
+    async fn test_token_manager_force_refresh_needs_refresh_token() {
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+
+        let err = manager.force_refresh(&service, &account).await.unwrap_err();
+        assert!(matches!(err, TokenError::NotFound { .. }));
+
+        manager
+            .store_token_set(&service, &account, TokenSet::new(Token::new("no-refresh")))
+            .await
+            .unwrap();
+        let err = manager.force_refresh(&service, &account).await.unwrap_err();
+        assert!(matches!(err, TokenError::Expired { .. }));
+    }
+
+    #[cfg(feature = "oauth")]
+    #[tokio::test]
+    async fn test_token_manager_revoke_at_provider() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/revoke"))
+            .and(body_string_contains("token=refresh-me"))
+            .and(body_string_contains("token_type_hint=refresh_token"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut registry = ProviderRegistry::new();
+        registry
+            .register(
+                ProviderConfig::new("test", "Test")
+                    .with_auth_url(format!("{}/authorize", server.uri()))
+                    .with_token_url(format!("{}/token", server.uri()))
+                    .with_revoke_url(format!("{}/revoke", server.uri())),
+            )
+            .unwrap();
+        let manager = DefaultTokenManager::new(MemoryStore::new(), registry);
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+
+        // Nothing to revoke yet
+        assert!(!manager
+            .revoke_at_provider(&service, &account)
+            .await
+            .unwrap());
+
+        manager
+            .store_credential(&service, &account, CredentialType::ClientId, "client-id")
+            .await
+            .unwrap();
+        let tokens = TokenSet::new(Token::new("cached")).with_refresh_token("refresh-me");
+        manager
+            .store_token_set(&service, &account, tokens)
+            .await
+            .unwrap();
+        assert!(manager
+            .revoke_at_provider(&service, &account)
+            .await
+            .unwrap());
+
+        // Revoking at the provider leaves the stored tokens alone
+        assert!(manager
+            .get_token_set(&service, &account)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[cfg(feature = "oauth")]
+    #[tokio::test]
+    async fn test_token_manager_revoke_at_provider_without_revoke_url() {
+        let server = wiremock::MockServer::start().await;
+        let manager = manager_with_refreshable_token(&server).await;
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+
+        assert!(!manager
+            .revoke_at_provider(&service, &account)
+            .await
+            .unwrap());
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    /// A flow against `server` and the detached authorization it started.
+    #[cfg(feature = "oauth")]
+    fn detached_flow(server: &wiremock::MockServer) -> (PkceFlow, PendingAuthorization) {
+        use crate::oauth::pkce::RedirectConfig;
+
+        let config = ProviderConfig::new("test", "Test")
+            .with_auth_url(format!("{}/authorize", server.uri()))
+            .with_token_url(format!("{}/token", server.uri()))
+            .with_pkce(true);
+        let flow = PkceFlow::new(
+            config,
+            "client-id".to_string(),
+            None,
+            RedirectConfig::localhost(8484),
+        )
+        .unwrap();
+        let (_url, pending) = flow.detached_authorization_url(vec![]).unwrap();
+        (flow, pending)
+    }
+
+    #[cfg(feature = "oauth")]
+    #[tokio::test]
+    async fn test_token_manager_pkce_verifier_is_single_use() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let (flow, pending) = detached_flow(&server);
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains(format!(
+                "code_verifier={}",
+                pending.code_verifier.expose()
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "access",
+                "token_type": "bearer"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+        manager
+            .save_pending_authorization(&service, &account, &pending)
+            .await
+            .unwrap();
+
+        let token_set = manager
+            .exchange_pending_code(&flow, &service, &account, "auth-code")
+            .await
+            .unwrap();
+        assert_eq!(token_set.access_token.access_token.expose(), "access");
+        let key = manager.credential_key(&service, &account, pkce_verifier_credential_type());
+        assert!(manager.store.get(&key).await.unwrap().is_none());
+
+        // Neither a second exchange nor a fresh flow can reuse the verifier
+        let err = manager
+            .exchange_pending_code(&flow, &service, &account, "auth-code")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TokenError::OAuthError { .. }));
+        let (fresh_flow, _) = detached_flow(&server);
+        assert!(
+            manager
+                .exchange_pending_code(&fresh_flow, &service, &account, "auth-code")
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "oauth")]
+    #[tokio::test]
+    async fn test_token_manager_pkce_verifier_kept_after_failed_exchange() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let (flow, pending) = detached_flow(&server);
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("code=mistyped"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": "invalid_grant"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("code=auth-code"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "access",
+                "token_type": "bearer"
+            })))
+            .mount(&server)
+            .await;
+
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let service = ServiceId::new("test");
+        let account = AccountId::new("test");
+        manager
+            .save_pending_authorization(&service, &account, &pending)
+            .await
+            .unwrap();
+
+        assert!(
+            manager
+                .exchange_pending_code(&flow, &service, &account, "mistyped")
+                .await
+                .is_err()
+        );
+        let token_set = manager
+            .exchange_pending_code(&flow, &service, &account, "auth-code")
+            .await
+            .unwrap();
+        assert_eq!(token_set.access_token.access_token.expose(), "access");
+
+        // Without a saved authorization there is nothing to exchange
+        manager
+            .mark_verifier_used(&service, &account)
+            .await
+            .unwrap();
+        let err = manager
+            .exchange_pending_code(&flow, &service, &account, "auth-code")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no unused PKCE verifier"));
+    }
 }
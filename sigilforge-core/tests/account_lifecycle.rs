@@ -7,7 +7,9 @@
 //! - Removing accounts
 //! - Error handling for edge cases
 
-use sigilforge_core::{Account, AccountId, AccountStore, AccountStoreError, ServiceId};
+use sigilforge_core::{
+    Account, AccountId, AccountStore, AccountStoreError, ImportMode, ImportResult, ServiceId,
+};
 use tempfile::TempDir;
 
 /// Helper to create a test store in a temporary directory.
@@ -359,3 +361,78 @@ fn test_complex_scopes() {
 
     assert_eq!(retrieved.scopes, scopes, "Should preserve all scopes");
 }
+
+#[test]
+fn test_export_import_round_trip() {
+    let (store, _temp) = test_store();
+    store
+        .add_account(test_account("spotify", "personal", vec!["user-read-email"]))
+        .unwrap();
+    store.add_account(test_account("github", "work", vec!["repo"])).unwrap();
+    store
+        .update_last_used(&ServiceId::new("github"), &AccountId::new("work"))
+        .unwrap();
+
+    let exported = store.export_json().unwrap();
+
+    // Restore into a fresh store
+    let (restored, _temp2) = test_store();
+    let result = restored.import_json(&exported, ImportMode::Merge).unwrap();
+    assert_eq!(result.imported, 2);
+    assert_eq!(restored.export_json().unwrap(), exported);
+
+    // The restore was persisted
+    let reloaded = AccountStore::load_from_path(restored.path().clone()).unwrap();
+    let work = reloaded
+        .get_account(&ServiceId::new("github"), &AccountId::new("work"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(work.scopes, vec!["repo"]);
+    assert!(work.last_used.is_some());
+
+    // Removing an account and merging the export brings it back
+    reloaded
+        .remove_account(&ServiceId::new("spotify"), &AccountId::new("personal"))
+        .unwrap();
+    let result = reloaded.import_json(&exported, ImportMode::Merge).unwrap();
+    assert_eq!((result.imported, result.skipped, result.removed), (1, 1, 0));
+    assert_eq!(reloaded.list_accounts(None).unwrap().len(), 2);
+}
+
+#[test]
+fn test_import_replace_discards_existing_accounts() {
+    let (source, _temp) = test_store();
+    source.add_account(test_account("spotify", "personal", vec![])).unwrap();
+    let exported = source.export_json().unwrap();
+
+    let (store, _temp2) = test_store();
+    store.add_account(test_account("github", "work", vec![])).unwrap();
+    store.add_account(test_account("spotify", "personal", vec!["old"])).unwrap();
+
+    let result = store.import_json(&exported, ImportMode::Replace).unwrap();
+    assert_eq!(result, ImportResult { imported: 1, skipped: 0, removed: 2 });
+
+    let accounts = store.list_accounts(None).unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert!(accounts[0].scopes.is_empty());
+}
+
+#[test]
+fn test_create_backup_copies_store_file() {
+    let (store, temp) = test_store();
+    store.add_account(test_account("spotify", "personal", vec![])).unwrap();
+
+    let dest = temp.path().join("backups").join("accounts-backup.json");
+    std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+    store.create_backup(&dest).unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&dest).unwrap(),
+        std::fs::read_to_string(store.path()).unwrap()
+    );
+
+    // Backups are importable
+    let (restored, _temp2) = test_store();
+    let json = std::fs::read_to_string(&dest).unwrap();
+    assert_eq!(restored.import_json(&json, ImportMode::Merge).unwrap().imported, 1);
+}
@@ -0,0 +1,72 @@
+//! Integration tests for account store file locking.
+//!
+//! The test binary re-runs itself to get two processes writing to the same
+//! store: `child_writer` does nothing unless the environment variables below
+//! are set.
+
+use std::path::Path;
+use std::process::{Child, Command};
+
+use sigilforge_core::{Account, AccountId, AccountStore, ServiceId};
+use tempfile::TempDir;
+
+/// Store file the child writes to.
+const STORE_ENV: &str = "SIGILFORGE_LOCK_TEST_STORE";
+
+/// Prefix of the account IDs the child adds.
+const WRITER_ENV: &str = "SIGILFORGE_LOCK_TEST_WRITER";
+
+/// Accounts each child adds.
+const WRITES: usize = 25;
+
+fn spawn_writer(path: &Path, writer: &str) -> Child {
+    Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "child_writer", "--nocapture", "--test-threads=1"])
+        .env(STORE_ENV, path)
+        .env(WRITER_ENV, writer)
+        .spawn()
+        .expect("failed to re-run test binary")
+}
+
+/// Adds [`WRITES`] accounts, re-reading the file after each one.
+#[test]
+fn child_writer() {
+    let (Some(path), Ok(writer)) = (std::env::var_os(STORE_ENV), std::env::var(WRITER_ENV)) else {
+        return;
+    };
+
+    let store = AccountStore::load_from_path(path.clone().into()).unwrap();
+    for i in 0..WRITES {
+        let account = Account::new(
+            ServiceId::new("github"),
+            AccountId::new(format!("{}-{}", writer, i)),
+            vec!["repo".to_string()],
+        );
+        store.add_account(account).unwrap();
+
+        // A torn write would fail to parse here
+        AccountStore::load_from_path(path.clone().into())
+            .unwrap_or_else(|e| panic!("{} read a corrupt store: {}", writer, e));
+    }
+}
+
+#[test]
+fn test_concurrent_writers_do_not_corrupt_store() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("accounts.json");
+
+    let writers = [spawn_writer(&path, "a"), spawn_writer(&path, "b")];
+    for writer in writers {
+        let output = writer.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "writer failed: {:?}",
+            output.status
+        );
+    }
+
+    // Each write re-reads the file under the lock, so no account is lost
+    let store = AccountStore::load_from_path(path).unwrap();
+    let accounts = store.list_accounts(None).unwrap();
+    assert_eq!(accounts.len(), 2 * WRITES);
+}
@@ -17,7 +17,7 @@ use sigilforge_core::{
     token_manager::DefaultTokenManager,
 };
 use wiremock::{
-    matchers::{body_string_contains, method, path},
+    matchers::{body_string_contains, header, method, path},
     Mock, MockServer, ResponseTemplate,
 };
 
@@ -32,16 +32,29 @@ fn create_test_provider(token_url: &str) -> ProviderConfig {
         default_scopes: vec![],
         supports_pkce: true,
         supports_device_code: false,
+        default_auth_params: Default::default(),
+        jwks_uri: None,
+        expected_audience: None,
+        issuer: None,
+        instance_url: None,
+        refresh_token_lifetime_secs: None,
     }
 }
 
 /// Helper to set up a token manager with a test provider.
 async fn setup_manager(
     token_url: &str,
+) -> (DefaultTokenManager<MemoryStore>, ServiceId, AccountId) {
+    setup_manager_with(create_test_provider(token_url)).await
+}
+
+/// Helper to set up a token manager for `provider` (whose ID must be "test-provider").
+async fn setup_manager_with(
+    provider: ProviderConfig,
 ) -> (DefaultTokenManager<MemoryStore>, ServiceId, AccountId) {
     let store = MemoryStore::new();
     let mut registry = ProviderRegistry::new();
-    registry.register(create_test_provider(token_url));
+    registry.register(provider).unwrap();
 
     let manager = DefaultTokenManager::new(store, registry);
     let service = ServiceId::new("test-provider");
@@ -147,6 +160,85 @@ async fn test_ensure_access_token_refreshes_expired_token() {
     );
 }
 
+#[tokio::test]
+async fn test_refresh_uses_custom_http_client() {
+    // The mock server stands in for a forward proxy: requests for the
+    // unresolvable token host only succeed if they are sent through it.
+    let proxy = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(header("host", "tokens.example.invalid"))
+        .and(body_string_contains("grant_type=refresh_token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "access_token": "proxied-access-token",
+            "token_type": "Bearer",
+            "expires_in": 3600
+        })))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let http_client = reqwest::Client::builder()
+        .proxy(reqwest::Proxy::http(proxy.uri()).unwrap())
+        .build()
+        .unwrap();
+
+    let (manager, service, account) = setup_manager("http://tokens.example.invalid/token").await;
+    let manager = manager.with_http_client(http_client);
+
+    let token = Token::new("expired-access-token").with_expiry(Utc::now() - Duration::hours(1));
+    manager
+        .store_token_set(&service, &account, TokenSet::new(token).with_refresh_token("refresh"))
+        .await
+        .unwrap();
+
+    let token = manager.ensure_access_token(&service, &account).await.unwrap();
+    assert_eq!(token.access_token.expose(), "proxied-access-token");
+}
+
+#[tokio::test]
+async fn test_refresh_uses_stored_instance_url() {
+    // Salesforce-style provider: tokens are refreshed against the org's own
+    // instance, and access tokens carry no expires_in.
+    let instance = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/services/oauth2/token"))
+        .and(body_string_contains("grant_type=refresh_token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "access_token": "org-access-token",
+            "token_type": "Bearer",
+            "instance_url": instance.uri()
+        })))
+        .expect(1)
+        .mount(&instance)
+        .await;
+
+    let mut provider = create_test_provider("https://login.example.invalid/services/oauth2/token");
+    provider.instance_url = Some("https://login.example.invalid".to_string());
+    let (manager, service, account) = setup_manager_with(provider).await;
+
+    let token = Token::new("expired-access-token").with_expiry(Utc::now() - Duration::hours(1));
+    let token_set = TokenSet::new(token)
+        .with_refresh_token("refresh")
+        .with_instance_url(instance.uri());
+    manager
+        .store_token_set(&service, &account, token_set)
+        .await
+        .unwrap();
+
+    let token = manager.ensure_access_token(&service, &account).await.unwrap();
+    assert_eq!(token.access_token.expose(), "org-access-token");
+    assert!(token.expires_at.is_none());
+
+    // A token without expiry stays usable and keeps its instance
+    let stored = manager.get_token_set(&service, &account).await.unwrap().unwrap();
+    assert_eq!(stored.instance_url, Some(instance.uri()));
+    let token = manager.ensure_access_token(&service, &account).await.unwrap();
+    assert_eq!(token.access_token.expose(), "org-access-token");
+}
+
 #[tokio::test]
 async fn test_ensure_access_token_refresh_fails() {
     // Start a mock server
@@ -0,0 +1,8 @@
+//! Generates the gRPC service from `proto/sigilforge.proto` when the `grpc`
+//! feature is enabled. Requires `protoc` on the `PATH`.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/sigilforge.proto")
+        .expect("failed to compile proto/sigilforge.proto");
+}
@@ -0,0 +1,299 @@
+//! Per-method access control for JSON-RPC requests.
+//!
+//! [`PeerPolicy`](crate::api::PeerPolicy) decides who may connect; the
+//! ACL decides which methods a connected peer may call, and for which
+//! services and accounts. Rules are configured in
+//! [`DaemonConfig::acl`](crate::config::DaemonConfig::acl).
+
+use serde::{Deserialize, Serialize};
+
+/// JSON-RPC error code returned when the ACL denies a request
+pub const FORBIDDEN_CODE: i32 = -32003;
+
+/// A JSON-RPC method that ACL rules can allow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcMethod {
+    GetToken,
+    ListAccounts,
+    AddAccount,
+    Resolve,
+    AccountsStatus,
+    HealthCheck,
+    ValidateProviders,
+}
+
+impl RpcMethod {
+    /// Every method the daemon serves
+    pub const ALL: [RpcMethod; 7] = [
+        RpcMethod::GetToken,
+        RpcMethod::ListAccounts,
+        RpcMethod::AddAccount,
+        RpcMethod::Resolve,
+        RpcMethod::AccountsStatus,
+        RpcMethod::HealthCheck,
+        RpcMethod::ValidateProviders,
+    ];
+
+    /// The method called `name` on the wire, if the daemon serves one
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|method| method.as_str() == name)
+    }
+
+    /// Name of the method on the wire
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcMethod::GetToken => "get_token",
+            RpcMethod::ListAccounts => "list_accounts",
+            RpcMethod::AddAccount => "add_account",
+            RpcMethod::Resolve => "resolve",
+            RpcMethod::AccountsStatus => "accounts_status",
+            RpcMethod::HealthCheck => "health_check",
+            RpcMethod::ValidateProviders => "validate_providers",
+        }
+    }
+}
+
+impl std::fmt::Display for RpcMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Who sent a request, as far as the transport can tell
+///
+/// Unix socket peers carry their credentials; TCP peers have neither a UID
+/// nor a GID.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PeerContext {
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+}
+
+impl PeerContext {
+    /// A Unix socket peer with these credentials
+    pub fn unix(uid: u32, gid: u32) -> Self {
+        Self {
+            uid: Some(uid),
+            gid: Some(gid),
+        }
+    }
+}
+
+/// Methods a peer may call for matching services and accounts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AclRule {
+    /// Peer UID the rule applies to; any peer if unset
+    #[serde(default)]
+    pub peer_uid: Option<u32>,
+    /// Peer primary GID the rule applies to; any peer if unset
+    #[serde(default)]
+    pub peer_gid: Option<u32>,
+    /// Services the rule covers, with `*` matching any run of characters
+    #[serde(default = "match_all")]
+    pub service_pattern: String,
+    /// Accounts the rule covers, with `*` matching any run of characters
+    #[serde(default = "match_all")]
+    pub account_pattern: String,
+    /// Methods the rule allows
+    pub allow: Vec<RpcMethod>,
+}
+
+fn match_all() -> String {
+    "*".to_string()
+}
+
+impl AclRule {
+    /// Allow `uid` every method on every account
+    pub fn owner(uid: u32) -> Self {
+        Self {
+            peer_uid: Some(uid),
+            peer_gid: None,
+            service_pattern: match_all(),
+            account_pattern: match_all(),
+            allow: RpcMethod::ALL.to_vec(),
+        }
+    }
+
+    /// Whether this rule lets `peer` call `method` on `service`/`account`
+    ///
+    /// A request without a service or account, such as `list_accounts`
+    /// without a filter, is only covered by a `*` pattern.
+    pub fn allows(
+        &self,
+        peer: &PeerContext,
+        method: RpcMethod,
+        service: Option<&str>,
+        account: Option<&str>,
+    ) -> bool {
+        let peer_matches =
+            |rule: Option<u32>, peer: Option<u32>| rule.is_none_or(|id| peer == Some(id));
+        peer_matches(self.peer_uid, peer.uid)
+            && peer_matches(self.peer_gid, peer.gid)
+            && pattern_matches(&self.service_pattern, service)
+            && pattern_matches(&self.account_pattern, account)
+            && self.allow.contains(&method)
+    }
+}
+
+/// Configured rules, behind a rule allowing the daemon owner everything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Acl {
+    rules: Vec<AclRule>,
+}
+
+impl Acl {
+    /// An ACL of `rules` for a daemon run by `owner_uid`
+    pub fn new(owner_uid: u32, rules: Vec<AclRule>) -> Self {
+        let mut all = Vec::with_capacity(rules.len() + 1);
+        all.push(AclRule::owner(owner_uid));
+        all.extend(rules);
+        Self { rules: all }
+    }
+
+    /// An ACL of `rules` for a daemon run by the current user
+    pub fn for_current_user(rules: Vec<AclRule>) -> Self {
+        #[cfg(unix)]
+        let owner_uid = unsafe { libc::getuid() };
+        #[cfg(not(unix))]
+        let owner_uid = 0;
+        Self::new(owner_uid, rules)
+    }
+
+    /// Whether any rule lets `peer` call `method` on `service`/`account`
+    pub fn permits(
+        &self,
+        peer: &PeerContext,
+        method: RpcMethod,
+        service: Option<&str>,
+        account: Option<&str>,
+    ) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.allows(peer, method, service, account))
+    }
+}
+
+/// Service and account a request targets; see
+/// [`audit::request_target`](crate::audit::request_target).
+pub fn request_target(
+    method: RpcMethod,
+    params: &serde_json::Value,
+) -> (Option<String>, Option<String>) {
+    crate::audit::request_target(method.as_str(), params)
+}
+
+fn pattern_matches(pattern: &str, value: Option<&str>) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    value.is_some_and(|value| glob_match(pattern, value))
+}
+
+/// Match `value` against `pattern`, where `*` matches any run of characters
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // `split` always yields at least one part
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*` in the pattern
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("github", "github"));
+        assert!(!glob_match("github", "github-enterprise"));
+        assert!(glob_match("git*", "github"));
+        assert!(glob_match("*hub", "github"));
+        assert!(glob_match("g*t*b", "github"));
+        assert!(glob_match("work-*", "work-"));
+        assert!(!glob_match("*hub", "gitlab"));
+        assert!(!glob_match("a*a", "a"));
+    }
+
+    #[test]
+    fn test_rule_matches_peer_and_target() {
+        let rule = AclRule {
+            peer_uid: Some(1001),
+            peer_gid: None,
+            service_pattern: "git*".to_string(),
+            account_pattern: "*".to_string(),
+            allow: vec![RpcMethod::GetToken],
+        };
+        let peer = PeerContext::unix(1001, 100);
+
+        assert!(rule.allows(&peer, RpcMethod::GetToken, Some("github"), Some("work")));
+        assert!(!rule.allows(&peer, RpcMethod::AddAccount, Some("github"), Some("work")));
+        assert!(!rule.allows(&peer, RpcMethod::GetToken, Some("spotify"), Some("work")));
+        assert!(!rule.allows(&peer, RpcMethod::GetToken, None, None));
+        let other = PeerContext::unix(1002, 100);
+        assert!(!rule.allows(&other, RpcMethod::GetToken, Some("github"), Some("work")));
+        let tcp = PeerContext::default();
+        assert!(!rule.allows(&tcp, RpcMethod::GetToken, Some("github"), Some("work")));
+    }
+
+    #[test]
+    fn test_owner_is_always_allowed() {
+        let acl = Acl::new(1000, Vec::new());
+        let owner = PeerContext::unix(1000, 1000);
+        for method in RpcMethod::ALL {
+            assert!(acl.permits(&owner, method, None, None));
+        }
+        assert!(!acl.permits(
+            &PeerContext::unix(1001, 1000),
+            RpcMethod::HealthCheck,
+            None,
+            None
+        ));
+    }
+
+    #[test]
+    fn test_rule_deserializes_from_toml() {
+        let rule: AclRule = toml::from_str(
+            r#"
+            peer_gid = 2000
+            account_pattern = "ci-*"
+            allow = ["get_token", "list_accounts"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(rule.peer_uid, None);
+        assert_eq!(rule.peer_gid, Some(2000));
+        assert_eq!(rule.service_pattern, "*");
+        assert_eq!(
+            rule.allow,
+            vec![RpcMethod::GetToken, RpcMethod::ListAccounts]
+        );
+    }
+
+    #[test]
+    fn test_resolve_targets_its_reference() {
+        let params = json!(["auth://github/work/token"]);
+        let (service, account) = request_target(RpcMethod::Resolve, &params);
+        assert_eq!(service.as_deref(), Some("github"));
+        assert_eq!(account.as_deref(), Some("work"));
+        assert_eq!(
+            request_target(RpcMethod::Resolve, &json!(["bad"])),
+            (None, None)
+        );
+    }
+}
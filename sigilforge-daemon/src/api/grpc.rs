@@ -0,0 +1,339 @@
+//! gRPC transport for the daemon API.
+//!
+//! Serves the RPCs in `proto/sigilforge.proto` over HTTP/2 on a Unix socket,
+//! delegating to the same [`SigilforgeApiImpl`] as the JSON-RPC servers, so
+//! both transports share accounts, tokens and the audit log. Connections are
+//! checked against the same [`PeerPolicy`] as the JSON-RPC socket, and each
+//! request against the ACL. Requires the `grpc` feature, and `protoc` at
+//! build time.
+
+use super::handlers::{ApiState, SigilforgeApiImpl, SigilforgeApiServer};
+use super::server::{bind_socket, PeerPolicy, ServerHandle, SocketOptions};
+use crate::acl::{self, PeerContext};
+use crate::audit::{self, AuditEntry};
+use anyhow::Result;
+use jsonrpsee::types::{ErrorCode, ErrorObjectOwned};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::UnixStream;
+use tokio_stream::wrappers::UnixListenerStream;
+use tokio_stream::StreamExt;
+use tonic::transport::server::UdsConnectInfo;
+use tonic::{Request, Response, Status};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Messages and service stubs generated from `proto/sigilforge.proto`
+pub mod proto {
+    tonic::include_proto!("sigilforge.v1");
+}
+
+use proto::sigilforge_server::{Sigilforge, SigilforgeServer};
+
+/// gRPC service backed by the JSON-RPC handlers
+pub struct SigilforgeGrpcService {
+    api: Arc<SigilforgeApiImpl>,
+}
+
+impl SigilforgeGrpcService {
+    /// Create a service answering from `state`.
+    pub fn new(state: ApiState) -> Self {
+        Self {
+            api: Arc::new(SigilforgeApiImpl::new(state)),
+        }
+    }
+
+    /// Log a handled request and record it in the audit log
+    ///
+    /// `params` are the request's JSON-RPC positional parameters, so gRPC
+    /// requests are audited exactly like their JSON-RPC counterparts.
+    fn finish(&self, method: &str, params: &serde_json::Value, started: Instant, success: bool) {
+        let request_id = Uuid::new_v4();
+        let duration_ms = started.elapsed().as_millis() as u64;
+        info!(
+            %request_id,
+            duration_ms,
+            success,
+            "Completed gRPC {} request",
+            method
+        );
+
+        if let Some(audit_log) = self.api.audit_log() {
+            let (service, account) = audit::request_target(method, params);
+            audit_log.record(&AuditEntry {
+                timestamp: chrono::Utc::now(),
+                request_id: request_id.to_string(),
+                method: method.to_string(),
+                service,
+                account,
+                success,
+                duration_ms,
+            });
+        }
+    }
+
+    /// Check a request from `peer` against the ACL, recording a denied
+    /// request as failed
+    fn authorize(
+        &self,
+        method: &str,
+        params: &serde_json::Value,
+        peer: &PeerContext,
+        started: Instant,
+    ) -> Result<(), Status> {
+        self.api.authorize(method, params, peer).map_err(|e| {
+            self.finish(method, params, started, false);
+            to_status(e)
+        })
+    }
+}
+
+/// Credentials of the peer that sent `request`; none if they are unknown,
+/// so that only ACL rules without a peer UID or GID apply
+fn peer_context<T>(request: &Request<T>) -> PeerContext {
+    request
+        .extensions()
+        .get::<UdsConnectInfo>()
+        .and_then(|info| info.peer_cred)
+        .map(|cred| PeerContext::unix(cred.uid(), cred.gid()))
+        .unwrap_or_default()
+}
+
+/// Whether the peer of a new connection may use the server
+fn permits_peer(stream: &UnixStream, policy: &PeerPolicy) -> bool {
+    match stream.peer_cred() {
+        Ok(cred) if policy.permits(cred.uid(), cred.gid()) => true,
+        Ok(cred) => {
+            warn!(
+                "Rejected gRPC connection from unauthorized user (UID {}, GID {})",
+                cred.uid(),
+                cred.gid()
+            );
+            false
+        }
+        Err(e) => {
+            warn!("Rejected gRPC connection without peer credentials: {}", e);
+            false
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Sigilforge for SigilforgeGrpcService {
+    async fn get_token(
+        &self,
+        request: Request<proto::GetTokenRequest>,
+    ) -> Result<Response<proto::GetTokenResponse>, Status> {
+        let started = Instant::now();
+        let peer = peer_context(&request);
+        let request = request.into_inner();
+        let params = serde_json::json!([request.service, request.account]);
+        self.authorize("get_token", &params, &peer, started)?;
+
+        let result = self
+            .api
+            .get_token(
+                request.service,
+                request.account,
+                Some(request.force_refresh),
+            )
+            .await;
+        self.finish("get_token", &params, started, result.is_ok());
+
+        let token = result.map_err(to_status)?;
+        Ok(Response::new(proto::GetTokenResponse {
+            token: token.token,
+            expires_at: token.expires_at,
+            scopes: token.scopes,
+        }))
+    }
+
+    async fn list_accounts(
+        &self,
+        request: Request<proto::ListAccountsRequest>,
+    ) -> Result<Response<proto::ListAccountsResponse>, Status> {
+        let started = Instant::now();
+        let peer = peer_context(&request);
+        let service = request.into_inner().service;
+        let params = serde_json::json!([service]);
+        self.authorize("list_accounts", &params, &peer, started)?;
+
+        let result = self.api.list_accounts(service).await;
+        self.finish("list_accounts", &params, started, result.is_ok());
+
+        let accounts = result
+            .map_err(to_status)?
+            .accounts
+            .into_iter()
+            .map(|account| proto::AccountInfo {
+                service: account.service,
+                account: account.account,
+                scopes: account.scopes,
+                created_at: account.created_at,
+                last_used: account.last_used,
+                source: account.source.to_string(),
+            })
+            .collect();
+        Ok(Response::new(proto::ListAccountsResponse { accounts }))
+    }
+
+    async fn add_account(
+        &self,
+        request: Request<proto::AddAccountRequest>,
+    ) -> Result<Response<proto::AddAccountResponse>, Status> {
+        let started = Instant::now();
+        let peer = peer_context(&request);
+        let request = request.into_inner();
+        let params = serde_json::json!([request.service, request.account, request.scopes]);
+        self.authorize("add_account", &params, &peer, started)?;
+
+        let result = self
+            .api
+            .add_account(
+                request.service,
+                request.account,
+                request.scopes,
+                Some(request.revoke_existing),
+            )
+            .await;
+        self.finish("add_account", &params, started, result.is_ok());
+
+        let response = result.map_err(to_status)?;
+        Ok(Response::new(proto::AddAccountResponse {
+            message: response.message,
+        }))
+    }
+
+    async fn resolve(
+        &self,
+        request: Request<proto::ResolveRequest>,
+    ) -> Result<Response<proto::ResolveResponse>, Status> {
+        let started = Instant::now();
+        let peer = peer_context(&request);
+        let reference = request.into_inner().reference;
+        let params = serde_json::json!([reference]);
+        self.authorize("resolve", &params, &peer, started)?;
+
+        let result = self.api.resolve(reference).await;
+        self.finish("resolve", &params, started, result.is_ok());
+
+        let response = result.map_err(to_status)?;
+        Ok(Response::new(proto::ResolveResponse {
+            value: response.value,
+        }))
+    }
+
+    async fn accounts_status(
+        &self,
+        request: Request<proto::AccountsStatusRequest>,
+    ) -> Result<Response<proto::AccountsStatusResponse>, Status> {
+        let started = Instant::now();
+        let params = serde_json::json!([]);
+        self.authorize("accounts_status", &params, &peer_context(&request), started)?;
+
+        let result = self.api.accounts_status().await;
+        self.finish("accounts_status", &params, started, result.is_ok());
+
+        let status = result.map_err(to_status)?;
+        let accounts = status
+            .accounts
+            .into_iter()
+            .map(|account| proto::AccountStatus {
+                service: account.service,
+                account: account.account,
+                token_valid: account.token_valid,
+                expires_soon: account.expires_soon,
+                expires_at: account.expires_at,
+                refreshable: account.refreshable,
+                error: account.error,
+            })
+            .collect();
+        Ok(Response::new(proto::AccountsStatusResponse {
+            accounts,
+            all_valid: status.all_valid,
+            any_expiring_soon: status.any_expiring_soon,
+        }))
+    }
+}
+
+/// Map a JSON-RPC error to the closest gRPC status
+fn to_status(error: ErrorObjectOwned) -> Status {
+    let message = error.message().to_string();
+    if error.code() == acl::FORBIDDEN_CODE {
+        return Status::permission_denied(message);
+    }
+    match ErrorCode::from(error.code()) {
+        ErrorCode::InvalidParams => Status::invalid_argument(message),
+        ErrorCode::InvalidRequest => Status::failed_precondition(message),
+        ErrorCode::MethodNotFound => Status::unimplemented(message),
+        _ => Status::internal(message),
+    }
+}
+
+/// Start the gRPC server on a Unix socket.
+///
+/// The socket gets the ownership and permissions in `options`, and peers
+/// are accepted as by the JSON-RPC server; see [`PeerPolicy`].
+pub async fn start_grpc_server(
+    socket_path: &Path,
+    state: ApiState,
+    options: SocketOptions,
+) -> Result<ServerHandle> {
+    info!("Starting gRPC server on {:?}", socket_path);
+    let (listener, policy) = bind_socket(socket_path, options)?;
+    let incoming = UnixListenerStream::new(listener).filter(move |connection| match connection {
+        Ok(stream) => permits_peer(stream, &policy),
+        Err(_) => true,
+    });
+
+    let service = SigilforgeServer::new(SigilforgeGrpcService::new(state));
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<()>(1);
+
+    let server_task = tokio::spawn(async move {
+        let shutdown = async move {
+            rx.recv().await;
+            debug!("gRPC server shutdown signal received");
+        };
+        let result = tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_incoming_shutdown(incoming, shutdown)
+            .await;
+        if let Err(e) = result {
+            warn!("gRPC server failed: {}", e);
+        }
+    });
+
+    Ok(ServerHandle::new(tx, server_task, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::Code;
+
+    #[test]
+    fn test_error_codes_map_to_status() {
+        let error = |code: ErrorCode| ErrorObjectOwned::owned(code.code(), "failed", None::<()>);
+
+        assert_eq!(
+            to_status(error(ErrorCode::InvalidParams)).code(),
+            Code::InvalidArgument
+        );
+        assert_eq!(
+            to_status(error(ErrorCode::InvalidRequest)).code(),
+            Code::FailedPrecondition
+        );
+        assert_eq!(
+            to_status(error(ErrorCode::MethodNotFound)).code(),
+            Code::Unimplemented
+        );
+
+        let forbidden = ErrorObjectOwned::owned(acl::FORBIDDEN_CODE, "Forbidden", None::<()>);
+        assert_eq!(to_status(forbidden).code(), Code::PermissionDenied);
+
+        let status = to_status(error(ErrorCode::InternalError));
+        assert_eq!(status.code(), Code::Internal);
+        assert_eq!(status.message(), "failed");
+    }
+}
@@ -2,15 +2,26 @@
 
 use sigilforge_core::{
     account_store::AccountStore,
-    model::{Account, AccountId, ServiceId},
+    model::{Account, AccountId, CredentialSource, CredentialType, ServiceId},
     store::{create_store, SecretStore},
-    token_manager::DefaultTokenManager,
+    token_manager::{DefaultTokenManager, DEFAULT_KEY_PREFIX},
     provider::ProviderRegistry,
+    TokenError,
     TokenManager,
     DefaultReferenceResolver,
     ReferenceResolver,
+    Token,
+    TokenEvent,
 };
+use crate::acl::{self, Acl, PeerContext, RpcMethod};
+use crate::audit::AuditLog;
+use crate::metrics;
+use crate::plugin::{BoxedPlugin, DaemonPlugin};
+use dashmap::{mapref::entry::Entry, DashMap};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 
 /// Information about a configured account (RPC response)
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -20,6 +31,8 @@ pub struct AccountInfo {
     pub scopes: Vec<String>,
     pub created_at: String,
     pub last_used: Option<String>,
+    #[serde(default)]
+    pub source: CredentialSource,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -31,6 +44,19 @@ pub struct AddAccountResponse {
 pub struct GetTokenResponse {
     pub token: String,
     pub expires_at: Option<String>,
+    /// Scopes granted to the token, if the provider reported them
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+impl From<Token> for GetTokenResponse {
+    fn from(token: Token) -> Self {
+        Self {
+            token: token.access_token.expose().to_string(),
+            expires_at: token.expires_at.map(|dt| dt.to_rfc3339()),
+            scopes: token.scopes,
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -48,23 +74,84 @@ pub struct ResolveResponse {
 pub struct AccountStatusInfo {
     pub service: String,
     pub account: String,
+    /// Whether the stored access token is unexpired
     pub token_valid: bool,
+    /// Whether a refresh token is stored to renew the access token with
+    #[serde(default)]
+    pub refreshable: bool,
     pub expires_soon: bool,
     pub expires_at: Option<String>,
+    /// Why the account's token could not be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 /// Response for accounts_status RPC method
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AccountsStatusResponse {
     pub accounts: Vec<AccountStatusInfo>,
+    /// Whether every account has a valid or refreshable token
     pub all_valid: bool,
     pub any_expiring_soon: bool,
 }
-use anyhow::Result;
+
+/// Validation problems of one provider the daemon loaded
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProviderValidationInfo {
+    pub id: String,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// A provider file the daemon skipped because it failed to load
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SkippedProviderFile {
+    pub path: String,
+    pub error: String,
+}
+
+/// Response for validate_providers RPC method
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ValidateProvidersResponse {
+    /// Every loaded provider, sorted by ID
+    pub providers: Vec<ProviderValidationInfo>,
+    pub skipped_files: Vec<SkippedProviderFile>,
+}
+
+/// Outcome of [`ApiState::validate_all_accounts`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StartupValidation {
+    /// Accounts with an unexpired token
+    pub valid: usize,
+    /// Accounts whose token has expired or was never stored
+    pub invalid: usize,
+    /// Accounts whose token could not be read
+    pub failed: usize,
+}
+
+/// Outcome of [`ApiState::pre_warm_tokens`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrewarmSummary {
+    /// Accounts whose token is valid, after a refresh if it had expired
+    pub prewarmed: usize,
+    /// Accounts without a usable token
+    pub failed: usize,
+}
+
+/// Response for health_check RPC method
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HealthResponse {
+    pub version: String,
+    pub uptime_secs: u64,
+    pub account_count: usize,
+    pub backend: String,
+    pub ok: bool,
+}
+use anyhow::{Context, Result};
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::types::{ErrorCode, ErrorObject};
-use tracing::{debug, info};
+use tracing::{debug, error, info, warn};
 
 /// Type alias for the token manager used by the daemon.
 pub type DaemonTokenManager = DefaultTokenManager<Box<dyn SecretStore>>;
@@ -72,7 +159,189 @@ pub type DaemonTokenManager = DefaultTokenManager<Box<dyn SecretStore>>;
 /// Type alias for the reference resolver used by the daemon.
 pub type DaemonResolver = DefaultReferenceResolver<Box<dyn SecretStore>, DaemonTokenManager>;
 
+/// HTTP client for outbound OAuth requests.
+///
+/// `HTTPS_PROXY` (or `https_proxy`) sends token endpoint traffic through a
+/// proxy, honouring `NO_PROXY`. Redirects are not followed.
+fn daemon_http_client() -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none());
+
+    let proxy_url = std::env::var("HTTPS_PROXY")
+        .or_else(|_| std::env::var("https_proxy"))
+        .ok()
+        .filter(|url| !url.is_empty());
+    if let Some(url) = proxy_url {
+        let proxy = reqwest::Proxy::https(&url)
+            .context("Invalid HTTPS_PROXY")?
+            .no_proxy(reqwest::NoProxy::from_env());
+        builder = builder.proxy(proxy);
+        info!("Routing OAuth requests through HTTPS_PROXY");
+    }
+
+    builder.build().context("Failed to build HTTP client")
+}
+
+/// Requests processed concurrently per connection unless configured otherwise
+pub const DEFAULT_MAX_PIPELINED_REQUESTS: usize = 10;
+
+/// How long a connection may go without sending a request before it is closed
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tokens expiring within this long are reported as expiring soon
+pub const DEFAULT_EXPIRY_WARNING: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long a request waits for an identical in-flight one before running
+/// on its own
+pub const DEFAULT_DEDUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Credentials deleted with an account from stores that cannot list their
+/// keys, as `sigilforge remove-account` does
+const KNOWN_CREDENTIAL_TYPES: [CredentialType; 7] = [
+    CredentialType::AccessToken,
+    CredentialType::RefreshToken,
+    CredentialType::TokenExpiry,
+    CredentialType::ApiKey,
+    CredentialType::ClientId,
+    CredentialType::ClientSecret,
+    CredentialType::TokenScopes,
+];
+
+/// Result of an in-flight request, sent to identical requests once known
+type SharedResult = watch::Sender<Option<serde_json::Value>>;
+
+/// Identifies a request to [`RequestDeduplicator`]: its method and the
+/// service and account it is for.
+pub type RequestKey = (&'static str, ServiceId, AccountId);
+
+/// Shares the result of an in-flight request with identical requests that
+/// arrive before it completes.
+///
+/// Requests are identical when their method, service and account match.
+/// The first caller runs the request and broadcasts its result, so
+/// N concurrent `get_token` calls for an expired token refresh it once.
+/// Waiters run the request themselves if the first caller is cancelled or
+/// takes longer than the timeout, and a timed-out entry is removed so later
+/// requests do not wait on it either.
+#[derive(Clone)]
+pub struct RequestDeduplicator {
+    in_flight: Arc<DashMap<RequestKey, Arc<SharedResult>>>,
+    timeout: Duration,
+}
+
+impl Default for RequestDeduplicator {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUP_TIMEOUT)
+    }
+}
+
+impl RequestDeduplicator {
+    /// Create a deduplicator whose waiters give up after `timeout`.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            in_flight: Arc::new(DashMap::new()),
+            timeout,
+        }
+    }
+
+    /// The key identifying a request for `method` on `service/account`.
+    pub fn key(method: &'static str, service: &ServiceId, account: &AccountId) -> RequestKey {
+        (method, service.clone(), account.clone())
+    }
+
+    /// Number of distinct requests in flight.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Run `request`, or wait for the result of an identical one in flight.
+    pub async fn run<T, F, Fut>(&self, key: RequestKey, request: F) -> RpcResult<T>
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = RpcResult<T>>,
+    {
+        // Subscribe while holding the entry, so the result cannot be sent
+        // between finding the entry and subscribing
+        let in_flight = match self.in_flight.entry(key.clone()) {
+            Entry::Occupied(entry) => Err((entry.get().subscribe(), Arc::downgrade(entry.get()))),
+            Entry::Vacant(entry) => {
+                let sender = Arc::new(watch::channel(None).0);
+                entry.insert(Arc::clone(&sender));
+                Ok(sender)
+            }
+        };
+
+        let sender = match in_flight {
+            Ok(sender) => sender,
+            Err((mut receiver, leader)) => {
+                match tokio::time::timeout(self.timeout, receiver.wait_for(Option::is_some)).await {
+                    Ok(Ok(shared)) => {
+                        debug!("Request {:?} shared an in-flight result", key);
+                        return unshare(shared.as_ref().unwrap_or(&serde_json::Value::Null));
+                    }
+                    // The first caller was cancelled and removed its entry
+                    Ok(Err(_)) => {}
+                    Err(_) => {
+                        warn!(
+                            "Request {:?} still in flight after {:?}; running it again",
+                            key, self.timeout
+                        );
+                        self.in_flight
+                            .remove_if(&key, |_, sender| Arc::as_ptr(sender) == leader.as_ptr());
+                    }
+                }
+                return request().await;
+            }
+        };
+
+        let _entry = InFlightEntry {
+            in_flight: &self.in_flight,
+            key: &key,
+            sender: &sender,
+        };
+        let result = request().await;
+        sender.send_replace(Some(share(&result)));
+        result
+    }
+}
+
+/// Removes a request's deduplication entry when it completes or is
+/// cancelled, unless it was already replaced.
+struct InFlightEntry<'a> {
+    in_flight: &'a DashMap<RequestKey, Arc<SharedResult>>,
+    key: &'a RequestKey,
+    sender: &'a Arc<SharedResult>,
+}
+
+impl Drop for InFlightEntry<'_> {
+    fn drop(&mut self) {
+        self.in_flight
+            .remove_if(self.key, |_, sender| Arc::ptr_eq(sender, self.sender));
+    }
+}
+
+/// Encode a request result for [`RequestDeduplicator`] waiters.
+fn share<T: serde::Serialize>(result: &RpcResult<T>) -> serde_json::Value {
+    match result {
+        Ok(value) => serde_json::json!({ "result": value }),
+        Err(e) => serde_json::json!({ "error": { "code": e.code(), "message": e.message() } }),
+    }
+}
+
+/// Decode a result encoded by [`share`].
+fn unshare<T: serde::de::DeserializeOwned>(shared: &serde_json::Value) -> RpcResult<T> {
+    if let Some(error) = shared.get("error") {
+        let code = error["code"]
+            .as_i64()
+            .unwrap_or(ErrorCode::InternalError.code().into());
+        let message = error["message"].as_str().unwrap_or_default().to_string();
+        return Err(ErrorObject::owned(code as i32, message, None::<()>));
+    }
+    serde_json::from_value(shared["result"].clone()).map_err(internal_error)
+}
+
 /// State shared across RPC handlers.
+#[derive(Clone)]
 pub struct ApiState {
     /// Persistent account store
     pub accounts: Arc<AccountStore>,
@@ -80,43 +349,101 @@ pub struct ApiState {
     pub token_manager: Arc<DaemonTokenManager>,
     /// Reference resolver for auth:// URIs
     pub resolver: Arc<DaemonResolver>,
+    /// When the state was created (used for uptime reporting)
+    pub started_at: Instant,
+    /// Whether a persistent (keyring) backend was requested
+    pub prefer_keyring: bool,
+    /// Whether responses carry an `x-request-id` field
+    pub emit_request_ids: bool,
+    /// Requests processed concurrently per connection
+    pub max_pipelined_requests: usize,
+    /// Whether pipelined responses keep request order
+    pub ordered_pipelining: bool,
+    /// Where handled requests are recorded, if anywhere
+    pub audit_log: Option<Arc<AuditLog>>,
+    /// Connections that send no request for this long are closed
+    pub idle_timeout: Duration,
+    /// Tokens expiring within this long are reported as expiring soon
+    pub expiry_warning: Duration,
+    /// Plugins consulted by `get_token` before the token manager
+    pub plugins: Vec<Arc<dyn DaemonPlugin>>,
+    /// Which methods each peer may call; every peer may call every method
+    /// if unset
+    pub acl: Option<Arc<Acl>>,
+    /// Shares results between identical concurrent requests
+    pub dedup: RequestDeduplicator,
 }
 
 impl ApiState {
-    /// Create a new API state.
+    /// Create a new API state with the built-in providers.
     pub fn new() -> Result<Self> {
-        let accounts = AccountStore::load()?;
+        Self::with_providers(ProviderRegistry::with_defaults())
+    }
+
+    /// Create a new API state that refreshes tokens using `providers`.
+    pub fn with_providers(providers: ProviderRegistry) -> Result<Self> {
+        Self::with_providers_in_namespace(AccountStore::load()?, providers, DEFAULT_KEY_PREFIX)
+    }
+
+    /// Create a new API state for `accounts` that refreshes tokens using
+    /// `providers` and keeps secrets under `namespace` in the secret store.
+    pub fn with_providers_in_namespace(
+        accounts: AccountStore,
+        providers: ProviderRegistry,
+        namespace: &str,
+    ) -> Result<Self> {
+        let accounts = Arc::new(accounts);
 
         // Create secret store (prefer keyring)
         let store = create_store(true);
 
-        // Create provider registry with defaults
-        let providers = ProviderRegistry::with_defaults();
-
         // Create token manager
-        let token_manager = DefaultTokenManager::new(store, providers);
+        let http_client = daemon_http_client()?;
+        let token_manager = DefaultTokenManager::new(store, providers.clone())
+            .with_http_client(http_client.clone())
+            .with_account_store(Arc::clone(&accounts))
+            .with_key_prefix(namespace);
 
         // Clone references for resolver (store is moved, so we need to create another)
         let resolver_store = create_store(true);
-        let resolver_token_manager = DefaultTokenManager::new(
-            create_store(true),
-            ProviderRegistry::with_defaults(),
-        );
-        let resolver = DefaultReferenceResolver::new(resolver_store, resolver_token_manager);
+        let resolver_token_manager = DefaultTokenManager::new(create_store(true), providers)
+            .with_http_client(http_client)
+            .with_key_prefix(namespace);
+        let resolver = DefaultReferenceResolver::new(resolver_store, resolver_token_manager)
+            .with_key_prefix(namespace);
 
         Ok(Self {
-            accounts: Arc::new(accounts),
+            accounts,
             token_manager: Arc::new(token_manager),
             resolver: Arc::new(resolver),
+            started_at: Instant::now(),
+            prefer_keyring: true,
+            emit_request_ids: false,
+            max_pipelined_requests: DEFAULT_MAX_PIPELINED_REQUESTS,
+            ordered_pipelining: true,
+            audit_log: None,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            expiry_warning: DEFAULT_EXPIRY_WARNING,
+            plugins: Vec::new(),
+            acl: None,
+            dedup: RequestDeduplicator::default(),
         })
     }
 
     /// Create API state with a provided account store (useful for tests).
     #[allow(dead_code)]
     pub fn with_store(accounts: AccountStore) -> Self {
+        Self::with_store_and_providers(accounts, ProviderRegistry::new())
+    }
+
+    /// Create API state with a provided account store whose tokens are
+    /// refreshed and revoked using `providers` (useful for tests).
+    #[allow(dead_code)]
+    pub fn with_store_and_providers(accounts: AccountStore, providers: ProviderRegistry) -> Self {
+        let accounts = Arc::new(accounts);
         let store = create_store(false); // Use memory store for tests
-        let providers = ProviderRegistry::new();
-        let token_manager = DefaultTokenManager::new(store, providers);
+        let token_manager =
+            DefaultTokenManager::new(store, providers).with_account_store(Arc::clone(&accounts));
 
         let resolver_store = create_store(false);
         let resolver_token_manager = DefaultTokenManager::new(
@@ -126,10 +453,251 @@ impl ApiState {
         let resolver = DefaultReferenceResolver::new(resolver_store, resolver_token_manager);
 
         Self {
-            accounts: Arc::new(accounts),
+            accounts,
             token_manager: Arc::new(token_manager),
             resolver: Arc::new(resolver),
+            started_at: Instant::now(),
+            prefer_keyring: false,
+            emit_request_ids: false,
+            max_pipelined_requests: DEFAULT_MAX_PIPELINED_REQUESTS,
+            ordered_pipelining: true,
+            audit_log: None,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            expiry_warning: DEFAULT_EXPIRY_WARNING,
+            plugins: Vec::new(),
+            acl: None,
+            dedup: RequestDeduplicator::default(),
+        }
+    }
+
+    /// Include each request's correlation ID in its response.
+    pub fn with_request_ids(mut self, emit: bool) -> Self {
+        self.emit_request_ids = emit;
+        self
+    }
+
+    /// Process up to `max_requests` requests per connection at once.
+    ///
+    /// With `ordered` set, responses are written in request order; otherwise
+    /// in the order they complete.
+    pub fn with_pipelining(mut self, max_requests: usize, ordered: bool) -> Self {
+        self.max_pipelined_requests = max_requests.max(1);
+        self.ordered_pipelining = ordered;
+        self
+    }
+
+    /// Record every handled request in `audit_log`.
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(Arc::new(audit_log));
+        self
+    }
+
+    /// Close connections that send no request for `timeout`.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Report tokens expiring within `threshold` as expiring soon.
+    pub fn with_expiry_warning(mut self, threshold: Duration) -> Self {
+        self.expiry_warning = threshold;
+        self
+    }
+
+    /// Answer only requests that `acl` allows the requesting peer.
+    pub fn with_acl(mut self, acl: Acl) -> Self {
+        self.acl = Some(Arc::new(acl));
+        self
+    }
+
+    /// Consult `plugin` in `get_token`, after any plugins registered before it.
+    ///
+    /// Register plugins before starting a server; states already handed to
+    /// a server keep the plugins they were cloned with.
+    pub fn register_plugin(&mut self, plugin: BoxedPlugin) {
+        info!("Registered token plugin {}", plugin.name());
+        self.plugins.push(Arc::from(plugin));
+    }
+
+    /// Remove `service/account` and all of its stored secrets, if it
+    /// exists, so it can be added again.
+    ///
+    /// The refresh token is revoked at the provider first. Revocation is
+    /// best effort: a provider that refuses it does not stop the removal.
+    async fn remove_existing_account(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+    ) -> Result<()> {
+        if self.accounts.get_account(service, account)?.is_none() {
+            return Ok(());
+        }
+
+        if let Err(e) = self
+            .token_manager
+            .revoke_at_provider(service, account)
+            .await
+        {
+            warn!(
+                "Could not revoke the refresh token of {}/{} at the provider: {}",
+                service, account, e
+            );
+        }
+        if let Err(e) = self.token_manager.revoke_tokens(service, account).await {
+            warn!(
+                "Could not delete the tokens of {}/{}: {}",
+                service, account, e
+            );
+        }
+        // Client credentials and other secrets go too, as with remove-account
+        let store = &self.token_manager.store;
+        let prefix = self.token_manager.account_key_prefix(service, account);
+        let keys = match store.list_keys(&prefix).await {
+            Ok(keys) => keys,
+            // The keyring cannot list keys, so delete the known types
+            Err(e) => {
+                debug!(
+                    "Could not list the secrets of {}/{} ({}), deleting known credentials",
+                    service, account, e
+                );
+                KNOWN_CREDENTIAL_TYPES
+                    .iter()
+                    .map(|cred_type| format!("{}{}", prefix, cred_type))
+                    .collect()
+            }
+        };
+        for key in keys {
+            store.delete(&key).await?;
         }
+        self.accounts.remove_account(service, account)?;
+        info!(
+            "Removed existing account {}/{} to replace it",
+            service, account
+        );
+        Ok(())
+    }
+
+    /// Get a valid token from the token manager, refreshing it if needed
+    /// or if `force_refresh` is set.
+    async fn fetch_token(
+        &self,
+        service: &ServiceId,
+        account: &AccountId,
+        force_refresh: bool,
+    ) -> RpcResult<GetTokenResponse> {
+        let token = if force_refresh {
+            self.token_manager.force_refresh(service, account).await
+        } else {
+            self.token_manager
+                .ensure_access_token(service, account)
+                .await
+        };
+        // If no token found, return a more helpful error
+        token.map(Into::into).map_err(|e| {
+            ErrorObject::owned(
+                ErrorCode::InternalError.code(),
+                format!(
+                    "Failed to get token: {}. You may need to re-authenticate.",
+                    e
+                ),
+                None::<()>,
+            )
+        })
+    }
+
+    /// The first registered plugin that handles `service`.
+    async fn plugin_for(&self, service: &str) -> Option<&Arc<dyn DaemonPlugin>> {
+        for plugin in &self.plugins {
+            if plugin.can_handle(service).await {
+                return Some(plugin);
+            }
+        }
+        None
+    }
+}
+
+impl ApiState {
+    /// Log the token status of every configured account.
+    ///
+    /// Run once at startup so expired tokens show up in the daemon log
+    /// before a client asks for them. Purely diagnostic: failures are
+    /// logged and counted, never returned.
+    pub async fn validate_all_accounts(&self) -> StartupValidation {
+        let mut result = StartupValidation::default();
+        let accounts = match self.accounts.list_accounts(None) {
+            Ok(accounts) => accounts,
+            Err(e) => {
+                error!("Startup validation could not list accounts: {}", e);
+                return result;
+            }
+        };
+
+        for account in accounts {
+            let (service, id) = (&account.service, &account.id);
+            match self.token_manager.introspect_token(service, id).await {
+                Ok(info) if info.active => {
+                    info!("Account {}/{} has a valid token", service, id);
+                    result.valid += 1;
+                }
+                Ok(_) => {
+                    warn!("Account {}/{} has an expired token", service, id);
+                    result.invalid += 1;
+                }
+                Err(TokenError::NotFound { .. }) => {
+                    warn!("Account {}/{} has no stored token", service, id);
+                    result.invalid += 1;
+                }
+                Err(e) => {
+                    error!("Could not check the token of {}/{}: {}", service, id, e);
+                    result.failed += 1;
+                }
+            }
+        }
+
+        metrics::record_startup_validation(result.valid, result.invalid + result.failed);
+        info!(
+            "Startup validation: {} valid, {} invalid, {} unreadable",
+            result.valid, result.invalid, result.failed
+        );
+        result
+    }
+
+    /// Load the token of every configured account, refreshing expired ones.
+    ///
+    /// Run in the background at startup so the first request for each
+    /// account is answered without a round trip to the provider. Failures
+    /// are logged and counted, never returned.
+    pub async fn pre_warm_tokens(&self) -> PrewarmSummary {
+        let mut summary = PrewarmSummary::default();
+        let accounts: Vec<_> = match self.accounts.list_accounts(None) {
+            Ok(accounts) => accounts
+                .into_iter()
+                .map(|account| (account.service, account.id))
+                .collect(),
+            Err(e) => {
+                error!("Token pre-warming could not list accounts: {}", e);
+                return summary;
+            }
+        };
+
+        for ((service, account), result) in self.token_manager.pre_warm_tokens(&accounts).await {
+            match result {
+                Ok(_) => summary.prewarmed += 1,
+                Err(e) => {
+                    warn!(
+                        "Could not pre-warm the token of {}/{}: {}",
+                        service, account, e
+                    );
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        info!(
+            "Token pre-warming: {} ready, {} failed",
+            summary.prewarmed, summary.failed
+        );
+        summary
     }
 }
 
@@ -148,12 +716,19 @@ pub trait SigilforgeApi {
     ///
     /// - `service`: Service identifier (e.g., "spotify")
     /// - `account`: Account identifier (e.g., "personal")
+    /// - `force_refresh`: Fetch a new token from the provider even if the
+    ///   cached one is still valid (default: false)
     ///
     /// # Returns
     ///
     /// A fresh access token and optional expiration timestamp.
     #[method(name = "get_token")]
-    async fn get_token(&self, service: String, account: String) -> RpcResult<GetTokenResponse>;
+    async fn get_token(
+        &self,
+        service: String,
+        account: String,
+        force_refresh: Option<bool>,
+    ) -> RpcResult<GetTokenResponse>;
 
     /// List all configured accounts, optionally filtered by service.
     ///
@@ -174,6 +749,9 @@ pub trait SigilforgeApi {
     /// - `service`: Service identifier
     /// - `account`: Account identifier
     /// - `scopes`: OAuth scopes to request
+    /// - `revoke_existing`: Replace an existing account of the same name,
+    ///   revoking its refresh token at the provider and deleting its stored
+    ///   credentials, instead of failing (default: false)
     ///
     /// # Returns
     ///
@@ -184,6 +762,7 @@ pub trait SigilforgeApi {
         service: String,
         account: String,
         scopes: Vec<String>,
+        revoke_existing: Option<bool>,
     ) -> RpcResult<AddAccountResponse>;
 
     /// Resolve a credential reference to its actual value.
@@ -201,13 +780,35 @@ pub trait SigilforgeApi {
     /// Get status of all accounts (for status bar plugins).
     ///
     /// Returns token validity and expiry information for each account.
-    /// Tokens expiring within 24 hours are marked as "expires_soon".
+    /// Tokens expiring within the configured warning threshold (24 hours by
+    /// default) are marked as "expires_soon". An account whose token cannot
+    /// be read is reported with an `error` instead of failing the request.
     ///
     /// # Returns
     ///
     /// Status information for all accounts.
     #[method(name = "accounts_status")]
     async fn accounts_status(&self) -> RpcResult<AccountsStatusResponse>;
+
+    /// Report daemon health.
+    ///
+    /// The daemon is considered degraded (`ok: false`) when a persistent
+    /// keyring backend was requested but secrets are held in memory instead.
+    ///
+    /// # Returns
+    ///
+    /// Version, uptime, account count, and secret store backend.
+    #[method(name = "health_check")]
+    async fn health_check(&self) -> RpcResult<HealthResponse>;
+
+    /// Validate the providers the daemon refreshes tokens with.
+    ///
+    /// # Returns
+    ///
+    /// Errors and warnings of each loaded provider, and the provider files
+    /// that were skipped because they failed to load.
+    #[method(name = "validate_providers")]
+    async fn validate_providers(&self) -> RpcResult<ValidateProvidersResponse>;
 }
 
 /// Implementation of the Sigilforge API.
@@ -220,28 +821,108 @@ impl SigilforgeApiImpl {
     pub fn new(state: ApiState) -> Self {
         Self { state }
     }
+
+    /// Whether responses should carry an `x-request-id` field.
+    pub(crate) fn emit_request_ids(&self) -> bool {
+        self.state.emit_request_ids
+    }
+
+    pub(crate) fn max_pipelined_requests(&self) -> usize {
+        self.state.max_pipelined_requests
+    }
+
+    pub(crate) fn ordered_pipelining(&self) -> bool {
+        self.state.ordered_pipelining
+    }
+
+    pub(crate) fn audit_log(&self) -> Option<&AuditLog> {
+        self.state.audit_log.as_deref()
+    }
+
+    pub(crate) fn idle_timeout(&self) -> Duration {
+        self.state.idle_timeout
+    }
+
+    /// Check a JSON-RPC request from `peer` against the ACL.
+    ///
+    /// Methods the daemon does not serve are let through to fail as usual.
+    pub(crate) fn authorize(
+        &self,
+        method: &str,
+        params: &serde_json::Value,
+        peer: &PeerContext,
+    ) -> RpcResult<()> {
+        let (Some(acl), Some(method)) = (&self.state.acl, RpcMethod::from_name(method)) else {
+            return Ok(());
+        };
+
+        let (service, account) = acl::request_target(method, params);
+        if acl.permits(peer, method, service.as_deref(), account.as_deref()) {
+            return Ok(());
+        }
+        warn!(
+            "ACL denied {} (service: {:?}, account: {:?}) to peer UID {:?}, GID {:?}",
+            method, service, account, peer.uid, peer.gid
+        );
+        Err(ErrorObject::owned(
+            acl::FORBIDDEN_CODE,
+            format!("Forbidden: not allowed to call {}", method),
+            None::<()>,
+        ))
+    }
 }
 
 #[async_trait::async_trait]
 impl SigilforgeApiServer for SigilforgeApiImpl {
-    async fn get_token(&self, service: String, account: String) -> RpcResult<GetTokenResponse> {
-        info!("RPC: get_token({}/{})", service, account);
+    async fn get_token(
+        &self,
+        service: String,
+        account: String,
+        force_refresh: Option<bool>,
+    ) -> RpcResult<GetTokenResponse> {
+        let force_refresh = force_refresh.unwrap_or(false);
+        info!(
+            "RPC: get_token({}/{}, force_refresh: {})",
+            service, account, force_refresh
+        );
+        metrics::record_request("get_token");
+
+        // Plugins serve their services without a stored account
+        if let Some(plugin) = self.state.plugin_for(&service).await {
+            debug!("get_token({}/{}) handled by plugin {}", service, account, plugin.name());
+            return match plugin.get_token(&service, &account).await {
+                Ok(token_set) => Ok(token_set.access_token.into()),
+                Err(e) => Err(ErrorObject::owned(
+                    ErrorCode::InternalError.code(),
+                    format!("Plugin {} failed to get token: {}", plugin.name(), e),
+                    None::<()>,
+                )),
+            };
+        }
 
         // Check if account exists
         let service_id = ServiceId::new(&service);
         let account_id = AccountId::new(&account);
-        if self
+        let Some(stored) = self
             .state
             .accounts
             .get_account(&service_id, &account_id)
             .map_err(internal_error)?
-            .is_none()
-        {
+        else {
             return Err(ErrorObject::owned(
                 ErrorCode::InvalidParams.code(),
                 format!("Account {}/{} not found", service, account),
                 None::<()>,
             ));
+        };
+
+        if let Some(TokenEvent::RefreshTokenExpiringSoon { expires_at, .. }) =
+            stored.refresh_token_event(chrono::Utc::now())
+        {
+            warn!(
+                "Refresh token for {}/{} expires at {}; re-authorize the account before then",
+                service, account, expires_at
+            );
         }
 
         // Update last_used timestamp
@@ -251,33 +932,27 @@ impl SigilforgeApiServer for SigilforgeApiImpl {
             .update_last_used(&service_id, &account_id)
             .map_err(internal_error);
 
-        // Use the token manager to get a valid token (handles refresh)
-        match self
-            .state
-            .token_manager
-            .ensure_access_token(&service_id, &account_id)
+        // Use the token manager to get a valid token (handles refresh).
+        // Identical concurrent requests share one lookup, so a burst of
+        // requests for an expired token refreshes it once.
+        let method = if force_refresh {
+            "force_refresh"
+        } else {
+            "get_token"
+        };
+        let key = RequestDeduplicator::key(method, &service_id, &account_id);
+        self.state
+            .dedup
+            .run(key, || {
+                self.state
+                    .fetch_token(&service_id, &account_id, force_refresh)
+            })
             .await
-        {
-            Ok(token) => {
-                let expires_at = token.expires_at.map(|dt| dt.to_rfc3339());
-                Ok(GetTokenResponse {
-                    token: token.access_token.expose().to_string(),
-                    expires_at,
-                })
-            }
-            Err(e) => {
-                // If no token found, return a more helpful error
-                Err(ErrorObject::owned(
-                    ErrorCode::InternalError.code(),
-                    format!("Failed to get token: {}. You may need to re-authenticate.", e),
-                    None::<()>,
-                ))
-            }
-        }
     }
 
     async fn list_accounts(&self, service: Option<String>) -> RpcResult<ListAccountsResponse> {
         debug!("RPC: list_accounts(service: {:?})", service);
+        metrics::record_request("list_accounts");
 
         let service_filter = service.as_ref().map(ServiceId::new);
         let accounts = self
@@ -285,6 +960,9 @@ impl SigilforgeApiServer for SigilforgeApiImpl {
             .accounts
             .list_accounts(service_filter.as_ref())
             .map_err(internal_error)?;
+        if service_filter.is_none() {
+            metrics::set_accounts_total(accounts.len());
+        }
 
         let filtered: Vec<AccountInfo> = accounts
             .into_iter()
@@ -294,6 +972,7 @@ impl SigilforgeApiServer for SigilforgeApiImpl {
                 scopes: a.scopes,
                 created_at: a.created_at.to_rfc3339(),
                 last_used: a.last_used.map(|dt| dt.to_rfc3339()),
+                source: a.source,
             })
             .collect();
 
@@ -307,14 +986,37 @@ impl SigilforgeApiServer for SigilforgeApiImpl {
         service: String,
         account: String,
         scopes: Vec<String>,
+        revoke_existing: Option<bool>,
     ) -> RpcResult<AddAccountResponse> {
-        info!("RPC: add_account({}/{}, scopes: {:?})", service, account, scopes);
-
-        let new_account = Account::new(
-            ServiceId::new(&service),
-            AccountId::new(&account),
-            scopes,
+        let revoke_existing = revoke_existing.unwrap_or(false);
+        info!(
+            "RPC: add_account({}/{}, scopes: {:?}, revoke_existing: {})",
+            service, account, scopes, revoke_existing
         );
+        metrics::record_request("add_account");
+
+        if self.state.accounts.is_read_only() {
+            return Err(ErrorObject::owned(
+                ErrorCode::InvalidRequest.code(),
+                format!(
+                    "Cannot add account {}/{}: the account store is read-only \
+                     (unset SIGILFORGE_READ_ONLY to allow changes)",
+                    service, account
+                ),
+                None::<()>,
+            ));
+        }
+
+        let (service_id, account_id) = (ServiceId::new(&service), AccountId::new(&account));
+        if revoke_existing {
+            self.state
+                .remove_existing_account(&service_id, &account_id)
+                .await
+                .map_err(internal_error)?;
+        }
+
+        let new_account =
+            Account::new(service_id, account_id, scopes).with_source(CredentialSource::Daemon);
 
         if let Err(e) = self.state.accounts.add_account(new_account) {
             return Err(match e {
@@ -336,6 +1038,7 @@ impl SigilforgeApiServer for SigilforgeApiImpl {
 
     async fn resolve(&self, reference: String) -> RpcResult<ResolveResponse> {
         info!("RPC: resolve({})", reference);
+        metrics::record_request("resolve");
 
         // Parse and validate the reference first
         use sigilforge_core::CredentialRef;
@@ -378,6 +1081,7 @@ impl SigilforgeApiServer for SigilforgeApiImpl {
 
     async fn accounts_status(&self) -> RpcResult<AccountsStatusResponse> {
         debug!("RPC: accounts_status()");
+        metrics::record_request("accounts_status");
 
         let accounts = self
             .state
@@ -386,46 +1090,63 @@ impl SigilforgeApiServer for SigilforgeApiImpl {
             .map_err(internal_error)?;
 
         let now = chrono::Utc::now();
-        let expiry_threshold = chrono::Duration::hours(24);
+        let expiry_threshold =
+            chrono::Duration::from_std(self.state.expiry_warning).unwrap_or(chrono::Duration::MAX);
 
         let mut status_list = Vec::new();
         let mut all_valid = true;
         let mut any_expiring_soon = false;
 
+        // Accounts without a stored token are missing from `tokens`
+        let mut tokens: std::collections::HashMap<_, _> = self
+            .state
+            .token_manager
+            .list_tokens()
+            .await
+            .map_err(internal_error)?
+            .into_iter()
+            .map(|(service, account, info)| ((service, account), info))
+            .collect();
+
         for account in accounts {
-            // Try to get token status for this account
-            let (token_valid, expires_soon, expires_at) = match self
-                .state
-                .token_manager
-                .ensure_access_token(&account.service, &account.id)
-                .await
-            {
-                Ok(token) => {
-                    let expires_soon = token.expires_at.map_or(false, |exp| {
-                        exp.signed_duration_since(now) < expiry_threshold
-                    });
-                    (true, expires_soon, token.expires_at.map(|dt| dt.to_rfc3339()))
+            let key = (account.service.clone(), account.id.clone());
+            let mut status = AccountStatusInfo {
+                service: account.service.to_string(),
+                account: account.id.to_string(),
+                token_valid: false,
+                refreshable: false,
+                expires_soon: false,
+                expires_at: None,
+                error: None,
+            };
+            match tokens.remove(&key) {
+                Some(Ok(info)) => {
+                    status.token_valid = info.active;
+                    status.refreshable = info.refreshable;
+                    status.expires_soon = info
+                        .expires_at
+                        .is_some_and(|exp| exp.signed_duration_since(now) < expiry_threshold);
+                    status.expires_at = info.expires_at.map(|dt| dt.to_rfc3339());
                 }
-                Err(_) => {
-                    // Token retrieval failed - mark as invalid
-                    (false, false, None)
+                Some(Err(e)) => {
+                    warn!(
+                        "Failed to read token for {}/{}: {}",
+                        account.service, account.id, e
+                    );
+                    status.error = Some(e.to_string());
                 }
-            };
+                None => {}
+            }
 
-            if !token_valid {
+            // An expired token that can be refreshed still works
+            if !status.token_valid && !status.refreshable {
                 all_valid = false;
             }
-            if expires_soon {
+            if status.expires_soon {
                 any_expiring_soon = true;
             }
 
-            status_list.push(AccountStatusInfo {
-                service: account.service.to_string(),
-                account: account.id.to_string(),
-                token_valid,
-                expires_soon,
-                expires_at,
-            });
+            status_list.push(status);
         }
 
         Ok(AccountsStatusResponse {
@@ -434,6 +1155,67 @@ impl SigilforgeApiServer for SigilforgeApiImpl {
             any_expiring_soon,
         })
     }
+
+    async fn health_check(&self) -> RpcResult<HealthResponse> {
+        debug!("RPC: health_check()");
+        metrics::record_request("health_check");
+
+        let account_count = self
+            .state
+            .accounts
+            .list_accounts(None)
+            .map_err(internal_error)?
+            .len();
+
+        let backend = self.state.token_manager.store.backend_name();
+        let ok = !(self.state.prefer_keyring && backend != "keyring");
+
+        Ok(HealthResponse {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_secs: self.state.started_at.elapsed().as_secs(),
+            account_count,
+            backend: backend.to_string(),
+            ok,
+        })
+    }
+
+    async fn validate_providers(&self) -> RpcResult<ValidateProvidersResponse> {
+        debug!("RPC: validate_providers()");
+        metrics::record_request("validate_providers");
+
+        let registry = self.state.token_manager.providers();
+        let mut ids = registry.list_ids();
+        ids.sort_unstable();
+        let providers = ids
+            .into_iter()
+            .map(|id| {
+                let problems = registry
+                    .get(id)
+                    .and_then(|config| config.validate().err())
+                    .unwrap_or_default();
+                let (warnings, errors): (Vec<_>, Vec<_>) =
+                    problems.iter().partition(|problem| problem.is_warning());
+                ProviderValidationInfo {
+                    id: id.to_string(),
+                    errors: errors.iter().map(ToString::to_string).collect(),
+                    warnings: warnings.iter().map(ToString::to_string).collect(),
+                }
+            })
+            .collect();
+        let skipped_files = registry
+            .skipped_files()
+            .iter()
+            .map(|(path, error)| SkippedProviderFile {
+                path: path.display().to_string(),
+                error: error.clone(),
+            })
+            .collect();
+
+        Ok(ValidateProvidersResponse {
+            providers,
+            skipped_files,
+        })
+    }
 }
 
 fn internal_error<E: std::fmt::Display>(err: E) -> ErrorObject<'static> {
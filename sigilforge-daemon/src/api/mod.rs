@@ -1,12 +1,22 @@
 //! JSON-RPC API for daemon IPC.
 //!
 //! This module provides a JSON-RPC interface for communication between
-//! the sigilforge-cli client and the sigilforged daemon.
+//! the sigilforge-cli client and the sigilforged daemon, and with the
+//! `grpc` feature the same API over gRPC.
 
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod handlers;
 pub mod server;
+pub mod tls;
 
 #[allow(unused_imports)]
-pub use handlers::{ApiState, AccountInfo, AddAccountResponse, GetTokenResponse, ListAccountsResponse, ResolveResponse};
+pub use handlers::{ApiState, AccountInfo, AddAccountResponse, GetTokenResponse, HealthResponse, ListAccountsResponse, PrewarmSummary, RequestDeduplicator, RequestKey, ResolveResponse, StartupValidation, ValidateProvidersResponse};
 #[allow(unused_imports)]
-pub use server::{start_server, ServerHandle};
+pub use server::{
+    start_server, start_server_with_options, start_tcp_server, PeerPolicy, ServerHandle,
+    SocketOptions, REQUEST_ID_FIELD,
+};
+#[cfg(feature = "grpc")]
+#[allow(unused_imports)]
+pub use grpc::{start_grpc_server, SigilforgeGrpcService};
@@ -1,14 +1,25 @@
-//! JSON-RPC server implementation with Unix socket support.
+//! JSON-RPC server implementation with Unix socket and TCP support.
 
 use super::handlers::{ApiState, SigilforgeApiImpl, SigilforgeApiServer};
+use crate::acl::PeerContext;
+use crate::audit::{self, AuditEntry};
+use crate::config::TlsConfig;
+use crate::metrics;
 use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
 use std::path::Path;
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::{UnixListener, UnixStream};
-use tokio::sync::{Mutex, Semaphore};
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, UnixListener, UnixStream};
+use tokio::sync::{mpsc, Mutex, OwnedSemaphorePermit, Semaphore};
 use tokio::task::JoinHandle;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, trace, warn, Span};
+use uuid::Uuid;
+
+/// Non-standard response field carrying the request's correlation ID
+pub const REQUEST_ID_FIELD: &str = "x-request-id";
 
 /// Maximum request size (1MB) to prevent memory exhaustion attacks
 const MAX_REQUEST_SIZE: usize = 1_048_576;
@@ -16,10 +27,85 @@ const MAX_REQUEST_SIZE: usize = 1_048_576;
 /// Maximum concurrent connections to prevent resource exhaustion
 const MAX_CONNECTIONS: usize = 100;
 
+/// Default socket permissions (owner read/write only)
+pub const DEFAULT_SOCKET_MODE: u32 = 0o600;
+
+/// Ownership and permission options applied to the Unix socket after binding.
+#[derive(Debug, Clone)]
+pub struct SocketOptions {
+    /// File mode for the socket (e.g., `0o660` for group access)
+    pub mode: u32,
+    /// Group that should own the socket; its members may connect
+    pub group: Option<String>,
+    /// Users besides the daemon owner that may connect, by UID
+    pub allowed_uids: Option<Vec<u32>>,
+    /// Accept connections from root (UID 0)
+    pub allow_root: bool,
+}
+
+impl Default for SocketOptions {
+    fn default() -> Self {
+        Self {
+            mode: DEFAULT_SOCKET_MODE,
+            group: None,
+            allowed_uids: None,
+            allow_root: false,
+        }
+    }
+}
+
+/// Which local users may connect to the Unix socket, checked against each
+/// peer's credentials.
+///
+/// The daemon owner is always accepted. Root is accepted only with
+/// `allow_root` (or when root owns the daemon); anyone else must be in
+/// `allowed_uids` or be a member of the socket group.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeerPolicy {
+    /// UID the daemon runs as
+    pub owner_uid: u32,
+    /// Other UIDs that may connect
+    pub allowed_uids: Option<Vec<u32>>,
+    /// Accept root peers
+    pub allow_root: bool,
+    /// Group whose members may connect
+    pub allowed_gid: Option<u32>,
+}
+
+impl PeerPolicy {
+    /// Check whether a peer with this UID and primary GID may connect.
+    pub fn permits(&self, peer_uid: u32, peer_gid: u32) -> bool {
+        if peer_uid == self.owner_uid {
+            return true;
+        }
+        if peer_uid == 0 {
+            return self.allow_root;
+        }
+        if self
+            .allowed_uids
+            .as_ref()
+            .is_some_and(|uids| uids.contains(&peer_uid))
+        {
+            return true;
+        }
+        #[cfg(unix)]
+        {
+            self.allowed_gid
+                .is_some_and(|gid| peer_in_group(peer_uid, peer_gid, gid))
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = peer_gid;
+            false
+        }
+    }
+}
+
 /// Handle to a running RPC server
 pub struct ServerHandle {
     shutdown: Arc<Mutex<Option<tokio::sync::mpsc::Sender<()>>>>,
     join_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    local_addr: Option<SocketAddr>,
 }
 
 /// Start the JSON-RPC server on a Unix socket.
@@ -33,28 +119,22 @@ pub struct ServerHandle {
 ///
 /// A handle to the running server that can be used to stop it.
 pub async fn start_server(socket_path: &Path, state: ApiState) -> Result<ServerHandle> {
-    // Remove existing socket if present (ignore errors - may not exist)
-    let _ = std::fs::remove_file(socket_path);
-
-    // Ensure parent directory exists
-    if let Some(parent) = socket_path.parent() {
-        std::fs::create_dir_all(parent)
-            .with_context(|| format!("Failed to create socket directory {:?}", parent))?;
-    }
+    start_server_with_options(socket_path, state, SocketOptions::default()).await
+}
 
+/// Start the JSON-RPC server with custom socket ownership and permissions.
+///
+/// When `options.group` is set, the socket is chowned to that group and
+/// connections from its members are accepted alongside the daemon owner.
+/// See [`PeerPolicy`] for how `allowed_uids` and `allow_root` apply.
+pub async fn start_server_with_options(
+    socket_path: &Path,
+    state: ApiState,
+    options: SocketOptions,
+) -> Result<ServerHandle> {
     info!("Starting JSON-RPC server on {:?}", socket_path);
-
-    // Create Unix listener
-    let listener = UnixListener::bind(socket_path)
-        .with_context(|| format!("Failed to bind Unix socket at {:?}", socket_path))?;
-
-    // Set socket permissions to 0600 (owner read/write only) for security
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))
-            .with_context(|| format!("Failed to set socket permissions at {:?}", socket_path))?;
-    }
+    let (listener, policy) = bind_socket(socket_path, options)?;
+    let policy = Arc::new(policy);
 
     // Create the RPC API implementation
     let api = Arc::new(SigilforgeApiImpl::new(state));
@@ -78,12 +158,15 @@ pub async fn start_server(socket_path: &Path, state: ApiState) -> Result<ServerH
                     match result {
                         Ok((stream, _addr)) => {
                             let api = api.clone();
+                            let policy = policy.clone();
                             let permit = semaphore.clone().try_acquire_owned();
                             match permit {
                                 Ok(permit) => {
                                     tokio::spawn(async move {
                                         let _permit = permit; // Held for connection lifetime
-                                        if let Err(e) = handle_connection(stream, api).await {
+                                        if let Err(e) =
+                                            handle_connection(stream, api, &policy).await
+                                        {
                                             warn!("Connection handler error: {}", e);
                                         }
                                     });
@@ -109,96 +192,431 @@ pub async fn start_server(socket_path: &Path, state: ApiState) -> Result<ServerH
     let handle = ServerHandle {
         shutdown: Arc::new(Mutex::new(Some(handle_tx))),
         join_handle: Arc::new(Mutex::new(Some(server_task))),
+        local_addr: None,
     };
 
     Ok(handle)
 }
 
-/// Handle a single connection
+/// Bind a Unix socket at `socket_path` with the ownership and permissions in
+/// `options`, replacing any socket left there, and return the listener with
+/// the policy its peers are checked against.
+pub(super) fn bind_socket(
+    socket_path: &Path,
+    options: SocketOptions,
+) -> Result<(UnixListener, PeerPolicy)> {
+    // Remove existing socket if present (ignore errors - may not exist)
+    let _ = std::fs::remove_file(socket_path);
+
+    // Ensure parent directory exists
+    if let Some(parent) = socket_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create socket directory {:?}", parent))?;
+    }
+
+    // Create Unix listener
+    let listener = UnixListener::bind(socket_path)
+        .with_context(|| format!("Failed to bind Unix socket at {:?}", socket_path))?;
+
+    // Look up the socket group before applying the mode: a group-readable
+    // socket must not be left with whatever group it was created with
+    #[cfg(unix)]
+    let group = match &options.group {
+        Some(name) => {
+            let group = nix::unistd::Group::from_name(name)
+                .with_context(|| format!("Failed to look up group {:?}", name))?;
+            if group.is_none() {
+                error!(
+                    "Socket group {:?} does not exist; restricting the socket to its owner",
+                    name
+                );
+            }
+            group
+        }
+        None => None,
+    };
+
+    // Set socket permissions (0600 by default: owner read/write only)
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = if options.group.is_some() && group.is_none() {
+            DEFAULT_SOCKET_MODE
+        } else {
+            options.mode
+        };
+        std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(mode))
+            .with_context(|| format!("Failed to set socket permissions at {:?}", socket_path))?;
+    }
+
+    // Hand the socket to the configured group so its members can connect
+    #[cfg(unix)]
+    let allowed_gid: Option<u32> = match group {
+        Some(group) => {
+            nix::unistd::chown(socket_path, None, Some(group.gid))
+                .with_context(|| format!("Failed to set socket group to {:?}", group.name))?;
+            info!("Socket group set to {} (gid {})", group.name, group.gid);
+            Some(group.gid.as_raw())
+        }
+        None => None,
+    };
+    #[cfg(not(unix))]
+    let allowed_gid: Option<u32> = None;
+
+    #[cfg(unix)]
+    let owner_uid = unsafe { libc::getuid() };
+    #[cfg(not(unix))]
+    let owner_uid = 0;
+    let policy = PeerPolicy {
+        owner_uid,
+        allowed_uids: options.allowed_uids,
+        allow_root: options.allow_root,
+        allowed_gid,
+    };
+    Ok((listener, policy))
+}
+
+/// Start the JSON-RPC server on a TCP address.
+///
+/// Every connection is wrapped in TLS, with client certificate verification
+/// if `client_ca_path` is set. Bind to port 0 and use
+/// [`ServerHandle::local_addr`] to discover the assigned port.
+///
+/// TCP peers have no UID or GID, so only ACL rules without `peer_uid` and
+/// `peer_gid` apply to them.
+pub async fn start_tcp_server(
+    addr: SocketAddr,
+    state: ApiState,
+    tls: &TlsConfig,
+) -> Result<ServerHandle> {
+    let acceptor = super::tls::build_acceptor(tls)?;
+
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind TCP listener at {}", addr))?;
+    let local_addr = listener.local_addr()?;
+
+    let mode = match tls.client_ca_path {
+        Some(_) => "mutual TLS",
+        None => "TLS",
+    };
+    info!(
+        "Starting JSON-RPC server on tcp://{} ({})",
+        local_addr, mode
+    );
+
+    let api = Arc::new(SigilforgeApiImpl::new(state));
+    let semaphore = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<()>(1);
+
+    let server_task: JoinHandle<()> = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = rx.recv() => {
+                    debug!("TCP server shutdown signal received");
+                    break;
+                }
+                result = listener.accept() => {
+                    let (stream, peer) = match result {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            warn!("Failed to accept TCP connection: {}", e);
+                            continue;
+                        }
+                    };
+
+                    let Ok(permit) = semaphore.clone().try_acquire_owned() else {
+                        warn!("Connection limit reached, rejecting connection from {}", peer);
+                        continue;
+                    };
+
+                    let api = api.clone();
+                    let acceptor = acceptor.clone();
+                    tokio::spawn(async move {
+                        let _permit = permit; // Held for connection lifetime
+                        let result = match acceptor.accept(stream).await {
+                            Ok(tls_stream) => {
+                                serve_stream(tls_stream, api, PeerContext::default()).await
+                            }
+                            Err(e) => Err(anyhow::anyhow!("TLS handshake failed: {}", e)),
+                        };
+                        if let Err(e) = result {
+                            warn!("TCP connection from {} failed: {}", peer, e);
+                        }
+                    });
+                }
+            }
+        }
+    });
+
+    Ok(ServerHandle::new(tx, server_task, Some(local_addr)))
+}
+
+/// Check whether a peer belongs to the given group, either as its primary
+/// group or as a supplementary member.
+///
+/// Supplementary groups come from the peer user's group list, as the
+/// kernel would compute it at login, so groups from NSS sources (e.g.
+/// LDAP) count too. Where that list is unavailable, the group's member
+/// names are checked instead.
+#[cfg(unix)]
+fn peer_in_group(peer_uid: u32, peer_gid: u32, group_gid: u32) -> bool {
+    use nix::unistd::{Gid, Group, Uid, User};
+
+    if peer_gid == group_gid {
+        return true;
+    }
+
+    let user = match User::from_uid(Uid::from_raw(peer_uid)) {
+        Ok(Some(user)) => user,
+        _ => return false,
+    };
+
+    #[cfg(not(any(target_vendor = "apple", target_os = "redox", target_os = "haiku")))]
+    if let Some(groups) = supplementary_groups(&user.name, peer_gid) {
+        return groups.contains(&Gid::from_raw(group_gid));
+    }
+
+    match Group::from_gid(Gid::from_raw(group_gid)) {
+        Ok(Some(group)) => group.mem.iter().any(|member| *member == user.name),
+        _ => false,
+    }
+}
+
+/// Groups of the user `name` whose primary group is `gid`, including `gid`.
+#[cfg(all(
+    unix,
+    not(any(target_vendor = "apple", target_os = "redox", target_os = "haiku"))
+))]
+fn supplementary_groups(name: &str, gid: u32) -> Option<Vec<nix::unistd::Gid>> {
+    let name = std::ffi::CString::new(name).ok()?;
+    nix::unistd::getgrouplist(&name, nix::unistd::Gid::from_raw(gid)).ok()
+}
+
+/// Handle a single Unix socket connection
 async fn handle_connection(
-    mut stream: UnixStream,
+    stream: UnixStream,
     api: Arc<SigilforgeApiImpl>,
+    policy: &PeerPolicy,
 ) -> Result<()> {
     // Verify peer credentials on Unix (security check)
     #[cfg(unix)]
-    {
+    let peer = {
         let peer_cred = stream.peer_cred()?;
-        let my_uid = unsafe { libc::getuid() };
-        if peer_cred.uid() != my_uid {
-            anyhow::bail!(
-                "Connection from unauthorized user (UID {} != {})",
+        if !policy.permits(peer_cred.uid(), peer_cred.gid()) {
+            warn!(
+                "Rejected connection from unauthorized user (UID {}, GID {})",
                 peer_cred.uid(),
-                my_uid
+                peer_cred.gid()
             );
+            return Ok(());
         }
-    }
+        PeerContext::unix(peer_cred.uid(), peer_cred.gid())
+    };
+    #[cfg(not(unix))]
+    let peer = {
+        let _ = policy;
+        PeerContext::default()
+    };
+
+    serve_stream(stream, api, peer).await
+}
+
+/// Serve newline-delimited JSON-RPC requests until the peer disconnects
+///
+/// Clients may pipeline requests: up to `max_pipelined_requests` of them run
+/// at once, and responses are written in request order (or as they complete
+/// when ordered pipelining is disabled). Every request is checked against
+/// the ACL as coming from `peer`.
+async fn serve_stream<S>(stream: S, api: Arc<SigilforgeApiImpl>, peer: PeerContext) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let _connection = metrics::ConnectionGuard::new();
+    let (reader, writer) = tokio::io::split(stream);
+    let (tx, rx) = mpsc::unbounded_channel();
+    let ordered = api.ordered_pipelining();
+
+    tokio::try_join!(
+        read_requests(reader, api, tx, peer),
+        write_responses(writer, rx, ordered)
+    )?;
+    Ok(())
+}
+
+/// A finished response and the pipeline slot its request occupied
+struct Completed {
+    /// Position of the request on the connection
+    seq: u64,
+    response: serde_json::Value,
+    /// Released once the response has been written
+    _slot: OwnedSemaphorePermit,
+}
 
-    let (reader, mut writer) = stream.split();
+/// Read requests and spawn a task for each, until the peer stops sending
+/// or sends nothing for the configured idle timeout
+///
+/// Reading pauses while every pipeline slot is taken, so a client cannot
+/// queue more than `max_pipelined_requests` unanswered requests.
+async fn read_requests<R>(
+    reader: R,
+    api: Arc<SigilforgeApiImpl>,
+    responses: mpsc::UnboundedSender<Completed>,
+    peer: PeerContext,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    let slots = Arc::new(Semaphore::new(api.max_pipelined_requests().max(1)));
+    let idle_timeout = api.idle_timeout();
     let mut reader = BufReader::new(reader);
     let mut line = String::new();
+    let mut last_activity = Instant::now();
 
-    loop {
+    for seq in 0u64.. {
         line.clear();
-        let n = reader.read_line(&mut line).await?;
+        let deadline = tokio::time::Instant::from_std(last_activity + idle_timeout);
+        let n = match tokio::time::timeout_at(deadline, reader.read_line(&mut line)).await {
+            Ok(read) => read?,
+            Err(_) => {
+                // Requests already spawned still get their responses written
+                debug!("connection idle timeout");
+                break;
+            }
+        };
+        last_activity = Instant::now();
 
         if n == 0 {
             // Connection closed
             break;
         }
 
+        let slot = slots.clone().acquire_owned().await?;
+        let request_id = Uuid::new_v4();
+
         // Check request size to prevent memory exhaustion
-        if line.len() > MAX_REQUEST_SIZE {
-            let error_response = serde_json::json!({
-                "jsonrpc": "2.0",
-                "error": {
-                    "code": -32600,
-                    "message": "Request too large"
-                },
-                "id": null
-            });
-            writer.write_all(error_response.to_string().as_bytes()).await?;
-            writer.write_all(b"\n").await?;
-            writer.flush().await?;
-            continue;
-        }
+        let request = if line.len() > MAX_REQUEST_SIZE {
+            Err(rejection(-32600, "Request too large".to_string()))
+        } else {
+            debug!("Received request: {}", line.trim());
+            serde_json::from_str::<serde_json::Value>(&line)
+                .map_err(|e| rejection(-32700, format!("Parse error: {}", e)))
+        };
 
-        debug!("Received request: {}", line.trim());
-
-        // Parse JSON-RPC request
-        let request: serde_json::Value = match serde_json::from_str(&line) {
-            Ok(req) => req,
-            Err(e) => {
-                let error_response = serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "error": {
-                        "code": -32700,
-                        "message": format!("Parse error: {}", e)
-                    },
-                    "id": null
+        let request = match request {
+            Ok(request) => request,
+            Err(mut response) => {
+                attach_request_id(&mut response, request_id, &api);
+                let _ = responses.send(Completed {
+                    seq,
+                    response,
+                    _slot: slot,
                 });
-                writer.write_all(error_response.to_string().as_bytes()).await?;
-                writer.write_all(b"\n").await?;
-                writer.flush().await?;
                 continue;
             }
         };
 
-        // Process request and send response
-        let response = process_request(request, &api).await;
-        writer.write_all(response.to_string().as_bytes()).await?;
-        writer.write_all(b"\n").await?;
-        writer.flush().await?;
+        let api = api.clone();
+        let responses = responses.clone();
+        tokio::spawn(async move {
+            let mut response = process_request(request, &api, request_id, &peer).await;
+            attach_request_id(&mut response, request_id, &api);
+            // The writer is gone if the connection failed; nothing to do
+            let _ = responses.send(Completed {
+                seq,
+                response,
+                _slot: slot,
+            });
+        });
+    }
+
+    Ok(())
+}
+
+/// Write responses until every request task has finished
+///
+/// In ordered mode, responses that complete early wait in a buffer until
+/// all earlier requests have been answered.
+async fn write_responses<W>(
+    mut writer: W,
+    mut responses: mpsc::UnboundedReceiver<Completed>,
+    ordered: bool,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut buffered: BTreeMap<u64, Completed> = BTreeMap::new();
+    let mut next_seq = 0;
+
+    while let Some(completed) = responses.recv().await {
+        if !ordered {
+            write_response(&mut writer, &completed.response).await?;
+            continue;
+        }
+
+        buffered.insert(completed.seq, completed);
+        while let Some(completed) = buffered.remove(&next_seq) {
+            write_response(&mut writer, &completed.response).await?;
+            next_seq += 1;
+        }
     }
 
     Ok(())
 }
 
+async fn write_response<W>(writer: &mut W, response: &serde_json::Value) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer.write_all(response.to_string().as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Error response for a line that could not be read as a request
+fn rejection(code: i64, message: String) -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": code,
+            "message": message
+        },
+        "id": null
+    })
+}
+
+/// Add the `x-request-id` field to a response if the daemon is configured to
+fn attach_request_id(response: &mut serde_json::Value, request_id: Uuid, api: &SigilforgeApiImpl) {
+    if !api.emit_request_ids() {
+        return;
+    }
+    if let Some(object) = response.as_object_mut() {
+        object.insert(
+            REQUEST_ID_FIELD.to_string(),
+            serde_json::Value::String(request_id.to_string()),
+        );
+    }
+}
+
 /// Process a JSON-RPC request
+///
+/// Runs inside an `rpc` span carrying `request_id` and `method`, so handler
+/// logs can be correlated with the response.
+#[tracing::instrument(
+    name = "rpc",
+    skip_all,
+    fields(request_id = tracing::field::Empty, method = tracing::field::Empty)
+)]
 async fn process_request(
     request: serde_json::Value,
     api: &Arc<SigilforgeApiImpl>,
+    request_id: Uuid,
+    peer: &PeerContext,
 ) -> serde_json::Value {
-    use jsonrpsee::types::ErrorObject;
+    let span = Span::current();
+    span.record("request_id", tracing::field::display(request_id));
+    let started = Instant::now();
 
     let id = request.get("id").cloned().unwrap_or(serde_json::Value::Null);
     let method = match request.get("method").and_then(|m| m.as_str()) {
@@ -215,16 +633,73 @@ async fn process_request(
         }
     };
 
+    span.record("method", method);
+    trace!(%request_id, "Received {} request", method);
+
     let params = request.get("params").cloned().unwrap_or(serde_json::Value::Array(vec![]));
 
-    // Call the appropriate method
-    let result = match method {
+    let result = match api.authorize(method, &params, peer) {
+        Ok(()) => call_method(method, &params, api).await,
+        Err(e) => Err(e),
+    };
+
+    info!(
+        %request_id,
+        duration_ms = started.elapsed().as_millis() as u64,
+        success = result.is_ok(),
+        "Completed {} request",
+        method
+    );
+
+    if let Some(audit_log) = api.audit_log() {
+        let (service, account) = audit::request_target(method, &params);
+        audit_log.record(&AuditEntry {
+            timestamp: chrono::Utc::now(),
+            request_id: request_id.to_string(),
+            method: method.to_string(),
+            service,
+            account,
+            success: result.is_ok(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+
+    match result {
+        Ok(value) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "result": value,
+            "id": id
+        }),
+        Err(error) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "error": {
+                "code": error.code(),
+                "message": error.message()
+            },
+            "id": id
+        }),
+    }
+}
+
+/// Call the handler for `method` with positional `params`
+async fn call_method(
+    method: &str,
+    params: &serde_json::Value,
+    api: &SigilforgeApiImpl,
+) -> Result<serde_json::Value, jsonrpsee::types::ErrorObjectOwned> {
+    use jsonrpsee::types::ErrorObject;
+
+    match method {
         "get_token" => {
             let params_array = params.as_array();
             if let Some(arr) = params_array {
                 if arr.len() >= 2 {
                     if let (Some(service), Some(account)) = (arr[0].as_str(), arr[1].as_str()) {
-                        match api.get_token(service.to_string(), account.to_string()).await {
+                        let force_refresh = arr.get(2).and_then(|v| v.as_bool());
+                        match api
+                            .get_token(service.to_string(), account.to_string(), force_refresh)
+                            .await
+                        {
                             Ok(resp) => Ok(serde_json::to_value(resp).unwrap()),
                             Err(e) => Err(e),
                         }
@@ -258,7 +733,8 @@ async fn process_request(
                             .iter()
                             .filter_map(|s| s.as_str().map(|s| s.to_string()))
                             .collect();
-                        match api.add_account(service.to_string(), account.to_string(), scopes_vec).await {
+                        let revoke_existing = arr.get(3).and_then(|v| v.as_bool());
+                        match api.add_account(service.to_string(), account.to_string(), scopes_vec, revoke_existing).await {
                             Ok(resp) => Ok(serde_json::to_value(resp).unwrap()),
                             Err(e) => Err(e),
                         }
@@ -291,27 +767,41 @@ async fn process_request(
                 Err(e) => Err(e),
             }
         }
+        "health_check" => {
+            match api.health_check().await {
+                Ok(resp) => Ok(serde_json::to_value(resp).unwrap()),
+                Err(e) => Err(e),
+            }
+        }
+        "validate_providers" => {
+            match api.validate_providers().await {
+                Ok(resp) => Ok(serde_json::to_value(resp).unwrap()),
+                Err(e) => Err(e),
+            }
+        }
         _ => Err(ErrorObject::owned(-32601, "Method not found", None::<()>)),
-    };
-
-    match result {
-        Ok(value) => serde_json::json!({
-            "jsonrpc": "2.0",
-            "result": value,
-            "id": id
-        }),
-        Err(error) => serde_json::json!({
-            "jsonrpc": "2.0",
-            "error": {
-                "code": error.code(),
-                "message": error.message()
-            },
-            "id": id
-        }),
     }
 }
 
 impl ServerHandle {
+    /// Handle to `task`, which stops once `shutdown` receives a message
+    pub(super) fn new(
+        shutdown: mpsc::Sender<()>,
+        task: JoinHandle<()>,
+        local_addr: Option<SocketAddr>,
+    ) -> Self {
+        Self {
+            shutdown: Arc::new(Mutex::new(Some(shutdown))),
+            join_handle: Arc::new(Mutex::new(Some(task))),
+            local_addr,
+        }
+    }
+
+    /// Address the TCP listener is bound to (`None` for Unix sockets)
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
     /// Stop the server
     pub async fn stop(&self) -> Result<()> {
         if let Some(tx) = self.shutdown.lock().await.take() {
@@ -0,0 +1,63 @@
+//! TLS setup for the TCP transport.
+
+use crate::config::TlsConfig;
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+/// Build a TLS acceptor from PEM files.
+///
+/// When `client_ca_path` is set, clients must present a certificate signed
+/// by one of those CAs.
+pub fn build_acceptor(config: &TlsConfig) -> Result<TlsAcceptor> {
+    let certs = load_certs(&config.cert_path)?;
+    let key = load_private_key(&config.key_path)?;
+
+    let builder = ServerConfig::builder();
+    let builder = match &config.client_ca_path {
+        Some(ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(ca_path)? {
+                roots
+                    .add(cert)
+                    .with_context(|| format!("Invalid client CA certificate in {:?}", ca_path))?;
+            }
+            let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                .build()
+                .context("Failed to build client certificate verifier")?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let server_config = builder
+        .with_single_cert(certs, key)
+        .context("Server certificate and key do not match")?;
+
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<std::io::Result<Vec<_>>>()
+        .with_context(|| format!("Failed to parse certificates in {:?}", path))?;
+
+    if certs.is_empty() {
+        anyhow::bail!("No certificates found in {:?}", path);
+    }
+    Ok(certs)
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .with_context(|| format!("Failed to parse private key in {:?}", path))?
+        .ok_or_else(|| anyhow::anyhow!("No private key found in {:?}", path))
+}
@@ -0,0 +1,195 @@
+//! Request audit log.
+//!
+//! When `audit_log_path` is configured, the daemon appends one JSON object
+//! per handled request to that file; `sigilforge audit` reads it back.
+//! Only the method and the service/account it targets are recorded, never
+//! tokens or other parameters.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sigilforge_core::CredentialRef;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::warn;
+
+/// One line of the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the request completed
+    pub timestamp: DateTime<Utc>,
+    /// Correlation ID of the request (see `emit_request_ids`)
+    pub request_id: String,
+    /// JSON-RPC method name
+    pub method: String,
+    /// Service the request targeted, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    /// Account the request targeted, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Whether the request succeeded
+    pub success: bool,
+    /// Time taken to handle the request
+    pub duration_ms: u64,
+}
+
+/// Append-only audit log file shared by every connection.
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    /// Open `path` for appending, creating it (owner read/write only) and
+    /// its parent directories if needed.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create audit log directory {:?}", parent))?;
+        }
+
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let file = options
+            .open(path)
+            .with_context(|| format!("Failed to open audit log {:?}", path))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Append `entry`; failures are logged rather than failing the request.
+    pub fn record(&self, entry: &AuditEntry) {
+        let mut line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to serialize audit entry: {}", e);
+                return;
+            }
+        };
+        line.push('\n');
+
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = file.write_all(line.as_bytes()) {
+            warn!("Failed to write audit log {:?}: {}", self.path, e);
+        }
+    }
+}
+
+/// Service and account targeted by a request, taken from its positional
+/// `params`; `resolve` targets the account of its reference.
+///
+/// Shared by the audit log and the ACL, so both see the same target.
+pub fn request_target(
+    method: &str,
+    params: &serde_json::Value,
+) -> (Option<String>, Option<String>) {
+    let param = |index: usize| {
+        params
+            .as_array()
+            .and_then(|arr| arr.get(index))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+
+    match method {
+        "get_token" | "add_account" => (param(0), param(1)),
+        "list_accounts" => (param(0), None),
+        "resolve" => match param(0).and_then(|uri| CredentialRef::from_auth_uri(&uri).ok()) {
+            Some(reference) => (
+                Some(reference.service.to_string()),
+                Some(reference.account.to_string()),
+            ),
+            None => (None, None),
+        },
+        _ => (None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn entry(method: &str, service: Option<&str>) -> AuditEntry {
+        AuditEntry {
+            timestamp: Utc::now(),
+            request_id: "00000000-0000-0000-0000-000000000000".to_string(),
+            method: method.to_string(),
+            service: service.map(|s| s.to_string()),
+            account: None,
+            success: true,
+            duration_ms: 3,
+        }
+    }
+
+    #[test]
+    fn test_record_appends_json_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("logs/audit.log");
+
+        let log = AuditLog::open(&path).unwrap();
+        log.record(&entry("get_token", Some("github")));
+        log.record(&entry("health_check", None));
+
+        // Reopening appends rather than truncating
+        AuditLog::open(&path)
+            .unwrap()
+            .record(&entry("list_accounts", None));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<AuditEntry> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].service.as_deref(), Some("github"));
+        assert_eq!(entries[2].method, "list_accounts");
+        assert!(!contents.lines().nth(1).unwrap().contains("service"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_log_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("audit.log");
+        AuditLog::open(&path).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_request_target() {
+        let params = json!(["github", "work", ["repo"]]);
+        assert_eq!(
+            request_target("get_token", &params),
+            (Some("github".to_string()), Some("work".to_string()))
+        );
+        assert_eq!(
+            request_target("list_accounts", &json!(["github"])),
+            (Some("github".to_string()), None)
+        );
+        assert_eq!(
+            request_target("resolve", &json!(["auth://github/work/token"])),
+            (Some("github".to_string()), Some("work".to_string()))
+        );
+        assert_eq!(request_target("resolve", &json!(["bad"])), (None, None));
+        assert_eq!(request_target("health_check", &json!([])), (None, None));
+        assert_eq!(request_target("get_token", &json!({})), (None, None));
+    }
+}
@@ -1,13 +1,25 @@
 //! Daemon configuration handling.
 
+use crate::acl::AclRule;
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_core::provider::ProviderRegistry;
+use sigilforge_core::token_manager;
+use std::ffi::OsString;
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use tracing::warn;
+
+/// Environment variable overriding `socket_path`.
+pub const SOCKET_ENV_VAR: &str = "SIGILFORGE_SOCKET";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DaemonConfig {
     /// Path to the Unix socket (Linux/macOS) or named pipe (Windows).
+    ///
+    /// Overridden by the `SIGILFORGE_SOCKET` environment variable.
     pub socket_path: PathBuf,
 
     /// Path to the configuration file that was loaded.
@@ -17,15 +29,341 @@ pub struct DaemonConfig {
     /// Directory for storing account metadata.
     pub data_dir: PathBuf,
 
+    /// age identity that accounts.json is encrypted to, as with `sigilforge
+    /// --encrypted --identity-file`.
+    ///
+    /// Required once the store has been encrypted; unset, the store is read
+    /// as plaintext.
+    #[serde(default)]
+    pub account_identity_path: Option<PathBuf>,
+
     /// Logging level.
     #[serde(default = "default_log_level")]
     pub log_level: String,
+
+    /// Group that should own the socket (e.g., "developers").
+    ///
+    /// Members of this group are allowed to connect to the daemon. If the
+    /// group does not exist, the error is logged and the socket is created
+    /// with mode `0o600` instead of `socket_mode`.
+    #[serde(default)]
+    pub socket_group: Option<String>,
+
+    /// File mode for the socket (default: `0o600`).
+    #[serde(default = "default_socket_mode")]
+    pub socket_mode: u32,
+
+    /// UIDs of other users allowed to connect, e.g. for a daemon shared on
+    /// a multi-user system.
+    ///
+    /// The daemon owner is always allowed. The socket mode must also let
+    /// these users open the socket.
+    #[serde(default)]
+    pub allowed_uids: Option<Vec<u32>>,
+
+    /// Accept connections from root (default: false).
+    #[serde(default)]
+    pub allow_root: bool,
+
+    /// Additionally listen for JSON-RPC over TCP on this address.
+    ///
+    /// Requires `tls`, even on loopback addresses. TCP peers carry no UID
+    /// the daemon can check, so set `client_ca_path` to only admit clients
+    /// with a trusted certificate.
+    #[serde(default)]
+    pub listen_tcp: Option<SocketAddr>,
+
+    /// TLS settings for the TCP listener.
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+
+    /// Add an `x-request-id` field to every JSON-RPC response.
+    ///
+    /// The same ID is recorded on the request's log span, so client-side
+    /// errors can be matched to daemon logs. Off by default since the field
+    /// is not part of JSON-RPC 2.0.
+    #[serde(default)]
+    pub emit_request_ids: bool,
+
+    /// Directories of `*.toml` provider files merged over the built-in
+    /// providers on startup.
+    ///
+    /// Directories are applied in order and files within each in filename
+    /// order; later definitions override earlier ones with the same `id`.
+    #[serde(default)]
+    pub provider_dirs: Vec<PathBuf>,
+
+    /// Additionally serve the API over gRPC on this Unix socket.
+    ///
+    /// The socket gets the same `socket_mode` and `socket_group` as
+    /// `socket_path`, its peers are checked against `allowed_uids` and
+    /// `allow_root`, and its requests against the `acl`. Requires the daemon
+    /// to be built with the `grpc` feature.
+    #[serde(default)]
+    pub grpc_socket_path: Option<PathBuf>,
+
+    /// Serve Prometheus metrics over HTTP on this address.
+    ///
+    /// Requires the daemon to be built with the `metrics` feature.
+    #[serde(default)]
+    pub metrics_addr: Option<SocketAddr>,
+
+    /// Maximum number of requests from one connection processed at once.
+    ///
+    /// Clients may pipeline requests without waiting for each response;
+    /// set to 1 to handle each connection's requests one at a time.
+    #[serde(default = "default_max_pipelined_requests")]
+    pub max_pipelined_requests: usize,
+
+    /// Write pipelined responses in the order their requests arrived.
+    ///
+    /// When disabled, each response is written as soon as it completes and
+    /// clients must match responses to requests by their `id`.
+    #[serde(default = "default_ordered_pipelining")]
+    pub ordered_pipelining: bool,
+
+    /// Append a JSON line for every handled request to this file.
+    ///
+    /// Read it with `sigilforge audit`.
+    #[serde(default)]
+    pub audit_log_path: Option<PathBuf>,
+
+    /// Close connections that send no request for this many seconds.
+    ///
+    /// A request still being processed does not count as activity, but its
+    /// response is delivered before the connection closes.
+    #[serde(default = "default_connection_idle_timeout_secs")]
+    pub connection_idle_timeout_secs: u64,
+
+    /// Report tokens expiring within this many minutes as expiring soon.
+    #[serde(default = "default_expiry_warning_mins")]
+    pub expiry_warning_mins: u64,
+
+    /// Log the token status of every account on startup (default: true).
+    ///
+    /// Expired or missing tokens are only logged; the daemon starts anyway.
+    #[serde(default = "default_startup_validation")]
+    pub startup_validation: bool,
+
+    /// Load every account's token in the background on startup, refreshing
+    /// expired ones, so first requests need no refresh (default: true).
+    #[serde(default = "default_prewarm_on_start")]
+    pub prewarm_on_start: bool,
+
+    /// Load token plugins from the shared libraries in this directory.
+    ///
+    /// Plugins are consulted by `get_token` before the token manager.
+    /// Requires the daemon to be built with the `plugins` feature.
+    #[serde(default)]
+    pub plugin_dir: Option<PathBuf>,
+
+    /// Namespace secrets are stored under in the secret store (default:
+    /// `sigilforge`).
+    ///
+    /// Keys are `{namespace}/{service}/{account}/{credential_type}`, so
+    /// applications sharing one keyring can keep their credentials apart.
+    /// Changing it does not move secrets already stored. Must not be empty
+    /// or contain `/`.
+    #[serde(default)]
+    pub store_namespace: Option<String>,
+
+    /// Which methods connected peers may call, and for which accounts.
+    ///
+    /// Empty by default, which lets every peer admitted to the socket call
+    /// every method. Once any rule is set, a request is answered only if a
+    /// rule allows it, and otherwise fails with error `-32003` (Forbidden).
+    /// The daemon owner is always allowed everything.
+    ///
+    /// ```toml
+    /// # UID 1001 may list accounts...
+    /// [[acl]]
+    /// peer_uid = 1001
+    /// allow = ["list_accounts"]
+    ///
+    /// # ...and read tokens of ci-* accounts
+    /// [[acl]]
+    /// peer_uid = 1001
+    /// account_pattern = "ci-*"
+    /// allow = ["get_token"]
+    /// ```
+    ///
+    /// A rule applies to peers matching both `peer_uid` and `peer_gid`
+    /// (primary GID); either may be left out to match any peer. Patterns
+    /// default to `*`, which is the only pattern matching requests without
+    /// a service or account, such as an unfiltered `list_accounts`. Methods
+    /// are `get_token`, `list_accounts`, `add_account`, `resolve`,
+    /// `accounts_status`, `health_check` and `validate_providers`. TCP
+    /// clients have no UID or GID and match only rules that set neither.
+    #[serde(default)]
+    pub acl: Vec<AclRule>,
+}
+
+/// TLS settings for the daemon's TCP transport.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// PEM file containing the server certificate chain.
+    pub cert_path: PathBuf,
+
+    /// PEM file containing the server private key.
+    pub key_path: PathBuf,
+
+    /// PEM file of CAs trusted to sign client certificates.
+    ///
+    /// When set, clients must present a certificate (mutual TLS).
+    #[serde(default)]
+    pub client_ca_path: Option<PathBuf>,
 }
 
 fn default_log_level() -> String {
     "info".to_string()
 }
 
+fn default_socket_mode() -> u32 {
+    0o600
+}
+
+fn default_max_pipelined_requests() -> usize {
+    crate::api::handlers::DEFAULT_MAX_PIPELINED_REQUESTS
+}
+
+fn default_ordered_pipelining() -> bool {
+    true
+}
+
+fn default_connection_idle_timeout_secs() -> u64 {
+    crate::api::handlers::DEFAULT_IDLE_TIMEOUT.as_secs()
+}
+
+fn default_expiry_warning_mins() -> u64 {
+    crate::api::handlers::DEFAULT_EXPIRY_WARNING.as_secs() / 60
+}
+
+fn default_startup_validation() -> bool {
+    true
+}
+
+fn default_prewarm_on_start() -> bool {
+    true
+}
+
+impl DaemonConfig {
+    /// Reject incompatible or unsafe option combinations.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(addr) = self.listen_tcp {
+            if self.socket_group.is_some() {
+                anyhow::bail!(
+                    "listen_tcp cannot be combined with socket_group: \
+                     group membership cannot be checked for TCP clients"
+                );
+            }
+
+            if self.allowed_uids.is_some() {
+                anyhow::bail!(
+                    "listen_tcp cannot be combined with allowed_uids: \
+                     UIDs cannot be checked for TCP clients"
+                );
+            }
+
+            if self.tls.is_none() {
+                anyhow::bail!("listen_tcp {} requires tls", addr);
+            }
+        } else if self.tls.is_some() {
+            anyhow::bail!("tls is configured but listen_tcp is not set");
+        }
+
+        if self.grpc_socket_path.as_ref() == Some(&self.socket_path) {
+            anyhow::bail!(
+                "grpc_socket_path {:?} must differ from socket_path",
+                self.socket_path
+            );
+        }
+
+        let allowlists_root = self.allowed_uids.iter().flatten().any(|&uid| uid == 0);
+        if allowlists_root && !self.allow_root {
+            anyhow::bail!("allowed_uids contains root (0); set allow_root = true instead");
+        }
+
+        if self.max_pipelined_requests == 0 {
+            anyhow::bail!("max_pipelined_requests must be at least 1");
+        }
+
+        if self.connection_idle_timeout_secs == 0 {
+            anyhow::bail!("connection_idle_timeout_secs must be at least 1");
+        }
+
+        let invalid_namespace = self
+            .store_namespace
+            .as_deref()
+            .filter(|namespace| !token_manager::is_valid_key_prefix(namespace));
+        if let Some(namespace) = invalid_namespace {
+            anyhow::bail!(
+                "store_namespace {:?} must not be empty or contain '/'",
+                namespace
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Built-in providers, then those saved by `sigilforge add-account
+    /// --oidc-issuer`, with every `provider_dirs` entry merged in.
+    ///
+    /// Directories that do not exist are skipped with a warning; unreadable
+    /// or invalid provider files are an error.
+    pub fn provider_registry(&self) -> Result<ProviderRegistry> {
+        let mut registry = ProviderRegistry::with_defaults()
+            .with_user_providers(None)
+            .context("Failed to load user providers")?;
+        for dir in &self.provider_dirs {
+            if !dir.exists() {
+                warn!("Provider directory {:?} does not exist, skipping", dir);
+                continue;
+            }
+            registry = registry
+                .merge_from_dir(dir)
+                .with_context(|| format!("Failed to load providers from {:?}", dir))?;
+        }
+        Ok(registry)
+    }
+
+    /// The account store at the default location, decrypted with the
+    /// identity in `account_identity_path` if that is set.
+    pub fn account_store(&self) -> Result<AccountStore> {
+        let Some(identity_path) = &self.account_identity_path else {
+            return Ok(AccountStore::load()?);
+        };
+        let identity = AccountStore::load_identity(identity_path)
+            .with_context(|| format!("Failed to load identity from {:?}", identity_path))?;
+        let path = AccountStore::default_path()?;
+        Ok(AccountStore::open_encrypted(&path, identity)?)
+    }
+
+    /// Apply settings from the environment on top of the config file.
+    ///
+    /// `SIGILFORGE_SOCKET`, if set and non-empty, replaces `socket_path`.
+    pub fn apply_env_overrides(&mut self) {
+        self.override_socket_path(std::env::var_os(SOCKET_ENV_VAR));
+    }
+
+    /// Replace `socket_path` with a `SIGILFORGE_SOCKET` value, unless it is
+    /// unset or empty.
+    pub fn override_socket_path(&mut self, value: Option<OsString>) {
+        if let Some(path) = value.filter(|path| !path.is_empty()) {
+            self.socket_path = PathBuf::from(path);
+        }
+    }
+
+    /// Socket ownership and permission options derived from this config.
+    pub fn socket_options(&self) -> crate::api::SocketOptions {
+        crate::api::SocketOptions {
+            mode: self.socket_mode,
+            group: self.socket_group.clone(),
+            allowed_uids: self.allowed_uids.clone(),
+            allow_root: self.allow_root,
+        }
+    }
+}
+
 impl Default for DaemonConfig {
     fn default() -> Self {
         let dirs = project_dirs();
@@ -46,17 +384,39 @@ impl Default for DaemonConfig {
             socket_path,
             config_path: PathBuf::new(),
             data_dir,
+            account_identity_path: None,
             log_level: default_log_level(),
+            socket_group: None,
+            socket_mode: default_socket_mode(),
+            allowed_uids: None,
+            allow_root: false,
+            listen_tcp: None,
+            tls: None,
+            emit_request_ids: false,
+            provider_dirs: Vec::new(),
+            grpc_socket_path: None,
+            metrics_addr: None,
+            max_pipelined_requests: default_max_pipelined_requests(),
+            ordered_pipelining: default_ordered_pipelining(),
+            audit_log_path: None,
+            connection_idle_timeout_secs: default_connection_idle_timeout_secs(),
+            expiry_warning_mins: default_expiry_warning_mins(),
+            startup_validation: default_startup_validation(),
+            prewarm_on_start: default_prewarm_on_start(),
+            plugin_dir: None,
+            store_namespace: None,
+            acl: Vec::new(),
         }
     }
 }
 
 /// Load configuration from the default location or create defaults.
+///
+/// The default location is `daemon.toml` in `$SIGILFORGE_CONFIG_DIR` or the
+/// platform configuration directory.
 pub fn load_config() -> Result<DaemonConfig> {
-    let dirs = project_dirs();
-    let config_path = dirs
-        .as_ref()
-        .map(|d| d.config_dir().join("daemon.toml"))
+    let config_path = sigilforge_core::account_store::config_dir()
+        .map(|dir| dir.join("daemon.toml"))
         .unwrap_or_else(|| PathBuf::from("sigilforge-daemon.toml"));
 
     let mut config = if config_path.exists() {
@@ -69,6 +429,10 @@ pub fn load_config() -> Result<DaemonConfig> {
     };
 
     config.config_path = config_path;
+    config.apply_env_overrides();
+    config
+        .validate()
+        .with_context(|| format!("Invalid config in {:?}", config.config_path))?;
 
     std::fs::create_dir_all(&config.data_dir)
         .with_context(|| format!("Failed to create data directory {:?}", config.data_dir))?;
@@ -3,8 +3,14 @@
 //! This library exposes the daemon's API and configuration for testing
 //! and potential embedding in other applications.
 
+pub mod acl;
 pub mod api;
+pub mod audit;
 pub mod config;
+pub mod metrics;
+pub mod plugin;
 
+pub use acl::{AclRule, RpcMethod};
 pub use api::{start_server, ApiState};
-pub use config::{load_config, DaemonConfig};
+pub use config::{load_config, DaemonConfig, TlsConfig};
+pub use plugin::DaemonPlugin;
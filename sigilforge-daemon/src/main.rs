@@ -11,12 +11,18 @@
 //! sigilforged
 //! ```
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use sigilforge_core::token_manager::DEFAULT_KEY_PREFIX;
+use std::time::Duration;
 use tracing::info;
 use tracing_subscriber::{fmt, EnvFilter};
 
+mod acl;
 mod api;
+mod audit;
 mod config;
+mod metrics;
+mod plugin;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -40,14 +46,111 @@ fn init_logging() {
         .init();
 }
 
+/// Plugins from `plugin_dir`, if set and supported by this build.
+fn configured_plugins(config: &config::DaemonConfig) -> Result<Vec<plugin::BoxedPlugin>> {
+    let Some(dir) = &config.plugin_dir else {
+        return Ok(Vec::new());
+    };
+
+    #[cfg(feature = "plugins")]
+    {
+        plugin::load_plugins(dir)
+    }
+    #[cfg(not(feature = "plugins"))]
+    {
+        tracing::warn!(
+            "plugin_dir {:?} is set but sigilforged was built without the `plugins` feature",
+            dir
+        );
+        Ok(Vec::new())
+    }
+}
+
 async fn run_daemon(config: config::DaemonConfig) -> Result<()> {
     info!("Daemon starting on {:?}", config.socket_path);
 
+    if let Some(addr) = config.metrics_addr {
+        #[cfg(feature = "metrics")]
+        {
+            metrics::install_prometheus_exporter(addr)?;
+            info!("Serving Prometheus metrics on http://{}/metrics", addr);
+        }
+        #[cfg(not(feature = "metrics"))]
+        tracing::warn!(
+            "metrics_addr {} is set but sigilforged was built without the `metrics` feature",
+            addr
+        );
+    }
+
     // Create API state
-    let state = api::ApiState::new()?;
+    let providers = config.provider_registry()?;
+    info!("Loaded {} OAuth providers", providers.len());
+    let namespace = config
+        .store_namespace
+        .as_deref()
+        .unwrap_or(DEFAULT_KEY_PREFIX);
+    let accounts = config.account_store()?;
+    let state = api::ApiState::with_providers_in_namespace(accounts, providers, namespace)?
+        .with_request_ids(config.emit_request_ids)
+        .with_pipelining(config.max_pipelined_requests, config.ordered_pipelining)
+        .with_idle_timeout(Duration::from_secs(config.connection_idle_timeout_secs))
+        .with_expiry_warning(Duration::from_secs(config.expiry_warning_mins.saturating_mul(60)));
+    let mut state = match &config.audit_log_path {
+        Some(path) => {
+            info!("Writing audit log to {:?}", path);
+            state.with_audit_log(audit::AuditLog::open(path)?)
+        }
+        None => state,
+    };
+    for plugin in configured_plugins(&config)? {
+        state.register_plugin(plugin);
+    }
+    if !config.acl.is_empty() {
+        info!("Checking requests against {} ACL rule(s)", config.acl.len());
+        state = state.with_acl(acl::Acl::for_current_user(config.acl.clone()));
+    }
+    if state.accounts.is_read_only() {
+        info!("Account store is read-only; add_account requests will be refused");
+    }
+    if config.startup_validation {
+        state.validate_all_accounts().await;
+    }
+    if config.prewarm_on_start {
+        let state = state.clone();
+        tokio::spawn(async move {
+            state.pre_warm_tokens().await;
+        });
+    }
+
+    // Start the TCP listener first so it shares the same state
+    let tcp_handle = match config.listen_tcp {
+        Some(addr) => {
+            let tls = config.tls.as_ref().context("listen_tcp requires tls")?;
+            Some(api::start_tcp_server(addr, state.clone(), tls).await?)
+        }
+        None => None,
+    };
+
+    let grpc_handle = match &config.grpc_socket_path {
+        #[cfg(feature = "grpc")]
+        Some(path) => {
+            Some(api::start_grpc_server(path, state.clone(), config.socket_options()).await?)
+        }
+        #[cfg(not(feature = "grpc"))]
+        Some(path) => {
+            tracing::warn!(
+                "grpc_socket_path {:?} is set but sigilforged was built without the `grpc` feature",
+                path
+            );
+            None::<api::ServerHandle>
+        }
+        None => None,
+    };
 
     // Start the JSON-RPC server
-    let server_handle = api::start_server(&config.socket_path, state).await?;
+    let server_handle =
+        api::start_server_with_options(&config.socket_path, state, config.socket_options())
+            .await?;
 
     info!("Daemon running. Press Ctrl+C to stop.");
 
@@ -58,12 +161,26 @@ async fn run_daemon(config: config::DaemonConfig) -> Result<()> {
     // Stop the server gracefully
     server_handle.stop().await?;
     server_handle.stopped().await;
+    if let Some(tcp_handle) = tcp_handle {
+        tcp_handle.stop().await?;
+    }
+    if let Some(grpc_handle) = grpc_handle {
+        grpc_handle.stop().await?;
+    }
 
-    // Clean up socket file
+    // Clean up socket files
     if config.socket_path.exists() {
         std::fs::remove_file(&config.socket_path)?;
         info!("Socket file removed");
     }
+    if let Some(path) = config
+        .grpc_socket_path
+        .as_ref()
+        .filter(|path| path.exists())
+    {
+        std::fs::remove_file(path)?;
+        info!("gRPC socket file removed");
+    }
 
     info!("Daemon stopped");
     Ok(())
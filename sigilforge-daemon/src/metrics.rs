@@ -0,0 +1,94 @@
+//! Daemon metrics.
+//!
+//! Metrics are emitted through the [`metrics`](https://docs.rs/metrics) facade
+//! when the `metrics` feature is enabled and exported in Prometheus format by
+//! [`install_prometheus_exporter`]. Without the feature every helper here is a
+//! no-op, so call sites need no `cfg` attributes.
+//!
+//! Token refresh durations are recorded by `DefaultTokenManager` in
+//! `sigilforge-core`, under `TOKEN_REFRESH_DURATION_METRIC`.
+
+/// Counter of JSON-RPC requests handled, labelled by `method`.
+#[cfg(feature = "metrics")]
+pub const RPC_REQUESTS_TOTAL: &str = "sigilforge.rpc.requests_total";
+
+/// Gauge of configured accounts, updated on every unfiltered `list_accounts`.
+#[cfg(feature = "metrics")]
+pub const ACCOUNTS_TOTAL: &str = "sigilforge.accounts.total";
+
+/// Gauge of currently open client connections (Unix socket and TCP).
+#[cfg(feature = "metrics")]
+pub const CONNECTIONS_ACTIVE: &str = "sigilforge.connections.active";
+
+/// Counter of accounts found with a valid token at startup.
+#[cfg(feature = "metrics")]
+pub const STARTUP_ACCOUNTS_VALID_TOTAL: &str = "sigilforge.startup.accounts_valid_total";
+
+/// Counter of accounts found at startup with an expired, missing, or
+/// unreadable token.
+#[cfg(feature = "metrics")]
+pub const STARTUP_ACCOUNTS_INVALID_TOTAL: &str = "sigilforge.startup.accounts_invalid_total";
+
+/// Count one request to the RPC `method`.
+pub fn record_request(method: &'static str) {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!(RPC_REQUESTS_TOTAL, "method" => method).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = method;
+}
+
+/// Set the number of configured accounts.
+pub fn set_accounts_total(count: usize) {
+    #[cfg(feature = "metrics")]
+    ::metrics::gauge!(ACCOUNTS_TOTAL).set(count as f64);
+    #[cfg(not(feature = "metrics"))]
+    let _ = count;
+}
+
+/// Record the outcome of the startup token check.
+pub fn record_startup_validation(valid: usize, invalid: usize) {
+    #[cfg(feature = "metrics")]
+    {
+        ::metrics::counter!(STARTUP_ACCOUNTS_VALID_TOTAL).increment(valid as u64);
+        ::metrics::counter!(STARTUP_ACCOUNTS_INVALID_TOTAL).increment(invalid as u64);
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (valid, invalid);
+}
+
+/// Tracks one open connection in the active connections gauge for as long as
+/// it lives.
+pub struct ConnectionGuard(());
+
+impl ConnectionGuard {
+    /// Increment the active connection gauge; it is decremented on drop.
+    pub fn new() -> Self {
+        #[cfg(feature = "metrics")]
+        ::metrics::gauge!(CONNECTIONS_ACTIVE).increment(1.0);
+        Self(())
+    }
+}
+
+impl Default for ConnectionGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "metrics")]
+        ::metrics::gauge!(CONNECTIONS_ACTIVE).decrement(1.0);
+    }
+}
+
+/// Install the global metrics recorder and serve `/metrics` on `addr`.
+///
+/// Must be called from within a Tokio runtime, at most once per process.
+#[cfg(feature = "metrics")]
+pub fn install_prometheus_exporter(addr: std::net::SocketAddr) -> anyhow::Result<()> {
+    metrics_exporter_prometheus::PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .install()
+        .map_err(|e| anyhow::anyhow!("Failed to start metrics exporter on {}: {}", addr, e))
+}
@@ -0,0 +1,145 @@
+//! Token plugins for services the token manager does not know about.
+//!
+//! `get_token` asks each registered [`DaemonPlugin`] in registration order
+//! whether it handles the requested service; the first that does serves the
+//! token, and the account store and token manager are not consulted.
+//!
+//! With the `plugins` feature, plugins can also be loaded from shared
+//! libraries in [`DaemonConfig::plugin_dir`](crate::config::DaemonConfig).
+//! A plugin library exports its constructor with [`declare_plugin!`]. Rust
+//! has no stable ABI, so plugins must be built with the same compiler and
+//! `sigilforge-daemon` version as the daemon loading them.
+
+use async_trait::async_trait;
+use sigilforge_core::{TokenError, TokenSet};
+
+/// A source of tokens consulted before the daemon's token manager.
+#[async_trait]
+pub trait DaemonPlugin: Send + Sync {
+    /// Name used in logs.
+    fn name(&self) -> &str;
+
+    /// Fetch a token for `service`/`account`.
+    ///
+    /// Only called for services this plugin [can handle](Self::can_handle).
+    async fn get_token(&self, service: &str, account: &str) -> Result<TokenSet, TokenError>;
+
+    /// Whether this plugin serves tokens for `service`.
+    async fn can_handle(&self, service: &str) -> bool;
+}
+
+/// A plugin as registered with [`ApiState::register_plugin`](crate::api::ApiState::register_plugin).
+pub type BoxedPlugin = Box<dyn DaemonPlugin + Send + Sync>;
+
+/// Symbol a plugin library exports to construct its plugin.
+#[cfg(feature = "plugins")]
+pub const PLUGIN_ENTRY_SYMBOL: &str = "sigilforge_daemon_plugin";
+
+/// Signature of [`PLUGIN_ENTRY_SYMBOL`].
+///
+/// Returns an owned pointer from `Box::into_raw`.
+#[cfg(feature = "plugins")]
+#[allow(improper_ctypes_definitions)]
+pub type PluginEntry = unsafe extern "C" fn() -> *mut BoxedPlugin;
+
+/// Export a plugin constructor from a `cdylib` crate.
+///
+/// ```ignore
+/// sigilforge_daemon::declare_plugin!(MyPlugin::new());
+/// ```
+#[macro_export]
+macro_rules! declare_plugin {
+    ($constructor:expr) => {
+        #[unsafe(no_mangle)]
+        #[allow(improper_ctypes_definitions)]
+        pub extern "C" fn sigilforge_daemon_plugin() -> *mut $crate::plugin::BoxedPlugin {
+            let plugin: $crate::plugin::BoxedPlugin = Box::new($constructor);
+            Box::into_raw(Box::new(plugin))
+        }
+    };
+}
+
+#[cfg(feature = "plugins")]
+pub use loader::load_plugins;
+
+#[cfg(feature = "plugins")]
+mod loader {
+    use super::{BoxedPlugin, DaemonPlugin, PluginEntry, PLUGIN_ENTRY_SYMBOL};
+    use anyhow::{Context, Result};
+    use async_trait::async_trait;
+    use libloading::Library;
+    use sigilforge_core::{TokenError, TokenSet};
+    use std::path::{Path, PathBuf};
+    use tracing::{info, warn};
+
+    /// A plugin together with the library its code lives in.
+    ///
+    /// Field order matters: the plugin must be dropped before the library
+    /// is unloaded.
+    struct LoadedPlugin {
+        plugin: BoxedPlugin,
+        _library: Library,
+    }
+
+    #[async_trait]
+    impl DaemonPlugin for LoadedPlugin {
+        fn name(&self) -> &str {
+            self.plugin.name()
+        }
+
+        async fn get_token(&self, service: &str, account: &str) -> Result<TokenSet, TokenError> {
+            self.plugin.get_token(service, account).await
+        }
+
+        async fn can_handle(&self, service: &str) -> bool {
+            self.plugin.can_handle(service).await
+        }
+    }
+
+    /// Load every shared library in `dir`, in filename order.
+    ///
+    /// A missing directory is skipped with a warning; a library that cannot
+    /// be loaded or lacks [`PLUGIN_ENTRY_SYMBOL`] is an error.
+    pub fn load_plugins(dir: &Path) -> Result<Vec<BoxedPlugin>> {
+        if !dir.exists() {
+            warn!("Plugin directory {:?} does not exist, skipping", dir);
+            return Ok(Vec::new());
+        }
+
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read plugin directory {:?}", dir))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+            })
+            .collect();
+        paths.sort();
+
+        let mut plugins = Vec::with_capacity(paths.len());
+        for path in paths {
+            let plugin = load_plugin(&path)
+                .with_context(|| format!("Failed to load plugin {:?}", path))?;
+            info!("Loaded plugin {} from {:?}", plugin.name(), path);
+            plugins.push(plugin);
+        }
+        Ok(plugins)
+    }
+
+    fn load_plugin(path: &Path) -> Result<BoxedPlugin> {
+        // SAFETY: Loading a library runs its initializers, and the entry
+        // point is trusted to have the `PluginEntry` signature. Plugin
+        // directories must only contain libraries built for this daemon.
+        unsafe {
+            let library = Library::new(path)?;
+            let entry = library.get::<PluginEntry>(PLUGIN_ENTRY_SYMBOL.as_bytes())?;
+            let raw = entry();
+            anyhow::ensure!(!raw.is_null(), "{} returned null", PLUGIN_ENTRY_SYMBOL);
+            let plugin = *Box::from_raw(raw);
+            Ok(Box::new(LoadedPlugin {
+                plugin,
+                _library: library,
+            }))
+        }
+    }
+}
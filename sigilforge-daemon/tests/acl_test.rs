@@ -0,0 +1,165 @@
+//! Integration tests for per-method ACL rules.
+//!
+//! The test process cannot connect as another user, so these tests make it
+//! a non-owner peer by building the ACL for a different owner UID.
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_core::{Account, AccountId, ServiceId, Token, TokenManager, TokenSet};
+use sigilforge_daemon::acl::{Acl, AclRule, PeerContext, RpcMethod, FORBIDDEN_CODE};
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle};
+use sigilforge_daemon::DaemonConfig;
+
+fn test_uid() -> u32 {
+    nix::unistd::getuid().as_raw()
+}
+
+/// A rule for the test process allowing `allow` on `service_pattern`.
+fn rule(service_pattern: &str, allow: Vec<RpcMethod>) -> AclRule {
+    AclRule {
+        peer_uid: Some(test_uid()),
+        peer_gid: None,
+        service_pattern: service_pattern.to_string(),
+        account_pattern: "*".to_string(),
+        allow,
+    }
+}
+
+/// Serve github/work and spotify/personal, both with stored tokens, to
+/// the test process as a non-owner peer restricted by `rules`.
+async fn start(temp_dir: &TempDir, rules: Vec<AclRule>) -> (PathBuf, ServerHandle) {
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store).with_acl(Acl::new(test_uid() + 1, rules));
+
+    for (service, account) in [("github", "work"), ("spotify", "personal")] {
+        let (service, account) = (ServiceId::new(service), AccountId::new(account));
+        state
+            .accounts
+            .add_account(Account::new(service.clone(), account.clone(), vec![]))
+            .unwrap();
+        state
+            .token_manager
+            .store_token_set(&service, &account, TokenSet::new(Token::new("token")))
+            .await
+            .unwrap();
+    }
+
+    let socket_path = temp_dir.path().join("acl.sock");
+    let handle = start_server(&socket_path, state).await.unwrap();
+    (socket_path, handle)
+}
+
+async fn call(socket_path: &Path, method: &str, params: serde_json::Value) -> serde_json::Value {
+    let mut stream = UnixStream::connect(socket_path).await.unwrap();
+    let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .await
+        .unwrap();
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).await.unwrap();
+    serde_json::from_str(&line).unwrap()
+}
+
+#[tokio::test]
+async fn test_peer_allowed_only_list_accounts() {
+    let temp_dir = TempDir::new().unwrap();
+    let rules = vec![rule("*", vec![RpcMethod::ListAccounts])];
+    let (socket_path, handle) = start(&temp_dir, rules).await;
+
+    let response = call(&socket_path, "list_accounts", json!([])).await;
+    assert_eq!(response["result"]["accounts"].as_array().unwrap().len(), 2);
+
+    let response = call(&socket_path, "get_token", json!(["github", "work"])).await;
+    assert_eq!(response["error"]["code"], FORBIDDEN_CODE);
+    let message = response["error"]["message"].as_str().unwrap();
+    assert!(message.contains("Forbidden"), "{}", message);
+    assert!(response.get("result").is_none());
+
+    let response = call(&socket_path, "health_check", json!([])).await;
+    assert_eq!(response["error"]["code"], FORBIDDEN_CODE);
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_service_pattern_limits_get_token() {
+    let temp_dir = TempDir::new().unwrap();
+    let allow = vec![RpcMethod::GetToken, RpcMethod::ListAccounts];
+    let rules = vec![rule("git*", allow)];
+    let (socket_path, handle) = start(&temp_dir, rules).await;
+
+    let response = call(&socket_path, "get_token", json!(["github", "work"])).await;
+    assert_eq!(response["result"]["token"], "token");
+
+    let response = call(&socket_path, "get_token", json!(["spotify", "personal"])).await;
+    assert_eq!(response["error"]["code"], FORBIDDEN_CODE);
+
+    // Listing every service is not covered by a service pattern
+    let response = call(&socket_path, "list_accounts", json!([])).await;
+    assert_eq!(response["error"]["code"], FORBIDDEN_CODE);
+    let response = call(&socket_path, "list_accounts", json!(["github"])).await;
+    assert_eq!(response["result"]["accounts"].as_array().unwrap().len(), 1);
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_owner_is_allowed_everything() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store).with_acl(Acl::for_current_user(Vec::new()));
+    let socket_path = temp_dir.path().join("acl.sock");
+    let handle = start_server(&socket_path, state).await.unwrap();
+
+    let response = call(&socket_path, "health_check", json!([])).await;
+    assert!(response.get("error").is_none(), "{}", response);
+    let response = call(&socket_path, "add_account", json!(["github", "work", []])).await;
+    assert!(response.get("error").is_none(), "{}", response);
+
+    handle.stop().await.unwrap();
+}
+
+#[test]
+fn test_config_parses_acl_rules() {
+    let config: DaemonConfig = toml::from_str(
+        r#"
+        socket_path = "/tmp/sigilforge.sock"
+        data_dir = "/tmp/sigilforge"
+
+        [[acl]]
+        peer_uid = 1001
+        allow = ["list_accounts"]
+
+        [[acl]]
+        peer_uid = 1001
+        account_pattern = "ci-*"
+        allow = ["get_token"]
+        "#,
+    )
+    .unwrap();
+    config.validate().unwrap();
+    assert_eq!(config.acl.len(), 2);
+    assert_eq!(config.acl[0].peer_uid, Some(1001));
+    assert_eq!(config.acl[0].service_pattern, "*");
+    assert_eq!(config.acl[0].account_pattern, "*");
+    assert_eq!(config.acl[1].allow, [RpcMethod::GetToken]);
+
+    // An unfiltered list_accounts has no account, so only the first rule
+    // can allow it
+    let acl = Acl::new(0, config.acl);
+    let peer = PeerContext::unix(1001, 1001);
+    assert!(acl.permits(&peer, RpcMethod::ListAccounts, None, None));
+    let github = Some("github");
+    assert!(acl.permits(&peer, RpcMethod::GetToken, github, Some("ci-deploy")));
+    assert!(!acl.permits(&peer, RpcMethod::GetToken, github, Some("work")));
+}
@@ -0,0 +1,92 @@
+//! Integration tests for the request audit log.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle, REQUEST_ID_FIELD};
+use sigilforge_daemon::audit::{AuditEntry, AuditLog};
+
+async fn start_test_server(temp_dir: &TempDir, audit_path: &Path) -> (PathBuf, ServerHandle) {
+    let socket_path = temp_dir.path().join("test.sock");
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store)
+        .with_request_ids(true)
+        .with_audit_log(AuditLog::open(audit_path).unwrap());
+    let handle = start_server(&socket_path, state).await.unwrap();
+    (socket_path, handle)
+}
+
+async fn send_requests(
+    socket_path: &Path,
+    requests: &[serde_json::Value],
+) -> Vec<serde_json::Value> {
+    let stream = UnixStream::connect(socket_path).await.unwrap();
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let mut responses = Vec::new();
+    for request in requests {
+        writer
+            .write_all(format!("{}\n", request).as_bytes())
+            .await
+            .unwrap();
+
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        responses.push(serde_json::from_str(&line).unwrap());
+    }
+    responses
+}
+
+fn request(id: u64, method: &str, params: serde_json::Value) -> serde_json::Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": id })
+}
+
+#[tokio::test]
+async fn test_requests_are_written_to_audit_log() {
+    let temp_dir = TempDir::new().unwrap();
+    let audit_path = temp_dir.path().join("audit.log");
+    let (socket_path, handle) = start_test_server(&temp_dir, &audit_path).await;
+
+    let responses = send_requests(
+        &socket_path,
+        &[
+            request(1, "get_token", json!(["github", "work"])),
+            request(2, "list_accounts", json!(["spotify"])),
+            request(3, "health_check", json!([])),
+        ],
+    )
+    .await;
+    handle.stop().await.unwrap();
+
+    let entries: Vec<AuditEntry> = std::fs::read_to_string(&audit_path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(entries.len(), 3);
+
+    // The account does not exist, so the token request fails
+    assert_eq!(entries[0].method, "get_token");
+    assert_eq!(entries[0].service.as_deref(), Some("github"));
+    assert_eq!(entries[0].account.as_deref(), Some("work"));
+    assert!(!entries[0].success);
+
+    assert_eq!(entries[1].method, "list_accounts");
+    assert_eq!(entries[1].service.as_deref(), Some("spotify"));
+    assert_eq!(entries[1].account, None);
+    assert!(entries[1].success);
+
+    assert_eq!(entries[2].method, "health_check");
+    assert_eq!(entries[2].service, None);
+
+    // Entries carry the same correlation ID as the response
+    for (entry, response) in entries.iter().zip(&responses) {
+        assert_eq!(response[REQUEST_ID_FIELD], entry.request_id.as_str());
+    }
+}
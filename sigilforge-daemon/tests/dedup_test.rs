@@ -0,0 +1,230 @@
+//! Integration tests for sharing results between identical concurrent
+//! requests.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use jsonrpsee::types::ErrorObject;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::task::JoinSet;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_core::{
+    Account, AccountId, CredentialType, ProviderConfig, ProviderRegistry, ServiceId, Token,
+    TokenManager, TokenSet,
+};
+use sigilforge_daemon::api::{start_server, ApiState, RequestDeduplicator};
+
+const CALLERS: usize = 20;
+
+/// Run `CALLERS` identical requests at once, each taking `duration` and
+/// returning `result`, and collect what each caller got.
+async fn run_concurrently(
+    dedup: &RequestDeduplicator,
+    runs: &Arc<AtomicUsize>,
+    duration: Duration,
+    result: Result<&'static str, &'static str>,
+) -> Vec<Result<String, String>> {
+    let mut tasks = JoinSet::new();
+    for _ in 0..CALLERS {
+        let (dedup, runs) = (dedup.clone(), Arc::clone(runs));
+        tasks.spawn(async move {
+            let (service, account) = (ServiceId::new("github"), AccountId::new("work"));
+            let key = RequestDeduplicator::key("get_token", &service, &account);
+            dedup
+                .run(key, || async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(duration).await;
+                    result
+                        .map(str::to_string)
+                        .map_err(|message| ErrorObject::owned(-32603, message, None::<()>))
+                })
+                .await
+                .map_err(|e| e.message().to_string())
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        results.push(result.unwrap());
+    }
+    results
+}
+
+#[tokio::test]
+async fn test_identical_requests_run_once() {
+    let dedup = RequestDeduplicator::default();
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let results = run_concurrently(&dedup, &runs, Duration::from_millis(100), Ok("token")).await;
+
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert_eq!(results, vec![Ok("token".to_string()); CALLERS]);
+    assert_eq!(dedup.in_flight(), 0);
+
+    // Requests after the first completed run again
+    run_concurrently(&dedup, &runs, Duration::ZERO, Ok("token")).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_errors_are_shared() {
+    let dedup = RequestDeduplicator::default();
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let error = Err("refresh failed");
+    let results = run_concurrently(&dedup, &runs, Duration::from_millis(100), error).await;
+
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert_eq!(results, vec![Err("refresh failed".to_string()); CALLERS]);
+}
+
+#[tokio::test]
+async fn test_different_keys_are_not_shared() {
+    let dedup = RequestDeduplicator::default();
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let mut tasks = JoinSet::new();
+    for account in ["work", "personal"] {
+        let (dedup, runs) = (dedup.clone(), Arc::clone(&runs));
+        tasks.spawn(async move {
+            let key = RequestDeduplicator::key(
+                "get_token",
+                &ServiceId::new("github"),
+                &AccountId::new(account),
+            );
+            dedup
+                .run(key, || async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    Ok(account.to_string())
+                })
+                .await
+                .unwrap()
+        });
+    }
+    tasks.join_all().await;
+
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_waiters_give_up_on_stuck_request() {
+    let dedup = RequestDeduplicator::new(Duration::from_secs(10));
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    // The first request hangs for a minute
+    let stuck = {
+        let (dedup, runs) = (dedup.clone(), Arc::clone(&runs));
+        tokio::spawn(async move {
+            let (service, account) = (ServiceId::new("github"), AccountId::new("work"));
+            let key = RequestDeduplicator::key("get_token", &service, &account);
+            dedup
+                .run(key, || async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok("stale".to_string())
+                })
+                .await
+        })
+    };
+    tokio::task::yield_now().await;
+    assert_eq!(dedup.in_flight(), 1);
+
+    // Waiters run on their own after the timeout and drop the stuck entry
+    let started = tokio::time::Instant::now();
+    let results = run_concurrently(&dedup, &runs, Duration::ZERO, Ok("fresh")).await;
+    assert_eq!(started.elapsed(), Duration::from_secs(10));
+    assert_eq!(results, vec![Ok("fresh".to_string()); CALLERS]);
+    assert_eq!(runs.load(Ordering::SeqCst), 1 + CALLERS);
+    assert_eq!(dedup.in_flight(), 0);
+
+    stuck.abort();
+}
+
+async fn get_token(socket_path: &Path) -> serde_json::Value {
+    let mut stream = UnixStream::connect(socket_path).await.unwrap();
+    let params = json!(["test", "work"]);
+    let request = json!({ "jsonrpc": "2.0", "method": "get_token", "params": params, "id": 1 });
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .await
+        .unwrap();
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).await.unwrap();
+    serde_json::from_str(&line).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_get_token_refreshes_once() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({
+                    "access_token": "fresh",
+                    "token_type": "bearer",
+                    "expires_in": 3600,
+                }))
+                .set_delay(Duration::from_millis(200)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut providers = ProviderRegistry::new();
+    providers
+        .register(
+            ProviderConfig::new("test", "Test")
+                .with_auth_url(format!("{}/authorize", server.uri()))
+                .with_token_url(format!("{}/token", server.uri())),
+        )
+        .unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store_and_providers(store, providers);
+
+    let (service, account) = (ServiceId::new("test"), AccountId::new("work"));
+    state
+        .accounts
+        .add_account(Account::new(service.clone(), account.clone(), vec![]))
+        .unwrap();
+    state
+        .token_manager
+        .store_credential(&service, &account, CredentialType::ClientId, "client-id")
+        .await
+        .unwrap();
+    let expired_at = chrono::Utc::now() - chrono::Duration::hours(1);
+    let expired = Token::new("expired").with_expiry(expired_at);
+    let tokens = TokenSet::new(expired).with_refresh_token("refresh");
+    state
+        .token_manager
+        .store_token_set(&service, &account, tokens)
+        .await
+        .unwrap();
+
+    let socket_path = temp_dir.path().join("test.sock");
+    let handle = start_server(&socket_path, state).await.unwrap();
+
+    let mut tasks = JoinSet::new();
+    for _ in 0..CALLERS {
+        let socket_path = socket_path.clone();
+        tasks.spawn(async move { get_token(&socket_path).await });
+    }
+    while let Some(response) = tasks.join_next().await {
+        let response = response.unwrap();
+        assert_eq!(response["result"]["token"], "fresh", "{}", response);
+    }
+
+    handle.stop().await.unwrap();
+    server.verify().await;
+}
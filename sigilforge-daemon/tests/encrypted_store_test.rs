@@ -0,0 +1,28 @@
+//! Tests for opening an encrypted account store with `account_identity_path`.
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_core::{Account, AccountId, ServiceId};
+use sigilforge_daemon::DaemonConfig;
+use tempfile::TempDir;
+
+#[test]
+fn test_account_identity_path_opens_encrypted_store() {
+    let temp_dir = TempDir::new().unwrap();
+    // SAFETY: this is the only test in this binary, so nothing reads the
+    // environment concurrently
+    unsafe { std::env::set_var("SIGILFORGE_CONFIG_DIR", temp_dir.path()) };
+
+    let identity_path = temp_dir.path().join("identity.txt");
+    let identity = AccountStore::generate_identity(&identity_path).unwrap();
+    let store = AccountStore::open_encrypted(&AccountStore::default_path().unwrap(), identity);
+    let account = Account::new(ServiceId::new("github"), AccountId::new("work"), vec![]);
+    store.unwrap().add_account(account).unwrap();
+
+    let mut config = DaemonConfig::default();
+    assert!(config.account_store().is_err());
+
+    config.account_identity_path = Some(identity_path);
+    let accounts = config.account_store().unwrap().list_accounts(None).unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].id.as_str(), "work");
+}
@@ -0,0 +1,141 @@
+//! Integration tests for the configurable "expires soon" threshold.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_core::{Account, AccountId, ServiceId, Token, TokenManager, TokenSet};
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle};
+use sigilforge_daemon::DaemonConfig;
+
+const WARNING_MINS: i64 = 60;
+
+/// Start a server whose accounts `minutes` each hold a token expiring that
+/// many minutes from now.
+async fn start_test_server(temp_dir: &TempDir, minutes: &[i64]) -> (PathBuf, ServerHandle) {
+    let socket_path = temp_dir.path().join("test.sock");
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store)
+        .with_expiry_warning(Duration::from_secs(WARNING_MINS as u64 * 60));
+
+    let service = ServiceId::new("github");
+    for minutes in minutes {
+        let account = AccountId::new(format!("expires-in-{}", minutes));
+        state
+            .accounts
+            .add_account(Account::new(service.clone(), account.clone(), vec![]))
+            .unwrap();
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(*minutes);
+        let tokens = TokenSet::new(Token::new("token").with_expiry(expires_at));
+        state
+            .token_manager
+            .store_token_set(&service, &account, tokens)
+            .await
+            .unwrap();
+    }
+
+    let handle = start_server(&socket_path, state).await.unwrap();
+    (socket_path, handle)
+}
+
+async fn accounts_status(socket_path: &Path) -> serde_json::Value {
+    let mut stream = UnixStream::connect(socket_path).await.unwrap();
+    let request = json!({ "jsonrpc": "2.0", "method": "accounts_status", "id": 1 });
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .await
+        .unwrap();
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).await.unwrap();
+    let response: serde_json::Value = serde_json::from_str(&line).unwrap();
+    response["result"].clone()
+}
+
+fn expires_soon(status: &serde_json::Value, account: &str) -> bool {
+    status["accounts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["account"] == account)
+        .unwrap_or_else(|| panic!("no status for {}", account))["expires_soon"]
+        .as_bool()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_token_outside_threshold_is_not_expiring_soon() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir, &[WARNING_MINS + 1]).await;
+
+    let status = accounts_status(&socket_path).await;
+    assert!(!expires_soon(&status, "expires-in-61"));
+    assert_eq!(status["any_expiring_soon"], false);
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_token_inside_threshold_is_expiring_soon() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) =
+        start_test_server(&temp_dir, &[WARNING_MINS - 1, WARNING_MINS + 1]).await;
+
+    let status = accounts_status(&socket_path).await;
+    assert!(expires_soon(&status, "expires-in-59"));
+    assert!(!expires_soon(&status, "expires-in-61"));
+    assert_eq!(status["any_expiring_soon"], true);
+    assert_eq!(status["all_valid"], true);
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_expired_refreshable_token_is_reported_separately() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("test.sock");
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store);
+
+    let (service, account) = (ServiceId::new("github"), AccountId::new("work"));
+    state
+        .accounts
+        .add_account(Account::new(service.clone(), account.clone(), vec![]))
+        .unwrap();
+    let expired_at = chrono::Utc::now() - chrono::Duration::minutes(5);
+    let tokens =
+        TokenSet::new(Token::new("token").with_expiry(expired_at)).with_refresh_token("refresh");
+    state
+        .token_manager
+        .store_token_set(&service, &account, tokens)
+        .await
+        .unwrap();
+    let handle = start_server(&socket_path, state).await.unwrap();
+
+    let status = accounts_status(&socket_path).await;
+    let entry = &status["accounts"][0];
+    assert_eq!(entry["token_valid"], false);
+    assert_eq!(entry["refreshable"], true);
+    assert!(entry.get("error").is_none());
+    assert_eq!(status["all_valid"], true);
+
+    handle.stop().await.unwrap();
+}
+
+#[test]
+fn test_expiry_warning_defaults_to_a_day() {
+    let config: DaemonConfig = toml::from_str(
+        r#"
+        socket_path = "/tmp/sigilforge.sock"
+        data_dir = "/tmp/sigilforge"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.expiry_warning_mins, 1440);
+    assert_eq!(DaemonConfig::default().expiry_warning_mins, 1440);
+}
@@ -0,0 +1,26 @@
+//! Plugin library for `tests/plugin_test.rs`.
+//!
+//! Serves `fixture-{account}` for the `fixture` service.
+
+use async_trait::async_trait;
+use sigilforge_core::{Token, TokenError, TokenSet};
+use sigilforge_daemon::DaemonPlugin;
+
+struct FixturePlugin;
+
+#[async_trait]
+impl DaemonPlugin for FixturePlugin {
+    fn name(&self) -> &str {
+        "fixture"
+    }
+
+    async fn get_token(&self, _service: &str, account: &str) -> Result<TokenSet, TokenError> {
+        Ok(TokenSet::new(Token::new(format!("fixture-{}", account))))
+    }
+
+    async fn can_handle(&self, service: &str) -> bool {
+        service == "fixture"
+    }
+}
+
+sigilforge_daemon::declare_plugin!(FixturePlugin);
@@ -0,0 +1,87 @@
+//! Integration tests for `get_token` with `force_refresh`.
+//!
+//! Refreshing against a provider is covered by the token manager's tests;
+//! these check how the daemon passes the flag through.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_core::{Account, AccountId, ServiceId, Token, TokenManager, TokenSet};
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle};
+
+/// Start a server with github/work holding a valid token but no refresh token.
+async fn start_test_server(temp_dir: &TempDir) -> (PathBuf, ServerHandle) {
+    let socket_path = temp_dir.path().join("test.sock");
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store);
+
+    let service = ServiceId::new("github");
+    let account = AccountId::new("work");
+    state
+        .accounts
+        .add_account(Account::new(service.clone(), account.clone(), vec![]))
+        .unwrap();
+    let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+    let tokens = TokenSet::new(Token::new("cached").with_expiry(expires_at));
+    state
+        .token_manager
+        .store_token_set(&service, &account, tokens)
+        .await
+        .unwrap();
+
+    let handle = start_server(&socket_path, state).await.unwrap();
+    (socket_path, handle)
+}
+
+async fn get_token(socket_path: &Path, params: serde_json::Value) -> serde_json::Value {
+    let mut stream = UnixStream::connect(socket_path).await.unwrap();
+    let request = json!({ "jsonrpc": "2.0", "method": "get_token", "params": params, "id": 1 });
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .await
+        .unwrap();
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).await.unwrap();
+    serde_json::from_str(&line).unwrap()
+}
+
+#[tokio::test]
+async fn test_cached_token_without_force_refresh() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir).await;
+
+    // Clients that predate the parameter send only service and account
+    let response = get_token(&socket_path, json!(["github", "work"])).await;
+    assert_eq!(response["result"]["token"], "cached");
+
+    let response = get_token(&socket_path, json!(["github", "work", false])).await;
+    assert_eq!(response["result"]["token"], "cached");
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_force_refresh_without_refresh_token_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir).await;
+
+    let response = get_token(&socket_path, json!(["github", "work", true])).await;
+    let message = response["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("no refresh token available"),
+        "{}",
+        message
+    );
+
+    // The cached token is left in place
+    let response = get_token(&socket_path, json!(["github", "work"])).await;
+    assert_eq!(response["result"]["token"], "cached");
+
+    handle.stop().await.unwrap();
+}
@@ -0,0 +1,264 @@
+//! Integration tests for the gRPC transport.
+//!
+//! Only built with the `grpc` feature. Requests go through the client stub
+//! generated alongside the server.
+
+#![cfg(feature = "grpc")]
+
+use std::path::{Path, PathBuf};
+
+use hyper_util::rt::TokioIo;
+use tempfile::TempDir;
+use tokio::net::UnixStream;
+use tonic::Code;
+use tonic::transport::{Channel, Endpoint, Error, Uri};
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_daemon::DaemonConfig;
+use sigilforge_daemon::acl::{Acl, AclRule, RpcMethod};
+use sigilforge_daemon::api::grpc::proto::{self, sigilforge_client::SigilforgeClient};
+use sigilforge_daemon::api::{start_grpc_server, ApiState, ServerHandle, SocketOptions};
+
+async fn connect(socket_path: &Path) -> Result<SigilforgeClient<Channel>, Error> {
+    let socket_path = socket_path.to_path_buf();
+    let channel = Endpoint::from_static("http://[::]:50051")
+        .connect_with_connector(tower::service_fn(move |_: Uri| {
+            let socket_path = socket_path.clone();
+            async move {
+                let stream = UnixStream::connect(socket_path).await?;
+                Ok::<_, std::io::Error>(TokioIo::new(stream))
+            }
+        }))
+        .await?;
+    Ok(SigilforgeClient::new(channel))
+}
+
+async fn start_with_state(
+    temp_dir: &TempDir,
+    state: ApiState,
+) -> (PathBuf, ServerHandle, SigilforgeClient<Channel>) {
+    let socket_path = temp_dir.path().join("grpc.sock");
+    let handle = start_grpc_server(&socket_path, state, SocketOptions::default())
+        .await
+        .unwrap();
+
+    let client = connect(&socket_path).await.unwrap();
+    (socket_path, handle, client)
+}
+
+async fn start_test_server(temp_dir: &TempDir) -> (ServerHandle, SigilforgeClient<Channel>) {
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let (_, handle, client) = start_with_state(temp_dir, ApiState::with_store(store)).await;
+    (handle, client)
+}
+
+fn add_request(service: &str, account: &str) -> proto::AddAccountRequest {
+    proto::AddAccountRequest {
+        service: service.to_string(),
+        account: account.to_string(),
+        scopes: vec!["repo".to_string()],
+        revoke_existing: false,
+    }
+}
+
+#[tokio::test]
+async fn test_add_and_list_accounts() {
+    let temp_dir = TempDir::new().unwrap();
+    let (handle, mut client) = start_test_server(&temp_dir).await;
+
+    let response = client
+        .add_account(add_request("github", "work"))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.message, "Account github/work added successfully");
+    client
+        .add_account(add_request("spotify", "personal"))
+        .await
+        .unwrap();
+
+    let accounts = client
+        .list_accounts(proto::ListAccountsRequest { service: None })
+        .await
+        .unwrap()
+        .into_inner()
+        .accounts;
+    assert_eq!(accounts.len(), 2);
+
+    let accounts = client
+        .list_accounts(proto::ListAccountsRequest {
+            service: Some("github".to_string()),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .accounts;
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].account, "work");
+    assert_eq!(accounts[0].scopes, ["repo"]);
+    assert_eq!(accounts[0].source, "daemon");
+    assert_eq!(accounts[0].last_used, None);
+
+    // Adding the same account twice is the caller's mistake
+    let status = client
+        .add_account(add_request("github", "work"))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert!(
+        status.message().contains("already exists"),
+        "{}",
+        status.message()
+    );
+
+    drop(client);
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_accounts_status_without_tokens() {
+    let temp_dir = TempDir::new().unwrap();
+    let (handle, mut client) = start_test_server(&temp_dir).await;
+    client
+        .add_account(add_request("github", "work"))
+        .await
+        .unwrap();
+
+    let status = client
+        .accounts_status(proto::AccountsStatusRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!status.all_valid);
+    assert!(!status.any_expiring_soon);
+    assert_eq!(status.accounts.len(), 1);
+    assert_eq!(status.accounts[0].service, "github");
+    assert!(!status.accounts[0].token_valid);
+    assert_eq!(status.accounts[0].expires_at, None);
+
+    drop(client);
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_errors_map_to_status_codes() {
+    let temp_dir = TempDir::new().unwrap();
+    let (handle, mut client) = start_test_server(&temp_dir).await;
+
+    let status = client
+        .get_token(proto::GetTokenRequest {
+            service: "github".to_string(),
+            account: "missing".to_string(),
+            force_refresh: false,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(status.message(), "Account github/missing not found");
+
+    let status = client
+        .resolve(proto::ResolveRequest {
+            reference: "not-a-reference".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert!(
+        status.message().starts_with("Invalid reference"),
+        "{}",
+        status.message()
+    );
+
+    // A stored account without a token fails inside the token manager
+    client
+        .add_account(add_request("github", "work"))
+        .await
+        .unwrap();
+    let status = client
+        .get_token(proto::GetTokenRequest {
+            service: "github".to_string(),
+            account: "work".to_string(),
+            force_refresh: false,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Internal);
+
+    drop(client);
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_acl_denies_methods_not_allowed() {
+    // The test process cannot connect as another user, so make it a
+    // non-owner peer by building the ACL for a different owner UID
+    let uid = nix::unistd::getuid().as_raw();
+    let rule = AclRule {
+        peer_uid: Some(uid),
+        peer_gid: None,
+        service_pattern: "*".to_string(),
+        account_pattern: "*".to_string(),
+        allow: vec![RpcMethod::ListAccounts],
+    };
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store).with_acl(Acl::new(uid + 1, vec![rule]));
+    let (_, handle, mut client) = start_with_state(&temp_dir, state).await;
+
+    client
+        .list_accounts(proto::ListAccountsRequest { service: None })
+        .await
+        .unwrap();
+
+    let status = client
+        .add_account(add_request("github", "work"))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+    let status = client
+        .accounts_status(proto::AccountsStatusRequest {})
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+
+    drop(client);
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_stop_closes_listener() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let (socket_path, handle, client) =
+        start_with_state(&temp_dir, ApiState::with_store(store)).await;
+
+    drop(client);
+    handle.stop().await.unwrap();
+    assert!(connect(&socket_path).await.is_err());
+}
+
+#[test]
+fn test_config_grpc_validation() {
+    let mut config = DaemonConfig {
+        grpc_socket_path: Some(PathBuf::from("/tmp/sigilforge-grpc.sock")),
+        ..DaemonConfig::default()
+    };
+    assert!(config.validate().is_ok());
+
+    // Peers and the ACL are checked as on the JSON-RPC socket
+    config.allowed_uids = Some(vec![1001]);
+    config.acl = vec![AclRule::owner(1001)];
+    assert!(config.validate().is_ok());
+
+    config.grpc_socket_path = Some(config.socket_path.clone());
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_client_proto_matches_daemon() {
+    assert_eq!(
+        include_str!("../../sigilforge-client/proto/sigilforge.proto"),
+        include_str!("../proto/sigilforge.proto"),
+        "sigilforge-client/proto/sigilforge.proto is out of date"
+    );
+}
@@ -0,0 +1,79 @@
+//! Integration tests for closing idle connections.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle};
+
+const IDLE_TIMEOUT: Duration = Duration::from_millis(300);
+
+async fn start_test_server(temp_dir: &TempDir) -> (PathBuf, ServerHandle) {
+    let socket_path = temp_dir.path().join("test.sock");
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store).with_idle_timeout(IDLE_TIMEOUT);
+    let handle = start_server(&socket_path, state).await.unwrap();
+    (socket_path, handle)
+}
+
+fn health_check(id: u64) -> String {
+    let request = json!({ "jsonrpc": "2.0", "method": "health_check", "id": id });
+    format!("{}\n", request)
+}
+
+/// Read one line, failing if nothing arrives well after the idle timeout.
+async fn read_line(reader: &mut BufReader<tokio::net::unix::OwnedReadHalf>) -> String {
+    let mut line = String::new();
+    tokio::time::timeout(IDLE_TIMEOUT * 5, reader.read_line(&mut line))
+        .await
+        .expect("connection was neither answered nor closed")
+        .unwrap();
+    line
+}
+
+#[tokio::test]
+async fn test_idle_connection_is_closed() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir).await;
+
+    let stream = UnixStream::connect(&socket_path).await.unwrap();
+    let (reader, _writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    tokio::time::sleep(IDLE_TIMEOUT * 2).await;
+
+    // EOF: the daemon closed its side of the connection
+    assert_eq!(read_line(&mut reader).await, "");
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_requests_reset_idle_timeout() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir).await;
+
+    let stream = UnixStream::connect(&socket_path).await.unwrap();
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    // Together these pauses outlast the timeout, but none does on its own
+    for id in 0..4 {
+        tokio::time::sleep(IDLE_TIMEOUT / 2).await;
+        writer.write_all(health_check(id).as_bytes()).await.unwrap();
+
+        let response: serde_json::Value = serde_json::from_str(&read_line(&mut reader).await)
+            .expect("connection closed while active");
+        assert_eq!(response["id"], id);
+    }
+
+    tokio::time::sleep(IDLE_TIMEOUT * 2).await;
+    assert_eq!(read_line(&mut reader).await, "");
+
+    handle.stop().await.unwrap();
+}
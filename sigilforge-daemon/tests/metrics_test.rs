@@ -0,0 +1,96 @@
+//! Integration test for the Prometheus metrics exporter.
+//!
+//! Only built with `--features metrics`.
+
+#![cfg(feature = "metrics")]
+
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpStream, UnixStream};
+use tokio::time::{sleep, Duration};
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_daemon::api::{start_server, ApiState};
+use sigilforge_daemon::metrics;
+
+/// Send one JSON-RPC request and return the raw response line.
+async fn call(
+    stream: &mut BufReader<UnixStream>,
+    method: &str,
+    params: serde_json::Value,
+    id: u64,
+) -> String {
+    let request = json!({"jsonrpc": "2.0", "method": method, "params": params, "id": id});
+    let mut line = serde_json::to_string(&request).unwrap();
+    line.push('\n');
+    stream.get_mut().write_all(line.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_line(&mut response).await.unwrap();
+    response
+}
+
+/// Fetch `/metrics` with a minimal HTTP/1.0 request.
+async fn scrape(addr: std::net::SocketAddr) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET /metrics HTTP/1.0\r\nHost: localhost\r\n\r\n")
+        .await
+        .unwrap();
+
+    let mut body = String::new();
+    stream.read_to_string(&mut body).await.unwrap();
+    body
+}
+
+fn can_bind_unix_socket() -> bool {
+    let path = std::env::temp_dir().join("sigilforge-metrics-permission-check.sock");
+    let _ = std::fs::remove_file(&path);
+    let ok = std::os::unix::net::UnixListener::bind(&path).is_ok();
+    let _ = std::fs::remove_file(&path);
+    ok
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_rpc_requests_are_exported() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test_rpc_requests_are_exported: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    metrics::install_prometheus_exporter(addr).unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("test.sock");
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let handle = start_server(&socket_path, ApiState::with_store(store))
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(100)).await;
+
+    let mut stream = BufReader::new(UnixStream::connect(&socket_path).await.unwrap());
+    assert!(call(&mut stream, "health_check", json!([]), 1).await.contains("\"result\""));
+    assert!(call(&mut stream, "list_accounts", json!([null]), 2).await.contains("\"result\""));
+
+    let body = scrape(addr).await;
+    assert!(body.starts_with("HTTP/1."), "unexpected response: {}", body);
+    assert!(
+        body.contains(r#"sigilforge_rpc_requests_total{method="health_check"} 1"#),
+        "missing request counter in:\n{}",
+        body
+    );
+    assert!(body.contains("sigilforge_accounts_total 0"), "missing accounts gauge in:\n{}", body);
+    assert!(
+        body.contains("sigilforge_connections_active 1"),
+        "missing connection gauge in:\n{}",
+        body
+    );
+
+    drop(stream);
+    handle.stop().await.unwrap();
+}
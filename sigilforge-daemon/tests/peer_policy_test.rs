@@ -0,0 +1,184 @@
+//! Integration tests for which local users may connect to the daemon.
+//!
+//! Other users cannot be impersonated in a test, so [`PeerPolicy`] is
+//! checked against simulated peer credentials; a real connection checks
+//! that the daemon owner is still accepted when an allowlist is set.
+
+#![cfg(unix)]
+
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_daemon::api::{start_server_with_options, ApiState, PeerPolicy, SocketOptions};
+use sigilforge_daemon::DaemonConfig;
+
+const OWNER: u32 = 1000;
+const OWNER_GID: u32 = 1000;
+
+fn owner_policy(allowed_uids: Option<Vec<u32>>, allow_root: bool) -> PeerPolicy {
+    PeerPolicy {
+        owner_uid: OWNER,
+        allowed_uids,
+        allow_root,
+        allowed_gid: None,
+    }
+}
+
+#[test]
+fn test_without_allowlist_only_owner_connects() {
+    let policy = owner_policy(None, false);
+    assert!(policy.permits(OWNER, OWNER_GID));
+    assert!(!policy.permits(1001, 1001));
+    assert!(!policy.permits(1001, OWNER_GID));
+    assert!(!policy.permits(0, 0));
+}
+
+#[test]
+fn test_allowlisted_uids_connect() {
+    let policy = owner_policy(Some(vec![1001, 1003]), false);
+    assert!(policy.permits(OWNER, OWNER_GID));
+    assert!(policy.permits(1001, 1001));
+    assert!(policy.permits(1003, 100));
+    assert!(!policy.permits(1002, 1002));
+
+    // An empty allowlist still admits the owner
+    let policy = owner_policy(Some(vec![]), false);
+    assert!(policy.permits(OWNER, OWNER_GID));
+    assert!(!policy.permits(1001, 1001));
+}
+
+#[test]
+fn test_root_needs_allow_root() {
+    assert!(!owner_policy(Some(vec![0, 1001]), false).permits(0, 0));
+    assert!(owner_policy(None, true).permits(0, 0));
+
+    // Root running the daemon is its owner
+    let policy = PeerPolicy {
+        owner_uid: 0,
+        ..PeerPolicy::default()
+    };
+    assert!(policy.permits(0, 0));
+    assert!(!policy.permits(1001, 1001));
+}
+
+#[test]
+fn test_socket_group_members_connect() {
+    let policy = PeerPolicy {
+        allowed_gid: Some(2000),
+        ..owner_policy(Some(vec![1001]), false)
+    };
+    assert!(policy.permits(1002, 2000));
+    assert!(policy.permits(1001, 1001));
+    // Root is not admitted through the group
+    assert!(!policy.permits(0, 2000));
+}
+
+#[test]
+fn test_supplementary_group_members_connect() {
+    use nix::unistd::{getgid, getgroups, getuid};
+
+    // Peers are looked up by UID, so the test process stands in for a
+    // peer that is not the daemon owner
+    let (uid, gid) = (getuid().as_raw(), getgid().as_raw());
+    let Some(supplementary) = getgroups()
+        .unwrap()
+        .into_iter()
+        .map(|gid| gid.as_raw())
+        .find(|&other| other != gid)
+    else {
+        eprintln!("Skipping test: no supplementary groups");
+        return;
+    };
+    if uid == 0 {
+        eprintln!("Skipping test: root is never admitted through a group");
+        return;
+    }
+
+    let policy = PeerPolicy {
+        owner_uid: uid + 1,
+        allowed_gid: Some(supplementary),
+        ..PeerPolicy::default()
+    };
+    assert!(policy.permits(uid, gid));
+
+    let policy = PeerPolicy {
+        allowed_gid: Some(u32::MAX - 1),
+        ..policy
+    };
+    assert!(!policy.permits(uid, gid));
+}
+
+#[test]
+fn test_config_peer_options() {
+    let config = DaemonConfig::default();
+    assert!(config.allowed_uids.is_none());
+    assert!(!config.allow_root);
+
+    let config: DaemonConfig = toml::from_str(
+        r#"
+        socket_path = "/tmp/sigilforge.sock"
+        data_dir = "/tmp/sigilforge"
+        socket_mode = 0o666
+        allowed_uids = [1001, 1002]
+        "#,
+    )
+    .unwrap();
+    config.validate().unwrap();
+    let options = config.socket_options();
+    assert_eq!(options.allowed_uids, Some(vec![1001, 1002]));
+    assert!(!options.allow_root);
+}
+
+#[test]
+fn test_config_rejects_root_in_allowlist() {
+    let mut config = DaemonConfig {
+        allowed_uids: Some(vec![0, 1001]),
+        ..DaemonConfig::default()
+    };
+    assert!(config.validate().is_err());
+
+    config.allow_root = true;
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_config_rejects_tcp_with_allowlist() {
+    let config = DaemonConfig {
+        allowed_uids: Some(vec![1001]),
+        listen_tcp: Some("127.0.0.1:7431".parse().unwrap()),
+        ..DaemonConfig::default()
+    };
+    assert!(config.validate().is_err());
+}
+
+#[tokio::test]
+async fn test_owner_connects_with_allowlist() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("allowlist.sock");
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+
+    let options = SocketOptions {
+        allowed_uids: Some(vec![nix::unistd::getuid().as_raw() + 1]),
+        ..SocketOptions::default()
+    };
+    let handle = start_server_with_options(&socket_path, ApiState::with_store(store), options)
+        .await
+        .unwrap();
+
+    let mut stream = UnixStream::connect(&socket_path).await.unwrap();
+    let request = json!({ "jsonrpc": "2.0", "method": "list_accounts", "params": [], "id": 1 });
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .await
+        .unwrap();
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).await.unwrap();
+    let response: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(response["result"]["accounts"], json!([]));
+
+    handle.stop().await.unwrap();
+}
@@ -0,0 +1,171 @@
+//! Integration tests for pipelined JSON-RPC requests.
+//!
+//! Clients may write several requests before reading any responses; the
+//! daemon processes them concurrently and answers in request order unless
+//! ordered pipelining is disabled.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle};
+
+const REQUESTS: u64 = 50;
+
+async fn start_test_server(
+    temp_dir: &TempDir,
+    max_requests: usize,
+    ordered: bool,
+) -> (PathBuf, ServerHandle) {
+    let socket_path = temp_dir.path().join("test.sock");
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store).with_pipelining(max_requests, ordered);
+    let handle = start_server(&socket_path, state).await.unwrap();
+    (socket_path, handle)
+}
+
+fn health_check(id: u64) -> String {
+    json!({ "jsonrpc": "2.0", "method": "health_check", "id": id }).to_string()
+}
+
+/// Write every line before reading, then collect one response per line.
+async fn send_pipelined(socket_path: &Path, lines: &[String]) -> Vec<serde_json::Value> {
+    let stream = UnixStream::connect(socket_path).await.unwrap();
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let batch: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    writer.write_all(batch.as_bytes()).await.unwrap();
+
+    let mut responses = Vec::new();
+    for _ in lines {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        responses.push(serde_json::from_str(&line).unwrap());
+    }
+    responses
+}
+
+/// Write each line and wait for its response before sending the next.
+async fn send_sequential(socket_path: &Path, lines: &[String]) -> Vec<serde_json::Value> {
+    let stream = UnixStream::connect(socket_path).await.unwrap();
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let mut responses = Vec::new();
+    for request in lines {
+        writer
+            .write_all(format!("{}\n", request).as_bytes())
+            .await
+            .unwrap();
+
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        responses.push(serde_json::from_str(&line).unwrap());
+    }
+    responses
+}
+
+#[tokio::test]
+async fn test_pipelined_responses_keep_request_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir, 10, true).await;
+
+    let mut lines: Vec<String> = (0..REQUESTS).map(health_check).collect();
+    // Rejected lines take their place in the order too
+    lines.insert(25, "not json".to_string());
+
+    let responses = send_pipelined(&socket_path, &lines).await;
+
+    assert_eq!(responses[25]["error"]["code"], -32700);
+    assert!(responses[25]["id"].is_null());
+
+    let ids: Vec<u64> = responses
+        .iter()
+        .filter_map(|response| response["id"].as_u64())
+        .collect();
+    assert_eq!(ids, (0..REQUESTS).collect::<Vec<_>>());
+    assert!(responses
+        .iter()
+        .filter(|response| !response["id"].is_null())
+        .all(|response| response["result"].is_object()));
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unordered_pipelining_answers_every_request() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir, 10, false).await;
+
+    let lines: Vec<String> = (0..REQUESTS).map(health_check).collect();
+    let responses = send_pipelined(&socket_path, &lines).await;
+
+    let ids: HashSet<u64> = responses
+        .iter()
+        .map(|response| response["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(ids, (0..REQUESTS).collect::<HashSet<_>>());
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_single_slot_still_serves_pipelined_requests() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir, 1, true).await;
+
+    let lines: Vec<String> = (0..REQUESTS).map(health_check).collect();
+    let responses = send_pipelined(&socket_path, &lines).await;
+
+    let ids: Vec<u64> = responses
+        .iter()
+        .map(|response| response["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(ids, (0..REQUESTS).collect::<Vec<_>>());
+
+    handle.stop().await.unwrap();
+}
+
+/// Compare 50 round trips against the same 50 requests sent as one batch.
+///
+/// Timings are printed rather than asserted since they depend on the
+/// machine; run with `--nocapture` to see them.
+#[tokio::test]
+async fn bench_pipelined_vs_sequential_latency() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir, 10, true).await;
+    let lines: Vec<String> = (0..REQUESTS).map(health_check).collect();
+
+    let started = Instant::now();
+    let sequential = send_sequential(&socket_path, &lines).await;
+    let sequential_time = started.elapsed();
+
+    let started = Instant::now();
+    let pipelined = send_pipelined(&socket_path, &lines).await;
+    let pipelined_time = started.elapsed();
+
+    assert_eq!(sequential.len(), pipelined.len());
+    for (a, b) in sequential.iter().zip(&pipelined) {
+        assert_eq!(a["id"], b["id"]);
+    }
+
+    eprintln!(
+        "{} requests: sequential {:?} ({:?}/request), pipelined {:?} ({:?}/request), {:.1}x",
+        REQUESTS,
+        sequential_time,
+        sequential_time / REQUESTS as u32,
+        pipelined_time,
+        pipelined_time / REQUESTS as u32,
+        sequential_time.as_secs_f64() / pipelined_time.as_secs_f64().max(f64::EPSILON),
+    );
+    assert!(pipelined_time < Duration::from_secs(10));
+
+    handle.stop().await.unwrap();
+}
@@ -0,0 +1,206 @@
+//! Integration tests for `get_token` with registered plugins.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_core::{Account, AccountId, ServiceId, Token, TokenError, TokenManager, TokenSet};
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle};
+use sigilforge_daemon::DaemonPlugin;
+
+/// Serves a fixed token for one service.
+struct StaticTokenPlugin {
+    service: &'static str,
+    token: &'static str,
+}
+
+#[async_trait]
+impl DaemonPlugin for StaticTokenPlugin {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    async fn get_token(&self, _service: &str, account: &str) -> Result<TokenSet, TokenError> {
+        let token = Token::new(format!("{}-{}", self.token, account))
+            .with_scopes(vec!["read".to_string()]);
+        Ok(TokenSet::new(token))
+    }
+
+    async fn can_handle(&self, service: &str) -> bool {
+        service == self.service
+    }
+}
+
+/// Claims every service and always fails.
+struct FailingPlugin;
+
+#[async_trait]
+impl DaemonPlugin for FailingPlugin {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    async fn get_token(&self, service: &str, account: &str) -> Result<TokenSet, TokenError> {
+        Err(TokenError::NotFound {
+            service: service.to_string(),
+            account: account.to_string(),
+        })
+    }
+
+    async fn can_handle(&self, _service: &str) -> bool {
+        true
+    }
+}
+
+/// A state with github/work holding a stored token.
+async fn test_state(temp_dir: &TempDir) -> ApiState {
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store);
+
+    let service = ServiceId::new("github");
+    let account = AccountId::new("work");
+    state
+        .accounts
+        .add_account(Account::new(service.clone(), account.clone(), vec![]))
+        .unwrap();
+    let tokens = TokenSet::new(Token::new("stored"));
+    state
+        .token_manager
+        .store_token_set(&service, &account, tokens)
+        .await
+        .unwrap();
+    state
+}
+
+async fn start(temp_dir: &TempDir, state: ApiState) -> (PathBuf, ServerHandle) {
+    let socket_path = temp_dir.path().join("test.sock");
+    let handle = start_server(&socket_path, state).await.unwrap();
+    (socket_path, handle)
+}
+
+async fn get_token(socket_path: &Path, params: serde_json::Value) -> serde_json::Value {
+    let mut stream = UnixStream::connect(socket_path).await.unwrap();
+    let request = json!({ "jsonrpc": "2.0", "method": "get_token", "params": params, "id": 1 });
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .await
+        .unwrap();
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).await.unwrap();
+    serde_json::from_str(&line).unwrap()
+}
+
+#[tokio::test]
+async fn test_plugin_serves_token_without_stored_account() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut state = test_state(&temp_dir).await;
+    state.register_plugin(Box::new(StaticTokenPlugin {
+        service: "corp",
+        token: "static",
+    }));
+    let (socket_path, handle) = start(&temp_dir, state).await;
+
+    let response = get_token(&socket_path, json!(["corp", "alice"])).await;
+    assert_eq!(response["result"]["token"], "static-alice");
+    assert_eq!(response["result"]["scopes"], json!(["read"]));
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unhandled_service_falls_back_to_token_manager() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut state = test_state(&temp_dir).await;
+    state.register_plugin(Box::new(StaticTokenPlugin {
+        service: "corp",
+        token: "static",
+    }));
+    let (socket_path, handle) = start(&temp_dir, state).await;
+
+    let response = get_token(&socket_path, json!(["github", "work"])).await;
+    assert_eq!(response["result"]["token"], "stored");
+
+    let response = get_token(&socket_path, json!(["gitlab", "work"])).await;
+    let message = response["error"]["message"].as_str().unwrap();
+    assert!(message.contains("not found"), "{}", message);
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_first_matching_plugin_wins() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut state = test_state(&temp_dir).await;
+    state.register_plugin(Box::new(StaticTokenPlugin {
+        service: "corp",
+        token: "static",
+    }));
+    state.register_plugin(Box::new(FailingPlugin));
+    let (socket_path, handle) = start(&temp_dir, state).await;
+
+    let response = get_token(&socket_path, json!(["corp", "alice"])).await;
+    assert_eq!(response["result"]["token"], "static-alice");
+
+    // The failing plugin claims every other service, including stored ones
+    let response = get_token(&socket_path, json!(["github", "work"])).await;
+    let message = response["error"]["message"].as_str().unwrap();
+    assert!(message.contains("Plugin failing failed to get token"), "{}", message);
+
+    handle.stop().await.unwrap();
+}
+
+/// Build `tests/fixtures/test-plugin` and copy the library into `dir`.
+///
+/// The library is built into its own target directory, since cargo holds
+/// the lock on the one running this test.
+#[cfg(feature = "plugins")]
+fn build_fixture_plugin(dir: &Path) {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("test-plugin");
+    let status = std::process::Command::new(env!("CARGO"))
+        .args(["build", "--quiet", "--package", "sigilforge-test-plugin"])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success(), "failed to build the fixture plugin");
+
+    let library = format!(
+        "{}sigilforge_test_plugin.{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_EXTENSION
+    );
+    std::fs::copy(target_dir.join("debug").join(&library), dir.join(&library)).unwrap();
+}
+
+#[cfg(feature = "plugins")]
+#[tokio::test]
+async fn test_plugin_loaded_from_shared_library() {
+    let temp_dir = TempDir::new().unwrap();
+    let plugin_dir = temp_dir.path().join("plugins");
+    std::fs::create_dir(&plugin_dir).unwrap();
+    build_fixture_plugin(&plugin_dir);
+
+    let plugins = sigilforge_daemon::plugin::load_plugins(&plugin_dir).unwrap();
+    assert_eq!(plugins.len(), 1);
+    assert_eq!(plugins[0].name(), "fixture");
+
+    let mut state = test_state(&temp_dir).await;
+    for plugin in plugins {
+        state.register_plugin(plugin);
+    }
+    let (socket_path, handle) = start(&temp_dir, state).await;
+
+    let response = get_token(&socket_path, json!(["fixture", "alice"])).await;
+    assert_eq!(response["result"]["token"], "fixture-alice");
+    let response = get_token(&socket_path, json!(["github", "work"])).await;
+    assert_eq!(response["result"]["token"], "stored");
+
+    handle.stop().await.unwrap();
+}
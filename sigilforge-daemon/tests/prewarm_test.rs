@@ -0,0 +1,70 @@
+//! Integration tests for pre-warming tokens at startup.
+
+use tempfile::TempDir;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_core::{Account, AccountId, ServiceId, Token, TokenManager, TokenSet};
+use sigilforge_daemon::api::{ApiState, PrewarmSummary};
+use sigilforge_daemon::DaemonConfig;
+
+/// Add `account` to the state, with a valid token if `token` is set.
+async fn add_account(state: &ApiState, service: &str, account: &str, token: Option<&str>) {
+    let (service, account) = (ServiceId::new(service), AccountId::new(account));
+    state
+        .accounts
+        .add_account(Account::new(service.clone(), account.clone(), vec![]))
+        .unwrap();
+
+    if let Some(token) = token {
+        let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+        let tokens = TokenSet::new(Token::new(token).with_expiry(expires_at));
+        state
+            .token_manager
+            .store_token_set(&service, &account, tokens)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_pre_warm_counts_ready_and_failed_accounts() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store);
+    add_account(&state, "github", "work", Some("gh-token")).await;
+    add_account(&state, "spotify", "personal", Some("sp-token")).await;
+    add_account(&state, "gitlab", "work", None).await;
+
+    let summary = state.pre_warm_tokens().await;
+    assert_eq!(
+        summary,
+        PrewarmSummary {
+            prewarmed: 2,
+            failed: 1,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_pre_warm_without_accounts() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store);
+
+    assert_eq!(state.pre_warm_tokens().await, PrewarmSummary::default());
+}
+
+#[test]
+fn test_prewarm_on_start_is_on_by_default() {
+    assert!(DaemonConfig::default().prewarm_on_start);
+
+    let config: DaemonConfig = toml::from_str(
+        r#"
+        socket_path = "/tmp/sigilforge.sock"
+        data_dir = "/tmp/sigilforge"
+        prewarm_on_start = false
+        "#,
+    )
+    .unwrap();
+    assert!(!config.prewarm_on_start);
+}
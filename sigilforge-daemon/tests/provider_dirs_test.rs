@@ -0,0 +1,52 @@
+//! Tests for loading provider directories from the daemon configuration.
+
+use std::path::PathBuf;
+
+use sigilforge_daemon::DaemonConfig;
+
+fn fixture_provider_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../sigilforge-core/tests/fixtures/providers")
+}
+
+#[test]
+fn test_provider_dirs_default_to_empty() {
+    let config: DaemonConfig = toml::from_str(
+        r#"
+        socket_path = "/tmp/sigilforge.sock"
+        data_dir = "/tmp/sigilforge"
+        "#,
+    )
+    .unwrap();
+
+    assert!(config.provider_dirs.is_empty());
+    let registry = config.provider_registry().unwrap();
+    assert_eq!(registry.get("github").unwrap().name, "GitHub");
+}
+
+#[test]
+fn test_provider_dirs_are_merged() {
+    let missing = tempfile::TempDir::new().unwrap().path().join("missing");
+    let config = DaemonConfig {
+        provider_dirs: vec![missing, fixture_provider_dir()],
+        ..DaemonConfig::default()
+    };
+
+    let registry = config.provider_registry().unwrap();
+    assert_eq!(registry.get("github").unwrap().name, "GitHub Enterprise");
+    assert_eq!(registry.get("gitea").unwrap().name, "Gitea");
+    assert!(registry.contains("spotify"));
+}
+
+#[test]
+fn test_invalid_provider_file_fails() {
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("broken.toml"), "id = ").unwrap();
+
+    let config = DaemonConfig {
+        provider_dirs: vec![dir.path().to_path_buf()],
+        ..DaemonConfig::default()
+    };
+
+    let err = config.provider_registry().unwrap_err();
+    assert!(format!("{:#}", err).contains("broken.toml"));
+}
@@ -0,0 +1,152 @@
+//! Integration tests for request correlation IDs.
+//!
+//! Each JSON-RPC request gets a UUID that is recorded on its log span and,
+//! when enabled, returned in the response's `x-request-id` field.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle, REQUEST_ID_FIELD};
+
+async fn start_test_server(temp_dir: &TempDir, emit_request_ids: bool) -> (PathBuf, ServerHandle) {
+    let socket_path = temp_dir.path().join("test.sock");
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store).with_request_ids(emit_request_ids);
+    let handle = start_server(&socket_path, state).await.unwrap();
+    (socket_path, handle)
+}
+
+/// Send each request on one connection and collect the responses.
+async fn send_requests(
+    socket_path: &Path,
+    requests: &[serde_json::Value],
+) -> Vec<serde_json::Value> {
+    let stream = UnixStream::connect(socket_path).await.unwrap();
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let mut responses = Vec::new();
+    for request in requests {
+        writer
+            .write_all(format!("{}\n", request).as_bytes())
+            .await
+            .unwrap();
+
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        responses.push(serde_json::from_str(&line).unwrap());
+    }
+    responses
+}
+
+fn health_check(id: u64) -> serde_json::Value {
+    json!({ "jsonrpc": "2.0", "method": "health_check", "id": id })
+}
+
+fn request_id(response: &serde_json::Value) -> String {
+    response[REQUEST_ID_FIELD]
+        .as_str()
+        .expect("response is missing x-request-id")
+        .to_string()
+}
+
+/// Log sink shared between the test and the tracing subscriber.
+#[derive(Clone, Default)]
+struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl LogBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+#[tokio::test]
+async fn test_each_response_has_unique_request_id() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir, true).await;
+
+    let mut responses = send_requests(
+        &socket_path,
+        &[health_check(1), health_check(2), json!({ "jsonrpc": "2.0", "id": 3 })],
+    )
+    .await;
+    // A second connection must not reuse IDs either
+    responses.extend(send_requests(&socket_path, &[health_check(4)]).await);
+
+    let ids: Vec<String> = responses.iter().map(request_id).collect();
+    for id in &ids {
+        assert!(uuid::Uuid::parse_str(id).is_ok(), "{} is not a UUID", id);
+    }
+    let unique: HashSet<&String> = ids.iter().collect();
+    assert_eq!(unique.len(), ids.len());
+
+    // Error responses carry an ID too
+    assert_eq!(responses[2]["error"]["code"], -32600);
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_request_id_omitted_by_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir, false).await;
+
+    let responses = send_requests(&socket_path, &[health_check(1)]).await;
+    assert!(responses[0].get(REQUEST_ID_FIELD).is_none());
+    assert!(responses[0].get("result").is_some());
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_request_id_appears_in_logs() {
+    let logs = LogBuffer::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    // The test runtime is single-threaded, so the server tasks log here too
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, handle) = start_test_server(&temp_dir, true).await;
+
+    let responses = send_requests(&socket_path, &[health_check(1)]).await;
+    let id = request_id(&responses[0]);
+
+    let output = logs.contents();
+    let mentions: Vec<&str> = output.lines().filter(|line| line.contains(&id)).collect();
+    assert!(
+        mentions.iter().any(|line| line.contains("Received health_check request")),
+        "receipt log missing request ID:\n{}",
+        output
+    );
+    assert!(
+        mentions.iter().any(|line| line.contains("Completed health_check request")),
+        "completion log missing request ID:\n{}",
+        output
+    );
+
+    handle.stop().await.unwrap();
+}
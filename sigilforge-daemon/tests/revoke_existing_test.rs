@@ -0,0 +1,260 @@
+//! Integration tests for `add_account` with `revoke_existing`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_core::store::{MemoryStore, Secret, SecretStore, StoreError};
+use sigilforge_core::token_manager::DefaultTokenManager;
+use sigilforge_core::{
+    Account, AccountId, CredentialType, ProviderConfig, ProviderRegistry, ServiceId, Token,
+    TokenManager, TokenSet,
+};
+use sigilforge_daemon::api::{start_server, ApiState, ServerHandle};
+
+/// Start a server whose "test" provider revokes tokens at `server`, with
+/// test/work already added and holding the refresh token "old-refresh".
+async fn start_test_server(
+    temp_dir: &TempDir,
+    server: &MockServer,
+) -> (PathBuf, ApiState, ServerHandle) {
+    let mut providers = ProviderRegistry::new();
+    providers
+        .register(
+            ProviderConfig::new("test", "Test")
+                .with_auth_url(format!("{}/authorize", server.uri()))
+                .with_token_url(format!("{}/token", server.uri()))
+                .with_revoke_url(format!("{}/revoke", server.uri())),
+        )
+        .unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store_and_providers(store, providers);
+
+    let (service, account) = (ServiceId::new("test"), AccountId::new("work"));
+    state
+        .accounts
+        .add_account(Account::new(
+            service.clone(),
+            account.clone(),
+            vec!["old".to_string()],
+        ))
+        .unwrap();
+    state
+        .token_manager
+        .store_credential(&service, &account, CredentialType::ClientId, "client-id")
+        .await
+        .unwrap();
+    let tokens = TokenSet::new(Token::new("old-access")).with_refresh_token("old-refresh");
+    state
+        .token_manager
+        .store_token_set(&service, &account, tokens)
+        .await
+        .unwrap();
+
+    let socket_path = temp_dir.path().join("test.sock");
+    let handle = start_server(&socket_path, state.clone()).await.unwrap();
+    (socket_path, state, handle)
+}
+
+async fn call(socket_path: &Path, method: &str, params: serde_json::Value) -> serde_json::Value {
+    let mut stream = UnixStream::connect(socket_path).await.unwrap();
+    let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .await
+        .unwrap();
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).await.unwrap();
+    serde_json::from_str(&line).unwrap()
+}
+
+#[tokio::test]
+async fn test_revoke_existing_replaces_account() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/revoke"))
+        .and(body_string_contains("token=old-refresh"))
+        .and(body_string_contains("token_type_hint=refresh_token"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, state, handle) = start_test_server(&temp_dir, &server).await;
+    let (service, account) = (ServiceId::new("test"), AccountId::new("work"));
+
+    let response = call(
+        &socket_path,
+        "add_account",
+        json!(["test", "work", ["new"], true]),
+    )
+    .await;
+    assert!(response.get("error").is_none(), "{}", response);
+
+    // The old tokens are gone and the account was added again
+    let tokens = state
+        .token_manager
+        .get_token_set(&service, &account)
+        .await
+        .unwrap();
+    assert!(tokens.is_none());
+    let added = state
+        .accounts
+        .get_account(&service, &account)
+        .unwrap()
+        .unwrap();
+    assert_eq!(added.scopes, vec!["new"]);
+
+    // Tokens from the new authorization are served as usual
+    let tokens = TokenSet::new(Token::new("new-access")).with_refresh_token("new-refresh");
+    state
+        .token_manager
+        .store_token_set(&service, &account, tokens)
+        .await
+        .unwrap();
+    let response = call(&socket_path, "get_token", json!(["test", "work"])).await;
+    assert_eq!(response["result"]["token"], "new-access");
+
+    handle.stop().await.unwrap();
+    server.verify().await;
+}
+
+#[tokio::test]
+async fn test_existing_account_without_revoke_existing_fails() {
+    let server = MockServer::start().await;
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, state, handle) = start_test_server(&temp_dir, &server).await;
+
+    for params in [
+        json!(["test", "work", []]),
+        json!(["test", "work", [], false]),
+    ] {
+        let response = call(&socket_path, "add_account", params).await;
+        let message = response["error"]["message"].as_str().unwrap();
+        assert!(message.contains("already exists"), "{}", message);
+    }
+
+    // Nothing was revoked or deleted
+    assert!(server.received_requests().await.unwrap().is_empty());
+    let tokens = state
+        .token_manager
+        .get_token_set(&ServiceId::new("test"), &AccountId::new("work"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tokens.refresh_token.unwrap().expose(), "old-refresh");
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_failed_revocation_still_replaces_account() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/revoke"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let (socket_path, state, handle) = start_test_server(&temp_dir, &server).await;
+
+    let response = call(
+        &socket_path,
+        "add_account",
+        json!(["test", "work", [], true]),
+    )
+    .await;
+    assert!(response.get("error").is_none(), "{}", response);
+    let tokens = state
+        .token_manager
+        .get_token_set(&ServiceId::new("test"), &AccountId::new("work"))
+        .await
+        .unwrap();
+    assert!(tokens.is_none());
+
+    handle.stop().await.unwrap();
+}
+
+/// A memory store that, like the keyring, cannot list its keys.
+struct UnlistableStore(Arc<MemoryStore>);
+
+#[async_trait]
+impl SecretStore for UnlistableStore {
+    async fn get(&self, key: &str) -> Result<Option<Secret>, StoreError> {
+        self.0.get(key).await
+    }
+
+    async fn set(&self, key: &str, secret: &Secret) -> Result<(), StoreError> {
+        self.0.set(key, secret).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StoreError> {
+        self.0.delete(key).await
+    }
+
+    async fn list_keys(&self, _prefix: &str) -> Result<Vec<String>, StoreError> {
+        Err(StoreError::BackendError {
+            message: "list_keys not supported".to_string(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_revoke_existing_without_key_listing() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let mut state = ApiState::with_store(store);
+    let secrets = Arc::new(MemoryStore::new());
+    let token_manager = DefaultTokenManager::new(
+        Box::new(UnlistableStore(secrets.clone())) as Box<dyn SecretStore>,
+        ProviderRegistry::new(),
+    )
+    .with_account_store(state.accounts.clone());
+    state.token_manager = Arc::new(token_manager);
+
+    let (service, account) = (ServiceId::new("test"), AccountId::new("work"));
+    state
+        .accounts
+        .add_account(Account::new(service.clone(), account.clone(), vec![]))
+        .unwrap();
+    state
+        .token_manager
+        .store_credential(&service, &account, CredentialType::ClientId, "client-id")
+        .await
+        .unwrap();
+    let tokens = TokenSet::new(Token::new("old-access")).with_refresh_token("old-refresh");
+    state
+        .token_manager
+        .store_token_set(&service, &account, tokens)
+        .await
+        .unwrap();
+
+    let socket_path = temp_dir.path().join("test.sock");
+    let handle = start_server(&socket_path, state.clone()).await.unwrap();
+    let response = call(
+        &socket_path,
+        "add_account",
+        json!(["test", "work", [], true]),
+    )
+    .await;
+    assert!(response.get("error").is_none(), "{}", response);
+
+    // The client ID went with the tokens
+    assert!(secrets.list_keys("sigilforge/test/work/").await.unwrap().is_empty());
+    assert!(state.accounts.get_account(&service, &account).unwrap().is_some());
+
+    handle.stop().await.unwrap();
+}
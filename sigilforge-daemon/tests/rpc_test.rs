@@ -302,3 +302,86 @@ async fn test_error_handling() {
 
     handle.stop().await.expect("Failed to stop server");
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_health_check() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test_health_check: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    let (_temp_dir, socket_path, handle) = setup_test_server().await;
+
+    let mut stream = UnixStream::connect(&socket_path)
+        .await
+        .expect("Failed to connect to daemon");
+
+    let _: AddAccountResponse = send_rpc_request(
+        &mut stream,
+        "add_account",
+        json!(["spotify", "personal", ["user-read-email"]]),
+        1,
+    )
+    .await
+    .expect("add_account failed");
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct HealthResponse {
+        version: String,
+        uptime_secs: u64,
+        account_count: usize,
+        backend: String,
+        ok: bool,
+    }
+
+    let health: HealthResponse = send_rpc_request(&mut stream, "health_check", json!([]), 2)
+        .await
+        .expect("health_check failed");
+
+    assert_eq!(health.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(health.account_count, 1);
+    assert_eq!(health.backend, "memory");
+    // Test state uses the memory store deliberately, so it is not degraded
+    assert!(health.ok);
+
+    handle.stop().await.expect("Failed to stop server");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_add_account_refused_when_read_only() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test_add_account_refused_when_read_only: Unix sockets not permitted");
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("test.sock");
+    let store = AccountStore::open_read_only(temp_dir.path().join("accounts.json")).unwrap();
+    let handle = start_server(&socket_path, ApiState::with_store(store))
+        .await
+        .unwrap();
+
+    let mut stream = UnixStream::connect(&socket_path)
+        .await
+        .expect("Failed to connect to daemon");
+
+    let result: Result<AddAccountResponse, _> = send_rpc_request(
+        &mut stream,
+        "add_account",
+        json!(["spotify", "personal", ["user-read-email"]]),
+        1,
+    )
+    .await;
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("read-only"), "unexpected error: {}", error);
+
+    // Reads still work
+    let list_response: ListAccountsResponse =
+        send_rpc_request(&mut stream, "list_accounts", json!([null]), 2)
+            .await
+            .expect("list_accounts failed");
+    assert!(list_response.accounts.is_empty());
+    assert!(!temp_dir.path().join("accounts.json").exists());
+
+    handle.stop().await.expect("Failed to stop server");
+}
@@ -0,0 +1,30 @@
+//! Tests for overriding the socket path with `SIGILFORGE_SOCKET`.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use sigilforge_daemon::DaemonConfig;
+
+#[test]
+fn test_socket_env_overrides_config() {
+    let mut config: DaemonConfig = toml::from_str(
+        r#"
+        socket_path = "/tmp/from-config.sock"
+        data_dir = "/tmp/sigilforge"
+        "#,
+    )
+    .unwrap();
+
+    config.override_socket_path(None);
+    assert_eq!(config.socket_path, PathBuf::from("/tmp/from-config.sock"));
+
+    config.override_socket_path(Some(OsString::new()));
+    assert_eq!(config.socket_path, PathBuf::from("/tmp/from-config.sock"));
+
+    config.override_socket_path(Some(OsString::from("/tmp/from-env.sock")));
+    assert_eq!(config.socket_path, PathBuf::from("/tmp/from-env.sock"));
+
+    let mut defaults = DaemonConfig::default();
+    defaults.override_socket_path(Some(OsString::from("/tmp/from-env.sock")));
+    assert_eq!(defaults.socket_path, PathBuf::from("/tmp/from-env.sock"));
+}
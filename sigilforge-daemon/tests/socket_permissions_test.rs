@@ -0,0 +1,150 @@
+//! Integration tests for socket mode and group ownership configuration.
+//!
+//! These tests verify that the configured mode and group are applied to the
+//! socket file and that clients can still connect afterwards.
+
+#![cfg(unix)]
+
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use tempfile::TempDir;
+use tokio::net::UnixStream;
+use tokio::time::{sleep, Duration};
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_daemon::api::{start_server_with_options, ApiState, SocketOptions};
+use sigilforge_daemon::DaemonConfig;
+
+/// Detect whether the sandbox allows binding Unix sockets. Skip tests if not.
+fn can_bind_unix_socket() -> bool {
+    let path = std::env::temp_dir().join("sigilforge-socket-permission-check.sock");
+    let _ = std::fs::remove_file(&path);
+    let result = std::os::unix::net::UnixListener::bind(&path);
+    let ok = result.is_ok();
+    let _ = std::fs::remove_file(&path);
+    ok
+}
+
+fn test_state(temp_dir: &TempDir) -> ApiState {
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    ApiState::with_store(store)
+}
+
+#[test]
+fn test_config_socket_defaults() {
+    let config = DaemonConfig::default();
+    assert_eq!(config.socket_mode, 0o600);
+    assert!(config.socket_group.is_none());
+
+    let parsed: DaemonConfig = toml::from_str(
+        r#"
+        socket_path = "/tmp/sigilforge.sock"
+        data_dir = "/tmp/sigilforge"
+        socket_group = "developers"
+        socket_mode = 0o660
+        "#,
+    )
+    .unwrap();
+    assert_eq!(parsed.socket_mode, 0o660);
+    assert_eq!(parsed.socket_group.as_deref(), Some("developers"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_socket_mode_applied_and_connect_succeeds() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("mode.sock");
+
+    let options = SocketOptions {
+        mode: 0o660,
+        group: None,
+        ..SocketOptions::default()
+    };
+    let handle = start_server_with_options(&socket_path, test_state(&temp_dir), options)
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(100)).await;
+
+    let mode = std::fs::metadata(&socket_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o660);
+
+    UnixStream::connect(&socket_path)
+        .await
+        .expect("owner should be able to connect");
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_socket_group_applied() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    // Use our own primary group, which we are always allowed to chown to
+    let gid = nix::unistd::getgid();
+    let group = match nix::unistd::Group::from_gid(gid) {
+        Ok(Some(group)) => group,
+        _ => {
+            eprintln!("Skipping test: primary group has no name");
+            return;
+        }
+    };
+
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("group.sock");
+
+    let options = SocketOptions {
+        mode: 0o660,
+        group: Some(group.name.clone()),
+        ..SocketOptions::default()
+    };
+    let handle = start_server_with_options(&socket_path, test_state(&temp_dir), options)
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(std::fs::metadata(&socket_path).unwrap().gid(), gid.as_raw());
+
+    UnixStream::connect(&socket_path)
+        .await
+        .expect("group member should be able to connect");
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_unknown_socket_group_falls_back_to_owner_only() {
+    if !can_bind_unix_socket() {
+        eprintln!("Skipping test: Unix sockets not permitted in sandbox");
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("nogroup.sock");
+
+    let options = SocketOptions {
+        mode: 0o660,
+        group: Some("sigilforge-no-such-group".to_string()),
+        ..SocketOptions::default()
+    };
+    let handle = start_server_with_options(&socket_path, test_state(&temp_dir), options)
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(100)).await;
+
+    // The group mode is not applied without the group
+    let metadata = std::fs::metadata(&socket_path).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    assert_eq!(metadata.uid(), nix::unistd::getuid().as_raw());
+
+    UnixStream::connect(&socket_path)
+        .await
+        .expect("owner should be able to connect");
+
+    handle.stop().await.unwrap();
+}
@@ -0,0 +1,159 @@
+//! Integration tests for the startup token check.
+//!
+//! `ApiState::validate_all_accounts` only logs what it finds, so the tests
+//! capture the log output of a thread-local subscriber.
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use tempfile::TempDir;
+
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_core::{Account, AccountId, ServiceId, Token, TokenManager, TokenSet};
+use sigilforge_daemon::api::{ApiState, StartupValidation};
+use sigilforge_daemon::DaemonConfig;
+
+/// Log sink shared between the test and the tracing subscriber.
+#[derive(Clone, Default)]
+struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl LogBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+/// Add `account` to the state, with a token expiring `minutes` from now
+/// unless `minutes` is `None`.
+async fn add_account(state: &ApiState, service: &str, account: &str, minutes: Option<i64>) {
+    let (service, account) = (ServiceId::new(service), AccountId::new(account));
+    state
+        .accounts
+        .add_account(Account::new(service.clone(), account.clone(), vec![]))
+        .unwrap();
+
+    if let Some(minutes) = minutes {
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(minutes);
+        let tokens = TokenSet::new(Token::new("token").with_expiry(expires_at));
+        state
+            .token_manager
+            .store_token_set(&service, &account, tokens)
+            .await
+            .unwrap();
+    }
+}
+
+/// The first log line mentioning `account`, e.g. `github/work`.
+fn log_line<'a>(logs: &'a str, account: &str) -> &'a str {
+    let needle = format!("Account {} ", account);
+    logs.lines()
+        .find(|line| line.contains(&needle))
+        .unwrap_or_else(|| panic!("no log line for {}:\n{}", account, logs))
+}
+
+#[tokio::test]
+async fn test_logs_status_of_each_account() {
+    let logs = LogBuffer::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store);
+    add_account(&state, "github", "work", Some(120)).await;
+    add_account(&state, "spotify", "personal", Some(-30)).await;
+    add_account(&state, "google", "personal", None).await;
+
+    let result = state.validate_all_accounts().await;
+    assert_eq!(
+        result,
+        StartupValidation {
+            valid: 1,
+            invalid: 2,
+            failed: 0,
+        }
+    );
+
+    let output = logs.contents();
+    let valid = log_line(&output, "github/work");
+    assert!(
+        valid.contains("INFO") && valid.contains("valid token"),
+        "{}",
+        valid
+    );
+    let expired = log_line(&output, "spotify/personal");
+    assert!(
+        expired.contains("WARN") && expired.contains("expired"),
+        "{}",
+        expired
+    );
+    let missing = log_line(&output, "google/personal");
+    assert!(
+        missing.contains("WARN") && missing.contains("no stored token"),
+        "{}",
+        missing
+    );
+    assert!(
+        output.contains("Startup validation: 1 valid, 2 invalid"),
+        "{}",
+        output
+    );
+}
+
+#[tokio::test]
+async fn test_all_expired_is_not_an_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let state = ApiState::with_store(store);
+    add_account(&state, "github", "work", Some(-1)).await;
+    add_account(&state, "github", "personal", Some(-60)).await;
+
+    let result = state.validate_all_accounts().await;
+    assert_eq!(result.valid, 0);
+    assert_eq!(result.invalid, 2);
+
+    // With no accounts there is nothing to check
+    let empty_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(empty_dir.path().join("accounts.json")).unwrap();
+    let result = ApiState::with_store(store).validate_all_accounts().await;
+    assert_eq!(result, StartupValidation::default());
+}
+
+#[test]
+fn test_startup_validation_is_on_by_default() {
+    assert!(DaemonConfig::default().startup_validation);
+
+    let config: DaemonConfig = toml::from_str(
+        r#"
+        socket_path = "/tmp/sigilforge.sock"
+        data_dir = "/tmp/sigilforge"
+        "#,
+    )
+    .unwrap();
+    assert!(config.startup_validation);
+
+    let config: DaemonConfig = toml::from_str(
+        r#"
+        socket_path = "/tmp/sigilforge.sock"
+        data_dir = "/tmp/sigilforge"
+        startup_validation = false
+        "#,
+    )
+    .unwrap();
+    assert!(!config.startup_validation);
+}
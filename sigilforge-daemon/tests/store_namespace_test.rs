@@ -0,0 +1,20 @@
+//! Tests for validating `store_namespace`.
+
+use sigilforge_daemon::DaemonConfig;
+
+#[test]
+fn test_config_rejects_invalid_store_namespace() {
+    for namespace in ["", "my/app", "myapp/"] {
+        let config = DaemonConfig {
+            store_namespace: Some(namespace.to_string()),
+            ..DaemonConfig::default()
+        };
+        assert!(config.validate().is_err(), "{:?}", namespace);
+    }
+
+    let config = DaemonConfig {
+        store_namespace: Some("myapp".to_string()),
+        ..DaemonConfig::default()
+    };
+    assert!(config.validate().is_ok());
+}
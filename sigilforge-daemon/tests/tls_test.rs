@@ -0,0 +1,252 @@
+//! Integration tests for the TCP transport and TLS configuration.
+//!
+//! Certificates are generated with `rcgen` for each test: a CA that signs
+//! the server certificate (for `localhost`) and a client certificate.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+};
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::Duration;
+
+use sigilforge_client::{SigilforgeClientBuilder, SigilforgeError};
+use sigilforge_core::account_store::AccountStore;
+use sigilforge_daemon::api::{start_tcp_server, ApiState, ServerHandle};
+use sigilforge_daemon::{DaemonConfig, TlsConfig};
+
+/// PEM files written for a test.
+struct TestPki {
+    ca: PathBuf,
+    server_cert: PathBuf,
+    server_key: PathBuf,
+    /// Client certificate and key signed by `ca`
+    client: PathBuf,
+    /// Client certificate and key signed by an unrelated CA
+    untrusted_client: PathBuf,
+}
+
+fn generate_ca() -> (Certificate, KeyPair) {
+    let key = KeyPair::generate().unwrap();
+    let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    (params.self_signed(&key).unwrap(), key)
+}
+
+fn generate_leaf(
+    name: &str,
+    usage: ExtendedKeyUsagePurpose,
+    ca: &Certificate,
+    ca_key: &KeyPair,
+) -> (Certificate, KeyPair) {
+    let key = KeyPair::generate().unwrap();
+    let mut params = CertificateParams::new(vec![name.to_string()]).unwrap();
+    params.extended_key_usages = vec![usage];
+    (params.signed_by(&key, ca, ca_key).unwrap(), key)
+}
+
+fn write_pki(dir: &Path) -> TestPki {
+    let (ca, ca_key) = generate_ca();
+    let (server, server_key) =
+        generate_leaf("localhost", ExtendedKeyUsagePurpose::ServerAuth, &ca, &ca_key);
+    let (client, client_key) =
+        generate_leaf("client", ExtendedKeyUsagePurpose::ClientAuth, &ca, &ca_key);
+
+    let (other_ca, other_ca_key) = generate_ca();
+    let (untrusted, untrusted_key) = generate_leaf(
+        "intruder",
+        ExtendedKeyUsagePurpose::ClientAuth,
+        &other_ca,
+        &other_ca_key,
+    );
+
+    let pki = TestPki {
+        ca: dir.join("ca.pem"),
+        server_cert: dir.join("server.pem"),
+        server_key: dir.join("server.key"),
+        client: dir.join("client.pem"),
+        untrusted_client: dir.join("untrusted.pem"),
+    };
+
+    std::fs::write(&pki.ca, ca.pem()).unwrap();
+    std::fs::write(&pki.server_cert, server.pem()).unwrap();
+    std::fs::write(&pki.server_key, server_key.serialize_pem()).unwrap();
+    std::fs::write(&pki.client, client.pem() + &client_key.serialize_pem()).unwrap();
+    std::fs::write(
+        &pki.untrusted_client,
+        untrusted.pem() + &untrusted_key.serialize_pem(),
+    )
+    .unwrap();
+
+    pki
+}
+
+async fn start_test_server(temp_dir: &TempDir, tls: &TlsConfig) -> ServerHandle {
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+    start_tcp_server(addr, ApiState::with_store(store), tls)
+        .await
+        .unwrap()
+}
+
+/// Send a daemon health check through the client library.
+async fn client_round_trip(port: u16, cert: &Path, ca: &Path) -> Result<(), SigilforgeError> {
+    let client = SigilforgeClientBuilder::new()
+        .tcp_address(format!("localhost:{}", port))
+        .with_tls(cert, ca)?
+        .build();
+
+    let health = client.health_check().await?;
+    assert!(health.running);
+    assert_eq!(health.version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+    assert_eq!(health.account_count, Some(0));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tls_without_client_auth() {
+    let temp_dir = TempDir::new().unwrap();
+    let pki = write_pki(temp_dir.path());
+    let tls = TlsConfig {
+        cert_path: pki.server_cert.clone(),
+        key_path: pki.server_key.clone(),
+        client_ca_path: None,
+    };
+
+    let handle = start_test_server(&temp_dir, &tls).await;
+    let port = handle.local_addr().unwrap().port();
+
+    client_round_trip(port, &pki.client, &pki.ca).await.unwrap();
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_plaintext_client_is_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let pki = write_pki(temp_dir.path());
+    let tls = TlsConfig {
+        cert_path: pki.server_cert.clone(),
+        key_path: pki.server_key.clone(),
+        client_ca_path: Some(pki.ca.clone()),
+    };
+
+    let handle = start_test_server(&temp_dir, &tls).await;
+    let addr = handle.local_addr().unwrap();
+
+    // A request sent without a TLS handshake is never answered
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = json!({ "jsonrpc": "2.0", "method": "health_check", "id": 1 });
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .await
+        .unwrap();
+
+    let mut response = Vec::new();
+    let read = stream.read_to_end(&mut response);
+    let _ = tokio::time::timeout(Duration::from_secs(5), read).await;
+    assert!(!String::from_utf8_lossy(&response).contains("jsonrpc"));
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_mutual_tls_accepts_trusted_client() {
+    let temp_dir = TempDir::new().unwrap();
+    let pki = write_pki(temp_dir.path());
+    let tls = TlsConfig {
+        cert_path: pki.server_cert.clone(),
+        key_path: pki.server_key.clone(),
+        client_ca_path: Some(pki.ca.clone()),
+    };
+
+    let handle = start_test_server(&temp_dir, &tls).await;
+    let port = handle.local_addr().unwrap().port();
+
+    client_round_trip(port, &pki.client, &pki.ca).await.unwrap();
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_mutual_tls_rejects_untrusted_client() {
+    let temp_dir = TempDir::new().unwrap();
+    let pki = write_pki(temp_dir.path());
+    let tls = TlsConfig {
+        cert_path: pki.server_cert.clone(),
+        key_path: pki.server_key.clone(),
+        client_ca_path: Some(pki.ca.clone()),
+    };
+
+    let handle = start_test_server(&temp_dir, &tls).await;
+    let port = handle.local_addr().unwrap().port();
+
+    let result = client_round_trip(port, &pki.untrusted_client, &pki.ca).await;
+    assert!(result.is_err(), "untrusted client certificate was accepted");
+
+    handle.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_invalid_tls_files_fail_to_start() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+    let tls = TlsConfig {
+        cert_path: temp_dir.path().join("missing.pem"),
+        key_path: temp_dir.path().join("missing.key"),
+        client_ca_path: None,
+    };
+
+    let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+    let result = start_tcp_server(addr, ApiState::with_store(store), &tls).await;
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_config_rejects_tcp_with_socket_group() {
+    let config: DaemonConfig = toml::from_str(
+        r#"
+        socket_path = "/tmp/sigilforge.sock"
+        data_dir = "/tmp/sigilforge"
+        socket_group = "developers"
+        listen_tcp = "127.0.0.1:7431"
+        "#,
+    )
+    .unwrap();
+
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_config_tcp_validation() {
+    let mut config = DaemonConfig {
+        listen_tcp: Some("127.0.0.1:7431".parse().unwrap()),
+        ..DaemonConfig::default()
+    };
+
+    // TCP needs TLS, even on loopback
+    assert!(config.validate().is_err());
+
+    // Client certificates are optional
+    config.tls = Some(TlsConfig {
+        cert_path: "server.pem".into(),
+        key_path: "server.key".into(),
+        client_ca_path: None,
+    });
+    assert!(config.validate().is_ok());
+
+    config.tls.as_mut().unwrap().client_ca_path = Some("ca.pem".into());
+    assert!(config.validate().is_ok());
+
+    config.listen_tcp = Some("0.0.0.0:7431".parse().unwrap());
+    assert!(config.validate().is_ok());
+
+    // TLS without a TCP listener is a misconfiguration
+    config.listen_tcp = None;
+    assert!(config.validate().is_err());
+}
@@ -1,13 +1,29 @@
 //! Application state management for Sigilforge TUI.
 
+use crate::credentials::{self, StoredCredentials, REVEAL_DURATION};
+use crate::diff::{TokenDiff, TokenInfo};
+use crate::export::{self, AccountEntry, ExportFormat, DEFAULT_EXPORT_FILE};
+use crate::input::TextInput;
+use crate::theme::Theme;
+use crate::wizard::{self, CreationWizard, FlowHandle, WizardEvent, WizardStep};
 use anyhow::Result;
 use chrono::{DateTime, Duration, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use sigilforge_client::{SigilforgeClient, TokenProvider};
+use sigilforge_core::{
+    oauth::pkce::open_browser, AccountStore, CredentialSource, DefaultTokenManager, KeyringStore,
+    ProviderRegistry, Secret, SecretStore, TokenSet,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Instant;
+use serde::Serialize;
+use tokio::sync::mpsc;
 use tracing::{debug, warn};
 
 /// Status of an OAuth account token
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum TokenStatus {
     /// Token is valid and not expiring soon
     Valid,
@@ -19,8 +35,58 @@ pub enum TokenStatus {
     Unknown,
 }
 
+/// Token status the accounts list is narrowed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenStatusFilter {
+    /// Every account
+    All,
+    /// Only [`TokenStatus::Valid`] accounts
+    Valid,
+    /// Only [`TokenStatus::ExpiringSoon`] accounts
+    ExpiringSoon,
+    /// Only [`TokenStatus::Expired`] accounts
+    Expired,
+    /// Only [`TokenStatus::Unknown`] accounts
+    Unknown,
+}
+
+impl TokenStatusFilter {
+    /// The filter after this one when cycling with `f`
+    pub fn next(self) -> Self {
+        match self {
+            TokenStatusFilter::All => TokenStatusFilter::Valid,
+            TokenStatusFilter::Valid => TokenStatusFilter::ExpiringSoon,
+            TokenStatusFilter::ExpiringSoon => TokenStatusFilter::Expired,
+            TokenStatusFilter::Expired => TokenStatusFilter::Unknown,
+            TokenStatusFilter::Unknown => TokenStatusFilter::All,
+        }
+    }
+
+    /// Whether an account with `status` passes the filter
+    pub fn matches(self, status: &TokenStatus) -> bool {
+        match self {
+            TokenStatusFilter::All => true,
+            TokenStatusFilter::Valid => *status == TokenStatus::Valid,
+            TokenStatusFilter::ExpiringSoon => *status == TokenStatus::ExpiringSoon,
+            TokenStatusFilter::Expired => *status == TokenStatus::Expired,
+            TokenStatusFilter::Unknown => *status == TokenStatus::Unknown,
+        }
+    }
+
+    /// Name shown in the status bar
+    pub fn label(self) -> &'static str {
+        match self {
+            TokenStatusFilter::All => "All",
+            TokenStatusFilter::Valid => "Valid",
+            TokenStatusFilter::ExpiringSoon => "Expiring Soon",
+            TokenStatusFilter::Expired => "Expired",
+            TokenStatusFilter::Unknown => "Unknown",
+        }
+    }
+}
+
 /// Information about a configured OAuth account
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AccountInfo {
     pub service: String,
     pub account: String,
@@ -29,6 +95,7 @@ pub struct AccountInfo {
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: String,
     pub last_used: Option<String>,
+    pub source: CredentialSource,
 }
 
 impl AccountInfo {
@@ -60,6 +127,260 @@ impl AccountInfo {
     }
 }
 
+/// A row in the accounts list: either a service heading or an account
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccountRow<'a> {
+    /// Service heading (only present when grouping by service)
+    Heading(&'a str),
+    /// A selectable account entry
+    Account(&'a AccountInfo),
+}
+
+/// How long a notification stays on screen
+const NOTIFICATION_DURATION: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// How long the token diff overlay stays on screen after a refresh
+const TOKEN_DIFF_DURATION: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How long the OAuth progress overlay stays on screen once the flow is done
+const OAUTH_PROGRESS_DURATION: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How long the first key of a sequence like `gg` waits for the second
+pub const KEY_SEQUENCE_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Frames of the spinner shown while an account's token is refreshed
+pub const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// How often the refresh spinner moves on a frame
+const SPINNER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Every key binding as `(keys, action)`, in the order the `?` overlay
+/// lists them
+pub const KEY_BINDINGS: &[(&str, &str)] = &[
+    ("j / ↓", "Next account"),
+    ("k / ↑", "Previous account"),
+    ("gg / Home", "First account"),
+    ("G / End", "Last account"),
+    ("Ctrl+d", "Half page down"),
+    ("Ctrl+u", "Half page up"),
+    ("Ctrl+↓ / Ctrl+↑", "Scroll details"),
+    ("l / →", "Focus details"),
+    ("h / ← / Esc", "Leave details"),
+    ("Enter", "Fold detail section"),
+    ("F", "Fullscreen panel"),
+    ("J", "Raw JSON view"),
+    ("/", "Search accounts"),
+    ("n / N", "Next / previous match"),
+    ("Esc", "Clear search"),
+    ("Tab", "Group by service"),
+    ("f", "Filter by status"),
+    ("r", "Refresh account"),
+    ("a", "Refresh all accounts"),
+    ("n", "New account"),
+    ("e", "Export accounts"),
+    ("c", "Next credential"),
+    ("v", "Reveal credential"),
+    ("?", "Toggle this help"),
+    ("q / Ctrl+c", "Quit"),
+];
+
+/// Outcome of a background token refresh
+#[derive(Debug)]
+struct RefreshResult {
+    /// `(service, account)` that was refreshed
+    key: (String, String),
+    result: std::result::Result<(), String>,
+}
+
+/// A transient message shown as an overlay
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub message: String,
+    shown_at: Instant,
+}
+
+/// Token changes from the last refresh, shown as an overlay
+#[derive(Debug, Clone)]
+pub struct TokenDiffOverlay {
+    /// Refreshed accounts whose token changed, as `(service, account, diff)`
+    pub changes: Vec<(String, String, TokenDiff)>,
+    shown_at: Instant,
+}
+
+/// A step of the account creation OAuth flow, in the order they happen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OAuthStep {
+    /// Binding the callback listener and building the authorization URL
+    BuildingUrl,
+    /// Opening the authorization URL in the browser
+    WaitingForBrowser,
+    /// Waiting for the provider to redirect back with a code
+    ListeningForCallback,
+    /// Exchanging the code for tokens
+    ExchangingCode,
+    /// Saving the account and its tokens
+    StoringTokens,
+    /// The account was added
+    Complete,
+}
+
+impl OAuthStep {
+    /// Every step, in order
+    pub const ALL: [OAuthStep; 6] = [
+        OAuthStep::BuildingUrl,
+        OAuthStep::WaitingForBrowser,
+        OAuthStep::ListeningForCallback,
+        OAuthStep::ExchangingCode,
+        OAuthStep::StoringTokens,
+        OAuthStep::Complete,
+    ];
+
+    /// Description shown in the progress overlay
+    pub fn label(self) -> &'static str {
+        match self {
+            OAuthStep::BuildingUrl => "Building authorization URL",
+            OAuthStep::WaitingForBrowser => "Opening browser",
+            OAuthStep::ListeningForCallback => "Waiting for callback",
+            OAuthStep::ExchangingCode => "Exchanging code for tokens",
+            OAuthStep::StoringTokens => "Storing tokens",
+            OAuthStep::Complete => "Complete",
+        }
+    }
+}
+
+/// A collapsible section of the account detail panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetailSection {
+    /// Granted scopes
+    Scopes,
+    /// When the account was created and last used
+    Timestamps,
+    /// Service, account, credential source and token status
+    Metadata,
+}
+
+impl DetailSection {
+    /// Every section, in the order shown
+    pub const ALL: [DetailSection; 3] = [
+        DetailSection::Metadata,
+        DetailSection::Scopes,
+        DetailSection::Timestamps,
+    ];
+
+    /// Heading shown above the section
+    pub fn title(self) -> &'static str {
+        match self {
+            DetailSection::Scopes => "Scopes",
+            DetailSection::Timestamps => "Timestamps",
+            DetailSection::Metadata => "Metadata",
+        }
+    }
+}
+
+/// A panel of the main screen that can be shown fullscreen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelId {
+    /// The accounts list
+    Accounts,
+    /// The selected account's details
+    Details,
+}
+
+impl PanelId {
+    /// Name shown in the status bar
+    pub fn label(self) -> &'static str {
+        match self {
+            PanelId::Accounts => "Accounts",
+            PanelId::Details => "Details",
+        }
+    }
+}
+
+/// How a step is shown in the progress overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepStatus {
+    Completed,
+    InProgress,
+    Pending,
+    /// The step the flow failed at
+    Failed,
+}
+
+/// Progress of the account creation OAuth flow, shown as an overlay
+#[derive(Debug, Clone, PartialEq)]
+pub struct OAuthProgressState {
+    pub current_step: OAuthStep,
+    /// Time since the flow started; stops when it completes or fails
+    pub elapsed: std::time::Duration,
+    /// Why the flow failed, if it did
+    pub error: Option<String>,
+    started_at: Instant,
+    /// When the flow completed or failed
+    finished_at: Option<Instant>,
+}
+
+impl OAuthProgressState {
+    /// A flow starting at `now` with the first step in progress
+    pub fn new(now: Instant) -> Self {
+        Self {
+            current_step: OAuthStep::BuildingUrl,
+            elapsed: std::time::Duration::ZERO,
+            error: None,
+            started_at: now,
+            finished_at: None,
+        }
+    }
+
+    /// Move on to `step`
+    ///
+    /// Steps never go backwards, and a finished flow does not change.
+    pub fn advance(&mut self, step: OAuthStep, now: Instant) {
+        if self.is_finished() || step <= self.current_step {
+            return;
+        }
+        self.current_step = step;
+        self.tick(now);
+        if step == OAuthStep::Complete {
+            self.finished_at = Some(now);
+        }
+    }
+
+    /// Stop at the current step because of `message`
+    pub fn fail(&mut self, message: impl Into<String>, now: Instant) {
+        if self.is_finished() {
+            return;
+        }
+        self.tick(now);
+        self.error = Some(message.into());
+        self.finished_at = Some(now);
+    }
+
+    /// Update the elapsed time, unless the flow has finished
+    pub fn tick(&mut self, now: Instant) {
+        if !self.is_finished() {
+            self.elapsed = now.duration_since(self.started_at);
+        }
+    }
+
+    /// Whether the flow completed or failed
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    /// How `step` is shown given the flow's progress
+    pub fn step_status(&self, step: OAuthStep) -> StepStatus {
+        if step < self.current_step || self.current_step == OAuthStep::Complete {
+            StepStatus::Completed
+        } else if step > self.current_step {
+            StepStatus::Pending
+        } else if self.error.is_some() {
+            StepStatus::Failed
+        } else {
+            StepStatus::InProgress
+        }
+    }
+}
+
 /// Format a duration as a human-readable string
 fn format_duration(duration: Duration) -> String {
     let days = duration.num_days();
@@ -78,16 +399,89 @@ fn format_duration(duration: Duration) -> String {
 
 /// Application state
 pub struct App {
-    /// Sigilforge client for daemon communication
-    client: SigilforgeClient,
+    /// Sigilforge client for daemon communication, shared with refresh tasks
+    client: Arc<SigilforgeClient>,
     /// List of accounts
     pub accounts: Vec<AccountInfo>,
-    /// Currently selected account index
+    /// Currently selected account index (into `sorted_accounts()`)
     pub selected: usize,
+    /// Whether the accounts list is grouped under service headings
+    pub group_by_service: bool,
+    /// Token status the accounts list is narrowed to, cycled with `f`
+    pub status_filter: Option<TokenStatusFilter>,
+    /// Vertical scroll offset of the detail panel
+    pub detail_scroll_offset: u16,
+    /// Largest useful detail scroll offset, updated on each render
+    detail_max_scroll: u16,
+    /// Whether keys move between and toggle detail panel sections
+    pub detail_focused: bool,
+    /// Panel filling the whole content area, toggled with `F`
+    pub fullscreen_panel: Option<PanelId>,
+    /// Whether the detail panel shows the selected account as JSON, toggled
+    /// with `J`
+    pub raw_view: bool,
+    /// Whether the keyboard shortcut overlay is open, toggled with `?`
+    pub show_help: bool,
+    /// Index into [`DetailSection::ALL`] of the section under the cursor
+    pub detail_cursor: usize,
+    /// Collapsed detail sections of each account, by `(service, account)`
+    collapsed_sections: HashMap<(String, String), HashSet<DetailSection>>,
+    /// Where stored credentials are listed and revealed from, if available
+    secrets: Option<Arc<dyn SecretStore>>,
+    /// Credential types stored for the selected account
+    pub stored_credentials: Option<StoredCredentials>,
+    /// Index into `stored_credentials.types` of the credential `v` reveals
+    pub credential_cursor: usize,
+    /// Credential type waiting for `y` to confirm revealing it
+    pub reveal_pending: Option<String>,
+    /// Revealed credential type and when it is redacted again
+    pub reveal_expiry: Option<(String, Instant)>,
+    /// Value of the credential in `reveal_expiry`
+    revealed_value: Option<Secret>,
     /// Whether the daemon is available
     pub daemon_available: bool,
     /// Status message to display
     pub status_message: String,
+    /// Colors used when rendering
+    pub theme: Theme,
+    /// Whether exports include token status
+    include_status: bool,
+    /// Filename prompt, open while choosing where to export
+    pub export_prompt: Option<TextInput>,
+    /// Search prompt, open while typing a `/` query
+    pub search_prompt: Option<TextInput>,
+    /// Active search; `n`/`N` jump between matching accounts while set
+    pub search_query: Option<String>,
+    /// First key of an unfinished sequence such as `gg`
+    pub pending_key: Option<char>,
+    /// When `pending_key` was pressed
+    pending_key_at: Instant,
+    /// Height of the accounts list panel from the last render
+    list_height: u16,
+    /// Account creation overlay, open from `n` until the account is saved
+    pub wizard: Option<CreationWizard>,
+    /// Steps of the wizard's OAuth flow, shown beneath it while it runs
+    pub oauth_progress: Option<OAuthProgressState>,
+    /// Whether the OAuth progress overlay is shown
+    show_progress: bool,
+    /// Overlay notification, cleared after `NOTIFICATION_DURATION`
+    pub notification: Option<Notification>,
+    /// Token details of each account as of its last refresh
+    last_token_info: HashMap<(String, String), TokenInfo>,
+    /// Changes from the last refresh, cleared after `TOKEN_DIFF_DURATION`
+    pub token_diff: Option<TokenDiffOverlay>,
+    /// Accounts whose token is being refreshed, by `(service, account)`
+    pub refresh_in_progress: HashSet<(String, String)>,
+    /// Refreshes that finished while others were still running
+    refresh_results: Vec<RefreshResult>,
+    /// Sending end handed to each refresh task
+    refresh_tx: mpsc::UnboundedSender<RefreshResult>,
+    /// Results of finished refresh tasks
+    refresh_rx: mpsc::UnboundedReceiver<RefreshResult>,
+    /// Spinner frames shown so far, advanced every `SPINNER_INTERVAL`
+    pub tick_count: usize,
+    /// When the spinner last moved on a frame
+    spinner_advanced_at: Instant,
     /// Last refresh time
     last_refresh: Instant,
     /// Auto-refresh interval (30 seconds)
@@ -96,22 +490,65 @@ pub struct App {
 
 impl App {
     /// Create a new application instance
-    pub async fn new() -> Result<Self> {
-        let client = SigilforgeClient::new();
+    ///
+    /// `include_status` controls whether exported accounts carry their token
+    /// status, `show_progress` whether account creation shows its progress.
+    pub async fn new(theme: Theme, include_status: bool, show_progress: bool) -> Result<Self> {
+        let client = Arc::new(SigilforgeClient::new());
 
         // Check daemon availability
         let daemon_available = client.is_daemon_available().await;
+        let (refresh_tx, refresh_rx) = mpsc::unbounded_channel();
 
         let mut app = Self {
             client,
             accounts: Vec::new(),
             selected: 0,
+            group_by_service: false,
+            status_filter: None,
+            detail_scroll_offset: 0,
+            detail_max_scroll: 0,
+            detail_focused: false,
+            fullscreen_panel: None,
+            raw_view: false,
+            show_help: false,
+            detail_cursor: 0,
+            collapsed_sections: HashMap::new(),
+            secrets: KeyringStore::try_new("sigilforge")
+                .map(|store| Arc::new(store) as Arc<dyn SecretStore>)
+                .inspect_err(|e| warn!("Stored credentials unavailable: {}", e))
+                .ok(),
+            stored_credentials: None,
+            credential_cursor: 0,
+            reveal_pending: None,
+            reveal_expiry: None,
+            revealed_value: None,
             daemon_available,
             status_message: if daemon_available {
                 "Connected to Sigilforge daemon".to_string()
             } else {
                 "WARNING: Sigilforge daemon is not available".to_string()
             },
+            theme,
+            include_status,
+            export_prompt: None,
+            search_prompt: None,
+            search_query: None,
+            pending_key: None,
+            pending_key_at: Instant::now(),
+            list_height: 0,
+            wizard: None,
+            oauth_progress: None,
+            show_progress,
+            notification: None,
+            last_token_info: HashMap::new(),
+            token_diff: None,
+            refresh_in_progress: HashSet::new(),
+            refresh_results: Vec::new(),
+            refresh_tx,
+            refresh_rx,
+            tick_count: 0,
+            spinner_advanced_at: Instant::now(),
             last_refresh: Instant::now(),
             refresh_interval: std::time::Duration::from_secs(30),
         };
@@ -138,10 +575,11 @@ impl App {
                 self.status_message = format!("Loaded {} accounts", self.accounts.len());
 
                 // Ensure selection is valid
-                if self.accounts.is_empty() {
-                    self.selected = 0;
-                } else if self.selected >= self.accounts.len() {
-                    self.selected = self.accounts.len() - 1;
+                let visible = self.visible_count();
+                if visible == 0 {
+                    self.set_selected(0);
+                } else if self.selected >= visible {
+                    self.set_selected(visible - 1);
                 }
             }
             Err(e) => {
@@ -166,115 +604,2041 @@ impl App {
         Ok(vec![])
     }
 
-    /// Refresh the selected account's token
-    pub async fn refresh_selected(&mut self) -> Result<()> {
+    /// Start refreshing the selected account's token
+    ///
+    /// The refresh runs in the background; [`App::tick`] picks up the result.
+    pub fn refresh_selected(&mut self) {
         if self.accounts.is_empty() {
             self.status_message = "No accounts to refresh".to_string();
-            return Ok(());
-        }
-
-        let account = &self.accounts[self.selected];
-        self.status_message = format!(
-            "Refreshing {}/{}...",
-            account.service, account.account
-        );
-
-        match self
-            .client
-            .ensure_token(&account.service, &account.account)
-            .await
-        {
-            Ok(_) => {
-                self.status_message = format!(
-                    "Refreshed {}/{} successfully",
-                    account.service, account.account
-                );
-                self.load_accounts().await?;
-            }
-            Err(e) => {
-                self.status_message = format!("Failed to refresh token: {}", e);
-            }
+            return;
         }
 
-        Ok(())
+        let (service, account) = match self.selected_account() {
+            Some(a) => (a.service.clone(), a.account.clone()),
+            None => return,
+        };
+        self.status_message = format!("Refreshing {}/{}...", service, account);
+        self.snapshot_token_info();
+        self.spawn_refresh(service, account);
     }
 
-    /// Refresh all accounts
-    pub async fn refresh_all(&mut self) -> Result<()> {
+    /// Start refreshing every account's token
+    pub fn refresh_all(&mut self) {
         if self.accounts.is_empty() {
             self.status_message = "No accounts to refresh".to_string();
-            return Ok(());
+            return;
         }
 
         self.status_message = "Refreshing all accounts...".to_string();
+        self.snapshot_token_info();
 
-        let mut success_count = 0;
-        let mut error_count = 0;
+        let keys: Vec<(String, String)> = self
+            .accounts
+            .iter()
+            .map(|a| (a.service.clone(), a.account.clone()))
+            .collect();
+        for (service, account) in keys {
+            self.spawn_refresh(service, account);
+        }
+    }
 
-        for account in &self.accounts {
-            match self
-                .client
-                .ensure_token(&account.service, &account.account)
+    /// Refresh one account's token in a background task
+    ///
+    /// Does nothing if the account is already being refreshed.
+    fn spawn_refresh(&mut self, service: String, account: String) {
+        let key = (service, account);
+        if !self.refresh_in_progress.insert(key.clone()) {
+            return;
+        }
+
+        let client = Arc::clone(&self.client);
+        let results = self.refresh_tx.clone();
+        tokio::spawn(async move {
+            let result = client
+                .ensure_token(&key.0, &key.1)
                 .await
-            {
-                Ok(_) => success_count += 1,
-                Err(_) => error_count += 1,
-            }
+                .map(|_| ())
+                .map_err(|e| e.to_string());
+            let _ = results.send(RefreshResult { key, result });
+        });
+    }
+
+    /// Collect finished refreshes
+    ///
+    /// Once the last running refresh finishes, reloads the accounts and
+    /// reports the outcome of every refresh since the list was last idle.
+    async fn poll_refreshes(&mut self) -> Result<()> {
+        while let Ok(finished) = self.refresh_rx.try_recv() {
+            self.refresh_in_progress.remove(&finished.key);
+            self.refresh_results.push(finished);
+        }
+        if self.refresh_results.is_empty() || !self.refresh_in_progress.is_empty() {
+            return Ok(());
         }
 
-        self.status_message = format!(
-            "Refreshed {} accounts, {} errors",
-            success_count, error_count
-        );
+        let results = std::mem::take(&mut self.refresh_results);
+        let refreshed: Vec<(String, String)> = results
+            .iter()
+            .filter(|r| r.result.is_ok())
+            .map(|r| r.key.clone())
+            .collect();
         self.load_accounts().await?;
+        self.show_token_diff(&refreshed);
+        self.status_message = match results.as_slice() {
+            [RefreshResult {
+                key: (service, account),
+                result: Ok(()),
+            }] => format!("Refreshed {}/{} successfully", service, account),
+            [RefreshResult { result: Err(e), .. }] => format!("Failed to refresh token: {}", e),
+            _ => format!(
+                "Refreshed {} accounts, {} errors",
+                refreshed.len(),
+                results.len() - refreshed.len()
+            ),
+        };
 
         Ok(())
     }
 
+    /// Spinner frame for `account` if its token is being refreshed
+    pub fn refresh_spinner(&self, account: &AccountInfo) -> Option<char> {
+        let key = (account.service.clone(), account.account.clone());
+        self.refresh_in_progress
+            .contains(&key)
+            .then_some(SPINNER_FRAMES[self.tick_count % SPINNER_FRAMES.len()])
+    }
+
+    /// Move the spinner on a frame if `SPINNER_INTERVAL` has passed
+    fn advance_spinner(&mut self, now: Instant) {
+        if now.duration_since(self.spinner_advanced_at) >= SPINNER_INTERVAL {
+            self.tick_count = self.tick_count.wrapping_add(1);
+            self.spinner_advanced_at = now;
+        }
+    }
+
+    /// Remember the current token details of accounts not seen before
+    ///
+    /// Accounts refreshed earlier keep the snapshot from that refresh.
+    fn snapshot_token_info(&mut self) {
+        for account in &self.accounts {
+            self.last_token_info
+                .entry((account.service.clone(), account.account.clone()))
+                .or_insert_with(|| TokenInfo::from_account(account));
+        }
+    }
+
+    /// Show how the `refreshed` accounts' tokens changed since their
+    /// snapshots, then store their current details as the new snapshots
+    fn show_token_diff(&mut self, refreshed: &[(String, String)]) {
+        let mut changes = Vec::new();
+        for key in refreshed {
+            let Some(account) = self
+                .accounts
+                .iter()
+                .find(|a| a.service == key.0 && a.account == key.1)
+            else {
+                continue;
+            };
+
+            let current = TokenInfo::from_account(account);
+            if let Some(previous) = self.last_token_info.get(key) {
+                let diff = TokenDiff::between(previous, &current);
+                if !diff.is_unchanged() {
+                    changes.push((key.0.clone(), key.1.clone(), diff));
+                }
+            }
+            self.last_token_info.insert(key.clone(), current);
+        }
+
+        self.token_diff = Some(TokenDiffOverlay {
+            changes,
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// Change the selected account, resetting the detail scroll if it moved
+    fn set_selected(&mut self, index: usize) {
+        if index != self.selected {
+            self.selected = index;
+            self.detail_scroll_offset = 0;
+        }
+    }
+
+    /// Number of accounts shown in the list
+    pub fn visible_count(&self) -> usize {
+        self.sorted_accounts().len()
+    }
+
     /// Select the next account
     pub fn select_next(&mut self) {
-        if !self.accounts.is_empty() {
-            self.selected = (self.selected + 1) % self.accounts.len();
+        let visible = self.visible_count();
+        if visible > 0 {
+            self.set_selected((self.selected + 1) % visible);
         }
     }
 
     /// Select the previous account
     pub fn select_previous(&mut self) {
-        if !self.accounts.is_empty() {
-            self.selected = if self.selected == 0 {
-                self.accounts.len() - 1
+        let visible = self.visible_count();
+        if visible > 0 {
+            let index = if self.selected == 0 {
+                visible - 1
             } else {
                 self.selected - 1
             };
+            self.set_selected(index);
         }
     }
 
     /// Select the first account
     pub fn select_first(&mut self) {
-        self.selected = 0;
+        self.set_selected(0);
     }
 
     /// Select the last account
     pub fn select_last(&mut self) {
-        if !self.accounts.is_empty() {
-            self.selected = self.accounts.len() - 1;
+        let visible = self.visible_count();
+        if visible > 0 {
+            self.set_selected(visible - 1);
+        }
+    }
+
+    /// Number of list rows visible in the accounts panel
+    ///
+    /// Computed from the panel height recorded by the last render, minus
+    /// its borders. Never less than one.
+    pub fn page_size(&self) -> usize {
+        usize::from(self.list_height.saturating_sub(2)).max(1)
+    }
+
+    /// Record the accounts panel height; called by the renderer
+    pub fn set_list_height(&mut self, height: u16) {
+        self.list_height = height;
+    }
+
+    /// Move the selection down half a page, stopping at the last account
+    pub fn select_half_page_down(&mut self) {
+        let visible = self.visible_count();
+        if visible > 0 {
+            let step = (self.page_size() / 2).max(1);
+            self.set_selected((self.selected + step).min(visible - 1));
+        }
+    }
+
+    /// Move the selection up half a page, stopping at the first account
+    pub fn select_half_page_up(&mut self) {
+        let step = (self.page_size() / 2).max(1);
+        self.set_selected(self.selected.saturating_sub(step));
+    }
+
+    /// Handle a navigation key, returning whether it was consumed
+    ///
+    /// `g` starts a sequence: a second `g` within [`KEY_SEQUENCE_TIMEOUT`]
+    /// jumps to the first account, anything else (or the timeout) runs the
+    /// pending `g` on its own before the new key is handled.
+    pub fn handle_navigation_key(&mut self, key: KeyEvent, now: Instant) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        if let Some(first) = self.pending_key.take() {
+            let in_time = now.duration_since(self.pending_key_at) < KEY_SEQUENCE_TIMEOUT;
+            if in_time && first == 'g' && key.code == KeyCode::Char('g') && !ctrl {
+                self.select_first();
+                return true;
+            }
+            self.run_pending_key(first);
+        }
+
+        // Esc leaves fullscreen before it does anything else
+        if key.code == KeyCode::Esc && self.fullscreen_panel.is_some() {
+            self.fullscreen_panel = None;
+            return true;
+        }
+
+        // With the detail panel focused, movement keys pick a section;
+        // other keys keep their usual meaning
+        if self.detail_focused && !ctrl {
+            let handled = match key.code {
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.detail_cursor_down();
+                    true
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.detail_cursor_up();
+                    true
+                }
+                KeyCode::Enter => {
+                    self.toggle_section_at_cursor();
+                    true
+                }
+                KeyCode::Esc | KeyCode::Left | KeyCode::Char('h') => {
+                    self.detail_focused = false;
+                    true
+                }
+                _ => false,
+            };
+            if handled {
+                return true;
+            }
+        }
+
+        match key.code {
+            KeyCode::Char('d') if ctrl => self.select_half_page_down(),
+            KeyCode::Char('u') if ctrl => self.select_half_page_up(),
+            KeyCode::Down if ctrl => self.scroll_detail_down(),
+            KeyCode::Up if ctrl => self.scroll_detail_up(),
+            _ if ctrl => return false,
+            KeyCode::Down | KeyCode::Char('j') => self.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.select_previous(),
+            KeyCode::Char('g') => {
+                self.pending_key = Some('g');
+                self.pending_key_at = now;
+            }
+            KeyCode::Home => self.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.select_last(),
+            KeyCode::Right | KeyCode::Char('l') => self.focus_details(),
+            KeyCode::Char('F') => self.toggle_fullscreen(),
+            KeyCode::Char('J') => self.toggle_raw_view(),
+            KeyCode::Char('/') => self.start_search(),
+            KeyCode::Char('n') if self.search_query.is_some() => self.search_next(),
+            KeyCode::Char('N') if self.search_query.is_some() => self.search_previous(),
+            KeyCode::Esc if self.search_query.is_some() => self.clear_search(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Run a pending sequence key on its own once [`KEY_SEQUENCE_TIMEOUT`] passes
+    pub fn expire_pending_key(&mut self, now: Instant) {
+        if self.pending_key.is_some()
+            && now.duration_since(self.pending_key_at) >= KEY_SEQUENCE_TIMEOUT
+        {
+            if let Some(key) = self.pending_key.take() {
+                self.run_pending_key(key);
+            }
+        }
+    }
+
+    /// Standalone action for a key that did not complete a sequence
+    fn run_pending_key(&mut self, key: char) {
+        if key == 'g' {
+            self.select_first();
+        }
+    }
+
+    /// Open the search prompt
+    pub fn start_search(&mut self) {
+        self.search_prompt = Some(TextInput::new(""));
+    }
+
+    /// Close the search prompt without changing the active search
+    pub fn cancel_search(&mut self) {
+        self.search_prompt = None;
+    }
+
+    /// Start searching for the prompt's text and jump to the first match
+    ///
+    /// An empty query clears the active search.
+    pub fn confirm_search(&mut self) {
+        let Some(prompt) = self.search_prompt.take() else {
+            return;
+        };
+
+        let query = prompt.value().trim().to_lowercase();
+        if query.is_empty() {
+            self.clear_search();
+            return;
+        }
+
+        self.search_query = Some(query);
+        if !self.matches_search(self.selected) {
+            self.search_next();
+        } else {
+            self.status_message = self.search_status();
+        }
+    }
+
+    /// Leave search mode
+    pub fn clear_search(&mut self) {
+        self.search_query = None;
+        self.status_message = "Search cleared".to_string();
+    }
+
+    /// Select the next account matching the search, wrapping around
+    pub fn search_next(&mut self) {
+        let (len, selected) = (self.visible_count(), self.selected);
+        self.search_step((1..=len).map(move |offset| (selected + offset) % len));
+    }
+
+    /// Select the previous account matching the search, wrapping around
+    pub fn search_previous(&mut self) {
+        let (len, selected) = (self.visible_count(), self.selected);
+        self.search_step((1..=len).map(move |offset| (selected + len - offset) % len));
+    }
+
+    /// Select the first candidate index that matches the search
+    fn search_step(&mut self, mut candidates: impl Iterator<Item = usize>) {
+        let found = candidates.find(|&i| self.matches_search(i));
+        match found {
+            Some(index) => {
+                self.set_selected(index);
+                self.status_message = self.search_status();
+            }
+            None => {
+                self.status_message = format!(
+                    "No accounts match '{}'",
+                    self.search_query.as_deref().unwrap_or_default()
+                );
+            }
         }
     }
 
+    /// Whether the account at `index` in display order matches the search
+    fn matches_search(&self, index: usize) -> bool {
+        let Some(query) = &self.search_query else {
+            return false;
+        };
+        self.sorted_accounts().get(index).is_some_and(|a| {
+            a.service.to_lowercase().contains(query) || a.account.to_lowercase().contains(query)
+        })
+    }
+
+    /// Status bar text describing the current search position
+    fn search_status(&self) -> String {
+        format!(
+            "Search: {} (n/N for next/previous, Esc to clear)",
+            self.search_query.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// Scroll the detail panel down one line
+    pub fn scroll_detail_down(&mut self) {
+        if self.detail_scroll_offset < self.detail_max_scroll {
+            self.detail_scroll_offset += 1;
+        }
+    }
+
+    /// Scroll the detail panel up one line
+    pub fn scroll_detail_up(&mut self) {
+        self.detail_scroll_offset = self.detail_scroll_offset.saturating_sub(1);
+    }
+
+    /// Move keyboard focus to the detail panel, if an account is selected
+    pub fn focus_details(&mut self) {
+        self.detail_focused = self.selected_account().is_some();
+    }
+
+    /// The panel with keyboard focus
+    pub fn focused_panel(&self) -> PanelId {
+        if self.detail_focused {
+            PanelId::Details
+        } else {
+            PanelId::Accounts
+        }
+    }
+
+    /// Show the focused panel fullscreen, or leave fullscreen
+    pub fn toggle_fullscreen(&mut self) {
+        self.fullscreen_panel = match self.fullscreen_panel {
+            Some(_) => None,
+            None => Some(self.focused_panel()),
+        };
+    }
+
+    /// Open or close the keyboard shortcut overlay
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+
+    /// Handle a key while the shortcut overlay is open
+    ///
+    /// The overlay is modal: `?` or Esc closes it and other keys are ignored.
+    pub fn handle_help_key(&mut self, key: KeyEvent) {
+        if matches!(key.code, KeyCode::Char('?') | KeyCode::Esc) {
+            self.show_help = false;
+        }
+    }
+
+    /// Switch the detail panel between its sections and raw JSON
+    pub fn toggle_raw_view(&mut self) {
+        self.raw_view = !self.raw_view;
+        self.detail_scroll_offset = 0;
+    }
+
+    /// The selected account as pretty-printed JSON for the raw view, with
+    /// its token details under `token_info`
+    pub fn selected_account_json(&self) -> Option<String> {
+        let account = self.selected_account()?;
+        let mut value = serde_json::to_value(account).ok()?;
+        let token_info = serde_json::to_value(TokenInfo::from_account(account)).ok()?;
+        value
+            .as_object_mut()?
+            .insert("token_info".to_string(), token_info);
+        serde_json::to_string_pretty(&value).ok()
+    }
+
+    /// Move the detail cursor to the next section, stopping at the last
+    pub fn detail_cursor_down(&mut self) {
+        self.detail_cursor = (self.detail_cursor + 1).min(DetailSection::ALL.len() - 1);
+    }
+
+    /// Move the detail cursor to the previous section, stopping at the first
+    pub fn detail_cursor_up(&mut self) {
+        self.detail_cursor = self.detail_cursor.saturating_sub(1);
+    }
+
+    /// Collapse or expand the section under the detail cursor for the
+    /// selected account
+    pub fn toggle_section_at_cursor(&mut self) {
+        let Some(account) = self.selected_account() else {
+            return;
+        };
+        let key = (account.service.clone(), account.account.clone());
+        let section = DetailSection::ALL[self.detail_cursor.min(DetailSection::ALL.len() - 1)];
+
+        let collapsed = self.collapsed_sections.entry(key).or_default();
+        if !collapsed.remove(&section) {
+            collapsed.insert(section);
+        }
+    }
+
+    /// Sections collapsed for the selected account
+    pub fn collapsed_sections(&self) -> HashSet<DetailSection> {
+        self.selected_account()
+            .and_then(|account| {
+                self.collapsed_sections
+                    .get(&(account.service.clone(), account.account.clone()))
+            })
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Largest useful detail scroll offset for the last rendered layout
+    pub fn detail_max_scroll(&self) -> u16 {
+        self.detail_max_scroll
+    }
+
+    /// Record how far the detail panel can scroll, clamping the current offset
+    ///
+    /// Called by the renderer once it knows the content and panel heights.
+    pub fn set_detail_max_scroll(&mut self, max: u16) {
+        self.detail_max_scroll = max;
+        self.detail_scroll_offset = self.detail_scroll_offset.min(max);
+    }
+
     /// Get the currently selected account
     pub fn selected_account(&self) -> Option<&AccountInfo> {
-        self.accounts.get(self.selected)
+        self.sorted_accounts().get(self.selected).copied()
     }
 
-    /// Periodic tick for background tasks
-    pub async fn tick(&mut self) -> Result<()> {
-        // Auto-refresh account list periodically
-        if self.last_refresh.elapsed() >= self.refresh_interval {
-            debug!("Auto-refreshing account list");
-            self.load_accounts().await?;
-            self.last_refresh = Instant::now();
+    /// Accounts in display order.
+    ///
+    /// When grouping by service, accounts are ordered by service name
+    /// (stable, so accounts within a service keep their original order).
+    /// Accounts not matching the status filter are left out.
+    pub fn sorted_accounts(&self) -> Vec<&AccountInfo> {
+        let filter = self.status_filter.unwrap_or(TokenStatusFilter::All);
+        self.ordered_accounts()
+            .into_iter()
+            .filter(|a| filter.matches(&a.status))
+            .collect()
+    }
+
+    /// Every account in display order, ignoring the status filter
+    fn ordered_accounts(&self) -> Vec<&AccountInfo> {
+        let mut accounts: Vec<&AccountInfo> = self.accounts.iter().collect();
+        if self.group_by_service {
+            accounts.sort_by(|a, b| a.service.cmp(&b.service));
         }
+        accounts
+    }
 
-        Ok(())
+    /// Show only accounts with the next token status, keeping the selection
+    ///
+    /// If the selected account is filtered out, the visible account closest
+    /// to it in the unfiltered list is selected instead.
+    pub fn cycle_status_filter(&mut self) {
+        let ordered: Vec<(String, String)> = self
+            .ordered_accounts()
+            .iter()
+            .map(|a| (a.service.clone(), a.account.clone()))
+            .collect();
+        let current = self
+            .selected_account()
+            .and_then(|a| ordered.iter().position(|(s, n)| *s == a.service && *n == a.account));
+
+        let next = self.status_filter.unwrap_or(TokenStatusFilter::All).next();
+        self.status_filter = (next != TokenStatusFilter::All).then_some(next);
+
+        // Position of each visible account in the unfiltered order
+        let visible: Vec<usize> = self
+            .sorted_accounts()
+            .iter()
+            .filter_map(|a| ordered.iter().position(|(s, n)| *s == a.service && *n == a.account))
+            .collect();
+        let index = match current {
+            Some(current) => visible
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, position)| (position.abs_diff(current), **position < current))
+                .map_or(0, |(index, _)| index),
+            None => 0,
+        };
+        self.set_selected(index);
+
+        self.status_message = format!(
+            "Filter: {} ({} of {} accounts)",
+            next.label(),
+            visible.len(),
+            self.accounts.len()
+        );
+    }
+
+    /// Rows to render in the accounts list, including service headings
+    /// when grouping is enabled.
+    pub fn account_rows(&self) -> Vec<AccountRow<'_>> {
+        let mut rows = Vec::new();
+        let mut current_service: Option<&str> = None;
+
+        for account in self.sorted_accounts() {
+            if self.group_by_service && current_service != Some(account.service.as_str()) {
+                current_service = Some(account.service.as_str());
+                rows.push(AccountRow::Heading(&account.service));
+            }
+            rows.push(AccountRow::Account(account));
+        }
+
+        rows
+    }
+
+    /// Row index of the selected account within `account_rows()`.
+    ///
+    /// Headings are skipped, so this is always an `AccountRow::Account` row.
+    pub fn selected_row(&self) -> Option<usize> {
+        self.account_rows()
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| matches!(row, AccountRow::Account(_)))
+            .nth(self.selected)
+            .map(|(index, _)| index)
+    }
+
+    /// Toggle grouping by service, keeping the same account selected
+    pub fn toggle_group_by_service(&mut self) {
+        let current = self
+            .selected_account()
+            .map(|a| (a.service.clone(), a.account.clone()));
+
+        self.group_by_service = !self.group_by_service;
+
+        if let Some((service, account)) = current {
+            if let Some(index) = self
+                .sorted_accounts()
+                .iter()
+                .position(|a| a.service == service && a.account == account)
+            {
+                self.selected = index;
+            }
+        }
+
+        self.status_message = if self.group_by_service {
+            "Grouping accounts by service".to_string()
+        } else {
+            "Showing flat account list".to_string()
+        };
+    }
+
+    /// Open the export filename prompt
+    pub fn start_export(&mut self) {
+        self.export_prompt = Some(TextInput::new(DEFAULT_EXPORT_FILE));
+    }
+
+    /// Close the export prompt without exporting
+    pub fn cancel_export(&mut self) {
+        self.export_prompt = None;
+        self.status_message = "Export cancelled".to_string();
+    }
+
+    /// Export accounts to the file named in the prompt
+    ///
+    /// The format follows the file extension (`.csv` or JSON otherwise).
+    pub fn confirm_export(&mut self) {
+        let Some(prompt) = self.export_prompt.take() else {
+            return;
+        };
+
+        let filename = prompt.value().trim();
+        if filename.is_empty() {
+            self.status_message = "Export cancelled: no filename given".to_string();
+            return;
+        }
+
+        let path = PathBuf::from(filename);
+        let entries: Vec<AccountEntry> = self
+            .sorted_accounts()
+            .into_iter()
+            .map(|a| AccountEntry::from_account(a, self.include_status))
+            .collect();
+
+        match export::export_accounts(&entries, ExportFormat::from_path(&path), &path) {
+            Ok(()) => {
+                let message = format!("Exported {} accounts to {}", entries.len(), path.display());
+                self.status_message = message.clone();
+                self.notify(message);
+            }
+            Err(e) => {
+                warn!("Export failed: {:#}", e);
+                self.status_message = format!("Export failed: {:#}", e);
+            }
+        }
+    }
+
+    /// Open the account creation form
+    pub fn start_wizard(&mut self) {
+        self.wizard = Some(CreationWizard::new());
+    }
+
+    /// Insert pasted text into the open form or prompt
+    ///
+    /// Pastes into the account creation form are sanitized as account IDs;
+    /// the search and export prompts drop only control characters such as
+    /// newlines. Pastes with nothing to receive them are ignored.
+    pub fn handle_paste(&mut self, text: &str) {
+        if let Some(wizard) = self.wizard.as_mut() {
+            if wizard.step == WizardStep::Form {
+                wizard.handle_paste(text);
+            }
+            return;
+        }
+        if let Some(prompt) = self.search_prompt.as_mut().or(self.export_prompt.as_mut()) {
+            let text: String = text.chars().filter(|c| !c.is_control()).collect();
+            prompt.insert_str(&text);
+        }
+    }
+
+    /// Close the account creation overlay, stopping any running flow
+    pub fn cancel_wizard(&mut self) {
+        self.wizard = None;
+        self.oauth_progress = None;
+        self.status_message = "Account creation cancelled".to_string();
+    }
+
+    /// Start the OAuth flow for the service and account in the form
+    pub fn submit_wizard(&mut self) {
+        let Some(wizard) = self.wizard.as_mut() else {
+            return;
+        };
+
+        let (service, account) = match wizard.names() {
+            Ok(names) => names,
+            Err(message) => {
+                wizard.fail(message);
+                return;
+            }
+        };
+        if self
+            .accounts
+            .iter()
+            .any(|a| a.service == service && a.account == account)
+        {
+            wizard.fail(format!("{}/{} is already configured", service, account));
+            return;
+        }
+
+        let registry = ProviderRegistry::with_defaults();
+        match wizard::build_flow(&registry, &service, |name| std::env::var(name).ok()) {
+            Ok((flow, scopes)) => {
+                wizard.start(FlowHandle::spawn(flow, scopes.clone()), scopes);
+                self.oauth_progress = self
+                    .show_progress
+                    .then(|| OAuthProgressState::new(Instant::now()));
+                self.status_message = format!("Starting OAuth flow for {}/{}...", service, account);
+            }
+            Err(message) => wizard.fail(message),
+        }
+    }
+
+    /// Act on progress from the account creation flow
+    async fn poll_wizard(&mut self) -> Result<()> {
+        let Some(event) = self.wizard.as_mut().and_then(CreationWizard::poll) else {
+            return Ok(());
+        };
+
+        match event {
+            WizardEvent::AuthorizationUrl(url) => {
+                self.advance_oauth_progress(OAuthStep::WaitingForBrowser);
+                self.status_message = match open_browser(&url) {
+                    Ok(()) => "Waiting for authorization in the browser...".to_string(),
+                    Err(e) => {
+                        warn!("Could not open browser: {}", e);
+                        "Could not open the browser; visit the URL shown".to_string()
+                    }
+                };
+                self.advance_oauth_progress(OAuthStep::ListeningForCallback);
+            }
+            WizardEvent::Progress(step) => self.advance_oauth_progress(step),
+            WizardEvent::Completed(tokens) => {
+                self.advance_oauth_progress(OAuthStep::StoringTokens);
+                self.finish_wizard(tokens).await?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Save the account whose flow just completed
+    async fn finish_wizard(&mut self, tokens: TokenSet) -> Result<()> {
+        let Some(wizard) = self.wizard.as_ref() else {
+            return Ok(());
+        };
+        let Ok((service, account)) = wizard.names() else {
+            return Ok(());
+        };
+        let scopes = wizard.scopes.clone();
+
+        let saved = async {
+            let store = AccountStore::load()?;
+            let manager = DefaultTokenManager::new(
+                KeyringStore::try_new("sigilforge")?,
+                ProviderRegistry::with_defaults(),
+            );
+            wizard::save_account(&store, &manager, &service, &account, scopes, tokens).await
+        }
+        .await;
+
+        match saved {
+            Ok(()) => {
+                self.wizard = None;
+                self.advance_oauth_progress(OAuthStep::Complete);
+                let message = format!("Added account {}/{}", service, account);
+                self.status_message = message.clone();
+                self.notify(message);
+                self.load_accounts().await?;
+            }
+            Err(e) => {
+                warn!("Saving {}/{} failed: {:#}", service, account, e);
+                if let Some(wizard) = self.wizard.as_mut() {
+                    wizard.fail(format!("Could not save account: {:#}", e));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move the OAuth progress overlay on to `step`
+    fn advance_oauth_progress(&mut self, step: OAuthStep) {
+        if let Some(progress) = self.oauth_progress.as_mut() {
+            progress.advance(step, Instant::now());
+        }
+    }
+
+    /// Keep the OAuth progress overlay in step with the wizard
+    ///
+    /// A failure is shown until the error is dismissed, a completed flow for
+    /// `OAUTH_PROGRESS_DURATION` after the wizard closes.
+    fn update_oauth_progress(&mut self, now: Instant) {
+        let Some(progress) = self.oauth_progress.as_mut() else {
+            return;
+        };
+
+        match self.wizard.as_ref().map(|wizard| &wizard.step) {
+            Some(WizardStep::Failed { message }) => progress.fail(message.clone(), now),
+            Some(WizardStep::Form) => self.oauth_progress = None,
+            Some(_) => progress.tick(now),
+            None if progress
+                .finished_at
+                .is_none_or(|at| now.duration_since(at) >= OAUTH_PROGRESS_DURATION) =>
+            {
+                self.oauth_progress = None
+            }
+            None => {}
+        }
+    }
+
+    /// Show a notification overlay
+    pub fn notify(&mut self, message: impl Into<String>) {
+        self.notification = Some(Notification {
+            message: message.into(),
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// List the stored credentials of the selected account, unless they are
+    /// already listed
+    ///
+    /// Selecting another account hides any revealed value.
+    pub async fn load_stored_credentials(&mut self) {
+        let Some(account) = self.selected_account() else {
+            self.stored_credentials = None;
+            self.hide_credential();
+            return;
+        };
+        if self
+            .stored_credentials
+            .as_ref()
+            .is_some_and(|loaded| loaded.is_for(&account.service, &account.account))
+        {
+            return;
+        }
+        let Some(secrets) = self.secrets.clone() else {
+            return;
+        };
+
+        let (service, account) = (account.service.clone(), account.account.clone());
+        self.stored_credentials = Some(credentials::load(secrets.as_ref(), &service, &account).await);
+        self.credential_cursor = 0;
+        self.hide_credential();
+    }
+
+    /// Credential type under the credential cursor
+    pub fn selected_credential(&self) -> Option<&str> {
+        self.stored_credentials
+            .as_ref()
+            .and_then(|loaded| loaded.types.get(self.credential_cursor))
+            .map(String::as_str)
+    }
+
+    /// Move the credential cursor to the next stored credential, wrapping
+    pub fn select_next_credential(&mut self) {
+        let count = self
+            .stored_credentials
+            .as_ref()
+            .map_or(0, |loaded| loaded.types.len());
+        if count > 0 {
+            self.credential_cursor = (self.credential_cursor + 1) % count;
+        }
+    }
+
+    /// Ask for confirmation before revealing the selected credential
+    pub fn request_reveal(&mut self) {
+        let Some(credential_type) = self.selected_credential().map(str::to_string) else {
+            self.status_message = "No stored credential selected".to_string();
+            return;
+        };
+        self.status_message = format!(
+            "Reveal {} for {}s? Press y to confirm",
+            credential_type,
+            REVEAL_DURATION.as_secs()
+        );
+        self.reveal_pending = Some(credential_type);
+    }
+
+    /// Drop a reveal request that was not confirmed
+    pub fn cancel_reveal(&mut self) {
+        if self.reveal_pending.take().is_some() {
+            self.status_message = "Reveal cancelled".to_string();
+        }
+    }
+
+    /// Read and show the credential awaiting confirmation until
+    /// [`REVEAL_DURATION`] after `now`
+    pub async fn confirm_reveal(&mut self, now: Instant) {
+        let Some(credential_type) = self.reveal_pending.take() else {
+            return;
+        };
+        let (Some(secrets), Some(loaded)) = (self.secrets.clone(), self.stored_credentials.as_ref())
+        else {
+            return;
+        };
+
+        let key = format!(
+            "{}{}",
+            credentials::key_prefix(&loaded.service, &loaded.account),
+            credential_type
+        );
+        match secrets.get(&key).await {
+            Ok(Some(value)) => {
+                self.status_message = format!(
+                    "Showing {} for {}s",
+                    credential_type,
+                    REVEAL_DURATION.as_secs()
+                );
+                self.revealed_value = Some(value);
+                self.reveal_expiry = Some((credential_type, now + REVEAL_DURATION));
+            }
+            Ok(None) => {
+                self.status_message = format!("{} is no longer stored", credential_type);
+            }
+            Err(e) => {
+                self.status_message = format!("Could not read {}: {}", credential_type, e);
+            }
+        }
+    }
+
+    /// Value of `credential_type` if it is currently revealed
+    pub fn revealed_value(&self, credential_type: &str) -> Option<&str> {
+        match &self.reveal_expiry {
+            Some((revealed, _)) if revealed == credential_type => {
+                self.revealed_value.as_ref().map(Secret::expose)
+            }
+            _ => None,
+        }
+    }
+
+    /// Redact the revealed credential once its time is up
+    pub fn expire_reveal(&mut self, now: Instant) {
+        if self
+            .reveal_expiry
+            .as_ref()
+            .is_some_and(|(_, expires_at)| now >= *expires_at)
+        {
+            self.hide_credential();
+        }
+    }
+
+    /// Redact the revealed credential and drop any pending reveal
+    fn hide_credential(&mut self) {
+        self.reveal_pending = None;
+        self.reveal_expiry = None;
+        self.revealed_value = None;
+    }
+
+    /// Periodic tick for background tasks
+    pub async fn tick(&mut self) -> Result<()> {
+        self.expire_pending_key(Instant::now());
+        self.advance_spinner(Instant::now());
+        self.poll_wizard().await?;
+        self.poll_refreshes().await?;
+        self.update_oauth_progress(Instant::now());
+        self.load_stored_credentials().await;
+        self.expire_reveal(Instant::now());
+
+        if self
+            .notification
+            .as_ref()
+            .is_some_and(|n| n.shown_at.elapsed() >= NOTIFICATION_DURATION)
+        {
+            self.notification = None;
+        }
+        if self
+            .token_diff
+            .as_ref()
+            .is_some_and(|d| d.shown_at.elapsed() >= TOKEN_DIFF_DURATION)
+        {
+            self.token_diff = None;
+        }
+
+        // Auto-refresh account list periodically
+        if self.last_refresh.elapsed() >= self.refresh_interval {
+            debug!("Auto-refreshing account list");
+            self.load_accounts().await?;
+            self.last_refresh = Instant::now();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl App {
+    /// Create an app with a fixed account list and no daemon connection.
+    pub(crate) fn with_accounts(accounts: Vec<AccountInfo>) -> Self {
+        let (refresh_tx, refresh_rx) = mpsc::unbounded_channel();
+        Self {
+            client: Arc::new(SigilforgeClient::new()),
+            accounts,
+            selected: 0,
+            group_by_service: false,
+            status_filter: None,
+            detail_scroll_offset: 0,
+            detail_max_scroll: 0,
+            detail_focused: false,
+            fullscreen_panel: None,
+            raw_view: false,
+            show_help: false,
+            detail_cursor: 0,
+            collapsed_sections: HashMap::new(),
+            secrets: None,
+            stored_credentials: None,
+            credential_cursor: 0,
+            reveal_pending: None,
+            reveal_expiry: None,
+            revealed_value: None,
+            daemon_available: false,
+            status_message: String::new(),
+            theme: Theme::default(),
+            include_status: true,
+            export_prompt: None,
+            search_prompt: None,
+            search_query: None,
+            pending_key: None,
+            pending_key_at: Instant::now(),
+            list_height: 0,
+            wizard: None,
+            oauth_progress: None,
+            show_progress: true,
+            notification: None,
+            last_token_info: HashMap::new(),
+            token_diff: None,
+            refresh_in_progress: HashSet::new(),
+            refresh_results: Vec::new(),
+            refresh_tx,
+            refresh_rx,
+            tick_count: 0,
+            spinner_advanced_at: Instant::now(),
+            last_refresh: Instant::now(),
+            refresh_interval: std::time::Duration::from_secs(30),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::Event;
+
+    fn account(service: &str, account: &str) -> AccountInfo {
+        AccountInfo {
+            service: service.to_string(),
+            account: account.to_string(),
+            scopes: vec![],
+            status: TokenStatus::Unknown,
+            expires_at: None,
+            created_at: String::new(),
+            last_used: None,
+            source: CredentialSource::Unknown,
+        }
+    }
+
+    fn three_service_app() -> App {
+        App::with_accounts(vec![
+            account("spotify", "personal"),
+            account("github", "work"),
+            account("google", "personal"),
+            account("github", "personal"),
+            account("spotify", "family"),
+        ])
+    }
+
+    fn selected_key(app: &App) -> (String, String) {
+        let a = app.selected_account().unwrap();
+        (a.service.clone(), a.account.clone())
+    }
+
+    fn account_with_status(service: &str, name: &str, status: TokenStatus) -> AccountInfo {
+        AccountInfo {
+            status,
+            ..account(service, name)
+        }
+    }
+
+    /// Accounts with every token status, some statuses more than once
+    fn mixed_status_app() -> App {
+        App::with_accounts(vec![
+            account_with_status("github", "work", TokenStatus::Valid),
+            account_with_status("spotify", "personal", TokenStatus::Expired),
+            account_with_status("google", "personal", TokenStatus::ExpiringSoon),
+            account_with_status("github", "personal", TokenStatus::Unknown),
+            account_with_status("dropbox", "backup", TokenStatus::Valid),
+            account_with_status("spotify", "family", TokenStatus::Expired),
+        ])
+    }
+
+    fn visible_keys(app: &App) -> Vec<String> {
+        app.sorted_accounts()
+            .iter()
+            .map(|a| format!("{}/{}", a.service, a.account))
+            .collect()
+    }
+
+    #[test]
+    fn test_status_filter_values() {
+        let mut app = mixed_status_app();
+
+        let cases = [
+            (
+                TokenStatusFilter::All,
+                vec![
+                    "github/work",
+                    "spotify/personal",
+                    "google/personal",
+                    "github/personal",
+                    "dropbox/backup",
+                    "spotify/family",
+                ],
+            ),
+            (TokenStatusFilter::Valid, vec!["github/work", "dropbox/backup"]),
+            (TokenStatusFilter::ExpiringSoon, vec!["google/personal"]),
+            (TokenStatusFilter::Expired, vec!["spotify/personal", "spotify/family"]),
+            (TokenStatusFilter::Unknown, vec!["github/personal"]),
+        ];
+        for (filter, expected) in cases {
+            app.status_filter = Some(filter);
+            assert_eq!(visible_keys(&app), expected, "filter {:?}", filter);
+        }
+
+        app.status_filter = None;
+        assert_eq!(app.visible_count(), 6);
+    }
+
+    #[test]
+    fn test_status_filter_applies_after_grouping() {
+        let mut app = mixed_status_app();
+        app.toggle_group_by_service();
+        app.status_filter = Some(TokenStatusFilter::Valid);
+
+        assert_eq!(visible_keys(&app), vec!["dropbox/backup", "github/work"]);
+        let headings = app
+            .account_rows()
+            .iter()
+            .filter(|r| matches!(r, AccountRow::Heading(_)))
+            .count();
+        assert_eq!(headings, 2);
+    }
+
+    #[test]
+    fn test_cycle_status_filter() {
+        let mut app = mixed_status_app();
+
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            app.cycle_status_filter();
+            seen.push(app.status_filter);
+        }
+        assert_eq!(
+            seen,
+            vec![
+                Some(TokenStatusFilter::Valid),
+                Some(TokenStatusFilter::ExpiringSoon),
+                Some(TokenStatusFilter::Expired),
+                Some(TokenStatusFilter::Unknown),
+                None,
+            ]
+        );
+        assert!(app.status_message.starts_with("Filter: All"));
+    }
+
+    #[test]
+    fn test_status_filter_keeps_visible_selection() {
+        let mut app = mixed_status_app();
+        app.selected = 4; // dropbox/backup
+
+        app.cycle_status_filter(); // Valid
+        assert_eq!(selected_key(&app), ("dropbox".into(), "backup".into()));
+        assert_eq!(app.selected, 1);
+        assert_eq!(app.status_message, "Filter: Valid (2 of 6 accounts)");
+    }
+
+    #[test]
+    fn test_status_filter_selects_nearest_visible_account() {
+        let mut app = mixed_status_app();
+        app.selected = 3; // github/personal, Unknown
+
+        // Valid: github/work (0) and dropbox/backup (4)
+        app.cycle_status_filter();
+        assert_eq!(selected_key(&app), ("dropbox".into(), "backup".into()));
+
+        // Expiring Soon: only google/personal (2)
+        app.cycle_status_filter();
+        assert_eq!(selected_key(&app), ("google".into(), "personal".into()));
+
+        // Expired: spotify/personal (1) and spotify/family (5)
+        app.cycle_status_filter();
+        assert_eq!(selected_key(&app), ("spotify".into(), "personal".into()));
+
+        // Nothing visible leaves the selection at the top
+        app.accounts.retain(|a| a.status != TokenStatus::Unknown);
+        app.cycle_status_filter();
+        assert_eq!(app.visible_count(), 0);
+        assert_eq!(app.selected, 0);
+        assert!(app.selected_account().is_none());
+    }
+
+    #[test]
+    fn test_navigation_stays_within_filter() {
+        let mut app = mixed_status_app();
+        app.status_filter = Some(TokenStatusFilter::Expired);
+
+        app.select_next();
+        assert_eq!(selected_key(&app), ("spotify".into(), "family".into()));
+        app.select_next();
+        assert_eq!(selected_key(&app), ("spotify".into(), "personal".into()));
+        app.select_last();
+        assert_eq!(app.selected, 1);
+    }
+
+    #[test]
+    fn test_flat_rows_have_no_headings() {
+        let app = three_service_app();
+        let rows = app.account_rows();
+
+        assert_eq!(rows.len(), 5);
+        assert!(rows.iter().all(|r| matches!(r, AccountRow::Account(_))));
+        assert_eq!(app.selected_row(), Some(0));
+    }
+
+    #[test]
+    fn test_grouped_rows_insert_service_headings() {
+        let mut app = three_service_app();
+        app.toggle_group_by_service();
+
+        let rows = app.account_rows();
+        let headings: Vec<&str> = rows
+            .iter()
+            .filter_map(|r| match r {
+                AccountRow::Heading(s) => Some(*s),
+                AccountRow::Account(_) => None,
+            })
+            .collect();
+
+        assert_eq!(headings, vec!["github", "google", "spotify"]);
+        assert_eq!(rows.len(), 8);
+        assert_eq!(rows[0], AccountRow::Heading("github"));
+    }
+
+    #[test]
+    fn test_selected_account_in_both_modes() {
+        let mut app = three_service_app();
+
+        app.selected = 1;
+        assert_eq!(selected_key(&app), ("github".into(), "work".into()));
+
+        app.group_by_service = true;
+        // github/work, github/personal, google/personal, spotify/personal, spotify/family
+        app.selected = 3;
+        assert_eq!(selected_key(&app), ("spotify".into(), "personal".into()));
+        // Three headings precede spotify/personal: github, google, spotify
+        assert_eq!(app.selected_row(), Some(6));
+    }
+
+    #[test]
+    fn test_toggle_preserves_selection() {
+        let mut app = three_service_app();
+        app.selected = 2; // google/personal
+
+        app.toggle_group_by_service();
+        assert_eq!(selected_key(&app), ("google".into(), "personal".into()));
+        assert_eq!(app.selected, 2);
+
+        app.selected = 4; // spotify/family
+        app.toggle_group_by_service();
+        assert_eq!(selected_key(&app), ("spotify".into(), "family".into()));
+        assert_eq!(app.selected, 4);
+    }
+
+    #[test]
+    fn test_detail_scroll_boundaries() {
+        let mut app = three_service_app();
+        app.set_detail_max_scroll(3);
+
+        app.scroll_detail_up();
+        assert_eq!(app.detail_scroll_offset, 0);
+
+        for _ in 0..5 {
+            app.scroll_detail_down();
+        }
+        assert_eq!(app.detail_scroll_offset, 3);
+
+        // Shrinking the content clamps the offset
+        app.set_detail_max_scroll(1);
+        assert_eq!(app.detail_scroll_offset, 1);
+
+        app.set_detail_max_scroll(0);
+        app.scroll_detail_down();
+        assert_eq!(app.detail_scroll_offset, 0);
+    }
+
+    #[test]
+    fn test_detail_scroll_resets_on_selection_change() {
+        let mut app = three_service_app();
+        app.set_detail_max_scroll(10);
+
+        app.scroll_detail_down();
+        app.scroll_detail_down();
+        app.select_next();
+        assert_eq!(app.detail_scroll_offset, 0);
+
+        app.scroll_detail_down();
+        app.select_last();
+        assert_eq!(app.detail_scroll_offset, 0);
+
+        // Re-selecting the same account keeps the offset
+        app.scroll_detail_down();
+        app.select_last();
+        assert_eq!(app.detail_scroll_offset, 1);
+
+        // Toggling grouping keeps the same account selected
+        app.toggle_group_by_service();
+        assert_eq!(app.detail_scroll_offset, 1);
+    }
+
+    #[test]
+    fn test_export_prompt_flow() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts.csv");
+
+        let mut app = three_service_app();
+        app.start_export();
+        assert_eq!(app.export_prompt.as_ref().unwrap().value(), DEFAULT_EXPORT_FILE);
+
+        app.export_prompt = Some(TextInput::new(path.to_str().unwrap()));
+        app.confirm_export();
+
+        assert!(app.export_prompt.is_none());
+        assert!(app.notification.as_ref().unwrap().message.contains("Exported 5 accounts"));
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(csv.lines().count(), 6);
+        assert!(csv.lines().next().unwrap().ends_with("token_valid"));
+    }
+
+    #[test]
+    fn test_export_without_status() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts.csv");
+
+        let mut app = three_service_app();
+        app.include_status = false;
+        app.export_prompt = Some(TextInput::new(path.to_str().unwrap()));
+        app.confirm_export();
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert!(!csv.contains("token_valid"));
+    }
+
+    #[test]
+    fn test_token_diff_compares_against_snapshot() {
+        let mut app = three_service_app();
+        app.snapshot_token_info();
+
+        let work = ("github".to_string(), "work".to_string());
+        let personal = ("spotify".to_string(), "personal".to_string());
+        app.accounts[1].scopes = vec!["repo".to_string()];
+        app.show_token_diff(&[work.clone(), personal]);
+
+        // Only the account whose token changed is listed
+        let changes = &app.token_diff.as_ref().unwrap().changes;
+        assert_eq!(changes.len(), 1);
+        let (service, account, diff) = &changes[0];
+        assert_eq!((service, account), (&work.0, &work.1));
+        assert_eq!(diff.scopes_added, ["repo"]);
+
+        // The refreshed details become the new snapshot
+        assert_eq!(app.last_token_info[&work].scopes, ["repo"]);
+        app.show_token_diff(&[work]);
+        assert!(app.token_diff.as_ref().unwrap().changes.is_empty());
+    }
+
+    #[test]
+    fn test_cancel_export() {
+        let mut app = three_service_app();
+        app.start_export();
+        app.cancel_export();
+
+        assert!(app.export_prompt.is_none());
+        assert!(app.notification.is_none());
+    }
+
+    #[test]
+    fn test_navigation_skips_headings() {
+        let mut app = three_service_app();
+        app.toggle_group_by_service();
+
+        let mut visited = Vec::new();
+        for _ in 0..app.accounts.len() {
+            let row = app.selected_row().unwrap();
+            assert!(matches!(app.account_rows()[row], AccountRow::Account(_)));
+            visited.push(selected_key(&app));
+            app.select_next();
+        }
+
+        assert_eq!(visited.len(), 5);
+        assert_eq!(app.selected, 0);
+    }
+
+    fn press(app: &mut App, code: KeyCode, at: Instant) -> bool {
+        app.handle_navigation_key(KeyEvent::new(code, KeyModifiers::NONE), at)
+    }
+
+    fn press_ctrl(app: &mut App, c: char, at: Instant) -> bool {
+        app.handle_navigation_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL), at)
+    }
+
+    #[test]
+    fn test_help_overlay_toggles_and_is_modal() {
+        let mut app = numbered_app(3);
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        app.toggle_help();
+        assert!(app.show_help);
+
+        // Other keys leave the overlay open and do nothing else
+        app.handle_help_key(key(KeyCode::Char('j')));
+        assert!(app.show_help);
+        assert_eq!(app.selected, 0);
+
+        app.handle_help_key(key(KeyCode::Char('?')));
+        assert!(!app.show_help);
+
+        app.toggle_help();
+        app.handle_help_key(key(KeyCode::Esc));
+        assert!(!app.show_help);
+    }
+
+    fn numbered_app(count: usize) -> App {
+        App::with_accounts((0..count).map(|i| account("svc", &format!("acct{}", i))).collect())
+    }
+
+    #[test]
+    fn test_gg_selects_first_account() {
+        let mut app = three_service_app();
+        let start = Instant::now();
+        app.selected = 3;
+
+        assert!(press(&mut app, KeyCode::Char('g'), start));
+        assert_eq!(app.pending_key, Some('g'));
+        assert_eq!(app.selected, 3);
+
+        assert!(press(&mut app, KeyCode::Char('g'), start + std::time::Duration::from_millis(100)));
+        assert_eq!(app.pending_key, None);
+        assert_eq!(selected_key(&app), ("spotify".into(), "personal".into()));
+    }
+
+    #[test]
+    fn test_pending_g_times_out_as_standalone() {
+        let mut app = three_service_app();
+        let start = Instant::now();
+        app.selected = 2;
+
+        press(&mut app, KeyCode::Char('g'), start);
+        app.expire_pending_key(start + std::time::Duration::from_millis(400));
+        assert_eq!(app.pending_key, Some('g'));
+
+        app.expire_pending_key(start + KEY_SEQUENCE_TIMEOUT);
+        assert_eq!(app.pending_key, None);
+        assert_eq!(app.selected, 0);
+    }
+
+    #[test]
+    fn test_late_second_g_starts_new_sequence() {
+        let mut app = three_service_app();
+        let start = Instant::now();
+        app.selected = 4;
+
+        press(&mut app, KeyCode::Char('g'), start);
+        press(&mut app, KeyCode::Char('g'), start + std::time::Duration::from_millis(600));
+
+        // The first g ran on its own; the second is waiting for a partner
+        assert_eq!(app.selected, 0);
+        assert_eq!(app.pending_key, Some('g'));
+    }
+
+    #[test]
+    fn test_g_followed_by_other_key() {
+        let mut app = three_service_app();
+        let start = Instant::now();
+        app.selected = 3;
+
+        press(&mut app, KeyCode::Char('g'), start);
+        press(&mut app, KeyCode::Char('j'), start + std::time::Duration::from_millis(50));
+
+        assert_eq!(app.pending_key, None);
+        assert_eq!(selected_key(&app), ("github".into(), "work".into()));
+
+        // Unhandled keys still flush the pending g
+        app.selected = 3;
+        press(&mut app, KeyCode::Char('g'), start);
+        assert!(!press(&mut app, KeyCode::Char('r'), start));
+        assert_eq!(app.selected, 0);
+    }
+
+    #[test]
+    fn test_capital_g_selects_last_account() {
+        let mut app = three_service_app();
+        assert!(press(&mut app, KeyCode::Char('G'), Instant::now()));
+        assert_eq!(selected_key(&app), ("spotify".into(), "family".into()));
+    }
+
+    #[test]
+    fn test_page_size_from_list_height() {
+        let mut app = numbered_app(3);
+        assert_eq!(app.page_size(), 1);
+
+        app.set_list_height(12);
+        assert_eq!(app.page_size(), 10);
+    }
+
+    #[test]
+    fn test_half_page_scrolling() {
+        let mut app = numbered_app(30);
+        let now = Instant::now();
+        app.set_list_height(12);
+
+        assert!(press_ctrl(&mut app, 'd', now));
+        assert_eq!(app.selected, 5);
+        press_ctrl(&mut app, 'd', now);
+        assert_eq!(app.selected, 10);
+
+        press_ctrl(&mut app, 'u', now);
+        assert_eq!(app.selected, 5);
+        press_ctrl(&mut app, 'u', now);
+        press_ctrl(&mut app, 'u', now);
+        assert_eq!(app.selected, 0);
+
+        // Stops at the end instead of wrapping
+        app.selected = 27;
+        press_ctrl(&mut app, 'd', now);
+        assert_eq!(app.selected, 29);
+    }
+
+    #[test]
+    fn test_search_next_and_previous() {
+        let mut app = three_service_app();
+        let now = Instant::now();
+
+        // n and N do nothing outside search mode
+        assert!(!press(&mut app, KeyCode::Char('n'), now));
+
+        assert!(press(&mut app, KeyCode::Char('/'), now));
+        for c in "PERSONAL".chars() {
+            app.search_prompt.as_mut().unwrap().insert(c);
+        }
+        app.confirm_search();
+        assert_eq!(app.search_query.as_deref(), Some("personal"));
+        assert_eq!(selected_key(&app), ("spotify".into(), "personal".into()));
+
+        press(&mut app, KeyCode::Char('n'), now);
+        assert_eq!(selected_key(&app), ("google".into(), "personal".into()));
+        press(&mut app, KeyCode::Char('n'), now);
+        assert_eq!(selected_key(&app), ("github".into(), "personal".into()));
+        press(&mut app, KeyCode::Char('n'), now);
+        assert_eq!(selected_key(&app), ("spotify".into(), "personal".into()));
+
+        press(&mut app, KeyCode::Char('N'), now);
+        assert_eq!(selected_key(&app), ("github".into(), "personal".into()));
+
+        press(&mut app, KeyCode::Esc, now);
+        assert!(app.search_query.is_none());
+    }
+
+    #[test]
+    fn test_search_without_matches() {
+        let mut app = three_service_app();
+        app.selected = 1;
+        app.start_search();
+        app.search_prompt.as_mut().unwrap().insert('z');
+        app.confirm_search();
+
+        assert_eq!(app.selected, 1);
+        assert!(app.status_message.contains("No accounts match"));
+    }
+
+    #[test]
+    fn test_oauth_progress_steps_only_move_forward() {
+        let start = Instant::now();
+        let at = |secs| start + std::time::Duration::from_secs(secs);
+        let mut progress = OAuthProgressState::new(start);
+        assert_eq!(progress.current_step, OAuthStep::BuildingUrl);
+        let status = |progress: &OAuthProgressState, step| progress.step_status(step);
+        assert_eq!(
+            status(&progress, OAuthStep::BuildingUrl),
+            StepStatus::InProgress
+        );
+        assert_eq!(status(&progress, OAuthStep::Complete), StepStatus::Pending);
+
+        progress.advance(OAuthStep::ListeningForCallback, at(2));
+        assert_eq!(progress.current_step, OAuthStep::ListeningForCallback);
+        assert_eq!(progress.elapsed, std::time::Duration::from_secs(2));
+        assert_eq!(
+            status(&progress, OAuthStep::WaitingForBrowser),
+            StepStatus::Completed
+        );
+
+        // A late report of an earlier step is ignored
+        progress.advance(OAuthStep::WaitingForBrowser, at(3));
+        assert_eq!(progress.current_step, OAuthStep::ListeningForCallback);
+
+        progress.tick(at(5));
+        assert_eq!(progress.elapsed, std::time::Duration::from_secs(5));
+
+        progress.advance(OAuthStep::Complete, at(6));
+        assert!(progress.is_finished());
+        assert!(OAuthStep::ALL
+            .iter()
+            .all(|&step| progress.step_status(step) == StepStatus::Completed));
+
+        // The clock stops once the flow is done
+        progress.tick(at(9));
+        progress.fail("too late", at(9));
+        assert_eq!(progress.elapsed, std::time::Duration::from_secs(6));
+        assert!(progress.error.is_none());
+    }
+
+    #[test]
+    fn test_oauth_progress_failure() {
+        let start = Instant::now();
+        let at = |secs| start + std::time::Duration::from_secs(secs);
+        let mut progress = OAuthProgressState::new(start);
+        progress.advance(OAuthStep::ExchangingCode, start);
+        progress.fail("token exchange failed", at(1));
+
+        assert_eq!(progress.error.as_deref(), Some("token exchange failed"));
+        let status = |step| progress.step_status(step);
+        assert_eq!(status(OAuthStep::ExchangingCode), StepStatus::Failed);
+        assert_eq!(status(OAuthStep::StoringTokens), StepStatus::Pending);
+
+        progress.advance(OAuthStep::StoringTokens, at(2));
+        assert_eq!(progress.current_step, OAuthStep::ExchangingCode);
+    }
+
+    /// Deliver a paste the way the main loop does
+    fn send(app: &mut App, event: Event) {
+        if let Event::Paste(text) = event {
+            app.handle_paste(&text);
+        }
+    }
+
+    #[test]
+    fn test_paste_into_wizard_fields() {
+        let mut app = three_service_app();
+        app.start_wizard();
+        send(&mut app, Event::Paste("my-service-name".to_string()));
+
+        let wizard = app.wizard.as_mut().unwrap();
+        assert_eq!(wizard.service.value(), "my-service-name");
+        wizard.handle_form_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        send(&mut app, Event::Paste(" work account!\n".to_string()));
+
+        let wizard = app.wizard.as_ref().unwrap();
+        assert_eq!(wizard.service.value(), "my-service-name");
+        assert_eq!(wizard.account.value(), "workaccount");
+    }
+
+    #[test]
+    fn test_paste_ignored_outside_wizard_form() {
+        let mut app = three_service_app();
+        app.start_wizard();
+        app.wizard
+            .as_mut()
+            .unwrap()
+            .fail("Service name is required");
+        send(&mut app, Event::Paste("github".to_string()));
+        assert_eq!(app.wizard.as_ref().unwrap().service.value(), "");
+
+        // Without a form or prompt open there is nothing to paste into
+        app.cancel_wizard();
+        send(&mut app, Event::Paste("github".to_string()));
+        assert!(app.search_prompt.is_none() && app.export_prompt.is_none());
+    }
+
+    #[test]
+    fn test_paste_into_search_prompt() {
+        let mut app = three_service_app();
+        app.start_search();
+        send(&mut app, Event::Paste("PERSON\nAL".to_string()));
+        assert_eq!(app.search_prompt.as_ref().unwrap().value(), "PERSONAL");
+
+        app.confirm_search();
+        assert_eq!(app.search_query.as_deref(), Some("personal"));
+        assert_eq!(selected_key(&app), ("spotify".into(), "personal".into()));
+    }
+
+    #[test]
+    fn test_oauth_progress_follows_wizard() {
+        let mut app = App::with_accounts(vec![]);
+        let now = Instant::now();
+        app.start_wizard();
+        app.oauth_progress = Some(OAuthProgressState::new(now));
+
+        // A failure stays on screen until the error is dismissed
+        app.wizard.as_mut().unwrap().fail("failed to bind");
+        app.update_oauth_progress(now);
+        let progress = app.oauth_progress.as_ref().unwrap();
+        assert_eq!(progress.error.as_deref(), Some("failed to bind"));
+
+        app.wizard.as_mut().unwrap().dismiss_error();
+        app.update_oauth_progress(now);
+        assert!(app.oauth_progress.is_none());
+
+        // A completed flow outlives the wizard briefly
+        app.oauth_progress = Some(OAuthProgressState::new(now));
+        app.wizard = None;
+        app.advance_oauth_progress(OAuthStep::Complete);
+        app.update_oauth_progress(Instant::now());
+        assert!(app.oauth_progress.is_some());
+        app.update_oauth_progress(Instant::now() + OAUTH_PROGRESS_DURATION);
+        assert!(app.oauth_progress.is_none());
+
+        app.start_wizard();
+        app.oauth_progress = Some(OAuthProgressState::new(now));
+        app.cancel_wizard();
+        assert!(app.oauth_progress.is_none());
+    }
+
+    #[test]
+    fn test_toggle_section_under_cursor() {
+        let mut app = three_service_app();
+        assert!(app.collapsed_sections().is_empty());
+
+        // Metadata, then Scopes
+        app.toggle_section_at_cursor();
+        app.detail_cursor_down();
+        app.toggle_section_at_cursor();
+        assert_eq!(
+            app.collapsed_sections(),
+            HashSet::from([DetailSection::Metadata, DetailSection::Scopes])
+        );
+
+        // Toggling again expands
+        app.toggle_section_at_cursor();
+        assert_eq!(
+            app.collapsed_sections(),
+            HashSet::from([DetailSection::Metadata])
+        );
+    }
+
+    #[test]
+    fn test_detail_cursor_stays_on_sections() {
+        let mut app = three_service_app();
+        app.detail_cursor_up();
+        assert_eq!(app.detail_cursor, 0);
+
+        for _ in 0..5 {
+            app.detail_cursor_down();
+        }
+        assert_eq!(app.detail_cursor, DetailSection::ALL.len() - 1);
+
+        app.toggle_section_at_cursor();
+        assert_eq!(
+            app.collapsed_sections(),
+            HashSet::from([DetailSection::Timestamps])
+        );
+    }
+
+    #[test]
+    fn test_collapsed_sections_are_per_account() {
+        let mut app = three_service_app();
+        app.toggle_section_at_cursor();
+
+        app.select_next();
+        assert!(app.collapsed_sections().is_empty());
+        app.detail_cursor_down();
+        app.toggle_section_at_cursor();
+        assert_eq!(
+            app.collapsed_sections(),
+            HashSet::from([DetailSection::Scopes])
+        );
+
+        // Regrouping reorders the list but each account keeps its state
+        app.toggle_group_by_service();
+        app.select_first();
+        assert_eq!(selected_key(&app), ("github".into(), "work".into()));
+        assert_eq!(
+            app.collapsed_sections(),
+            HashSet::from([DetailSection::Scopes])
+        );
+
+        app.selected = app
+            .sorted_accounts()
+            .iter()
+            .position(|a| a.service == "spotify")
+            .unwrap();
+        assert_eq!(
+            app.collapsed_sections(),
+            HashSet::from([DetailSection::Metadata])
+        );
+    }
+
+    #[test]
+    fn test_detail_focus_keys() {
+        let mut app = three_service_app();
+        let now = Instant::now();
+
+        // Enter does nothing until the detail panel has focus
+        assert!(!press(&mut app, KeyCode::Enter, now));
+        assert!(press(&mut app, KeyCode::Char('l'), now));
+        assert!(app.detail_focused);
+
+        // j moves the cursor instead of the selection
+        press(&mut app, KeyCode::Char('j'), now);
+        assert_eq!((app.selected, app.detail_cursor), (0, 1));
+        assert!(press(&mut app, KeyCode::Enter, now));
+        assert_eq!(
+            app.collapsed_sections(),
+            HashSet::from([DetailSection::Scopes])
+        );
+
+        // Other keys keep working, and Esc hands focus back to the list
+        assert!(press(&mut app, KeyCode::Char('G'), now));
+        assert_eq!(app.selected, 4);
+        press(&mut app, KeyCode::Esc, now);
+        assert!(!app.detail_focused);
+        press(&mut app, KeyCode::Char('k'), now);
+        assert_eq!(app.selected, 3);
+    }
+
+    #[test]
+    fn test_fullscreen_toggles_focused_panel() {
+        let mut app = three_service_app();
+        let now = Instant::now();
+
+        assert!(press(&mut app, KeyCode::Char('F'), now));
+        assert_eq!(app.fullscreen_panel, Some(PanelId::Accounts));
+        press(&mut app, KeyCode::Char('F'), now);
+        assert_eq!(app.fullscreen_panel, None);
+
+        // Esc leaves fullscreen first and keeps the detail panel focused
+        press(&mut app, KeyCode::Char('l'), now);
+        press(&mut app, KeyCode::Char('F'), now);
+        assert_eq!(app.fullscreen_panel, Some(PanelId::Details));
+        assert!(press(&mut app, KeyCode::Esc, now));
+        assert_eq!(app.fullscreen_panel, None);
+        assert!(app.detail_focused);
+    }
+
+    #[test]
+    fn test_raw_view_toggle_and_json() {
+        let mut app = three_service_app();
+        let now = Instant::now();
+        app.detail_scroll_offset = 2;
+
+        assert!(press(&mut app, KeyCode::Char('J'), now));
+        assert!(app.raw_view);
+        assert_eq!(app.detail_scroll_offset, 0);
+
+        let json = app.selected_account_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let (service, account) = selected_key(&app);
+        assert_eq!(value["service"], service.as_str());
+        assert_eq!(value["account"], account.as_str());
+        assert_eq!(value["status"], "Unknown");
+        assert_eq!(
+            value["token_info"],
+            serde_json::json!({ "expires_at": null, "scopes": [] })
+        );
+
+        press(&mut app, KeyCode::Char('J'), now);
+        assert!(!app.raw_view);
+        assert!(App::with_accounts(vec![]).selected_account_json().is_none());
+    }
+
+    #[test]
+    fn test_detail_focus_needs_an_account() {
+        let mut app = App::with_accounts(vec![]);
+        app.focus_details();
+        assert!(!app.detail_focused);
+    }
+
+    fn finish_refresh(app: &App, service: &str, account: &str, result: Result<(), &str>) {
+        let key = (service.to_string(), account.to_string());
+        let result = result.map_err(str::to_string);
+        app.refresh_tx.send(RefreshResult { key, result }).unwrap();
+    }
+
+    #[test]
+    fn test_refresh_spinner_advances_every_interval() {
+        let mut app = three_service_app();
+        let refreshing = app.accounts[1].clone();
+        app.refresh_in_progress
+            .insert(("github".to_string(), "work".to_string()));
+        assert_eq!(app.refresh_spinner(&app.accounts[0]), None);
+
+        let start = app.spinner_advanced_at;
+        let first = app.refresh_spinner(&refreshing).unwrap();
+        app.advance_spinner(start + SPINNER_INTERVAL / 2);
+        assert_eq!(app.refresh_spinner(&refreshing), Some(first));
+
+        let mut frames = vec![first];
+        for i in 1..=SPINNER_FRAMES.len() as u32 {
+            app.advance_spinner(start + SPINNER_INTERVAL * i);
+            frames.push(app.refresh_spinner(&refreshing).unwrap());
+        }
+        assert_eq!(frames[..SPINNER_FRAMES.len()], SPINNER_FRAMES);
+        assert_eq!(frames.last(), Some(&first));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_spinner_clears_when_refresh_completes() {
+        let mut app = three_service_app();
+        for account in ["work", "personal"] {
+            app.refresh_in_progress
+                .insert(("github".to_string(), account.to_string()));
+        }
+        let work = app.accounts[1].clone();
+        let personal = app.accounts[3].clone();
+
+        finish_refresh(&app, "github", "work", Ok(()));
+        app.tick().await.unwrap();
+        assert_eq!(app.refresh_spinner(&work), None);
+        assert!(app.refresh_spinner(&personal).is_some());
+
+        finish_refresh(&app, "github", "personal", Err("invalid_grant"));
+        app.tick().await.unwrap();
+        assert!(app.refresh_in_progress.is_empty());
+        assert_eq!(app.refresh_spinner(&personal), None);
+        assert!(app.refresh_results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_single_refresh_failure_status() {
+        let mut app = three_service_app();
+        app.refresh_in_progress
+            .insert(("github".to_string(), "work".to_string()));
+
+        finish_refresh(&app, "github", "work", Err("invalid_grant"));
+        app.poll_refreshes().await.unwrap();
+        assert_eq!(app.status_message, "Failed to refresh token: invalid_grant");
+    }
+
+    /// An app over github/work with two credentials in a memory store
+    async fn app_with_credentials() -> App {
+        let store = sigilforge_core::MemoryStore::new();
+        for (key, value) in [
+            ("sigilforge/github/work/refresh_token", "refresh-secret"),
+            ("sigilforge/github/work/access_token", "access-secret"),
+            ("sigilforge/github/personal/access_token", "other-secret"),
+        ] {
+            store.set(key, &Secret::new(value)).await.unwrap();
+        }
+
+        let mut app = App::with_accounts(vec![account("github", "work")]);
+        app.secrets = Some(Arc::new(store));
+        app
+    }
+
+    #[tokio::test]
+    async fn test_stored_credentials_list_selected_account() {
+        let mut app = app_with_credentials().await;
+        app.load_stored_credentials().await;
+
+        let loaded = app.stored_credentials.as_ref().unwrap();
+        assert!(loaded.is_for("github", "work"));
+        assert_eq!(loaded.types, vec!["access_token", "refresh_token"]);
+        assert_eq!(loaded.error, None);
+        assert_eq!(app.selected_credential(), Some("access_token"));
+
+        app.select_next_credential();
+        assert_eq!(app.selected_credential(), Some("refresh_token"));
+        app.select_next_credential();
+        assert_eq!(app.selected_credential(), Some("access_token"));
+    }
+
+    #[tokio::test]
+    async fn test_reveal_requires_confirmation_and_expires() {
+        let mut app = app_with_credentials().await;
+        app.load_stored_credentials().await;
+        let now = Instant::now();
+
+        app.request_reveal();
+        assert_eq!(app.reveal_pending.as_deref(), Some("access_token"));
+        assert_eq!(app.revealed_value("access_token"), None);
+        app.cancel_reveal();
+        app.confirm_reveal(now).await;
+        assert_eq!(app.revealed_value("access_token"), None);
+
+        app.request_reveal();
+        app.confirm_reveal(now).await;
+        assert_eq!(app.revealed_value("access_token"), Some("access-secret"));
+        assert_eq!(app.revealed_value("refresh_token"), None);
+        assert_eq!(
+            app.reveal_expiry,
+            Some(("access_token".to_string(), now + REVEAL_DURATION))
+        );
+
+        app.expire_reveal(now + REVEAL_DURATION - std::time::Duration::from_secs(1));
+        assert_eq!(app.revealed_value("access_token"), Some("access-secret"));
+
+        app.expire_reveal(now + REVEAL_DURATION);
+        assert_eq!(app.revealed_value("access_token"), None);
+        assert_eq!(app.reveal_expiry, None);
     }
 }
@@ -0,0 +1,102 @@
+//! Stored credentials of the selected account, shown in the detail panel.
+//!
+//! Only credential type names are listed; a value is read from the store
+//! when the user asks to reveal it and is hidden again after
+//! [`REVEAL_DURATION`].
+
+use sigilforge_core::store::is_ttl_key;
+use sigilforge_core::{SecretStore, StoreError};
+use std::time::Duration;
+
+/// How long a revealed credential value stays on screen
+pub const REVEAL_DURATION: Duration = Duration::from_secs(10);
+
+/// Shown in place of credential values that are not revealed
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Credential types probed for when the store cannot list its keys
+///
+/// These are the types the CLI, daemon and token manager write.
+const KNOWN_TYPES: &[&str] = &[
+    "access_token",
+    "refresh_token",
+    "token_expiry",
+    "token_scopes",
+    "api_key",
+    "client_id",
+    "client_secret",
+    "subject",
+    "instance_url",
+    "app_id",
+    "installation_id",
+    "private_key",
+    "api_url",
+];
+
+/// Credential types stored for one account
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredCredentials {
+    pub service: String,
+    pub account: String,
+    /// Credential type names, sorted
+    pub types: Vec<String>,
+    /// Why the types could not be read, if they could not
+    pub error: Option<String>,
+}
+
+impl StoredCredentials {
+    /// Whether these are the credentials of `service`/`account`
+    pub fn is_for(&self, service: &str, account: &str) -> bool {
+        self.service == service && self.account == account
+    }
+}
+
+/// Prefix of the store keys holding the credentials of `service`/`account`
+pub fn key_prefix(service: &str, account: &str) -> String {
+    format!("sigilforge/{}/{}/", service, account)
+}
+
+/// Load the credential types stored for `service`/`account`
+///
+/// Stores that cannot list keys, such as the OS keyring, are asked for each
+/// of the known credential types instead, so custom types are missed there.
+pub async fn load(store: &dyn SecretStore, service: &str, account: &str) -> StoredCredentials {
+    let (types, error) = match list_types(store, service, account).await {
+        Ok(types) => (types, None),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+    StoredCredentials {
+        service: service.to_string(),
+        account: account.to_string(),
+        types,
+        error,
+    }
+}
+
+async fn list_types(
+    store: &dyn SecretStore,
+    service: &str,
+    account: &str,
+) -> Result<Vec<String>, StoreError> {
+    let prefix = key_prefix(service, account);
+    let mut types: Vec<String> = match store.list_keys(&prefix).await {
+        // Expiry entries belong to the credential they expire
+        Ok(keys) => keys
+            .iter()
+            .filter(|key| !is_ttl_key(key))
+            .filter_map(|key| key.strip_prefix(&prefix))
+            .map(str::to_string)
+            .collect(),
+        Err(_) => {
+            let mut found = Vec::new();
+            for credential_type in KNOWN_TYPES {
+                if store.exists(&format!("{}{}", prefix, credential_type)).await? {
+                    found.push(credential_type.to_string());
+                }
+            }
+            found
+        }
+    };
+    types.sort();
+    Ok(types)
+}
@@ -0,0 +1,157 @@
+//! Comparing an account's token before and after a refresh.
+
+use crate::app::AccountInfo;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// The parts of an account's token shown in the refresh diff
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenInfo {
+    pub expires_at: Option<DateTime<Utc>>,
+    pub scopes: Vec<String>,
+}
+
+impl TokenInfo {
+    /// Snapshot the token details of `account`
+    pub fn from_account(account: &AccountInfo) -> Self {
+        Self {
+            expires_at: account.expires_at,
+            scopes: account.scopes.clone(),
+        }
+    }
+}
+
+/// What changed between two snapshots of the same token
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenDiff {
+    pub old_expires_at: Option<DateTime<Utc>>,
+    pub new_expires_at: Option<DateTime<Utc>>,
+    /// Scopes granted now that were not before, in the new token's order
+    pub scopes_added: Vec<String>,
+    /// Scopes no longer granted, in the old token's order
+    pub scopes_removed: Vec<String>,
+}
+
+impl TokenDiff {
+    /// Compare `new` against the earlier snapshot `old`
+    pub fn between(old: &TokenInfo, new: &TokenInfo) -> Self {
+        Self {
+            old_expires_at: old.expires_at,
+            new_expires_at: new.expires_at,
+            scopes_added: missing_from(&new.scopes, &old.scopes),
+            scopes_removed: missing_from(&old.scopes, &new.scopes),
+        }
+    }
+
+    /// Whether the expiry changed
+    pub fn expiry_changed(&self) -> bool {
+        self.old_expires_at != self.new_expires_at
+    }
+
+    /// Whether the new token expires earlier than the old one did
+    pub fn expiry_regressed(&self) -> bool {
+        matches!(
+            (self.old_expires_at, self.new_expires_at),
+            (Some(old), Some(new)) if new < old
+        )
+    }
+
+    /// Whether neither the expiry nor the scopes changed
+    pub fn is_unchanged(&self) -> bool {
+        !self.expiry_changed() && self.scopes_added.is_empty() && self.scopes_removed.is_empty()
+    }
+}
+
+/// Entries of `scopes` that are not in `other`
+fn missing_from(scopes: &[String], other: &[String]) -> Vec<String> {
+    scopes
+        .iter()
+        .filter(|scope| !other.contains(*scope))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn info(expires_in_hours: Option<i64>, scopes: &[&str]) -> TokenInfo {
+        let base = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        TokenInfo {
+            expires_at: expires_in_hours.map(|hours| base + Duration::hours(hours)),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_no_change() {
+        let token = info(Some(1), &["repo", "user"]);
+        let diff = TokenDiff::between(&token, &token);
+
+        assert!(diff.is_unchanged());
+        assert!(!diff.expiry_changed());
+        assert!(!diff.expiry_regressed());
+    }
+
+    #[test]
+    fn test_scope_order_is_not_a_change() {
+        let diff = TokenDiff::between(
+            &info(None, &["repo", "user"]),
+            &info(None, &["user", "repo"]),
+        );
+        assert!(diff.is_unchanged());
+    }
+
+    #[test]
+    fn test_expiry_extension() {
+        let old = info(Some(1), &["repo"]);
+        let new = info(Some(2), &["repo"]);
+        let diff = TokenDiff::between(&old, &new);
+
+        assert!(!diff.is_unchanged());
+        assert!(diff.expiry_changed());
+        assert!(!diff.expiry_regressed());
+        assert_eq!(diff.old_expires_at, old.expires_at);
+        assert_eq!(diff.new_expires_at, new.expires_at);
+        assert!(diff.scopes_added.is_empty() && diff.scopes_removed.is_empty());
+    }
+
+    #[test]
+    fn test_expiry_regression() {
+        let diff = TokenDiff::between(&info(Some(2), &[]), &info(Some(1), &[]));
+        assert!(diff.expiry_changed());
+        assert!(diff.expiry_regressed());
+
+        // Gaining or losing an expiry is a change but not a regression
+        let gained = TokenDiff::between(&info(None, &[]), &info(Some(1), &[]));
+        assert!(gained.expiry_changed() && !gained.expiry_regressed());
+        let lost = TokenDiff::between(&info(Some(1), &[]), &info(None, &[]));
+        assert!(lost.expiry_changed() && !lost.expiry_regressed());
+    }
+
+    #[test]
+    fn test_scope_addition() {
+        let diff = TokenDiff::between(
+            &info(Some(1), &["repo"]),
+            &info(Some(1), &["repo", "gist", "user"]),
+        );
+
+        assert!(!diff.is_unchanged());
+        assert!(!diff.expiry_changed());
+        assert_eq!(diff.scopes_added, ["gist", "user"]);
+        assert!(diff.scopes_removed.is_empty());
+    }
+
+    #[test]
+    fn test_scope_removal() {
+        let diff = TokenDiff::between(
+            &info(Some(1), &["repo", "gist", "user"]),
+            &info(Some(1), &["user"]),
+        );
+
+        assert!(!diff.is_unchanged());
+        assert!(diff.scopes_added.is_empty());
+        assert_eq!(diff.scopes_removed, ["repo", "gist"]);
+    }
+}
@@ -0,0 +1,250 @@
+//! Export account metadata to JSON or CSV.
+//!
+//! Exports describe which accounts exist and their token state. Token values
+//! are never part of [`AccountEntry`], so they cannot end up in an export.
+
+use crate::app::{AccountInfo, TokenStatus};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// File name suggested when the export prompt opens
+pub const DEFAULT_EXPORT_FILE: &str = "sigilforge-accounts.json";
+
+/// Output format for an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    /// Pick a format from the file extension (`.csv` for CSV, JSON otherwise)
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => ExportFormat::Csv,
+            _ => ExportFormat::Json,
+        }
+    }
+}
+
+/// One exported account
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountEntry {
+    pub service: String,
+    pub account: String,
+    pub scopes: Vec<String>,
+    pub created_at: String,
+    pub last_used: Option<String>,
+    /// Token status label, omitted when exporting with `--no-status`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_valid: Option<String>,
+}
+
+impl AccountEntry {
+    /// Build an entry from displayed account info
+    pub fn from_account(account: &AccountInfo, include_status: bool) -> Self {
+        Self {
+            service: account.service.clone(),
+            account: account.account.clone(),
+            scopes: account.scopes.clone(),
+            created_at: account.created_at.clone(),
+            last_used: account.last_used.clone(),
+            token_valid: include_status.then(|| status_label(&account.status).to_string()),
+        }
+    }
+}
+
+/// Machine-readable label for a token status
+fn status_label(status: &TokenStatus) -> &'static str {
+    match status {
+        TokenStatus::Valid => "valid",
+        TokenStatus::ExpiringSoon => "expiring_soon",
+        TokenStatus::Expired => "expired",
+        TokenStatus::Unknown => "unknown",
+    }
+}
+
+/// Write `accounts` to `path` in the given format
+pub fn export_accounts(accounts: &[AccountEntry], format: ExportFormat, path: &Path) -> Result<()> {
+    let contents = match format {
+        ExportFormat::Json => to_json(accounts)?,
+        ExportFormat::Csv => to_csv(accounts),
+    };
+
+    std::fs::write(path, contents)
+        .with_context(|| format!("Failed to write export to {}", path.display()))
+}
+
+/// Render accounts as a pretty-printed JSON array
+pub fn to_json(accounts: &[AccountEntry]) -> Result<String> {
+    let mut json = serde_json::to_string_pretty(accounts).context("Failed to serialize accounts")?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Render accounts as CSV with a header row
+///
+/// Scopes are joined with spaces. The `token_valid` column is only present
+/// when at least one entry carries a status.
+pub fn to_csv(accounts: &[AccountEntry]) -> String {
+    let include_status = accounts.iter().any(|a| a.token_valid.is_some());
+
+    let mut header = vec!["service", "account", "scopes", "created_at", "last_used"];
+    if include_status {
+        header.push("token_valid");
+    }
+
+    let mut csv = header.join(",");
+    csv.push('\n');
+
+    for entry in accounts {
+        let mut fields = vec![
+            csv_field(&entry.service),
+            csv_field(&entry.account),
+            csv_field(&entry.scopes.join(" ")),
+            csv_field(&entry.created_at),
+            csv_field(entry.last_used.as_deref().unwrap_or("")),
+        ];
+        if include_status {
+            fields.push(csv_field(entry.token_valid.as_deref().unwrap_or("")));
+        }
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Quote a CSV field if it contains a delimiter, quote, or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sigilforge_core::CredentialSource;
+    use tempfile::TempDir;
+
+    fn fixture_accounts() -> Vec<AccountInfo> {
+        vec![
+            AccountInfo {
+                service: "github".to_string(),
+                account: "work".to_string(),
+                scopes: vec!["repo".to_string(), "read:user".to_string()],
+                status: TokenStatus::Valid,
+                expires_at: None,
+                created_at: "2025-01-15T10:00:00Z".to_string(),
+                last_used: Some("2025-02-01T08:30:00Z".to_string()),
+                source: CredentialSource::Unknown,
+            },
+            AccountInfo {
+                service: "spotify".to_string(),
+                account: "family, shared".to_string(),
+                scopes: vec![],
+                status: TokenStatus::Expired,
+                expires_at: None,
+                created_at: "2025-01-20T12:00:00Z".to_string(),
+                last_used: None,
+                source: CredentialSource::Unknown,
+            },
+        ]
+    }
+
+    fn entries(include_status: bool) -> Vec<AccountEntry> {
+        fixture_accounts()
+            .iter()
+            .map(|a| AccountEntry::from_account(a, include_status))
+            .collect()
+    }
+
+    #[test]
+    fn test_json_export() {
+        let json = to_json(&entries(true)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!([
+                {
+                    "service": "github",
+                    "account": "work",
+                    "scopes": ["repo", "read:user"],
+                    "created_at": "2025-01-15T10:00:00Z",
+                    "last_used": "2025-02-01T08:30:00Z",
+                    "token_valid": "valid"
+                },
+                {
+                    "service": "spotify",
+                    "account": "family, shared",
+                    "scopes": [],
+                    "created_at": "2025-01-20T12:00:00Z",
+                    "last_used": null,
+                    "token_valid": "expired"
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_json_export_without_status() {
+        let json = to_json(&entries(false)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert!(value[0].get("token_valid").is_none());
+        assert!(value[1].get("token_valid").is_none());
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let csv = to_csv(&entries(true));
+
+        assert_eq!(
+            csv,
+            "service,account,scopes,created_at,last_used,token_valid\n\
+             github,work,repo read:user,2025-01-15T10:00:00Z,2025-02-01T08:30:00Z,valid\n\
+             spotify,\"family, shared\",,2025-01-20T12:00:00Z,,expired\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_export_without_status() {
+        let csv = to_csv(&entries(false));
+        let header = csv.lines().next().unwrap();
+
+        assert_eq!(header, "service,account,scopes,created_at,last_used");
+        assert_eq!(csv.lines().count(), 3);
+    }
+
+    #[test]
+    fn test_csv_field_escapes_quotes() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(ExportFormat::from_path(Path::new("out.csv")), ExportFormat::Csv);
+        assert_eq!(ExportFormat::from_path(Path::new("OUT.CSV")), ExportFormat::Csv);
+        assert_eq!(ExportFormat::from_path(Path::new("out.json")), ExportFormat::Json);
+        assert_eq!(ExportFormat::from_path(Path::new("accounts")), ExportFormat::Json);
+    }
+
+    #[test]
+    fn test_export_writes_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts.csv");
+
+        export_accounts(&entries(true), ExportFormat::Csv, &path).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("service,account,"));
+        assert_eq!(written, to_csv(&entries(true)));
+    }
+}
@@ -0,0 +1,186 @@
+//! Single-line text input for prompts.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Editable single-line text with a cursor
+///
+/// The cursor is a character index, so multi-byte input is handled safely.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextInput {
+    value: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    /// Create an input pre-filled with `initial`, cursor at the end
+    pub fn new(initial: &str) -> Self {
+        Self {
+            value: initial.to_string(),
+            cursor: initial.chars().count(),
+        }
+    }
+
+    /// Current text
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Cursor position in characters
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Insert a character at the cursor
+    pub fn insert(&mut self, c: char) {
+        let index = self.byte_index();
+        self.value.insert(index, c);
+        self.cursor += 1;
+    }
+
+    /// Insert `text` at the cursor, leaving the cursor after it
+    pub fn insert_str(&mut self, text: &str) {
+        let index = self.byte_index();
+        self.value.insert_str(index, text);
+        self.cursor += text.chars().count();
+    }
+
+    /// Delete the character before the cursor
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let index = self.byte_index();
+            self.value.remove(index);
+        }
+    }
+
+    /// Delete the character under the cursor
+    pub fn delete(&mut self) {
+        if self.cursor < self.value.chars().count() {
+            let index = self.byte_index();
+            self.value.remove(index);
+        }
+    }
+
+    /// Move the cursor one character left
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Move the cursor one character right
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.value.chars().count());
+    }
+
+    /// Move the cursor to the start
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Move the cursor to the end
+    pub fn move_end(&mut self) {
+        self.cursor = self.value.chars().count();
+    }
+
+    /// Apply an editing key, returning whether it was consumed
+    ///
+    /// Handles printable characters, Backspace, Delete, and cursor movement;
+    /// Enter, Esc, and Tab are left to the caller.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.insert(c),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left => self.move_left(),
+            KeyCode::Right => self.move_right(),
+            KeyCode::Home => self.move_home(),
+            KeyCode::End => self.move_end(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Byte offset of the cursor within `value`
+    fn byte_index(&self) -> usize {
+        self.value
+            .char_indices()
+            .nth(self.cursor)
+            .map(|(index, _)| index)
+            .unwrap_or(self.value.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editing() {
+        let mut input = TextInput::new("acounts.json");
+        assert_eq!(input.cursor(), 12);
+
+        input.move_home();
+        input.move_right();
+        input.insert('c');
+        assert_eq!(input.value(), "accounts.json");
+
+        input.move_end();
+        input.backspace();
+        input.backspace();
+        input.backspace();
+        input.backspace();
+        input.insert('c');
+        input.insert('s');
+        input.insert('v');
+        assert_eq!(input.value(), "accounts.csv");
+
+        input.move_home();
+        input.delete();
+        assert_eq!(input.value(), "ccounts.csv");
+    }
+
+    #[test]
+    fn test_insert_str_at_cursor() {
+        let mut input = TextInput::new("github");
+        input.move_home();
+        input.insert_str("my-");
+        assert_eq!(input.value(), "my-github");
+        assert_eq!(input.cursor(), 3);
+
+        input.move_end();
+        input.insert_str("-é");
+        assert_eq!(input.value(), "my-github-é");
+        assert_eq!(input.cursor(), 11);
+    }
+
+    #[test]
+    fn test_handle_key() {
+        let mut input = TextInput::new("ab");
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        assert!(input.handle_key(key(KeyCode::Left)));
+        assert!(input.handle_key(key(KeyCode::Char('x'))));
+        assert_eq!(input.value(), "axb");
+
+        let ctrl_u = KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert!(!input.handle_key(ctrl_u));
+        assert!(!input.handle_key(key(KeyCode::Enter)));
+        assert_eq!(input.value(), "axb");
+    }
+
+    #[test]
+    fn test_cursor_bounds_and_multibyte() {
+        let mut input = TextInput::new("é");
+        input.move_right();
+        assert_eq!(input.cursor(), 1);
+
+        input.insert('ß');
+        input.move_left();
+        input.move_left();
+        input.move_left();
+        assert_eq!(input.cursor(), 0);
+
+        input.backspace();
+        input.delete();
+        assert_eq!(input.value(), "ß");
+    }
+}
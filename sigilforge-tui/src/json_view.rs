@@ -0,0 +1,155 @@
+//! Syntax highlighting for the detail panel's raw JSON view.
+//!
+//! Lines of pretty-printed JSON are split into [`ColorSpan`]s by a small
+//! scanner rather than a parser, so any line highlights on its own.
+
+/// What a run of JSON text is, which decides its color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonKind {
+    /// An object key, including its quotes
+    Key,
+    /// A string value, including its quotes
+    String,
+    /// A number value
+    Number,
+    /// Punctuation, whitespace, `true`, `false` and `null`
+    Plain,
+}
+
+/// A run of one line of JSON text of a single kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorSpan {
+    pub text: String,
+    pub kind: JsonKind,
+}
+
+impl ColorSpan {
+    fn new(text: &str, kind: JsonKind) -> Self {
+        Self {
+            text: text.to_string(),
+            kind,
+        }
+    }
+}
+
+/// Split one line of JSON into colored runs
+///
+/// A string is a key when the next non-space character after it is `:`.
+pub fn highlight_line(line: &str) -> Vec<ColorSpan> {
+    let bytes = line.as_bytes();
+    let mut spans = Vec::new();
+    let mut plain_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let starts_number = bytes[i].is_ascii_digit()
+            || (bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit));
+
+        let (end, kind) = if bytes[i] == b'"' {
+            let end = string_end(bytes, i);
+            let is_key = line[end..].trim_start().starts_with(':');
+            let kind = if is_key {
+                JsonKind::Key
+            } else {
+                JsonKind::String
+            };
+            (end, kind)
+        } else if starts_number {
+            let length = line[i + 1..]
+                .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')))
+                .unwrap_or(line.len() - i - 1);
+            (i + 1 + length, JsonKind::Number)
+        } else {
+            i += 1;
+            continue;
+        };
+
+        if plain_start < i {
+            spans.push(ColorSpan::new(&line[plain_start..i], JsonKind::Plain));
+        }
+        spans.push(ColorSpan::new(&line[i..end], kind));
+        i = end;
+        plain_start = end;
+    }
+
+    if plain_start < line.len() {
+        spans.push(ColorSpan::new(&line[plain_start..], JsonKind::Plain));
+    }
+    spans
+}
+
+/// Index just past the closing quote of the string opening at `start`
+///
+/// An unterminated string runs to the end of the line.
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(parts: &[(&str, JsonKind)]) -> Vec<ColorSpan> {
+        parts
+            .iter()
+            .map(|(text, kind)| ColorSpan::new(text, *kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_key_and_string_value() {
+        assert_eq!(
+            highlight_line(r#"  "service": "github","#),
+            spans(&[
+                ("  ", JsonKind::Plain),
+                (r#""service""#, JsonKind::Key),
+                (": ", JsonKind::Plain),
+                (r#""github""#, JsonKind::String),
+                (",", JsonKind::Plain),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_numbers_and_literals() {
+        assert_eq!(
+            highlight_line(r#""expires_in": -12.5e3, "ok": true"#),
+            spans(&[
+                (r#""expires_in""#, JsonKind::Key),
+                (": ", JsonKind::Plain),
+                ("-12.5e3", JsonKind::Number),
+                (", ", JsonKind::Plain),
+                (r#""ok""#, JsonKind::Key),
+                (": true", JsonKind::Plain),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_escaped_quotes_stay_in_string() {
+        assert_eq!(
+            highlight_line(r#""a\"b: 1""#),
+            spans(&[(r#""a\"b: 1""#, JsonKind::String)])
+        );
+        // Digits inside a string are not numbers
+        assert_eq!(
+            highlight_line(r#""v2""#),
+            spans(&[(r#""v2""#, JsonKind::String)])
+        );
+    }
+
+    #[test]
+    fn test_spans_cover_the_whole_line() {
+        let line = r#"    "scopes": ["repo", "read:org"],"#;
+        let joined: String = highlight_line(line).into_iter().map(|s| s.text).collect();
+        assert_eq!(joined, line);
+    }
+}
@@ -1,23 +1,55 @@
 //! Sigilforge TUI - Interactive terminal interface for OAuth token management.
 
 use anyhow::Result;
+use clap::Parser;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    event::{
+        self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use fusabi_tui_render::prelude::*;
 use std::io::{self, stdout};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::{error, info};
 
 mod app;
+mod credentials;
+mod diff;
+mod export;
+mod input;
+mod json_view;
+mod theme;
 mod ui;
+mod wizard;
 
 use app::App;
+use theme::Theme;
+use wizard::{FormAction, WizardStep};
+
+#[derive(Parser)]
+#[command(name = "sigilforge-tui")]
+#[command(about = "Interactive TUI for Sigilforge OAuth token management", version)]
+struct Cli {
+    /// Color theme (default, catppuccin-mocha, gruvbox); overrides tui-theme.toml
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
+
+    /// Omit token status (`token_valid`) from exported accounts
+    #[arg(long)]
+    no_status: bool,
+
+    /// Hide the step-by-step progress shown while adding an account
+    #[arg(long)]
+    no_progress: bool,
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -29,13 +61,16 @@ async fn main() -> Result<()> {
 
     info!("Starting Sigilforge TUI");
 
+    // Resolve the theme before touching the terminal so errors print normally
+    let theme = Theme::resolve(cli.theme.as_deref())?;
+
     // Create application
-    let mut app = App::new().await?;
+    let mut app = App::new(theme, !cli.no_status, !cli.no_progress).await?;
 
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
 
     // Create renderer
     let mut renderer = CrosstermRenderer::new(stdout)?;
@@ -45,7 +80,7 @@ async fn main() -> Result<()> {
 
     // Restore terminal
     disable_raw_mode()?;
-    execute!(io::stdout(), LeaveAlternateScreen)?;
+    execute!(io::stdout(), DisableBracketedPaste, LeaveAlternateScreen)?;
 
     // Handle any errors from the main loop
     if let Err(e) = &result {
@@ -66,42 +101,60 @@ async fn run_app(app: &mut App, renderer: &mut CrosstermRenderer<io::Stdout>) ->
 
         // Handle input with timeout
         if event::poll(Duration::from_millis(250))? {
-            if let Event::Key(key) = event::read()? {
+            let event = event::read()?;
+            if let Event::Paste(text) = &event {
+                app.handle_paste(text);
+            } else if let Event::Key(key) = event {
                 // Only process key press events (ignore release)
                 if key.kind == KeyEventKind::Press {
-                    // Handle input
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Char('Q') => {
-                            // Quit
-                            break;
-                        }
-                        KeyCode::Char('c') | KeyCode::Char('C')
-                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
-                        {
-                            // Ctrl+C to quit
-                            break;
-                        }
-                        KeyCode::Char('r') | KeyCode::Char('R') => {
-                            // Refresh selected account
-                            app.refresh_selected().await?;
-                        }
-                        KeyCode::Char('a') | KeyCode::Char('A') => {
-                            // Refresh all accounts
-                            app.refresh_all().await?;
-                        }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            app.select_next();
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            app.select_previous();
+                    if app.wizard.is_some() {
+                        handle_wizard_key(app, key);
+                    } else if app.export_prompt.is_some() || app.search_prompt.is_some() {
+                        handle_prompt_key(app, key);
+                    } else if app.reveal_pending.is_some() {
+                        // Any key but `y` cancels, so a stray key never reveals
+                        if key.code == KeyCode::Char('y') {
+                            app.confirm_reveal(Instant::now()).await;
+                        } else {
+                            app.cancel_reveal();
                         }
-                        KeyCode::Home | KeyCode::Char('g') => {
-                            app.select_first();
+                    } else if is_quit_key(key) {
+                        break;
+                    } else if app.show_help {
+                        app.handle_help_key(key);
+                    } else if !app.handle_navigation_key(key, Instant::now()) {
+                        match key.code {
+                            KeyCode::Char('r') | KeyCode::Char('R') => {
+                                // Refresh selected account
+                                app.refresh_selected();
+                            }
+                            KeyCode::Char('a') | KeyCode::Char('A') => {
+                                // Refresh all accounts
+                                app.refresh_all();
+                            }
+                            KeyCode::Tab => {
+                                app.toggle_group_by_service();
+                            }
+                            KeyCode::Char('f') => {
+                                app.cycle_status_filter();
+                            }
+                            KeyCode::Char('e') | KeyCode::Char('E') => {
+                                app.start_export();
+                            }
+                            KeyCode::Char('n') => {
+                                app.start_wizard();
+                            }
+                            KeyCode::Char('c') => {
+                                app.select_next_credential();
+                            }
+                            KeyCode::Char('v') => {
+                                app.request_reveal();
+                            }
+                            KeyCode::Char('?') => {
+                                app.toggle_help();
+                            }
+                            _ => {}
                         }
-                        KeyCode::End | KeyCode::Char('G') => {
-                            app.select_last();
-                        }
-                        _ => {}
                     }
                 }
             }
@@ -113,3 +166,65 @@ async fn run_app(app: &mut App, renderer: &mut CrosstermRenderer<io::Stdout>) ->
 
     Ok(())
 }
+
+/// `q` or Ctrl+C
+fn is_quit_key(key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Char('Q') => true,
+        KeyCode::Char('c') | KeyCode::Char('C') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
+
+/// Route a key press to the account creation overlay
+fn handle_wizard_key(app: &mut App, key: KeyEvent) {
+    let Some(wizard) = app.wizard.as_mut() else {
+        return;
+    };
+
+    match wizard.step {
+        WizardStep::Form => {
+            let action = wizard.handle_form_key(key);
+            match action {
+                FormAction::Submit => app.submit_wizard(),
+                FormAction::Cancel => app.cancel_wizard(),
+                FormAction::None => {}
+            }
+        }
+        WizardStep::Failed { .. } => {
+            if matches!(key.code, KeyCode::Enter | KeyCode::Esc) {
+                wizard.dismiss_error();
+            }
+        }
+        WizardStep::Starting | WizardStep::Authorizing { .. } => {
+            if key.code == KeyCode::Esc {
+                app.cancel_wizard();
+            }
+        }
+    }
+}
+
+/// Route a key press to the open search or export prompt
+fn handle_prompt_key(app: &mut App, key: KeyEvent) {
+    let searching = app.search_prompt.is_some();
+    if key.code == KeyCode::Enter {
+        if searching {
+            app.confirm_search();
+        } else {
+            app.confirm_export();
+        }
+        return;
+    }
+    if key.code == KeyCode::Esc {
+        if searching {
+            app.cancel_search();
+        } else {
+            app.cancel_export();
+        }
+        return;
+    }
+
+    if let Some(prompt) = app.search_prompt.as_mut().or(app.export_prompt.as_mut()) {
+        prompt.handle_key(key);
+    }
+}
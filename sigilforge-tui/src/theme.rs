@@ -0,0 +1,483 @@
+//! Color themes for the TUI.
+//!
+//! A theme is picked from a built-in preset (`--theme=<name>`) or loaded from
+//! `$XDG_CONFIG_HOME/sigilforge/tui-theme.toml`:
+//!
+//! ```toml
+//! # Start from a preset (optional), then override individual colors
+//! preset = "gruvbox"
+//! primary = "#83a598"
+//! dim = "dark_gray"
+//! ```
+//!
+//! Colors are either names (`cyan`, `light_red`, `dark_gray`, `reset`, ...)
+//! or `#rrggbb` hex values. On terminals limited to 8 colors, every color is
+//! mapped to the nearest of the basic eight.
+
+use fusabi_tui_core::style::Color;
+use serde::{Deserialize, Deserializer};
+use std::path::{Path, PathBuf};
+
+/// Names accepted by `--theme` and the `preset` key
+pub const PRESET_NAMES: &[&str] = &["default", "catppuccin-mocha", "gruvbox"];
+
+/// Errors raised while selecting or loading a theme
+#[derive(Debug, thiserror::Error)]
+pub enum ThemeError {
+    #[error("unknown theme '{name}' (available: {})", PRESET_NAMES.join(", "))]
+    UnknownPreset { name: String },
+
+    #[error("failed to read theme file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("invalid theme file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+/// Colors used throughout the UI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Titles, headings, and highlights
+    pub primary: Color,
+    /// Valid tokens and the connected indicator
+    pub success: Color,
+    /// Tokens expiring soon
+    pub warning: Color,
+    /// Expired tokens and errors
+    pub error: Color,
+    /// Regular text
+    pub text: Color,
+    /// Labels and secondary text
+    pub dim: Color,
+    /// Screen background
+    pub background: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            primary: Color::Cyan,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            text: Color::White,
+            dim: Color::DarkGray,
+            background: Color::Reset,
+        }
+    }
+}
+
+impl Theme {
+    /// Catppuccin Mocha palette
+    pub fn catppuccin_mocha() -> Self {
+        Self {
+            primary: Color::Rgb(0x89, 0xb4, 0xfa),    // blue
+            success: Color::Rgb(0xa6, 0xe3, 0xa1),    // green
+            warning: Color::Rgb(0xf9, 0xe2, 0xaf),    // yellow
+            error: Color::Rgb(0xf3, 0x8b, 0xa8),      // red
+            text: Color::Rgb(0xcd, 0xd6, 0xf4),       // text
+            dim: Color::Rgb(0x6c, 0x70, 0x86),        // overlay0
+            background: Color::Rgb(0x1e, 0x1e, 0x2e), // base
+        }
+    }
+
+    /// Gruvbox (dark) palette
+    pub fn gruvbox() -> Self {
+        Self {
+            primary: Color::Rgb(0x83, 0xa5, 0x98),    // bright aqua-blue
+            success: Color::Rgb(0xb8, 0xbb, 0x26),    // bright green
+            warning: Color::Rgb(0xfa, 0xbd, 0x2f),    // bright yellow
+            error: Color::Rgb(0xfb, 0x49, 0x34),      // bright red
+            text: Color::Rgb(0xeb, 0xdb, 0xb2),       // fg
+            dim: Color::Rgb(0x92, 0x83, 0x74),        // gray
+            background: Color::Rgb(0x28, 0x28, 0x28), // bg
+        }
+    }
+
+    /// Look up a built-in preset by name
+    pub fn preset(name: &str) -> Result<Self, ThemeError> {
+        match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "default" => Ok(Self::default()),
+            "catppuccin-mocha" | "catppuccin" => Ok(Self::catppuccin_mocha()),
+            "gruvbox" => Ok(Self::gruvbox()),
+            _ => Err(ThemeError::UnknownPreset {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    /// Parse a theme from TOML
+    pub fn from_toml(contents: &str) -> Result<Self, toml::de::Error> {
+        let file: ThemeFile = toml::from_str(contents)?;
+        file.into_theme()
+    }
+
+    /// Load a theme file
+    pub fn load(path: &Path) -> Result<Self, ThemeError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| ThemeError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_toml(&contents).map_err(|source| ThemeError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Pick the theme to use at startup
+    ///
+    /// `--theme` wins; otherwise the user's theme file is used if present.
+    /// The result is reduced to 8 colors when `TERM` says that is all the
+    /// terminal supports.
+    pub fn resolve(cli_preset: Option<&str>) -> Result<Self, ThemeError> {
+        let theme = match (cli_preset, default_theme_path()) {
+            (Some(name), _) => Self::preset(name)?,
+            (None, Some(path)) if path.exists() => Self::load(&path)?,
+            _ => Self::default(),
+        };
+
+        let term = std::env::var("TERM").ok();
+        let colorterm = std::env::var("COLORTERM").ok();
+        if is_8_color_terminal(term.as_deref(), colorterm.as_deref()) {
+            Ok(theme.to_8_colors())
+        } else {
+            Ok(theme)
+        }
+    }
+
+    /// Map every color to the nearest of the eight basic ANSI colors
+    pub fn to_8_colors(self) -> Self {
+        Self {
+            primary: nearest_basic_color(self.primary),
+            success: nearest_basic_color(self.success),
+            warning: nearest_basic_color(self.warning),
+            error: nearest_basic_color(self.error),
+            text: nearest_basic_color(self.text),
+            dim: nearest_basic_color(self.dim),
+            background: nearest_basic_color(self.background),
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/sigilforge/tui-theme.toml` (or `~/.config/...`)
+pub fn default_theme_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("sigilforge").join("tui-theme.toml"))
+}
+
+/// Whether the terminal only supports the 8 basic colors
+///
+/// `COLORTERM` (truecolor/24bit) or a `256color` TERM mean full color;
+/// `dumb`, `linux`, `vt100`, `ansi`, and bare `xterm`/`screen` mean 8.
+pub fn is_8_color_terminal(term: Option<&str>, colorterm: Option<&str>) -> bool {
+    if colorterm.is_some_and(|c| !c.is_empty()) {
+        return false;
+    }
+
+    match term {
+        Some(term) if term.contains("256color") || term.contains("direct") => false,
+        Some(term) => matches!(
+            term,
+            "dumb" | "linux" | "vt100" | "vt220" | "ansi" | "xterm" | "screen" | "cons25"
+        ),
+        None => false,
+    }
+}
+
+/// Theme file layout: an optional base preset plus per-color overrides
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    preset: Option<String>,
+    primary: Option<ThemeColor>,
+    success: Option<ThemeColor>,
+    warning: Option<ThemeColor>,
+    error: Option<ThemeColor>,
+    text: Option<ThemeColor>,
+    dim: Option<ThemeColor>,
+    background: Option<ThemeColor>,
+}
+
+impl ThemeFile {
+    fn into_theme(self) -> Result<Theme, toml::de::Error> {
+        let mut theme = match &self.preset {
+            Some(name) => Theme::preset(name)
+                .map_err(|e| <toml::de::Error as serde::de::Error>::custom(e.to_string()))?,
+            None => Theme::default(),
+        };
+
+        let overrides = [
+            (&mut theme.primary, self.primary),
+            (&mut theme.success, self.success),
+            (&mut theme.warning, self.warning),
+            (&mut theme.error, self.error),
+            (&mut theme.text, self.text),
+            (&mut theme.dim, self.dim),
+            (&mut theme.background, self.background),
+        ];
+        for (slot, value) in overrides {
+            if let Some(ThemeColor(color)) = value {
+                *slot = color;
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+/// A color written as a name or `#rrggbb`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ThemeColor(Color);
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse_color(&value).map(ThemeColor).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "unknown color '{}': expected a color name (e.g. cyan, light_red, dark_gray) \
+                 or a #rrggbb hex value",
+                value
+            ))
+        })
+    }
+}
+
+/// Parse a color name or `#rrggbb` value
+fn parse_color(value: &str) -> Option<Color> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    let color = match value.to_ascii_lowercase().replace(['-', ' '], "_").as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "dark_gray" | "dark_grey" | "darkgray" => Color::DarkGray,
+        "light_red" => Color::LightRed,
+        "light_green" => Color::LightGreen,
+        "light_yellow" => Color::LightYellow,
+        "light_blue" => Color::LightBlue,
+        "light_magenta" => Color::LightMagenta,
+        "light_cyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// The eight basic ANSI colors
+const BASIC_COLORS: [Color; 8] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+/// Chroma below which a color is treated as a shade of gray
+const GRAYSCALE_CHROMA: u8 = 48;
+
+/// Brightness from which a gray maps to white rather than black
+const GRAYSCALE_WHITE_FROM: u8 = 96;
+
+/// Approximate RGB value of a non-basic color, `None` for the rest
+fn approximate_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let rgb = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Gray => (190, 190, 190),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 85, 85),
+        Color::LightGreen => (85, 255, 85),
+        Color::LightYellow => (255, 255, 85),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 85, 255),
+        Color::LightCyan => (85, 255, 255),
+        _ => return None,
+    };
+    Some(rgb)
+}
+
+/// Map a color to the basic color with the closest hue
+///
+/// Hue is used rather than RGB distance so pastel palettes keep their
+/// meaning (a pale green is still green, not white). Near-grays become
+/// black or white depending on brightness.
+fn nearest_basic_color(color: Color) -> Color {
+    let Some((r, g, b)) = approximate_rgb(color) else {
+        return color;
+    };
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    if chroma < GRAYSCALE_CHROMA {
+        return if max >= GRAYSCALE_WHITE_FROM {
+            Color::White
+        } else {
+            Color::Black
+        };
+    }
+
+    let channel = |v: u8| f32::from(v) / f32::from(chroma);
+    let sector = if max == r {
+        (channel(g) - channel(b)).rem_euclid(6.0)
+    } else if max == g {
+        channel(b) - channel(r) + 2.0
+    } else {
+        channel(r) - channel(g) + 4.0
+    };
+
+    match (sector * 60.0) as u16 {
+        0..=29 | 330.. => Color::Red,
+        30..=89 => Color::Yellow,
+        90..=149 => Color::Green,
+        150..=209 => Color::Cyan,
+        210..=269 => Color::Blue,
+        _ => Color::Magenta,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_by_name() {
+        assert_eq!(Theme::preset("default").unwrap(), Theme::default());
+        assert_eq!(
+            Theme::preset("catppuccin-mocha").unwrap(),
+            Theme::catppuccin_mocha()
+        );
+        assert_eq!(
+            Theme::preset("Catppuccin_Mocha").unwrap(),
+            Theme::catppuccin_mocha()
+        );
+        assert_eq!(Theme::preset("gruvbox").unwrap(), Theme::gruvbox());
+
+        let err = Theme::preset("solarized").unwrap_err();
+        assert!(err.to_string().contains("unknown theme 'solarized'"));
+        assert!(err.to_string().contains("gruvbox"));
+    }
+
+    #[test]
+    fn test_parse_theme_file() {
+        let theme = Theme::from_toml(
+            r##"
+            primary = "magenta"
+            dim = "dark-gray"
+            background = "#101010"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(theme.primary, Color::Magenta);
+        assert_eq!(theme.dim, Color::DarkGray);
+        assert_eq!(theme.background, Color::Rgb(0x10, 0x10, 0x10));
+        // Unset colors keep the default
+        assert_eq!(theme.success, Theme::default().success);
+    }
+
+    #[test]
+    fn test_theme_file_with_preset() {
+        let theme = Theme::from_toml("preset = \"gruvbox\"\nerror = \"light_red\"").unwrap();
+
+        assert_eq!(theme.error, Color::LightRed);
+        assert_eq!(theme.primary, Theme::gruvbox().primary);
+    }
+
+    #[test]
+    fn test_unknown_color_name_is_a_clear_error() {
+        let err = Theme::from_toml("primary = \"blurple\"")
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("unknown color 'blurple'"), "{}", err);
+        assert!(err.contains("#rrggbb"), "{}", err);
+        assert!(err.contains("primary"), "{}", err);
+    }
+
+    #[test]
+    fn test_invalid_theme_file_errors() {
+        assert!(Theme::from_toml("primary = \"#12345\"").is_err());
+        assert!(Theme::from_toml("primray = \"cyan\"").is_err());
+
+        let err = Theme::from_toml("preset = \"neon\"")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown theme 'neon'"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_reports_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("tui-theme.toml");
+        std::fs::write(&path, "text = \"chartreuse\"").unwrap();
+
+        let err = Theme::load(&path).unwrap_err().to_string();
+        assert!(err.contains(&path.display().to_string()));
+        assert!(err.contains("unknown color 'chartreuse'"));
+    }
+
+    #[test]
+    fn test_8_color_detection() {
+        assert!(is_8_color_terminal(Some("linux"), None));
+        assert!(is_8_color_terminal(Some("xterm"), None));
+        assert!(!is_8_color_terminal(Some("xterm-256color"), None));
+        assert!(!is_8_color_terminal(Some("xterm"), Some("truecolor")));
+        assert!(!is_8_color_terminal(None, None));
+    }
+
+    #[test]
+    fn test_8_color_fallback() {
+        let theme = Theme::catppuccin_mocha().to_8_colors();
+        for color in [
+            theme.primary,
+            theme.success,
+            theme.warning,
+            theme.error,
+            theme.text,
+            theme.dim,
+            theme.background,
+        ] {
+            assert!(
+                BASIC_COLORS.contains(&color),
+                "{:?} is not a basic color",
+                color
+            );
+        }
+        assert_eq!(theme.primary, Color::Blue);
+        assert_eq!(theme.success, Color::Green);
+        assert_eq!(theme.warning, Color::Yellow);
+        assert_eq!(theme.error, Color::Red);
+        assert_eq!(theme.text, Color::White);
+        assert_eq!(theme.background, Color::Black);
+
+        // Reset and basic colors are left alone
+        let default = Theme::default().to_8_colors();
+        assert_eq!(default.background, Color::Reset);
+        assert_eq!(default.primary, Color::Cyan);
+        assert_eq!(default.dim, Color::White);
+    }
+}
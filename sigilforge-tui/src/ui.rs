@@ -1,7 +1,17 @@
 //! UI rendering for Sigilforge TUI.
 
-use crate::app::{App, TokenStatus};
+use crate::app::{
+    AccountInfo, AccountRow, App, DetailSection, Notification, OAuthProgressState, OAuthStep,
+    PanelId, StepStatus, TokenDiffOverlay, TokenStatus, KEY_BINDINGS,
+};
+use crate::credentials::REDACTED;
+use crate::diff::TokenDiff;
+use crate::input::TextInput;
+use crate::json_view::{self, JsonKind};
+use crate::theme::Theme;
+use crate::wizard::{CreationWizard, WizardField, WizardStep};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use fusabi_tui_core::{
     buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
@@ -15,23 +25,26 @@ use fusabi_tui_widgets::{
     text::{Line, Span, Text},
     widget::{StatefulWidget, Widget},
 };
-
-// Sigilforge color theme
-const COLOR_PRIMARY: Color = Color::Cyan;
-const COLOR_SUCCESS: Color = Color::Green;
-const COLOR_WARNING: Color = Color::Yellow;
-const COLOR_ERROR: Color = Color::Red;
-const COLOR_TEXT: Color = Color::White;
-const COLOR_DIM: Color = Color::DarkGray;
+use std::collections::HashSet;
 
 /// Render the entire UI
-pub fn render(app: &App) -> Result<Buffer> {
+///
+/// Takes the app mutably so the detail panel's scroll bounds can be updated
+/// for the current terminal size.
+pub fn render(app: &mut App) -> Result<Buffer> {
     // Get terminal size (default to 80x24 if we can't detect)
     let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
     let area = Rect::new(0, 0, width, height);
 
     let mut buffer = Buffer::new(area);
 
+    // Paint the theme background first; `Reset` keeps the terminal's own
+    if app.theme.background != Color::Reset {
+        Paragraph::new(Text::from(""))
+            .style(Style::default().bg(app.theme.background))
+            .render(area, &mut buffer);
+    }
+
     // Create main layout: title | content | status bar
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -43,7 +56,7 @@ pub fn render(app: &App) -> Result<Buffer> {
         .split(area);
 
     // Render title
-    render_title(chunks[0], &mut buffer);
+    render_title(&app.theme, chunks[0], &mut buffer);
 
     // Render content (accounts list and details)
     render_content(app, chunks[1], &mut buffer);
@@ -51,64 +64,154 @@ pub fn render(app: &App) -> Result<Buffer> {
     // Render status bar
     render_status_bar(app, chunks[2], &mut buffer);
 
+    // Overlays are drawn last so they sit on top of the panels
+    if let Some(prompt) = &app.export_prompt {
+        render_export_prompt(&app.theme, prompt, area, &mut buffer);
+    }
+    // The progress overlay sits directly below the wizard, the two centered together
+    let wizard_height = app.wizard.as_ref().map_or(0, |_| WIZARD_HEIGHT);
+    let progress_height = app
+        .oauth_progress
+        .as_ref()
+        .map_or(0, |_| OAUTH_PROGRESS_HEIGHT);
+    let group = centered_rect(70, wizard_height + progress_height, area);
+    let wizard_height = wizard_height.min(group.height);
+    let wizard_area = Rect::new(group.x, group.y, group.width, wizard_height);
+    if let Some(wizard) = &app.wizard {
+        render_wizard(&app.theme, wizard, wizard_area, &mut buffer);
+    }
+    if let Some(progress) = &app.oauth_progress {
+        let progress_area = Rect::new(
+            group.x,
+            group.y + wizard_area.height,
+            group.width,
+            group.height - wizard_area.height,
+        );
+        render_oauth_progress(&app.theme, progress, progress_area, &mut buffer);
+    }
+    if let Some(overlay) = &app.token_diff {
+        render_token_diff(&app.theme, overlay, area, &mut buffer);
+    }
+    if let Some(notification) = &app.notification {
+        render_notification(&app.theme, notification, area, &mut buffer);
+    }
+    if app.show_help {
+        render_help_overlay(&app.theme, area, &mut buffer);
+    }
+
     Ok(buffer)
 }
 
 /// Render the title bar
-fn render_title(area: Rect, buffer: &mut Buffer) {
+fn render_title(theme: &Theme, area: Rect, buffer: &mut Buffer) {
     let title_block = Block::default()
         .title(
             Title::new("Sigilforge OAuth Token Manager")
                 .alignment(TitleAlignment::Center)
                 .style(
                     Style::default()
-                        .fg(COLOR_PRIMARY)
+                        .fg(theme.primary)
                         .add_modifier(Modifier::BOLD),
                 ),
         )
         .borders(Borders::ALL)
         .border_type(BorderType::Double)
-        .border_style(Style::default().fg(COLOR_PRIMARY));
+        .border_style(Style::default().fg(theme.primary));
 
     title_block.render(area, buffer);
 }
 
+/// Width of the service column in the accounts list
+const SERVICE_COLUMN_WIDTH: usize = 12;
+
+/// Width of the service column when the accounts list is fullscreen
+const FULLSCREEN_SERVICE_COLUMN_WIDTH: usize = 24;
+
+/// Where each panel of the content area goes; hidden panels are `None`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ContentLayout {
+    accounts: Option<Rect>,
+    details: Option<Rect>,
+    help: Option<Rect>,
+}
+
+/// Split the content area into panels
+///
+/// Normally the accounts list, details and help sit side by side; a
+/// fullscreen panel takes the whole area and the others are hidden.
+fn content_layout(area: Rect, fullscreen: Option<PanelId>) -> ContentLayout {
+    match fullscreen {
+        Some(PanelId::Accounts) => ContentLayout {
+            accounts: Some(area),
+            details: None,
+            help: None,
+        },
+        Some(PanelId::Details) => ContentLayout {
+            accounts: None,
+            details: Some(area),
+            help: None,
+        },
+        None => {
+            // Split into three columns: accounts list | details | help
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(&[
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(20),
+                ])
+                .split(area);
+            ContentLayout {
+                accounts: Some(chunks[0]),
+                details: Some(chunks[1]),
+                help: Some(chunks[2]),
+            }
+        }
+    }
+}
+
 /// Render the main content area
-fn render_content(app: &App, area: Rect, buffer: &mut Buffer) {
-    // Split into three columns: accounts list | details | help
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(&[
-            Constraint::Percentage(40),
-            Constraint::Percentage(40),
-            Constraint::Percentage(20),
-        ])
-        .split(area);
+fn render_content(app: &mut App, area: Rect, buffer: &mut Buffer) {
+    let layout = content_layout(area, app.fullscreen_panel);
 
-    render_accounts_list(app, chunks[0], buffer);
-    render_account_details(app, chunks[1], buffer);
-    render_help(chunks[2], buffer);
+    if let Some(details) = layout.details {
+        app.set_detail_max_scroll(detail_max_scroll(app, details));
+        render_account_details(app, details, buffer);
+    }
+    if let Some(accounts) = layout.accounts {
+        app.set_list_height(accounts.height);
+        render_accounts_list(app, accounts, buffer);
+    }
+    if let Some(help) = layout.help {
+        render_help(&app.theme, help, buffer);
+    }
 }
 
 /// Render the accounts list
 fn render_accounts_list(app: &App, area: Rect, buffer: &mut Buffer) {
+    let theme = &app.theme;
     let list_block = Block::default()
-        .title("OAuth Accounts")
+        .title(format!("OAuth Accounts ({}/{})", app.visible_count(), app.accounts.len()))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(COLOR_TEXT));
+        .border_style(Style::default().fg(theme.text));
+
+    let service_width = match app.fullscreen_panel {
+        Some(PanelId::Accounts) => FULLSCREEN_SERVICE_COLUMN_WIDTH,
+        _ => SERVICE_COLUMN_WIDTH,
+    };
 
     if app.accounts.is_empty() {
         // Show empty message
         let empty_text = if app.daemon_available {
-            "No OAuth accounts configured.\n\nUse the sigilforge CLI to add accounts."
+            "No OAuth accounts configured.\n\nPress n to add an account."
         } else {
             "Sigilforge daemon is not available.\n\nPlease start the daemon:\n  sigilforged"
         };
 
         let paragraph = Paragraph::new(Text::from(empty_text))
             .block(list_block)
-            .style(Style::default().fg(COLOR_DIM))
+            .style(Style::default().fg(theme.dim))
             .alignment(Alignment::Center)
             .wrap(Wrap::WordWrap);
 
@@ -116,31 +219,23 @@ fn render_accounts_list(app: &App, area: Rect, buffer: &mut Buffer) {
     } else {
         // Create list items
         let items: Vec<ListItem> = app
-            .accounts
-            .iter()
-            .map(|account| {
-                let status_color = match account.status {
-                    TokenStatus::Valid => COLOR_SUCCESS,
-                    TokenStatus::ExpiringSoon => COLOR_WARNING,
-                    TokenStatus::Expired => COLOR_ERROR,
-                    TokenStatus::Unknown => COLOR_DIM,
+            .account_rows()
+            .into_iter()
+            .map(|row| {
+                let account = match row {
+                    AccountRow::Heading(service) => {
+                        return ListItem::new(Line::from(Span::styled(
+                            service.to_string(),
+                            Style::default()
+                                .fg(theme.primary)
+                                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                        )));
+                    }
+                    AccountRow::Account(account) => account,
                 };
 
-                let line = Line::from(vec![
-                    Span::styled(
-                        format!("{:12}", account.service),
-                        Style::default().fg(COLOR_TEXT).add_modifier(Modifier::BOLD),
-                    ),
-                    Span::raw(" "),
-                    Span::styled(
-                        format!("[{}]", account.status_text()),
-                        Style::default().fg(status_color),
-                    ),
-                    Span::raw("  "),
-                    Span::styled(&account.account, Style::default().fg(COLOR_DIM)),
-                ]);
-
-                ListItem::new(line)
+                let spinner = app.refresh_spinner(account);
+                ListItem::new(account_line(theme, account, spinner, service_width))
             })
             .collect();
 
@@ -148,149 +243,363 @@ fn render_accounts_list(app: &App, area: Rect, buffer: &mut Buffer) {
             .block(list_block)
             .highlight_style(
                 Style::default()
-                    .bg(COLOR_PRIMARY)
+                    .bg(theme.primary)
                     .fg(Color::Black)
                     .add_modifier(Modifier::BOLD),
             );
 
         let mut state = ListState::default();
-        state.select(Some(app.selected));
+        state.select(app.selected_row());
 
         list.render(area, buffer, &mut state);
     }
 }
 
+/// Build the accounts list line for an account
+///
+/// `spinner` replaces the status while the account's token is refreshed;
+/// the service name is padded to `service_width`.
+fn account_line<'a>(
+    theme: &Theme,
+    account: &'a AccountInfo,
+    spinner: Option<char>,
+    service_width: usize,
+) -> Line<'a> {
+    let status = match spinner {
+        Some(frame) => Span::styled(format!("[{}]", frame), Style::default().fg(theme.primary)),
+        None => Span::styled(
+            format!("[{}]", account.status_text()),
+            Style::default().fg(status_color(theme, &account.status)),
+        ),
+    };
+
+    Line::from(vec![
+        Span::styled(
+            format!("{:width$}", account.service, width = service_width),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" "),
+        status,
+        Span::raw("  "),
+        Span::styled(&account.account, Style::default().fg(theme.dim)),
+    ])
+}
+
 /// Render account details panel
 fn render_account_details(app: &App, area: Rect, buffer: &mut Buffer) {
+    let theme = &app.theme;
+    let border_color = if app.detail_focused {
+        theme.primary
+    } else {
+        theme.text
+    };
+    let title = if app.raw_view {
+        "Account Details (JSON)"
+    } else {
+        "Account Details"
+    };
     let details_block = Block::default()
-        .title("Account Details")
+        .title(title)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(COLOR_TEXT));
+        .border_style(Style::default().fg(border_color));
 
     if let Some(account) = app.selected_account() {
-        // Pre-compute strings that need to be owned
-        let expiry_text = account.expiry_display();
+        let cursor = app.detail_focused.then_some(app.detail_cursor);
+        let mut paragraph = Paragraph::new(Text::from(detail_lines(app, account, cursor)))
+            .block(details_block)
+            .scroll((app.detail_scroll_offset, 0));
+        // JSON keeps its indentation, so long lines are cut off rather than wrapped
+        if !app.raw_view {
+            paragraph = paragraph.wrap(Wrap::WordWrap);
+        }
 
-        let mut lines = vec![
-            Line::from(vec![
-                Span::styled("Service: ", Style::default().fg(COLOR_DIM)),
-                Span::styled(
-                    &account.service,
-                    Style::default()
-                        .fg(COLOR_TEXT)
-                        .add_modifier(Modifier::BOLD),
-                ),
-            ]),
-            Line::from(vec![
-                Span::styled("Account: ", Style::default().fg(COLOR_DIM)),
-                Span::styled(&account.account, Style::default().fg(COLOR_TEXT)),
-            ]),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("Status: ", Style::default().fg(COLOR_DIM)),
+        paragraph.render(area, buffer);
+
+        if app.detail_scroll_offset > 0 {
+            render_scroll_indicator(theme, "▲", area, area.y, buffer);
+        }
+        if app.detail_scroll_offset < app.detail_max_scroll() {
+            let bottom = area.y + area.height.saturating_sub(1);
+            render_scroll_indicator(theme, "▼", area, bottom, buffer);
+        }
+    } else {
+        let empty_text = "No account selected";
+        let paragraph = Paragraph::new(Text::from(empty_text))
+            .block(details_block)
+            .style(Style::default().fg(theme.dim))
+            .alignment(Alignment::Center);
+
+        paragraph.render(area, buffer);
+    }
+}
+
+/// Lines shown in the detail panel for the selected `account`: its raw
+/// JSON in the raw view, otherwise its sections and stored credentials
+fn detail_lines<'a>(
+    app: &'a App,
+    account: &'a AccountInfo,
+    cursor: Option<usize>,
+) -> Vec<Line<'a>> {
+    if app.raw_view {
+        if let Some(json) = app.selected_account_json() {
+            return raw_json_lines(&app.theme, &json);
+        }
+    }
+
+    let mut lines = account_detail_lines(&app.theme, account, &app.collapsed_sections(), cursor);
+    lines.extend(stored_credential_lines(app));
+    lines
+}
+
+/// Highlight pretty-printed JSON: keys in the primary color, strings in the
+/// success color and numbers in the warning color
+fn raw_json_lines(theme: &Theme, json: &str) -> Vec<Line<'static>> {
+    json.lines()
+        .map(|line| {
+            let spans: Vec<Span<'static>> = json_view::highlight_line(line)
+                .into_iter()
+                .map(|span| {
+                    let color = match span.kind {
+                        JsonKind::Key => theme.primary,
+                        JsonKind::String => theme.success,
+                        JsonKind::Number => theme.warning,
+                        JsonKind::Plain => theme.text,
+                    };
+                    Span::styled(span.text, Style::default().fg(color))
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Build the lines shown in the detail panel for an account
+///
+/// Each [`DetailSection`] starts with a `▼`/`▶` header; collapsed sections
+/// show only that header. `cursor` highlights a header while the panel has
+/// focus.
+fn account_detail_lines<'a>(
+    theme: &Theme,
+    account: &'a AccountInfo,
+    collapsed: &HashSet<DetailSection>,
+    cursor: Option<usize>,
+) -> Vec<Line<'a>> {
+    let mut lines = Vec::new();
+
+    for (index, section) in DetailSection::ALL.into_iter().enumerate() {
+        let is_collapsed = collapsed.contains(&section);
+        let marker = if is_collapsed { "▶" } else { "▼" };
+        let mut header_style = Style::default().fg(theme.text).add_modifier(Modifier::BOLD);
+        if cursor == Some(index) {
+            header_style = header_style.add_modifier(Modifier::REVERSED);
+        }
+        lines.push(Line::from(Span::styled(
+            format!("{} {}", marker, section.title()),
+            header_style,
+        )));
+
+        if is_collapsed {
+            continue;
+        }
+        lines.extend(detail_section_lines(theme, account, section));
+        lines.push(Line::from(""));
+    }
+
+    lines
+}
+
+/// Content of one expanded detail section
+fn detail_section_lines<'a>(
+    theme: &Theme,
+    account: &'a AccountInfo,
+    section: DetailSection,
+) -> Vec<Line<'a>> {
+    let field = |label: &'static str, value: Span<'a>| {
+        Line::from(vec![
+            Span::styled(label, Style::default().fg(theme.dim)),
+            value,
+        ])
+    };
+    let text = Style::default().fg(theme.text);
+
+    match section {
+        DetailSection::Metadata => vec![
+            field(
+                "Service: ",
+                Span::styled(&account.service, text.add_modifier(Modifier::BOLD)),
+            ),
+            field("Account: ", Span::styled(&account.account, text)),
+            field("Source: ", Span::styled(account.source.to_string(), text)),
+            field(
+                "Status: ",
                 Span::styled(
                     account.status_text(),
-                    Style::default().fg(match account.status {
-                        TokenStatus::Valid => COLOR_SUCCESS,
-                        TokenStatus::ExpiringSoon => COLOR_WARNING,
-                        TokenStatus::Expired => COLOR_ERROR,
-                        TokenStatus::Unknown => COLOR_DIM,
-                    }),
+                    Style::default().fg(status_color(theme, &account.status)),
                 ),
-            ]),
-            Line::from(vec![
-                Span::styled("Expiry: ", Style::default().fg(COLOR_DIM)),
-                Span::styled(expiry_text, Style::default().fg(COLOR_TEXT)),
-            ]),
-            Line::from(""),
-        ];
-
-        // Add scopes
-        if !account.scopes.is_empty() {
-            lines.push(Line::from(Span::styled(
-                "Scopes:",
-                Style::default().fg(COLOR_DIM),
-            )));
-            for scope in &account.scopes {
-                lines.push(Line::from(format!("  - {}", scope)));
+            ),
+            field("Expiry: ", Span::styled(account.expiry_display(), text)),
+        ],
+        DetailSection::Scopes if account.scopes.is_empty() => {
+            vec![Line::from(Span::styled(
+                "  (none)",
+                Style::default().fg(theme.dim),
+            ))]
+        }
+        DetailSection::Scopes => account
+            .scopes
+            .iter()
+            .map(|scope| Line::from(format!("  - {}", scope)))
+            .collect(),
+        DetailSection::Timestamps => {
+            let mut lines = vec![field("Created: ", Span::styled(&account.created_at, text))];
+            if let Some(last_used) = &account.last_used {
+                lines.push(field("Last used: ", Span::styled(last_used, text)));
             }
-            lines.push(Line::from(""));
+            lines
         }
+    }
+}
 
-        // Add timestamps
+/// "Stored Credentials" lines for the selected account
+///
+/// Values are shown as [`REDACTED`] unless revealed; `▸` marks the
+/// credential `v` reveals.
+fn stored_credential_lines(app: &App) -> Vec<Line<'_>> {
+    let theme = &app.theme;
+    let Some(loaded) = app.stored_credentials.as_ref().filter(|loaded| {
+        app.selected_account()
+            .is_some_and(|account| loaded.is_for(&account.service, &account.account))
+    }) else {
+        return Vec::new();
+    };
+
+    let dim = Style::default().fg(theme.dim);
+    let mut lines = vec![Line::from(Span::styled(
+        "Stored Credentials",
+        Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+    ))];
+    if let Some(error) = &loaded.error {
+        lines.push(Line::from(Span::styled(format!("  (unavailable: {})", error), dim)));
+    } else if loaded.types.is_empty() {
+        lines.push(Line::from(Span::styled("  (none)", dim)));
+    }
+
+    for (index, credential_type) in loaded.types.iter().enumerate() {
+        let marker = if index == app.credential_cursor { "▸ " } else { "  " };
+        let value = match app.revealed_value(credential_type) {
+            Some(value) => Span::styled(value, Style::default().fg(theme.warning)),
+            None => Span::styled(REDACTED, dim),
+        };
         lines.push(Line::from(vec![
-            Span::styled("Created: ", Style::default().fg(COLOR_DIM)),
-            Span::styled(&account.created_at, Style::default().fg(COLOR_TEXT)),
+            Span::styled(marker, Style::default().fg(theme.primary)),
+            Span::styled(format!("{}: ", credential_type), dim),
+            value,
         ]));
+    }
+    lines
+}
 
-        if let Some(last_used) = &account.last_used {
-            lines.push(Line::from(vec![
-                Span::styled("Last used: ", Style::default().fg(COLOR_DIM)),
-                Span::styled(last_used, Style::default().fg(COLOR_TEXT)),
-            ]));
-        }
+/// Number of lines the detail panel content extends past the panel
+///
+/// Wrapped height is estimated from line width, so word wrapping that breaks
+/// early may leave a line or two unreachable until the panel grows. The raw
+/// view does not wrap, so there it is exact.
+fn detail_max_scroll(app: &App, area: Rect) -> u16 {
+    let Some(account) = app.selected_account() else {
+        return 0;
+    };
 
-        let paragraph = Paragraph::new(Text::from(lines))
-            .block(details_block)
-            .wrap(Wrap::WordWrap);
+    let inner_width = area.width.saturating_sub(2).max(1) as usize;
+    let inner_height = area.height.saturating_sub(2);
 
-        paragraph.render(area, buffer);
+    let lines = detail_lines(app, account, None);
+    let content_height: usize = if app.raw_view {
+        lines.len()
     } else {
-        let empty_text = "No account selected";
-        let paragraph = Paragraph::new(Text::from(empty_text))
-            .block(details_block)
-            .style(Style::default().fg(COLOR_DIM))
-            .alignment(Alignment::Center);
+        lines
+            .iter()
+            .map(|line| line.width().div_ceil(inner_width).max(1))
+            .sum()
+    };
 
-        paragraph.render(area, buffer);
+    u16::try_from(content_height)
+        .unwrap_or(u16::MAX)
+        .saturating_sub(inner_height)
+}
+
+/// Draw a scroll arrow on the right end of a panel border row
+fn render_scroll_indicator(theme: &Theme, symbol: &str, area: Rect, y: u16, buffer: &mut Buffer) {
+    if area.width < 3 || area.height < 2 {
+        return;
     }
+
+    let x = area.x + area.width - 2;
+    Paragraph::new(Text::from(symbol))
+        .style(Style::default().fg(theme.primary))
+        .render(Rect::new(x, y, 1, 1), buffer);
 }
 
 /// Render help panel
-fn render_help(area: Rect, buffer: &mut Buffer) {
+fn render_help(theme: &Theme, area: Rect, buffer: &mut Buffer) {
     let help_block = Block::default()
         .title("Keyboard")
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(COLOR_TEXT));
+        .border_style(Style::default().fg(theme.text));
 
     let help_text = vec![
         Line::from(Span::styled(
             "Navigation:",
             Style::default()
-                .fg(COLOR_PRIMARY)
+                .fg(theme.primary)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from("j/↓  - Next"),
         Line::from("k/↑  - Previous"),
-        Line::from("g    - First"),
+        Line::from("gg   - First"),
         Line::from("G    - Last"),
+        Line::from("C-d/u - Half page"),
+        Line::from("/    - Search"),
+        Line::from("n/N  - Next/prev match"),
+        Line::from("Tab  - Group"),
+        Line::from("f    - Filter status"),
+        Line::from("C-↓/↑ - Scroll"),
+        Line::from("l/→  - Focus details"),
+        Line::from("F    - Fullscreen"),
+        Line::from("J    - Raw JSON"),
+        Line::from("Enter - Fold section"),
         Line::from(""),
         Line::from(Span::styled(
             "Actions:",
             Style::default()
-                .fg(COLOR_PRIMARY)
+                .fg(theme.primary)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from("r    - Refresh"),
         Line::from("a    - Refresh all"),
+        Line::from("n    - New account"),
+        Line::from("e    - Export"),
+        Line::from("c    - Next credential"),
+        Line::from("v    - Reveal credential"),
+        Line::from("?    - All shortcuts"),
         Line::from("q    - Quit"),
     ];
 
     let paragraph = Paragraph::new(Text::from(help_text))
         .block(help_block)
-        .style(Style::default().fg(COLOR_TEXT));
+        .style(Style::default().fg(theme.text));
 
     paragraph.render(area, buffer);
 }
 
 /// Render the status bar
 fn render_status_bar(app: &App, area: Rect, buffer: &mut Buffer) {
+    let theme = &app.theme;
     let status_style = if app.daemon_available {
-        Style::default().fg(COLOR_SUCCESS)
+        Style::default().fg(theme.success)
     } else {
-        Style::default().fg(COLOR_ERROR)
+        Style::default().fg(theme.error)
     };
 
     let status_block = Block::default()
@@ -304,16 +613,34 @@ fn render_status_bar(app: &App, area: Rect, buffer: &mut Buffer) {
         Span::styled(
             " Daemon Unavailable ",
             Style::default()
-                .fg(COLOR_ERROR)
+                .fg(theme.error)
                 .add_modifier(Modifier::BOLD),
         )
     };
 
-    let status_line = Line::from(vec![
-        daemon_status,
-        Span::raw(" | "),
-        Span::styled(&app.status_message, Style::default().fg(COLOR_TEXT)),
-    ]);
+    let mut spans = vec![daemon_status, Span::raw(" | ")];
+    if let Some(panel) = app.fullscreen_panel {
+        spans.push(Span::styled(
+            format!("Fullscreen: {} (F/Esc to exit)", panel.label()),
+            Style::default().fg(theme.primary),
+        ));
+        spans.push(Span::raw(" | "));
+    }
+    if let Some(filter) = app.status_filter {
+        spans.push(Span::styled(
+            format!("Filter: {}", filter.label()),
+            Style::default().fg(theme.primary),
+        ));
+        spans.push(Span::raw(" | "));
+    }
+    match &app.search_prompt {
+        Some(prompt) => {
+            spans.push(Span::styled("/", Style::default().fg(theme.primary)));
+            spans.extend(prompt_spans(theme, prompt));
+        }
+        None => spans.push(Span::styled(&app.status_message, Style::default().fg(theme.text))),
+    }
+    let status_line = Line::from(spans);
 
     let paragraph = Paragraph::new(Text::from(vec![status_line]))
         .block(status_block)
@@ -321,3 +648,680 @@ fn render_status_bar(app: &App, area: Rect, buffer: &mut Buffer) {
 
     paragraph.render(area, buffer);
 }
+
+/// Render the export filename prompt as a centered popup
+fn render_export_prompt(theme: &Theme, prompt: &TextInput, area: Rect, buffer: &mut Buffer) {
+    let popup = centered_rect(60, 6, area);
+    let inner_width = popup.width.saturating_sub(2) as usize;
+
+    let mut file_spans = vec![Span::styled("File: ", Style::default().fg(theme.dim))];
+    file_spans.extend(prompt_spans(theme, prompt));
+
+    let lines = vec![
+        Line::from(file_spans),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter - Export (.json or .csv)  Esc - Cancel",
+            Style::default().fg(theme.dim),
+        )),
+    ];
+
+    let block = Block::default()
+        .title("Export Accounts")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary));
+
+    Paragraph::new(Text::from(pad_lines(lines, inner_width, popup.height)))
+        .block(block)
+        .render(popup, buffer);
+}
+
+/// Height of the account creation popup
+const WIZARD_HEIGHT: u16 = 10;
+
+/// Height of the OAuth progress popup: a line per step, a blank line and the
+/// elapsed time inside the borders
+const OAUTH_PROGRESS_HEIGHT: u16 = OAuthStep::ALL.len() as u16 + 4;
+
+/// Render the account creation overlay for the wizard's current step in `popup`
+fn render_wizard(theme: &Theme, wizard: &CreationWizard, popup: Rect, buffer: &mut Buffer) {
+    let inner_width = popup.width.saturating_sub(2) as usize;
+    let hint = |text: &'static str| Line::from(Span::styled(text, Style::default().fg(theme.dim)));
+
+    let (title, border, lines) = match &wizard.step {
+        WizardStep::Form => {
+            let field_line = |label: &'static str, field: WizardField, input: &TextInput| {
+                let focused = wizard.field == field;
+                let label_color = if focused { theme.primary } else { theme.dim };
+                let mut spans = vec![Span::styled(label, Style::default().fg(label_color))];
+                if focused {
+                    spans.extend(prompt_spans(theme, input));
+                } else {
+                    let value = input.value().to_string();
+                    spans.push(Span::styled(value, Style::default().fg(theme.text)));
+                }
+                Line::from(spans)
+            };
+            let lines = vec![
+                field_line("Service: ", WizardField::Service, &wizard.service),
+                field_line("Account: ", WizardField::Account, &wizard.account),
+                Line::from(""),
+                hint("Tab - Switch field  Enter - Next / Authorize  Esc - Cancel"),
+            ];
+            ("New Account", theme.primary, lines)
+        }
+        WizardStep::Starting => {
+            let lines = vec![
+                Line::from(Span::styled(
+                    "Starting OAuth flow...",
+                    Style::default().fg(theme.text),
+                )),
+                Line::from(""),
+                hint("Esc - Cancel"),
+            ];
+            ("New Account", theme.primary, lines)
+        }
+        WizardStep::Authorizing { auth_url } => {
+            let mut lines = vec![
+                Line::from(Span::styled(
+                    "Authorize in your browser, or visit:",
+                    Style::default().fg(theme.text),
+                )),
+                Line::from(""),
+            ];
+            // Break the URL by hand; it has no spaces for word wrapping
+            let chars: Vec<char> = auth_url.chars().collect();
+            for chunk in chars.chunks(inner_width.max(1)) {
+                let chunk: String = chunk.iter().collect();
+                lines.push(Line::from(Span::styled(
+                    chunk,
+                    Style::default().fg(theme.primary),
+                )));
+            }
+            lines.push(Line::from(""));
+            lines.push(hint("Waiting for the callback...  Esc - Cancel"));
+            ("New Account", theme.primary, lines)
+        }
+        WizardStep::Failed { message } => {
+            let lines = vec![
+                Line::from(Span::styled(
+                    message.clone(),
+                    Style::default()
+                        .fg(theme.error)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                hint("Enter/Esc - Back to the form"),
+            ];
+            ("Account Creation Failed", theme.error, lines)
+        }
+    };
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(border));
+
+    Paragraph::new(Text::from(pad_lines(lines, inner_width, popup.height)))
+        .block(block)
+        .wrap(Wrap::WordWrap)
+        .render(popup, buffer);
+}
+
+/// Render the steps of the account creation OAuth flow in `popup`
+fn render_oauth_progress(
+    theme: &Theme,
+    progress: &OAuthProgressState,
+    popup: Rect,
+    buffer: &mut Buffer,
+) {
+    let inner_width = popup.width.saturating_sub(2) as usize;
+    let border = if progress.error.is_some() {
+        theme.error
+    } else {
+        theme.primary
+    };
+
+    let block = Block::default()
+        .title("OAuth Progress")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(border));
+
+    let lines = oauth_progress_lines(theme, progress);
+    Paragraph::new(Text::from(pad_lines(lines, inner_width, popup.height)))
+        .block(block)
+        .render(popup, buffer);
+}
+
+/// One line per step, marked ✓ when completed, ⟳ while in progress, □ while
+/// pending, or ✗ where the flow failed, then the elapsed time
+fn oauth_progress_lines(theme: &Theme, progress: &OAuthProgressState) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line<'static>> = OAuthStep::ALL
+        .iter()
+        .map(|&step| {
+            let status = progress.step_status(step);
+            let (mark, color) = match status {
+                StepStatus::Completed => ("✓", theme.success),
+                StepStatus::InProgress => ("⟳", theme.primary),
+                StepStatus::Pending => ("□", theme.dim),
+                StepStatus::Failed => ("✗", theme.error),
+            };
+            let label_color = if status == StepStatus::Pending {
+                theme.dim
+            } else {
+                theme.text
+            };
+            Line::from(vec![
+                Span::styled(format!(" {} ", mark), Style::default().fg(color)),
+                Span::styled(step.label(), Style::default().fg(label_color)),
+            ])
+        })
+        .collect();
+
+    let secs = progress.elapsed.as_secs();
+    let elapsed = if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(" Elapsed: {}", elapsed),
+        Style::default().fg(theme.dim),
+    )));
+    lines
+}
+
+/// A prompt's text with the character under the cursor highlighted
+fn prompt_spans(theme: &Theme, prompt: &TextInput) -> Vec<Span<'static>> {
+    let value: Vec<char> = prompt.value().chars().collect();
+    let before: String = value[..prompt.cursor()].iter().collect();
+    let under = value.get(prompt.cursor()).copied().unwrap_or(' ').to_string();
+    let after: String = value.get(prompt.cursor() + 1..).unwrap_or(&[]).iter().collect();
+
+    vec![
+        Span::styled(before, Style::default().fg(theme.text)),
+        Span::styled(
+            under,
+            Style::default().fg(theme.text).add_modifier(Modifier::REVERSED),
+        ),
+        Span::styled(after, Style::default().fg(theme.text)),
+    ]
+}
+
+/// Render a notification as a centered popup
+fn render_notification(
+    theme: &Theme,
+    notification: &Notification,
+    area: Rect,
+    buffer: &mut Buffer,
+) {
+    let width = (notification.message.chars().count() as u16).saturating_add(6);
+    let popup = centered_rect(width, 3, area);
+    let inner_width = popup.width.saturating_sub(2) as usize;
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.success));
+
+    let lines = vec![Line::from(Span::styled(
+        format!(" {}", notification.message),
+        Style::default().fg(theme.success).add_modifier(Modifier::BOLD),
+    ))];
+
+    Paragraph::new(Text::from(pad_lines(lines, inner_width, popup.height)))
+        .block(block)
+        .render(popup, buffer);
+}
+
+/// Render the changes from the last refresh as a centered popup
+fn render_token_diff(theme: &Theme, overlay: &TokenDiffOverlay, area: Rect, buffer: &mut Buffer) {
+    let lines = token_diff_lines(theme, &overlay.changes);
+    let popup = centered_rect(64, (lines.len() as u16).saturating_add(2), area);
+    let inner_width = popup.width.saturating_sub(2) as usize;
+
+    let block = Block::default()
+        .title("Token Changes")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary));
+
+    Paragraph::new(Text::from(pad_lines(lines, inner_width, popup.height)))
+        .block(block)
+        .render(popup, buffer);
+}
+
+/// Render every key binding as a centered popup, opened with `?`
+fn render_help_overlay(theme: &Theme, area: Rect, buffer: &mut Buffer) {
+    let lines = help_overlay_lines(theme, KEY_BINDINGS);
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let height = (lines.len() as u16).saturating_add(2);
+    let popup = centered_rect(width.saturating_add(4), height, area);
+    let inner_width = popup.width.saturating_sub(2) as usize;
+
+    let block = Block::default()
+        .title("Keyboard Shortcuts")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary));
+
+    Paragraph::new(Text::from(pad_lines(lines, inner_width, popup.height)))
+        .block(block)
+        .render(popup, buffer);
+}
+
+/// One line per binding, keys in a column on the left and actions on the
+/// right, followed by how to close the popup
+fn help_overlay_lines(theme: &Theme, bindings: &[(&str, &str)]) -> Vec<Line<'static>> {
+    let key_width = bindings
+        .iter()
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<Line<'static>> = bindings
+        .iter()
+        .map(|(keys, action)| {
+            let padding = key_width - keys.chars().count();
+            Line::from(vec![
+                Span::styled(
+                    format!(" {}{}  ", keys, " ".repeat(padding)),
+                    Style::default()
+                        .fg(theme.primary)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(action.to_string(), Style::default().fg(theme.text)),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " Press ? or Esc to close",
+        Style::default().fg(theme.dim),
+    )));
+    lines
+}
+
+/// Old vs. new expiry and scope changes for each refreshed account
+///
+/// Added scopes and extended expiries are shown in the success color,
+/// removed scopes and earlier expiries in the error color.
+fn token_diff_lines(theme: &Theme, changes: &[(String, String, TokenDiff)]) -> Vec<Line<'static>> {
+    if changes.is_empty() {
+        return vec![Line::from(Span::styled(
+            " No token changes",
+            Style::default().fg(theme.dim),
+        ))];
+    }
+
+    let mut lines = Vec::new();
+    for (service, account, diff) in changes {
+        lines.push(Line::from(Span::styled(
+            format!(" {}/{}", service, account),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        )));
+        if diff.expiry_changed() {
+            let new_color = if diff.expiry_regressed() {
+                theme.error
+            } else {
+                theme.success
+            };
+            let old = diff_expiry(diff.old_expires_at);
+            let new = diff_expiry(diff.new_expires_at);
+            lines.push(Line::from(vec![
+                Span::styled("   Expires: ", Style::default().fg(theme.dim)),
+                Span::styled(old, Style::default().fg(theme.text)),
+                Span::styled(" -> ", Style::default().fg(theme.dim)),
+                Span::styled(new, Style::default().fg(new_color)),
+            ]));
+        }
+        for scope in &diff.scopes_added {
+            lines.push(Line::from(Span::styled(
+                format!("   + {}", scope),
+                Style::default().fg(theme.success),
+            )));
+        }
+        for scope in &diff.scopes_removed {
+            lines.push(Line::from(Span::styled(
+                format!("   - {}", scope),
+                Style::default().fg(theme.error),
+            )));
+        }
+    }
+    lines
+}
+
+/// Expiry time as shown in the token diff popup
+fn diff_expiry(expires_at: Option<DateTime<Utc>>) -> String {
+    expires_at
+        .map(|expiry| expiry.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| "never".to_string())
+}
+
+/// Color used for a token status
+fn status_color(theme: &Theme, status: &TokenStatus) -> Color {
+    match status {
+        TokenStatus::Valid => theme.success,
+        TokenStatus::ExpiringSoon => theme.warning,
+        TokenStatus::Expired => theme.error,
+        TokenStatus::Unknown => theme.dim,
+    }
+}
+
+/// A rectangle of at most `width` x `height` centered in `area`
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Pad popup lines with spaces so they cover the panels underneath
+fn pad_lines(mut lines: Vec<Line<'_>>, inner_width: usize, height: u16) -> Vec<Line<'_>> {
+    let inner_height = height.saturating_sub(2) as usize;
+    lines.resize_with(inner_height.max(lines.len()), || Line::from(""));
+
+    for line in &mut lines {
+        let padding = inner_width.saturating_sub(line.width());
+        line.spans.push(Span::raw(" ".repeat(padding)));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sigilforge_core::CredentialSource;
+
+    #[test]
+    fn test_status_colors_follow_theme() {
+        for theme in [Theme::default(), Theme::catppuccin_mocha(), Theme::gruvbox()] {
+            assert_eq!(status_color(&theme, &TokenStatus::Valid), theme.success);
+            assert_eq!(status_color(&theme, &TokenStatus::ExpiringSoon), theme.warning);
+            assert_eq!(status_color(&theme, &TokenStatus::Expired), theme.error);
+            assert_eq!(status_color(&theme, &TokenStatus::Unknown), theme.dim);
+        }
+    }
+
+    #[test]
+    fn test_detail_lines_use_theme_colors() {
+        let theme = Theme::gruvbox();
+        let account = AccountInfo {
+            service: "github".to_string(),
+            account: "personal".to_string(),
+            scopes: vec!["repo".to_string()],
+            created_at: "2024-01-01".to_string(),
+            last_used: None,
+            expires_at: None,
+            status: TokenStatus::Expired,
+            source: CredentialSource::OAuthPkce {
+                provider_id: "github".to_string(),
+            },
+        };
+
+        let lines = account_detail_lines(&theme, &account, &HashSet::new(), None);
+        let colors: Vec<Option<Color>> = lines
+            .iter()
+            .flat_map(|line| line.spans.iter().map(|span| span.style.fg))
+            .collect();
+
+        assert!(colors.contains(&Some(theme.error)));
+        assert!(colors
+            .iter()
+            .all(|c| [Some(theme.dim), Some(theme.text), Some(theme.error)].contains(c)));
+
+        let text: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        assert!(text.contains(&"Source: OAuth PKCE (github)".to_string()));
+    }
+
+    #[test]
+    fn test_raw_view_renders_highlighted_json() {
+        let account = AccountInfo {
+            service: "github".to_string(),
+            account: "work".to_string(),
+            scopes: vec!["repo".to_string()],
+            created_at: "2024-01-01".to_string(),
+            last_used: None,
+            expires_at: None,
+            status: TokenStatus::Valid,
+            source: CredentialSource::Unknown,
+        };
+        let mut app = App::with_accounts(vec![account]);
+        let account = app.selected_account().unwrap().clone();
+
+        let sections: Vec<String> = detail_lines(&app, &account, None)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert!(sections.contains(&"▼ Metadata".to_string()));
+
+        app.toggle_raw_view();
+        let lines = detail_lines(&app, &account, None);
+        let json: String = lines
+            .iter()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_ne!(json, sections.join("\n"));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["token_info"]["scopes"], serde_json::json!(["repo"]));
+
+        // Keys come out sorted, so `"account": "work",` is first
+        assert_eq!(lines[1].to_string(), r#"  "account": "work","#);
+        assert_eq!(lines[1].spans[1].style.fg, Some(app.theme.primary));
+        assert_eq!(lines[1].spans[3].style.fg, Some(app.theme.success));
+    }
+
+    #[test]
+    fn test_account_line_shows_refresh_spinner() {
+        let theme = Theme::default();
+        let account = AccountInfo {
+            service: "github".to_string(),
+            account: "work".to_string(),
+            scopes: vec![],
+            created_at: String::new(),
+            last_used: None,
+            expires_at: None,
+            status: TokenStatus::Valid,
+            source: CredentialSource::Unknown,
+        };
+
+        let line = account_line(&theme, &account, None, SERVICE_COLUMN_WIDTH);
+        assert_eq!(line.to_string(), "github       [ACTIVE]  work");
+        assert_eq!(line.spans[2].style.fg, Some(theme.success));
+
+        let line = account_line(&theme, &account, Some('⠹'), SERVICE_COLUMN_WIDTH);
+        assert_eq!(line.to_string(), "github       [⠹]  work");
+        assert_eq!(line.spans[2].style.fg, Some(theme.primary));
+
+        let line = account_line(&theme, &account, None, FULLSCREEN_SERVICE_COLUMN_WIDTH);
+        assert_eq!(line.to_string(), format!("{:24} [ACTIVE]  work", "github"));
+    }
+
+    #[test]
+    fn test_help_overlay_lists_every_binding() {
+        let theme = Theme::default();
+        let lines: Vec<String> = help_overlay_lines(&theme, KEY_BINDINGS)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        assert!(KEY_BINDINGS.len() >= 15);
+        assert_eq!(lines.len(), KEY_BINDINGS.len() + 2);
+        let expected = [
+            ("j / ↓", "Next account"),
+            ("k / ↑", "Previous account"),
+            ("gg / Home", "First account"),
+            ("G / End", "Last account"),
+            ("Ctrl+d", "Half page down"),
+            ("Ctrl+u", "Half page up"),
+            ("/", "Search accounts"),
+            ("n / N", "Next / previous match"),
+            ("Esc", "Clear search"),
+            ("r", "Refresh account"),
+            ("a", "Refresh all accounts"),
+            ("e", "Export accounts"),
+            ("v", "Reveal credential"),
+            ("?", "Toggle this help"),
+            ("q / Ctrl+c", "Quit"),
+        ];
+        for (keys, action) in expected {
+            let line = format!(" {:15}  {}", keys, action);
+            assert!(lines.contains(&line), "missing {:?} in {:#?}", line, lines);
+        }
+        assert_eq!(lines.last().unwrap(), " Press ? or Esc to close");
+
+        // Keys and actions each line up in a column
+        let action_column = " Ctrl+↓ / Ctrl+↑  ".chars().count();
+        for line in &lines[..KEY_BINDINGS.len()] {
+            let before: String = line.chars().take(action_column).collect();
+            assert!(before.ends_with("  "), "{:?}", line);
+            assert_ne!(line.chars().nth(action_column), Some(' '), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_help_overlay_styles_keys_and_actions() {
+        let theme = Theme::default();
+        let lines = help_overlay_lines(&theme, &[("x", "Do a thing")]);
+
+        assert_eq!(lines[0].to_string(), " x  Do a thing");
+        assert_eq!(lines[0].spans[0].style.fg, Some(theme.primary));
+        assert_eq!(lines[0].spans[1].style.fg, Some(theme.text));
+    }
+
+    #[test]
+    fn test_content_layout_splits_three_panels() {
+        let area = Rect::new(0, 3, 100, 30);
+        let layout = content_layout(area, None);
+
+        let panels = [layout.accounts, layout.details, layout.help].map(Option::unwrap);
+        assert_eq!(panels.map(|panel| panel.width), [40, 40, 20]);
+        assert!(panels.iter().all(|panel| panel.y == 3));
+        assert!(panels.iter().all(|panel| panel.height == 30));
+    }
+
+    #[test]
+    fn test_content_layout_fullscreen_panel_fills_area() {
+        let area = Rect::new(0, 3, 100, 30);
+
+        let layout = content_layout(area, Some(PanelId::Accounts));
+        assert_eq!(
+            layout,
+            ContentLayout {
+                accounts: Some(area),
+                details: None,
+                help: None,
+            }
+        );
+
+        let layout = content_layout(area, Some(PanelId::Details));
+        assert_eq!(
+            layout,
+            ContentLayout {
+                accounts: None,
+                details: Some(area),
+                help: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_token_diff_lines_color_changes() {
+        use chrono::TimeZone;
+
+        let theme = Theme::default();
+        let old = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let diff = TokenDiff {
+            old_expires_at: Some(old),
+            new_expires_at: Some(old - chrono::Duration::hours(1)),
+            scopes_added: vec!["gist".to_string()],
+            scopes_removed: vec!["repo".to_string()],
+        };
+
+        let lines = token_diff_lines(&theme, &[("github".into(), "work".into(), diff)]);
+        let text: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        assert_eq!(
+            text,
+            [
+                " github/work",
+                "   Expires: 2025-01-01 12:00 UTC -> 2025-01-01 11:00 UTC",
+                "   + gist",
+                "   - repo",
+            ]
+        );
+
+        // An earlier expiry is an error, as is a removed scope
+        assert_eq!(lines[1].spans[3].style.fg, Some(theme.error));
+        assert_eq!(lines[2].spans[0].style.fg, Some(theme.success));
+        assert_eq!(lines[3].spans[0].style.fg, Some(theme.error));
+
+        let lines = token_diff_lines(&theme, &[]);
+        assert_eq!(lines[0].to_string(), " No token changes");
+    }
+
+    use std::time::{Duration, Instant};
+
+    fn progress_text(progress: &OAuthProgressState) -> Vec<String> {
+        oauth_progress_lines(&Theme::default(), progress)
+            .iter()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_oauth_progress_marks_completed_steps() {
+        let theme = Theme::default();
+        let start = Instant::now();
+        let mut progress = OAuthProgressState::new(start);
+        progress.advance(
+            OAuthStep::ListeningForCallback,
+            start + Duration::from_secs(75),
+        );
+
+        assert_eq!(
+            progress_text(&progress),
+            [
+                " ✓ Building authorization URL",
+                " ✓ Opening browser",
+                " ⟳ Waiting for callback",
+                " □ Exchanging code for tokens",
+                " □ Storing tokens",
+                " □ Complete",
+                "",
+                " Elapsed: 1m 15s",
+            ]
+        );
+        let lines = oauth_progress_lines(&theme, &progress);
+        assert_eq!(lines[0].spans[0].style.fg, Some(theme.success));
+        assert_eq!(lines[2].spans[0].style.fg, Some(theme.primary));
+        assert_eq!(lines[3].spans[0].style.fg, Some(theme.dim));
+
+        progress.advance(OAuthStep::Complete, start + Duration::from_secs(80));
+        let text = progress_text(&progress);
+        assert!(text[..6].iter().all(|line| line.starts_with(" ✓ ")));
+        assert_eq!(text[7], " Elapsed: 1m 20s");
+    }
+
+    #[test]
+    fn test_oauth_progress_marks_failed_step() {
+        let start = Instant::now();
+        let mut progress = OAuthProgressState::new(start);
+        progress.advance(OAuthStep::ExchangingCode, start);
+        progress.fail("token exchange failed", start + Duration::from_secs(4));
+
+        let text = progress_text(&progress);
+        assert_eq!(text[2], " ✓ Waiting for callback");
+        assert_eq!(text[3], " ✗ Exchanging code for tokens");
+        assert_eq!(text[4], " □ Storing tokens");
+        assert_eq!(text[7], " Elapsed: 4s");
+    }
+}
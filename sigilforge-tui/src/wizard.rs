@@ -0,0 +1,605 @@
+//! Inline account creation.
+//!
+//! `n` opens a form for the service and account names. Submitting it runs
+//! the PKCE flow in a background task: one `oneshot` channel carries the
+//! authorization URL back to the UI, a second carries the token set once the
+//! browser callback arrives. The UI polls both from its tick, along with a
+//! `watch` channel on which the task reports when it starts the exchange.
+//!
+//! Text pasted into the form is reduced to the characters valid in service
+//! and account IDs.
+
+use crate::app::OAuthStep;
+use crate::input::TextInput;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use sigilforge_core::{
+    oauth::pkce::{PkceFlow, RedirectConfig},
+    Account, AccountId, AccountStore, CredentialSource, ProviderRegistry, ServiceId, TokenManager,
+    TokenSet,
+};
+use std::collections::HashMap;
+use tokio::sync::oneshot::{self, error::TryRecvError};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Callback port used when `OAUTH_CALLBACK_PORT` is unset (same as the CLI)
+pub const DEFAULT_CALLBACK_PORT: u16 = 8484;
+
+/// Longest service or account name the form accepts, in characters
+pub const MAX_NAME_LEN: usize = 64;
+
+/// Keep only the characters valid in a service or account ID
+pub fn sanitize_name(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        .collect()
+}
+
+/// Which form field has focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WizardField {
+    Service,
+    Account,
+}
+
+/// Where the wizard is in creating the account
+#[derive(Debug, Clone, PartialEq)]
+pub enum WizardStep {
+    /// Editing the service and account names
+    Form,
+    /// Flow task started; waiting for the authorization URL
+    Starting,
+    /// Waiting for the user to authorize in the browser
+    Authorizing { auth_url: String },
+    /// The flow failed; shown until dismissed
+    Failed { message: String },
+}
+
+/// What a key press in the form asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormAction {
+    None,
+    Submit,
+    Cancel,
+}
+
+/// Progress reported by [`CreationWizard::poll`]
+#[derive(Debug)]
+pub enum WizardEvent {
+    /// The authorization URL is ready to open in the browser
+    AuthorizationUrl(String),
+    /// The flow task moved on to another step
+    Progress(OAuthStep),
+    /// The callback arrived and the code was exchanged for tokens
+    Completed(TokenSet),
+}
+
+/// Receiving ends of a running flow task
+///
+/// Dropping the handle aborts the task, which frees the callback port.
+pub struct FlowHandle {
+    auth_url: oneshot::Receiver<Result<String, String>>,
+    tokens: oneshot::Receiver<Result<TokenSet, String>>,
+    progress: watch::Receiver<OAuthStep>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl FlowHandle {
+    /// Run `flow` in a background task
+    pub fn spawn(flow: PkceFlow, scopes: Vec<String>) -> Self {
+        let (url_tx, auth_url) = oneshot::channel();
+        let (tokens_tx, tokens) = oneshot::channel();
+        let (progress_tx, progress) = watch::channel(OAuthStep::BuildingUrl);
+
+        let task = tokio::spawn(async move {
+            let host = flow.redirect().host.clone();
+            let (flow, listener) = match flow.prepare_flow(scopes, &host).await {
+                Ok(bound) => bound,
+                Err(e) => {
+                    let _ = url_tx.send(Err(e.to_string()));
+                    return;
+                }
+            };
+            let (auth_url, csrf_state) = flow.build_authorization_url(Vec::new(), HashMap::new());
+            let _ = url_tx.send(Ok(auth_url));
+
+            let result = match flow.listen_for_callback(listener, &csrf_state).await {
+                Ok(code) => {
+                    let _ = progress_tx.send(OAuthStep::ExchangingCode);
+                    flow.exchange_code(code).await
+                }
+                Err(e) => Err(e),
+            };
+            let _ = tokens_tx.send(result.map_err(|e| e.to_string()));
+        });
+
+        Self {
+            auth_url,
+            tokens,
+            progress,
+            task: Some(task),
+        }
+    }
+
+    /// A handle fed by the given channels instead of a real flow
+    #[cfg(test)]
+    fn from_channels(
+        auth_url: oneshot::Receiver<Result<String, String>>,
+        tokens: oneshot::Receiver<Result<TokenSet, String>>,
+        progress: watch::Receiver<OAuthStep>,
+    ) -> Self {
+        Self {
+            auth_url,
+            tokens,
+            progress,
+            task: None,
+        }
+    }
+}
+
+impl Drop for FlowHandle {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
+
+/// State of the account creation overlay
+pub struct CreationWizard {
+    pub service: TextInput,
+    pub account: TextInput,
+    pub field: WizardField,
+    pub step: WizardStep,
+    /// Scopes requested by the running flow
+    pub scopes: Vec<String>,
+    flow: Option<FlowHandle>,
+}
+
+impl Default for CreationWizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CreationWizard {
+    /// An empty form with the service field focused
+    pub fn new() -> Self {
+        Self {
+            service: TextInput::new(""),
+            account: TextInput::new(""),
+            field: WizardField::Service,
+            step: WizardStep::Form,
+            scopes: Vec::new(),
+            flow: None,
+        }
+    }
+
+    /// Handle a key while the form is shown
+    ///
+    /// Tab or the arrow keys switch fields, Enter moves from the service to
+    /// the account field and submits from there, Esc cancels.
+    pub fn handle_form_key(&mut self, key: KeyEvent) -> FormAction {
+        match key.code {
+            KeyCode::Esc => return FormAction::Cancel,
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => self.toggle_field(),
+            KeyCode::Enter if self.field == WizardField::Service => {
+                self.field = WizardField::Account;
+            }
+            KeyCode::Enter => return FormAction::Submit,
+            KeyCode::Char(_) if self.focused_input().value().chars().count() >= MAX_NAME_LEN => {}
+            _ => {
+                self.focused_input().handle_key(key);
+            }
+        }
+        FormAction::None
+    }
+
+    /// Insert pasted text into the focused field
+    ///
+    /// Characters other than letters, digits, `-`, and `_` are dropped, and
+    /// the field is cut off at [`MAX_NAME_LEN`].
+    pub fn handle_paste(&mut self, text: &str) {
+        let input = self.focused_input();
+        let room = MAX_NAME_LEN.saturating_sub(input.value().chars().count());
+        let pasted: String = sanitize_name(text).chars().take(room).collect();
+        input.insert_str(&pasted);
+    }
+
+    /// The input that currently has focus
+    pub fn focused_input(&mut self) -> &mut TextInput {
+        match self.field {
+            WizardField::Service => &mut self.service,
+            WizardField::Account => &mut self.account,
+        }
+    }
+
+    fn toggle_field(&mut self) {
+        self.field = match self.field {
+            WizardField::Service => WizardField::Account,
+            WizardField::Account => WizardField::Service,
+        };
+    }
+
+    /// Trimmed service and account names, or why the form is incomplete
+    pub fn names(&self) -> Result<(String, String), String> {
+        let service = self.service.value().trim();
+        let account = self.account.value().trim();
+        if service.is_empty() {
+            return Err("Service name is required".to_string());
+        }
+        if account.is_empty() {
+            return Err("Account name is required".to_string());
+        }
+        Ok((service.to_string(), account.to_string()))
+    }
+
+    /// Wait on `flow`, which requests `scopes`, for the authorization URL
+    pub fn start(&mut self, flow: FlowHandle, scopes: Vec<String>) {
+        self.flow = Some(flow);
+        self.scopes = scopes;
+        self.step = WizardStep::Starting;
+    }
+
+    /// Stop any running flow and show `message`
+    pub fn fail(&mut self, message: impl Into<String>) {
+        self.flow = None;
+        self.step = WizardStep::Failed {
+            message: message.into(),
+        };
+    }
+
+    /// Return from an error to the form, keeping what was typed
+    pub fn dismiss_error(&mut self) {
+        if matches!(self.step, WizardStep::Failed { .. }) {
+            self.step = WizardStep::Form;
+        }
+    }
+
+    /// Check the flow task for progress without blocking
+    pub fn poll(&mut self) -> Option<WizardEvent> {
+        let flow = self.flow.as_mut()?;
+
+        match self.step {
+            WizardStep::Starting => {
+                let received = flow.auth_url.try_recv();
+                match received {
+                    Ok(Ok(auth_url)) => {
+                        self.step = WizardStep::Authorizing {
+                            auth_url: auth_url.clone(),
+                        };
+                        Some(WizardEvent::AuthorizationUrl(auth_url))
+                    }
+                    Ok(Err(message)) => self.failed(message),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Closed) => self.failed("OAuth flow stopped unexpectedly"),
+                }
+            }
+            WizardStep::Authorizing { .. } => {
+                let received = flow.tokens.try_recv();
+                match received {
+                    Ok(Ok(tokens)) => {
+                        self.flow = None;
+                        Some(WizardEvent::Completed(tokens))
+                    }
+                    Ok(Err(message)) => self.failed(format!("Authorization failed: {}", message)),
+                    Err(TryRecvError::Empty) if flow.progress.has_changed().unwrap_or(false) => {
+                        Some(WizardEvent::Progress(*flow.progress.borrow_and_update()))
+                    }
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Closed) => self.failed("OAuth flow stopped unexpectedly"),
+                }
+            }
+            WizardStep::Form | WizardStep::Failed { .. } => None,
+        }
+    }
+
+    fn failed(&mut self, message: impl Into<String>) -> Option<WizardEvent> {
+        self.fail(message);
+        None
+    }
+}
+
+/// Build the PKCE flow for `service` and the scopes to request
+///
+/// Client credentials come from `{SERVICE}_CLIENT_ID`/`_CLIENT_SECRET` or
+/// `OAUTH_CLIENT_ID`/`_SECRET`, the callback port from
+/// `OAUTH_CALLBACK_PORT`, exactly as in `sigilforge add-account`. `env`
+/// looks up a variable.
+pub fn build_flow(
+    registry: &ProviderRegistry,
+    service: &str,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<(PkceFlow, Vec<String>), String> {
+    let provider = registry.get(service).ok_or_else(|| {
+        format!(
+            "Unknown provider '{}'. Available: {}",
+            service,
+            registry.list_ids().join(", ")
+        )
+    })?;
+
+    let prefix = service.to_uppercase();
+    let client_id = env(&format!("{}_CLIENT_ID", prefix))
+        .or_else(|| env("OAUTH_CLIENT_ID"))
+        .ok_or_else(|| format!("Set {}_CLIENT_ID or OAUTH_CLIENT_ID", prefix))?;
+    let client_secret =
+        env(&format!("{}_CLIENT_SECRET", prefix)).or_else(|| env("OAUTH_CLIENT_SECRET"));
+    let port = env("OAUTH_CALLBACK_PORT")
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(DEFAULT_CALLBACK_PORT);
+
+    let flow = PkceFlow::new(
+        provider.clone(),
+        client_id,
+        client_secret,
+        RedirectConfig::localhost(port),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok((flow, provider.default_scopes.clone()))
+}
+
+/// Record the new account and store its tokens
+///
+/// The account entry is removed again if the tokens cannot be stored.
+pub async fn save_account<M: TokenManager>(
+    store: &AccountStore,
+    manager: &M,
+    service: &str,
+    account: &str,
+    scopes: Vec<String>,
+    token_set: TokenSet,
+) -> Result<()> {
+    let source = CredentialSource::OAuthPkce {
+        provider_id: service.to_string(),
+    };
+    let service = ServiceId::new(service);
+    let account = AccountId::new(account);
+
+    store.add_account(Account::new(service.clone(), account.clone(), scopes).with_source(source))?;
+
+    if let Err(e) = manager.store_token_set(&service, &account, token_set).await {
+        let _ = store.remove_account(&service, &account);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use sigilforge_core::{DefaultTokenManager, MemoryStore, Token};
+    use std::collections::HashMap;
+
+    fn press(wizard: &mut CreationWizard, code: KeyCode) -> FormAction {
+        wizard.handle_form_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_text(wizard: &mut CreationWizard, text: &str) {
+        for c in text.chars() {
+            press(wizard, KeyCode::Char(c));
+        }
+    }
+
+    type Senders = (
+        oneshot::Sender<Result<String, String>>,
+        oneshot::Sender<Result<TokenSet, String>>,
+        watch::Sender<OAuthStep>,
+    );
+
+    fn started_wizard() -> (CreationWizard, Senders) {
+        let (url_tx, url_rx) = oneshot::channel();
+        let (tokens_tx, tokens_rx) = oneshot::channel();
+        let (progress_tx, progress_rx) = watch::channel(OAuthStep::BuildingUrl);
+        let mut wizard = CreationWizard::new();
+        let flow = FlowHandle::from_channels(url_rx, tokens_rx, progress_rx);
+        wizard.start(flow, vec![]);
+        (wizard, (url_tx, tokens_tx, progress_tx))
+    }
+
+    #[test]
+    fn test_form_fields_and_submit() {
+        let mut wizard = CreationWizard::new();
+
+        type_text(&mut wizard, "github");
+        assert_eq!(press(&mut wizard, KeyCode::Enter), FormAction::None);
+        assert_eq!(wizard.field, WizardField::Account);
+
+        type_text(&mut wizard, "work");
+        press(&mut wizard, KeyCode::Tab);
+        assert_eq!(wizard.field, WizardField::Service);
+        press(&mut wizard, KeyCode::Backspace);
+        press(&mut wizard, KeyCode::Tab);
+
+        assert_eq!(wizard.service.value(), "githu");
+        assert_eq!(wizard.account.value(), "work");
+        assert_eq!(press(&mut wizard, KeyCode::Enter), FormAction::Submit);
+        assert_eq!(press(&mut wizard, KeyCode::Esc), FormAction::Cancel);
+    }
+
+    #[test]
+    fn test_paste_is_sanitized() {
+        assert_eq!(sanitize_name("my-service-name"), "my-service-name");
+        assert_eq!(sanitize_name(" work account\n"), "workaccount");
+        assert_eq!(sanitize_name("me@example.com/ci_2"), "meexamplecomci_2");
+        assert_eq!(sanitize_name("服务"), "");
+
+        let mut wizard = CreationWizard::new();
+        wizard.handle_paste("  git\thub\n");
+        press(&mut wizard, KeyCode::Tab);
+        wizard.handle_paste("my.work-account");
+        assert_eq!(wizard.service.value(), "github");
+        assert_eq!(wizard.account.value(), "myworkaccount");
+    }
+
+    #[test]
+    fn test_name_length_is_limited() {
+        let mut wizard = CreationWizard::new();
+        wizard.handle_paste(&"a".repeat(60));
+        wizard.handle_paste("bcdefgh");
+        assert_eq!(wizard.service.value(), format!("{}bcde", "a".repeat(60)));
+
+        // Typing stops at the limit too, but editing keys still work
+        type_text(&mut wizard, "x");
+        assert_eq!(wizard.service.value().chars().count(), MAX_NAME_LEN);
+        press(&mut wizard, KeyCode::Backspace);
+        type_text(&mut wizard, "xy");
+        assert!(wizard.service.value().ends_with("bcdx"));
+    }
+
+    #[test]
+    fn test_names_are_required() {
+        let mut wizard = CreationWizard::new();
+        assert!(wizard.names().unwrap_err().contains("Service"));
+
+        wizard.service = TextInput::new(" github ");
+        assert!(wizard.names().unwrap_err().contains("Account"));
+
+        wizard.account = TextInput::new("work");
+        assert_eq!(
+            wizard.names().unwrap(),
+            ("github".to_string(), "work".to_string())
+        );
+    }
+
+    #[test]
+    fn test_poll_through_successful_flow() {
+        let (mut wizard, (url_tx, tokens_tx, _progress_tx)) = started_wizard();
+        assert_eq!(wizard.step, WizardStep::Starting);
+        assert!(wizard.poll().is_none());
+
+        url_tx
+            .send(Ok("https://auth.example/authorize".to_string()))
+            .unwrap();
+        match wizard.poll() {
+            Some(WizardEvent::AuthorizationUrl(url)) => {
+                assert_eq!(url, "https://auth.example/authorize")
+            }
+            other => panic!("expected authorization URL, got {:?}", other),
+        }
+        assert!(matches!(wizard.step, WizardStep::Authorizing { .. }));
+        assert!(wizard.poll().is_none());
+
+        tokens_tx
+            .send(Ok(TokenSet::new(Token::new("access"))))
+            .unwrap();
+        match wizard.poll() {
+            Some(WizardEvent::Completed(tokens)) => {
+                assert_eq!(tokens.access_token.access_token.expose(), "access")
+            }
+            other => panic!("expected tokens, got {:?}", other),
+        }
+        assert!(wizard.poll().is_none());
+    }
+
+    #[test]
+    fn test_poll_reports_flow_errors() {
+        let (mut wizard, (url_tx, _tokens_tx, _progress_tx)) = started_wizard();
+        url_tx
+            .send(Err("failed to bind to localhost:8484".to_string()))
+            .unwrap();
+
+        assert!(wizard.poll().is_none());
+        assert_eq!(
+            wizard.step,
+            WizardStep::Failed {
+                message: "failed to bind to localhost:8484".to_string()
+            }
+        );
+
+        wizard.dismiss_error();
+        assert_eq!(wizard.step, WizardStep::Form);
+    }
+
+    #[test]
+    fn test_poll_reports_progress_before_tokens() {
+        let (mut wizard, (url_tx, tokens_tx, progress_tx)) = started_wizard();
+        url_tx
+            .send(Ok("https://auth.example/authorize".to_string()))
+            .unwrap();
+        wizard.poll();
+
+        progress_tx.send(OAuthStep::ExchangingCode).unwrap();
+        assert!(matches!(
+            wizard.poll(),
+            Some(WizardEvent::Progress(OAuthStep::ExchangingCode))
+        ));
+        // Each change is reported once
+        assert!(wizard.poll().is_none());
+
+        tokens_tx
+            .send(Ok(TokenSet::new(Token::new("access"))))
+            .unwrap();
+        assert!(matches!(wizard.poll(), Some(WizardEvent::Completed(_))));
+    }
+
+    #[test]
+    fn test_poll_detects_stopped_task() {
+        let (mut wizard, (url_tx, tokens_tx, _progress_tx)) = started_wizard();
+        url_tx
+            .send(Ok("https://auth.example/authorize".to_string()))
+            .unwrap();
+        wizard.poll();
+
+        drop(tokens_tx);
+        assert!(wizard.poll().is_none());
+        assert!(matches!(wizard.step, WizardStep::Failed { .. }));
+    }
+
+    #[test]
+    fn test_build_flow_reads_client_from_env() {
+        let registry = ProviderRegistry::with_defaults();
+        let vars: HashMap<&str, &str> = [("GITHUB_CLIENT_ID", "id"), ("OAUTH_CALLBACK_PORT", "0")]
+            .into_iter()
+            .collect();
+        let env = |name: &str| vars.get(name).map(|v| v.to_string());
+
+        let (flow, scopes) = build_flow(&registry, "github", env).unwrap();
+        assert_eq!(flow.redirect().port, 0);
+        assert_eq!(scopes, registry.get("github").unwrap().default_scopes);
+
+        let error = build_flow(&registry, "spotify", env).err().unwrap();
+        assert!(error.contains("SPOTIFY_CLIENT_ID"), "{}", error);
+
+        let error = build_flow(&registry, "nope", env).err().unwrap();
+        assert!(error.contains("Unknown provider"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_save_account_stores_account_and_tokens() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = AccountStore::load_from_path(temp_dir.path().join("accounts.json")).unwrap();
+        let manager = DefaultTokenManager::new(MemoryStore::new(), ProviderRegistry::new());
+        let tokens = || TokenSet::new(Token::new("access")).with_refresh_token("refresh");
+
+        save_account(
+            &store,
+            &manager,
+            "github",
+            "work",
+            vec!["repo".into()],
+            tokens(),
+        )
+        .await
+        .unwrap();
+
+        let (service, account) = (ServiceId::new("github"), AccountId::new("work"));
+        let saved = store.get_account(&service, &account).unwrap().unwrap();
+        assert_eq!(saved.scopes, vec!["repo".to_string()]);
+        let stored = manager
+            .get_token_set(&service, &account)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.access_token.access_token.expose(), "access");
+
+        // An existing account is not overwritten
+        let again = save_account(&store, &manager, "github", "work", vec![], tokens()).await;
+        assert!(again.is_err());
+        assert_eq!(store.list_accounts(None).unwrap().len(), 1);
+    }
+}